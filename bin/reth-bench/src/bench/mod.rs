@@ -9,6 +9,8 @@ mod context;
 mod new_payload_fcu;
 mod new_payload_only;
 mod output;
+mod replay_engine;
+mod rpc_workload;
 
 /// `reth bench` command
 #[derive(Debug, Parser)]
@@ -28,6 +30,13 @@ pub enum Subcommands {
 
     /// Benchmark which only calls subsequent `newPayload` calls.
     NewPayloadOnly(new_payload_only::Command),
+
+    /// Benchmark which replays the `newPayload` and `forkchoiceUpdated` calls recorded with
+    /// `--debug.engine-api-store`.
+    ReplayEngine(replay_engine::Command),
+
+    /// Benchmark which replays a workload of RPC requests.
+    RpcWorkload(rpc_workload::Command),
 }
 
 impl BenchmarkCommand {
@@ -39,6 +48,8 @@ impl BenchmarkCommand {
         match self.command {
             Subcommands::NewPayloadFcu(command) => command.execute(ctx).await,
             Subcommands::NewPayloadOnly(command) => command.execute(ctx).await,
+            Subcommands::ReplayEngine(command) => command.execute(ctx).await,
+            Subcommands::RpcWorkload(command) => command.execute(ctx).await,
         }
     }
 
@@ -5,8 +5,8 @@ use crate::{
     bench::{
         context::BenchContext,
         output::{
-            CombinedResult, NewPayloadResult, TotalGasOutput, TotalGasRow, COMBINED_OUTPUT_SUFFIX,
-            GAS_OUTPUT_SUFFIX,
+            CombinedResult, LatencyPercentiles, NewPayloadResult, TotalGasOutput, TotalGasRow,
+            COMBINED_OUTPUT_SUFFIX, GAS_OUTPUT_SUFFIX,
         },
     },
     valid_payload::{call_forkchoice_updated, call_new_payload},
@@ -141,6 +141,12 @@ impl Command {
         let (gas_output_results, combined_results): (_, Vec<CombinedResult>) =
             results.into_iter().unzip();
 
+        if let Some(percentiles) =
+            LatencyPercentiles::new(combined_results.iter().map(|r| r.total_latency).collect())
+        {
+            info!(%percentiles, "Combined newPayload and forkchoiceUpdated latency");
+        }
+
         // write the csv output to files
         if let Some(path) = self.benchmark.output {
             // first write the combined results to a file
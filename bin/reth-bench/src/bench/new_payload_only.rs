@@ -4,7 +4,7 @@ use crate::{
     bench::{
         context::BenchContext,
         output::{
-            NewPayloadResult, TotalGasOutput, TotalGasRow, GAS_OUTPUT_SUFFIX,
+            LatencyPercentiles, NewPayloadResult, TotalGasOutput, TotalGasRow, GAS_OUTPUT_SUFFIX,
             NEW_PAYLOAD_OUTPUT_SUFFIX,
         },
     },
@@ -98,6 +98,12 @@ impl Command {
         let (gas_output_results, new_payload_results): (_, Vec<NewPayloadResult>) =
             results.into_iter().unzip();
 
+        if let Some(percentiles) =
+            LatencyPercentiles::new(new_payload_results.iter().map(|r| r.latency).collect())
+        {
+            info!(%percentiles, "newPayload latency");
+        }
+
         // write the csv output to files
         if let Some(path) = self.benchmark.output {
             // first write the new payload results to a file
@@ -14,6 +14,12 @@ pub(crate) const COMBINED_OUTPUT_SUFFIX: &str = "combined_latency.csv";
 /// This is the suffix for new payload output csv files.
 pub(crate) const NEW_PAYLOAD_OUTPUT_SUFFIX: &str = "new_payload_latency.csv";
 
+/// This is the suffix for forkchoice updated output csv files.
+pub(crate) const FCU_OUTPUT_SUFFIX: &str = "forkchoice_updated_latency.csv";
+
+/// This is the suffix for RPC workload output csv files.
+pub(crate) const RPC_OUTPUT_SUFFIX: &str = "rpc_latency.csv";
+
 /// This represents the results of a single `newPayload` call in the benchmark, containing the gas
 /// used and the `newPayload` latency.
 #[derive(Debug)]
@@ -115,6 +121,87 @@ impl Serialize for CombinedResult {
     }
 }
 
+/// This represents the latency of a single RPC call in the benchmark.
+#[derive(Debug)]
+pub(crate) struct RpcCallResult {
+    /// The RPC method that was called.
+    pub(crate) method: String,
+    /// Whether the call returned an error.
+    pub(crate) success: bool,
+    /// The latency of the call.
+    pub(crate) latency: Duration,
+}
+
+/// This is a [`Serialize`] implementation for the [`RpcCallResult`] struct, serializing the
+/// latency as microseconds because the csv writer would fail otherwise.
+impl Serialize for RpcCallResult {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        // convert the time to microseconds
+        let latency = self.latency.as_micros();
+        let mut state = serializer.serialize_struct("RpcCallResult", 3)?;
+        state.serialize_field("method", &self.method)?;
+        state.serialize_field("success", &self.success)?;
+        state.serialize_field("latency", &latency)?;
+        state.end()
+    }
+}
+
+/// This represents the latency distribution of a set of calls in the benchmark.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct LatencyPercentiles {
+    /// The number of calls.
+    pub(crate) count: usize,
+    /// The mean latency.
+    pub(crate) mean: Duration,
+    /// The median latency.
+    pub(crate) p50: Duration,
+    /// The 90th percentile latency.
+    pub(crate) p90: Duration,
+    /// The 99th percentile latency.
+    pub(crate) p99: Duration,
+    /// The maximum latency.
+    pub(crate) max: Duration,
+}
+
+impl LatencyPercentiles {
+    /// Computes the latency distribution of the given latencies, or returns `None` if there are
+    /// none.
+    ///
+    /// Percentiles use the nearest-rank method.
+    pub(crate) fn new(mut latencies: Vec<Duration>) -> Option<Self> {
+        if latencies.is_empty() {
+            return None
+        }
+        latencies.sort_unstable();
+
+        let count = latencies.len();
+        let percentile = |p: usize| latencies[(count * p).div_ceil(100).max(1) - 1];
+        let mean = latencies.iter().sum::<Duration>() / count as u32;
+
+        Some(Self {
+            count,
+            mean,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: latencies[count - 1],
+        })
+    }
+}
+
+impl std::fmt::Display for LatencyPercentiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} calls, mean: {:?}, p50: {:?}, p90: {:?}, p99: {:?}, max: {:?}",
+            self.count, self.mean, self.p50, self.p90, self.p99, self.max
+        )
+    }
+}
+
 /// This represents a row of total gas data in the benchmark.
 #[derive(Debug)]
 pub(crate) struct TotalGasRow {
@@ -203,4 +290,29 @@ mod tests {
         let second_line = result.next().unwrap().unwrap();
         assert_eq!(second_line, expected_second_line);
     }
+
+    #[test]
+    fn test_latency_percentiles() {
+        assert_eq!(LatencyPercentiles::new(vec![]), None);
+
+        let latencies = (1..=100).rev().map(Duration::from_millis).collect();
+        let percentiles = LatencyPercentiles::new(latencies).unwrap();
+        assert_eq!(
+            percentiles,
+            LatencyPercentiles {
+                count: 100,
+                mean: Duration::from_micros(50_500),
+                p50: Duration::from_millis(50),
+                p90: Duration::from_millis(90),
+                p99: Duration::from_millis(99),
+                max: Duration::from_millis(100),
+            }
+        );
+
+        let percentiles = LatencyPercentiles::new(vec![Duration::from_millis(7)]).unwrap();
+        assert_eq!(
+            (percentiles.p50, percentiles.p99),
+            (Duration::from_millis(7), Duration::from_millis(7))
+        );
+    }
 }
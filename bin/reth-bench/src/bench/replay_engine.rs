@@ -0,0 +1,171 @@
+//! Runs the `reth bench` command, replaying engine API messages recorded with
+//! `--debug.engine-api-store`.
+
+use crate::{
+    bench::{
+        context::auth_provider,
+        output::{
+            LatencyPercentiles, NewPayloadResult, TotalGasOutput, TotalGasRow, FCU_OUTPUT_SUFFIX,
+            GAS_OUTPUT_SUFFIX, NEW_PAYLOAD_OUTPUT_SUFFIX,
+        },
+    },
+    bench_mode::BenchMode,
+    valid_payload::{call_forkchoice_updated, call_new_payload},
+};
+use clap::Parser;
+use csv::Writer;
+use reth_cli_runner::CliContext;
+use reth_engine_util::engine_store::{EngineMessageStore, StoredEngineApiMessage};
+use reth_node_api::EngineApiMessageVersion;
+use reth_node_core::args::BenchmarkArgs;
+use reth_rpc_types::engine::PayloadAttributes;
+use std::{path::PathBuf, time::Instant};
+use tracing::{debug, info};
+
+/// `reth benchmark replay-engine` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The directory of the engine API messages recorded with `--debug.engine-api-store`.
+    ///
+    /// Messages are replayed in the order they were received. If `--from` and `--to` are
+    /// provided, only the messages from the `newPayload` of the first block to the `newPayload`
+    /// of the last block are replayed.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    engine_store: PathBuf,
+
+    #[command(flatten)]
+    benchmark: BenchmarkArgs,
+}
+
+impl Command {
+    /// Execute `benchmark replay-engine` command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        let benchmark_mode = BenchMode::new(self.benchmark.from, self.benchmark.to)?;
+        let auth_provider = auth_provider(&self.benchmark).await?;
+        let store = EngineMessageStore::new(self.engine_store.clone());
+
+        // put results in summary vecs so they can be printed at the end
+        let mut results = Vec::new();
+        let mut fcu_latencies = Vec::new();
+        let total_benchmark_duration = Instant::now();
+
+        // the forkchoiceUpdated version follows the version of the last newPayload
+        let mut message_version = EngineApiMessageVersion::V3;
+        let mut started = false;
+        for path in store.engine_messages_iter()? {
+            let message: StoredEngineApiMessage<PayloadAttributes> =
+                serde_json::from_slice(&reth_fs_util::read(&path)?)?;
+            match message {
+                StoredEngineApiMessage::NewPayload { payload, cancun_fields } => {
+                    let block_number = payload.block_number();
+                    if !benchmark_mode.contains(block_number) {
+                        if started {
+                            break
+                        }
+                        continue
+                    }
+                    started = true;
+
+                    let gas_used = payload.as_v1().gas_used;
+                    let (parent_beacon_block_root, versioned_hashes) = cancun_fields
+                        .map(|fields| {
+                            (Some(fields.parent_beacon_block_root), fields.versioned_hashes)
+                        })
+                        .unwrap_or_default();
+
+                    debug!(number = block_number, "Sending payload to engine");
+                    let start = Instant::now();
+                    message_version = call_new_payload(
+                        &auth_provider,
+                        payload,
+                        parent_beacon_block_root,
+                        versioned_hashes,
+                    )
+                    .await?;
+
+                    let new_payload_result =
+                        NewPayloadResult { gas_used, latency: start.elapsed() };
+                    info!(%new_payload_result);
+
+                    let row = TotalGasRow {
+                        block_number,
+                        gas_used,
+                        time: total_benchmark_duration.elapsed(),
+                    };
+                    results.push((row, new_payload_result));
+                }
+                StoredEngineApiMessage::ForkchoiceUpdated { state, payload_attrs } => {
+                    if !started {
+                        continue
+                    }
+
+                    debug!(head = %state.head_block_hash, "Sending forkchoiceUpdated to engine");
+                    let start = Instant::now();
+                    call_forkchoice_updated(&auth_provider, message_version, state, payload_attrs)
+                        .await?;
+                    let latency = start.elapsed();
+                    info!(?latency, "Forkchoice updated");
+                    fcu_latencies.push(latency);
+                }
+            }
+        }
+
+        if results.is_empty() {
+            return Err(eyre::eyre!("No newPayload messages were replayed"))
+        }
+
+        let (gas_output_results, new_payload_results): (_, Vec<NewPayloadResult>) =
+            results.into_iter().unzip();
+
+        // write the csv output to files
+        if let Some(path) = &self.benchmark.output {
+            let output_path = path.join(NEW_PAYLOAD_OUTPUT_SUFFIX);
+            info!("Writing newPayload call latency output to file: {:?}", output_path);
+            let mut writer = Writer::from_path(output_path)?;
+            for result in &new_payload_results {
+                writer.serialize(result)?;
+            }
+            writer.flush()?;
+
+            let output_path = path.join(FCU_OUTPUT_SUFFIX);
+            info!("Writing forkchoiceUpdated call latency output to file: {:?}", output_path);
+            let mut writer = Writer::from_path(output_path)?;
+            writer.write_record(["latency"])?;
+            for latency in &fcu_latencies {
+                writer.write_record([latency.as_micros().to_string()])?;
+            }
+            writer.flush()?;
+
+            let output_path = path.join(GAS_OUTPUT_SUFFIX);
+            info!("Writing total gas output to file: {:?}", output_path);
+            let mut writer = Writer::from_path(output_path)?;
+            for row in &gas_output_results {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+
+            info!("Finished writing benchmark output files to {:?}.", path);
+        }
+
+        if let Some(percentiles) = LatencyPercentiles::new(
+            new_payload_results.iter().map(|result| result.latency).collect(),
+        ) {
+            info!(%percentiles, "newPayload latency");
+        }
+        if let Some(percentiles) = LatencyPercentiles::new(fcu_latencies) {
+            info!(%percentiles, "forkchoiceUpdated latency");
+        }
+
+        // accumulate the results and calculate the overall Ggas/s
+        let gas_output = TotalGasOutput::new(gas_output_results);
+        info!(
+            total_duration=?gas_output.total_duration,
+            total_gas_used=?gas_output.total_gas_used,
+            blocks_processed=?gas_output.blocks_processed,
+            "Total Ggas/s: {:.4}",
+            gas_output.total_gigagas_per_second()
+        );
+
+        Ok(())
+    }
+}
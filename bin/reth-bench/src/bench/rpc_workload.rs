@@ -0,0 +1,125 @@
+//! Runs the `reth bench` command, replaying a workload of RPC requests against a node.
+
+use crate::bench::output::{LatencyPercentiles, RpcCallResult, RPC_OUTPUT_SUFFIX};
+use alloy_provider::{Provider, ProviderBuilder};
+use clap::Parser;
+use csv::Writer;
+use futures::{stream, StreamExt};
+use reth_cli_runner::CliContext;
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::PathBuf, time::Instant};
+use tracing::{info, warn};
+
+/// A request of an RPC workload.
+#[derive(Debug, Clone, Deserialize)]
+struct WorkloadRequest {
+    /// The RPC method to call.
+    method: String,
+    /// The parameters of the call.
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+/// `reth benchmark rpc-workload` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The RPC url of the node to benchmark.
+    #[arg(long, value_name = "RPC_URL", verbatim_doc_comment)]
+    rpc_url: String,
+
+    /// The path to the workload, a file of JSON-RPC requests, one `{"method": .., "params": ..}`
+    /// object per line.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    workload: PathBuf,
+
+    /// The number of requests in flight at the same time.
+    #[arg(long, default_value_t = 1)]
+    concurrency: usize,
+
+    /// The number of times the workload is replayed.
+    #[arg(long, default_value_t = 1)]
+    iterations: usize,
+
+    /// The path to the output directory for granular benchmark results.
+    #[arg(long, short, value_name = "BENCHMARK_OUTPUT", verbatim_doc_comment)]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `benchmark rpc-workload` command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        if let Some(output) = &self.output {
+            if output.is_file() {
+                return Err(eyre::eyre!("Output path must be a directory"));
+            }
+        }
+
+        let workload = std::fs::read_to_string(&self.workload)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<WorkloadRequest>, _>>()?;
+        if workload.is_empty() {
+            return Err(eyre::eyre!("The workload has no requests"));
+        }
+
+        info!("Running RPC workload of {} requests against {}", workload.len(), self.rpc_url);
+        let provider = ProviderBuilder::new().on_http(self.rpc_url.parse()?);
+
+        let total_benchmark_duration = Instant::now();
+        let requests = std::iter::repeat(workload).take(self.iterations).flatten();
+        let results = stream::iter(requests)
+            .map(|request| {
+                let provider = &provider;
+                async move {
+                    let start = Instant::now();
+                    let response = provider
+                        .raw_request::<_, serde_json::Value>(
+                            request.method.clone().into(),
+                            request.params,
+                        )
+                        .await;
+                    let latency = start.elapsed();
+                    if let Err(err) = &response {
+                        warn!(method = %request.method, %err, "RPC request failed");
+                    }
+                    RpcCallResult { method: request.method, success: response.is_ok(), latency }
+                }
+            })
+            .buffer_unordered(self.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        let total_duration = total_benchmark_duration.elapsed();
+
+        if let Some(path) = &self.output {
+            let output_path = path.join(RPC_OUTPUT_SUFFIX);
+            info!("Writing RPC call latency output to file: {:?}", output_path);
+            let mut writer = Writer::from_path(output_path)?;
+            for result in &results {
+                writer.serialize(result)?;
+            }
+            writer.flush()?;
+        }
+
+        let mut latencies_by_method = BTreeMap::<_, Vec<_>>::new();
+        for result in results.iter().filter(|result| result.success) {
+            latencies_by_method.entry(result.method.as_str()).or_default().push(result.latency);
+        }
+        for (method, latencies) in latencies_by_method {
+            if let Some(percentiles) = LatencyPercentiles::new(latencies) {
+                info!(method, %percentiles, "RPC latency");
+            }
+        }
+
+        let failed = results.iter().filter(|result| !result.success).count();
+        info!(
+            ?total_duration,
+            requests = results.len(),
+            failed,
+            "Requests per second: {:.2}",
+            results.len() as f64 / total_duration.as_secs_f64()
+        );
+
+        Ok(())
+    }
+}
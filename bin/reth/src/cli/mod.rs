@@ -6,9 +6,10 @@ use crate::{
         LogArgs,
     },
     commands::{
-        config_cmd, db, debug_cmd, dump_genesis, import, init_cmd, init_state,
+        config_cmd, db, debug_cmd, doctor, dump_genesis, exex, export_blocks, import,
+        import_blocks, init_cmd, init_state,
         node::{self, NoArgs},
-        p2p, prune, recover, stage, test_vectors,
+        p2p, prune, recover, snapshot_sync, stage, test_vectors,
     },
     version::{LONG_VERSION, SHORT_VERSION},
 };
@@ -149,7 +150,10 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             }
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::InitState(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::SnapshotSync(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ImportBlocks(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ExportBlocks(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             #[cfg(feature = "optimism")]
             Commands::ImportOp(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             #[cfg(feature = "optimism")]
@@ -165,6 +169,8 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Prune(command) => runner.run_until_ctrl_c(command.execute()),
+            Commands::ExEx(command) => runner.run_until_ctrl_c(command.execute()),
+            Commands::Doctor(command) => runner.run_until_ctrl_c(command.execute()),
         }
     }
 
@@ -190,9 +196,18 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Initialize the database from a state dump file.
     #[command(name = "init-state")]
     InitState(init_state::InitStateCommand),
+    /// Initialize the database from a state snapshot verified against a trusted state root.
+    #[command(name = "snapshot-sync")]
+    SnapshotSync(snapshot_sync::SnapshotSyncCommand),
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand),
+    /// Syncs blocks from RLP or era1 files, with a configurable validation level.
+    #[command(name = "import-blocks")]
+    ImportBlocks(import_blocks::ImportBlocksCommand),
+    /// Exports a range of blocks to RLP or era1 files.
+    #[command(name = "export-blocks")]
+    ExportBlocks(export_blocks::ExportBlocksCommand),
     /// This syncs RLP encoded OP blocks below Bedrock from a file, without executing.
     #[cfg(feature = "optimism")]
     #[command(name = "import-op")]
@@ -227,6 +242,12 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Prune according to the configuration without any limits
     #[command(name = "prune")]
     Prune(prune::PruneCommand),
+    /// Inspect the execution extensions of the node
+    #[command(name = "exex")]
+    ExEx(exex::Command),
+    /// Diagnose common problems of the environment the node runs in
+    #[command(name = "doctor")]
+    Doctor(doctor::DoctorCommand),
 }
 
 #[cfg(test)]
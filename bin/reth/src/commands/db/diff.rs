@@ -4,14 +4,27 @@ use crate::{
 };
 use clap::Parser;
 use reth_db::{open_db_read_only, tables_to_generic, DatabaseEnv, Tables};
-use reth_db_api::{cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx};
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    table::{Decode, Encode, Table, TableRow},
+    transaction::DbTx,
+    DatabaseError,
+};
 use reth_db_common::DbTool;
+use reth_primitives::{BlockNumber, StaticFileSegment, TxNumber};
+use reth_provider::{
+    providers::StaticFileProvider, BlockReader, HeaderProvider, ProviderError, ProviderResult,
+    ReceiptProvider, StaticFileProviderFactory, TransactionsProvider,
+};
 use std::{
+    cmp::Ordering,
     collections::HashMap,
     fmt::Debug,
     fs::{self, File},
     hash::Hash,
     io::Write,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -28,10 +41,27 @@ pub struct Command {
     #[command(flatten)]
     second_db: DatabaseArgs,
 
-    /// The table name to diff. If not specified, all tables are diffed.
+    /// The table name to diff. If neither a table nor a segment is specified, all tables and
+    /// static file segments are diffed.
     #[arg(long, verbatim_doc_comment)]
     table: Option<Tables>,
 
+    /// The static file segment to diff. If neither a table nor a segment is specified, all tables
+    /// and static file segments are diffed.
+    #[arg(long, value_enum, verbatim_doc_comment)]
+    segment: Option<StaticFileSegment>,
+
+    /// The first block of the range to diff.
+    ///
+    /// Tables keyed by block or transaction number, and the static files, are only diffed within
+    /// the block range. All other tables are diffed entirely.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    from: Option<BlockNumber>,
+
+    /// The last block of the range to diff, inclusive.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    to: Option<BlockNumber>,
+
     /// The output directory for the diff report.
     #[arg(long, verbatim_doc_comment)]
     output: PlatformPath<PathBuf>,
@@ -41,27 +71,43 @@ impl Command {
     /// Execute the `db diff` command.
     ///
     /// This first opens the `db/` folder from the secondary datadir, where the second database is
-    /// opened read-only.
+    /// opened read-only, and the `static_files/` folder of the secondary datadir.
     ///
-    /// The tool will then iterate through all key-value pairs for the primary and secondary
-    /// databases. The value for each key will be compared with its corresponding value in the
-    /// other database. If the values are different, a discrepancy will be recorded in-memory. If
-    /// one key is present in one database but not the other, this will be recorded as an "extra
-    /// element" for that database.
+    /// The tool will then walk the key-value pairs of the primary and secondary databases side by
+    /// side, within the block range for tables keyed by block or transaction number. The value for
+    /// each key will be compared with its corresponding value in the other database. If the values
+    /// are different, a discrepancy will be recorded in-memory. If one key is present in one
+    /// database but not the other, this will be recorded as an "extra element" for that database.
+    /// The static file segments are compared block by block, and transaction by transaction, in
+    /// the same way.
     ///
-    /// The discrepancies and extra elements, along with a brief summary of the diff results are
-    /// then written to a file in the output directory.
+    /// The discrepancies and extra elements, along with a brief summary of the diff results and the
+    /// first divergent entry, are then written to a file per table or segment in the output
+    /// directory.
     pub fn execute(self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<()> {
         warn!("Make sure the node is not running when running `reth db diff`!");
         // open second db
         let second_db_path: PathBuf = self.secondary_datadir.join("db").into();
         let second_db = open_db_read_only(&second_db_path, self.second_db.database_args())?;
 
-        let tables = match &self.table {
-            Some(table) => std::slice::from_ref(table),
-            None => Tables::ALL,
+        let tables = match (&self.table, &self.segment) {
+            (Some(table), _) => std::slice::from_ref(table),
+            (None, Some(_)) => &[],
+            (None, None) => Tables::ALL,
+        };
+        let segments = match (&self.segment, &self.table) {
+            (Some(segment), _) => vec![*segment],
+            (None, Some(_)) => vec![],
+            (None, None) => vec![
+                StaticFileSegment::Headers,
+                StaticFileSegment::Transactions,
+                StaticFileSegment::Receipts,
+            ],
         };
 
+        let block_range = self.block_range();
+        let tx_range = self.tx_range(tool)?;
+
         for table in tables {
             let mut primary_tx = tool.provider_factory.db_ref().tx()?;
             let mut secondary_tx = second_db.tx()?;
@@ -71,22 +117,134 @@ impl Command {
             primary_tx.disable_long_read_transaction_safety();
             secondary_tx.disable_long_read_transaction_safety();
 
+            let key_range = match table_key_kind(*table) {
+                TableKeyKind::BlockNumber => block_range.clone(),
+                TableKeyKind::TxNumber => tx_range.clone(),
+                TableKeyKind::Other => None,
+            };
+
             let output_dir = self.output.clone();
             tables_to_generic!(table, |Table| find_diffs::<Table>(
                 primary_tx,
                 secondary_tx,
+                key_range,
                 output_dir
             ))?;
         }
 
+        if !segments.is_empty() {
+            let secondary_static_files_path: PathBuf =
+                self.secondary_datadir.join("static_files").into();
+            let secondary_static_files =
+                StaticFileProvider::read_only(&secondary_static_files_path)?;
+
+            for segment in segments {
+                find_static_file_diffs(
+                    tool,
+                    &secondary_static_files,
+                    segment,
+                    self.from,
+                    self.to,
+                    self.output.as_ref(),
+                )?;
+            }
+        }
+
         Ok(())
     }
+
+    /// Returns the configured block range, if any.
+    fn block_range(&self) -> Option<RangeInclusive<BlockNumber>> {
+        if self.from.is_none() && self.to.is_none() {
+            return None
+        }
+        Some(self.from.unwrap_or_default()..=self.to.unwrap_or(BlockNumber::MAX))
+    }
+
+    /// Returns the range of transactions of the configured block range, if any, according to the
+    /// block body indices of the primary database.
+    fn tx_range(
+        &self,
+        tool: &DbTool<Arc<DatabaseEnv>>,
+    ) -> eyre::Result<Option<RangeInclusive<TxNumber>>> {
+        let Some(block_range) = self.block_range() else { return Ok(None) };
+        let provider = tool.provider_factory.provider()?;
+
+        let start = match provider.block_body_indices(*block_range.start())? {
+            Some(indices) => indices.first_tx_num(),
+            None => {
+                warn!(block = *block_range.start(), "Primary database has no body indices for the first block of the range, tables keyed by transaction number are not diffed");
+                TxNumber::MAX
+            }
+        };
+        let end = provider
+            .block_body_indices(*block_range.end())?
+            .map_or(TxNumber::MAX, |indices| indices.tx_num_range().end.saturating_sub(1));
+
+        Ok(Some(start..=end))
+    }
+}
+
+/// The kind of number the keys of a table start with, which determines how the block range of the
+/// diff applies to the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableKeyKind {
+    /// The keys start with a block number.
+    BlockNumber,
+    /// The keys start with a transaction number.
+    TxNumber,
+    /// The keys are not ordered by block, so the table is always diffed entirely.
+    Other,
+}
+
+/// Returns the [`TableKeyKind`] of the given table.
+const fn table_key_kind(table: Tables) -> TableKeyKind {
+    match table {
+        Tables::CanonicalHeaders |
+        Tables::HeaderTerminalDifficulties |
+        Tables::Headers |
+        Tables::BlockBodyIndices |
+        Tables::BlockOmmers |
+        Tables::BlockWithdrawals |
+        Tables::BlockRequests |
+        Tables::AccountChangeSets |
+        Tables::StorageChangeSets => TableKeyKind::BlockNumber,
+        Tables::Transactions |
+        Tables::TransactionBlocks |
+        Tables::Receipts |
+        Tables::TransactionSenders => TableKeyKind::TxNumber,
+        _ => TableKeyKind::Other,
+    }
+}
+
+/// Returns the number the encoded key starts with, see [`TableKeyKind`].
+fn key_number<K: Encode>(key: K) -> Option<u64> {
+    let encoded = key.encode();
+    let bytes = encoded.as_ref().get(..8)?;
+    Some(u64::from_be_bytes(bytes.try_into().ok()?))
+}
+
+/// Returns the smallest key of the table that starts with the given number, derived from the
+/// encoding of the first key of the table, or `None` if the table is empty.
+fn first_key_with_number<T: Table>(
+    cursor: &mut impl DbCursorRO<T>,
+    number: u64,
+) -> eyre::Result<Option<T::Key>> {
+    let Some((first_key, _)) = cursor.first()? else { return Ok(None) };
+    let mut encoded: Vec<u8> = first_key.encode().into();
+    if encoded.len() < 8 {
+        return Ok(None)
+    }
+    encoded.fill(0);
+    encoded[..8].copy_from_slice(&number.to_be_bytes());
+    Ok(T::Key::decode(&encoded).ok())
 }
 
 /// Find diffs for a table, then analyzing the result
 fn find_diffs<T: Table>(
     primary_tx: impl DbTx,
     secondary_tx: impl DbTx,
+    key_range: Option<RangeInclusive<u64>>,
     output_dir: impl AsRef<Path>,
 ) -> eyre::Result<()>
 where
@@ -96,7 +254,7 @@ where
     let table = T::NAME;
 
     info!("Analyzing table {table}...");
-    let result = find_diffs_advanced::<T>(&primary_tx, &secondary_tx)?;
+    let result = find_diffs_advanced::<T>(&primary_tx, &secondary_tx, key_range)?;
     info!("Done analyzing table {table}!");
 
     // Pretty info summary header: newline then header
@@ -142,6 +300,11 @@ where
         info!("No extra elements found in table {table}");
     }
 
+    if let Some(first) = result.first_divergence() {
+        writeln!(file, "First divergence: {first}")?;
+        info!("First divergence in table {table}: {first}");
+    }
+
     info!("Writing diff results for {table} to {file_name}...");
 
     if discrepancies > 0 {
@@ -165,64 +328,93 @@ where
     Ok(())
 }
 
-/// This diff algorithm is slightly different, it will walk _each_ table, cross-checking for the
-/// element in the other table.
+/// This diff algorithm walks both tables side by side in key order. If the keys are the same, the
+/// values are compared, otherwise the smaller key is missing in the other table.
+///
+/// If a key range is given, only the keys starting with a number in the range are compared, see
+/// [`TableKeyKind`].
 fn find_diffs_advanced<T: Table>(
     primary_tx: &impl DbTx,
     secondary_tx: &impl DbTx,
+    key_range: Option<RangeInclusive<u64>>,
 ) -> eyre::Result<TableDiffResult<T>>
 where
     T::Value: PartialEq,
     T::Key: Hash,
 {
-    // initialize the zipped walker
-    let mut primary_zip_cursor =
-        primary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-    let primary_walker = primary_zip_cursor.walk(None)?;
-
-    let mut secondary_zip_cursor =
-        secondary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-    let secondary_walker = secondary_zip_cursor.walk(None)?;
-    let zipped_cursor = primary_walker.zip(secondary_walker);
-
-    // initialize the cursors for seeking when we are cross checking elements
     let mut primary_cursor =
         primary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-
     let mut secondary_cursor =
         secondary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
 
+    let (primary_start, secondary_start) = match &key_range {
+        Some(range) => (
+            first_key_with_number(&mut primary_cursor, *range.start())?,
+            first_key_with_number(&mut secondary_cursor, *range.start())?,
+        ),
+        None => (None, None),
+    };
+
+    // the walkers start at the beginning of the range if a start key could be derived, entries
+    // outside of the range are skipped regardless
+    let number = |entry: &Result<TableRow<T>, DatabaseError>| {
+        entry.as_ref().ok().and_then(|(key, _)| key_number(key.clone()))
+    };
+    let before_start = |entry: &Result<TableRow<T>, DatabaseError>| {
+        key_range.as_ref().zip(number(entry)).is_some_and(|(range, n)| n < *range.start())
+    };
+    let until_end = |entry: &Result<TableRow<T>, DatabaseError>| {
+        key_range.as_ref().zip(number(entry)).map_or(true, |(range, n)| n <= *range.end())
+    };
+    let mut primary_walker =
+        primary_cursor.walk(primary_start)?.skip_while(before_start).take_while(until_end);
+    let mut secondary_walker =
+        secondary_cursor.walk(secondary_start)?.skip_while(before_start).take_while(until_end);
+
     let mut result = TableDiffResult::<T>::default();
 
-    // this loop will walk both tables, cross-checking for the element in the other table.
-    // it basically just loops through both tables at the same time. if the keys are different, it
-    // will check each key in the other table. if the keys are the same, it will compare the
-    // values
-    for (primary_entry, secondary_entry) in zipped_cursor {
-        let (primary_key, primary_value) = primary_entry?;
-        let (secondary_key, secondary_value) = secondary_entry?;
-
-        if primary_key != secondary_key {
-            // if the keys are different, we need to check if the key is in the other table
-            let crossed_secondary =
-                secondary_cursor.seek_exact(primary_key.clone())?.map(|(_, value)| value);
-            result.try_push_discrepancy(
-                primary_key.clone(),
-                Some(primary_value),
-                crossed_secondary,
-            );
-
-            // now do the same for the primary table
-            let crossed_primary =
-                primary_cursor.seek_exact(secondary_key.clone())?.map(|(_, value)| value);
-            result.try_push_discrepancy(
-                secondary_key.clone(),
-                crossed_primary,
-                Some(secondary_value),
-            );
-        } else {
-            // the keys are the same, so we need to compare the values
-            result.try_push_discrepancy(primary_key, Some(primary_value), Some(secondary_value));
+    let mut primary_entry = primary_walker.next().transpose()?;
+    let mut secondary_entry = secondary_walker.next().transpose()?;
+    loop {
+        match (primary_entry.take(), secondary_entry.take()) {
+            (None, None) => break,
+            (Some((primary_key, primary_value)), None) => {
+                result.try_push_discrepancy(primary_key, Some(primary_value), None);
+                primary_entry = primary_walker.next().transpose()?;
+            }
+            (None, Some((secondary_key, secondary_value))) => {
+                result.try_push_discrepancy(secondary_key, None, Some(secondary_value));
+                secondary_entry = secondary_walker.next().transpose()?;
+            }
+            (Some((primary_key, primary_value)), Some((secondary_key, secondary_value))) => {
+                // the tables are ordered by the encoded keys
+                match primary_key
+                    .clone()
+                    .encode()
+                    .as_ref()
+                    .cmp(secondary_key.clone().encode().as_ref())
+                {
+                    Ordering::Equal => {
+                        result.try_push_discrepancy(
+                            primary_key,
+                            Some(primary_value),
+                            Some(secondary_value),
+                        );
+                        primary_entry = primary_walker.next().transpose()?;
+                        secondary_entry = secondary_walker.next().transpose()?;
+                    }
+                    Ordering::Less => {
+                        result.try_push_discrepancy(primary_key, Some(primary_value), None);
+                        primary_entry = primary_walker.next().transpose()?;
+                        secondary_entry = Some((secondary_key, secondary_value));
+                    }
+                    Ordering::Greater => {
+                        result.try_push_discrepancy(secondary_key, None, Some(secondary_value));
+                        primary_entry = Some((primary_key, primary_value));
+                        secondary_entry = secondary_walker.next().transpose()?;
+                    }
+                }
+            }
         }
     }
 
@@ -255,6 +447,9 @@ where
 
     /// Any extra elements, and the table they are in
     extra_elements: HashMap<T::Key, ExtraTableElement<T>>,
+
+    /// The key of the first discrepancy or extra element, in the order of the table
+    first_key: Option<T::Key>,
 }
 
 impl<T> Default for TableDiffResult<T>
@@ -263,7 +458,7 @@ where
     T::Key: Hash,
 {
     fn default() -> Self {
-        Self { discrepancies: HashMap::new(), extra_elements: HashMap::new() }
+        Self { discrepancies: HashMap::new(), extra_elements: HashMap::new(), first_key: None }
     }
 }
 
@@ -273,13 +468,25 @@ where
 {
     /// Push a diff result into the discrepancies set.
     fn push_discrepancy(&mut self, discrepancy: TableDiffElement<T>) {
+        self.first_key.get_or_insert_with(|| discrepancy.key.clone());
         self.discrepancies.insert(discrepancy.key.clone(), discrepancy);
     }
 
     /// Push an extra element into the extra elements set.
     fn push_extra_element(&mut self, element: ExtraTableElement<T>) {
+        self.first_key.get_or_insert_with(|| element.key().clone());
         self.extra_elements.insert(element.key().clone(), element);
     }
+
+    /// Returns the first discrepancy or extra element in the order of the table, formatted with
+    /// the values of both databases.
+    fn first_divergence(&self) -> Option<String> {
+        let key = self.first_key.as_ref()?;
+        self.discrepancies
+            .get(key)
+            .map(|discrepancy| format!("{discrepancy:?}"))
+            .or_else(|| self.extra_elements.get(key).map(|element| format!("{element:?}")))
+    }
 }
 
 impl<T> TableDiffResult<T>
@@ -343,3 +550,150 @@ impl<T: Table> ExtraTableElement<T> {
         }
     }
 }
+
+/// Find diffs for a static file segment within the block range, then write the result to the
+/// output directory.
+///
+/// The headers are compared by block number. The transactions and receipts are compared by
+/// transaction number, where the transactions of each block are looked up in the block body
+/// indices of the primary database.
+fn find_static_file_diffs(
+    tool: &DbTool<Arc<DatabaseEnv>>,
+    secondary: &StaticFileProvider,
+    segment: StaticFileSegment,
+    from: Option<BlockNumber>,
+    to: Option<BlockNumber>,
+    output_dir: &Path,
+) -> eyre::Result<()> {
+    let primary = tool.provider_factory.static_file_provider();
+
+    let highest_block = primary
+        .get_highest_static_file_block(segment)
+        .max(secondary.get_highest_static_file_block(segment));
+    let Some(highest_block) = highest_block else {
+        info!("No static files for segment {segment} in either datadir, skipping");
+        return Ok(())
+    };
+    let block_range = from.unwrap_or_default()..=to.unwrap_or(highest_block).min(highest_block);
+
+    info!("Analyzing static file segment {segment} for blocks {block_range:?}...");
+    let mut result = SegmentDiffResult::default();
+    match segment {
+        StaticFileSegment::Headers => {
+            for block in block_range.clone() {
+                result.compare(
+                    block,
+                    not_found_as_none(primary.sealed_header(block))?,
+                    not_found_as_none(secondary.sealed_header(block))?,
+                );
+                result.compare(
+                    block,
+                    not_found_as_none(primary.header_td_by_number(block))?,
+                    not_found_as_none(secondary.header_td_by_number(block))?,
+                );
+            }
+        }
+        StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            let provider = tool.provider_factory.provider()?;
+            for block in block_range.clone() {
+                let Some(indices) = provider.block_body_indices(block)? else {
+                    warn!(block, "Primary database has no body indices for block, stopping diff of segment {segment}");
+                    break
+                };
+                for tx in indices.tx_num_range() {
+                    if segment == StaticFileSegment::Transactions {
+                        result.compare(
+                            tx,
+                            not_found_as_none(primary.transaction_by_id_no_hash(tx))?,
+                            not_found_as_none(secondary.transaction_by_id_no_hash(tx))?,
+                        );
+                    } else {
+                        result.compare(
+                            tx,
+                            not_found_as_none(primary.receipt(tx))?,
+                            not_found_as_none(secondary.receipt(tx))?,
+                        );
+                    }
+                }
+            }
+        }
+    }
+    info!("Done analyzing static file segment {segment}!");
+
+    fs::create_dir_all(output_dir)?;
+    let file_name = format!("static_file_{segment}.txt");
+    let mut file = File::create(output_dir.join(&file_name))?;
+
+    let key_name = if segment == StaticFileSegment::Headers { "block" } else { "transaction" };
+    writeln!(file, "Diff results for static file segment {segment}, blocks {block_range:?}")?;
+    writeln!(file, "Found {} discrepancies", result.discrepancies)?;
+    writeln!(file, "Found {} extra elements", result.extra_elements)?;
+    info!(
+        "Found {} discrepancies and {} extra elements in static file segment {segment}",
+        result.discrepancies, result.extra_elements
+    );
+
+    if let Some(first) = result.entries.first() {
+        writeln!(file, "First divergence at {key_name} {first}")?;
+        info!("First divergence in static file segment {segment} at {key_name} {first}");
+    }
+
+    if !result.entries.is_empty() {
+        writeln!(file, "Divergent entries:")?;
+    }
+    for entry in &result.entries {
+        writeln!(file, "{key_name} {entry}")?;
+    }
+
+    info!("Done writing diff results for {segment} to {}", output_dir.join(file_name).display());
+    Ok(())
+}
+
+/// Maps the errors of a static file provider for a block or transaction that is not in the static
+/// files to `None`.
+fn not_found_as_none<T>(result: ProviderResult<Option<T>>) -> ProviderResult<Option<T>> {
+    match result {
+        Err(
+            ProviderError::MissingStaticFileBlock(..) |
+            ProviderError::MissingStaticFileTx(..) |
+            ProviderError::ExpiredStaticFileBlock(..) |
+            ProviderError::ExpiredStaticFileTx(..),
+        ) => Ok(None),
+        result => result,
+    }
+}
+
+/// The diff result for a static file segment.
+#[derive(Debug, Default)]
+struct SegmentDiffResult {
+    /// The number of entries that are in both static files, but are different
+    discrepancies: usize,
+
+    /// The number of entries that are only in one of the static files
+    extra_elements: usize,
+
+    /// All divergent entries, formatted with their block or transaction number
+    entries: Vec<String>,
+}
+
+impl SegmentDiffResult {
+    /// Compares the entries of both static files for the given block or transaction number,
+    /// recording them if they are different.
+    fn compare<V: PartialEq + Debug>(&mut self, number: u64, first: Option<V>, second: Option<V>) {
+        match (first, second) {
+            (Some(first), Some(second)) if first != second => {
+                self.discrepancies += 1;
+                self.entries.push(format!("{number}: first: {first:?}, second: {second:?}"));
+            }
+            (Some(first), None) => {
+                self.extra_elements += 1;
+                self.entries.push(format!("{number}: only in first: {first:?}"));
+            }
+            (None, Some(second)) => {
+                self.extra_elements += 1;
+                self.entries.push(format!("{number}: only in second: {second:?}"));
+            }
+            _ => {}
+        }
+    }
+}
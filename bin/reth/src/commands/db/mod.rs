@@ -11,6 +11,7 @@ mod clear;
 mod diff;
 mod get;
 mod list;
+mod repair;
 mod stats;
 /// DB List TUI
 mod tui;
@@ -34,7 +35,8 @@ pub enum Subcommands {
     List(list::Command),
     /// Calculates the content checksum of a table
     Checksum(checksum::Command),
-    /// Create a diff between two database tables or two entire databases.
+    /// Create a diff between two database tables or two entire datadirs, including their static
+    /// files.
     Diff(diff::Command),
     /// Gets the content of a table for the given key
     Get(get::Command),
@@ -46,6 +48,8 @@ pub enum Subcommands {
     },
     /// Deletes all table entries
     Clear(clear::Command),
+    /// Detects and repairs known inconsistencies of the database and static files
+    Repair(repair::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -120,6 +124,11 @@ impl Command {
                 let Environment { provider_factory, .. } = self.env.init(AccessRights::RW)?;
                 command.execute(provider_factory)?;
             }
+            Subcommands::Repair(command) => {
+                let Environment { provider_factory, config, .. } =
+                    self.env.init(AccessRights::RW)?;
+                command.execute(provider_factory, &config)?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
@@ -0,0 +1,406 @@
+use clap::{Parser, ValueEnum};
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use reth_config::Config;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO, DbDupCursorRW},
+    database::Database,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_primitives::{BlockNumber, StaticFileSegment};
+use reth_provider::{
+    BlockNumReader, BlockReader, DatabaseProvider, ProviderError, ProviderFactory,
+    PruneCheckpointReader, StageCheckpointReader, StageCheckpointWriter, StaticFileProviderFactory,
+    StaticFileWriter, TransactionsProvider,
+};
+use reth_prune_types::PruneSegment;
+use reth_stages::{StageCheckpoint, StageId};
+use std::fmt;
+use tracing::*;
+
+/// Identifier of the progress of the [`Routine::TxHashIndex`] routine, which is saved as a stage
+/// checkpoint progress so that an interrupted repair resumes where it stopped.
+const TX_HASH_INDEX_PROGRESS: StageId = StageId::Other("RepairTxHashIndex");
+
+/// The arguments for the `reth db repair` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The repair routines to run. All routines are run if none is given.
+    #[arg(long = "routine", value_enum)]
+    routines: Vec<Routine>,
+
+    /// Only detect and report the inconsistencies, without repairing them.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// The number of transactions checked by the transaction hash index routine between commits
+    /// of its progress.
+    #[arg(long, default_value_t = 100_000)]
+    batch_size: u64,
+}
+
+/// A class of inconsistencies, repaired by a targeted routine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Routine {
+    /// Static file segments ending ahead of or behind the checkpoint of their stage.
+    ///
+    /// Static files ahead of the checkpoint are pruned to it. Static files behind the checkpoint
+    /// whose missing blocks are not in the database require an unwind, which is reported.
+    StaticFiles,
+    /// Block body indices at the tip without a header or transactions, which are deleted.
+    BodyIndices,
+    /// Indexed transactions missing from the transaction hash index, which are inserted.
+    TxHashIndex,
+    /// Storage trie nodes of accounts without storage, which are deleted.
+    TrieNodes,
+}
+
+impl fmt::Display for Routine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routine = self.to_possible_value().expect("no skipped variants");
+        f.write_str(routine.get_name())
+    }
+}
+
+/// The report of a repair routine.
+#[derive(Debug)]
+struct RoutineReport {
+    /// The routine.
+    routine: Routine,
+    /// Number of found inconsistencies.
+    found: u64,
+    /// Number of repaired inconsistencies.
+    repaired: u64,
+    /// Details of the found inconsistencies.
+    notes: Vec<String>,
+}
+
+impl RoutineReport {
+    const fn new(routine: Routine) -> Self {
+        Self { routine, found: 0, repaired: 0, notes: Vec::new() }
+    }
+}
+
+impl Command {
+    /// Execute `db repair` command
+    pub fn execute<DB: Database>(
+        self,
+        provider_factory: ProviderFactory<DB>,
+        config: &Config,
+    ) -> eyre::Result<()> {
+        let routines =
+            if self.routines.is_empty() { Routine::value_variants() } else { &self.routines[..] };
+
+        let mut reports = Vec::with_capacity(routines.len());
+        for routine in routines {
+            info!(target: "reth::cli", %routine, dry_run = self.dry_run, "Running repair routine");
+            let report = match routine {
+                Routine::StaticFiles => {
+                    let has_receipt_pruning =
+                        config.prune.as_ref().map_or(false, |prune| prune.has_receipts_pruning());
+                    self.repair_static_files(&provider_factory, has_receipt_pruning)?
+                }
+                Routine::BodyIndices => self.repair_body_indices(&provider_factory)?,
+                Routine::TxHashIndex => self.repair_tx_hash_index(&provider_factory)?,
+                Routine::TrieNodes => self.repair_trie_nodes(&provider_factory)?,
+            };
+            info!(target: "reth::cli", %routine, found = report.found, repaired = report.repaired, "Finished repair routine");
+            reports.push(report);
+        }
+
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header(["Routine", "Found", "Repaired"]);
+        for report in &reports {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(report.routine))
+                .add_cell(Cell::new(report.found))
+                .add_cell(Cell::new(report.repaired));
+            table.add_row(row);
+        }
+        println!("{table}");
+
+        for report in &reports {
+            for note in &report.notes {
+                println!("{}: {note}", report.routine);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prunes static files ahead of the checkpoint of their stage, and reports static files behind
+    /// it whose missing blocks are not in the database.
+    fn repair_static_files<DB: Database>(
+        &self,
+        provider_factory: &ProviderFactory<DB>,
+        has_receipt_pruning: bool,
+    ) -> eyre::Result<RoutineReport> {
+        let mut report = RoutineReport::new(Routine::StaticFiles);
+        let provider = provider_factory.provider()?;
+        let static_file_provider = provider_factory.static_file_provider();
+
+        for (segment, stage) in [
+            (StaticFileSegment::Headers, StageId::Headers),
+            (StaticFileSegment::Transactions, StageId::Bodies),
+            (StaticFileSegment::Receipts, StageId::Execution),
+        ] {
+            if has_receipt_pruning && segment.is_receipts() {
+                // Pruned nodes (including full node) do not store receipts as static files.
+                continue
+            }
+
+            let Some(highest_block) = static_file_provider.get_highest_static_file_block(segment)
+            else {
+                continue
+            };
+            let checkpoint = provider.get_stage_checkpoint(stage)?.unwrap_or_default().block_number;
+
+            if highest_block > checkpoint {
+                report.found += 1;
+                report.notes.push(format!(
+                    "{segment} static files end at block {highest_block}, ahead of the {stage} checkpoint at block {checkpoint}"
+                ));
+                if self.dry_run {
+                    continue
+                }
+
+                let mut writer = static_file_provider.latest_writer(segment)?;
+                if segment.is_headers() {
+                    writer.prune_headers(highest_block - checkpoint)?;
+                } else {
+                    let next_tx = provider
+                        .block_body_indices(checkpoint)?
+                        .ok_or(ProviderError::BlockBodyIndicesNotFound(checkpoint))?
+                        .next_tx_num();
+                    let highest_tx = static_file_provider
+                        .get_highest_static_file_tx(segment)
+                        .map_or(0, |highest_tx| highest_tx + 1);
+                    let to_delete = highest_tx.saturating_sub(next_tx);
+                    if segment.is_receipts() {
+                        writer.prune_receipts(to_delete, checkpoint)?;
+                    } else {
+                        writer.prune_transactions(to_delete, checkpoint)?;
+                    }
+                }
+                writer.commit()?;
+                report.repaired += 1;
+            } else if highest_block < checkpoint &&
+                !has_database_block(&provider, segment, highest_block + 1)?
+            {
+                report.found += 1;
+                report.notes.push(format!(
+                    "{segment} static files end at block {highest_block}, behind the {stage} checkpoint at block {checkpoint}, unwind with `reth stage unwind to-block {highest_block}`"
+                ));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Deletes the block body indices at the tip which have no header, or whose transactions are
+    /// missing.
+    fn repair_body_indices<DB: Database>(
+        &self,
+        provider_factory: &ProviderFactory<DB>,
+    ) -> eyre::Result<RoutineReport> {
+        let mut report = RoutineReport::new(Routine::BodyIndices);
+        let provider_rw = provider_factory.provider_rw()?;
+        let tx = provider_rw.tx_ref();
+
+        let last_header = provider_rw.last_block_number()?;
+        let last_tx =
+            tx.cursor_read::<tables::Transactions>()?.last()?.map(|(tx_num, _)| tx_num).max(
+                provider_factory
+                    .static_file_provider()
+                    .get_highest_static_file_tx(StaticFileSegment::Transactions),
+            );
+
+        let mut last_body = None;
+        let mut dangling = Vec::new();
+        for entry in tx.cursor_read::<tables::BlockBodyIndices>()?.walk_back(None)? {
+            let (block_number, indices) = entry?;
+            let has_transactions = indices.tx_count == 0 ||
+                last_tx.is_some_and(|last_tx| indices.last_tx_num() <= last_tx);
+            if block_number <= last_header && has_transactions {
+                last_body = Some(block_number);
+                break
+            }
+            dangling.push((block_number, indices));
+        }
+
+        report.found = dangling.len() as u64;
+        if let (Some((first, _)), Some((last, _))) = (dangling.last(), dangling.first()) {
+            report.notes.push(format!(
+                "body indices of blocks {first}..={last} have no header or transactions"
+            ));
+        }
+        if self.dry_run || dangling.is_empty() {
+            return Ok(report)
+        }
+
+        for (block_number, indices) in dangling {
+            tx.delete::<tables::BlockBodyIndices>(block_number, None)?;
+            tx.delete::<tables::BlockOmmers>(block_number, None)?;
+            tx.delete::<tables::BlockWithdrawals>(block_number, None)?;
+            tx.delete::<tables::BlockRequests>(block_number, None)?;
+            if indices.tx_count > 0 {
+                tx.delete::<tables::TransactionBlocks>(indices.last_tx_num(), None)?;
+            }
+            report.repaired += 1;
+        }
+
+        // the bodies stage can't be ahead of the stored bodies
+        let last_body = last_body.unwrap_or_default();
+        if let Some(checkpoint) = provider_rw.get_stage_checkpoint(StageId::Bodies)? {
+            if checkpoint.block_number > last_body {
+                provider_rw
+                    .save_stage_checkpoint(StageId::Bodies, StageCheckpoint::new(last_body))?;
+                report.notes.push(format!("reset the Bodies checkpoint to block {last_body}"));
+            }
+        }
+
+        provider_rw.commit()?;
+        Ok(report)
+    }
+
+    /// Inserts the transactions up to the checkpoint of the transaction lookup stage that are
+    /// missing from the transaction hash index.
+    ///
+    /// The progress is committed every [`Command::batch_size`] transactions, and an interrupted
+    /// repair resumes from it.
+    fn repair_tx_hash_index<DB: Database>(
+        &self,
+        provider_factory: &ProviderFactory<DB>,
+    ) -> eyre::Result<RoutineReport> {
+        let mut report = RoutineReport::new(Routine::TxHashIndex);
+        let provider = provider_factory.provider()?;
+
+        let checkpoint = provider
+            .get_stage_checkpoint(StageId::TransactionLookup)?
+            .unwrap_or_default()
+            .block_number;
+        let Some(end) =
+            provider.block_body_indices(checkpoint)?.map(|indices| indices.next_tx_num())
+        else {
+            return Ok(report)
+        };
+
+        // transactions below the prune checkpoint are not indexed
+        let mut start = provider
+            .get_prune_checkpoint(PruneSegment::TransactionLookup)?
+            .and_then(|checkpoint| checkpoint.tx_number)
+            .map_or(0, |tx_number| tx_number + 1);
+        if let Some(progress) = provider.get_stage_checkpoint_progress(TX_HASH_INDEX_PROGRESS)? {
+            let progress = u64::from_be_bytes(
+                progress.try_into().map_err(|_| eyre::eyre!("invalid repair progress"))?,
+            );
+            info!(target: "reth::cli", progress, "Resuming transaction hash index repair");
+            start = start.max(progress);
+        }
+        drop(provider);
+
+        while start < end {
+            let batch_end = end.min(start + self.batch_size.max(1));
+            let provider_rw = provider_factory.provider_rw()?;
+            let transactions = provider_rw.transactions_by_tx_range(start..batch_end)?;
+            for (tx_number, transaction) in (start..).zip(transactions) {
+                let hash = transaction.hash();
+                if provider_rw.tx_ref().get::<tables::TransactionHashNumbers>(hash)?.is_none() {
+                    report.found += 1;
+                    if !self.dry_run {
+                        provider_rw
+                            .tx_ref()
+                            .put::<tables::TransactionHashNumbers>(hash, tx_number)?;
+                        report.repaired += 1;
+                    }
+                }
+            }
+
+            start = batch_end;
+            if !self.dry_run {
+                provider_rw.save_stage_checkpoint_progress(
+                    TX_HASH_INDEX_PROGRESS,
+                    start.to_be_bytes().to_vec(),
+                )?;
+                provider_rw.commit()?;
+            }
+            info!(target: "reth::cli", checked = start, total = end, "Checked transaction hash index");
+        }
+
+        if report.found > 0 {
+            report.notes.push(format!("{} transactions are missing from the index", report.found));
+        }
+        if !self.dry_run {
+            // the repair finished, the next one starts from the beginning
+            let provider_rw = provider_factory.provider_rw()?;
+            provider_rw.tx_ref().delete::<tables::StageCheckpointProgresses>(
+                TX_HASH_INDEX_PROGRESS.to_string(),
+                None,
+            )?;
+            provider_rw.commit()?;
+        }
+
+        Ok(report)
+    }
+
+    /// Deletes the storage tries of accounts which don't exist or have no storage.
+    fn repair_trie_nodes<DB: Database>(
+        &self,
+        provider_factory: &ProviderFactory<DB>,
+    ) -> eyre::Result<RoutineReport> {
+        let mut report = RoutineReport::new(Routine::TrieNodes);
+        let provider_rw = provider_factory.provider_rw()?;
+        let tx = provider_rw.tx_ref();
+
+        let mut hashed_account_cursor = tx.cursor_read::<tables::HashedAccounts>()?;
+        let mut hashed_storage_cursor = tx.cursor_dup_read::<tables::HashedStorages>()?;
+        let mut storage_trie_cursor = tx.cursor_dup_write::<tables::StoragesTrie>()?;
+
+        let mut entry = storage_trie_cursor.first()?;
+        while let Some((hashed_address, _)) = entry {
+            let orphaned = hashed_account_cursor.seek_exact(hashed_address)?.is_none() ||
+                hashed_storage_cursor.seek_exact(hashed_address)?.is_none();
+            if orphaned {
+                report.found += 1;
+                if !self.dry_run {
+                    storage_trie_cursor.delete_current_duplicates()?;
+                    report.repaired += 1;
+                    // the cursor is positioned after the deleted entries
+                    entry = storage_trie_cursor.seek(hashed_address)?;
+                    continue
+                }
+            }
+            entry = storage_trie_cursor.next_no_dup()?;
+        }
+
+        if report.found > 0 {
+            report.notes.push(format!("{} accounts have orphaned storage tries", report.found));
+        }
+        if !self.dry_run {
+            provider_rw.commit()?;
+        }
+        Ok(report)
+    }
+}
+
+/// Returns `true` if the data of the block of the static file segment is in the database.
+fn has_database_block<TX: DbTx>(
+    provider: &DatabaseProvider<TX>,
+    segment: StaticFileSegment,
+    block_number: BlockNumber,
+) -> Result<bool, ProviderError> {
+    if segment.is_headers() {
+        return Ok(provider.tx_ref().get::<tables::Headers>(block_number)?.is_some())
+    }
+
+    let Some(indices) = provider.block_body_indices(block_number)? else { return Ok(false) };
+    if indices.tx_count == 0 {
+        return Ok(true)
+    }
+    Ok(if segment.is_receipts() {
+        provider.tx_ref().get::<tables::Receipts>(indices.first_tx_num)?.is_some()
+    } else {
+        provider.tx_ref().get::<tables::Transactions>(indices.first_tx_num)?.is_some()
+    })
+}
@@ -0,0 +1,90 @@
+//! Command for comparing the execution of a block with another client.
+
+use crate::commands::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use jsonrpsee::http_client::HttpClientBuilder;
+use reth_differential::{fetch_remote_outcomes, DifferentialExecutor, DivergenceReport};
+use reth_fs_util as fs;
+use reth_primitives::BlockNumber;
+use reth_provider::{BlockReader, ChainSpecProvider, HeaderProvider, TransactionVariant};
+use reth_revm::database::StateProviderDatabase;
+use std::path::PathBuf;
+use tracing::*;
+
+/// `reth debug differential` command
+/// This debug routine re-executes a block on top of the state of its parent, and compares the
+/// status, gas used, logs and state changes of each transaction with the receipts and
+/// `prestateTracer` traces of another client fetched over RPC.
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The number of the block to compare.
+    #[arg(long)]
+    block: BlockNumber,
+
+    /// The HTTP RPC URL of the other client, which must serve `debug_traceBlockByNumber` and
+    /// `eth_getBlockReceipts`.
+    #[arg(long, value_name = "URL")]
+    rpc_url: String,
+
+    /// The path to write the JSON divergence report to.
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `debug differential` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+
+        if self.block == 0 {
+            eyre::bail!("the genesis block has no transactions to compare")
+        }
+
+        let block = provider_factory
+            .block_with_senders(self.block.into(), TransactionVariant::WithHash)?
+            .ok_or_else(|| eyre::eyre!("block {} not found", self.block))?;
+        let block_hash = block.header.hash_slow();
+        let total_difficulty = provider_factory
+            .header_td_by_number(self.block)?
+            .ok_or_else(|| eyre::eyre!("total difficulty of block {} not found", self.block))?;
+
+        #[cfg(not(feature = "optimism"))]
+        let evm_config = reth_node_ethereum::EthEvmConfig::default();
+
+        #[cfg(feature = "optimism")]
+        let evm_config = reth_node_optimism::OptimismEvmConfig::default();
+
+        info!(target: "reth::cli", block = self.block, "Executing block");
+        let db =
+            StateProviderDatabase::new(provider_factory.history_by_block_number(self.block - 1)?);
+        let local = DifferentialExecutor::new(provider_factory.chain_spec(), evm_config).execute(
+            &block,
+            total_difficulty,
+            db,
+        )?;
+
+        info!(target: "reth::cli", rpc_url = %self.rpc_url, "Fetching traces of the other client");
+        let client = HttpClientBuilder::default().build(&self.rpc_url)?;
+        let remote = fetch_remote_outcomes(&client, self.block).await?;
+
+        let report = DivergenceReport::new(self.block, block_hash, &local, &remote);
+        for divergence in &report.divergences {
+            warn!(target: "reth::cli", %divergence, "Divergence");
+        }
+        if report.is_empty() {
+            info!(target: "reth::cli", transactions = report.transactions, "Execution matches the other client");
+        } else {
+            warn!(target: "reth::cli", divergences = report.divergences.len(), first = %report.first().expect("not empty"), "Execution diverges from the other client");
+        }
+
+        if let Some(output) = &self.output {
+            fs::write(output, serde_json::to_string_pretty(&report)?)?;
+            info!(target: "reth::cli", path = %output.display(), "Wrote divergence report");
+        }
+
+        Ok(())
+    }
+}
@@ -4,10 +4,12 @@ use clap::{Parser, Subcommand};
 use reth_cli_runner::CliContext;
 
 mod build_block;
+mod differential;
 mod execution;
 mod in_memory_merkle;
 mod merkle;
 mod replay_engine;
+mod trie_bench;
 
 /// `reth debug` command
 #[derive(Debug, Parser)]
@@ -29,6 +31,10 @@ pub enum Subcommands {
     BuildBlock(build_block::Command),
     /// Debug engine API by replaying stored messages.
     ReplayEngine(replay_engine::Command),
+    /// Debug the execution of a block by comparing it with another client.
+    Differential(differential::Command),
+    /// Benchmark the state root calculations by replaying recorded hashed state changes.
+    TrieBench(trie_bench::Command),
 }
 
 impl Command {
@@ -40,6 +46,8 @@ impl Command {
             Subcommands::InMemoryMerkle(command) => command.execute(ctx).await,
             Subcommands::BuildBlock(command) => command.execute(ctx).await,
             Subcommands::ReplayEngine(command) => command.execute(ctx).await,
+            Subcommands::Differential(command) => command.execute().await,
+            Subcommands::TrieBench(command) => command.execute().await,
         }
     }
 }
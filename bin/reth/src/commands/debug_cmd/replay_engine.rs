@@ -27,7 +27,11 @@ use reth_stages::Pipeline;
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::noop::NoopTransactionPool;
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::oneshot;
 use tracing::*;
 
@@ -49,6 +53,14 @@ pub struct Command {
     /// The number of milliseconds between Engine API messages.
     #[arg(long = "interval", default_value_t = 1_000)]
     interval: u64,
+
+    /// Replay the Engine API messages with the delays between them as they were recorded,
+    /// divided by the given factor, instead of a fixed interval.
+    ///
+    /// A factor of `1` replays the messages at the recorded speed, `10` replays them ten times
+    /// faster.
+    #[arg(long = "speed", value_name = "FACTOR", conflicts_with = "interval")]
+    speed: Option<f64>,
 }
 
 impl Command {
@@ -75,6 +87,10 @@ impl Command {
 
     /// Execute `debug replay-engine` command
     pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
+        if let Some(speed) = self.speed {
+            eyre::ensure!(speed.is_finite() && speed > 0., "speed must be a positive number");
+        }
+
         let Environment { provider_factory, config, data_dir } = self.env.init(AccessRights::RW)?;
 
         let consensus: Arc<dyn Consensus> =
@@ -166,7 +182,16 @@ impl Command {
         });
 
         let engine_api_store = EngineMessageStore::new(self.engine_api_store.clone());
-        for filepath in engine_api_store.engine_messages_iter()? {
+        let replay_started_at = Instant::now();
+        let mut first_received_at = None;
+        for (received_at, filepath) in engine_api_store.timestamped_engine_messages_iter()? {
+            if let Some(speed) = self.speed {
+                // Wait until the message is due, relative to the first message
+                let first_received_at = *first_received_at.get_or_insert(received_at);
+                let offset = Duration::from_millis(received_at - first_received_at).div_f64(speed);
+                tokio::time::sleep_until((replay_started_at + offset).into()).await;
+            }
+
             let contents =
                 fs::read(&filepath).wrap_err(format!("failed to read: {}", filepath.display()))?;
             let message = serde_json::from_slice(&contents)
@@ -185,7 +210,9 @@ impl Command {
             };
 
             // Pause before next message
-            tokio::time::sleep(Duration::from_millis(self.interval)).await;
+            if self.speed.is_none() {
+                tokio::time::sleep(Duration::from_millis(self.interval)).await;
+            }
         }
 
         info!(target: "reth::cli", "Finished replaying engine API messages");
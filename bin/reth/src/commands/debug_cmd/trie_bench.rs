@@ -0,0 +1,220 @@
+//! Command for benchmarking the state root calculations with the hashed state changes of recorded
+//! blocks.
+
+use crate::commands::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::{Parser, Subcommand};
+use reth_db::DatabaseEnv;
+use reth_primitives::BlockNumber;
+use reth_provider::{providers::ConsistentDbView, ProviderFactory};
+use reth_tasks::pool::BlockingTaskPool;
+use reth_trie::{
+    hashed_cursor::{CountingHashedCursorFactory, HashedPostStateCursorFactory},
+    trie_cursor::CountingTrieCursorFactory,
+    HashedPostState, StateRoot,
+};
+use reth_trie_parallel::{async_root::AsyncStateRoot, parallel_root::ParallelStateRoot};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::*;
+
+/// `reth debug trie-bench` command
+///
+/// Records the hashed state changes of a range of blocks, and replays them through the state root
+/// implementations, reporting the wall time and the number of database reads of every
+/// implementation.
+///
+/// The recorded state of a block holds the values before the block of all keys changed by the
+/// block. Replayed on top of a database that is synced past the block, it touches the same trie
+/// nodes as the block did, so the work of the state root calculations is representative, even
+/// though the calculated roots are not.
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth debug trie-bench` subcommands
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    /// Record the hashed state changes of a range of blocks.
+    Record {
+        /// The first block of the range.
+        #[arg(long)]
+        from: BlockNumber,
+
+        /// The last block of the range.
+        #[arg(long)]
+        to: BlockNumber,
+
+        /// The file to write the recorded states to, one JSON object per line.
+        #[arg(long, value_name = "FILE")]
+        output: PathBuf,
+    },
+    /// Replay recorded hashed state changes through the state root implementations.
+    Replay {
+        /// The file to read the recorded states from.
+        #[arg(long, value_name = "FILE")]
+        input: PathBuf,
+
+        /// The number of times every state is replayed.
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+    },
+}
+
+/// The hashed state changes of a block.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedState {
+    block_number: BlockNumber,
+    state: HashedPostState,
+}
+
+impl Command {
+    /// Execute `debug trie-bench` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+
+        match self.command {
+            Subcommands::Record { from, to, output } => record(&provider_factory, from, to, output),
+            Subcommands::Replay { input, iterations } => {
+                replay(provider_factory, input, iterations).await
+            }
+        }
+    }
+}
+
+/// Writes the hashed state changes of the blocks to the output file.
+fn record(
+    provider_factory: &ProviderFactory<Arc<DatabaseEnv>>,
+    from: BlockNumber,
+    to: BlockNumber,
+    output: PathBuf,
+) -> eyre::Result<()> {
+    eyre::ensure!(from <= to, "invalid block range {from}..={to}");
+
+    let provider = provider_factory.provider()?;
+    let mut writer = BufWriter::new(File::create(&output)?);
+    for block_number in from..=to {
+        let state =
+            HashedPostState::from_revert_range(provider.tx_ref(), block_number..=block_number)?;
+        serde_json::to_writer(&mut writer, &RecordedState { block_number, state })?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+
+    info!(target: "reth::cli", blocks = to - from + 1, output = %output.display(), "Recorded hashed state changes");
+    Ok(())
+}
+
+/// Replays the recorded states of the input file through the state root implementations.
+async fn replay(
+    provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+    input: PathBuf,
+    iterations: usize,
+) -> eyre::Result<()> {
+    let states = BufReader::new(File::open(&input)?)
+        .lines()
+        .map(|line| Ok(serde_json::from_str::<RecordedState>(&line?)?))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    info!(target: "reth::cli", blocks = states.len(), iterations, "Replaying recorded hashed state changes");
+
+    let view = ConsistentDbView::new_with_latest_tip(provider_factory.clone())?;
+    let blocking_pool = BlockingTaskPool::build()?;
+
+    // incremental root, reading from the database through counting cursors
+    let mut report = Report::default();
+    for _ in 0..iterations {
+        for RecordedState { state, .. } in &states {
+            let provider = provider_factory.provider()?;
+            let tx = provider.tx_ref();
+            let trie_cursor_factory = CountingTrieCursorFactory::new(tx);
+            let hashed_cursor_factory = CountingHashedCursorFactory::new(tx);
+
+            let started_at = Instant::now();
+            let sorted_state = state.clone().into_sorted();
+            StateRoot::from_tx(tx)
+                .with_trie_cursor_factory(trie_cursor_factory.clone())
+                .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                    hashed_cursor_factory.clone(),
+                    &sorted_state,
+                ))
+                .with_prefix_sets(state.construct_prefix_sets())
+                .root()?;
+            report.record(started_at.elapsed());
+            report.trie_node_reads += trie_cursor_factory.reads();
+            report.hashed_entry_reads += hashed_cursor_factory.reads();
+        }
+    }
+    report.log("incremental root");
+
+    let mut report = Report::default();
+    for _ in 0..iterations {
+        for RecordedState { state, .. } in &states {
+            let started_at = Instant::now();
+            ParallelStateRoot::new(view.clone(), state.clone()).incremental_root()?;
+            report.record(started_at.elapsed());
+        }
+    }
+    report.log("parallel root");
+
+    let mut report = Report::default();
+    for _ in 0..iterations {
+        for RecordedState { state, .. } in &states {
+            let started_at = Instant::now();
+            AsyncStateRoot::new(view.clone(), blocking_pool.clone(), state.clone())
+                .incremental_root()
+                .await?;
+            report.record(started_at.elapsed());
+        }
+    }
+    report.log("async root");
+
+    Ok(())
+}
+
+/// The wall time and database reads of the replayed states of an implementation.
+#[derive(Debug, Default)]
+struct Report {
+    /// The wall time of every replayed state.
+    durations: Vec<Duration>,
+    /// The number of trie nodes read, if counted.
+    trie_node_reads: u64,
+    /// The number of hashed accounts and storage slots read, if counted.
+    hashed_entry_reads: u64,
+}
+
+impl Report {
+    fn record(&mut self, duration: Duration) {
+        self.durations.push(duration);
+    }
+
+    fn log(mut self, implementation: &str) {
+        if self.durations.is_empty() {
+            return
+        }
+        self.durations.sort_unstable();
+        let total = self.durations.iter().sum::<Duration>();
+        let percentile = |p: usize| self.durations[(self.durations.len() - 1) * p / 100];
+        info!(
+            target: "reth::cli",
+            implementation,
+            total = ?total,
+            mean = ?total / self.durations.len() as u32,
+            p50 = ?percentile(50),
+            p99 = ?percentile(99),
+            max = ?percentile(100),
+            trie_node_reads = self.trie_node_reads,
+            hashed_entry_reads = self.hashed_entry_reads,
+            "Replayed recorded states"
+        );
+    }
+}
@@ -0,0 +1,541 @@
+//! Command that diagnoses common problems of the environment a node runs in.
+
+use crate::commands::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use human_bytes::human_bytes;
+use reth_discv4::DEFAULT_DISCOVERY_PORT;
+use reth_provider::StaticFileProviderFactory;
+use reth_rpc_layer::read_jwt_secrets;
+use reth_rpc_server_types::constants::{
+    DEFAULT_AUTH_PORT, DEFAULT_HTTP_RPC_PORT, DEFAULT_WS_RPC_PORT,
+};
+use std::{
+    fmt, io,
+    net::{Ipv4Addr, TcpListener, UdpSocket},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Free disk space below which a warning is reported.
+const MIN_FREE_SPACE: u64 = 100 * 1024 * 1024 * 1024;
+/// Share of free inodes below which a warning is reported, in percent.
+const MIN_FREE_INODES_PERCENT: u64 = 5;
+/// Clock offset above which a warning is reported.
+const MAX_CLOCK_OFFSET: Duration = Duration::from_millis(500);
+/// Open file limit below which a warning is reported.
+const MIN_OPEN_FILES: u64 = 65_536;
+/// Seconds between the NTP epoch, 1900-01-01, and the unix epoch.
+const NTP_UNIX_EPOCH_OFFSET: f64 = 2_208_988_800.0;
+
+/// `reth doctor` command
+#[derive(Debug, Parser)]
+pub struct DoctorCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The network listening port of the node.
+    #[arg(long = "port", value_name = "PORT", default_value_t = DEFAULT_DISCOVERY_PORT)]
+    port: u16,
+
+    /// The UDP port of the discovery of the node.
+    #[arg(long = "discovery.port", value_name = "DISCOVERY_PORT", default_value_t = DEFAULT_DISCOVERY_PORT)]
+    discovery_port: u16,
+
+    /// The port of the HTTP RPC server of the node.
+    #[arg(long = "http.port", default_value_t = DEFAULT_HTTP_RPC_PORT)]
+    http_port: u16,
+
+    /// The port of the WS RPC server of the node.
+    #[arg(long = "ws.port", default_value_t = DEFAULT_WS_RPC_PORT)]
+    ws_port: u16,
+
+    /// The port of the auth server of the node.
+    #[arg(long = "authrpc.port", default_value_t = DEFAULT_AUTH_PORT)]
+    auth_port: u16,
+
+    /// The path to the JWT secret of the auth server, defaults to the `jwt.hex` file of the data
+    /// directory.
+    #[arg(long = "authrpc.jwtsecret", value_name = "PATH")]
+    auth_jwtsecret: Option<PathBuf>,
+
+    /// The NTP server that is queried to check the clock skew.
+    #[arg(long, value_name = "HOST:PORT", default_value = "pool.ntp.org:123")]
+    ntp_server: String,
+
+    /// Skips the checks that require access to the internet.
+    #[arg(long)]
+    offline: bool,
+}
+
+/// The severity of a [`Finding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    /// The check passed.
+    Ok,
+    /// The check found a problem that may degrade the node.
+    Warning,
+    /// The check found a problem that prevents the node from working.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => f.write_str("ok"),
+            Self::Warning => f.write_str("warning"),
+            Self::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// The result of a diagnostic check.
+#[derive(Debug)]
+struct Finding {
+    /// The name of the check.
+    check: &'static str,
+    /// The severity of the finding.
+    severity: Severity,
+    /// What was found, and how to fix it.
+    message: String,
+}
+
+impl Finding {
+    fn new(check: &'static str, severity: Severity, message: impl Into<String>) -> Self {
+        Self { check, severity, message: message.into() }
+    }
+}
+
+impl DoctorCommand {
+    /// Execute `doctor` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.env.datadir.clone().resolve_datadir(self.env.chain.chain);
+
+        let mut findings = vec![check_datadir(data_dir.data_dir())];
+        findings.push(self.check_storage_consistency(&data_dir.db()));
+        findings.extend(check_filesystem(data_dir.data_dir()));
+        if !self.offline {
+            findings.push(check_clock(&self.ntp_server));
+        }
+        findings.push(check_open_files());
+        findings.extend(self.check_ports());
+        findings
+            .push(check_jwt_secret(&self.auth_jwtsecret.clone().unwrap_or_else(|| data_dir.jwt())));
+
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header(["Check", "Status", "Finding"]);
+        for finding in &findings {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(finding.check))
+                .add_cell(Cell::new(finding.severity))
+                .add_cell(Cell::new(&finding.message));
+            table.add_row(row);
+        }
+        println!("{table}");
+
+        let errors = findings.iter().filter(|finding| finding.severity == Severity::Error).count();
+        if errors > 0 {
+            eyre::bail!("{errors} checks failed")
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the static files are consistent with the database.
+    fn check_storage_consistency(&self, db_path: &Path) -> Finding {
+        const CHECK: &str = "storage consistency";
+
+        if !db_path.exists() {
+            return Finding::new(CHECK, Severity::Ok, "No database yet, it's created on startup")
+        }
+
+        let result = self.env.init(AccessRights::RO).and_then(
+            |Environment { config, provider_factory, .. }| {
+                let has_receipt_pruning =
+                    config.prune.as_ref().map_or(false, |prune| prune.has_receipts_pruning());
+                Ok(provider_factory
+                    .static_file_provider()
+                    .check_consistency(&provider_factory.provider()?, has_receipt_pruning)?)
+            },
+        );
+        match result {
+            Ok(None) => Finding::new(CHECK, Severity::Ok, "Static files are consistent with the database"),
+            Ok(Some(unwind_target)) => Finding::new(
+                CHECK,
+                Severity::Warning,
+                format!("Static files are inconsistent with the database, the node heals this on startup with an unwind to {unwind_target}. Run `reth db repair --dry-run` for details"),
+            ),
+            Err(err) => Finding::new(
+                CHECK,
+                Severity::Error,
+                format!("Failed to open the database: {err}. Check that the datadir and chain are correct"),
+            ),
+        }
+    }
+
+    /// Checks that the ports of the node are not in use.
+    fn check_ports(&self) -> Vec<Finding> {
+        const CHECK: &str = "ports";
+
+        let tcp_ports = [
+            ("p2p", self.port),
+            ("http rpc", self.http_port),
+            ("ws rpc", self.ws_port),
+            ("auth rpc", self.auth_port),
+        ];
+        let mut findings = Vec::new();
+        for (name, port) in tcp_ports {
+            if let Err(err) = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)) {
+                findings.push(port_in_use(name, "TCP", port, &err));
+            }
+        }
+        if let Err(err) = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, self.discovery_port)) {
+            findings.push(port_in_use("discovery", "UDP", self.discovery_port, &err));
+        }
+
+        if findings.is_empty() {
+            findings.push(Finding::new(
+                CHECK,
+                Severity::Ok,
+                format!("All ports are available. Peers can only connect if TCP port {} and UDP port {} are reachable from the internet, e.g. forwarded by the router", self.port, self.discovery_port),
+            ));
+        }
+        findings
+    }
+}
+
+/// Returns the finding for a port that can't be bound.
+fn port_in_use(name: &str, protocol: &str, port: u16, err: &io::Error) -> Finding {
+    Finding::new(
+        "ports",
+        Severity::Error,
+        format!("The {name} {protocol} port {port} can't be bound: {err}. Stop the process using it, e.g. a running node, or configure another port"),
+    )
+}
+
+/// Checks that the data directory is writable.
+fn check_datadir(path: &Path) -> Finding {
+    const CHECK: &str = "datadir permissions";
+
+    if !path.exists() {
+        return match path.ancestors().find(|ancestor| ancestor.exists()) {
+            Some(ancestor) if is_writable(ancestor) => Finding::new(
+                CHECK,
+                Severity::Ok,
+                format!("{} doesn't exist yet, it's created on startup", path.display()),
+            ),
+            _ => Finding::new(
+                CHECK,
+                Severity::Error,
+                format!(
+                    "{} doesn't exist and can't be created. Create it, or use another --datadir",
+                    path.display()
+                ),
+            ),
+        }
+    }
+
+    if is_writable(path) {
+        Finding::new(CHECK, Severity::Ok, format!("{} is writable", path.display()))
+    } else {
+        Finding::new(
+            CHECK,
+            Severity::Error,
+            format!(
+                "{} isn't writable by the current user. Fix its ownership or permissions",
+                path.display()
+            ),
+        )
+    }
+}
+
+/// Returns `true` if a file can be created in the directory.
+fn is_writable(dir: &Path) -> bool {
+    tempfile::tempfile_in(dir).is_ok()
+}
+
+/// Space and inodes of a filesystem.
+#[derive(Debug, Clone, Copy)]
+struct FilesystemStats {
+    free_bytes: u64,
+    total_bytes: u64,
+    free_inodes: u64,
+    total_inodes: u64,
+}
+
+/// Checks the free space and inodes of the filesystem of the data directory.
+fn check_filesystem(path: &Path) -> Vec<Finding> {
+    let path = path.ancestors().find(|ancestor| ancestor.exists()).unwrap_or(path);
+    match filesystem_stats(path) {
+        Ok(stats) => filesystem_findings(stats),
+        Err(err) => vec![Finding::new(
+            "disk space",
+            Severity::Warning,
+            format!("Failed to read the filesystem stats of {}: {err}", path.display()),
+        )],
+    }
+}
+
+/// Returns the findings for the free space and inodes of a filesystem.
+fn filesystem_findings(stats: FilesystemStats) -> Vec<Finding> {
+    let space = format!(
+        "{} free of {}",
+        human_bytes(stats.free_bytes as f64),
+        human_bytes(stats.total_bytes as f64)
+    );
+    let space = if stats.free_bytes < MIN_FREE_SPACE {
+        Finding::new(
+            "disk space",
+            Severity::Warning,
+            format!("{space}. The database grows with the chain, free up space or move the datadir to a larger disk"),
+        )
+    } else {
+        Finding::new("disk space", Severity::Ok, space)
+    };
+
+    // some filesystems, like btrfs, don't have a fixed number of inodes
+    let inodes = if stats.total_inodes == 0 {
+        Finding::new("inodes", Severity::Ok, "The filesystem has no inode limit")
+    } else if stats.free_inodes * 100 < stats.total_inodes * MIN_FREE_INODES_PERCENT {
+        Finding::new(
+            "inodes",
+            Severity::Warning,
+            format!(
+                "{} free of {}. Static files and logs need inodes, remove unused files",
+                stats.free_inodes, stats.total_inodes
+            ),
+        )
+    } else {
+        Finding::new(
+            "inodes",
+            Severity::Ok,
+            format!("{} free of {}", stats.free_inodes, stats.total_inodes),
+        )
+    };
+
+    vec![space, inodes]
+}
+
+/// Returns the stats of the filesystem the path is on.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn filesystem_stats(path: &Path) -> io::Result<FilesystemStats> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: `statvfs` only writes to the zeroed struct, and the path is nul-terminated.
+    let stat = unsafe {
+        let mut stat: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            return Err(io::Error::last_os_error())
+        }
+        stat
+    };
+
+    Ok(FilesystemStats {
+        free_bytes: stat.f_bavail as u64 * stat.f_frsize as u64,
+        total_bytes: stat.f_blocks as u64 * stat.f_frsize as u64,
+        free_inodes: stat.f_favail as u64,
+        total_inodes: stat.f_files as u64,
+    })
+}
+
+/// Returns the stats of the filesystem the path is on.
+#[cfg(not(unix))]
+fn filesystem_stats(_path: &Path) -> io::Result<FilesystemStats> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "unsupported platform"))
+}
+
+/// Checks the offset of the system clock against an NTP server.
+fn check_clock(ntp_server: &str) -> Finding {
+    const CHECK: &str = "clock skew";
+
+    match query_clock_offset(ntp_server) {
+        Ok(offset) if offset.abs() > MAX_CLOCK_OFFSET.as_secs_f64() => Finding::new(
+            CHECK,
+            Severity::Warning,
+            format!("The clock is off by {offset:.3}s from {ntp_server}. Blocks are timed by slots, enable time synchronization, e.g. with chrony or systemd-timesyncd"),
+        ),
+        Ok(offset) => {
+            Finding::new(CHECK, Severity::Ok, format!("The clock is off by {offset:.3}s from {ntp_server}"))
+        }
+        Err(err) => Finding::new(
+            CHECK,
+            Severity::Warning,
+            format!("Failed to query {ntp_server}: {err}. Use --ntp-server to query another server, or --offline to skip this check"),
+        ),
+    }
+}
+
+/// Returns the offset of the system clock from the NTP server in seconds, positive if the system
+/// clock is behind.
+fn query_clock_offset(ntp_server: &str) -> io::Result<f64> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_read_timeout(Some(Duration::from_secs(5)))?;
+    socket.connect(ntp_server)?;
+
+    // SNTP client request: no leap indicator, version 3, client mode
+    let mut packet = [0u8; 48];
+    packet[0] = 0x1b;
+
+    let sent = unix_now();
+    socket.send(&packet)?;
+    let len = socket.recv(&mut packet)?;
+    let received = unix_now();
+    if len < packet.len() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated NTP response"))
+    }
+
+    let server_received = ntp_timestamp(&packet[32..40]);
+    let server_sent = ntp_timestamp(&packet[40..48]);
+    Ok(((server_received - sent) + (server_sent - received)) / 2.0)
+}
+
+/// Returns the current unix time in seconds.
+fn unix_now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+}
+
+/// Decodes an NTP timestamp to unix time in seconds.
+fn ntp_timestamp(bytes: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    seconds as f64 - NTP_UNIX_EPOCH_OFFSET + fraction as f64 / (1u64 << 32) as f64
+}
+
+/// Checks the open file limit. The node raises its soft limit to the hard limit on startup.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn check_open_files() -> Finding {
+    const CHECK: &str = "open files limit";
+
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    // SAFETY: `getrlimit` only writes to the struct.
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return Finding::new(
+            CHECK,
+            Severity::Warning,
+            format!("Failed to read the open files limit: {}", io::Error::last_os_error()),
+        )
+    }
+
+    let hard_limit = limit.rlim_max as u64;
+    if limit.rlim_max == libc::RLIM_INFINITY || hard_limit >= MIN_OPEN_FILES {
+        Finding::new(CHECK, Severity::Ok, format!("The hard limit is {hard_limit}"))
+    } else {
+        Finding::new(
+            CHECK,
+            Severity::Warning,
+            format!("The hard limit is {hard_limit}, below {MIN_OPEN_FILES}. Raise it with `ulimit -Hn`, or `LimitNOFILE` of the systemd service"),
+        )
+    }
+}
+
+/// Checks the open file limit. The node raises its soft limit to the hard limit on startup.
+#[cfg(not(unix))]
+fn check_open_files() -> Finding {
+    Finding::new("open files limit", Severity::Ok, "Not limited on this platform")
+}
+
+/// Checks that the JWT secret of the auth server is valid, and only readable by its owner.
+fn check_jwt_secret(path: &Path) -> Finding {
+    const CHECK: &str = "jwt secret";
+
+    if !path.exists() {
+        return Finding::new(
+            CHECK,
+            Severity::Ok,
+            format!("{} doesn't exist yet, a secret is generated on startup. The consensus client must use the same file", path.display()),
+        )
+    }
+
+    if let Err(err) = read_jwt_secrets(path) {
+        return Finding::new(
+            CHECK,
+            Severity::Error,
+            format!(
+                "{} is invalid: {err}. It must contain a 32 byte hex encoded secret per line",
+                path.display()
+            ),
+        )
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Ok(metadata) = reth_fs_util::metadata(path) {
+            let mode = metadata.permissions().mode();
+            if mode & 0o077 != 0 {
+                return Finding::new(
+                    CHECK,
+                    Severity::Warning,
+                    format!(
+                        "{} is accessible by other users (mode {:o}). Restrict it with `chmod 600`",
+                        path.display(),
+                        mode & 0o777
+                    ),
+                )
+            }
+        }
+    }
+
+    Finding::new(
+        CHECK,
+        Severity::Ok,
+        format!("{} is valid. The consensus client must use the same file", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_doctor_command() {
+        let args = DoctorCommand::parse_from(["reth", "--http.port", "9545", "--offline"]);
+        assert_eq!(args.http_port, 9545);
+        assert_eq!(args.port, DEFAULT_DISCOVERY_PORT);
+        assert!(args.offline);
+    }
+
+    #[test]
+    fn decode_ntp_timestamp() {
+        // 2024-01-01T00:00:00.5Z
+        let mut bytes = (1_704_067_200u32 + NTP_UNIX_EPOCH_OFFSET as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&(1u32 << 31).to_be_bytes());
+        assert_eq!(ntp_timestamp(&bytes), 1_704_067_200.5);
+    }
+
+    #[test]
+    fn filesystem_headroom() {
+        let stats = FilesystemStats {
+            free_bytes: MIN_FREE_SPACE,
+            total_bytes: 4 * MIN_FREE_SPACE,
+            free_inodes: 4,
+            total_inodes: 100,
+        };
+        let findings = filesystem_findings(stats);
+        assert_eq!(findings[0].severity, Severity::Ok);
+        assert_eq!(findings[1].severity, Severity::Warning);
+
+        let findings = filesystem_findings(FilesystemStats {
+            free_bytes: MIN_FREE_SPACE - 1,
+            total_inodes: 0,
+            ..stats
+        });
+        assert_eq!(findings[0].severity, Severity::Warning);
+        assert_eq!(findings[1].severity, Severity::Ok);
+    }
+
+    #[test]
+    fn jwt_secret_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+        assert_eq!(check_jwt_secret(&path).severity, Severity::Ok);
+
+        reth_fs_util::write(&path, "invalid").unwrap();
+        assert_eq!(check_jwt_secret(&path).severity, Severity::Error);
+    }
+}
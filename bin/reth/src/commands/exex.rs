@@ -0,0 +1,85 @@
+//! Command for inspecting the execution extensions of the node.
+
+use crate::args::{
+    utils::{chain_help, chain_value_parser, SUPPORTED_CHAINS},
+    DatadirArgs,
+};
+use clap::{Parser, Subcommand};
+use reth_chainspec::ChainSpec;
+use reth_exex::ExExCheckpointStore;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// `reth exex` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Parameters for datadir configuration
+    #[command(flatten)]
+    datadir: DatadirArgs,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = chain_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth exex` subcommands
+#[derive(Debug, Subcommand)]
+pub enum Subcommands {
+    /// Prints the checkpoints of the execution extensions as JSON, including the last height each
+    /// of them finished processing
+    Checkpoints {
+        /// Only print the checkpoints of the execution extension with this ID
+        #[arg(long)]
+        id: Option<String>,
+    },
+}
+
+impl Command {
+    /// Execute `exex` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.datadir.resolve_datadir(self.chain.chain);
+        let store = ExExCheckpointStore::new(data_dir.exex_checkpoints())?;
+
+        match self.command {
+            Subcommands::Checkpoints { id } => {
+                let ids = match id {
+                    Some(id) => vec![id],
+                    None => store.exex_ids()?,
+                };
+
+                let mut checkpoints = BTreeMap::new();
+                for id in ids {
+                    let entries = store.checkpoints(&id)?.entries();
+                    checkpoints.insert(id, entries);
+                }
+                println!("{}", serde_json::to_string_pretty(&checkpoints)?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_checkpoints() {
+        let cmd =
+            Command::parse_from(["reth", "--datadir", "dir", "checkpoints", "--id", "OPBridge"]);
+        assert!(
+            matches!(cmd.command, Subcommands::Checkpoints { id: Some(id) } if id == "OPBridge")
+        );
+    }
+}
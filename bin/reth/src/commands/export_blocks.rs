@@ -0,0 +1,162 @@
+//! Command that exports a range of blocks to RLP or era1 files.
+
+use crate::commands::{
+    common::{AccessRights, Environment, EnvironmentArgs},
+    import_blocks::BlockFileFormat,
+};
+use alloy_rlp::Encodable;
+use clap::Parser;
+use reth_era::{era1_file_name, Era1Block, Era1Writer, MAX_BLOCKS_PER_ERA1};
+use reth_fs_util::{self as fs, FsPathError};
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    BlockNumReader, BlockReader, ChainSpecProvider, HeaderProvider, ReceiptProvider,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Exports a range of blocks to RLP or era1 files.
+#[derive(Debug, Parser)]
+pub struct ExportBlocksCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The format of the exported files.
+    #[arg(long, value_enum, default_value_t)]
+    format: BlockFileFormat,
+
+    /// The first block to export.
+    #[arg(long, default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export, defaults to the last synced block.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// Exports the receipts of the blocks. Only supported by the era format.
+    #[arg(long)]
+    receipts: bool,
+
+    /// The path to the RLP file, or to the directory of the era1 files.
+    ///
+    /// Era1 files are aligned to multiples of 8192 blocks, and named
+    /// `<chain>-<epoch>-<short accumulator root>.era1`.
+    #[arg(value_name = "EXPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ExportBlocksCommand {
+    /// Execute `export-blocks` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+
+        let to = match self.to {
+            Some(to) => to,
+            None => provider_factory.best_block_number()?,
+        };
+        if self.from > to {
+            eyre::bail!("invalid block range {}..={to}", self.from)
+        }
+        if self.receipts && self.format == BlockFileFormat::Rlp {
+            eyre::bail!("receipts can only be exported in the era format")
+        }
+
+        info!(target: "reth::cli", from = self.from, to, format = ?self.format, "Exporting blocks");
+        match self.format {
+            BlockFileFormat::Rlp => {
+                let mut writer = create_file(&self.path)?;
+                let mut buf = Vec::new();
+                for number in self.from..=to {
+                    let block = provider_factory
+                        .block_by_number(number)?
+                        .ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+                    buf.clear();
+                    block.encode(&mut buf);
+                    writer.write_all(&buf)?;
+                }
+                writer.flush()?;
+            }
+            BlockFileFormat::Era => {
+                fs::create_dir_all(&self.path)?;
+                let chain = provider_factory.chain_spec().chain.to_string();
+
+                let mut start = self.from;
+                while start <= to {
+                    // era1 files are aligned to epochs of blocks
+                    let epoch = start / MAX_BLOCKS_PER_ERA1 as u64;
+                    let end = to.min((epoch + 1) * MAX_BLOCKS_PER_ERA1 as u64 - 1);
+
+                    let tmp_path = self.path.join(format!("{chain}-{epoch:05}.era1.tmp"));
+                    let mut writer = Era1Writer::new(create_file(&tmp_path)?)?;
+                    for number in start..=end {
+                        let block = provider_factory
+                            .block_by_number(number)?
+                            .ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+                        let total_difficulty =
+                            provider_factory.header_td_by_number(number)?.ok_or_else(|| {
+                                eyre::eyre!("total difficulty of block {number} not found")
+                            })?;
+                        let receipts = if self.receipts {
+                            let receipts = provider_factory
+                                .receipts_by_block(number.into())?
+                                .ok_or_else(|| {
+                                    eyre::eyre!(
+                                        "receipts of block {number} not found, they may be pruned"
+                                    )
+                                })?;
+                            Some(receipts.into_iter().map(|receipt| receipt.with_bloom()).collect())
+                        } else {
+                            None
+                        };
+                        writer.append(&Era1Block::from_block(block, receipts, total_difficulty))?;
+                    }
+                    let (mut file, accumulator) = writer.finish()?;
+                    file.flush()?;
+
+                    let path = self.path.join(era1_file_name(&chain, epoch, accumulator));
+                    fs::rename(&tmp_path, &path)?;
+                    info!(target: "reth::cli", blocks = ?(start..=end), path = %path.display(), "Exported era1 file");
+
+                    start = end + 1;
+                }
+            }
+        }
+
+        info!(target: "reth::cli", blocks = to - self.from + 1, path = %self.path.display(), "Blocks exported");
+
+        Ok(())
+    }
+}
+
+/// Creates the file at the path, and returns a buffered writer to it.
+fn create_file(path: &Path) -> eyre::Result<BufWriter<File>> {
+    let file = File::create(path).map_err(|err| FsPathError::create_file(err, path))?;
+    Ok(BufWriter::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_export_blocks_command() {
+        let args = ExportBlocksCommand::parse_from([
+            "reth",
+            "--format",
+            "era",
+            "--from",
+            "10",
+            "--to",
+            "20",
+            "--receipts",
+            "era1",
+        ]);
+        assert_eq!(args.format, BlockFileFormat::Era);
+        assert_eq!((args.from, args.to), (10, Some(20)));
+        assert!(args.receipts);
+    }
+}
@@ -0,0 +1,295 @@
+//! Command that imports blocks from RLP or era1 files, with a configurable validation level.
+
+use crate::{
+    commands::{
+        common::{AccessRights, Environment, EnvironmentArgs},
+        import::build_import_pipeline,
+    },
+    version::SHORT_VERSION,
+};
+use clap::{Parser, ValueEnum};
+use reth_beacon_consensus::{EthBeaconConsensus, Ethash};
+use reth_config::Config;
+use reth_consensus::{noop::NoopConsensus, Consensus};
+use reth_db::DatabaseEnv;
+use reth_downloaders::file_client::{ChunkedFileReader, FileClient};
+use reth_era::Era1File;
+use reth_fs_util as fs;
+use reth_primitives::{Header, U256};
+use reth_provider::{HeaderProvider, ProviderFactory, StageCheckpointReader};
+use reth_prune::PruneModes;
+use reth_stages::StageId;
+use reth_static_file::StaticFileProducer;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, info};
+
+/// The file format of exported blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BlockFileFormat {
+    /// RLP encoded blocks, one after another in a single file.
+    #[default]
+    Rlp,
+    /// Era1 files of at most 8192 blocks, with their total difficulty and optionally their
+    /// receipts.
+    Era,
+}
+
+/// How thoroughly imported blocks are validated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ValidationLevel {
+    /// The blocks are trusted, and only the stages that don't require state are run.
+    None,
+    /// The headers and bodies are validated by consensus, and only the stages that don't require
+    /// state are run.
+    Header,
+    /// The headers and bodies are validated by consensus, and the blocks are executed.
+    #[default]
+    FullExecution,
+}
+
+/// Syncs blocks from RLP or era1 files.
+#[derive(Debug, Parser)]
+pub struct ImportBlocksCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The format of the imported files.
+    #[arg(long, value_enum, default_value_t)]
+    format: BlockFileFormat,
+
+    /// How thoroughly the imported blocks are validated.
+    ///
+    /// The stages requiring state are only run with `full-execution`, and run on the next sync
+    /// otherwise.
+    #[arg(long, value_enum, default_value_t, verbatim_doc_comment)]
+    validation: ValidationLevel,
+
+    /// Verify the ethash seals of the imported proof-of-work headers before importing them.
+    ///
+    /// The seals are verified in parallel, with the cache of each epoch generated on demand.
+    #[arg(long, verbatim_doc_comment)]
+    verify_pow: bool,
+
+    /// Chunk byte length to read from RLP files.
+    #[arg(long, value_name = "CHUNK_LEN", verbatim_doc_comment)]
+    chunk_len: Option<u64>,
+
+    /// The path to an RLP file, or to an era1 file or a directory of era1 files.
+    ///
+    /// Era1 files are imported in the order of their names. The archived total difficulties are
+    /// checked against the total difficulty of the database, and archived receipts are not
+    /// imported, since they are recomputed by the execution.
+    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ImportBlocksCommand {
+    /// Execute `import-blocks` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        let Environment { provider_factory, config, .. } = self.env.init(AccessRights::RW)?;
+
+        match self.validation {
+            ValidationLevel::None => {
+                self.import(provider_factory, &config, &Arc::new(NoopConsensus::default())).await
+            }
+            ValidationLevel::Header | ValidationLevel::FullExecution => {
+                let consensus = Arc::new(EthBeaconConsensus::new(self.env.chain.clone()));
+                self.import(provider_factory, &config, &consensus).await
+            }
+        }
+    }
+
+    async fn import<C: Consensus + 'static>(
+        &self,
+        provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+        config: &Config,
+        consensus: &Arc<C>,
+    ) -> eyre::Result<()> {
+        let disable_exec = self.validation != ValidationLevel::FullExecution;
+        if disable_exec {
+            info!(target: "reth::cli", "Disabled stages requiring state");
+        }
+
+        let ethash = self.verify_pow.then(|| Arc::new(Ethash::default()));
+
+        let mut total_imported_blocks = 0;
+        match self.format {
+            BlockFileFormat::Rlp => {
+                let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
+                while let Some(file_client) = reader.next_chunk::<FileClient>().await? {
+                    if let Some(ethash) = &ethash {
+                        verify_seals(ethash, file_client.headers_iter().cloned().collect()).await?;
+                    }
+
+                    total_imported_blocks += file_client.headers_len();
+                    run_import_pipeline(
+                        &provider_factory,
+                        config,
+                        consensus,
+                        file_client,
+                        disable_exec,
+                    )
+                    .await?;
+                }
+            }
+            BlockFileFormat::Era => {
+                for path in era1_files(&self.path)? {
+                    debug!(target: "reth::cli", path = %path.display(), "Reading era1 file");
+                    let era1 = Era1File::read(&fs::read(&path)?[..]).map_err(|err| {
+                        eyre::eyre!("invalid era1 file {}: {err}", path.display())
+                    })?;
+                    if era1.blocks.is_empty() {
+                        continue
+                    }
+
+                    let parent_total_difficulty =
+                        parent_total_difficulty(&provider_factory, era1.starting_number)?;
+                    era1.verify_total_difficulty(parent_total_difficulty).map_err(|err| {
+                        eyre::eyre!("invalid era1 file {}: {err}", path.display())
+                    })?;
+                    if let Some(ethash) = &ethash {
+                        let headers = era1.blocks.iter().map(|block| block.header.clone());
+                        verify_seals(ethash, headers.collect()).await?;
+                    }
+
+                    total_imported_blocks += era1.blocks.len();
+                    run_import_pipeline(
+                        &provider_factory,
+                        config,
+                        consensus,
+                        era1.into_blocks().collect(),
+                        disable_exec,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        info!(target: "reth::cli", total_imported_blocks, "Blocks imported");
+
+        Ok(())
+    }
+}
+
+/// Returns the era1 files of the path, which is either an era1 file or a directory of era1 files,
+/// sorted by name.
+fn era1_files(path: &Path) -> eyre::Result<Vec<PathBuf>> {
+    if !fs::metadata(path)?.is_dir() {
+        return Ok(vec![path.to_path_buf()])
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "era1") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the total difficulty of the parent of the block from the database, which the archived
+/// total difficulties of an era1 file starting at the block must continue.
+fn parent_total_difficulty(
+    provider_factory: &ProviderFactory<Arc<DatabaseEnv>>,
+    number: u64,
+) -> eyre::Result<U256> {
+    let Some(parent) = number.checked_sub(1) else { return Ok(U256::ZERO) };
+    provider_factory.header_td_by_number(parent)?.ok_or_else(|| {
+        eyre::eyre!("missing total difficulty of block {parent}, import the preceding blocks first")
+    })
+}
+
+/// Verifies the ethash seals of the proof-of-work headers in parallel, skipping post-merge
+/// headers.
+async fn verify_seals(ethash: &Arc<Ethash>, headers: Vec<Header>) -> eyre::Result<()> {
+    let ethash = ethash.clone();
+    tokio::task::spawn_blocking(move || {
+        let headers = headers
+            .into_iter()
+            .filter(|header| !header.is_zero_difficulty())
+            .map(Header::seal_slow)
+            .collect::<Vec<_>>();
+        debug!(target: "reth::cli", headers = headers.len(), "Verifying proof-of-work seals");
+        ethash.verify_seals(&headers)
+    })
+    .await??;
+    Ok(())
+}
+
+/// Runs the import pipeline over the blocks of the file client.
+async fn run_import_pipeline<C: Consensus + 'static>(
+    provider_factory: &ProviderFactory<Arc<DatabaseEnv>>,
+    config: &Config,
+    consensus: &Arc<C>,
+    file_client: FileClient,
+    disable_exec: bool,
+) -> eyre::Result<()> {
+    let tip = file_client.tip().ok_or(eyre::eyre!("file client has no tip"))?;
+    info!(target: "reth::cli", min_block = ?file_client.min_block(), max_block = ?file_client.max_block(), "Importing blocks");
+
+    let (mut pipeline, events) = build_import_pipeline(
+        config,
+        provider_factory.clone(),
+        consensus,
+        Arc::new(file_client),
+        StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+        disable_exec,
+    )
+    .await?;
+
+    // override the tip
+    pipeline.set_tip(tip);
+    debug!(target: "reth::cli", ?tip, "Tip manually set");
+
+    let latest_block_number = provider_factory
+        .provider()?
+        .get_stage_checkpoint(StageId::Finish)?
+        .map(|ch| ch.block_number);
+    tokio::spawn(reth_node_events::node::handle_events(
+        None,
+        latest_block_number,
+        events,
+        provider_factory.db_ref().clone(),
+    ));
+
+    info!(target: "reth::cli", "Starting sync pipeline");
+    tokio::select! {
+        res = pipeline.run() => res?,
+        _ = tokio::signal::ctrl_c() => {},
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_import_blocks_command() {
+        let args = ImportBlocksCommand::parse_from([
+            "reth",
+            "--format",
+            "era",
+            "--validation",
+            "header",
+            "--verify-pow",
+            "era1",
+        ]);
+        assert_eq!(args.format, BlockFileFormat::Era);
+        assert_eq!(args.validation, ValidationLevel::Header);
+        assert!(args.verify_pow);
+
+        let args = ImportBlocksCommand::parse_from(["reth", "blocks.rlp"]);
+        assert_eq!(args.format, BlockFileFormat::Rlp);
+        assert_eq!(args.validation, ValidationLevel::FullExecution);
+        assert!(!args.verify_pow);
+    }
+}
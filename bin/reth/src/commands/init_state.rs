@@ -1,22 +1,62 @@
 //! Command that initializes the node from a genesis file.
 
 use crate::commands::common::{AccessRights, Environment, EnvironmentArgs};
-use clap::Parser;
+use alloy_rlp::Decodable;
+use clap::{Parser, ValueEnum};
 use reth_config::config::EtlConfig;
-use reth_db_api::database::Database;
-use reth_db_common::init::init_from_state_dump;
-use reth_primitives::B256;
-use reth_provider::ProviderFactory;
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTxMut};
+use reth_db_common::init::{init_from_geth_state_dump, init_from_state_dump};
+use reth_fs_util as fs;
+use reth_primitives::{hex, Header, SealedHeader, StaticFileSegment, B256, U256};
+use reth_provider::{
+    providers::StaticFileWriter, BlockNumReader, ProviderFactory, StageCheckpointWriter,
+    StaticFileProviderFactory,
+};
+use reth_stages::{StageCheckpoint, StageId};
 
-use std::{fs::File, io::BufReader, path::PathBuf};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 use tracing::info;
 
+/// The format of a state dump file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum StateDumpFormat {
+    /// JSONL file with the state root on the first line and one account per line, as written by
+    /// `geth dump --iterative`.
+    #[default]
+    Jsonl,
+    /// JSON object with the state root and the accounts keyed by address, as written by
+    /// `geth dump`. The whole file is read into memory.
+    Geth,
+}
+
 /// Initializes the database with the genesis block.
 #[derive(Debug, Parser)]
 pub struct InitStateCommand {
     #[command(flatten)]
     env: EnvironmentArgs,
 
+    /// The format of the state dump.
+    #[arg(long, value_enum, default_value_t)]
+    format: StateDumpFormat,
+
+    /// File with the RLP encoded header of the block the state dump is at, either binary or hex
+    /// encoded.
+    ///
+    /// The chain is set up until this block without importing the blocks before it, and the
+    /// stage checkpoints are set to it, so that the node syncs forward from this block. The
+    /// database must not contain any block but the genesis block.
+    #[arg(long, value_name = "HEADER_FILE", requires = "total_difficulty", verbatim_doc_comment)]
+    header: Option<PathBuf>,
+
+    /// The total difficulty of the chain at the block of `--header`.
+    #[arg(long, value_name = "TOTAL_DIFFICULTY", requires = "header")]
+    total_difficulty: Option<U256>,
+
     /// JSONL file with state dump.
     ///
     /// Must contain accounts in following format, additional account fields are ignored. Must
@@ -33,7 +73,8 @@ pub struct InitStateCommand {
     /// }
     ///
     /// Allows init at a non-genesis block. Caution! Blocks must be manually imported up until
-    /// and including the non-genesis block to init chain at. See 'import' command.
+    /// and including the non-genesis block to init chain at, unless `--header` is set. See
+    /// 'import' command.
     #[arg(value_name = "STATE_DUMP_FILE", verbatim_doc_comment)]
     state: PathBuf,
 }
@@ -45,9 +86,24 @@ impl InitStateCommand {
 
         let Environment { config, provider_factory, .. } = self.env.init(AccessRights::RW)?;
 
+        if let (Some(path), Some(total_difficulty)) = (&self.header, self.total_difficulty) {
+            let header = read_header(path)?;
+            info!(target: "reth::cli", number = header.number, hash = ?header.hash(), "Setting up chain at header");
+            setup_chain_at_header(&provider_factory, header, total_difficulty)?;
+        }
+
         info!(target: "reth::cli", "Initiating state dump");
 
-        let hash = init_at_state(self.state, provider_factory, config.stages.etl)?;
+        let hash = match self.format {
+            StateDumpFormat::Jsonl => {
+                init_at_state(self.state, provider_factory, config.stages.etl)?
+            }
+            StateDumpFormat::Geth => init_from_geth_state_dump(
+                BufReader::new(File::open(&self.state)?),
+                provider_factory,
+                config.stages.etl,
+            )?,
+        };
 
         info!(target: "reth::cli", hash = ?hash, "Genesis block written");
         Ok(())
@@ -69,3 +125,88 @@ pub fn init_at_state<DB: Database>(
 
     init_from_state_dump(reader, factory, etl_config)
 }
+
+/// Reads a RLP encoded header from a file, which is either binary or hex encoded.
+fn read_header(path: &Path) -> eyre::Result<SealedHeader> {
+    decode_header(fs::read(path)?)
+}
+
+/// Decodes a RLP encoded header, which is either binary or hex encoded.
+pub(crate) fn decode_header(content: Vec<u8>) -> eyre::Result<SealedHeader> {
+    let rlp = match std::str::from_utf8(&content) {
+        Ok(text) => hex::decode(text.trim())?,
+        Err(_) => content,
+    };
+    Ok(Header::decode(&mut rlp.as_slice())?.seal_slow())
+}
+
+/// Sets up the chain until the block of the header without the blocks before it, which are
+/// replaced by empty placeholders, and sets the checkpoints of all stages to the block.
+pub(crate) fn setup_chain_at_header<DB: Database>(
+    factory: &ProviderFactory<DB>,
+    header: SealedHeader,
+    total_difficulty: U256,
+) -> eyre::Result<()> {
+    let last_block = factory.last_block_number()?;
+    if last_block != 0 {
+        eyre::bail!("database already contains blocks up until {last_block}")
+    }
+    if header.number == 0 {
+        eyre::bail!("header must not be the genesis header")
+    }
+
+    let static_file_provider = factory.static_file_provider();
+    {
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers)?;
+        for number in 1..header.number {
+            writer.append_header(
+                Header { number, ..Default::default() },
+                U256::ZERO,
+                B256::ZERO,
+            )?;
+        }
+        writer.append_header(header.header().clone(), total_difficulty, header.hash())?;
+    }
+    for segment in [StaticFileSegment::Transactions, StaticFileSegment::Receipts] {
+        let mut writer = static_file_provider.latest_writer(segment)?;
+        for number in 1..=header.number {
+            writer.increment_block(segment, number)?;
+        }
+    }
+
+    let provider_rw = factory.provider_rw()?;
+    provider_rw.tx_ref().put::<tables::HeaderNumbers>(header.hash(), header.number)?;
+    provider_rw.tx_ref().put::<tables::BlockBodyIndices>(header.number, Default::default())?;
+    for stage in StageId::ALL {
+        provider_rw.save_stage_checkpoint(stage, StageCheckpoint::new(header.number))?;
+    }
+
+    provider_rw.commit()?;
+    static_file_provider.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_init_state_command() {
+        let args = InitStateCommand::parse_from([
+            "reth",
+            "--format",
+            "geth",
+            "--header",
+            "header.rlp",
+            "--total-difficulty",
+            "58750003716598352816469",
+            "state.json",
+        ]);
+        assert_eq!(args.format, StateDumpFormat::Geth);
+        assert_eq!(args.total_difficulty, Some(U256::from(58750003716598352816469u128)));
+
+        let args = InitStateCommand::try_parse_from(["reth", "--header", "header.rlp", "state"]);
+        assert!(args.is_err());
+    }
+}
@@ -3,8 +3,12 @@
 pub mod config_cmd;
 pub mod db;
 pub mod debug_cmd;
+pub mod doctor;
 pub mod dump_genesis;
+pub mod exex;
+pub mod export_blocks;
 pub mod import;
+pub mod import_blocks;
 pub mod import_op;
 pub mod import_receipts_op;
 pub mod init_cmd;
@@ -13,6 +17,7 @@ pub mod node;
 pub mod p2p;
 pub mod prune;
 pub mod recover;
+pub mod snapshot_sync;
 pub mod stage;
 pub mod test_vectors;
 
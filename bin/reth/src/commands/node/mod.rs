@@ -2,8 +2,8 @@
 
 use crate::args::{
     utils::{chain_help, chain_value_parser, parse_socket_address, SUPPORTED_CHAINS},
-    DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-    RpcServerArgs, TxPoolArgs,
+    DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineArgs, NetworkArgs, PayloadBuilderArgs,
+    PruningArgs, RpcServerArgs, TxPoolArgs,
 };
 use clap::{value_parser, Args, Parser};
 use reth_chainspec::ChainSpec;
@@ -99,6 +99,10 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub pruning: PruningArgs,
 
+    /// All engine related arguments with --engine prefix
+    #[command(flatten)]
+    pub engine: EngineArgs,
+
     /// Additional cli arguments
     #[command(flatten, next_help_heading = "Extension")]
     pub ext: Ext,
@@ -147,9 +151,13 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            engine,
             ext,
         } = self;
 
+        // in dev mode the genesis allocation can be configured
+        let chain = if dev.dev { dev.apply_genesis_alloc(chain) } else { chain };
+
         // set up node config
         let mut node_config = NodeConfig {
             datadir,
@@ -165,6 +173,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            engine,
         };
 
         // Register the prometheus recorder before creating the database,
@@ -2,8 +2,16 @@
 
 use crate::commands::common::{AccessRights, Environment, EnvironmentArgs};
 use clap::Parser;
-use reth_prune::PrunerBuilder;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::{DatabaseEnv, Tables};
+use reth_db_api::database::Database;
+use reth_provider::{BlockNumReader, ProviderFactory, StaticFileProviderFactory};
+use reth_prune::{PruneSegment, PrunerBuilder, PrunerStats};
 use reth_static_file::StaticFileProducer;
+use std::sync::Arc;
+use tokio::sync::watch;
 use tracing::info;
 
 /// Prunes according to the configuration without any limits
@@ -11,14 +19,40 @@ use tracing::info;
 pub struct PruneCommand {
     #[command(flatten)]
     env: EnvironmentArgs,
+
+    /// Report how many entries and approximately how many bytes would be deleted per segment,
+    /// without copying data to static files or deleting anything.
+    ///
+    /// Only data that has already been copied to static files is taken into account.
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
 }
 
 impl PruneCommand {
     /// Execute the `prune` command
     pub async fn execute(self) -> eyre::Result<()> {
-        let Environment { config, provider_factory, .. } = self.env.init(AccessRights::RW)?;
+        let access_rights = if self.dry_run { AccessRights::RO } else { AccessRights::RW };
+        let Environment { config, provider_factory, .. } = self.env.init(access_rights)?;
         let prune_config = config.prune.unwrap_or_default();
 
+        if self.dry_run {
+            let Some(prune_tip) =
+                provider_factory.static_file_provider().get_highest_static_files().min()
+            else {
+                info!(target: "reth::cli", "No data in static files, nothing to prune");
+                return Ok(())
+            };
+
+            info!(target: "reth::cli", ?prune_tip, ?prune_config, "Estimating pruned data...");
+            let pruner = PrunerBuilder::new(prune_config)
+                .persisted_height(watch::channel(Some(provider_factory.best_block_number()?)).1)
+                .build(provider_factory.clone());
+            let stats = pruner.dry_run(prune_tip)?;
+
+            println!("{}", dry_run_table(&provider_factory, &stats)?);
+            return Ok(())
+        }
+
         // Copy data from database to static files
         info!(target: "reth::cli", "Copying data from database to static files...");
         let static_file_producer =
@@ -29,9 +63,12 @@ impl PruneCommand {
         // Delete data which has been copied to static files.
         if let Some(prune_tip) = lowest_static_file_height {
             info!(target: "reth::cli", ?prune_tip, ?prune_config, "Pruning data from database...");
-            // Run the pruner according to the configuration, and don't enforce any limits on it
+            // Run the pruner according to the configuration, and don't enforce any limits on it.
+            // Only blocks that are persisted to the database can be pruned.
+            let persisted_height = provider_factory.best_block_number()?;
             let mut pruner = PrunerBuilder::new(prune_config)
                 .prune_delete_limit(usize::MAX)
+                .persisted_height(watch::channel(Some(persisted_height)).1)
                 .build(provider_factory);
 
             pruner.run(prune_tip)?;
@@ -41,3 +78,72 @@ impl PruneCommand {
         Ok(())
     }
 }
+
+/// Builds the table of entries and estimated bytes that would be deleted per segment.
+///
+/// The size of a segment is estimated from the average entry size of the tables it prunes.
+fn dry_run_table(
+    provider_factory: &ProviderFactory<Arc<DatabaseEnv>>,
+    stats: &PrunerStats,
+) -> eyre::Result<ComfyTable> {
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header(["Segment", "Entries", "Estimated Size"]);
+
+    provider_factory.db_ref().view(|tx| {
+        let mut total_size = 0.0;
+        for (segment, (_, pruned)) in stats {
+            let (mut table_entries, mut table_size) = (0, 0);
+            for db_table in segment_tables(*segment) {
+                let table_db =
+                    tx.inner.open_db(Some(db_table.name())).wrap_err("Could not open db.")?;
+                let stats = tx
+                    .inner
+                    .db_stat(&table_db)
+                    .wrap_err(format!("Could not find table: {db_table}"))?;
+
+                table_entries += stats.entries();
+                table_size += stats.page_size() as usize *
+                    (stats.leaf_pages() + stats.branch_pages() + stats.overflow_pages());
+            }
+
+            let size = if table_entries == 0 {
+                0.0
+            } else {
+                *pruned as f64 * table_size as f64 / table_entries as f64
+            };
+            total_size += size;
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new(segment))
+                .add_cell(Cell::new(pruned))
+                .add_cell(Cell::new(human_bytes(size)));
+            table.add_row(row);
+        }
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Total"))
+            .add_cell(Cell::new(stats.values().map(|(_, pruned)| pruned).sum::<usize>()))
+            .add_cell(Cell::new(human_bytes(total_size)));
+        table.add_row(row);
+
+        Ok::<(), eyre::Report>(())
+    })??;
+
+    Ok(table)
+}
+
+/// Returns the database tables that entries of the given segment are deleted from.
+const fn segment_tables(segment: PruneSegment) -> &'static [Tables] {
+    match segment {
+        PruneSegment::SenderRecovery => &[Tables::TransactionSenders],
+        PruneSegment::TransactionLookup => &[Tables::TransactionHashNumbers],
+        PruneSegment::Receipts | PruneSegment::ContractLogs => &[Tables::Receipts],
+        PruneSegment::AccountHistory => &[Tables::AccountChangeSets, Tables::AccountsHistory],
+        PruneSegment::StorageHistory => &[Tables::StorageChangeSets, Tables::StoragesHistory],
+        PruneSegment::Headers => {
+            &[Tables::Headers, Tables::HeaderTerminalDifficulties, Tables::CanonicalHeaders]
+        }
+        PruneSegment::Transactions => &[Tables::Transactions],
+    }
+}
@@ -0,0 +1,286 @@
+//! Command that initializes the node from a state snapshot of a recent block, verified against a
+//! trusted state root.
+
+use crate::commands::{
+    common::{AccessRights, Environment, EnvironmentArgs},
+    init_state::{decode_header, setup_chain_at_header},
+};
+use clap::Parser;
+use reqwest::{Client, Url};
+use reth_db_common::init::init_from_state_dump;
+use reth_fs_util as fs;
+use reth_primitives::{BlockHash, BlockNumber, SealedHeader, B256, U256};
+use serde::Deserialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+use tracing::info;
+
+/// The interval in bytes at which the progress of the state download is logged.
+const DOWNLOAD_PROGRESS_INTERVAL: u64 = 1024 * 1024 * 1024;
+
+/// Initializes the database from a state snapshot of a recent block, so that the node syncs
+/// forward from that block instead of from genesis.
+///
+/// The snapshot is downloaded from an HTTP mirror serving a `manifest.json`. Its state root must
+/// match a trusted state root, which is either given with `--trusted-state-root` or read from the
+/// execution payload of the finalized beacon block at the slot of the snapshot, as reported by the
+/// beacon API of the consensus client. The state is written to the hashed state tables, the trie
+/// is rebuilt from it, and the computed state root is verified before anything is committed.
+///
+/// Afterwards, `reth node` syncs forward from the block of the snapshot. The blocks before it are
+/// not available, so the node can't serve historical data.
+#[derive(Debug, Parser)]
+pub struct SnapshotSyncCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The URL of the snapshot mirror, serving the `manifest.json` of the snapshot.
+    #[arg(long, value_name = "URL")]
+    url: Url,
+
+    /// The trusted state root the snapshot must match.
+    #[arg(long, value_name = "STATE_ROOT", conflicts_with = "beacon_api")]
+    trusted_state_root: Option<B256>,
+
+    /// The URL of the beacon API of the consensus client to read the trusted state root from.
+    #[arg(long, value_name = "URL", required_unless_present = "trusted_state_root")]
+    beacon_api: Option<Url>,
+}
+
+impl SnapshotSyncCommand {
+    /// Execute the `snapshot-sync` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", url = %self.url, "Reth snapshot-sync starting");
+
+        let Environment { config, provider_factory, data_dir } = self.env.init(AccessRights::RW)?;
+        let client = Client::new();
+
+        let manifest: SnapshotManifest =
+            client.get(self.url.clone()).send().await?.error_for_status()?.json().await?;
+        let header = decode_header(manifest.header.into_bytes())?;
+        info!(target: "reth::cli", number = header.number, hash = ?header.hash(), "Fetched snapshot manifest");
+
+        let trusted_state_root = match (self.trusted_state_root, &self.beacon_api) {
+            (Some(state_root), _) => state_root,
+            (None, Some(beacon_api)) => {
+                let payload = fetch_finalized_payload(&client, beacon_api, manifest.slot).await?;
+                payload.verify_header(&header)?;
+                payload.state_root
+            }
+            (None, None) => eyre::bail!("either --trusted-state-root or --beacon-api must be set"),
+        };
+        eyre::ensure!(
+            header.state_root == trusted_state_root,
+            "state root {} of the snapshot does not match the trusted state root {trusted_state_root}",
+            header.state_root
+        );
+
+        let state_path = data_dir.data_dir().join("snapshot-state.jsonl");
+        download(&client, self.url.join(&manifest.state)?, &state_path).await?;
+        let dump_state_root = read_state_root(&state_path)?;
+        eyre::ensure!(
+            dump_state_root == trusted_state_root,
+            "state root {dump_state_root} of the state dump does not match the trusted state root {trusted_state_root}"
+        );
+
+        info!(target: "reth::cli", number = header.number, "Setting up chain at snapshot header");
+        setup_chain_at_header(&provider_factory, header, manifest.total_difficulty)?;
+
+        // writes the hashed state, rebuilds the trie and verifies the computed state root
+        let hash = init_from_state_dump(
+            BufReader::new(File::open(&state_path)?),
+            provider_factory,
+            config.stages.etl,
+        )?;
+        fs::remove_file(&state_path)?;
+
+        info!(target: "reth::cli", ?hash, "Snapshot installed, `reth node` syncs forward from its block");
+        Ok(())
+    }
+}
+
+/// The manifest of a snapshot served by a snapshot mirror.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotManifest {
+    /// The beacon chain slot of the block of the snapshot.
+    slot: u64,
+    /// The hex encoded RLP of the header of the block of the snapshot.
+    header: String,
+    /// The total difficulty of the chain at the block of the snapshot.
+    total_difficulty: U256,
+    /// The URL of the state dump, relative to the manifest, in the JSONL format of
+    /// `reth init-state`.
+    state: String,
+}
+
+/// The fields of an execution payload of a beacon block that identify the block of a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct ExecutionPayloadSummary {
+    #[serde(with = "quoted")]
+    block_number: BlockNumber,
+    block_hash: BlockHash,
+    state_root: B256,
+}
+
+impl ExecutionPayloadSummary {
+    /// Verifies that the payload is for the given header.
+    fn verify_header(&self, header: &SealedHeader) -> eyre::Result<()> {
+        eyre::ensure!(
+            self.block_number == header.number && self.block_hash == header.hash(),
+            "snapshot block {} ({}) is not the finalized block {} ({}) of the consensus client",
+            header.number,
+            header.hash(),
+            self.block_number,
+            self.block_hash
+        );
+        Ok(())
+    }
+}
+
+/// The response of the `/eth/v2/beacon/blocks/{block_id}` endpoint of the beacon API, reduced to
+/// the execution payload.
+#[derive(Debug, Deserialize)]
+struct BeaconBlockResponse {
+    /// Whether the block is finalized, not present on older consensus clients.
+    #[serde(default)]
+    finalized: bool,
+    data: BeaconBlockData,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockData {
+    message: BeaconBlockMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockMessage {
+    body: BeaconBlockBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockBody {
+    execution_payload: ExecutionPayloadSummary,
+}
+
+/// Fetches the execution payload of the finalized beacon block at the given slot.
+async fn fetch_finalized_payload(
+    client: &Client,
+    beacon_api: &Url,
+    slot: u64,
+) -> eyre::Result<ExecutionPayloadSummary> {
+    let url = beacon_api.join(&format!("eth/v2/beacon/blocks/{slot}"))?;
+    let response: BeaconBlockResponse =
+        client.get(url).send().await?.error_for_status()?.json().await?;
+    eyre::ensure!(response.finalized, "beacon block at slot {slot} is not finalized");
+    Ok(response.data.message.body.execution_payload)
+}
+
+/// Downloads the file at the url to the given path, without holding it in memory.
+async fn download(client: &Client, url: Url, path: &Path) -> eyre::Result<()> {
+    info!(target: "reth::cli", %url, path = %path.display(), "Downloading state dump");
+
+    let mut response = client.get(url).send().await?.error_for_status()?;
+    let mut file = BufWriter::new(File::create(path)?);
+    let mut downloaded = 0u64;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+        let before = downloaded;
+        downloaded += chunk.len() as u64;
+        if downloaded / DOWNLOAD_PROGRESS_INTERVAL > before / DOWNLOAD_PROGRESS_INTERVAL {
+            info!(target: "reth::cli", downloaded_bytes = downloaded, "Downloading state dump");
+        }
+    }
+    file.flush()?;
+
+    info!(target: "reth::cli", downloaded_bytes = downloaded, "Downloaded state dump");
+    Ok(())
+}
+
+/// Reads the state root from the first line of a state dump.
+fn read_state_root(path: &Path) -> eyre::Result<B256> {
+    #[derive(Deserialize)]
+    struct StateRoot {
+        root: B256,
+    }
+
+    let mut line = String::new();
+    BufReader::new(File::open(path)?).read_line(&mut line)?;
+    Ok(serde_json::from_str::<StateRoot>(&line)?.root)
+}
+
+/// Deserializes numbers that the beacon API encodes as decimal strings.
+mod quoted {
+    use serde::{Deserialize, Deserializer};
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{hex, Header};
+
+    #[test]
+    fn parse_snapshot_sync_command() {
+        let args = SnapshotSyncCommand::parse_from([
+            "reth",
+            "--url",
+            "https://snapshots.example.com/mainnet/manifest.json",
+            "--beacon-api",
+            "http://localhost:5052",
+        ]);
+        assert_eq!(args.beacon_api, Some("http://localhost:5052".parse().unwrap()));
+
+        // a trusted state root is required
+        let args = SnapshotSyncCommand::try_parse_from([
+            "reth",
+            "--url",
+            "https://snapshots.example.com/mainnet/manifest.json",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn verify_finalized_payload() {
+        let header = Header { number: 20_000_000, ..Default::default() }.seal_slow();
+        let manifest: SnapshotManifest = serde_json::from_value(serde_json::json!({
+            "slot": 9_220_000,
+            "header": hex::encode_prefixed(alloy_rlp::encode(header.header())),
+            "totalDifficulty": "0xc70d815d562d3cfa955",
+            "state": "state.jsonl",
+        }))
+        .unwrap();
+        assert_eq!(decode_header(manifest.header.into_bytes()).unwrap(), header);
+
+        let response: BeaconBlockResponse = serde_json::from_value(serde_json::json!({
+            "version": "deneb",
+            "execution_optimistic": false,
+            "finalized": true,
+            "data": {
+                "message": {
+                    "slot": "9220000",
+                    "body": {
+                        "execution_payload": {
+                            "block_number": "20000000",
+                            "block_hash": header.hash(),
+                            "state_root": header.state_root,
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        assert!(response.finalized);
+        let payload = response.data.message.body.execution_payload;
+        payload.verify_header(&header).unwrap();
+
+        let other = Header { number: 20_000_001, ..Default::default() }.seal_slow();
+        assert!(payload.verify_header(&other).is_err());
+    }
+}
@@ -154,6 +154,7 @@ async fn dry_run<DB: Database>(
     let mut stage = MerkleStage::Execution {
         // Forces updating the root instead of calculating from scratch
         clean_threshold: u64::MAX,
+        clean_chunk_nibbles: 0,
     };
 
     loop {
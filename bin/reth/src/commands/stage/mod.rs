@@ -5,6 +5,7 @@ use reth_cli_runner::CliContext;
 
 pub mod drop;
 pub mod dump;
+pub mod rerun;
 pub mod run;
 pub mod unwind;
 
@@ -31,6 +32,8 @@ pub enum Subcommands {
     Dump(dump::Command),
     /// Unwinds a certain block range, deleting it from the database.
     Unwind(unwind::Command),
+    /// Re-runs a stage from a block, together with all stages that depend on its output.
+    Rerun(rerun::Command),
 }
 
 impl Command {
@@ -41,6 +44,7 @@ impl Command {
             Subcommands::Drop(command) => command.execute().await,
             Subcommands::Dump(command) => command.execute().await,
             Subcommands::Unwind(command) => command.execute().await,
+            Subcommands::Rerun(command) => command.execute().await,
         }
     }
 }
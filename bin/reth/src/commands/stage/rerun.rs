@@ -0,0 +1,140 @@
+//! Re-running a stage together with the stages that depend on it
+
+use crate::{
+    args::StageEnum,
+    commands::common::{AccessRights, Environment, EnvironmentArgs},
+    macros::block_executor,
+};
+use clap::Parser;
+use reth_primitives::BlockNumber;
+use reth_provider::{ChainSpecProvider, StageCheckpointReader};
+use reth_stages::{sets::OfflineStages, stages::FinishStage, Pipeline, StageId, StageSet};
+use reth_static_file::StaticFileProducer;
+use tracing::info;
+
+/// `reth stage rerun` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The name of the stage to re-run
+    #[arg(value_enum)]
+    stage: StageEnum,
+
+    /// The block to re-run the stage from, inclusive
+    #[arg(long)]
+    from: BlockNumber,
+}
+
+impl Command {
+    /// Execute `stage rerun` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let Environment { provider_factory, config, .. } = self.env.init(AccessRights::RW)?;
+
+        if self.from == 0 {
+            eyre::bail!("Cannot re-run stages from the genesis block")
+        }
+
+        let stages = stage_ids(self.stage)?;
+
+        // Every stage that reads the output of the re-run stages has to be re-run as well,
+        // otherwise it would keep data derived from the old output.
+        let mut invalidated = stages.to_vec();
+        for dependent in stages.iter().flat_map(StageId::dependents) {
+            if !invalidated.contains(&dependent) {
+                invalidated.push(dependent);
+            }
+        }
+        invalidated.sort_by_key(|stage| StageId::ALL.iter().position(|id| id == stage));
+
+        let provider = provider_factory.provider()?;
+        let mut target = 0;
+        for stage in stages {
+            target =
+                target.max(provider.get_stage_checkpoint(*stage)?.unwrap_or_default().block_number);
+        }
+        drop(provider);
+
+        if self.from > target {
+            eyre::bail!(
+                "Stage {} has only reached block {target}, cannot re-run it from block {}",
+                self.stage,
+                self.from
+            )
+        }
+
+        info!(
+            target: "reth::cli",
+            stage = %self.stage,
+            from = self.from,
+            to = target,
+            ?invalidated,
+            "Re-running stages"
+        );
+
+        let prune_modes = config.prune.clone().map(|prune| prune.segments).unwrap_or_default();
+        let executor = block_executor!(provider_factory.chain_spec());
+
+        // Only the invalidated stages are part of the pipeline, all other stages keep their data
+        let disabled = StageId::ALL
+            .into_iter()
+            .filter(|stage| !stage.is_downloading_stage() && !invalidated.contains(stage))
+            .collect::<Vec<_>>();
+        let mut pipeline = Pipeline::builder()
+            .add_stages(
+                OfflineStages::new(executor, config.stages, prune_modes.clone())
+                    .builder()
+                    .add_stage(FinishStage::default())
+                    .disable_all(&disabled),
+            )
+            .with_max_block(target)
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory, prune_modes),
+            );
+
+        // Fail before any data is moved or deleted if the unwind target is below pruned data.
+        pipeline.check_unwind_target(self.from - 1)?;
+
+        // Move all applicable data from database to static files before unwinding, like the
+        // pipeline unwind of `reth stage unwind` does.
+        pipeline.move_to_static_files()?;
+        pipeline.unwind(self.from - 1, None)?;
+        pipeline.run().await?;
+
+        info!(target: "reth::cli", stage = %self.stage, from = self.from, to = target, "Re-ran stages");
+
+        Ok(())
+    }
+}
+
+/// Returns the IDs of the pipeline stages that make up the given stage argument.
+fn stage_ids(stage: StageEnum) -> eyre::Result<&'static [StageId]> {
+    Ok(match stage {
+        StageEnum::Headers | StageEnum::Bodies => {
+            eyre::bail!("Stage {stage} requires network access, use `reth stage unwind` instead")
+        }
+        StageEnum::Senders => &[StageId::SenderRecovery],
+        StageEnum::Execution => &[StageId::Execution],
+        StageEnum::AccountHashing => &[StageId::AccountHashing],
+        StageEnum::StorageHashing => &[StageId::StorageHashing],
+        StageEnum::Hashing => &[StageId::AccountHashing, StageId::StorageHashing],
+        StageEnum::Merkle => &[StageId::MerkleUnwind, StageId::MerkleExecute],
+        StageEnum::TxLookup => &[StageId::TransactionLookup],
+        StageEnum::AccountHistory => &[StageId::IndexAccountHistory],
+        StageEnum::StorageHistory => &[StageId::IndexStorageHistory],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rerun() {
+        let cmd = Command::parse_from(["reth", "--datadir", "dir", "execution", "--from", "100"]);
+        assert_eq!(cmd.stage, StageEnum::Execution);
+        assert_eq!(cmd.from, 100);
+    }
+}
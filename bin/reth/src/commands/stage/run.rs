@@ -3,7 +3,7 @@
 //! Stage debugging tool
 
 use crate::{
-    args::{get_secret_key, NetworkArgs, StageEnum},
+    args::{get_secret_key, DatadirArgs, NetworkArgs, StageEnum},
     commands::common::{AccessRights, Environment, EnvironmentArgs},
     macros::block_executor,
     prometheus_exporter,
@@ -26,7 +26,7 @@ use reth_stages::{
     },
     ExecInput, ExecOutput, Stage, StageExt, UnwindInput, UnwindOutput,
 };
-use std::{any::Any, net::SocketAddr, sync::Arc, time::Instant};
+use std::{any::Any, net::SocketAddr, path::PathBuf, sync::Arc, time::Instant};
 use tracing::*;
 
 /// `reth stage` command
@@ -77,18 +77,67 @@ pub struct Command {
     #[arg(long)]
     checkpoints: bool,
 
+    /// Runs the stage on a copy of the database and static files in the given datadir instead
+    /// of the node's datadir, which is only read.
+    ///
+    /// The copy is taken anew on every run, so the stage can be run repeatedly on the same
+    /// input, e.g. for benchmarking, without mutating the node's database. `--commit` and
+    /// `--checkpoints` write to the copy.
+    #[arg(long, value_name = "DATA_DIR")]
+    scratch_datadir: Option<PathBuf>,
+
     #[command(flatten)]
     network: NetworkArgs,
 }
 
 impl Command {
+    /// Copies the database and static files of the node's datadir into the scratch datadir,
+    /// replacing any previous copy, and returns the read-write [`Environment`] of the copy.
+    fn init_scratch_environment(&self, path: PathBuf) -> eyre::Result<Environment> {
+        let Environment { provider_factory, data_dir, .. } = self.env.init(AccessRights::RO)?;
+
+        let scratch = EnvironmentArgs {
+            datadir: DatadirArgs { datadir: path.into(), static_files_path: None },
+            config: Some(self.env.config.clone().unwrap_or_else(|| data_dir.config())),
+            chain: self.env.chain.clone(),
+            db: self.env.db,
+        };
+        let scratch_dir = scratch.datadir.clone().resolve_datadir(scratch.chain.chain);
+        if scratch_dir.data_dir().canonicalize().ok() == data_dir.data_dir().canonicalize().ok() {
+            eyre::bail!("the scratch datadir must differ from the node's datadir")
+        }
+
+        let (db_path, sf_path) = (scratch_dir.db(), scratch_dir.static_files());
+        info!(target: "reth::cli", ?db_path, ?sf_path, "Copying storage to scratch datadir");
+        for dir in [&db_path, &sf_path] {
+            if dir.exists() {
+                reth_fs_util::remove_dir_all(dir)?;
+            }
+            reth_fs_util::create_dir_all(dir)?;
+        }
+
+        provider_factory.db_ref().copy(&db_path.join("mdbx.dat"), false)?;
+        for entry in reth_fs_util::read_dir(data_dir.static_files())? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                reth_fs_util::copy(entry.path(), sf_path.join(entry.file_name()))?;
+            }
+        }
+        drop(provider_factory);
+
+        scratch.init(AccessRights::RW)
+    }
+
     /// Execute `stage` command
     pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
         // Raise the fd limit of the process.
         // Does not do anything on windows.
         let _ = fdlimit::raise_fd_limit();
 
-        let Environment { provider_factory, config, data_dir } = self.env.init(AccessRights::RW)?;
+        let Environment { provider_factory, config, data_dir } = match &self.scratch_datadir {
+            Some(path) => self.init_scratch_environment(path.clone())?,
+            None => self.env.init(AccessRights::RW)?,
+        };
 
         let mut provider_rw = provider_factory.provider_rw()?;
 
@@ -188,7 +237,7 @@ impl Command {
                 }
                 StageEnum::TxLookup => (
                     Box::new(TransactionLookupStage::new(
-                        TransactionLookupConfig { chunk_size: batch_size },
+                        TransactionLookupConfig { chunk_size: batch_size, ..Default::default() },
                         etl_config,
                         prune_modes.transaction_lookup,
                     )),
@@ -83,6 +83,9 @@ impl Command {
             // This will build an offline-only pipeline if the `offline` flag is enabled
             let mut pipeline = self.build_pipeline(config, provider_factory.clone()).await?;
 
+            // Fail before any data is moved or deleted if the unwind target is below pruned data.
+            pipeline.check_unwind_target((*range.start()).saturating_sub(1))?;
+
             // Move all applicable data from database to static files.
             pipeline.move_to_static_files()?;
 
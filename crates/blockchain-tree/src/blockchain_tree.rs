@@ -28,6 +28,7 @@ use reth_stages_api::{MetricEvent, MetricEventsSender};
 use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use std::{
     collections::{btree_map::Entry, BTreeMap, HashSet},
+    path::PathBuf,
     sync::Arc,
 };
 use tracing::{debug, error, info, instrument, trace, warn};
@@ -166,6 +167,16 @@ where
         self
     }
 
+    /// Sets the directory the state of sidechains is spilled to when it exceeds the memory budget
+    /// of the [`BlockchainTreeConfig`].
+    ///
+    /// The state is spilled to a temporary directory in the given directory, which defaults to the
+    /// system temporary directory.
+    pub fn with_spill_dir(mut self, dir: PathBuf) -> Self {
+        self.state.set_spill_dir(dir);
+        self
+    }
+
     /// Check if the block is known to blockchain tree or database and return its status.
     ///
     /// Function will check:
@@ -243,7 +254,7 @@ where
     /// Returns the block's receipts with matching hash from any side-chain.
     ///
     /// Caution: This will not return blocks from the canonical chain.
-    pub fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<&Receipt>> {
+    pub fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<Receipt>> {
         self.state.receipts_by_block_hash(block_hash)
     }
 
@@ -273,12 +284,12 @@ where
         if let Some(chain_id) = self.block_indices().get_block_chain_id(&block_hash) {
             trace!(target: "blockchain_tree", ?block_hash, "Constructing post state data based on non-canonical chain");
             // get block state
-            let Some(chain) = self.state.chains.get(&chain_id) else {
+            let Some(execution_outcome) =
+                self.state.execution_outcome_at_block(chain_id, block_hash)
+            else {
                 debug!(target: "blockchain_tree", ?chain_id, "Chain with ID not present");
                 return None;
             };
-            let block_number = chain.block_number(block_hash)?;
-            let execution_outcome = chain.execution_outcome_at_block(block_number)?;
 
             // get parent hashes
             let mut parent_block_hashes = self.all_chain_hashes(chain_id);
@@ -461,7 +472,8 @@ where
             BlockchainTreeError::BlockSideChainIdConsistency { chain_id: chain_id.into() }
         })?;
 
-        // get chain that block needs to join to.
+        // get chain that block needs to join to, reading its state if it was spilled to disk.
+        self.state.restore_state(chain_id);
         let parent_chain = self.state.chains.get_mut(&chain_id).ok_or_else(|| {
             BlockchainTreeError::BlockSideChainIdConsistency { chain_id: chain_id.into() }
         })?;
@@ -634,7 +646,7 @@ where
 
                 // prepend state to all chains that fork from this block.
                 for chain_id in chains_to_bump {
-                    let Some(chain) = self.state.chains.get_mut(&chain_id) else {
+                    let Some(chain) = self.state.chain_mut(chain_id) else {
                         debug!(target: "blockchain_tree", ?chain_id, "Chain not in tree");
                         return None;
                     };
@@ -784,9 +796,18 @@ where
         let status = self
             .try_insert_validated_block(block.clone(), block_validation_kind)
             .map_err(|kind| InsertBlockError::new(block.block, kind))?;
+        self.enforce_memory_budget();
         Ok(InsertPayloadOk::Inserted(status))
     }
 
+    /// Spills the state of old sidechains to disk if the state of the chains in memory exceeds the
+    /// configured memory budget.
+    fn enforce_memory_budget(&mut self) {
+        if let Some(budget) = self.config.memory_budget() {
+            self.state.enforce_memory_budget(budget);
+        }
+    }
+
     /// Discard all blocks that precede block number from the buffer.
     pub fn remove_old_blocks(&mut self, block: BlockNumber) {
         self.state.buffered_blocks.remove_old_blocks(block);
@@ -801,7 +822,7 @@ where
         );
         // remove chains of removed blocks
         while let Some(chain_id) = remove_chains.pop_first() {
-            if let Some(chain) = self.state.chains.remove(&chain_id) {
+            if let Some(chain) = self.state.discard_chain(chain_id) {
                 remove_chains.extend(self.state.block_indices.remove_chain(&chain));
             }
         }
@@ -850,7 +871,7 @@ where
 
         // remove all chains that got discarded
         while let Some(chain_id) = remove_chains.first() {
-            if let Some(chain) = self.state.chains.remove(chain_id) {
+            if let Some(chain) = self.state.discard_chain(*chain_id) {
                 remove_chains.extend(self.state.block_indices.remove_chain(&chain));
             }
         }
@@ -943,7 +964,7 @@ where
         chain_id: BlockchainId,
         split_at: ChainSplitTarget,
     ) -> Option<Chain> {
-        let chain = self.state.chains.remove(&chain_id)?;
+        let chain = self.state.take_chain(chain_id)?;
         match chain.into_inner().split(split_at) {
             ChainSplit::Split { canonical, pending } => {
                 trace!(target: "blockchain_tree", ?canonical, ?pending, "Split chain");
@@ -1192,14 +1213,14 @@ where
             .into_iter()
             .for_each(|child| {
                 if let Some(chain_id) = self.block_indices().get_block_chain_id(&child) {
-                    if let Some(chain) = self.state.chains.get_mut(&chain_id) {
-                        chain.clear_trie_updates();
-                    }
+                    self.state.clear_trie_updates(chain_id);
                 }
             });
 
         durations_recorder.record_relative(MakeCanonicalAction::ClearTrieUpdatesForOtherChildren);
 
+        self.enforce_memory_budget();
+
         // Send notification about new canonical chain and return outcome of canonicalization.
         let outcome = CanonicalOutcome::Committed { head: chain_notification.tip().header.clone() };
         let _ = self.canon_state_notification_sender.send(chain_notification);
@@ -1282,6 +1303,7 @@ where
             self.state.block_indices.unwind_canonical_chain(unwind_to);
             // insert old canonical chain to BlockchainTree.
             self.insert_unwound_chain(AppendableChain::new(old_canon_chain));
+            self.enforce_memory_budget();
         }
 
         Ok(())
@@ -1357,6 +1379,7 @@ where
         }
 
         self.metrics.sidechains.set(self.state.chains.len() as f64);
+        self.metrics.spilled_sidechains.set(self.state.spilled.len() as f64);
         self.metrics.canonical_chain_height.set(height as f64);
         if let Some(metrics_tx) = self.sync_metrics_tx.as_mut() {
             let _ = metrics_tx.send(MetricEvent::SyncHeight { height });
@@ -1971,6 +1994,67 @@ mod tests {
         assert_eq!(chain1.first_block(), block2.number);
     }
 
+    #[test]
+    fn spill_side_chain_state() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let genesis = data.genesis;
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec2.clone(), exec2, exec1]);
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, genesis);
+
+        // make tree that spills the state of all chains but the pending one
+        let spill_dir = tempfile::tempdir().unwrap();
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2).with_memory_budget(Some(0));
+        let mut tree = BlockchainTree::new(externals, config, None)
+            .expect("failed to create tree")
+            .with_spill_dir(spill_dir.path().to_path_buf());
+        // genesis block 10 is already canonical
+        tree.make_canonical(B256::ZERO).unwrap();
+
+        // make genesis block 10 as finalized
+        tree.finalize_block(10).unwrap();
+
+        tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap();
+        tree.insert_block(block2.clone(), BlockValidationKind::Exhaustive).unwrap();
+
+        let mut block2a = block2.clone();
+        let block2a_hash = B256::new([0x34; 32]);
+        block2a.set_hash(block2a_hash);
+        assert_eq!(
+            tree.insert_block(block2a.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::HistoricalFork))
+        );
+
+        // chain 0 holds the pending block and stays in memory, the state of chain 1 is spilled.
+        assert!(!tree.state.spilled.contains(&0.into()));
+        assert!(tree.state.spilled.contains(&1.into()));
+        let chain1 = tree.state.chains.get(&1.into()).unwrap();
+        assert_eq!(chain1.execution_outcome(), &ExecutionOutcome::default());
+        assert_eq!(tree.block_with_senders_by_hash(block2a_hash), Some(&block2a));
+
+        // the spilled state is read from disk
+        let execution_outcome = tree.post_state_data(block2a_hash).unwrap().execution_outcome;
+        assert_eq!(execution_outcome.receipts().len(), 1);
+        assert_eq!(execution_outcome.first_block(), block2.number);
+        assert!(tree.receipts_by_block_hash(block2a_hash).is_some());
+
+        // and restored when the chain becomes canonical
+        assert_eq!(
+            tree.make_canonical(block2a_hash).unwrap(),
+            CanonicalOutcome::Committed { head: block2a.header.clone() }
+        );
+        assert!(!tree.state.spilled.contains(&1.into()));
+
+        // the state of block 2, which is now a sidechain, is spilled
+        let block2_chain_id = tree.state.block_indices.get_block_chain_id(&block2.hash()).unwrap();
+        assert!(tree.state.spilled.contains(&block2_chain_id));
+    }
+
     #[test]
     fn sanity_path() {
         let data = BlockchainTestData::default_from_number(11);
@@ -2367,11 +2451,15 @@ mod tests {
             provider_factory: externals.provider_factory.clone(),
             executor_factory: externals.executor_factory.clone(),
             consensus: externals.consensus.clone(),
+            shared_cached_reads: None,
+            invalid_block_hook: None,
         };
         let cloned_externals_2 = TreeExternals {
             provider_factory: externals.provider_factory.clone(),
             executor_factory: externals.executor_factory.clone(),
             consensus: externals.consensus.clone(),
+            shared_cached_reads: None,
+            invalid_block_hook: None,
         };
 
         // last finalized block would be number 9.
@@ -4,7 +4,7 @@
 //! blocks, as well as a list of the blocks the chain is composed of.
 
 use super::externals::TreeExternals;
-use crate::BundleStateDataRef;
+use crate::{BundleStateDataRef, InvalidBlock};
 use reth_blockchain_tree_api::{
     error::{BlockchainTreeError, InsertBlockErrorKind},
     BlockAttachment, BlockValidationKind,
@@ -15,7 +15,8 @@ use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
 use reth_execution_errors::BlockExecutionError;
 use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_primitives::{
-    BlockHash, BlockNumber, ForkBlock, GotExpected, SealedBlockWithSenders, SealedHeader, U256,
+    BlockHash, BlockNumber, BlockWithSenders, ForkBlock, GotExpected, Receipt,
+    SealedBlockWithSenders, SealedHeader, B256, U256,
 };
 use reth_provider::{
     providers::{BundleStateProvider, ConsistentDbView},
@@ -204,42 +205,99 @@ impl AppendableChain {
 
         let provider = BundleStateProvider::new(state_provider, bundle_state_data_provider);
 
-        let db = StateProviderDatabase::new(&provider);
+        // reuse the reads of other tasks on top of the parent, e.g. of building this payload
+        let mut cached_reads = externals
+            .shared_cached_reads
+            .as_ref()
+            .and_then(|shared| shared.get(block.parent_hash))
+            .unwrap_or_default();
+        let db = cached_reads.as_db_mut(StateProviderDatabase::new(&provider));
         let executor = externals.executor_factory.executor(db);
         let block_hash = block.hash();
         let block = block.unseal();
 
-        let state = executor.execute((&block, U256::MAX).into())?;
-        let BlockExecutionOutput { state, receipts, requests, .. } = state;
-        externals
-            .consensus
-            .validate_block_post_execution(&block, PostExecutionInput::new(&receipts, &requests))?;
+        let state = executor.execute((&block, U256::MAX).into());
+        // the reads of the parent state, turned into reads of the state of this block once it is
+        // valid, so that the validation of its child doesn't read them from the database again
+        let mut post_state_reads = None;
+        if let Some(shared) = &externals.shared_cached_reads {
+            post_state_reads = Some(cached_reads.clone());
+            shared.insert(block.parent_hash, cached_reads);
+        }
+        let output = match state {
+            Ok(output) => output,
+            Err(err) => {
+                return Err(Self::on_invalid_block(
+                    externals,
+                    parent_block,
+                    &block,
+                    block_hash,
+                    None,
+                    None,
+                    err,
+                ))
+            }
+        };
+        if let Err(err) = externals.consensus.validate_block_post_execution(
+            &block,
+            PostExecutionInput::new(&output.receipts, &output.requests),
+        ) {
+            return Err(Self::on_invalid_block(
+                externals,
+                parent_block,
+                &block,
+                block_hash,
+                Some(&output),
+                None,
+                err.into(),
+            ))
+        }
 
-        let initial_execution_outcome =
-            ExecutionOutcome::new(state, receipts.into(), block.number, vec![requests.into()]);
+        let into_execution_outcome = |output: BlockExecutionOutput<Receipt>| {
+            ExecutionOutcome::new(
+                output.state,
+                output.receipts.into(),
+                block.number,
+                vec![output.requests.into()],
+            )
+        };
 
         // check state root if the block extends the canonical chain __and__ if state root
         // validation was requested.
-        if block_validation_kind.is_exhaustive() {
+        let trie_updates = if block_validation_kind.is_exhaustive() {
             // calculate and check state root
             let start = Instant::now();
             let (state_root, trie_updates) = if block_attachment.is_canonical() {
                 let mut execution_outcome =
                     provider.block_execution_data_provider.execution_outcome().clone();
-                execution_outcome.extend(initial_execution_outcome.clone());
+                execution_outcome.extend(ExecutionOutcome::new(
+                    output.state.clone(),
+                    output.receipts.clone().into(),
+                    block.number,
+                    vec![output.requests.clone().into()],
+                ));
                 let hashed_state = execution_outcome.hash_state_slow();
                 ParallelStateRoot::new(consistent_view, hashed_state)
                     .incremental_root_with_updates()
                     .map(|(root, updates)| (root, Some(updates)))
                     .map_err(ProviderError::from)?
             } else {
-                (provider.state_root(initial_execution_outcome.state())?, None)
+                (provider.state_root(&output.state)?, None)
             };
             if block.state_root != state_root {
-                return Err(ConsensusError::BodyStateRootDiff(
+                let err = ConsensusError::BodyStateRootDiff(
                     GotExpected { got: state_root, expected: block.state_root }.into(),
                 )
-                .into())
+                .into();
+                return Err(Self::on_invalid_block(
+                    externals,
+                    parent_block,
+                    &block,
+                    block_hash,
+                    Some(&output),
+                    Some((state_root, trie_updates.as_ref())),
+                    err,
+                ))
             }
 
             tracing::debug!(
@@ -250,10 +308,43 @@ impl AppendableChain {
                 "Validated state root"
             );
 
-            Ok((initial_execution_outcome, trie_updates))
+            trie_updates
         } else {
-            Ok((initial_execution_outcome, None))
+            None
+        };
+
+        if let (Some(shared), Some(mut reads)) = (&externals.shared_cached_reads, post_state_reads)
+        {
+            reads.apply_bundle(&output.state);
+            shared.insert(block_hash, reads);
+        }
+
+        Ok((into_execution_outcome(output), trie_updates))
+    }
+
+    /// Invokes the [`InvalidBlockHook`](crate::InvalidBlockHook) of the externals, if any, with
+    /// the block that failed validation, and returns the error.
+    fn on_invalid_block<DB, E>(
+        externals: &TreeExternals<DB, E>,
+        parent_header: &SealedHeader,
+        block: &BlockWithSenders,
+        block_hash: BlockHash,
+        output: Option<&BlockExecutionOutput<Receipt>>,
+        state_root: Option<(B256, Option<&TrieUpdates>)>,
+        error: BlockExecutionError,
+    ) -> BlockExecutionError {
+        if let Some(hook) = &externals.invalid_block_hook {
+            let block = block.clone().seal(block_hash);
+            hook.on_invalid_block(InvalidBlock {
+                parent_header,
+                block: &block,
+                output,
+                state_root: state_root.map(|(root, _)| root),
+                trie_updates: state_root.and_then(|(_, updates)| updates),
+                error: &error,
+            });
         }
+        error
     }
 
     /// Validate and execute the given block, and append it to this chain.
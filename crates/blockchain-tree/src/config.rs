@@ -19,6 +19,9 @@ pub struct BlockchainTreeConfig {
     /// be 256. It covers both number of blocks required for reorg, and number of blocks
     /// required for `BLOCKHASH` EVM opcode.
     num_of_additional_canonical_block_hashes: u64,
+    /// The maximum memory in bytes the state of the sidechains can use before the state of the
+    /// oldest sidechains is spilled to disk. Unbounded if `None`.
+    memory_budget: Option<usize>,
 }
 
 impl Default for BlockchainTreeConfig {
@@ -33,6 +36,8 @@ impl Default for BlockchainTreeConfig {
             num_of_additional_canonical_block_hashes: 256,
             // max unconnected blocks.
             max_unconnected_blocks: 200,
+            // keep the state of all sidechains in memory.
+            memory_budget: None,
         }
     }
 }
@@ -54,9 +59,17 @@ impl BlockchainTreeConfig {
             max_reorg_depth,
             num_of_additional_canonical_block_hashes,
             max_unconnected_blocks,
+            memory_budget: None,
         }
     }
 
+    /// Sets the maximum memory in bytes the state of the sidechains can use before the state of
+    /// the oldest sidechains is spilled to disk.
+    pub const fn with_memory_budget(mut self, memory_budget: Option<usize>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
     /// Return the maximum reorg depth.
     pub const fn max_reorg_depth(&self) -> u64 {
         self.max_reorg_depth
@@ -88,4 +101,9 @@ impl BlockchainTreeConfig {
     pub const fn max_unconnected_blocks(&self) -> u32 {
         self.max_unconnected_blocks
     }
+
+    /// Return the maximum memory in bytes the state of the sidechains can use, if bounded.
+    pub const fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
 }
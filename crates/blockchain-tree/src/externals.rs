@@ -1,5 +1,6 @@
 //! Blockchain tree externals.
 
+use crate::InvalidBlockHook;
 use reth_consensus::Consensus;
 use reth_db::{static_file::HeaderMask, tables};
 use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
@@ -8,6 +9,7 @@ use reth_provider::{
     FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory, StaticFileProviderFactory,
     StatsReader,
 };
+use reth_revm::cached::SharedCachedReads;
 use reth_storage_errors::provider::ProviderResult;
 use std::{collections::BTreeMap, sync::Arc};
 
@@ -28,6 +30,11 @@ pub struct TreeExternals<DB, E> {
     pub(crate) consensus: Arc<dyn Consensus>,
     /// The executor factory to execute blocks with.
     pub(crate) executor_factory: E,
+    /// Reads of the state of recent blocks shared with other tasks, e.g. payload building, reused
+    /// when executing blocks on top of them.
+    pub(crate) shared_cached_reads: Option<SharedCachedReads>,
+    /// Hook invoked with blocks that failed validation.
+    pub(crate) invalid_block_hook: Option<Arc<dyn InvalidBlockHook>>,
 }
 
 impl<DB, E> TreeExternals<DB, E> {
@@ -37,7 +44,30 @@ impl<DB, E> TreeExternals<DB, E> {
         consensus: Arc<dyn Consensus>,
         executor_factory: E,
     ) -> Self {
-        Self { provider_factory, consensus, executor_factory }
+        Self {
+            provider_factory,
+            consensus,
+            executor_factory,
+            shared_cached_reads: None,
+            invalid_block_hook: None,
+        }
+    }
+
+    /// Sets the reads of the state of recent blocks shared with other tasks.
+    ///
+    /// Blocks are executed on top of the cached reads of their parent, and the reads of their
+    /// execution are added to the cache. The reads updated with the changes of a valid block are
+    /// cached as the state of that block, which its child is executed on top of.
+    pub fn with_shared_cached_reads(mut self, shared_cached_reads: SharedCachedReads) -> Self {
+        self.shared_cached_reads = Some(shared_cached_reads);
+        self
+    }
+
+    /// Sets the hook that is invoked with blocks that failed to execute or failed validation after
+    /// execution.
+    pub fn with_invalid_block_hook(mut self, hook: Arc<dyn InvalidBlockHook>) -> Self {
+        self.invalid_block_hook = Some(hook);
+        self
     }
 }
 
@@ -0,0 +1,33 @@
+//! Hook invoked with blocks that failed validation.
+
+use reth_evm::execute::BlockExecutionOutput;
+use reth_execution_errors::BlockExecutionError;
+use reth_primitives::{Receipt, SealedBlockWithSenders, SealedHeader, B256};
+use reth_trie::updates::TrieUpdates;
+use std::fmt;
+
+/// A block that failed validation after it was executed, or failed to execute.
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidBlock<'a> {
+    /// The header of the parent of the block.
+    pub parent_header: &'a SealedHeader,
+    /// The invalid block.
+    pub block: &'a SealedBlockWithSenders,
+    /// The output of the execution of the block, if it was executed successfully.
+    pub output: Option<&'a BlockExecutionOutput<Receipt>>,
+    /// The state root computed after executing the block, if it was computed.
+    pub state_root: Option<B256>,
+    /// The trie updates of the computed state root, if the block extends the canonical chain.
+    pub trie_updates: Option<&'a TrieUpdates>,
+    /// The reason the block is invalid.
+    pub error: &'a BlockExecutionError,
+}
+
+/// A hook that is invoked with blocks that failed to execute or that failed validation after
+/// execution, e.g. because of a state root mismatch.
+///
+/// The hook is called on the thread validating the block, before the error is returned.
+pub trait InvalidBlockHook: fmt::Debug + Send + Sync {
+    /// Invoked with a block that failed validation.
+    fn on_invalid_block(&self, invalid: InvalidBlock<'_>);
+}
@@ -36,6 +36,9 @@ pub use config::BlockchainTreeConfig;
 pub mod externals;
 pub use externals::TreeExternals;
 
+pub mod invalid_block_hook;
+pub use invalid_block_hook::{InvalidBlock, InvalidBlockHook};
+
 pub mod shareable;
 pub use shareable::ShareableBlockchainTree;
 
@@ -54,6 +57,8 @@ pub use block_buffer::BlockBuffer;
 /// Implementation of Tree traits that does nothing.
 pub mod noop;
 
+mod spilled;
+
 mod state;
 
 use aquamarine as _;
@@ -11,6 +11,8 @@ use std::time::{Duration, Instant};
 pub struct TreeMetrics {
     /// Total number of sidechains (not including the canonical chain)
     pub sidechains: Gauge,
+    /// Number of sidechains whose state was spilled to disk
+    pub spilled_sidechains: Gauge,
     /// The highest block number in the canonical chain
     pub canonical_chain_height: Gauge,
     /// The number of reorgs
@@ -158,14 +158,12 @@ where
     fn pending_block_and_receipts(&self) -> Option<(SealedBlock, Vec<Receipt>)> {
         let tree = self.tree.read();
         let pending_block = tree.pending_block()?.clone();
-        let receipts =
-            tree.receipts_by_block_hash(pending_block.hash())?.into_iter().cloned().collect();
+        let receipts = tree.receipts_by_block_hash(pending_block.hash())?;
         Some((pending_block, receipts))
     }
 
     fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<Receipt>> {
-        let tree = self.tree.read();
-        Some(tree.receipts_by_block_hash(block_hash)?.into_iter().cloned().collect())
+        self.tree.read().receipts_by_block_hash(block_hash)
     }
 }
 
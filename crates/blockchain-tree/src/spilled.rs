@@ -0,0 +1,236 @@
+//! Sidechain state spilled to disk.
+
+use crate::state::BlockchainId;
+use reth_execution_types::{Chain, ExecutionOutcome};
+use reth_primitives::{Log, Receipt};
+use reth_trie::updates::{TrieKey, TrieOp, TrieUpdates};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, io, mem::size_of, path::PathBuf};
+use tempfile::TempDir;
+
+/// Approximate memory used by an entry of the bundle state, i.e. an account, a storage slot, a
+/// revert or a contract, including the overhead of the maps holding it.
+const STATE_ENTRY_SIZE: usize = 128;
+
+/// Approximate memory used by a trie update, including the overhead of the map holding it.
+const TRIE_UPDATE_SIZE: usize = 256;
+
+/// Returns an estimate of the memory used by the state of the chain, i.e. its execution outcome
+/// and trie updates.
+pub(crate) fn estimated_state_size(chain: &Chain) -> usize {
+    let execution_outcome = chain.execution_outcome();
+    let receipts = execution_outcome
+        .receipts()
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|receipt| {
+            size_of::<Receipt>() +
+                receipt
+                    .logs
+                    .iter()
+                    .map(|log| size_of::<Log>() + log.data.data.len())
+                    .sum::<usize>()
+        })
+        .sum::<usize>();
+    let trie_updates = chain.trie_updates().map_or(0, |updates| updates.len());
+
+    execution_outcome.bundle.size_hint() * STATE_ENTRY_SIZE +
+        receipts +
+        trie_updates * TRIE_UPDATE_SIZE
+}
+
+/// The state of sidechains that was written to disk to bound the memory used by the tree.
+///
+/// The state is written to a temporary directory, which is created in the configured directory
+/// when the first chain is spilled and removed on drop.
+#[derive(Debug)]
+pub(crate) struct SpilledStates {
+    /// The directory to create the temporary directory in.
+    parent_dir: PathBuf,
+    /// The temporary directory holding the spilled state, if any was spilled.
+    dir: Option<TempDir>,
+    /// The chains whose state was spilled.
+    chains: HashMap<BlockchainId, SpilledState>,
+}
+
+impl SpilledStates {
+    /// Creates an empty store spilling to a temporary directory in the given directory.
+    pub(crate) fn new(parent_dir: PathBuf) -> Self {
+        Self { parent_dir, dir: None, chains: HashMap::new() }
+    }
+
+    /// Returns `true` if the state of the chain was spilled.
+    pub(crate) fn contains(&self, chain_id: &BlockchainId) -> bool {
+        self.chains.contains_key(chain_id)
+    }
+
+    /// Returns the number of chains whose state was spilled.
+    pub(crate) fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    /// Writes the execution outcome and trie updates of the chain to disk.
+    pub(crate) fn spill(&mut self, chain_id: BlockchainId, chain: &Chain) -> io::Result<()> {
+        let dir = match &mut self.dir {
+            Some(dir) => dir,
+            dir @ None => {
+                fs::create_dir_all(&self.parent_dir)?;
+                dir.insert(
+                    tempfile::Builder::new()
+                        .prefix("blockchain-tree-")
+                        .tempdir_in(&self.parent_dir)?,
+                )
+            }
+        };
+        let path = dir.path().join(format!("{}.json", u64::from(chain_id)));
+
+        let state = ChainStateRef {
+            execution_outcome: chain.execution_outcome(),
+            trie_updates: chain.trie_updates().map(|updates| updates.iter().collect()),
+        };
+        fs::write(&path, serde_json::to_vec(&state)?)?;
+
+        self.chains.insert(chain_id, SpilledState { path, clear_trie_updates: false });
+        Ok(())
+    }
+
+    /// Reads the spilled state of the chain, if it was spilled.
+    pub(crate) fn read(&self, chain_id: &BlockchainId) -> Option<io::Result<ChainState>> {
+        self.chains.get(chain_id).map(SpilledState::read)
+    }
+
+    /// Removes the spilled state of the chain and returns it, if it was spilled.
+    pub(crate) fn take(&mut self, chain_id: &BlockchainId) -> Option<io::Result<ChainState>> {
+        let spilled = self.chains.remove(chain_id)?;
+        let state = spilled.read();
+        spilled.remove_file();
+        Some(state)
+    }
+
+    /// Discards the spilled state of the chain, if it was spilled.
+    pub(crate) fn remove(&mut self, chain_id: &BlockchainId) {
+        if let Some(spilled) = self.chains.remove(chain_id) {
+            spilled.remove_file();
+        }
+    }
+
+    /// Drops the trie updates of the chain once its spilled state is read.
+    pub(crate) fn clear_trie_updates(&mut self, chain_id: &BlockchainId) {
+        if let Some(spilled) = self.chains.get_mut(chain_id) {
+            spilled.clear_trie_updates = true;
+        }
+    }
+}
+
+/// A chain whose state was spilled.
+#[derive(Debug)]
+struct SpilledState {
+    /// The file the state was written to.
+    path: PathBuf,
+    /// Whether the trie updates were cleared after the state was spilled.
+    clear_trie_updates: bool,
+}
+
+impl SpilledState {
+    fn read(&self) -> io::Result<ChainState> {
+        let mut state: ChainState = serde_json::from_slice(&fs::read(&self.path)?)?;
+        if self.clear_trie_updates {
+            state.trie_updates = None;
+        }
+        Ok(state)
+    }
+
+    fn remove_file(self) {
+        // the temporary directory is removed on drop if this fails
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// The state of a chain, as written to disk.
+#[derive(Serialize)]
+struct ChainStateRef<'a> {
+    execution_outcome: &'a ExecutionOutcome,
+    /// Trie updates as a list, because their keys can't be JSON object keys.
+    trie_updates: Option<Vec<(&'a TrieKey, &'a TrieOp)>>,
+}
+
+/// The state of a chain, as read from disk.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ChainState {
+    execution_outcome: ExecutionOutcome,
+    trie_updates: Option<Vec<(TrieKey, TrieOp)>>,
+}
+
+impl ChainState {
+    /// Returns a chain with the blocks of the given chain and this state.
+    pub(crate) fn into_chain(self, chain: &Chain) -> Chain {
+        let (execution_outcome, trie_updates) = self.into_parts();
+        Chain::new(chain.blocks().values().cloned(), execution_outcome, trie_updates)
+    }
+
+    /// Returns the given chain with its state replaced by this state.
+    pub(crate) fn restore(self, chain: Chain) -> Chain {
+        let (execution_outcome, trie_updates) = self.into_parts();
+        let (blocks, _, _) = chain.into_inner();
+        Chain::new(blocks.into_blocks(), execution_outcome, trie_updates)
+    }
+
+    fn into_parts(self) -> (ExecutionOutcome, Option<TrieUpdates>) {
+        let trie_updates = self.trie_updates.map(|updates| {
+            let mut trie_updates = TrieUpdates::default();
+            trie_updates.extend(updates);
+            trie_updates
+        });
+        (self.execution_outcome, trie_updates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, SealedBlockWithSenders, U256};
+    use reth_revm::{db::BundleState, primitives::AccountInfo};
+    use reth_trie::{BranchNodeCompact, StoredNibbles};
+
+    #[test]
+    fn spill_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spilled = SpilledStates::new(dir.path().to_path_buf());
+
+        let block = SealedBlockWithSenders::default();
+        let execution_outcome = ExecutionOutcome::new(
+            BundleState::new(
+                vec![(
+                    Address::new([1; 20]),
+                    None,
+                    Some(AccountInfo::default()),
+                    HashMap::from([(U256::from(1), (U256::ZERO, U256::from(2)))]),
+                )],
+                vec![vec![(Address::new([1; 20]), None, vec![(U256::from(1), U256::ZERO)])]],
+                vec![],
+            ),
+            vec![vec![Some(Receipt::default())]].into(),
+            block.number,
+            vec![],
+        );
+        let trie_updates = TrieUpdates::from([(
+            TrieKey::AccountNode(StoredNibbles::from(vec![1])),
+            TrieOp::Update(BranchNodeCompact::default()),
+        )]);
+        let chain = Chain::new([block], execution_outcome, Some(trie_updates));
+        let chain_id = BlockchainId::from(1);
+
+        spilled.spill(chain_id, &chain).unwrap();
+        assert!(spilled.contains(&chain_id));
+        let stub = Chain::new(chain.blocks().values().cloned(), Default::default(), None);
+        assert_eq!(spilled.read(&chain_id).unwrap().unwrap().into_chain(&stub), chain);
+
+        spilled.clear_trie_updates(&chain_id);
+        let restored = spilled.take(&chain_id).unwrap().unwrap().restore(stub);
+        assert_eq!(restored.execution_outcome(), chain.execution_outcome());
+        assert_eq!(restored.trie_updates(), None);
+        assert!(!spilled.contains(&chain_id));
+        assert!(spilled.take(&chain_id).is_none());
+    }
+}
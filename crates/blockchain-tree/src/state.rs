@@ -1,8 +1,17 @@
 //! Blockchain tree state.
 
-use crate::{AppendableChain, BlockBuffer, BlockIndices};
+use crate::{
+    spilled::{estimated_state_size, SpilledStates},
+    AppendableChain, BlockBuffer, BlockIndices,
+};
+use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_primitives::{BlockHash, BlockNumber, Receipt, SealedBlock, SealedBlockWithSenders};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io,
+    path::PathBuf,
+};
+use tracing::{debug, error, warn};
 
 /// Container to hold the state of the blockchain tree.
 #[derive(Debug)]
@@ -10,7 +19,13 @@ pub(crate) struct TreeState {
     /// Keeps track of new unique identifiers for chains
     block_chain_id_generator: u64,
     /// The tracked chains and their current data.
+    ///
+    /// The state of chains in [`Self::spilled`] was written to disk, and the chains only hold
+    /// their blocks. Use [`Self::chain_mut`] and [`Self::take_chain`] to access chains with their
+    /// state.
     pub(crate) chains: HashMap<BlockchainId, AppendableChain>,
+    /// The state of chains that was spilled to disk to stay within the memory budget.
+    pub(crate) spilled: SpilledStates,
     /// Indices to block and their connection to the canonical chain.
     ///
     /// This gets modified by the tree itself and is read from engine API/RPC to access the pending
@@ -36,9 +51,15 @@ impl TreeState {
                 BTreeMap::from_iter(last_canonical_hashes),
             ),
             buffered_blocks: BlockBuffer::new(buffer_limit),
+            spilled: SpilledStates::new(std::env::temp_dir()),
         }
     }
 
+    /// Sets the directory the state of chains is spilled to.
+    pub(crate) fn set_spill_dir(&mut self, dir: PathBuf) {
+        self.spilled = SpilledStates::new(dir);
+    }
+
     /// Issues a new unique identifier for a new chain.
     #[inline]
     fn next_id(&mut self) -> BlockchainId {
@@ -76,10 +97,149 @@ impl TreeState {
     /// Returns the block's receipts with matching hash from any side-chain.
     ///
     /// Caution: This will not return blocks from the canonical chain.
-    pub(crate) fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<&Receipt>> {
+    pub(crate) fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<Receipt>> {
         let id = self.block_indices.get_block_chain_id(&block_hash)?;
-        let chain = self.chains.get(&id)?;
-        chain.receipts_by_block_hash(block_hash)
+        self.with_chain_state(id, |chain| {
+            Some(chain.receipts_by_block_hash(block_hash)?.into_iter().cloned().collect())
+        })?
+    }
+
+    /// Returns the state of the chain at the block with matching hash.
+    pub(crate) fn execution_outcome_at_block(
+        &self,
+        chain_id: BlockchainId,
+        block_hash: BlockHash,
+    ) -> Option<ExecutionOutcome> {
+        self.with_chain_state(chain_id, |chain| {
+            chain.execution_outcome_at_block(chain.block_number(block_hash)?)
+        })?
+    }
+
+    /// Calls the closure with the chain and its state, reading the state from disk if it was
+    /// spilled.
+    fn with_chain_state<R>(
+        &self,
+        chain_id: BlockchainId,
+        f: impl FnOnce(&Chain) -> R,
+    ) -> Option<R> {
+        let chain = self.chains.get(&chain_id)?;
+        match self.spilled.read(&chain_id) {
+            None => Some(f(chain)),
+            Some(Ok(state)) => Some(f(&state.into_chain(chain))),
+            Some(Err(err)) => {
+                warn!(target: "blockchain_tree", ?chain_id, %err, "Failed to read spilled chain state");
+                None
+            }
+        }
+    }
+
+    /// Returns the chain with its state, reading the state back into memory if it was spilled.
+    pub(crate) fn chain_mut(&mut self, chain_id: BlockchainId) -> Option<&mut AppendableChain> {
+        self.restore_state(chain_id);
+        self.chains.get_mut(&chain_id)
+    }
+
+    /// Removes the chain and returns it with its state, reading the state if it was spilled.
+    ///
+    /// This does not update the block indices.
+    pub(crate) fn take_chain(&mut self, chain_id: BlockchainId) -> Option<AppendableChain> {
+        self.restore_state(chain_id);
+        self.chains.remove(&chain_id)
+    }
+
+    /// Removes the chain and discards its state.
+    ///
+    /// The returned chain has no state if it was spilled, and should only be used to update the
+    /// block indices.
+    pub(crate) fn discard_chain(&mut self, chain_id: BlockchainId) -> Option<AppendableChain> {
+        self.spilled.remove(&chain_id);
+        self.chains.remove(&chain_id)
+    }
+
+    /// Clears the trie updates of the chain, whether its state is in memory or was spilled.
+    pub(crate) fn clear_trie_updates(&mut self, chain_id: BlockchainId) {
+        if let Some(chain) = self.chains.get_mut(&chain_id) {
+            chain.clear_trie_updates();
+        }
+        self.spilled.clear_trie_updates(&chain_id);
+    }
+
+    /// Reads the state of the chain back into memory if it was spilled.
+    ///
+    /// If the state can't be read, the chain and all chains forking off it are removed from the
+    /// tree.
+    pub(crate) fn restore_state(&mut self, chain_id: BlockchainId) {
+        let Some(state) = self.spilled.take(&chain_id) else { return };
+        match state {
+            Ok(state) => {
+                if let Some(chain) = self.chains.remove(&chain_id) {
+                    debug!(target: "blockchain_tree", ?chain_id, "Restoring spilled chain state");
+                    let chain = state.restore(chain.into_inner());
+                    self.chains.insert(chain_id, AppendableChain::new(chain));
+                }
+            }
+            Err(err) => {
+                error!(target: "blockchain_tree", ?chain_id, %err, "Failed to restore spilled chain state, removing chain");
+                let mut remove_chains = BTreeSet::from([chain_id]);
+                while let Some(chain_id) = remove_chains.pop_first() {
+                    if let Some(chain) = self.discard_chain(chain_id) {
+                        remove_chains.extend(self.block_indices.remove_chain(&chain));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Spills the state of the sidechains with the oldest tips to disk until the estimated memory
+    /// used by the state of the chains in memory is within the budget.
+    ///
+    /// The chain of the pending block is kept in memory.
+    pub(crate) fn enforce_memory_budget(&mut self, budget: usize) {
+        let mut in_memory = self
+            .chains
+            .iter()
+            .filter(|(chain_id, _)| !self.spilled.contains(*chain_id))
+            .map(|(chain_id, chain)| (*chain_id, chain.tip().number, estimated_state_size(chain)))
+            .collect::<Vec<_>>();
+        let mut size = in_memory.iter().map(|(_, _, size)| size).sum::<usize>();
+        if size <= budget {
+            return
+        }
+
+        let pending_chain_id = self
+            .block_indices
+            .pending_block_num_hash()
+            .and_then(|block| self.block_indices.get_block_chain_id(&block.hash));
+        in_memory.sort_unstable_by_key(|(chain_id, tip, _)| (*tip, *chain_id));
+        for (chain_id, _, chain_size) in in_memory {
+            if size <= budget {
+                break
+            }
+            if Some(chain_id) == pending_chain_id {
+                continue
+            }
+            if let Err(err) = self.spill_state(chain_id) {
+                warn!(target: "blockchain_tree", ?chain_id, %err, "Failed to spill chain state");
+                break
+            }
+            size -= chain_size;
+        }
+        debug!(target: "blockchain_tree", size, budget, spilled = self.spilled.len(), "Enforced memory budget");
+    }
+
+    /// Writes the state of the chain to disk, and only keeps its blocks in memory.
+    fn spill_state(&mut self, chain_id: BlockchainId) -> io::Result<()> {
+        let Some(chain) = self.chains.remove(&chain_id) else { return Ok(()) };
+        if let Err(err) = self.spilled.spill(chain_id, &chain) {
+            self.chains.insert(chain_id, chain);
+            return Err(err)
+        }
+
+        debug!(target: "blockchain_tree", ?chain_id, tip = ?chain.tip().num_hash(), "Spilled chain state");
+        let (blocks, _, _) = chain.into_inner().into_inner();
+        let chain = Chain::new(blocks.into_blocks(), ExecutionOutcome::default(), None);
+        self.chains.insert(chain_id, AppendableChain::new(chain));
+        Ok(())
     }
 
     /// Insert a chain into the tree.
@@ -65,6 +65,7 @@ pub static MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
         prune_delete_limit: 3500,
+        bootnodes: None,
     }
     .into()
 });
@@ -89,6 +90,7 @@ pub static GOERLI: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
         prune_delete_limit: 1700,
+        bootnodes: None,
     }
     .into()
 });
@@ -113,6 +115,7 @@ pub static SEPOLIA: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
         prune_delete_limit: 1700,
+        bootnodes: None,
     }
     .into()
 });
@@ -135,6 +138,7 @@ pub static HOLESKY: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
         prune_delete_limit: 1700,
+        bootnodes: None,
     }
     .into()
 });
@@ -342,6 +346,12 @@ pub struct ChainSpec {
     /// the amount of blocks between pruner runs to account for the difference in amount of new
     /// data coming in.
     pub prune_delete_limit: usize,
+
+    /// The bootnodes of the chain.
+    ///
+    /// If set, these are used instead of the known bootnodes of the named chain, e.g. for custom
+    /// testnets.
+    pub bootnodes: Option<Vec<NodeRecord>>,
 }
 
 impl Default for ChainSpec {
@@ -355,6 +365,7 @@ impl Default for ChainSpec {
             deposit_contract: Default::default(),
             base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
             prune_delete_limit: MAINNET.prune_delete_limit,
+            bootnodes: None,
         }
     }
 }
@@ -554,6 +565,34 @@ impl ChainSpec {
         }
     }
 
+    /// Checks that the activation blocks and timestamps of the hardforks don't decrease in the
+    /// order the hardforks were introduced, like geth does for custom chains.
+    pub fn validate_hardfork_order(&self) -> Result<(), HardforkOrderError> {
+        let mut last_block: Option<(&'static str, u64)> = None;
+        let mut last_timestamp: Option<(&'static str, u64)> = None;
+        for (hardfork, condition) in self.hardforks.forks_iter() {
+            let (last, activation) = match condition {
+                ForkCondition::Block(block) |
+                ForkCondition::TTD { fork_block: Some(block), .. } => (&mut last_block, block),
+                ForkCondition::Timestamp(timestamp) => (&mut last_timestamp, timestamp),
+                ForkCondition::TTD { fork_block: None, .. } | ForkCondition::Never => continue,
+            };
+
+            if let Some((previous, previous_activation)) = *last {
+                if activation < previous_activation {
+                    return Err(HardforkOrderError {
+                        hardfork: hardfork.name(),
+                        activation,
+                        previous,
+                        previous_activation,
+                    })
+                }
+            }
+            *last = Some((hardfork.name(), activation));
+        }
+        Ok(())
+    }
+
     /// Returns the hardfork display helper.
     pub fn display_hardforks(&self) -> DisplayHardforks {
         DisplayHardforks::new(
@@ -718,8 +757,13 @@ impl ChainSpec {
         ChainSpecBuilder::default()
     }
 
-    /// Returns the known bootnode records for the given chain.
+    /// Returns the bootnode records of the chain, or the known bootnode records of the named
+    /// chain if the chain spec doesn't configure any.
     pub fn bootnodes(&self) -> Option<Vec<NodeRecord>> {
+        if let Some(bootnodes) = &self.bootnodes {
+            return Some(bootnodes.clone())
+        }
+
         use NamedChain as C;
         let chain = self.chain;
         match chain.try_into().ok()? {
@@ -746,7 +790,10 @@ impl From<Genesis> for ChainSpec {
             (EthereumHardfork::Homestead.boxed(), genesis.config.homestead_block),
             (EthereumHardfork::Dao.boxed(), genesis.config.dao_fork_block),
             (EthereumHardfork::Tangerine.boxed(), genesis.config.eip150_block),
-            (EthereumHardfork::SpuriousDragon.boxed(), genesis.config.eip155_block),
+            (
+                EthereumHardfork::SpuriousDragon.boxed(),
+                genesis.config.eip155_block.or(genesis.config.eip158_block),
+            ),
             (EthereumHardfork::Byzantium.boxed(), genesis.config.byzantium_block),
             (EthereumHardfork::Constantinople.boxed(), genesis.config.constantinople_block),
             (EthereumHardfork::Petersburg.boxed(), genesis.config.petersburg_block),
@@ -767,15 +814,18 @@ impl From<Genesis> for ChainSpec {
         // Paris
         let paris_block_and_final_difficulty =
             if let Some(ttd) = genesis.config.terminal_total_difficulty {
+                // networks that start merged, like most geth devnets, reach the terminal total
+                // difficulty with the genesis block
+                let fork_block = genesis
+                    .config
+                    .merge_netsplit_block
+                    .or_else(|| (genesis.difficulty >= ttd).then_some(0));
                 hardforks.push((
                     EthereumHardfork::Paris.boxed(),
-                    ForkCondition::TTD {
-                        total_difficulty: ttd,
-                        fork_block: genesis.config.merge_netsplit_block,
-                    },
+                    ForkCondition::TTD { total_difficulty: ttd, fork_block },
                 ));
 
-                genesis.config.merge_netsplit_block.map(|block| (block, ttd))
+                fork_block.map(|block| (block, ttd))
             } else {
                 None
             };
@@ -817,9 +867,11 @@ impl From<Genesis> for ChainSpec {
         let mut ordered_hardforks = Vec::with_capacity(hardforks.len());
         for (hardfork, _) in mainnet_order {
             if let Some(pos) = hardforks.iter().position(|(e, _)| **e == *hardfork) {
-                ordered_hardforks.push(hardforks[pos].clone());
+                ordered_hardforks.push(hardforks.remove(pos));
             }
         }
+        // hardforks that aren't scheduled on mainnet yet follow in their genesis order
+        ordered_hardforks.extend(hardforks);
 
         // NOTE: in full node, we prune all receipts except the deposit contract's. We do not
         // have the deployment block in the genesis file, so we use block zero. We use the same
@@ -829,20 +881,79 @@ impl From<Genesis> for ChainSpec {
             DepositContract { address, block: 0, topic: MAINNET_DEPOSIT_CONTRACT.topic }
         });
 
+        let default = Self::default();
+        #[cfg(not(feature = "optimism"))]
+        let base_fee_params = genesis_base_fee_params(&genesis).unwrap_or(default.base_fee_params);
+        #[cfg(feature = "optimism")]
+        let base_fee_params = optimism_genesis_info.base_fee_params;
+        let prune_delete_limit = genesis
+            .config
+            .extra_fields
+            .get("pruneDeleteLimit")
+            .and_then(|limit| limit.as_u64())
+            .map_or(default.prune_delete_limit, |limit| limit as usize);
+
         Self {
             chain: genesis.config.chain_id.into(),
             genesis,
             genesis_hash: None,
-            hardforks: ChainHardforks::new(hardforks),
+            hardforks: ChainHardforks::new(ordered_hardforks),
             paris_block_and_final_difficulty,
             deposit_contract,
-            #[cfg(feature = "optimism")]
-            base_fee_params: optimism_genesis_info.base_fee_params,
-            ..Default::default()
+            base_fee_params,
+            prune_delete_limit,
+            bootnodes: None,
         }
     }
 }
 
+/// Returns the EIP-1559 base fee parameters of the optional `eip1559Elasticity` and
+/// `eip1559Denominator` fields of the genesis chain config.
+#[cfg(not(feature = "optimism"))]
+fn genesis_base_fee_params(genesis: &Genesis) -> Option<BaseFeeParamsKind> {
+    let field = |name| genesis.config.extra_fields.get(name).and_then(|value| value.as_u64());
+    let elasticity = field("eip1559Elasticity");
+    let denominator = field("eip1559Denominator");
+    if elasticity.is_none() && denominator.is_none() {
+        return None
+    }
+
+    let ethereum = BaseFeeParams::ethereum();
+    Some(
+        BaseFeeParams::new(
+            denominator.map_or(ethereum.max_change_denominator, u128::from),
+            elasticity.map_or(ethereum.elasticity_multiplier, u128::from),
+        )
+        .into(),
+    )
+}
+
+/// A hardfork that is activated before a hardfork that was introduced earlier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardforkOrderError {
+    /// The hardfork that is activated too early.
+    pub hardfork: &'static str,
+    /// The activation block or timestamp of the hardfork.
+    pub activation: u64,
+    /// The hardfork that was introduced earlier.
+    pub previous: &'static str,
+    /// The activation block or timestamp of the earlier hardfork.
+    pub previous_activation: u64,
+}
+
+impl core::fmt::Display for HardforkOrderError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "unsupported hardfork ordering: {} is activated at {}, before {} at {}",
+            self.hardfork, self.activation, self.previous, self.previous_activation
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HardforkOrderError {}
+
 /// A helper to build custom chain specs
 #[derive(Debug, Default, Clone)]
 pub struct ChainSpecBuilder {
@@ -2521,6 +2632,81 @@ Post-merge hard forks (timestamp based):
         assert_eq!(chainspec.paris_block_and_final_difficulty, Some((72, U256::from(9454784))));
     }
 
+    #[test]
+    fn test_geth_genesis_custom_hardfork_schedule() {
+        let s = r#"{
+            "config": {
+                "chainId": 1337,
+                "homesteadBlock": 0,
+                "eip150Block": 0,
+                "eip158Block": 0,
+                "byzantiumBlock": 0,
+                "constantinopleBlock": 0,
+                "petersburgBlock": 0,
+                "istanbulBlock": 0,
+                "berlinBlock": 0,
+                "londonBlock": 0,
+                "terminalTotalDifficulty": 0,
+                "terminalTotalDifficultyPassed": true,
+                "shanghaiTime": 0,
+                "cancunTime": 100,
+                "pragueTime": 200,
+                "eip1559Elasticity": 4,
+                "pruneDeleteLimit": 100
+            },
+            "difficulty": "0x0",
+            "gasLimit": "0x1c9c380",
+            "alloc": {}
+        }"#;
+        let genesis: Genesis = serde_json::from_str(s).unwrap();
+        let chainspec = ChainSpec::from(genesis);
+
+        assert_eq!(
+            chainspec.hardforks.get(EthereumHardfork::SpuriousDragon),
+            Some(ForkCondition::Block(0))
+        );
+        assert_eq!(
+            chainspec.hardforks.get(EthereumHardfork::Paris),
+            Some(ForkCondition::TTD { fork_block: Some(0), total_difficulty: U256::ZERO })
+        );
+        assert_eq!(chainspec.paris_block_and_final_difficulty, Some((0, U256::ZERO)));
+
+        // hardforks are ordered like on mainnet, followed by the unscheduled ones
+        let (last, condition) = chainspec.hardforks.last().unwrap();
+        assert_eq!(last.name(), EthereumHardfork::Prague.name());
+        assert_eq!(condition, ForkCondition::Timestamp(200));
+        assert!(chainspec.validate_hardfork_order().is_ok());
+
+        #[cfg(not(feature = "optimism"))]
+        assert_eq!(
+            chainspec.base_fee_params,
+            BaseFeeParamsKind::Constant(BaseFeeParams::new(8, 4))
+        );
+        assert_eq!(chainspec.prune_delete_limit, 100);
+    }
+
+    #[test]
+    fn test_validate_hardfork_order() {
+        let genesis = Genesis {
+            config: ChainConfig {
+                berlin_block: Some(10),
+                london_block: Some(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = ChainSpec::from(genesis).validate_hardfork_order().unwrap_err();
+        assert_eq!(
+            err,
+            HardforkOrderError {
+                hardfork: "London",
+                activation: 5,
+                previous: "Berlin",
+                previous_activation: 10
+            }
+        );
+    }
+
     #[test]
     fn test_parse_genesis_json() {
         let s = r#"{"config":{"ethash":{},"chainId":1337,"homesteadBlock":0,"eip150Block":0,"eip155Block":0,"eip158Block":0,"byzantiumBlock":0,"constantinopleBlock":0,"petersburgBlock":0,"istanbulBlock":0,"berlinBlock":0,"londonBlock":0,"terminalTotalDifficulty":0,"terminalTotalDifficultyPassed":true,"shanghaiTime":0},"nonce":"0x0","timestamp":"0x0","extraData":"0x","gasLimit":"0x4c4b40","difficulty":"0x1","mixHash":"0x0000000000000000000000000000000000000000000000000000000000000000","coinbase":"0x0000000000000000000000000000000000000000","alloc":{"658bdf435d810c91414ec09147daa6db62406379":{"balance":"0x487a9a304539440000"},"aa00000000000000000000000000000000000000":{"code":"0x6042","storage":{"0x0000000000000000000000000000000000000000000000000000000000000000":"0x0000000000000000000000000000000000000000000000000000000000000000","0x0100000000000000000000000000000000000000000000000000000000000000":"0x0100000000000000000000000000000000000000000000000000000000000000","0x0200000000000000000000000000000000000000000000000000000000000000":"0x0200000000000000000000000000000000000000000000000000000000000000","0x0300000000000000000000000000000000000000000000000000000000000000":"0x0000000000000000000000000000000000000000000000000000000000000303"},"balance":"0x1","nonce":"0x1"},"bb00000000000000000000000000000000000000":{"code":"0x600154600354","storage":{"0x0000000000000000000000000000000000000000000000000000000000000000":"0x0000000000000000000000000000000000000000000000000000000000000000","0x0100000000000000000000000000000000000000000000000000000000000000":"0x0100000000000000000000000000000000000000000000000000000000000000","0x0200000000000000000000000000000000000000000000000000000000000000":"0x0200000000000000000000000000000000000000000000000000000000000000","0x0300000000000000000000000000000000000000000000000000000000000000":"0x0000000000000000000000000000000000000000000000000000000000000303"},"balance":"0x2","nonce":"0x1"}},"number":"0x0","gasUsed":"0x0","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","baseFeePerGas":"0x1337"}"#;
@@ -2676,6 +2862,15 @@ Post-merge hard forks (timestamp based):
         assert_eq!(genesis, deserialized_genesis);
     }
 
+    #[test]
+    fn custom_bootnodes() {
+        assert_eq!(HOLESKY.bootnodes(), Some(holesky_nodes()));
+
+        let bootnodes = sepolia_nodes();
+        let spec = ChainSpec { bootnodes: Some(bootnodes.clone()), ..(**HOLESKY).clone() };
+        assert_eq!(spec.bootnodes(), Some(bootnodes));
+    }
+
     #[test]
     fn check_fork_id_chainspec_with_fork_condition_never() {
         let spec = ChainSpec {
@@ -1,11 +1,16 @@
 //! Configuration files.
 
 use reth_network_types::{PeersConfig, SessionsConfig};
-use reth_prune_types::PruneModes;
+use reth_prune_types::{PruneMode, PruneModes, PruneSchedule};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
+    collections::BTreeMap,
     ffi::OsStr,
+    fmt,
+    net::IpAddr,
+    num::NonZeroU32,
     path::{Path, PathBuf},
+    str::FromStr,
     time::Duration,
 };
 
@@ -25,6 +30,10 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for the execution extensions.
+    pub exex: ExExConfig,
+    /// Configuration for the RPC servers.
+    pub rpc: RpcConfig,
 }
 
 impl Config {
@@ -254,11 +263,16 @@ impl Default for MerkleConfig {
 pub struct TransactionLookupConfig {
     /// The maximum number of transactions to process before writing to disk.
     pub chunk_size: u64,
+    /// The number of shards, by hash prefix, the transaction lookup index is written in. Each
+    /// shard is committed to the database separately.
+    ///
+    /// Values are clamped to the range `1..=256`.
+    pub shards: usize,
 }
 
 impl Default for TransactionLookupConfig {
     fn default() -> Self {
-        Self { chunk_size: 5_000_000 }
+        Self { chunk_size: 5_000_000, shards: 1 }
     }
 }
 
@@ -319,11 +333,22 @@ pub struct PruneConfig {
     /// Pruning configuration for every part of the data that can be pruned.
     #[serde(alias = "parts")]
     pub segments: PruneModes,
+    /// Per-segment delete limits, chunking and time windows of pruner runs.
+    pub schedule: PruneSchedule,
+    /// Retention boundary of headers, transactions and receipts static files. Whole static files
+    /// below the boundary are deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_expiry: Option<PruneMode>,
 }
 
 impl Default for PruneConfig {
     fn default() -> Self {
-        Self { block_interval: 5, segments: PruneModes::none() }
+        Self {
+            block_interval: 5,
+            segments: PruneModes::none(),
+            schedule: PruneSchedule::default(),
+            history_expiry: None,
+        }
     }
 }
 
@@ -334,6 +359,401 @@ impl PruneConfig {
     }
 }
 
+/// Configuration of the execution extensions (`ExEx`'s).
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct ExExConfig {
+    /// Maximum number of notifications the `ExEx` manager buffers in memory.
+    pub max_buffered_notifications: usize,
+    /// Number of blocks an `ExEx` can fall behind the tip of the latest notification before the
+    /// [lag policy](Self::lag_policy) applies. If not set, the lag policy only applies once the
+    /// notification buffer is full.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lag_blocks: Option<u64>,
+    /// What the node does when an `ExEx` falls too far behind.
+    pub lag_policy: ExExLagPolicy,
+}
+
+impl Default for ExExConfig {
+    fn default() -> Self {
+        Self {
+            max_buffered_notifications: 1024,
+            max_lag_blocks: None,
+            lag_policy: Default::default(),
+        }
+    }
+}
+
+/// What the node does when an `ExEx` falls too far behind, see [`ExExConfig`].
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExExLagPolicy {
+    /// Stop accepting new notifications until the `ExEx` catches up, which pauses the pipeline
+    /// and the engine.
+    #[default]
+    Pause,
+    /// Keep accepting new notifications. Notifications that don't fit into the in-memory buffer
+    /// are only kept in the write-ahead log, and loaded again when the `ExEx` catches up.
+    Buffer,
+    /// Stop sending notifications to the `ExEx` and ignore it for pruning, logging an error.
+    Detach,
+}
+
+/// Configuration of the RPC servers.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct RpcConfig {
+    /// Rate limits of individual RPC methods, keyed by the method name, e.g.
+    /// `eth_getLogs = "5/s"`.
+    ///
+    /// Calls that exceed the quota of their method are rejected, methods without a quota are not
+    /// limited.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub method_rate_limits: BTreeMap<String, RateQuota>,
+    /// Costs of individual RPC methods in batch requests, keyed by the method name, e.g.
+    /// `eth_getLogs = 20`.
+    ///
+    /// Only used if the cost of batch requests is limited with `--rpc.batch-cost-budget`. Calls
+    /// cost 1, except for a set of expensive methods like `eth_call` that cost 10 by default.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub method_costs: BTreeMap<String, u64>,
+    /// Rate limit of the requests of individual clients of the http and ws servers, disabled if
+    /// not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_rate_limit: Option<IpRateLimitConfig>,
+    /// API keys authenticating the clients of the http and ws servers, keyed by the name of the
+    /// key.
+    ///
+    /// If any key is configured, requests without a valid key are rejected.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub api_keys: BTreeMap<String, ApiKeyConfig>,
+    /// Reverse proxies or load balancers in front of the http and ws servers, whose client
+    /// addresses are used instead of the address of their connections, e.g. by the
+    /// [`ip_rate_limit`](Self::ip_rate_limit).
+    ///
+    /// Headers with client addresses are only read from requests of the trusted proxies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+    /// When the `/ready` endpoint of the http server reports the node as ready.
+    pub health: HealthConfig,
+}
+
+/// Readiness criteria of the `/ready` endpoint of the http server, see [`RpcConfig`].
+///
+/// The node is never ready while it is syncing or its database is not accessible.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Minimum number of connected peers.
+    pub min_peers: usize,
+    /// Maximum age of the latest block, e.g. `1m`, not checked if not set.
+    #[serde(
+        serialize_with = "humantime_serde::serialize",
+        deserialize_with = "deserialize_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_block_age: Option<Duration>,
+}
+
+/// Trusted reverse proxies of the http and ws servers, see [`RpcConfig`].
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// The addresses or networks of the trusted proxies, e.g. `10.0.0.1` or `10.0.0.0/8`.
+    ///
+    /// The client address sent by other peers is ignored.
+    pub trusted_proxies: Vec<IpNetwork>,
+    /// Name of the header the proxies send the client address in.
+    ///
+    /// If the header contains a list of addresses, the last address that isn't a trusted proxy
+    /// is used, so that chains of trusted proxies are supported.
+    pub header: String,
+    /// Whether the proxies send a [PROXY protocol v2](https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt)
+    /// header at the start of every connection, e.g. TCP load balancers.
+    ///
+    /// If enabled, connections of trusted proxies without a valid header are closed.
+    pub proxy_protocol: bool,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            trusted_proxies: Vec::new(),
+            header: "X-Forwarded-For".to_string(),
+            proxy_protocol: false,
+        }
+    }
+}
+
+/// An API key of the http and ws servers, see [`RpcConfig`].
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ApiKeyConfig {
+    /// The secret of the key.
+    pub secret: String,
+    /// How clients authenticate with the secret.
+    #[serde(default)]
+    pub kind: ApiKeyKind,
+    /// The methods the key is allowed to call, e.g. `eth_getLogs`, or `eth_*` for all methods of
+    /// a namespace. All methods are allowed if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+}
+
+/// How clients authenticate with an API key, see [`ApiKeyConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyKind {
+    /// Clients send the secret itself.
+    #[default]
+    Static,
+    /// Clients send tokens of the form `<name>.<expiry>.<signature>`, where the expiry is a unix
+    /// timestamp in seconds and the signature is the hex encoded HMAC-SHA256 of `<name>.<expiry>`
+    /// with the secret as key.
+    Hmac,
+}
+
+/// Rate limit of the requests of individual client IP addresses, see [`RpcConfig`].
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IpRateLimitConfig {
+    /// The quota of every client IP address.
+    pub quota: RateQuota,
+    /// Length of the prefix that identifies IPv6 clients, e.g. `64` for all addresses of a `/64`
+    /// network.
+    ///
+    /// Hosts are usually assigned a whole `/64` network, so limiting full IPv6 addresses would
+    /// let clients evade their quota by switching addresses.
+    pub ipv6_prefix_len: u8,
+    /// Number of consecutively rejected requests after which a client is banned. Bans are
+    /// disabled if zero.
+    pub ban_threshold: u32,
+    /// How long a client is banned, during which all of its requests are rejected.
+    #[serde(with = "humantime_serde")]
+    pub ban_duration: Duration,
+}
+
+impl Default for IpRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            quota: RateQuota::per_second(NonZeroU32::new(100).expect("not zero")),
+            ipv6_prefix_len: 64,
+            ban_threshold: 100,
+            // 5 minutes
+            ban_duration: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// A number of requests that are allowed per period.
+///
+/// Written as `<requests>/<period>`, where the period is either `s`, `m` or `h`, or a duration
+/// like `10s` or `500ms`, e.g. `5/s` or `100/10m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RateQuota {
+    requests: NonZeroU32,
+    period: Duration,
+}
+
+impl RateQuota {
+    /// Creates a new quota of `requests` per `period`.
+    ///
+    /// # Panics
+    ///
+    /// If the period is zero.
+    pub fn new(requests: NonZeroU32, period: Duration) -> Self {
+        assert!(!period.is_zero(), "rate quota period must not be zero");
+        Self { requests, period }
+    }
+
+    /// Creates a new quota of `requests` per second.
+    pub const fn per_second(requests: NonZeroU32) -> Self {
+        Self { requests, period: Duration::from_secs(1) }
+    }
+
+    /// Returns the number of requests that are allowed per period.
+    pub const fn requests(&self) -> NonZeroU32 {
+        self.requests
+    }
+
+    /// Returns the period of the quota.
+    pub const fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+impl fmt::Display for RateQuota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.period.as_secs() {
+            1 if self.period.subsec_nanos() == 0 => write!(f, "{}/s", self.requests),
+            60 if self.period.subsec_nanos() == 0 => write!(f, "{}/m", self.requests),
+            3600 if self.period.subsec_nanos() == 0 => write!(f, "{}/h", self.requests),
+            _ => write!(f, "{}/{}", self.requests, humantime::format_duration(self.period)),
+        }
+    }
+}
+
+impl FromStr for RateQuota {
+    type Err = ParseRateQuotaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseRateQuotaError(s.to_string());
+
+        let (requests, period) = s.split_once('/').ok_or_else(err)?;
+        let requests = requests.trim().parse::<NonZeroU32>().map_err(|_| err())?;
+        let period = match period.trim() {
+            "s" => Duration::from_secs(1),
+            "m" => Duration::from_secs(60),
+            "h" => Duration::from_secs(60 * 60),
+            period => humantime::parse_duration(period).map_err(|_| err())?,
+        };
+        if period.is_zero() {
+            return Err(err())
+        }
+
+        Ok(Self { requests, period })
+    }
+}
+
+impl TryFrom<String> for RateQuota {
+    type Error = ParseRateQuotaError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RateQuota> for String {
+    fn from(quota: RateQuota) -> Self {
+        quota.to_string()
+    }
+}
+
+/// Error returned when parsing an invalid [`RateQuota`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRateQuotaError(String);
+
+impl fmt::Display for ParseRateQuotaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rate quota `{}`, expected `<requests>/<period>`, e.g. `5/s`", self.0)
+    }
+}
+
+impl std::error::Error for ParseRateQuotaError {}
+
+/// An IP address or a network of IP addresses.
+///
+/// Written as an address, e.g. `10.0.0.1`, or in CIDR notation, e.g. `10.0.0.0/8` or
+/// `fd00::/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// Creates a new network of the addresses that share the first `prefix_len` bits with
+    /// `addr`.
+    ///
+    /// Returns `None` if the prefix is longer than the address.
+    pub const fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max_prefix_len {
+            return None
+        }
+        Some(Self { addr, prefix_len })
+    }
+
+    /// Returns the address of the network.
+    pub const fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the length of the prefix of the network.
+    pub const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns `true` if the address is part of the network.
+    ///
+    /// IPv4-mapped IPv6 addresses, e.g. `::ffff:10.0.0.1`, are matched as IPv4 addresses.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            ip => ip,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<IpAddr> for IpNetwork {
+    fn from(addr: IpAddr) -> Self {
+        Self { addr, prefix_len: if addr.is_ipv4() { 32 } else { 128 } }
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Self::from(self.addr) {
+            write!(f, "{}", self.addr)
+        } else {
+            write!(f, "{}/{}", self.addr, self.prefix_len)
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = ParseIpNetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseIpNetworkError(s.to_string());
+
+        let Some((addr, prefix_len)) = s.split_once('/') else {
+            return s.trim().parse::<IpAddr>().map(Self::from).map_err(|_| err())
+        };
+        let addr = addr.trim().parse::<IpAddr>().map_err(|_| err())?;
+        let prefix_len = prefix_len.trim().parse::<u8>().map_err(|_| err())?;
+        Self::new(addr, prefix_len).ok_or_else(err)
+    }
+}
+
+impl TryFrom<String> for IpNetwork {
+    type Error = ParseIpNetworkError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<IpNetwork> for String {
+    fn from(network: IpNetwork) -> Self {
+        network.to_string()
+    }
+}
+
+/// Error returned when parsing an invalid [`IpNetwork`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIpNetworkError(String);
+
+impl fmt::Display for ParseIpNetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid IP network `{}`, expected e.g. `10.0.0.1` or `10.0.0.0/8`", self.0)
+    }
+}
+
+impl std::error::Error for ParseIpNetworkError {}
+
 /// Helper type to support older versions of Duration deserialization.
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
@@ -354,7 +774,12 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, EXTENSION};
+    use super::{
+        ApiKeyConfig, ApiKeyKind, Config, ExExConfig, ExExLagPolicy, HealthConfig, IpNetwork,
+        IpRateLimitConfig, ProxyConfig, PruneConfig, RateQuota, EXTENSION,
+    };
+    use alloy_primitives::{Address, B256};
+    use reth_prune_types::{PruneMode, ReceiptsLogPruneConfig, ReceiptsLogPruneRule};
     use std::time::Duration;
 
     fn with_tempdir(filename: &str, proc: fn(&std::path::Path)) {
@@ -405,6 +830,220 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_load_receipts_log_filter() {
+        with_tempdir("config-load-test", |config_path| {
+            let mut prune_config = PruneConfig::default();
+            prune_config.segments.receipts_log_filter = ReceiptsLogPruneConfig(vec![
+                ReceiptsLogPruneRule::address(Address::with_last_byte(1), PruneMode::Before(100)),
+                ReceiptsLogPruneRule {
+                    address: None,
+                    topic0: Some(B256::with_last_byte(2)),
+                    ..ReceiptsLogPruneRule::address(Address::ZERO, PruneMode::Distance(20000))
+                },
+            ]);
+            let config = Config { prune: Some(prune_config), ..Default::default() };
+            confy::store_path(config_path, &config).unwrap();
+
+            let loaded_config: Config = confy::load_path(config_path).unwrap();
+            assert_eq!(config, loaded_config);
+        })
+    }
+
+    #[test]
+    fn test_exex_config() {
+        let exex = r#"
+[exex]
+max_lag_blocks = 64
+lag_policy = "detach"
+"#;
+        let conf: Config = toml::from_str(exex).unwrap();
+        assert_eq!(
+            conf.exex,
+            ExExConfig {
+                max_buffered_notifications: 1024,
+                max_lag_blocks: Some(64),
+                lag_policy: ExExLagPolicy::Detach,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rpc_config() {
+        let rpc = r#"
+[rpc.method_rate_limits]
+eth_getLogs = "5/s"
+debug_traceTransaction = "1/m"
+eth_call = "100/500ms"
+"#;
+        let conf: Config = toml::from_str(rpc).unwrap();
+        let limits = &conf.rpc.method_rate_limits;
+        assert_eq!(limits["eth_getLogs"], RateQuota::per_second(5.try_into().unwrap()));
+        assert_eq!(
+            limits["debug_traceTransaction"],
+            RateQuota::new(1.try_into().unwrap(), Duration::from_secs(60))
+        );
+        assert_eq!(
+            limits["eth_call"],
+            RateQuota::new(100.try_into().unwrap(), Duration::from_millis(500))
+        );
+
+        let serialized = toml::to_string(&conf).unwrap();
+        assert!(serialized.contains(r#"eth_getLogs = "5/s""#));
+        assert!(serialized.contains(r#"eth_call = "100/500ms""#));
+        assert_eq!(toml::from_str::<Config>(&serialized).unwrap(), conf);
+
+        for invalid in ["5", "0/s", "5/0s", "five/s", "5/fortnight"] {
+            assert!(invalid.parse::<RateQuota>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_rpc_method_costs_config() {
+        let rpc = r#"
+[rpc.method_costs]
+eth_getLogs = 20
+eth_call = 5
+"#;
+        let conf: Config = toml::from_str(rpc).unwrap();
+        assert_eq!(conf.rpc.method_costs["eth_getLogs"], 20);
+        assert_eq!(conf.rpc.method_costs["eth_call"], 5);
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&conf).unwrap()).unwrap(), conf);
+    }
+
+    #[test]
+    fn test_rpc_ip_rate_limit_config() {
+        let conf: Config = toml::from_str("").unwrap();
+        assert_eq!(conf.rpc.ip_rate_limit, None);
+
+        let conf: Config = toml::from_str("[rpc.ip_rate_limit]").unwrap();
+        assert_eq!(conf.rpc.ip_rate_limit, Some(IpRateLimitConfig::default()));
+
+        let rpc = r#"
+[rpc.ip_rate_limit]
+quota = "20/s"
+ipv6_prefix_len = 56
+ban_threshold = 10
+ban_duration = "1h"
+"#;
+        let conf: Config = toml::from_str(rpc).unwrap();
+        assert_eq!(
+            conf.rpc.ip_rate_limit,
+            Some(IpRateLimitConfig {
+                quota: RateQuota::per_second(20.try_into().unwrap()),
+                ipv6_prefix_len: 56,
+                ban_threshold: 10,
+                ban_duration: Duration::from_secs(60 * 60),
+            })
+        );
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&conf).unwrap()).unwrap(), conf);
+    }
+
+    #[test]
+    fn test_rpc_api_keys_config() {
+        let rpc = r#"
+[rpc.api_keys.wallet]
+secret = "wallet-secret"
+
+[rpc.api_keys.indexer]
+secret = "indexer-secret"
+kind = "hmac"
+methods = ["eth_getLogs", "debug_*"]
+"#;
+        let conf: Config = toml::from_str(rpc).unwrap();
+        assert_eq!(
+            conf.rpc.api_keys["wallet"],
+            ApiKeyConfig {
+                secret: "wallet-secret".to_string(),
+                kind: ApiKeyKind::Static,
+                methods: vec![],
+            }
+        );
+        assert_eq!(
+            conf.rpc.api_keys["indexer"],
+            ApiKeyConfig {
+                secret: "indexer-secret".to_string(),
+                kind: ApiKeyKind::Hmac,
+                methods: vec!["eth_getLogs".to_string(), "debug_*".to_string()],
+            }
+        );
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&conf).unwrap()).unwrap(), conf);
+    }
+
+    #[test]
+    fn test_rpc_proxy_config() {
+        let conf: Config = toml::from_str("").unwrap();
+        assert_eq!(conf.rpc.proxy, None);
+
+        let rpc = r#"
+[rpc.proxy]
+trusted_proxies = ["10.0.0.0/8", "192.0.2.1", "fd00::/8"]
+proxy_protocol = true
+"#;
+        let conf: Config = toml::from_str(rpc).unwrap();
+        assert_eq!(
+            conf.rpc.proxy,
+            Some(ProxyConfig {
+                trusted_proxies: vec![
+                    "10.0.0.0/8".parse().unwrap(),
+                    "192.0.2.1".parse().unwrap(),
+                    "fd00::/8".parse().unwrap(),
+                ],
+                header: "X-Forwarded-For".to_string(),
+                proxy_protocol: true,
+            })
+        );
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&conf).unwrap()).unwrap(), conf);
+
+        assert!(
+            toml::from_str::<Config>("[rpc.proxy]\ntrusted_proxies = [\"10.0.0.0/33\"]").is_err()
+        );
+    }
+
+    #[test]
+    fn test_rpc_health_config() {
+        let conf: Config = toml::from_str("").unwrap();
+        assert_eq!(conf.rpc.health, HealthConfig::default());
+
+        let rpc = r#"
+[rpc.health]
+min_peers = 3
+max_block_age = "1m"
+"#;
+        let conf: Config = toml::from_str(rpc).unwrap();
+        assert_eq!(
+            conf.rpc.health,
+            HealthConfig { min_peers: 3, max_block_age: Some(Duration::from_secs(60)) }
+        );
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&conf).unwrap()).unwrap(), conf);
+    }
+
+    #[test]
+    fn test_ip_network() {
+        let network: IpNetwork = "10.0.0.0/8".parse().unwrap();
+        assert!(network.contains([10, 1, 2, 3].into()));
+        assert!(!network.contains([11, 0, 0, 1].into()));
+        assert!(network.contains("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(!network.contains("fd00::1".parse().unwrap()));
+        assert_eq!(network.to_string(), "10.0.0.0/8");
+
+        let address: IpNetwork = "192.0.2.1".parse().unwrap();
+        assert!(address.contains([192, 0, 2, 1].into()));
+        assert!(!address.contains([192, 0, 2, 2].into()));
+        assert_eq!(address.to_string(), "192.0.2.1");
+
+        let network: IpNetwork = "fd00::/8".parse().unwrap();
+        assert!(network.contains("fd12::1".parse().unwrap()));
+        assert!(!network.contains("fe80::1".parse().unwrap()));
+
+        let any: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains([203, 0, 113, 7].into()));
+
+        for invalid in ["10.0.0.0/33", "fd00::/129", "10.0.0.0/", "localhost"] {
+            assert!(invalid.parse::<IpNetwork>().is_err(), "{invalid}");
+        }
+    }
+
     // ensures config deserialization is backwards compatible
     #[test]
     fn test_backwards_compatibility() {
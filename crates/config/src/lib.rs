@@ -9,4 +9,10 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod config;
-pub use config::{BodiesConfig, Config, PruneConfig};
+pub use config::{
+    ApiKeyConfig, ApiKeyKind, BodiesConfig, Config, HealthConfig, IpNetwork, IpRateLimitConfig,
+    ProxyConfig, PruneConfig, RateQuota, RpcConfig,
+};
+
+pub mod reload;
+pub use reload::{ConfigReloadReport, ConfigReloader};
@@ -0,0 +1,130 @@
+//! Support for reloading the configuration file at runtime.
+
+use crate::Config;
+use reth_network_types::PeersConfig;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+/// Settings that are applied when the configuration file is reloaded at runtime. Changes to all
+/// other settings only take effect after a restart.
+pub const RUNTIME_SETTINGS: [&str; 3] =
+    ["peers.connection_info", "prune.block_interval", "prune.schedule"];
+
+impl Config {
+    /// Returns the settings that differ from the other configuration.
+    ///
+    /// Settings are named after their section, e.g. `peers.connection_info`. Sections that are
+    /// only compared as a whole, like `stages`, are named after the section.
+    pub fn changed_settings(&self, other: &Self) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+
+        if self.stages != other.stages {
+            changed.push("stages");
+        }
+
+        match (&self.prune, &other.prune) {
+            (Some(prune), Some(other_prune)) => {
+                if prune.block_interval != other_prune.block_interval {
+                    changed.push("prune.block_interval");
+                }
+                if prune.segments != other_prune.segments {
+                    changed.push("prune.segments");
+                }
+                if prune.schedule != other_prune.schedule {
+                    changed.push("prune.schedule");
+                }
+                if prune.history_expiry != other_prune.history_expiry {
+                    changed.push("prune.history_expiry");
+                }
+            }
+            (None, None) => {}
+            _ => changed.push("prune"),
+        }
+
+        if self.peers.connection_info != other.peers.connection_info {
+            changed.push("peers.connection_info");
+        }
+        let peers = PeersConfig {
+            connection_info: other.peers.connection_info.clone(),
+            ..self.peers.clone()
+        };
+        if peers != other.peers {
+            changed.push("peers");
+        }
+
+        if self.sessions != other.sessions {
+            changed.push("sessions");
+        }
+        if self.exex != other.exex {
+            changed.push("exex");
+        }
+        if self.rpc != other.rpc {
+            changed.push("rpc");
+        }
+
+        changed
+    }
+}
+
+/// Report of a reload of the configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadReport {
+    /// The changed settings that were applied.
+    pub applied: Vec<String>,
+    /// The changed settings that only take effect after a restart.
+    pub requires_restart: Vec<String>,
+}
+
+impl ConfigReloadReport {
+    /// Creates the report of the changes from the current to the reloaded configuration.
+    pub fn new(current: &Config, reloaded: &Config) -> Self {
+        let (applied, requires_restart): (Vec<_>, Vec<_>) = current
+            .changed_settings(reloaded)
+            .into_iter()
+            .partition(|setting| RUNTIME_SETTINGS.contains(setting));
+
+        Self {
+            applied: applied.into_iter().map(String::from).collect(),
+            requires_restart: requires_restart.into_iter().map(String::from).collect(),
+        }
+    }
+
+    /// Returns `true` if no setting was changed.
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.requires_restart.is_empty()
+    }
+}
+
+/// Reloads the configuration file of a running node.
+pub trait ConfigReloader: fmt::Debug + Send + Sync + 'static {
+    /// Re-reads the configuration file and applies the changed settings that are safe to change at
+    /// runtime.
+    fn reload_config(&self) -> Result<ConfigReloadReport, Box<dyn Error + Send + Sync>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PruneConfig;
+
+    #[test]
+    fn config_reload_report() {
+        let current = Config { prune: Some(PruneConfig::default()), ..Default::default() };
+        assert!(ConfigReloadReport::new(&current, &current).is_empty());
+
+        let mut reloaded = current.clone();
+        reloaded.peers.connection_info.max_inbound += 1;
+        reloaded.peers.trusted_nodes_only = true;
+        reloaded.prune.as_mut().unwrap().block_interval += 1;
+        reloaded.stages.bodies.downloader_max_buffered_blocks_size_bytes += 1;
+
+        let report = ConfigReloadReport::new(&current, &reloaded);
+        assert_eq!(report.applied, vec!["prune.block_interval", "peers.connection_info"]);
+        assert_eq!(report.requires_restart, vec!["stages", "peers"]);
+
+        let reloaded = Config::default();
+        let report = ConfigReloadReport::new(&current, &reloaded);
+        assert_eq!(report.requires_restart, vec!["prune"]);
+    }
+}
@@ -44,7 +44,7 @@ mod task;
 pub use crate::client::AutoSealClient;
 pub use mode::{FixedBlockTimeMiner, MiningMode, ReadyTransactionMiner};
 use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
-pub use task::MiningTask;
+pub use task::{InvalidNextBlockTimestamp, MiningHandle, MiningTask};
 
 /// A consensus implementation intended for local development and testing purposes.
 #[derive(Debug, Clone)]
@@ -217,6 +217,8 @@ pub(crate) struct StorageInner {
     pub(crate) best_hash: B256,
     /// The total difficulty of the chain until this block
     pub(crate) total_difficulty: U256,
+    /// The timestamp of the next block, if set via [`MiningHandle::set_next_block_timestamp`]
+    pub(crate) next_timestamp: Option<u64>,
 }
 
 // === impl StorageInner ===
@@ -346,7 +348,11 @@ impl StorageInner {
         Executor: BlockExecutorProvider,
         Provider: StateProviderFactory,
     {
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let timestamp = self.next_timestamp.unwrap_or_else(|| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            // the timestamp can't go backwards if the timestamp of a previous block was set
+            self.headers.get(&self.best_block).map_or(now, |parent| now.max(parent.timestamp))
+        });
 
         // if shanghai is active, include empty withdrawals
         let withdrawals =
@@ -432,6 +438,7 @@ impl StorageInner {
 
         // finally insert into storage
         self.insert_new_block(header.clone(), body);
+        self.next_timestamp = None;
 
         // set new header with hash that should have been updated by insert_new_block
         let new_header = header.seal(self.best_hash);
@@ -4,7 +4,7 @@ use reth_beacon_consensus::{BeaconEngineMessage, ForkchoiceStatus};
 use reth_chainspec::ChainSpec;
 use reth_engine_primitives::EngineTypes;
 use reth_evm::execute::BlockExecutorProvider;
-use reth_primitives::IntoRecoveredTransaction;
+use reth_primitives::{IntoRecoveredTransaction, SealedHeader};
 use reth_provider::{CanonChainTracker, StateProviderFactory};
 use reth_rpc_types::engine::ForkchoiceState;
 use reth_stages_api::PipelineEvent;
@@ -12,12 +12,16 @@ use reth_tokio_util::EventStream;
 use reth_transaction_pool::{TransactionPool, ValidPoolTransaction};
 use std::{
     collections::VecDeque,
+    fmt,
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
 };
-use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
 use tracing::{debug, error, warn};
 
 /// A Future that listens for new ready transactions and puts new blocks into storage
@@ -34,8 +38,17 @@ pub struct MiningTask<Client, Pool: TransactionPool, Executor, Engine: EngineTyp
     storage: Storage,
     /// Pool where transactions are stored
     pool: Pool,
-    /// backlog of sets of transactions ready to be mined
-    queued: VecDeque<Vec<Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>>,
+    /// backlog of sets of transactions ready to be mined, and the senders of the mined blocks
+    /// requested via the [`MiningHandle`]
+    #[allow(clippy::type_complexity)]
+    queued: VecDeque<(
+        Vec<Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>,
+        Option<oneshot::Sender<SealedHeader>>,
+    )>,
+    /// Sender of the blocks requested via the [`MiningHandle`]
+    to_mine: UnboundedSender<oneshot::Sender<SealedHeader>>,
+    /// Receiver of the blocks requested via the [`MiningHandle`]
+    mine_requests: UnboundedReceiver<oneshot::Sender<SealedHeader>>,
     // TODO: ideally this would just be a sender of hashes
     to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
     /// The pipeline events to listen on
@@ -60,6 +73,7 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
         pool: Pool,
         block_executor: Executor,
     ) -> Self {
+        let (to_mine, mine_requests) = unbounded_channel();
         Self {
             chain_spec,
             client,
@@ -69,6 +83,8 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
             pool,
             to_engine,
             queued: Default::default(),
+            to_mine,
+            mine_requests,
             pipe_line_events: None,
             block_executor,
         }
@@ -78,6 +94,11 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
     pub fn set_pipeline_events(&mut self, events: EventStream<PipelineEvent>) {
         self.pipe_line_events = Some(events);
     }
+
+    /// Returns a handle to mine blocks on demand and to set the timestamp of the next block.
+    pub fn handle(&self) -> MiningHandle {
+        MiningHandle { storage: self.storage.clone(), to_mine: self.to_mine.clone() }
+    }
 }
 
 impl<Executor, Client, Pool, Engine> Future for MiningTask<Client, Pool, Executor, Engine>
@@ -97,7 +118,13 @@ where
         loop {
             if let Poll::Ready(transactions) = this.miner.poll(&this.pool, cx) {
                 // miner returned a set of transaction that we feed to the producer
-                this.queued.push_back(transactions);
+                this.queued.push_back((transactions, None));
+            }
+
+            while let Poll::Ready(Some(tx)) = this.mine_requests.poll_recv(cx) {
+                // the transactions of requested blocks are taken from the pool when the block is
+                // built, so they are not mined twice
+                this.queued.push_back((Vec::new(), Some(tx)));
             }
 
             if this.insert_task.is_none() {
@@ -108,7 +135,11 @@ where
 
                 // ready to queue in new insert task
                 let storage = this.storage.clone();
-                let transactions = this.queued.pop_front().expect("not empty");
+                let (mut transactions, mined_block_tx) =
+                    this.queued.pop_front().expect("not empty");
+                if mined_block_tx.is_some() {
+                    transactions = this.pool.best_transactions().collect();
+                }
 
                 let to_engine = this.to_engine.clone();
                 let client = this.client.clone();
@@ -190,6 +221,10 @@ where
                             client.set_canonical_head(new_header.clone());
                             client.set_safe(new_header.clone());
                             client.set_finalized(new_header.clone());
+
+                            if let Some(tx) = mined_block_tx {
+                                let _ = tx.send(new_header);
+                            }
                         }
                         Err(err) => {
                             warn!(target: "consensus::auto", %err, "failed to execute block")
@@ -224,3 +259,69 @@ impl<Client, Pool: TransactionPool, EvmConfig: std::fmt::Debug, Engine: EngineTy
         f.debug_struct("MiningTask").finish_non_exhaustive()
     }
 }
+
+/// A handle to the [`MiningTask`], to mine blocks on demand and to set the timestamp of the next
+/// block.
+#[derive(Debug, Clone)]
+pub struct MiningHandle {
+    /// Shared storage of the mining task
+    storage: Storage,
+    /// Sender of the blocks requested from the mining task
+    to_mine: UnboundedSender<oneshot::Sender<SealedHeader>>,
+}
+
+// === impl MiningHandle ===
+
+impl MiningHandle {
+    /// Mines a new block with all ready transactions of the pool, even if there are none.
+    ///
+    /// Returns the header of the block once it is canonical, or `None` if the block could not be
+    /// mined.
+    pub async fn mine(&self) -> Option<SealedHeader> {
+        let (tx, rx) = oneshot::channel();
+        self.to_mine.send(tx).ok()?;
+        rx.await.ok()
+    }
+
+    /// Sets the timestamp of the next mined block, which must be greater than the timestamp of the
+    /// best block.
+    pub async fn set_next_block_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> Result<(), InvalidNextBlockTimestamp> {
+        let mut storage = self.storage.write().await;
+        let parent_timestamp = storage
+            .headers
+            .get(&storage.best_block)
+            .map(|parent| parent.timestamp)
+            .unwrap_or_default();
+        if timestamp <= parent_timestamp {
+            return Err(InvalidNextBlockTimestamp { timestamp, parent_timestamp })
+        }
+
+        storage.next_timestamp = Some(timestamp);
+        Ok(())
+    }
+}
+
+/// Error returned if the timestamp of the next block is not greater than the timestamp of the best
+/// block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidNextBlockTimestamp {
+    /// The rejected timestamp
+    pub timestamp: u64,
+    /// The timestamp of the best block
+    pub parent_timestamp: u64,
+}
+
+impl fmt::Display for InvalidNextBlockTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "timestamp {} is not greater than the timestamp {} of the best block",
+            self.timestamp, self.parent_timestamp
+        )
+    }
+}
+
+impl std::error::Error for InvalidNextBlockTimestamp {}
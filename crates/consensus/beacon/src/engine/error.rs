@@ -55,6 +55,10 @@ pub enum BeaconForkChoiceUpdateError {
     /// Thrown when the engine task is unavailable/stopped.
     #[error("beacon consensus engine task stopped")]
     EngineUnavailable,
+    /// Thrown when the forkchoice update succeeded, but the payload builder rejected the payload
+    /// attributes.
+    #[error("invalid payload attributes: {0}")]
+    InvalidPayloadAttributes(Box<dyn std::error::Error + Send + Sync>),
     /// An internal error occurred, not necessarily related to the update.
     #[error(transparent)]
     Internal(Box<dyn std::error::Error + Send + Sync>),
@@ -1,13 +1,17 @@
-use crate::engine::{error::BeaconOnNewPayloadError, forkchoice::ForkchoiceStatus};
+use crate::engine::{
+    error::{BeaconForkChoiceUpdateError, BeaconOnNewPayloadError},
+    forkchoice::ForkchoiceStatus,
+};
 use futures::{future::Either, FutureExt};
 use reth_engine_primitives::EngineTypes;
 use reth_errors::RethResult;
 use reth_payload_builder::error::PayloadBuilderError;
 use reth_rpc_types::engine::{
-    CancunPayloadFields, ExecutionPayload, ForkChoiceUpdateResult, ForkchoiceState,
-    ForkchoiceUpdateError, ForkchoiceUpdated, PayloadId, PayloadStatus, PayloadStatusEnum,
+    CancunPayloadFields, ExecutionPayload, ForkchoiceState, ForkchoiceUpdateError,
+    ForkchoiceUpdated, PayloadId, PayloadStatus, PayloadStatusEnum,
 };
 use std::{
+    fmt::{self, Display, Formatter},
     future::Future,
     pin::Pin,
     task::{ready, Context, Poll},
@@ -16,7 +20,8 @@ use tokio::sync::oneshot;
 
 /// Represents the outcome of forkchoice update.
 ///
-/// This is a future that resolves to [`ForkChoiceUpdateResult`]
+/// This is a future that resolves to the [`ForkchoiceUpdated`] response, or the error returned to
+/// the CL.
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 #[derive(Debug)]
 pub struct OnForkChoiceUpdated {
@@ -26,7 +31,10 @@ pub struct OnForkChoiceUpdated {
     /// depending on the payload attributes, even if the forkchoice update itself is valid.
     forkchoice_status: ForkchoiceStatus,
     /// Returns the result of the forkchoice update.
-    fut: Either<futures::future::Ready<ForkChoiceUpdateResult>, PendingPayloadId>,
+    fut: Either<
+        futures::future::Ready<Result<ForkchoiceUpdated, BeaconForkChoiceUpdateError>>,
+        PendingPayloadId,
+    >,
 }
 
 // === impl OnForkChoiceUpdated ===
@@ -69,7 +77,9 @@ impl OnForkChoiceUpdated {
     pub(crate) fn invalid_state() -> Self {
         Self {
             forkchoice_status: ForkchoiceStatus::Invalid,
-            fut: Either::Left(futures::future::ready(Err(ForkchoiceUpdateError::InvalidState))),
+            fut: Either::Left(futures::future::ready(Err(
+                ForkchoiceUpdateError::InvalidState.into()
+            ))),
         }
     }
 
@@ -80,7 +90,7 @@ impl OnForkChoiceUpdated {
             // This is valid because this is only reachable if the state and payload is valid
             forkchoice_status: ForkchoiceStatus::Valid,
             fut: Either::Left(futures::future::ready(Err(
-                ForkchoiceUpdateError::UpdatedInvalidPayloadAttributes,
+                ForkchoiceUpdateError::UpdatedInvalidPayloadAttributes.into(),
             ))),
         }
     }
@@ -101,7 +111,7 @@ impl OnForkChoiceUpdated {
 }
 
 impl Future for OnForkChoiceUpdated {
-    type Output = ForkChoiceUpdateResult;
+    type Output = Result<ForkchoiceUpdated, BeaconForkChoiceUpdateError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         self.get_mut().fut.poll_unpin(cx)
@@ -117,7 +127,7 @@ struct PendingPayloadId {
 }
 
 impl Future for PendingPayloadId {
-    type Output = ForkChoiceUpdateResult;
+    type Output = Result<ForkchoiceUpdated, BeaconForkChoiceUpdateError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
@@ -127,9 +137,13 @@ impl Future for PendingPayloadId {
                 payload_status: this.payload_status.take().expect("Polled after completion"),
                 payload_id: Some(payload_id),
             })),
+            Ok(Err(PayloadBuilderError::InvalidPayloadAttributes(err))) => {
+                // the payload builder rejected the attributes
+                Poll::Ready(Err(BeaconForkChoiceUpdateError::InvalidPayloadAttributes(err)))
+            }
             Err(_) | Ok(Err(_)) => {
                 // failed to initiate a payload build job
-                Poll::Ready(Err(ForkchoiceUpdateError::UpdatedInvalidPayloadAttributes))
+                Poll::Ready(Err(ForkchoiceUpdateError::UpdatedInvalidPayloadAttributes.into()))
             }
         }
     }
@@ -160,3 +174,25 @@ pub enum BeaconEngineMessage<Engine: EngineTypes> {
     /// Message with exchanged transition configuration.
     TransitionConfigurationExchanged,
 }
+
+impl<Engine: EngineTypes> Display for BeaconEngineMessage<Engine> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NewPayload { payload, .. } => write!(
+                f,
+                "NewPayload(number={}, hash={})",
+                payload.block_number(),
+                payload.block_hash()
+            ),
+            Self::ForkchoiceUpdated { state, payload_attrs, .. } => write!(
+                f,
+                "ForkchoiceUpdated(head={}, has_attrs={})",
+                state.head_block_hash,
+                payload_attrs.is_some()
+            ),
+            Self::TransitionConfigurationExchanged => {
+                f.write_str("TransitionConfigurationExchanged")
+            }
+        }
+    }
+}
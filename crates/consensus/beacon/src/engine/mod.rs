@@ -27,7 +27,7 @@ use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, ForkchoiceState, PayloadStatus, PayloadStatusEnum,
     PayloadValidationError,
 };
-use reth_stages_api::{ControlFlow, Pipeline, PipelineTarget, StageId};
+use reth_stages_api::{ControlFlow, Pipeline, PipelinePauseHandle, PipelineTarget, StageId};
 use reth_tasks::TaskSpawner;
 use reth_tokio_util::EventSender;
 use std::{
@@ -110,7 +110,9 @@ pub const MIN_BLOCKS_FOR_PIPELINE_RUN: u64 = EPOCH_SLOTS;
 /// chain, it will be fully validated added to a chain in the [`BlockchainTreeEngine`]: `VALID`
 ///
 /// If the payload's chain is disconnected (at least 1 block is missing) then it will be buffered:
-/// `SYNCING` ([`BlockStatus::Disconnected`]).
+/// `SYNCING` ([`BlockStatus::Disconnected`]). The missing blocks are then backfilled, either by
+/// downloading them or, if too many are missing, by running the pipeline, after which the buffered
+/// payload is inserted into the tree.
 ///
 /// ## Forkchoice Update (FCU) (`engine_forkchoiceUpdatedV{}`)
 ///
@@ -212,6 +214,13 @@ where
     /// be used to download and execute the missing blocks.
     pipeline_run_threshold: u64,
     hooks: EngineHooksController,
+    /// Handle to pause live sync together with the pipeline.
+    ///
+    /// While paused, new payloads are buffered, forkchoice updates are answered with `SYNCING`
+    /// and no hooks are run, so the database isn't modified.
+    pause_handle: PipelinePauseHandle,
+    /// Whether live sync was paused when the pause handle was last checked.
+    paused: bool,
     /// Sender for engine events.
     event_sender: EventSender<BeaconConsensusEngineEvent>,
     /// Consensus engine metrics.
@@ -292,6 +301,7 @@ where
     ) -> RethResult<(Self, BeaconConsensusEngineHandle<EngineT>)> {
         let event_sender = EventSender::default();
         let handle = BeaconConsensusEngineHandle::new(to_engine, event_sender.clone());
+        let pause_handle = pipeline.pause_handle();
         let sync = EngineSyncController::new(
             pipeline,
             client,
@@ -314,6 +324,8 @@ where
             pending_forkchoice_update: None,
             pipeline_run_threshold,
             hooks: EngineHooksController::new(hooks),
+            pause_handle,
+            paused: false,
             event_sender,
             metrics: EngineMetrics::default(),
         };
@@ -374,6 +386,11 @@ where
             return Ok(Some(OnForkChoiceUpdated::syncing()))
         }
 
+        if self.paused {
+            trace!(target: "consensus::engine", "Sync is paused, skipping forkchoice update");
+            return Ok(Some(OnForkChoiceUpdated::syncing()))
+        }
+
         Ok(None)
     }
 
@@ -561,6 +578,10 @@ where
             ForkchoiceStatus::Syncing => {
                 // we're syncing
                 self.sync_state_updater.update_sync_state(SyncState::Syncing);
+                // the head is not canonical yet, but the safe and finalized blocks might be
+                if let Err(error) = self.update_known_safe_and_finalized_blocks(&state) {
+                    debug!(target: "consensus::engine", %error, ?state, "Failed to update the tracked safe and finalized blocks");
+                }
             }
         }
 
@@ -619,6 +640,19 @@ where
         self.handle.clone()
     }
 
+    /// Configures the engine to run the pipeline continuously.
+    ///
+    /// In this mode the pipeline is re-spawned as long as it makes progress and the canonical head
+    /// is advanced after every run, even if no forkchoice update was ever received. This allows
+    /// following the chain with a trusted tip source (e.g. `--debug.tip`) and without a consensus
+    /// client.
+    ///
+    /// Note: this should be combined with a pipeline run threshold of `0`, so that new targets are
+    /// always synced via the pipeline.
+    pub fn set_pipeline_run_continuously(&mut self, continuous: bool) {
+        self.sync.set_run_pipeline_continuously(continuous);
+    }
+
     /// Returns true if the distance from the local tip to the block is greater than the configured
     /// threshold.
     ///
@@ -962,6 +996,43 @@ where
         Ok(())
     }
 
+    /// Updates the tracked safe and finalized blocks to the blocks of the forkchoice state that are
+    /// already part of the local canonical chain.
+    ///
+    /// This is used while the head of the forkchoice state can't be made canonical yet, so that
+    /// the `safe` and `finalized` block tags can be resolved during sync. Blocks that are unknown
+    /// or not canonical are skipped.
+    fn update_known_safe_and_finalized_blocks(
+        &self,
+        state: &ForkchoiceState,
+    ) -> ProviderResult<()> {
+        // the pipeline might not have executed the canonical blocks yet
+        if !self.sync.is_pipeline_idle() {
+            return Ok(())
+        }
+
+        if self.blockchain.safe_block_hash()? != Some(state.safe_block_hash) {
+            if let Some(safe) = self.canonical_header_by_hash(state.safe_block_hash)? {
+                self.blockchain.set_safe(safe);
+            }
+        }
+        if self.blockchain.finalized_block_hash()? != Some(state.finalized_block_hash) {
+            if let Some(finalized) = self.canonical_header_by_hash(state.finalized_block_hash)? {
+                self.blockchain.set_finalized(finalized);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the header of the canonical block with the given hash, if it's in the database.
+    fn canonical_header_by_hash(&self, hash: B256) -> ProviderResult<Option<SealedHeader>> {
+        if hash.is_zero() {
+            return Ok(None)
+        }
+        let Some(number) = self.blockchain.block_number(hash)? else { return Ok(None) };
+        self.blockchain.sealed_header(number)
+    }
+
     /// Handler for a failed a forkchoice update due to a canonicalization error.
     ///
     /// This will determine if the state's head is invalid, and if so, return immediately.
@@ -1249,7 +1320,29 @@ where
                 latest_valid_hash = Some(block_hash);
                 PayloadStatusEnum::Valid
             }
-            InsertPayloadOk::Inserted(BlockStatus::Disconnected { .. }) |
+            InsertPayloadOk::Inserted(BlockStatus::Disconnected { head, missing_ancestor }) => {
+                let block_num_hash = block.num_hash();
+
+                // check if the block's parent is already marked as invalid
+                if let Some(status) =
+                    self.check_invalid_ancestor_with_head(block.parent_hash, block.hash()).map_err(
+                        |error| InsertBlockError::new(block, InsertBlockErrorKind::Provider(error)),
+                    )?
+                {
+                    return Ok(status)
+                }
+
+                // backfill the missing ancestors, the payload is buffered and will be connected
+                // once they are inserted
+                if !self.sync.is_inflight_request(missing_ancestor.hash) &&
+                    !self.sync.is_inflight_range_request(missing_ancestor.hash)
+                {
+                    self.on_disconnected_block(block_num_hash, missing_ancestor, head);
+                }
+
+                // not known to be invalid, but we don't know anything else
+                PayloadStatusEnum::Syncing
+            }
             InsertPayloadOk::AlreadySeen(BlockStatus::Disconnected { .. }) => {
                 // check if the block's parent is already marked as invalid
                 if let Some(status) =
@@ -1267,10 +1360,11 @@ where
         Ok(PayloadStatus::new(status, latest_valid_hash))
     }
 
-    /// This handles downloaded blocks that are shown to be disconnected from the canonical chain.
+    /// This handles downloaded blocks and new payloads that are shown to be disconnected from the
+    /// canonical chain.
     ///
-    /// This mainly compares the missing parent of the downloaded block with the current canonical
-    /// tip, and decides whether or not the pipeline should be run.
+    /// This mainly compares the missing parent of the block with the current canonical tip, and
+    /// decides whether or not the pipeline should be run.
     ///
     /// The canonical tip is compared to the missing parent using `exceeds_pipeline_run_threshold`,
     /// which returns true if the missing parent is sufficiently ahead of the canonical tip. If so,
@@ -1449,7 +1543,14 @@ where
             None => {
                 // This is only possible if the node was run with `debug.tip`
                 // argument and without CL.
-                warn!(target: "consensus::engine", "No fork choice state available");
+                if self.sync.run_pipeline_continuously() {
+                    // there's no forkchoice state to follow, so we expose the pipeline progress
+                    // as the canonical head
+                    self.set_canonical_head(ctrl.block_number().unwrap_or_default())?;
+                    self.blockchain.update_block_hashes_and_clear_buffered()?;
+                } else {
+                    warn!(target: "consensus::engine", "No fork choice state available");
+                }
                 return Ok(())
             }
         };
@@ -1526,6 +1627,10 @@ where
             self.blockchain.connect_buffered_blocks_to_canonical_hashes_and_finalize(number).inspect_err(|error| {
                 error!(target: "consensus::engine", %error, "Error restoring blockchain tree state");
             })?;
+            // the safe and finalized blocks can be resolved before the head is canonical
+            if let Err(error) = self.update_known_safe_and_finalized_blocks(&sync_target_state) {
+                debug!(target: "consensus::engine", %error, "Failed to update the tracked safe and finalized blocks");
+            }
         } else {
             // We don't have the finalized block in the database, so we need to
             // trigger another pipeline run.
@@ -1585,6 +1690,32 @@ where
         Ok(())
     }
 
+    /// Checks whether live sync was paused or resumed together with the pipeline.
+    ///
+    /// While paused, the node reports that it's syncing. Once resumed, the blocks that were
+    /// buffered in the meantime are connected to the canonical chain.
+    fn on_pause_state(&mut self) -> Result<(), BeaconConsensusEngineError> {
+        let paused = self.pause_handle.is_paused();
+        if paused == self.paused {
+            return Ok(())
+        }
+        self.paused = paused;
+
+        if paused {
+            info!(target: "consensus::engine", "Sync paused");
+            self.sync_state_updater.update_sync_state(SyncState::Syncing);
+        } else {
+            info!(target: "consensus::engine", "Sync resumed");
+            self.sync_state_updater.update_sync_state(SyncState::Idle);
+            if let Err(error) = self.blockchain.connect_buffered_blocks_to_canonical_hashes() {
+                error!(target: "consensus::engine", %error, "Error connecting buffered blocks to canonical hashes on resume");
+                return Err(RethError::Canonical(error).into())
+            }
+        }
+
+        Ok(())
+    }
+
     /// Process the next set blockchain tree action.
     /// The handler might set next blockchain tree action to perform,
     /// so the state change should be handled accordingly.
@@ -1625,9 +1756,9 @@ where
             BlockchainTreeAction::InsertNewPayload { block, tx } => {
                 let block_hash = block.hash();
                 let block_num_hash = block.num_hash();
-                let result = if self.sync.is_pipeline_idle() {
+                let result = if self.sync.is_pipeline_idle() && !self.paused {
                     // we can only insert new payloads if the pipeline is _not_ running, because it
-                    // holds exclusive access to the database
+                    // holds exclusive access to the database, and sync isn't paused
                     self.try_insert_new_payload(block)
                 } else {
                     self.try_buffer_payload(block)
@@ -1719,6 +1850,14 @@ where
             }
 
             BlockchainTreeAction::InsertDownloadedPayload { block } => {
+                if self.paused {
+                    // the block is connected from the buffer once sync is resumed
+                    if let Err(error) = self.blockchain.buffer_block_without_senders(block) {
+                        debug!(target: "consensus::engine", %error, "Failed to buffer downloaded block while sync is paused");
+                    }
+                    return Ok(EngineEventOutcome::Processed)
+                }
+
                 let downloaded_num_hash = block.num_hash();
                 match self.blockchain.insert_block_without_senders(
                     block,
@@ -1807,6 +1946,10 @@ where
             // Poll a running hook with db write access (if any) and CL messages first, draining
             // both and then proceeding to polling other parts such as SyncController and hooks.
             loop {
+                // The pause state is only changed through the admin API, so it's checked before
+                // processing the next engine message instead of being polled.
+                this.on_pause_state()?;
+
                 // Poll a running hook with db write access first, as we will not be able to process
                 // any engine messages until it's finished.
                 if let Poll::Ready(result) =
@@ -1849,6 +1992,9 @@ where
                 // These messages can affect the state of the SyncController and they're also time
                 // sensitive, hence they are polled first.
                 if let Poll::Ready(Some(msg)) = this.engine_message_stream.poll_next_unpin(cx) {
+                    let _span =
+                        debug_span!(target: "consensus::engine", "engine_message", message = %msg)
+                            .entered();
                     match msg {
                         BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
                             this.on_forkchoice_updated(state, payload_attrs, tx);
@@ -1899,7 +2045,8 @@ where
             // Poll next hook if all conditions are met:
             // 1. Engine and sync messages are fully drained (both pending)
             // 2. Latest FCU status is not INVALID
-            if !this.forkchoice_state_tracker.is_latest_invalid() {
+            // 3. Sync is not paused
+            if !this.forkchoice_state_tracker.is_latest_invalid() && !this.paused {
                 if let Poll::Ready(result) = this.hooks.poll_next_hook(
                     cx,
                     this.current_engine_hook_context()?,
@@ -2264,6 +2411,70 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn paused_forkchoice() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+            let block1 = random_block(&mut rng, 1, Some(genesis.hash()), None, Some(0));
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    StaticFileProvider::read_write(static_dir_path).unwrap(),
+                ),
+                [&genesis, &block1].into_iter(),
+            );
+            env.db
+                .update(|tx| {
+                    tx.put::<tables::StageCheckpoints>(
+                        StageId::Finish.to_string(),
+                        StageCheckpoint::new(block1.number),
+                    )
+                })
+                .unwrap()
+                .unwrap();
+
+            let pause_handle = consensus_engine.pause_handle.clone();
+            assert!(pause_handle.pause().unwrap());
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            let forkchoice = ForkchoiceState {
+                head_block_hash: block1.hash(),
+                finalized_block_hash: block1.hash(),
+                ..Default::default()
+            };
+
+            // the forkchoice isn't applied while sync is paused
+            let result = env.send_forkchoice_updated(forkchoice).await.unwrap();
+            assert_eq!(result, ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing));
+
+            assert!(pause_handle.resume().unwrap());
+            let result = env.send_forkchoice_updated(forkchoice).await.unwrap();
+            let expected_result = ForkchoiceUpdated::new(PayloadStatus::new(
+                PayloadStatusEnum::Valid,
+                Some(block1.hash()),
+            ));
+            assert_eq!(result, expected_result);
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn unknown_head_hash() {
             let mut rng = generators::rng();
@@ -2380,6 +2591,58 @@ mod tests {
             drop(engine);
         }
 
+        #[tokio::test]
+        async fn syncing_forkchoice_tracks_known_finalized_block() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .disable_blockchain_tree_sync()
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+            let block1 = random_block(&mut rng, 1, Some(genesis.hash()), None, Some(0));
+
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    StaticFileProvider::read_write(static_dir_path).unwrap(),
+                ),
+                [&genesis, &block1].into_iter(),
+            );
+
+            let engine = spawn_consensus_engine(consensus_engine);
+            assert_eq!(env.provider.finalized_block_hash().unwrap(), None);
+
+            // the head is unknown, but the finalized block is already canonical
+            let res = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: rng.gen(),
+                    safe_block_hash: rng.gen(),
+                    finalized_block_hash: block1.hash(),
+                })
+                .await;
+            let expected_result = ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing);
+            assert_matches!(res, Ok(result) => assert_eq!(result, expected_result));
+
+            assert_eq!(env.provider.finalized_block_hash().unwrap(), Some(block1.hash()));
+            assert_eq!(env.provider.safe_block_hash().unwrap(), None);
+            drop(engine);
+        }
+
         #[tokio::test]
         async fn forkchoice_updated_pre_merge() {
             let mut rng = generators::rng();
@@ -2708,12 +2971,25 @@ mod tests {
             assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => assert_eq!(payload_status, expected_result));
 
             // Send new payload
+            let mut events = env.event_listener();
             let parent = rng.gen();
             let block = random_block(&mut rng, 2, Some(parent), None, Some(0));
             let res = env.send_new_payload(block_to_payload_v1(block), None).await;
             let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
             assert_matches!(res, Ok(result) => assert_eq!(result, expected_result));
 
+            // the missing parent is downloaded
+            loop {
+                if let BeaconConsensusEngineEvent::LiveSyncProgress(
+                    ConsensusEngineLiveSyncProgress::DownloadingBlocks { remaining_blocks, target },
+                ) = events.next().await.unwrap()
+                {
+                    assert_eq!(remaining_blocks, 1);
+                    assert_eq!(target, parent);
+                    break
+                }
+            }
+
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
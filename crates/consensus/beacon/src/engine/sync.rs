@@ -46,6 +46,11 @@ where
     pipeline_state: PipelineState<DB>,
     /// Pending target block for the pipeline to sync
     pending_pipeline_target: Option<PipelineTarget>,
+    /// If enabled, the pipeline is spawned again without a new target as long as the previous run
+    /// made progress, so it keeps following the most recent tip without forkchoice updates.
+    run_pipeline_continuously: bool,
+    /// Whether a continuous pipeline run is queued and will be triggered on the next `poll`.
+    pending_continuous_run: bool,
     /// In-flight full block requests in progress.
     inflight_full_block_requests: Vec<FetchFullBlockFuture<Client>>,
     /// In-flight full block _range_ requests in progress.
@@ -84,6 +89,8 @@ where
             pipeline_task_spawner,
             pipeline_state: PipelineState::Idle(Some(pipeline)),
             pending_pipeline_target: None,
+            run_pipeline_continuously: false,
+            pending_continuous_run: false,
             inflight_full_block_requests: Vec::new(),
             inflight_block_range_requests: Vec::new(),
             range_buffered_blocks: BinaryHeap::new(),
@@ -93,6 +100,20 @@ where
         }
     }
 
+    /// Configures whether the pipeline should be re-run after it finished with progress, without
+    /// waiting for a new sync target.
+    pub(crate) fn set_run_pipeline_continuously(&mut self, continuous: bool) {
+        self.run_pipeline_continuously = continuous;
+        if !continuous {
+            self.pending_continuous_run = false;
+        }
+    }
+
+    /// Returns `true` if the pipeline is configured to run continuously.
+    pub(crate) const fn run_pipeline_continuously(&self) -> bool {
+        self.run_pipeline_continuously
+    }
+
     /// Sets the metrics for the active downloads
     fn update_block_download_metrics(&self) {
         self.metrics.active_block_downloads.set(self.inflight_full_block_requests.len() as f64);
@@ -122,7 +143,8 @@ where
     /// Returns `true` if a pipeline target is queued and will be triggered on the next `poll`.
     #[allow(dead_code)]
     pub(crate) const fn is_pipeline_sync_pending(&self) -> bool {
-        self.pending_pipeline_target.is_some() && self.pipeline_state.is_idle()
+        (self.pending_pipeline_target.is_some() || self.pending_continuous_run) &&
+            self.pipeline_state.is_idle()
     }
 
     /// Returns `true` if the pipeline is idle.
@@ -140,14 +162,21 @@ where
         self.inflight_full_block_requests.iter().any(|req| *req.hash() == hash)
     }
 
+    /// Returns true if there's already a range request starting at the given hash.
+    pub(crate) fn is_inflight_range_request(&self, hash: B256) -> bool {
+        self.inflight_block_range_requests.iter().any(|req| req.start_hash() == hash)
+    }
+
     /// Starts requesting a range of blocks from the network, in reverse from the given hash.
     ///
     /// If the `count` is 1, this will use the `download_full_block` method instead, because it
     /// downloads headers and bodies for the block concurrently.
+    ///
+    /// Does nothing if a range starting at the given hash is already being downloaded.
     pub(crate) fn download_block_range(&mut self, hash: B256, count: u64) {
         if count == 1 {
             self.download_full_block(hash);
-        } else {
+        } else if !self.is_inflight_range_request(hash) {
             trace!(
                 target: "consensus::engine",
                 ?hash,
@@ -248,6 +277,11 @@ where
                 let reached_max_block =
                     self.has_reached_max_block(minimum_block_number.unwrap_or_default());
                 self.pipeline_state = PipelineState::Idle(Some(pipeline));
+                // in continuous mode, keep following the tip as long as the pipeline makes
+                // progress
+                self.pending_continuous_run = self.run_pipeline_continuously &&
+                    !reached_max_block &&
+                    matches!(result, Ok(ControlFlow::Continue { .. }));
                 EngineSyncEvent::PipelineFinished { result, reached_max_block }
             }
             Err(_) => {
@@ -263,14 +297,18 @@ where
     fn try_spawn_pipeline(&mut self) -> Option<EngineSyncEvent> {
         match &mut self.pipeline_state {
             PipelineState::Idle(pipeline) => {
-                let target = self.pending_pipeline_target.take()?;
+                let target = self.pending_pipeline_target.take();
+                let continuous_run = std::mem::take(&mut self.pending_continuous_run);
+                if target.is_none() && !continuous_run {
+                    return None
+                }
                 let (tx, rx) = oneshot::channel();
 
                 let pipeline = pipeline.take().expect("exists");
                 self.pipeline_task_spawner.spawn_critical_blocking(
                     "pipeline task",
                     Box::pin(async move {
-                        let result = pipeline.run_as_fut(Some(target)).await;
+                        let result = pipeline.run_as_fut(target).await;
                         let _ = tx.send(result);
                     }),
                 );
@@ -280,7 +318,7 @@ where
                 // outdated (included in the range the pipeline is syncing anyway)
                 self.clear_block_download_requests();
 
-                Some(EngineSyncEvent::PipelineStarted(Some(target)))
+                Some(EngineSyncEvent::PipelineStarted(target))
             }
             PipelineState::Running(_) => None,
         }
@@ -587,6 +625,62 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn continuous_pipeline_reruns_while_making_progress() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(MAINNET.genesis.clone())
+                .paris_activated()
+                .build(),
+        );
+
+        let client = TestFullBlockClient::default();
+        insert_headers_into_client(&client, SealedHeader::default(), 0..10);
+        // the first run makes progress, the second one doesn't
+        let pipeline = TestPipelineBuilder::new()
+            .with_pipeline_exec_outputs(VecDeque::from([
+                Ok(ExecOutput { checkpoint: StageCheckpoint::new(5), done: true }),
+                Ok(ExecOutput { checkpoint: StageCheckpoint::new(5), done: true }),
+            ]))
+            .build(chain_spec.clone());
+
+        let mut sync_controller = TestSyncControllerBuilder::new()
+            .with_client(client.clone())
+            .build(pipeline, chain_spec);
+        sync_controller.set_run_pipeline_continuously(true);
+
+        // nothing to do without an initial target
+        assert!(!sync_controller.is_pipeline_sync_pending());
+
+        let tip = client.highest_block().expect("there should be blocks here");
+        sync_controller.set_pipeline_sync_target(tip.hash().into());
+
+        let next_event = poll_fn(|cx| sync_controller.poll(cx)).await;
+        assert_matches!(next_event, EngineSyncEvent::PipelineStarted(Some(_)));
+        let next_event = poll_fn(|cx| sync_controller.poll(cx)).await;
+        assert_matches!(
+            next_event,
+            EngineSyncEvent::PipelineFinished {
+                result: Ok(ControlFlow::Continue { block_number: 5 }),
+                ..
+            }
+        );
+
+        // the pipeline made progress, so it's spawned again without a target
+        assert!(sync_controller.is_pipeline_sync_pending());
+        let next_event = poll_fn(|cx| sync_controller.poll(cx)).await;
+        assert_matches!(next_event, EngineSyncEvent::PipelineStarted(None));
+        let next_event = poll_fn(|cx| sync_controller.poll(cx)).await;
+        assert_matches!(
+            next_event,
+            EngineSyncEvent::PipelineFinished { result: Ok(ControlFlow::NoProgress { .. }), .. }
+        );
+
+        // no progress, so we wait for the next target
+        assert!(!sync_controller.is_pipeline_sync_pending());
+    }
+
     fn insert_headers_into_client(
         client: &TestFullBlockClient,
         genesis_header: SealedHeader,
@@ -644,6 +738,10 @@ mod tests {
         assert_eq!(first_req.start_hash(), tip.hash());
         assert_eq!(first_req.count(), tip.number);
 
+        // requesting the same range again doesn't start another request
+        sync_controller.download_block_range(tip.hash(), tip.number);
+        assert_eq!(sync_controller.inflight_block_range_requests.len(), 1);
+
         // ensure they are in ascending order
         for num in 1..=10 {
             let sync_future = poll_fn(|cx| sync_controller.poll(cx));
@@ -1,7 +1,8 @@
 use crate::{
     engine::hooks::PruneHook, hooks::EngineHooks, BeaconConsensusEngine,
-    BeaconConsensusEngineError, BeaconConsensusEngineHandle, BeaconForkChoiceUpdateError,
-    BeaconOnNewPayloadError, EthBeaconConsensus, MIN_BLOCKS_FOR_PIPELINE_RUN,
+    BeaconConsensusEngineError, BeaconConsensusEngineEvent, BeaconConsensusEngineHandle,
+    BeaconForkChoiceUpdateError, BeaconOnNewPayloadError, EthBeaconConsensus,
+    MIN_BLOCKS_FOR_PIPELINE_RUN,
 };
 use reth_blockchain_tree::{
     config::BlockchainTreeConfig, externals::TreeExternals, BlockchainTree, ShareableBlockchainTree,
@@ -20,7 +21,7 @@ use reth_evm_ethereum::execute::EthExecutorProvider;
 use reth_exex_types::FinishedExExHeight;
 use reth_network_p2p::{
     bodies::client::BodiesClient, headers::client::HeadersClient, sync::NoopSyncStateUpdater,
-    test_utils::NoopFullBlockClient,
+    test_utils::PendingFullBlockClient,
 };
 use reth_payload_builder::test_utils::spawn_test_payload_service;
 use reth_primitives::{BlockNumber, B256};
@@ -29,13 +30,14 @@ use reth_provider::{
     ExecutionOutcome,
 };
 use reth_prune::Pruner;
-use reth_prune_types::PruneModes;
+use reth_prune_types::{PruneModes, PruneSchedule};
 use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, ForkchoiceState, ForkchoiceUpdated, PayloadStatus,
 };
 use reth_stages::{sets::DefaultStages, test_utils::TestStages, ExecOutput, Pipeline, StageError};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TokioTaskExecutor;
+use reth_tokio_util::EventStream;
 use std::{collections::VecDeque, sync::Arc};
 use tokio::sync::{oneshot, watch};
 
@@ -44,13 +46,15 @@ type DatabaseEnv = TempDatabase<DE>;
 type TestBeaconConsensusEngine<Client> = BeaconConsensusEngine<
     Arc<DatabaseEnv>,
     BlockchainProvider<Arc<DatabaseEnv>>,
-    Arc<Either<Client, NoopFullBlockClient>>,
+    Arc<Either<Client, PendingFullBlockClient>>,
     EthEngineTypes,
 >;
 
-#[derive(Debug)]
+#[allow(missing_debug_implementations)]
 pub struct TestEnv<DB> {
     pub db: DB,
+    /// The provider of the engine, tracking the canonical head and the safe and finalized blocks.
+    pub provider: BlockchainProvider<DB>,
     // Keep the tip receiver around, so it's not dropped.
     #[allow(dead_code)]
     tip_rx: watch::Receiver<B256>,
@@ -60,10 +64,11 @@ pub struct TestEnv<DB> {
 impl<DB> TestEnv<DB> {
     const fn new(
         db: DB,
+        provider: BlockchainProvider<DB>,
         tip_rx: watch::Receiver<B256>,
         engine_handle: BeaconConsensusEngineHandle<EthEngineTypes>,
     ) -> Self {
-        Self { db, tip_rx, engine_handle }
+        Self { db, provider, tip_rx, engine_handle }
     }
 
     pub async fn send_new_payload<T: Into<ExecutionPayload>>(
@@ -110,6 +115,11 @@ impl<DB> TestEnv<DB> {
             }
         }
     }
+
+    /// Creates a new listener for the events of the consensus engine.
+    pub fn event_listener(&self) -> EventStream<BeaconConsensusEngineEvent> {
+        self.engine_handle.event_listener()
+    }
 }
 
 // TODO: add with_consensus in case we want to use the TestConsensus purposeful failure - this
@@ -240,7 +250,7 @@ impl TestConsensusEngineBuilder {
     /// Builds the test consensus engine into a `TestConsensusEngine` and `TestEnv`.
     pub fn build(
         self,
-    ) -> (TestBeaconConsensusEngine<NoopFullBlockClient>, TestEnv<Arc<DatabaseEnv>>) {
+    ) -> (TestBeaconConsensusEngine<PendingFullBlockClient>, TestEnv<Arc<DatabaseEnv>>) {
         let networked = NetworkedTestConsensusEngineBuilder { base_config: self, client: None };
 
         networked.build()
@@ -333,11 +343,13 @@ where
         };
         let payload_builder = spawn_test_payload_service::<EthEngineTypes>();
 
-        // use either noop client or a user provided client (for example TestFullBlockClient)
+        // use either a client without peers or a user provided client (for example
+        // TestFullBlockClient). The requests of the default client never complete, so blocks the
+        // engine starts downloading stay in flight instead of being requested again right away.
         let client = Arc::new(
             self.client
                 .map(Either::Left)
-                .unwrap_or_else(|| Either::Right(NoopFullBlockClient::default())),
+                .unwrap_or_else(|| Either::Right(PendingFullBlockClient::default())),
         );
 
         // use either test executor or real executor
@@ -406,7 +418,11 @@ where
             self.base_config.chain_spec.prune_delete_limit,
             config.max_reorg_depth() as usize,
             None,
+            PruneSchedule::default(),
+            None,
             watch::channel(FinishedExExHeight::NoExExs).1,
+            watch::channel(None).1,
+            None,
         );
 
         let mut hooks = EngineHooks::new();
@@ -415,7 +431,7 @@ where
         let (mut engine, handle) = BeaconConsensusEngine::new(
             client,
             pipeline,
-            blockchain_provider,
+            blockchain_provider.clone(),
             Box::<TokioTaskExecutor>::default(),
             Box::<NoopSyncStateUpdater>::default(),
             None,
@@ -430,7 +446,10 @@ where
             engine.sync.set_max_block(max_block)
         }
 
-        (engine, TestEnv::new(provider_factory.db_ref().clone(), tip_rx, handle))
+        (
+            engine,
+            TestEnv::new(provider_factory.db_ref().clone(), blockchain_provider, tip_rx, handle),
+        )
     }
 }
 
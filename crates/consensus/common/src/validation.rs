@@ -8,7 +8,9 @@ use reth_primitives::{
         MAXIMUM_EXTRA_DATA_SIZE,
     },
     eip4844::calculate_excess_blob_gas,
-    EthereumHardfork, GotExpected, Header, SealedBlock, SealedHeader,
+    proofs::calculate_requests_root,
+    Block, EthereumHardfork, GotExpected, GotExpectedBoxed, Header, Request, SealedBlock,
+    SealedHeader, B256,
 };
 
 /// Gas used needs to be less than gas limit. Gas used is going to be checked after execution.
@@ -104,6 +106,62 @@ pub fn validate_block_pre_execution(
     Ok(())
 }
 
+/// Validates the [EIP-7685](https://eips.ethereum.org/EIPS/eip-7685) requests of the execution
+/// against the requests root of the block header.
+///
+/// If the roots differ, the requests are compared by type against the requests of the block body,
+/// which are committed to by the header (see [`validate_block_pre_execution`]), to report which
+/// requests don't match:
+///
+/// - EIP-6110 deposit requests parsed from the receipts
+/// - EIP-7002 withdrawal requests of the withdrawal requests contract
+/// - EIP-7251 consolidation requests of the consolidation requests contract
+///
+/// If the requests of each type match, e.g. because the requests of the body aren't ordered by
+/// type, or the block has no body requests, [`ConsensusError::BodyRequestsRootDiff`] is returned.
+pub fn validate_requests(block: &Block, requests: &[Request]) -> Result<(), ConsensusError> {
+    let header_requests_root = block.requests_root.ok_or(ConsensusError::RequestsRootMissing)?;
+    let requests_root = calculate_requests_root(requests);
+    if requests_root == header_requests_root {
+        return Ok(())
+    }
+
+    if let Some(body_requests) = &block.requests {
+        let errors: [fn(GotExpectedBoxed<B256>) -> ConsensusError; 3] = [
+            ConsensusError::BodyDepositRequestsRootDiff,
+            ConsensusError::BodyWithdrawalRequestsRootDiff,
+            ConsensusError::BodyConsolidationRequestsRootDiff,
+        ];
+        let roots = requests_roots_by_type(requests)
+            .into_iter()
+            .zip(requests_roots_by_type(&body_requests.0));
+        for ((got, expected), error) in roots.zip(errors) {
+            if got != expected {
+                return Err(error(GotExpected { got, expected }.into()))
+            }
+        }
+    }
+
+    Err(ConsensusError::BodyRequestsRootDiff(
+        GotExpected { got: requests_root, expected: header_requests_root }.into(),
+    ))
+}
+
+/// Returns the roots of the deposit, withdrawal and consolidation requests.
+fn requests_roots_by_type(requests: &[Request]) -> [B256; 3] {
+    let mut by_type: [Vec<Request>; 3] = Default::default();
+    for request in requests {
+        let index = match request {
+            Request::DepositRequest(_) => 0,
+            Request::WithdrawalRequest(_) => 1,
+            Request::ConsolidationRequest(_) => 2,
+            _ => continue,
+        };
+        by_type[index].push(*request);
+    }
+    by_type.map(|requests| calculate_requests_root(&requests))
+}
+
 /// Validates that the EIP-4844 header fields exist and conform to the spec. This ensures that:
 ///
 ///  * `blob_gas_used` exists as a header field
@@ -269,13 +327,16 @@ pub fn validate_against_parent_4844(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_eips::{
+        eip6110::DepositRequest, eip7002::WithdrawalRequest, eip7251::ConsolidationRequest,
+    };
     use mockall::mock;
     use rand::Rng;
     use reth_chainspec::ChainSpecBuilder;
     use reth_primitives::{
         hex_literal::hex, proofs, Account, Address, BlockBody, BlockHash, BlockHashOrNumber,
-        BlockNumber, Bytes, Signature, Transaction, TransactionSigned, TxEip4844, Withdrawal,
-        Withdrawals, U256,
+        BlockNumber, Bytes, Requests, Signature, Transaction, TransactionSigned, TxEip4844,
+        Withdrawal, Withdrawals, U256,
     };
     use reth_storage_api::{
         errors::provider::ProviderResult, AccountReader, HeaderProvider, WithdrawalsProvider,
@@ -494,6 +555,48 @@ mod tests {
             .return_const(Ok(Some(Withdrawal { index: 2, ..Default::default() })));
     }
 
+    #[test]
+    fn requests_root_mismatch_by_type() {
+        let deposit = Request::DepositRequest(DepositRequest { index: 1, ..Default::default() });
+        let withdrawal =
+            Request::WithdrawalRequest(WithdrawalRequest { amount: 1, ..Default::default() });
+        let consolidation = Request::ConsolidationRequest(ConsolidationRequest::default());
+        let requests = vec![deposit, withdrawal, consolidation];
+        let block = Block {
+            header: Header {
+                requests_root: Some(proofs::calculate_requests_root(&requests)),
+                ..Default::default()
+            },
+            requests: Some(Requests(requests.clone())),
+            ..Default::default()
+        };
+        assert_eq!(validate_requests(&block, &requests), Ok(()));
+
+        let missing_deposit = [withdrawal, consolidation];
+        assert!(matches!(
+            validate_requests(&block, &missing_deposit),
+            Err(ConsensusError::BodyDepositRequestsRootDiff(_))
+        ));
+        let other_withdrawal =
+            Request::WithdrawalRequest(WithdrawalRequest { amount: 2, ..Default::default() });
+        assert!(matches!(
+            validate_requests(&block, &[deposit, other_withdrawal, consolidation]),
+            Err(ConsensusError::BodyWithdrawalRequestsRootDiff(_))
+        ));
+        assert!(matches!(
+            validate_requests(&block, &[deposit, withdrawal]),
+            Err(ConsensusError::BodyConsolidationRequestsRootDiff(_))
+        ));
+        // the requests of each type match, but are ordered differently
+        assert!(matches!(
+            validate_requests(&block, &[withdrawal, deposit, consolidation]),
+            Err(ConsensusError::BodyRequestsRootDiff(_))
+        ));
+
+        let block = Block { header: Header::default(), ..block };
+        assert_eq!(validate_requests(&block, &requests), Err(ConsensusError::RequestsRootMissing));
+    }
+
     #[test]
     fn cancun_block_incorrect_blob_gas_used() {
         let chain_spec = ChainSpecBuilder::mainnet().cancun_activated().build();
@@ -10,9 +10,9 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use reth_primitives::{
-    constants::MINIMUM_GAS_LIMIT, BlockHash, BlockNumber, BlockWithSenders, Bloom, GotExpected,
-    GotExpectedBoxed, Header, InvalidTransactionError, Receipt, Request, SealedBlock, SealedHeader,
-    B256, U256,
+    constants::{MAXIMUM_EXTRA_DATA_SIZE, MINIMUM_GAS_LIMIT},
+    BlockHash, BlockNumber, BlockWithSenders, Bloom, GotExpected, GotExpectedBoxed, Header,
+    InvalidTransactionError, Receipt, Request, SealedBlock, SealedHeader, B256, U256,
 };
 
 #[cfg(feature = "std")]
@@ -127,6 +127,49 @@ pub trait Consensus: Debug + Send + Sync {
     ) -> Result<(), ConsensusError>;
 }
 
+/// Header rules that differ between chains.
+///
+/// Consensus implementations that perform the Ethereum checks delegate the base fee, extra data
+/// and timestamp checks to their rules, so that custom chains can change these without replacing
+/// the whole consensus implementation.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait HeaderRules: Debug + Send + Sync {
+    /// Returns the base fee the header must have given its parent, or `None` if the base fee of
+    /// the header is not checked, e.g. before EIP-1559.
+    fn expected_base_fee(
+        &self,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<Option<u64>, ConsensusError>;
+
+    /// Validates the extra data of the header.
+    ///
+    /// By default, the extra data must not exceed [`MAXIMUM_EXTRA_DATA_SIZE`] bytes.
+    fn validate_extra_data(&self, header: &Header) -> Result<(), ConsensusError> {
+        if header.extra_data.len() > MAXIMUM_EXTRA_DATA_SIZE {
+            return Err(ConsensusError::ExtraDataExceedsMax { len: header.extra_data.len() })
+        }
+        Ok(())
+    }
+
+    /// Validates the timestamp of the header against its parent.
+    ///
+    /// By default, the header must be newer than its parent.
+    fn validate_timestamp(
+        &self,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<(), ConsensusError> {
+        if header.is_timestamp_in_past(parent.timestamp) {
+            return Err(ConsensusError::TimestampIsInPast {
+                parent_timestamp: parent.timestamp,
+                timestamp: header.timestamp,
+            })
+        }
+        Ok(())
+    }
+}
+
 /// Consensus Errors
 #[derive(thiserror_no_std::Error, Debug, PartialEq, Eq, Clone)]
 pub enum ConsensusError {
@@ -179,6 +222,21 @@ pub enum ConsensusError {
     #[error("mismatched block requests root: {0}")]
     BodyRequestsRootDiff(GotExpectedBoxed<B256>),
 
+    /// Error when the root of the EIP-6110 deposit requests parsed from the receipts is different
+    /// from the root of the deposit requests in the block.
+    #[error("mismatched block deposit requests root: {0}")]
+    BodyDepositRequestsRootDiff(GotExpectedBoxed<B256>),
+
+    /// Error when the root of the EIP-7002 withdrawal requests of the execution is different from
+    /// the root of the withdrawal requests in the block.
+    #[error("mismatched block withdrawal requests root: {0}")]
+    BodyWithdrawalRequestsRootDiff(GotExpectedBoxed<B256>),
+
+    /// Error when the root of the EIP-7251 consolidation requests of the execution is different
+    /// from the root of the consolidation requests in the block.
+    #[error("mismatched block consolidation requests root: {0}")]
+    BodyConsolidationRequestsRootDiff(GotExpectedBoxed<B256>),
+
     /// Error when a block with a specific hash and number is already known.
     #[error("block with [hash={hash}, number={number}] is already known")]
     BlockKnown {
@@ -246,6 +304,18 @@ pub enum ConsensusError {
     #[error("ommer root after merge is not empty")]
     TheMergeOmmerRootIsNotEmpty,
 
+    /// Error when the ethash mix digest of a proof-of-work header is different from its mix hash.
+    #[error("mismatched ethash mix digest: {0}")]
+    EthashMixDigestDiff(GotExpectedBoxed<B256>),
+
+    /// Error when the ethash result of a proof-of-work header exceeds the target of its
+    /// difficulty.
+    #[error("ethash seal does not satisfy the difficulty {difficulty}")]
+    EthashInvalidSeal {
+        /// The difficulty of the header.
+        difficulty: U256,
+    },
+
     /// Error when the withdrawals root is missing.
     #[error("missing withdrawals root")]
     WithdrawalsRootMissing,
@@ -399,4 +469,4 @@ impl ConsensusError {
 /// `HeaderConsensusError` combines a `ConsensusError` with the `SealedHeader` it relates to.
 #[derive(thiserror_no_std::Error, Debug)]
 #[error("Consensus error: {0}, Invalid header: {1:?}")]
-pub struct HeaderConsensusError(ConsensusError, SealedHeader);
+pub struct HeaderConsensusError(pub ConsensusError, pub SealedHeader);
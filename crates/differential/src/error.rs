@@ -0,0 +1,38 @@
+use reth_execution_errors::BlockExecutionError;
+use reth_storage_errors::provider::ProviderError;
+
+/// Error of the differential execution of a block.
+#[derive(Debug, thiserror::Error)]
+pub enum DifferentialError {
+    /// The block could not be executed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The state before a transaction could not be read.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// The request to the other client failed.
+    #[error(transparent)]
+    Rpc(#[from] jsonrpsee::core::ClientError),
+    /// The other client doesn't have the receipts of the block.
+    #[error("receipts of block {0} not found")]
+    MissingReceipts(u64),
+    /// The other client returned a different number of traces and receipts.
+    #[error("got {traces} traces but {receipts} receipts")]
+    TraceCountMismatch {
+        /// Number of traces.
+        traces: usize,
+        /// Number of receipts.
+        receipts: usize,
+    },
+    /// The other client failed to trace a transaction.
+    #[error("failed to trace transaction {index}: {message}")]
+    TraceFailed {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// The error message of the other client.
+        message: String,
+    },
+    /// The other client returned a trace that is not in `diffMode`.
+    #[error("trace of transaction {0} is not in diff mode")]
+    UnexpectedTrace(usize),
+}
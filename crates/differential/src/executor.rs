@@ -0,0 +1,266 @@
+use crate::{AccountChange, AccountDiff, DifferentialError, StateDiff, TransactionOutcome};
+use reth_chainspec::{ChainSpec, EthereumHardforks};
+use reth_evm::ConfigureEvm;
+use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+use reth_primitives::{BlockWithSenders, B256, U256};
+use reth_revm::{
+    db::{State, StateBuilder},
+    primitives::{
+        BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, EvmState, ResultAndState,
+    },
+    system_calls::{SystemCallBlock, SystemCaller},
+    Database, DatabaseCommit,
+};
+use reth_storage_errors::provider::ProviderError;
+use std::{fmt::Display, sync::Arc};
+
+/// Executes the transactions of a block and records the [`TransactionOutcome`] of each, to be
+/// compared with the outcomes of other clients.
+///
+/// The pre-block system calls are applied before the transactions, but their state changes are not
+/// recorded, the same as in the traces of the transactions of a block. Post-block changes, e.g.
+/// block rewards and withdrawals, are not applied.
+#[derive(Debug, Clone)]
+pub struct DifferentialExecutor<EvmConfig> {
+    /// The chain spec of the chain.
+    chain_spec: Arc<ChainSpec>,
+    /// How to create an EVM.
+    evm_config: EvmConfig,
+    /// Applies the pre-block system calls.
+    system_caller: SystemCaller,
+}
+
+impl<EvmConfig> DifferentialExecutor<EvmConfig> {
+    /// Creates a new executor for the chain.
+    pub fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
+        let system_caller = SystemCaller::new(chain_spec.clone());
+        Self { chain_spec, evm_config, system_caller }
+    }
+
+    /// Sets the system caller applying the pre-block system calls, e.g. with additional hooks of
+    /// the chain.
+    pub fn with_system_caller(mut self, system_caller: SystemCaller) -> Self {
+        self.system_caller = system_caller;
+        self
+    }
+}
+
+impl<EvmConfig: ConfigureEvm> DifferentialExecutor<EvmConfig> {
+    /// Executes the transactions of the block on top of the state of its parent, and returns the
+    /// outcome of each transaction.
+    pub fn execute<DB>(
+        &self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+        db: DB,
+    ) -> Result<Vec<TransactionOutcome>, DifferentialError>
+    where
+        DB: Database<Error: Into<ProviderError> + Display>,
+    {
+        let state_clear = self.chain_spec.is_spurious_dragon_active_at_block(block.number);
+        let mut state = StateBuilder::new_with_database(db).without_state_clear().build();
+        state.set_state_clear_flag(state_clear);
+
+        let mut cfg = CfgEnvWithHandlerCfg::new(Default::default(), Default::default());
+        let mut block_env = BlockEnv::default();
+        EvmConfig::fill_cfg_and_block_env(
+            &mut cfg,
+            &mut block_env,
+            &self.chain_spec,
+            &block.header,
+            total_difficulty,
+        );
+        let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, Default::default());
+        let mut evm = self.evm_config.evm_with_env(&mut state, env);
+
+        self.system_caller.apply_pre_block(&SystemCallBlock::from(&block.header), &mut evm)?;
+
+        let mut outcomes = Vec::with_capacity(block.body.len());
+        for (sender, transaction) in block.transactions_with_sender() {
+            self.evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
+            let ResultAndState { result, state } = evm.transact().map_err(|err| {
+                let error = match err {
+                    EVMError::Transaction(e) => EVMError::Transaction(e),
+                    EVMError::Header(e) => EVMError::Header(e),
+                    EVMError::Database(e) => EVMError::Database(e.into()),
+                    EVMError::Custom(e) => EVMError::Custom(e),
+                    EVMError::Precompile(e) => EVMError::Precompile(e),
+                };
+                BlockExecutionError::from(BlockValidationError::EVM {
+                    hash: transaction.recalculate_hash(),
+                    error: Box::new(error),
+                })
+            })?;
+
+            let state_diff = state_diff(evm.db_mut(), &state, state_clear)?;
+            evm.db_mut().commit(state);
+
+            outcomes.push(TransactionOutcome {
+                tx_hash: transaction.hash(),
+                success: Some(result.is_success()),
+                gas_used: result.gas_used(),
+                logs: result.into_logs(),
+                state_diff,
+            });
+        }
+        Ok(outcomes)
+    }
+}
+
+/// Returns the changes of the state by a transaction, compared to the state before the transaction
+/// which is not committed yet.
+fn state_diff<DB>(
+    db: &mut State<DB>,
+    state: &EvmState,
+    state_clear: bool,
+) -> Result<StateDiff, DifferentialError>
+where
+    DB: Database<Error: Into<ProviderError>>,
+{
+    let mut state_diff = StateDiff::new();
+    for (address, account) in state {
+        if !account.is_touched() {
+            continue
+        }
+
+        let previous = db.basic(*address).map_err(|err| DifferentialError::Provider(err.into()))?;
+        if account.is_selfdestructed() || (state_clear && account.is_empty()) {
+            // accounts that didn't exist before are not reported as deleted
+            if previous.is_some() {
+                state_diff.insert(*address, AccountDiff::Deleted);
+            }
+            continue
+        }
+
+        let previous = previous.unwrap_or_default();
+        let info = &account.info;
+        let code = if info.code_hash == previous.code_hash {
+            None
+        } else if let Some(code) = &info.code {
+            Some(code.original_bytes())
+        } else {
+            Some(
+                db.code_by_hash(info.code_hash)
+                    .map_err(|err| DifferentialError::Provider(err.into()))?
+                    .original_bytes(),
+            )
+        };
+        let change = AccountChange {
+            balance: (info.balance != previous.balance).then_some(info.balance),
+            nonce: (info.nonce != previous.nonce).then_some(info.nonce),
+            code,
+            storage: account
+                .changed_storage_slots()
+                .map(|(slot, value)| (B256::from(*slot), value.present_value()))
+                .collect(),
+        };
+        if !change.is_empty() {
+            state_diff.insert(*address, AccountDiff::Changed(change));
+        }
+    }
+    Ok(state_diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{ChainSpecBuilder, MAINNET};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        public_key_to_address, Account, Address, Block, Bytes, Header, Transaction, TxKind,
+        TxLegacy,
+    };
+    use reth_revm::{database::StateProviderDatabase, test_utils::StateProviderTest};
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use secp256k1::{Keypair, Secp256k1};
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn execute_transactions() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).berlin_activated().build());
+        let executor = DifferentialExecutor::new(chain_spec.clone(), EthEvmConfig::default());
+
+        let secp = Secp256k1::new();
+        let sender_key_pair = Keypair::new(&secp, &mut generators::rng());
+        let sender = public_key_to_address(sender_key_pair.public_key());
+        let contract = Address::repeat_byte(2);
+        // PUSH1 0x2a PUSH1 0x00 SSTORE PUSH1 0x00 PUSH1 0x01 SSTORE STOP
+        let code =
+            Bytes::from_static(&[0x60, 0x2a, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x01, 0x55, 0x00]);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            sender,
+            Account { balance: U256::from(1_000_000_000), ..Default::default() },
+            None,
+            HashMap::new(),
+        );
+        db.insert_account(
+            contract,
+            Account { nonce: 1, ..Default::default() },
+            Some(code),
+            HashMap::from([(B256::with_last_byte(1), U256::from(5))]),
+        );
+
+        let transaction = sign_tx_with_key_pair(
+            sender_key_pair,
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(chain_spec.chain.id()),
+                nonce: 0,
+                gas_price: 1,
+                gas_limit: 100_000,
+                to: TxKind::Call(contract),
+                value: U256::ZERO,
+                input: Bytes::new(),
+            }),
+        );
+        let tx_hash = transaction.hash();
+        let block = Block {
+            header: Header { number: 1, gas_limit: 1_000_000, ..Default::default() },
+            body: vec![transaction],
+            ..Default::default()
+        }
+        .with_recovered_senders()
+        .unwrap();
+
+        let outcomes =
+            executor.execute(&block, U256::ZERO, StateProviderDatabase::new(&db)).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        let outcome = &outcomes[0];
+        assert_eq!(outcome.tx_hash, tx_hash);
+        assert_eq!(outcome.success, Some(true));
+        assert!(outcome.logs.is_empty());
+
+        let fee = U256::from(outcome.gas_used);
+        assert_eq!(
+            outcome.state_diff,
+            StateDiff::from([
+                (
+                    Address::ZERO,
+                    AccountDiff::Changed(AccountChange {
+                        balance: Some(fee),
+                        ..Default::default()
+                    })
+                ),
+                (
+                    sender,
+                    AccountDiff::Changed(AccountChange {
+                        balance: Some(U256::from(1_000_000_000) - fee),
+                        nonce: Some(1),
+                        ..Default::default()
+                    })
+                ),
+                (
+                    contract,
+                    AccountDiff::Changed(AccountChange {
+                        storage: BTreeMap::from([
+                            (B256::ZERO, U256::from(0x2a)),
+                            (B256::with_last_byte(1), U256::ZERO),
+                        ]),
+                        ..Default::default()
+                    })
+                ),
+            ])
+        );
+    }
+}
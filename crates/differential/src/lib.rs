@@ -0,0 +1,33 @@
+//! Differential execution of blocks against other clients.
+//!
+//! A block is re-executed locally, recording the status, gas used, logs and state changes of each
+//! transaction. These are compared with the receipts and the `prestateTracer` traces of the block
+//! fetched from another client, and the differences are collected in a [`DivergenceReport`].
+//!
+//! This is useful to pinpoint the first diverging transaction, account or storage slot when
+//! investigating consensus bugs.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod error;
+pub use error::DifferentialError;
+
+mod executor;
+pub use executor::DifferentialExecutor;
+
+mod outcome;
+pub use outcome::{
+    state_diff_from_prestate, AccountChange, AccountDiff, StateDiff, TransactionOutcome,
+};
+
+mod remote;
+pub use remote::fetch_remote_outcomes;
+
+mod report;
+pub use report::{Divergence, DivergenceReport};
@@ -0,0 +1,176 @@
+use alloy_consensus::Eip658Value;
+use reth_primitives::{Address, Bytes, Log, TxHash, B256, U256};
+use reth_rpc_types::{
+    trace::geth::{AccountState, DiffMode},
+    AnyTransactionReceipt,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The changes of the state by a transaction, keyed by account.
+pub type StateDiff = BTreeMap<Address, AccountDiff>;
+
+/// The change of an account by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountDiff {
+    /// The account was created or modified.
+    Changed(AccountChange),
+    /// The account was deleted, e.g. by a self-destruct or as an empty account touched by the
+    /// transaction.
+    Deleted,
+}
+
+/// The new values of the modified fields of an account.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountChange {
+    /// The new balance, if changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// The new nonce, if changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// The new code, if changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The new values of the changed storage slots, including cleared slots.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, U256>,
+}
+
+impl AccountChange {
+    /// Returns `true` if no field of the account changed.
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() &&
+            self.nonce.is_none() &&
+            self.code.is_none() &&
+            self.storage.is_empty()
+    }
+}
+
+/// The outcome of a transaction, as compared between clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionOutcome {
+    /// Hash of the transaction.
+    pub tx_hash: TxHash,
+    /// Whether the transaction succeeded.
+    ///
+    /// This is unknown for receipts of other clients containing a post-state root instead of a
+    /// status, i.e. before [EIP-658](https://eips.ethereum.org/EIPS/eip-658).
+    pub success: Option<bool>,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Logs emitted by the transaction.
+    pub logs: Vec<Log>,
+    /// Changes of the state by the transaction.
+    pub state_diff: StateDiff,
+}
+
+impl TransactionOutcome {
+    /// Creates the outcome of a transaction executed by another client, from its receipt and the
+    /// `diffMode` output of the `prestateTracer`.
+    pub fn from_remote(receipt: &AnyTransactionReceipt, diff: DiffMode) -> Self {
+        let envelope = &receipt.inner.inner;
+        let success = match envelope.inner.receipt.status {
+            Eip658Value::Eip658(success) => Some(success),
+            Eip658Value::PostState(_) => None,
+        };
+        Self {
+            tx_hash: receipt.transaction_hash,
+            success,
+            gas_used: receipt.gas_used as u64,
+            logs: envelope.logs().iter().map(|log| log.inner.clone()).collect(),
+            state_diff: state_diff_from_prestate(diff),
+        }
+    }
+}
+
+/// Converts the `diffMode` output of the `prestateTracer` to a [`StateDiff`].
+///
+/// The tracer omits the accounts and storage slots that are deleted by the transaction from the
+/// post state: accounts only in the pre state are deleted, and storage slots only in the pre state
+/// of an account are cleared.
+pub fn state_diff_from_prestate(diff: DiffMode) -> StateDiff {
+    let DiffMode { mut pre, post } = diff;
+    let mut state_diff = StateDiff::new();
+    for (address, account) in post {
+        let AccountState { balance, code, nonce, storage } = account;
+        let mut change = AccountChange {
+            balance,
+            nonce,
+            code,
+            storage: storage
+                .into_iter()
+                .map(|(slot, value)| (slot, U256::from_be_bytes(value.0)))
+                .collect(),
+        };
+        if let Some(pre) = pre.remove(&address) {
+            for slot in pre.storage.into_keys() {
+                change.storage.entry(slot).or_insert(U256::ZERO);
+            }
+        }
+        state_diff.insert(address, AccountDiff::Changed(change));
+    }
+    state_diff.extend(pre.into_keys().map(|address| (address, AccountDiff::Deleted)));
+    state_diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prestate_diff() {
+        let diff: DiffMode = serde_json::from_value(serde_json::json!({
+            "pre": {
+                "0x0000000000000000000000000000000000000001": {
+                    "balance": "0x10",
+                    "nonce": 1,
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000005",
+                        "0x0000000000000000000000000000000000000000000000000000000000000002": "0x0000000000000000000000000000000000000000000000000000000000000006"
+                    }
+                },
+                "0x0000000000000000000000000000000000000002": { "balance": "0x1" }
+            },
+            "post": {
+                "0x0000000000000000000000000000000000000001": {
+                    "balance": "0x20",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000007"
+                    }
+                },
+                "0x0000000000000000000000000000000000000003": { "balance": "0x2", "code": "0x00" }
+            }
+        }))
+        .unwrap();
+
+        let state_diff = state_diff_from_prestate(diff);
+        assert_eq!(
+            state_diff,
+            StateDiff::from([
+                (
+                    Address::with_last_byte(1),
+                    AccountDiff::Changed(AccountChange {
+                        balance: Some(U256::from(0x20)),
+                        storage: BTreeMap::from([
+                            (B256::with_last_byte(1), U256::from(7)),
+                            (B256::with_last_byte(2), U256::ZERO),
+                        ]),
+                        ..Default::default()
+                    })
+                ),
+                (Address::with_last_byte(2), AccountDiff::Deleted),
+                (
+                    Address::with_last_byte(3),
+                    AccountDiff::Changed(AccountChange {
+                        balance: Some(U256::from(2)),
+                        code: Some(Bytes::from_static(&[0x00])),
+                        ..Default::default()
+                    })
+                ),
+            ])
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{DifferentialError, TransactionOutcome};
+use jsonrpsee::core::{client::ClientT, rpc_params};
+use reth_primitives::BlockNumberOrTag;
+use reth_rpc_types::{
+    trace::{
+        common::TraceResult,
+        geth::{
+            GethDebugBuiltInTracerType, GethDebugTracingOptions, PreStateConfig, PreStateFrame,
+        },
+    },
+    AnyTransactionReceipt,
+};
+
+/// Fetches the outcomes of the transactions of a block from another client.
+///
+/// The state changes are taken from the `prestateTracer` output of `debug_traceBlockByNumber` in
+/// `diffMode`, the status, gas used and logs from the receipts of `eth_getBlockReceipts`.
+pub async fn fetch_remote_outcomes<C>(
+    client: &C,
+    block_number: u64,
+) -> Result<Vec<TransactionOutcome>, DifferentialError>
+where
+    C: ClientT + Sync,
+{
+    let block = BlockNumberOrTag::Number(block_number);
+    let options = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::PreStateTracer.into())
+        .with_prestate_config(PreStateConfig { diff_mode: Some(true) });
+
+    let traces: Vec<TraceResult<PreStateFrame, String>> =
+        client.request("debug_traceBlockByNumber", rpc_params![block, options]).await?;
+    let receipts: Option<Vec<AnyTransactionReceipt>> =
+        client.request("eth_getBlockReceipts", rpc_params![block]).await?;
+    let receipts = receipts.ok_or(DifferentialError::MissingReceipts(block_number))?;
+
+    if traces.len() != receipts.len() {
+        return Err(DifferentialError::TraceCountMismatch {
+            traces: traces.len(),
+            receipts: receipts.len(),
+        })
+    }
+
+    traces
+        .into_iter()
+        .zip(&receipts)
+        .enumerate()
+        .map(|(index, (trace, receipt))| match trace {
+            TraceResult::Success { result: PreStateFrame::Diff(diff), .. } => {
+                Ok(TransactionOutcome::from_remote(receipt, diff))
+            }
+            TraceResult::Success { .. } => Err(DifferentialError::UnexpectedTrace(index)),
+            TraceResult::Error { error, .. } => {
+                Err(DifferentialError::TraceFailed { index, message: error })
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,305 @@
+use crate::{AccountDiff, TransactionOutcome};
+use reth_primitives::{Address, Log, TxHash, B256};
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt};
+
+/// A divergence between the outcome of a block executed locally and by another client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Divergence {
+    /// The block has a different number of transactions.
+    TransactionCount {
+        /// Number of transactions executed locally.
+        local: usize,
+        /// Number of transactions of the other client.
+        remote: usize,
+    },
+    /// A different transaction is at the index of the block.
+    ///
+    /// The outcomes of the transaction are not compared.
+    TransactionHash {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Hash of the transaction executed locally.
+        local: TxHash,
+        /// Hash of the transaction of the other client.
+        remote: TxHash,
+    },
+    /// The transaction succeeded on one client but failed on the other.
+    Status {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Hash of the transaction.
+        tx_hash: TxHash,
+        /// Whether the transaction succeeded locally.
+        local: bool,
+        /// Whether the transaction succeeded on the other client.
+        remote: bool,
+    },
+    /// The transaction used a different amount of gas.
+    GasUsed {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Hash of the transaction.
+        tx_hash: TxHash,
+        /// Gas used locally.
+        local: u64,
+        /// Gas used on the other client.
+        remote: u64,
+    },
+    /// The transaction emitted a different number of logs.
+    ///
+    /// The individual logs are not compared.
+    LogCount {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Hash of the transaction.
+        tx_hash: TxHash,
+        /// Number of logs emitted locally.
+        local: usize,
+        /// Number of logs emitted on the other client.
+        remote: usize,
+    },
+    /// A log of the transaction differs.
+    Log {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Hash of the transaction.
+        tx_hash: TxHash,
+        /// Index of the log in the logs of the transaction.
+        log_index: usize,
+        /// The log emitted locally.
+        local: Log,
+        /// The log emitted on the other client.
+        remote: Log,
+    },
+    /// The transaction changed an account differently.
+    Account {
+        /// Index of the transaction in the block.
+        index: usize,
+        /// Hash of the transaction.
+        tx_hash: TxHash,
+        /// Address of the account.
+        address: Address,
+        /// The change of the account locally, if changed.
+        local: Option<AccountDiff>,
+        /// The change of the account on the other client, if changed.
+        remote: Option<AccountDiff>,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TransactionCount { local, remote } => {
+                write!(f, "transaction count: local {local}, remote {remote}")
+            }
+            Self::TransactionHash { index, local, remote } => {
+                write!(f, "transaction {index}: hash local {local}, remote {remote}")
+            }
+            Self::Status { index, tx_hash, local, remote } => {
+                write!(f, "transaction {index} ({tx_hash}): success local {local}, remote {remote}")
+            }
+            Self::GasUsed { index, tx_hash, local, remote } => {
+                write!(f, "transaction {index} ({tx_hash}): gas used local {local}, remote {remote}")
+            }
+            Self::LogCount { index, tx_hash, local, remote } => {
+                write!(f, "transaction {index} ({tx_hash}): log count local {local}, remote {remote}")
+            }
+            Self::Log { index, tx_hash, log_index, local, remote } => write!(
+                f,
+                "transaction {index} ({tx_hash}): log {log_index} local {local:?}, remote {remote:?}"
+            ),
+            Self::Account { index, tx_hash, address, local, remote } => write!(
+                f,
+                "transaction {index} ({tx_hash}): account {address} local {local:?}, remote {remote:?}"
+            ),
+        }
+    }
+}
+
+/// The divergences between the outcome of a block executed locally and by another client.
+///
+/// Divergences are ordered by transaction, then by receipt fields, logs and accounts, so that the
+/// report of a block is deterministic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DivergenceReport {
+    /// Number of the block.
+    pub block_number: u64,
+    /// Hash of the block.
+    pub block_hash: B256,
+    /// Number of compared transactions.
+    pub transactions: usize,
+    /// The divergences, empty if the outcomes match.
+    pub divergences: Vec<Divergence>,
+}
+
+impl DivergenceReport {
+    /// Compares the outcomes of the transactions of the block executed locally and by another
+    /// client.
+    pub fn new(
+        block_number: u64,
+        block_hash: B256,
+        local: &[TransactionOutcome],
+        remote: &[TransactionOutcome],
+    ) -> Self {
+        let mut divergences = Vec::new();
+        if local.len() != remote.len() {
+            divergences
+                .push(Divergence::TransactionCount { local: local.len(), remote: remote.len() });
+        }
+
+        let transactions = local.len().min(remote.len());
+        for (index, (local, remote)) in local.iter().zip(remote).enumerate() {
+            compare_transaction(index, local, remote, &mut divergences);
+        }
+
+        Self { block_number, block_hash, transactions, divergences }
+    }
+
+    /// Returns `true` if the outcomes of the block match.
+    pub fn is_empty(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    /// Returns the first divergence, which is usually the cause of the following ones.
+    pub fn first(&self) -> Option<&Divergence> {
+        self.divergences.first()
+    }
+}
+
+/// Compares the outcome of a transaction, and appends the divergences.
+fn compare_transaction(
+    index: usize,
+    local: &TransactionOutcome,
+    remote: &TransactionOutcome,
+    divergences: &mut Vec<Divergence>,
+) {
+    if local.tx_hash != remote.tx_hash {
+        divergences.push(Divergence::TransactionHash {
+            index,
+            local: local.tx_hash,
+            remote: remote.tx_hash,
+        });
+        return
+    }
+    let tx_hash = local.tx_hash;
+
+    if let (Some(local), Some(remote)) = (local.success, remote.success) {
+        if local != remote {
+            divergences.push(Divergence::Status { index, tx_hash, local, remote });
+        }
+    }
+
+    if local.gas_used != remote.gas_used {
+        divergences.push(Divergence::GasUsed {
+            index,
+            tx_hash,
+            local: local.gas_used,
+            remote: remote.gas_used,
+        });
+    }
+
+    if local.logs.len() == remote.logs.len() {
+        for (log_index, (local, remote)) in local.logs.iter().zip(&remote.logs).enumerate() {
+            if local != remote {
+                divergences.push(Divergence::Log {
+                    index,
+                    tx_hash,
+                    log_index,
+                    local: local.clone(),
+                    remote: remote.clone(),
+                });
+            }
+        }
+    } else {
+        divergences.push(Divergence::LogCount {
+            index,
+            tx_hash,
+            local: local.logs.len(),
+            remote: remote.logs.len(),
+        });
+    }
+
+    let addresses: BTreeSet<_> =
+        local.state_diff.keys().chain(remote.state_diff.keys()).copied().collect();
+    for address in addresses {
+        let local = local.state_diff.get(&address);
+        let remote = remote.state_diff.get(&address);
+        if local != remote {
+            divergences.push(Divergence::Account {
+                index,
+                tx_hash,
+                address,
+                local: local.cloned(),
+                remote: remote.cloned(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccountChange, StateDiff};
+    use reth_primitives::{Bytes, U256};
+
+    fn outcome(tx_hash: TxHash) -> TransactionOutcome {
+        TransactionOutcome {
+            tx_hash,
+            success: Some(true),
+            gas_used: 21_000,
+            logs: vec![Log::new_unchecked(Address::ZERO, vec![], Bytes::new())],
+            state_diff: StateDiff::from([(
+                Address::ZERO,
+                AccountDiff::Changed(AccountChange {
+                    balance: Some(U256::from(1)),
+                    ..Default::default()
+                }),
+            )]),
+        }
+    }
+
+    #[test]
+    fn compare_outcomes() {
+        let local = vec![outcome(TxHash::with_last_byte(1)), outcome(TxHash::with_last_byte(2))];
+        let report = DivergenceReport::new(1, B256::ZERO, &local, &local);
+        assert!(report.is_empty());
+
+        let mut remote = local.clone();
+        remote[0].success = None;
+        remote[1].gas_used = 22_000;
+        remote[1].logs.clear();
+        remote[1].state_diff.insert(Address::with_last_byte(1), AccountDiff::Deleted);
+        remote.push(outcome(TxHash::with_last_byte(3)));
+
+        let report = DivergenceReport::new(1, B256::ZERO, &local, &remote);
+        assert_eq!(report.transactions, 2);
+        assert_eq!(
+            report.divergences,
+            vec![
+                Divergence::TransactionCount { local: 2, remote: 3 },
+                Divergence::GasUsed {
+                    index: 1,
+                    tx_hash: TxHash::with_last_byte(2),
+                    local: 21_000,
+                    remote: 22_000
+                },
+                Divergence::LogCount {
+                    index: 1,
+                    tx_hash: TxHash::with_last_byte(2),
+                    local: 1,
+                    remote: 0
+                },
+                Divergence::Account {
+                    index: 1,
+                    tx_hash: TxHash::with_last_byte(2),
+                    address: Address::with_last_byte(1),
+                    local: None,
+                    remote: Some(AccountDiff::Deleted)
+                },
+            ]
+        );
+    }
+}
@@ -6,7 +6,7 @@ use alloy_network::{eip2718::Encodable2718, EthereumWallet, TransactionBuilder};
 use alloy_rpc_types::{TransactionInput, TransactionRequest};
 use alloy_signer_local::PrivateKeySigner;
 use eyre::Ok;
-use reth_primitives::{hex, Address, Bytes, U256};
+use reth_primitives::{hex, Address, Bytes, TxKind, U256};
 
 use reth_primitives::B256;
 
@@ -25,6 +25,22 @@ impl TransactionTestContext {
         signed.encoded_2718().into()
     }
 
+    /// Creates a transfer with the given nonce to a recipient derived from that nonce, and signs
+    /// it, returning bytes.
+    ///
+    /// Unlike [`Self::transfer_tx_bytes`], the resulting transaction only depends on its inputs,
+    /// so chains built from it are reproducible across runs.
+    pub async fn deterministic_transfer_tx_bytes(
+        chain_id: u64,
+        wallet: PrivateKeySigner,
+        nonce: u64,
+    ) -> Bytes {
+        let mut tx = tx(chain_id, None, nonce);
+        tx.to = Some(TxKind::Call(Address::left_padding_from(&(nonce + 1).to_be_bytes())));
+        let signer = EthereumWallet::from(wallet);
+        tx.build(&signer).await.unwrap().encoded_2718().into()
+    }
+
     /// Creates a tx with blob sidecar and sign it
     pub async fn tx_with_blobs(
         chain_id: u64,
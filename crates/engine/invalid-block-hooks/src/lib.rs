@@ -0,0 +1,25 @@
+//! Hooks invoked with blocks that failed validation.
+//!
+//! The [`InvalidBlockWitnessHook`] dumps blocks that failed to execute or failed validation after
+//! execution, e.g. because of a state root mismatch, to a directory for offline analysis. The
+//! block is re-executed on top of its parent to record the [`ExecutionWitness`] of the block, and
+//! the receipts and trie updates of the re-execution are compared with the ones of the original
+//! validation.
+//!
+//! [`ExecutionWitness`]: reth_stateless::ExecutionWitness
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod report;
+pub use report::{
+    diff_receipts, diff_trie_updates, InvalidBlockReport, ReceiptDiff, TrieUpdateDiff,
+};
+
+mod witness;
+pub use witness::InvalidBlockWitnessHook;
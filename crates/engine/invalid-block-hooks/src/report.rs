@@ -0,0 +1,195 @@
+use reth_blockchain_tree::InvalidBlock;
+use reth_primitives::{BlockHash, BlockNumber, Receipt, SealedBlockWithSenders, TxHash, B256};
+use reth_trie::updates::{TrieKey, TrieOp, TrieUpdates};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Summary of a block that failed validation, written to `report.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidBlockReport {
+    /// Number of the block.
+    pub number: BlockNumber,
+    /// Hash of the block.
+    pub hash: BlockHash,
+    /// Hash of the parent of the block.
+    pub parent_hash: BlockHash,
+    /// The reason the block is invalid.
+    pub error: String,
+    /// The gas used according to the header.
+    pub header_gas_used: u64,
+    /// The gas used by the execution of the block, if it was executed successfully.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// The state root according to the header.
+    pub header_state_root: B256,
+    /// The state root computed during validation, if it was computed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_root: Option<B256>,
+    /// The state root computed from the re-execution of the block, if it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reexecuted_state_root: Option<B256>,
+    /// Why the block could not be re-executed, or its witness not be recorded, if so.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reexecution_error: Option<String>,
+    /// Number of transactions whose receipts differ between the validation and the
+    /// re-execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt_diffs: Option<usize>,
+    /// Number of trie nodes whose updates differ between the validation and the re-execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trie_update_diffs: Option<usize>,
+}
+
+impl InvalidBlockReport {
+    /// Creates the report of the invalid block, without the results of its re-execution.
+    pub fn new(invalid: &InvalidBlock<'_>) -> Self {
+        let block = invalid.block;
+        Self {
+            number: block.number,
+            hash: block.hash(),
+            parent_hash: block.parent_hash,
+            error: invalid.error.to_string(),
+            header_gas_used: block.gas_used,
+            gas_used: invalid.output.map(|output| output.gas_used),
+            header_state_root: block.state_root,
+            state_root: invalid.state_root,
+            reexecuted_state_root: None,
+            reexecution_error: None,
+            receipt_diffs: None,
+            trie_update_diffs: None,
+        }
+    }
+}
+
+/// The receipts of a transaction that differ between the validation and the re-execution of a
+/// block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptDiff {
+    /// Index of the transaction in the block.
+    pub index: usize,
+    /// Hash of the transaction.
+    pub tx_hash: TxHash,
+    /// The receipt of the validation, if the transaction was executed.
+    pub original: Option<Receipt>,
+    /// The receipt of the re-execution, if the transaction was executed.
+    pub reexecuted: Option<Receipt>,
+}
+
+/// Returns the receipts of the transactions of the block that differ between the original and the
+/// re-execution, ordered by transaction index.
+pub fn diff_receipts(
+    block: &SealedBlockWithSenders,
+    original: &[Receipt],
+    reexecuted: &[Receipt],
+) -> Vec<ReceiptDiff> {
+    block
+        .body
+        .iter()
+        .enumerate()
+        .filter_map(|(index, tx)| {
+            let original = original.get(index);
+            let reexecuted = reexecuted.get(index);
+            (original != reexecuted).then(|| ReceiptDiff {
+                index,
+                tx_hash: tx.hash(),
+                original: original.cloned(),
+                reexecuted: reexecuted.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// The update of a trie node that differs between the validation and the re-execution of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrieUpdateDiff {
+    /// The key of the trie node.
+    pub key: TrieKey,
+    /// The update of the validation, if any.
+    pub original: Option<TrieOp>,
+    /// The update of the re-execution, if any.
+    pub reexecuted: Option<TrieOp>,
+}
+
+/// Returns the updates of trie nodes that differ between the original and the re-execution,
+/// ordered by key.
+pub fn diff_trie_updates(original: &TrieUpdates, reexecuted: &TrieUpdates) -> Vec<TrieUpdateDiff> {
+    original
+        .keys()
+        .chain(reexecuted.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|key| {
+            let original = original.get(key);
+            let reexecuted = reexecuted.get(key);
+            (original != reexecuted).then(|| TrieUpdateDiff {
+                key: key.clone(),
+                original: original.cloned(),
+                reexecuted: reexecuted.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Block, TransactionSigned, TxType};
+    use reth_trie::{BranchNodeCompact, StoredNibbles};
+
+    #[test]
+    fn receipts_diff() {
+        let block = SealedBlockWithSenders {
+            block: Block {
+                body: vec![TransactionSigned::default(), TransactionSigned::default()],
+                ..Default::default()
+            }
+            .seal_slow(),
+            senders: vec![Default::default(); 2],
+        };
+        let receipt = Receipt {
+            tx_type: TxType::Legacy,
+            success: true,
+            cumulative_gas_used: 21_000,
+            ..Default::default()
+        };
+        let failed = Receipt { success: false, ..receipt.clone() };
+
+        assert!(diff_receipts(&block, &[receipt.clone()], &[receipt.clone()]).is_empty());
+
+        let diffs = diff_receipts(
+            &block,
+            &[receipt.clone(), receipt.clone()],
+            &[receipt.clone(), failed.clone()],
+        );
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].index, 1);
+        assert_eq!(diffs[0].original, Some(receipt.clone()));
+        assert_eq!(diffs[0].reexecuted, Some(failed));
+
+        // the re-execution failed after the first transaction
+        let diffs = diff_receipts(&block, &[receipt.clone(), receipt], &[]);
+        assert_eq!(diffs.iter().map(|diff| diff.index).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(diffs.iter().all(|diff| diff.reexecuted.is_none()));
+    }
+
+    #[test]
+    fn trie_updates_diff() {
+        let key = |byte: u8| TrieKey::AccountNode(StoredNibbles::from(vec![byte]));
+        let node = TrieOp::Update(BranchNodeCompact::default());
+
+        let original = TrieUpdates::from([(key(1), node.clone()), (key(2), TrieOp::Delete)]);
+        let reexecuted = TrieUpdates::from([(key(1), node.clone()), (key(3), node.clone())]);
+
+        assert!(diff_trie_updates(&original, &original).is_empty());
+        assert_eq!(
+            diff_trie_updates(&original, &reexecuted),
+            vec![
+                TrieUpdateDiff { key: key(2), original: Some(TrieOp::Delete), reexecuted: None },
+                TrieUpdateDiff { key: key(3), original: None, reexecuted: Some(node) },
+            ]
+        );
+    }
+}
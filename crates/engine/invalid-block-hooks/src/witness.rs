@@ -0,0 +1,183 @@
+use crate::{diff_receipts, diff_trie_updates, InvalidBlockReport};
+use reth_blockchain_tree::{InvalidBlock, InvalidBlockHook};
+use reth_db_api::database::Database;
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider};
+use reth_fs_util as fs;
+use reth_primitives::{Receipt, SealedBlockWithSenders, SealedHeader, B256, U256};
+use reth_provider::{BlockHashReader, BlockNumReader, ProviderFactory, StateRootProvider};
+use reth_stateless::{ExecutionWitness, WitnessGenerator, WitnessedExecution};
+use reth_trie::updates::TrieUpdates;
+use serde::Serialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+/// An [`InvalidBlockHook`] that dumps blocks that failed validation to a directory.
+///
+/// Every invalid block is written to a `<number>_<hash>` subdirectory of the output directory,
+/// containing:
+///
+/// - `block.json`: the block, with the senders of its transactions.
+/// - `report.json`: the error, and the gas used and state roots of the header, the validation and
+///   the re-execution, see [`InvalidBlockReport`].
+/// - `witness.json`: the [`ExecutionWitness`] of the block, recorded by re-executing it on top of
+///   its parent.
+/// - `receipts.diff.json`: the receipts of the transactions that differ between the validation and
+///   the re-execution.
+/// - `trie_updates.diff.json`: the trie updates that differ between the state root computed during
+///   validation and the one computed from the re-execution, if the state root was computed.
+///
+/// Proofs of the state are only available for the latest state, so the block is only re-executed
+/// if its parent is the canonical head, which is the case for new payloads extending the chain.
+/// Otherwise only the block and the report are written.
+pub struct InvalidBlockWitnessHook<DB, E> {
+    /// The provider factory to read the state of the parent from.
+    provider_factory: ProviderFactory<DB>,
+    /// The generator re-executing the block to record its witness.
+    witness_generator: WitnessGenerator<E>,
+    /// The directory to write the invalid blocks to.
+    output_directory: PathBuf,
+}
+
+impl<DB, E> InvalidBlockWitnessHook<DB, E> {
+    /// Creates a new hook writing invalid blocks to the given directory.
+    pub const fn new(
+        provider_factory: ProviderFactory<DB>,
+        executor_provider: E,
+        output_directory: PathBuf,
+    ) -> Self {
+        Self {
+            provider_factory,
+            witness_generator: WitnessGenerator::new(executor_provider),
+            output_directory,
+        }
+    }
+}
+
+impl<DB, E> InvalidBlockWitnessHook<DB, E>
+where
+    DB: Database,
+    E: BlockExecutorProvider,
+{
+    /// Writes the invalid block, its report and the results of its re-execution, and returns the
+    /// directory they were written to.
+    fn dump(&self, invalid: InvalidBlock<'_>) -> eyre::Result<PathBuf> {
+        let block = invalid.block;
+        let directory = self.output_directory.join(format!("{}_{}", block.number, block.hash()));
+        fs::create_dir_all(&directory)?;
+        write_json(&directory, "block.json", block)?;
+
+        let mut report = InvalidBlockReport::new(&invalid);
+        match self.reexecute(invalid.parent_header, block) {
+            Ok(reexecution) => {
+                write_json(&directory, "witness.json", &reexecution.witness)?;
+
+                let (reexecuted_receipts, reexecuted_state) = match &reexecution.output {
+                    Ok((output, state_root, trie_updates)) => {
+                        report.reexecuted_state_root = Some(*state_root);
+                        (output.receipts.as_slice(), Some(trie_updates))
+                    }
+                    Err(err) => {
+                        report.reexecution_error = Some(err.to_string());
+                        (&[][..], None)
+                    }
+                };
+
+                let original_receipts =
+                    invalid.output.map(|output| output.receipts.as_slice()).unwrap_or_default();
+                let receipt_diffs = diff_receipts(block, original_receipts, reexecuted_receipts);
+                report.receipt_diffs = Some(receipt_diffs.len());
+                write_json(&directory, "receipts.diff.json", &receipt_diffs)?;
+
+                if let (Some(original), Some(reexecuted)) = (invalid.trie_updates, reexecuted_state)
+                {
+                    let trie_update_diffs = diff_trie_updates(original, reexecuted);
+                    report.trie_update_diffs = Some(trie_update_diffs.len());
+                    write_json(&directory, "trie_updates.diff.json", &trie_update_diffs)?;
+                }
+            }
+            Err(err) => report.reexecution_error = Some(err.to_string()),
+        }
+        write_json(&directory, "report.json", &report)?;
+
+        Ok(directory)
+    }
+
+    /// Re-executes the block on top of the latest state, recording its witness.
+    ///
+    /// Fails if the parent of the block is not the canonical head.
+    fn reexecute(
+        &self,
+        parent: &SealedHeader,
+        block: &SealedBlockWithSenders,
+    ) -> eyre::Result<Reexecution> {
+        let best_number = self.provider_factory.best_block_number()?;
+        if parent.number != best_number ||
+            self.provider_factory.block_hash(parent.number)? != Some(parent.hash())
+        {
+            eyre::bail!("parent block {} is not the canonical head", parent.hash())
+        }
+        let state_provider = self.provider_factory.latest()?;
+
+        let WitnessedExecution { witness, output } = self.witness_generator.execute(
+            &state_provider,
+            &self.provider_factory,
+            &block.clone().unseal(),
+            U256::MAX,
+        )?;
+        let output = output.map_err(eyre::Report::from).and_then(|output| {
+            let (state_root, trie_updates) =
+                state_provider.state_root_with_updates(&output.state)?;
+            Ok((output, state_root, trie_updates))
+        });
+
+        Ok(Reexecution { witness, output })
+    }
+}
+
+impl<DB, E> InvalidBlockHook for InvalidBlockWitnessHook<DB, E>
+where
+    DB: Database + 'static,
+    E: BlockExecutorProvider,
+{
+    fn on_invalid_block(&self, invalid: InvalidBlock<'_>) {
+        let number = invalid.block.number;
+        let hash = invalid.block.hash();
+        match self.dump(invalid) {
+            Ok(directory) => {
+                info!(target: "engine::invalid_block_hooks::witness", number, %hash, ?directory, "Dumped invalid block")
+            }
+            Err(err) => {
+                warn!(target: "engine::invalid_block_hooks::witness", number, %hash, %err, "Failed to dump invalid block")
+            }
+        }
+    }
+}
+
+impl<DB, E> fmt::Debug for InvalidBlockWitnessHook<DB, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidBlockWitnessHook")
+            .field("output_directory", &self.output_directory)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The results of the re-execution of an invalid block.
+struct Reexecution {
+    /// The witness of the state accessed by the re-execution.
+    witness: ExecutionWitness,
+    /// The output of the re-execution, with the state root and trie updates computed from it.
+    output: eyre::Result<(BlockExecutionOutput<Receipt>, B256, TrieUpdates)>,
+}
+
+/// Writes the value as pretty-printed JSON to the file in the directory.
+fn write_json<T: Serialize + ?Sized>(
+    directory: &Path,
+    filename: &str,
+    value: &T,
+) -> eyre::Result<()> {
+    fs::write(directory.join(filename), serde_json::to_vec_pretty(value)?)?;
+    Ok(())
+}
@@ -96,6 +96,14 @@ impl EngineMessageStore {
 
     /// Finds and iterates through any stored engine API message files, ordered by timestamp.
     pub fn engine_messages_iter(&self) -> eyre::Result<impl Iterator<Item = PathBuf>> {
+        Ok(self.timestamped_engine_messages_iter()?.map(|(_, path)| path))
+    }
+
+    /// Finds and iterates through any stored engine API message files together with the time they
+    /// were received at, in milliseconds since the unix epoch, ordered by timestamp.
+    pub fn timestamped_engine_messages_iter(
+        &self,
+    ) -> eyre::Result<impl Iterator<Item = (u64, PathBuf)>> {
         let mut filenames_by_ts = BTreeMap::<u64, Vec<PathBuf>>::default();
         for entry in fs::read_dir(&self.path)? {
             let entry = entry?;
@@ -111,7 +119,9 @@ impl EngineMessageStore {
                 tracing::warn!(target: "engine::store", ?filename, "Skipping non json file");
             }
         }
-        Ok(filenames_by_ts.into_iter().flat_map(|(_, paths)| paths))
+        Ok(filenames_by_ts
+            .into_iter()
+            .flat_map(|(timestamp, paths)| paths.into_iter().map(move |path| (timestamp, path))))
     }
 }
 
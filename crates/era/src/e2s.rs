@@ -0,0 +1,145 @@
+//! The e2store type-length-value container format.
+//!
+//! Each entry is an 8 byte header, made of a little endian `u16` type, a little endian `u32`
+//! length and two reserved zero bytes, followed by the data of the entry.
+
+use crate::EraError;
+use std::io::{self, Read, Write};
+
+/// Length of the header of an entry.
+pub const HEADER_LEN: usize = 8;
+
+/// An e2store entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The type of the entry.
+    pub entry_type: u16,
+    /// The data of the entry.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Creates a new entry.
+    pub const fn new(entry_type: u16, data: Vec<u8>) -> Self {
+        Self { entry_type, data }
+    }
+
+    /// Returns the length of the encoded entry, including its header.
+    pub fn encoded_len(&self) -> usize {
+        HEADER_LEN + self.data.len()
+    }
+}
+
+/// Writes e2store entries, tracking the offset of each entry.
+#[derive(Debug)]
+pub struct E2StoreWriter<W> {
+    writer: W,
+    position: u64,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    /// Creates a new writer at the start of a file.
+    pub const fn new(writer: W) -> Self {
+        Self { writer, position: 0 }
+    }
+
+    /// Returns the offset of the next entry.
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Writes an entry, and returns its offset.
+    pub fn write_entry(&mut self, entry: &Entry) -> io::Result<u64> {
+        let len = u32::try_from(entry.data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "entry data too large"))?;
+
+        let mut header = [0u8; HEADER_LEN];
+        header[..2].copy_from_slice(&entry.entry_type.to_le_bytes());
+        header[2..6].copy_from_slice(&len.to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&entry.data)?;
+
+        let offset = self.position;
+        self.position += entry.encoded_len() as u64;
+        Ok(offset)
+    }
+
+    /// Flushes the writer and returns it.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads e2store entries, tracking the offset of each entry.
+#[derive(Debug)]
+pub struct E2StoreReader<R> {
+    reader: R,
+    position: u64,
+}
+
+impl<R: Read> E2StoreReader<R> {
+    /// Creates a new reader at the start of a file.
+    pub const fn new(reader: R) -> Self {
+        Self { reader, position: 0 }
+    }
+
+    /// Reads the next entry and its offset, or returns `None` at the end of the file.
+    pub fn read_entry(&mut self) -> Result<Option<(u64, Entry)>, EraError> {
+        let mut header = [0u8; HEADER_LEN];
+        let mut read = 0;
+        while read < HEADER_LEN {
+            match self.reader.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(n) => read += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let offset = self.position;
+        if header[6..] != [0, 0] {
+            return Err(EraError::InvalidReserved(offset))
+        }
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+
+        let mut data = vec![0u8; len];
+        self.reader.read_exact(&mut data)?;
+
+        let entry = Entry::new(entry_type, data);
+        self.position += entry.encoded_len() as u64;
+        Ok(Some((offset, entry)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_roundtrip() {
+        let entries = [Entry::new(0x3265, vec![]), Entry::new(0x03, vec![1, 2, 3])];
+
+        let mut writer = E2StoreWriter::new(Vec::new());
+        for entry in &entries {
+            writer.write_entry(entry).unwrap();
+        }
+        assert_eq!(writer.position(), 19);
+        let bytes = writer.into_inner().unwrap();
+        assert_eq!(&bytes[..HEADER_LEN], &[0x65, 0x32, 0, 0, 0, 0, 0, 0]);
+
+        let mut reader = E2StoreReader::new(&bytes[..]);
+        assert_eq!(reader.read_entry().unwrap(), Some((0, entries[0].clone())));
+        assert_eq!(reader.read_entry().unwrap(), Some((8, entries[1].clone())));
+        assert_eq!(reader.read_entry().unwrap(), None);
+
+        let mut reserved = bytes;
+        reserved[7] = 1;
+        assert!(matches!(
+            E2StoreReader::new(&reserved[..]).read_entry(),
+            Err(EraError::InvalidReserved(0))
+        ));
+    }
+}
@@ -0,0 +1,473 @@
+use crate::{
+    e2s::{E2StoreReader, E2StoreWriter, Entry},
+    EraError,
+};
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{hex, Block, BlockBody, BlockNumber, Header, ReceiptWithBloom, B256, U256};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+/// Maximum number of blocks of an era1 file.
+pub const MAX_BLOCKS_PER_ERA1: usize = 8192;
+
+/// Entry type of the version, which starts every era1 file.
+const VERSION: u16 = 0x3265;
+/// Entry type of a snappy compressed RLP encoded header.
+const COMPRESSED_HEADER: u16 = 0x03;
+/// Entry type of a snappy compressed RLP encoded block body.
+const COMPRESSED_BODY: u16 = 0x04;
+/// Entry type of snappy compressed RLP encoded receipts of a block.
+const COMPRESSED_RECEIPTS: u16 = 0x05;
+/// Entry type of the little endian total difficulty of a block.
+const TOTAL_DIFFICULTY: u16 = 0x06;
+/// Entry type of the accumulator of the block hashes and total difficulties.
+const ACCUMULATOR: u16 = 0x07;
+/// Entry type of the block index, which ends every era1 file.
+const BLOCK_INDEX: u16 = 0x3266;
+
+/// A block of an era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The header of the block.
+    pub header: Header,
+    /// The body of the block.
+    pub body: BlockBody,
+    /// The receipts of the block, if archived.
+    pub receipts: Option<Vec<ReceiptWithBloom>>,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+impl Era1Block {
+    /// Creates an era1 block from a block.
+    pub fn from_block(
+        block: Block,
+        receipts: Option<Vec<ReceiptWithBloom>>,
+        total_difficulty: U256,
+    ) -> Self {
+        let Block { header, body, ommers, withdrawals, requests } = block;
+        let body = BlockBody { transactions: body, ommers, withdrawals, requests };
+        Self { header, body, receipts, total_difficulty }
+    }
+
+    /// Converts the era1 block into a block, discarding the receipts and the total difficulty.
+    pub fn into_block(self) -> Block {
+        let BlockBody { transactions, ommers, withdrawals, requests } = self.body;
+        Block { header: self.header, body: transactions, ommers, withdrawals, requests }
+    }
+}
+
+/// Writes the blocks of an era1 file.
+///
+/// The blocks must be consecutive, and either all or none of them must have receipts.
+#[derive(Debug)]
+pub struct Era1Writer<W> {
+    writer: E2StoreWriter<W>,
+    starting_number: Option<BlockNumber>,
+    header_offsets: Vec<u64>,
+    records: Vec<(B256, U256)>,
+    with_receipts: bool,
+}
+
+impl<W: Write> Era1Writer<W> {
+    /// Creates a new writer, and writes the version of the file.
+    pub fn new(writer: W) -> Result<Self, EraError> {
+        let mut writer = E2StoreWriter::new(writer);
+        writer.write_entry(&Entry::new(VERSION, Vec::new()))?;
+        Ok(Self {
+            writer,
+            starting_number: None,
+            header_offsets: Vec::new(),
+            records: Vec::new(),
+            with_receipts: false,
+        })
+    }
+
+    /// Returns the number of written blocks.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if no block was written.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Appends a block to the file.
+    pub fn append(&mut self, block: &Era1Block) -> Result<(), EraError> {
+        if self.records.len() == MAX_BLOCKS_PER_ERA1 {
+            return Err(EraError::TooManyBlocks(MAX_BLOCKS_PER_ERA1))
+        }
+        let number = block.header.number;
+        match self.starting_number {
+            Some(starting_number) => {
+                let expected = starting_number + self.records.len() as u64;
+                if number != expected {
+                    return Err(EraError::NonContiguousBlock { expected, got: number })
+                }
+                if self.with_receipts != block.receipts.is_some() {
+                    return Err(EraError::MissingReceipts(number))
+                }
+            }
+            None => {
+                self.starting_number = Some(number);
+                self.with_receipts = block.receipts.is_some();
+            }
+        }
+
+        let header_offset = self.writer.write_entry(&Entry::new(
+            COMPRESSED_HEADER,
+            compress(&alloy_rlp::encode(&block.header))?,
+        ))?;
+        self.writer.write_entry(&Entry::new(
+            COMPRESSED_BODY,
+            compress(&alloy_rlp::encode(&block.body))?,
+        ))?;
+        if let Some(receipts) = &block.receipts {
+            let mut encoded = Vec::new();
+            receipts.encode(&mut encoded);
+            self.writer.write_entry(&Entry::new(COMPRESSED_RECEIPTS, compress(&encoded)?))?;
+        }
+        self.writer.write_entry(&Entry::new(
+            TOTAL_DIFFICULTY,
+            block.total_difficulty.to_le_bytes::<32>().to_vec(),
+        ))?;
+
+        self.header_offsets.push(header_offset);
+        self.records.push((block.header.hash_slow(), block.total_difficulty));
+        Ok(())
+    }
+
+    /// Writes the accumulator and the block index of the file.
+    ///
+    /// Returns the inner writer and the accumulator root.
+    pub fn finish(mut self) -> Result<(W, B256), EraError> {
+        let accumulator = accumulator_root(&self.records);
+        self.writer.write_entry(&Entry::new(ACCUMULATOR, accumulator.to_vec()))?;
+
+        let index_offset = self.writer.position();
+        let mut index = Vec::with_capacity(16 + 8 * self.header_offsets.len());
+        index.extend_from_slice(&self.starting_number.unwrap_or_default().to_le_bytes());
+        for header_offset in &self.header_offsets {
+            index.extend_from_slice(&(*header_offset as i64 - index_offset as i64).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.header_offsets.len() as u64).to_le_bytes());
+        self.writer.write_entry(&Entry::new(BLOCK_INDEX, index))?;
+
+        Ok((self.writer.into_inner()?, accumulator))
+    }
+}
+
+/// The blocks of an era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1File {
+    /// The number of the first block.
+    pub starting_number: BlockNumber,
+    /// The blocks.
+    pub blocks: Vec<Era1Block>,
+    /// The accumulator root of the blocks.
+    pub accumulator: B256,
+}
+
+impl Era1File {
+    /// Reads an era1 file.
+    ///
+    /// The block index and the accumulator are checked against the blocks of the file.
+    pub fn read<R: Read>(reader: R) -> Result<Self, EraError> {
+        let mut reader = E2StoreReader::new(reader);
+        let mut next_entry = || reader.read_entry()?.ok_or(EraError::MissingBlockIndex);
+
+        let (offset, version) = next_entry()?;
+        expect_entry(offset, &version, VERSION)?;
+
+        let mut blocks = Vec::new();
+        let mut header_offsets = Vec::new();
+        let mut accumulator = None;
+        let (index_offset, index) = loop {
+            let (offset, entry) = next_entry()?;
+            match entry.entry_type {
+                COMPRESSED_HEADER => {
+                    header_offsets.push(offset);
+                    let header = Header::decode(&mut &decompress(&entry.data)?[..])?;
+
+                    let (offset, entry) = next_entry()?;
+                    expect_entry(offset, &entry, COMPRESSED_BODY)?;
+                    let body = BlockBody::decode(&mut &decompress(&entry.data)?[..])?;
+
+                    let (mut offset, mut entry) = next_entry()?;
+                    let mut receipts = None;
+                    if entry.entry_type == COMPRESSED_RECEIPTS {
+                        receipts = Some(Vec::<ReceiptWithBloom>::decode(
+                            &mut &decompress(&entry.data)?[..],
+                        )?);
+                        (offset, entry) = next_entry()?;
+                    }
+
+                    expect_entry(offset, &entry, TOTAL_DIFFICULTY)?;
+                    let total_difficulty: [u8; 32] =
+                        entry.data.as_slice().try_into().map_err(|_| EraError::InvalidLength {
+                            entry_type: TOTAL_DIFFICULTY,
+                            len: entry.data.len(),
+                        })?;
+
+                    blocks.push(Era1Block {
+                        header,
+                        body,
+                        receipts,
+                        total_difficulty: U256::from_le_bytes(total_difficulty),
+                    });
+                }
+                ACCUMULATOR => {
+                    accumulator = Some(B256::try_from(entry.data.as_slice()).map_err(|_| {
+                        EraError::InvalidLength { entry_type: ACCUMULATOR, len: entry.data.len() }
+                    })?);
+                }
+                BLOCK_INDEX => break (offset, entry.data),
+                // other entries are allowed by the format, and ignored
+                _ => {}
+            }
+        };
+
+        let starting_number = verify_block_index(index_offset, &index, &blocks, &header_offsets)?;
+
+        let records = blocks
+            .iter()
+            .map(|block| (block.header.hash_slow(), block.total_difficulty))
+            .collect::<Vec<_>>();
+        let got = accumulator_root(&records);
+        let expected = accumulator.ok_or(EraError::UnexpectedEntry {
+            offset: index_offset,
+            expected: ACCUMULATOR,
+            got: BLOCK_INDEX,
+        })?;
+        if expected != got {
+            return Err(EraError::AccumulatorMismatch { expected, got })
+        }
+
+        if let Some(block) =
+            blocks.iter().find(|block| block.receipts.is_some() != blocks[0].receipts.is_some())
+        {
+            return Err(EraError::MissingReceipts(block.header.number))
+        }
+
+        Ok(Self { starting_number, blocks, accumulator: expected })
+    }
+
+    /// Checks that the total difficulty of every block is the total difficulty of its parent plus
+    /// its difficulty, starting at the total difficulty of the parent of the first block.
+    ///
+    /// The accumulator only commits to the archived total difficulties, so this is what ties them
+    /// to the difficulties of the headers.
+    pub fn verify_total_difficulty(&self, parent_total_difficulty: U256) -> Result<(), EraError> {
+        let mut expected = parent_total_difficulty;
+        for block in &self.blocks {
+            expected += block.header.difficulty;
+            if block.total_difficulty != expected {
+                return Err(EraError::TotalDifficultyMismatch {
+                    number: block.header.number,
+                    expected,
+                    got: block.total_difficulty,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the era1 file into its blocks.
+    pub fn into_blocks(self) -> impl Iterator<Item = Block> {
+        self.blocks.into_iter().map(Era1Block::into_block)
+    }
+}
+
+/// Checks the block index against the blocks of the file, and returns the starting number.
+fn verify_block_index(
+    index_offset: u64,
+    index: &[u8],
+    blocks: &[Era1Block],
+    header_offsets: &[u64],
+) -> Result<BlockNumber, EraError> {
+    if index.len() != 16 + 8 * blocks.len() {
+        return Err(EraError::InvalidBlockIndex)
+    }
+    let read_u64 = |at: usize| u64::from_le_bytes(index[at..at + 8].try_into().expect("8 bytes"));
+
+    let starting_number = read_u64(0);
+    if read_u64(index.len() - 8) != blocks.len() as u64 {
+        return Err(EraError::InvalidBlockIndex)
+    }
+
+    for (i, (block, header_offset)) in blocks.iter().zip(header_offsets).enumerate() {
+        let expected = starting_number + i as u64;
+        if block.header.number != expected {
+            return Err(EraError::NonContiguousBlock { expected, got: block.header.number })
+        }
+
+        // offsets are relative to the block index
+        let relative_offset = read_u64(8 + 8 * i) as i64;
+        if index_offset as i64 + relative_offset != *header_offset as i64 {
+            return Err(EraError::InvalidBlockIndex)
+        }
+    }
+
+    Ok(starting_number)
+}
+
+/// Returns an error if the entry is not of the expected type.
+const fn expect_entry(offset: u64, entry: &Entry, expected: u16) -> Result<(), EraError> {
+    if entry.entry_type != expected {
+        return Err(EraError::UnexpectedEntry { offset, expected, got: entry.entry_type })
+    }
+    Ok(())
+}
+
+/// Computes the accumulator root of the block hashes and total difficulties of an era1 file.
+///
+/// This is the SSZ `hash_tree_root` of a `List[HeaderRecord, 8192]`, where a `HeaderRecord` is a
+/// container of the block hash and the total difficulty.
+pub fn accumulator_root(records: &[(B256, U256)]) -> B256 {
+    let leaves = records
+        .iter()
+        .map(|(hash, total_difficulty)| {
+            sha256(hash.as_slice(), &total_difficulty.to_le_bytes::<32>())
+        })
+        .collect();
+    let root = merkleize(leaves, MAX_BLOCKS_PER_ERA1.trailing_zeros());
+
+    let mut length = [0u8; 32];
+    length[..8].copy_from_slice(&(records.len() as u64).to_le_bytes());
+    B256::from(sha256(&root, &length))
+}
+
+/// Returns the conventional name of an era1 file: `<network>-<epoch>-<short accumulator>.era1`.
+pub fn era1_file_name(network: &str, epoch: u64, accumulator: B256) -> String {
+    format!("{network}-{epoch:05}-{}.era1", hex::encode(&accumulator[..4]))
+}
+
+/// Computes the merkle root of the leaves, padded with zero leaves to a tree of the given depth.
+fn merkleize(mut layer: Vec<[u8; 32]>, depth: u32) -> [u8; 32] {
+    let mut zero = [0u8; 32];
+    for _ in 0..depth {
+        if layer.len() % 2 == 1 {
+            layer.push(zero);
+        }
+        layer = layer.chunks(2).map(|pair| sha256(&pair[0], &pair[1])).collect();
+        zero = sha256(&zero, &zero);
+    }
+    layer.first().copied().unwrap_or(zero)
+}
+
+fn sha256(left: &[u8], right: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Compresses the data with the snappy framing format.
+fn compress(data: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    encoder.into_inner().map_err(|err| EraError::Io(err.into_error()))
+}
+
+/// Decompresses data of the snappy framing format.
+fn decompress(data: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut decompressed = Vec::new();
+    snap::read::FrameDecoder::new(data).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Log, Receipt, TxType};
+
+    #[allow(clippy::needless_update)]
+    fn block(number: BlockNumber, receipts: bool) -> Era1Block {
+        let receipt = Receipt {
+            tx_type: TxType::Eip1559,
+            success: true,
+            cumulative_gas_used: 21_000,
+            logs: vec![Log::empty()],
+            ..Default::default()
+        };
+        Era1Block {
+            header: Header { number, gas_limit: 30_000_000, ..Default::default() },
+            body: BlockBody::default(),
+            receipts: receipts.then(|| vec![receipt.with_bloom()]),
+            total_difficulty: U256::from(number * 100),
+        }
+    }
+
+    fn write(blocks: &[Era1Block]) -> (Vec<u8>, B256) {
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        for block in blocks {
+            writer.append(block).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn era1_roundtrip() {
+        for receipts in [false, true] {
+            let blocks = (10..13).map(|number| block(number, receipts)).collect::<Vec<_>>();
+            let (bytes, accumulator) = write(&blocks);
+
+            let file = Era1File::read(&bytes[..]).unwrap();
+            assert_eq!(file.starting_number, 10);
+            assert_eq!(file.accumulator, accumulator);
+            assert_eq!(file.blocks, blocks);
+        }
+
+        let (bytes, _) = write(&[]);
+        let file = Era1File::read(&bytes[..]).unwrap();
+        assert!(file.blocks.is_empty());
+    }
+
+    #[test]
+    fn era1_rejects_invalid_blocks() {
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        writer.append(&block(10, true)).unwrap();
+        assert!(matches!(
+            writer.append(&block(12, true)),
+            Err(EraError::NonContiguousBlock { expected: 11, got: 12 })
+        ));
+        assert!(matches!(writer.append(&block(11, false)), Err(EraError::MissingReceipts(11))));
+
+        // a tampered total difficulty doesn't match the accumulator
+        let (mut bytes, _) = write(&[block(10, false)]);
+        let (mut reader, mut td_offset) = (E2StoreReader::new(&bytes[..]), None);
+        while let Some((offset, entry)) = reader.read_entry().unwrap() {
+            if entry.entry_type == TOTAL_DIFFICULTY {
+                td_offset = Some(offset as usize);
+            }
+        }
+        bytes[td_offset.unwrap() + 8] ^= 1;
+        assert!(matches!(Era1File::read(&bytes[..]), Err(EraError::AccumulatorMismatch { .. })));
+    }
+
+    #[test]
+    fn era1_total_difficulty() {
+        let blocks = (10..13)
+            .map(|number| {
+                let mut block = block(number, false);
+                block.header.difficulty = U256::from(100);
+                block
+            })
+            .collect::<Vec<_>>();
+        let file = Era1File::read(&write(&blocks).0[..]).unwrap();
+        assert!(file.verify_total_difficulty(U256::from(900)).is_ok());
+        assert!(matches!(
+            file.verify_total_difficulty(U256::from(800)),
+            Err(EraError::TotalDifficultyMismatch { number: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn era1_name() {
+        assert_eq!(
+            era1_file_name("mainnet", 3, B256::with_last_byte(1)),
+            "mainnet-00003-00000000.era1"
+        );
+    }
+}
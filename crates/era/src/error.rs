@@ -0,0 +1,72 @@
+use reth_primitives::{BlockNumber, B256, U256};
+
+/// Errors of reading and writing e2store and era1 files.
+#[derive(Debug, thiserror::Error)]
+pub enum EraError {
+    /// Failed to read or write the file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Failed to decode the RLP of an entry.
+    #[error("failed to decode entry: {0}")]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The reserved bytes of an entry header are not zero.
+    #[error("entry at offset {0} has non-zero reserved bytes")]
+    InvalidReserved(u64),
+    /// An entry of another type was expected.
+    #[error("unexpected entry type {got:#06x} at offset {offset}, expected {expected:#06x}")]
+    UnexpectedEntry {
+        /// Offset of the entry in the file.
+        offset: u64,
+        /// The expected entry type.
+        expected: u16,
+        /// The actual entry type.
+        got: u16,
+    },
+    /// The file ended before the era1 block index.
+    #[error("missing block index")]
+    MissingBlockIndex,
+    /// The data of an entry has an invalid length.
+    #[error("entry of type {entry_type:#06x} has invalid length {len}")]
+    InvalidLength {
+        /// The entry type.
+        entry_type: u16,
+        /// The length of the data.
+        len: usize,
+    },
+    /// The block index doesn't match the blocks of the file.
+    #[error("block index doesn't match the blocks of the file")]
+    InvalidBlockIndex,
+    /// The accumulator doesn't match the blocks of the file.
+    #[error("accumulator mismatch: expected {expected}, got {got}")]
+    AccumulatorMismatch {
+        /// The accumulator of the file.
+        expected: B256,
+        /// The accumulator computed from the blocks.
+        got: B256,
+    },
+    /// A block doesn't follow the previous block of the file.
+    #[error("block {got} doesn't follow block {}", expected - 1)]
+    NonContiguousBlock {
+        /// The expected block number.
+        expected: BlockNumber,
+        /// The actual block number.
+        got: BlockNumber,
+    },
+    /// Some blocks have receipts and others don't.
+    #[error("block {0} is missing receipts")]
+    MissingReceipts(BlockNumber),
+    /// The total difficulty of a block is not the total difficulty of its parent plus its
+    /// difficulty.
+    #[error("total difficulty mismatch of block {number}: expected {expected}, got {got}")]
+    TotalDifficultyMismatch {
+        /// The block number.
+        number: BlockNumber,
+        /// The total difficulty computed from the difficulties.
+        expected: U256,
+        /// The archived total difficulty.
+        got: U256,
+    },
+    /// The file already holds the maximum number of blocks.
+    #[error("era1 files hold at most {0} blocks")]
+    TooManyBlocks(usize),
+}
@@ -0,0 +1,26 @@
+//! Readers and writers of e2store and era1 archive files.
+//!
+//! [e2store](https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md) is a simple
+//! type-length-value container format. Era1 files are e2store files holding up to
+//! [`MAX_BLOCKS_PER_ERA1`] consecutive execution layer blocks, with their receipts and total
+//! difficulty, followed by an accumulator of the block hashes and an index of the blocks.
+//!
+//! They are used to archive ranges of the chain and to bootstrap nodes without the network.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+pub mod e2s;
+
+mod era1;
+pub use era1::{
+    accumulator_root, era1_file_name, Era1Block, Era1File, Era1Writer, MAX_BLOCKS_PER_ERA1,
+};
+
+mod error;
+pub use error::EraError;
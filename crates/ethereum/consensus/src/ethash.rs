@@ -0,0 +1,319 @@
+//! Verification of the ethash proof-of-work seals of pre-merge headers.
+//!
+//! Seals are verified with the light cache of their epoch, computing the accessed items of the
+//! dataset on the fly, see the [ethash spec](https://ethereum.org/en/developers/docs/consensus-mechanisms/pow/mining/mining-algorithms/ethash/).
+
+use alloy_rlp::Encodable;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use reth_consensus::{ConsensusError, HeaderConsensusError};
+use reth_primitives::{keccak256, GotExpected, Header, SealedHeader, B256, U256};
+use sha3::{Digest, Keccak512};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, OnceLock},
+};
+
+/// Number of blocks of an ethash epoch, which share a cache and a dataset.
+pub const EPOCH_LENGTH: u64 = 30_000;
+
+const CACHE_BYTES_INIT: u64 = 1 << 24;
+const CACHE_BYTES_GROWTH: u64 = 1 << 17;
+const DATASET_BYTES_INIT: u64 = 1 << 30;
+const DATASET_BYTES_GROWTH: u64 = 1 << 23;
+const HASH_BYTES: u64 = 64;
+const MIX_BYTES: u64 = 128;
+const HASH_WORDS: usize = 16;
+const MIX_WORDS: usize = 32;
+const DATASET_PARENTS: u32 = 256;
+const CACHE_ROUNDS: usize = 3;
+const ACCESSES: u32 = 64;
+const FNV_PRIME: u32 = 0x01000193;
+
+/// Maximum number of epoch caches kept by [`Ethash`].
+const MAX_CACHES: usize = 3;
+
+/// A 512-bit hash as little-endian words.
+type Hash512 = [u32; HASH_WORDS];
+
+/// Returns the size in bytes of the cache of the epoch.
+pub fn cache_size(epoch: u64) -> u64 {
+    let mut size = CACHE_BYTES_INIT + CACHE_BYTES_GROWTH * epoch - HASH_BYTES;
+    while !is_prime(size / HASH_BYTES) {
+        size -= 2 * HASH_BYTES;
+    }
+    size
+}
+
+/// Returns the size in bytes of the dataset of the epoch.
+pub fn dataset_size(epoch: u64) -> u64 {
+    let mut size = DATASET_BYTES_INIT + DATASET_BYTES_GROWTH * epoch - MIX_BYTES;
+    while !is_prime(size / MIX_BYTES) {
+        size -= 2 * MIX_BYTES;
+    }
+    size
+}
+
+/// Returns the seed hash of the epoch, from which its cache is generated.
+pub fn seed_hash(epoch: u64) -> B256 {
+    (0..epoch).fold(B256::ZERO, |seed, _| keccak256(seed))
+}
+
+/// Returns the hash of the header that is sealed by the proof-of-work, i.e. the hash of the
+/// header without its mix hash and nonce.
+pub fn seal_hash(header: &Header) -> B256 {
+    let mut payload = Vec::new();
+    header.parent_hash.encode(&mut payload);
+    header.ommers_hash.encode(&mut payload);
+    header.beneficiary.encode(&mut payload);
+    header.state_root.encode(&mut payload);
+    header.transactions_root.encode(&mut payload);
+    header.receipts_root.encode(&mut payload);
+    header.logs_bloom.encode(&mut payload);
+    header.difficulty.encode(&mut payload);
+    header.number.encode(&mut payload);
+    header.gas_limit.encode(&mut payload);
+    header.gas_used.encode(&mut payload);
+    header.timestamp.encode(&mut payload);
+    header.extra_data.encode(&mut payload);
+    if let Some(base_fee) = header.base_fee_per_gas {
+        base_fee.encode(&mut payload);
+    }
+
+    let mut out = Vec::with_capacity(payload.len() + 4);
+    alloy_rlp::Header { list: true, payload_length: payload.len() }.encode(&mut out);
+    out.extend_from_slice(&payload);
+    keccak256(out)
+}
+
+/// The cache of an ethash epoch, from which the items of its dataset are computed.
+#[derive(Debug)]
+pub struct EthashCache {
+    epoch: u64,
+    items: Vec<Hash512>,
+}
+
+impl EthashCache {
+    /// Generates the cache of the epoch.
+    ///
+    /// This takes about a second and tens of megabytes, growing with the epoch.
+    pub fn new(epoch: u64) -> Self {
+        let len = (cache_size(epoch) / HASH_BYTES) as usize;
+        let mut items = Vec::with_capacity(len);
+        items.push(keccak512(seed_hash(epoch).as_slice()));
+        for i in 1..len {
+            items.push(keccak512(&to_bytes(&items[i - 1])));
+        }
+
+        for _ in 0..CACHE_ROUNDS {
+            for i in 0..len {
+                let other = items[items[i][0] as usize % len];
+                let previous = items[(i + len - 1) % len];
+                let mut mixed = [0; HASH_WORDS];
+                for (word, (a, b)) in mixed.iter_mut().zip(previous.iter().zip(other)) {
+                    *word = a ^ b;
+                }
+                items[i] = keccak512(&to_bytes(&mixed));
+            }
+        }
+
+        Self { epoch, items }
+    }
+
+    /// Returns the epoch of the cache.
+    pub const fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Computes the ethash mix digest and result of the seal hash and nonce of a header.
+    pub fn hashimoto(&self, seal_hash: B256, nonce: u64) -> (B256, B256) {
+        let rows = (dataset_size(self.epoch) / MIX_BYTES) as u32;
+
+        let mut seed = [0; 40];
+        seed[..32].copy_from_slice(seal_hash.as_slice());
+        seed[32..].copy_from_slice(&nonce.to_le_bytes());
+        let seed = keccak512(&seed);
+
+        let mut mix = [0; MIX_WORDS];
+        mix[..HASH_WORDS].copy_from_slice(&seed);
+        mix[HASH_WORDS..].copy_from_slice(&seed);
+        for i in 0..ACCESSES {
+            let row = fnv(i ^ seed[0], mix[i as usize % MIX_WORDS]) % rows;
+            for (j, words) in mix.chunks_exact_mut(HASH_WORDS).enumerate() {
+                let item = self.dataset_item(row * 2 + j as u32);
+                for (word, item) in words.iter_mut().zip(item) {
+                    *word = fnv(*word, item);
+                }
+            }
+        }
+
+        let mut digest = [0; MIX_WORDS / 4];
+        for (word, words) in digest.iter_mut().zip(mix.chunks_exact(4)) {
+            *word = fnv(fnv(fnv(words[0], words[1]), words[2]), words[3]);
+        }
+        let digest = to_bytes(&digest);
+
+        let mut result = to_bytes(&seed);
+        result.extend_from_slice(&digest);
+        (B256::from_slice(&digest), keccak256(result))
+    }
+
+    /// Computes the item of the dataset at the index.
+    fn dataset_item(&self, index: u32) -> Hash512 {
+        let len = self.items.len() as u32;
+        let mut mix = self.items[(index % len) as usize];
+        mix[0] ^= index;
+        mix = keccak512(&to_bytes(&mix));
+        for j in 0..DATASET_PARENTS {
+            let parent = &self.items[(fnv(index ^ j, mix[j as usize % HASH_WORDS]) % len) as usize];
+            for (word, parent) in mix.iter_mut().zip(parent) {
+                *word = fnv(*word, *parent);
+            }
+        }
+        keccak512(&to_bytes(&mix))
+    }
+}
+
+/// Verifies ethash seals with the caches of the most recent epochs, generating them on demand.
+#[derive(Debug, Default)]
+pub struct Ethash {
+    caches: Mutex<VecDeque<(u64, Arc<OnceLock<EthashCache>>)>>,
+}
+
+impl Ethash {
+    /// Verifies the ethash seal of a proof-of-work header, i.e. that the mix hash of the header
+    /// is the mix digest of its seal and that the result satisfies its difficulty.
+    pub fn verify_seal(&self, header: &Header) -> Result<(), ConsensusError> {
+        if header.difficulty.is_zero() {
+            return Err(ConsensusError::EthashInvalidSeal { difficulty: header.difficulty })
+        }
+
+        let epoch = header.number / EPOCH_LENGTH;
+        let cache = self.cache(epoch);
+        let (digest, result) = cache
+            .get_or_init(|| EthashCache::new(epoch))
+            .hashimoto(seal_hash(header), header.nonce);
+
+        if digest != header.mix_hash {
+            return Err(ConsensusError::EthashMixDigestDiff(
+                GotExpected { got: digest, expected: header.mix_hash }.into(),
+            ))
+        }
+
+        // the result must not exceed 2^256 / difficulty
+        let (quotient, remainder) = U256::MAX.div_rem(header.difficulty);
+        let target = if remainder == header.difficulty - U256::from(1) {
+            quotient.saturating_add(U256::from(1))
+        } else {
+            quotient
+        };
+        if U256::from_be_bytes(result.0) > target {
+            return Err(ConsensusError::EthashInvalidSeal { difficulty: header.difficulty })
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the ethash seals of the headers in parallel.
+    ///
+    /// Returns the error of a header with an invalid seal, if any.
+    pub fn verify_seals(&self, headers: &[SealedHeader]) -> Result<(), HeaderConsensusError> {
+        headers.par_iter().try_for_each(|header| {
+            self.verify_seal(header).map_err(|err| HeaderConsensusError(err, header.clone()))
+        })
+    }
+
+    /// Returns the cache of the epoch, which is generated by the first caller that needs it.
+    fn cache(&self, epoch: u64) -> Arc<OnceLock<EthashCache>> {
+        let mut caches = self.caches.lock();
+        if let Some((_, cache)) = caches.iter().find(|(cached, _)| *cached == epoch) {
+            return cache.clone()
+        }
+        if caches.len() == MAX_CACHES {
+            caches.pop_front();
+        }
+        let cache = Arc::new(OnceLock::new());
+        caches.push_back((epoch, cache.clone()));
+        cache
+    }
+}
+
+/// The FNV-1 inspired mixing function of ethash.
+const fn fnv(a: u32, b: u32) -> u32 {
+    a.wrapping_mul(FNV_PRIME) ^ b
+}
+
+fn keccak512(data: &[u8]) -> Hash512 {
+    bytes_to_words(&Keccak512::digest(data)).try_into().expect("keccak512 has 16 words")
+}
+
+fn bytes_to_words(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes(word.try_into().expect("4 bytes")))
+        .collect()
+}
+
+fn to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|i| i * i <= n).all(|i| n % i != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        b256, bytes, constants::EMPTY_ROOT_HASH, hex, Address, Bloom, EMPTY_OMMER_ROOT_HASH,
+    };
+
+    /// Header of mainnet block 1.
+    fn mainnet_block_1() -> Header {
+        Header {
+            parent_hash: b256!("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"),
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            beneficiary: Address::from(hex!("05a56e2d52c817161883f50c441c3228cfe54d9f")),
+            state_root: b256!("d67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3"),
+            transactions_root: EMPTY_ROOT_HASH,
+            receipts_root: EMPTY_ROOT_HASH,
+            logs_bloom: Bloom::ZERO,
+            difficulty: U256::from(17_171_480_576u64),
+            number: 1,
+            gas_limit: 5000,
+            gas_used: 0,
+            timestamp: 1_438_269_988,
+            extra_data: bytes!("476574682f76312e302e302f6c696e75782f676f312e342e32"),
+            mix_hash: b256!("969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59"),
+            nonce: 0x539bd4979fef1ec4,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn epoch_sizes() {
+        assert_eq!(cache_size(0), 16_776_896);
+        assert_eq!(dataset_size(0), 1_073_739_904);
+        assert_eq!(seed_hash(0), B256::ZERO);
+        assert_eq!(seed_hash(1), keccak256(B256::ZERO));
+    }
+
+    #[test]
+    fn verify_mainnet_seal() {
+        let header = mainnet_block_1();
+        assert_eq!(
+            header.hash_slow(),
+            b256!("88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6")
+        );
+
+        let ethash = Ethash::default();
+        assert!(ethash.verify_seals(&[header.clone().seal_slow()]).is_ok());
+
+        let tampered = Header { nonce: header.nonce + 1, ..header };
+        assert!(matches!(
+            ethash.verify_seal(&tampered),
+            Err(ConsensusError::EthashMixDigestDiff(_))
+        ));
+    }
+}
@@ -9,35 +9,99 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use reth_chainspec::{Chain, ChainSpec, EthereumHardfork, EthereumHardforks};
-use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_consensus::{Consensus, ConsensusError, HeaderRules, PostExecutionInput};
 use reth_consensus_common::validation::{
     validate_4844_header_standalone, validate_against_parent_4844,
-    validate_against_parent_eip1559_base_fee, validate_against_parent_hash_number,
-    validate_against_parent_timestamp, validate_block_pre_execution, validate_header_base_fee,
-    validate_header_extradata, validate_header_gas,
+    validate_against_parent_hash_number, validate_block_pre_execution, validate_header_base_fee,
+    validate_header_gas,
 };
 use reth_primitives::{
-    constants::MINIMUM_GAS_LIMIT, BlockWithSenders, Header, SealedBlock, SealedHeader,
-    EMPTY_OMMER_ROOT_HASH, U256,
+    constants::{EIP1559_INITIAL_BASE_FEE, MINIMUM_GAS_LIMIT},
+    BlockWithSenders, GotExpected, Header, SealedBlock, SealedHeader, EMPTY_OMMER_ROOT_HASH, U256,
 };
 use std::{sync::Arc, time::SystemTime};
 
+pub mod ethash;
+use ethash::Ethash;
+
 mod validation;
 pub use validation::validate_block_post_execution;
 
+/// The [`HeaderRules`] of Ethereum.
+///
+/// The base fee follows EIP-1559 with the base fee params of the chain spec.
+#[derive(Debug, Clone)]
+pub struct EthHeaderRules {
+    chain_spec: Arc<ChainSpec>,
+}
+
+impl EthHeaderRules {
+    /// Creates the rules of the given chain.
+    pub const fn new(chain_spec: Arc<ChainSpec>) -> Self {
+        Self { chain_spec }
+    }
+}
+
+impl HeaderRules for EthHeaderRules {
+    fn expected_base_fee(
+        &self,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<Option<u64>, ConsensusError> {
+        let london = self.chain_spec.fork(EthereumHardfork::London);
+        if !london.active_at_block(header.number) {
+            return Ok(None)
+        }
+        if london.transitions_at_block(header.number) {
+            return Ok(Some(EIP1559_INITIAL_BASE_FEE))
+        }
+        // This BaseFeeMissing will not happen as previous blocks are checked to have them.
+        parent
+            .next_block_base_fee(self.chain_spec.base_fee_params_at_timestamp(header.timestamp))
+            .map(Some)
+            .ok_or(ConsensusError::BaseFeeMissing)
+    }
+}
+
 /// Ethereum beacon consensus
 ///
-/// This consensus engine does basic checks as outlined in the execution specs.
+/// This consensus engine does basic checks as outlined in the execution specs. The base fee, extra
+/// data and timestamp checks are delegated to its [`HeaderRules`], see
+/// [`EthBeaconConsensus::with_rules`]. The proof-of-work seals of pre-merge headers are only
+/// verified with [`EthBeaconConsensus::with_ethash`].
 #[derive(Debug)]
-pub struct EthBeaconConsensus {
+pub struct EthBeaconConsensus<R = EthHeaderRules> {
     /// Configuration
     chain_spec: Arc<ChainSpec>,
+    /// Rules of the headers that differ between chains
+    rules: R,
+    /// Verifier of the seals of pre-merge headers, if enabled
+    ethash: Option<Arc<Ethash>>,
 }
 
 impl EthBeaconConsensus {
     /// Create a new instance of [`EthBeaconConsensus`]
-    pub const fn new(chain_spec: Arc<ChainSpec>) -> Self {
-        Self { chain_spec }
+    pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
+        Self { rules: EthHeaderRules::new(chain_spec.clone()), chain_spec, ethash: None }
+    }
+}
+
+impl<R> EthBeaconConsensus<R> {
+    /// Replaces the [`HeaderRules`], keeping all other checks of Ethereum.
+    pub fn with_rules<T: HeaderRules>(self, rules: T) -> EthBeaconConsensus<T> {
+        EthBeaconConsensus { chain_spec: self.chain_spec, rules, ethash: self.ethash }
+    }
+
+    /// Verifies the ethash seals of pre-merge headers with the given verifier, whose caches can
+    /// be shared with other consumers.
+    pub fn with_ethash(mut self, ethash: Arc<Ethash>) -> Self {
+        self.ethash = Some(ethash);
+        self
+    }
+
+    /// Returns the [`HeaderRules`].
+    pub const fn rules(&self) -> &R {
+        &self.rules
     }
 
     /// Checks the gas limit for consistency between parent and self headers.
@@ -85,7 +149,7 @@ impl EthBeaconConsensus {
     }
 }
 
-impl Consensus for EthBeaconConsensus {
+impl<R: HeaderRules> Consensus for EthBeaconConsensus<R> {
     fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
         validate_header_gas(header)?;
         validate_header_base_fee(header, &self.chain_spec)?;
@@ -130,13 +194,21 @@ impl Consensus for EthBeaconConsensus {
     ) -> Result<(), ConsensusError> {
         validate_against_parent_hash_number(header, parent)?;
 
-        validate_against_parent_timestamp(header, parent)?;
+        self.rules.validate_timestamp(header, parent)?;
 
         // TODO Check difficulty increment between parent and self
         // Ace age did increment it by some formula that we need to follow.
         self.validate_against_parent_gas_limit(header, parent)?;
 
-        validate_against_parent_eip1559_base_fee(header, parent, &self.chain_spec)?;
+        if let Some(expected_base_fee) = self.rules.expected_base_fee(header, parent)? {
+            let base_fee = header.base_fee_per_gas.ok_or(ConsensusError::BaseFeeMissing)?;
+            if expected_base_fee != base_fee {
+                return Err(ConsensusError::BaseFeeDiff(GotExpected {
+                    expected: expected_base_fee,
+                    got: base_fee,
+                }))
+            }
+        }
 
         // ensure that the blob gas fields for this block
         if self.chain_spec.is_cancun_active_at_timestamp(header.timestamp) {
@@ -178,14 +250,17 @@ impl Consensus for EthBeaconConsensus {
             // is greater than its parent timestamp.
 
             // validate header extradata for all networks post merge
-            validate_header_extradata(header)?;
+            self.rules.validate_extra_data(header)?;
 
             // mixHash is used instead of difficulty inside EVM
             // https://eips.ethereum.org/EIPS/eip-4399#using-mixhash-field-instead-of-difficulty
         } else {
             // TODO Consensus checks for old blocks:
-            //  * difficulty, mix_hash & nonce aka PoW stuff
+            //  * difficulty aka PoW stuff
             // low priority as syncing is done in reverse order
+            if let Some(ethash) = &self.ethash {
+                ethash.verify_seal(header)?;
+            }
 
             // Check if timestamp is in the future. Clock can drift but this can be consensus issue.
             let present_timestamp =
@@ -202,7 +277,7 @@ impl Consensus for EthBeaconConsensus {
             //  * If the network is goerli pre-merge, ignore the extradata check, since we do not
             //  support clique. Same goes for OP blocks below Bedrock.
             if self.chain_spec.chain != Chain::goerli() && !self.chain_spec.is_optimism() {
-                validate_header_extradata(header)?;
+                self.rules.validate_extra_data(header)?;
             }
         }
 
@@ -299,6 +374,71 @@ mod tests {
         );
     }
 
+    /// Rules with a fixed base fee and arbitrary extra data.
+    #[derive(Debug)]
+    struct FixedBaseFee(u64);
+
+    impl HeaderRules for FixedBaseFee {
+        fn expected_base_fee(
+            &self,
+            _header: &SealedHeader,
+            _parent: &SealedHeader,
+        ) -> Result<Option<u64>, ConsensusError> {
+            Ok(Some(self.0))
+        }
+
+        fn validate_extra_data(&self, _header: &Header) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn custom_header_rules() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().paris_activated().build());
+        let consensus = EthBeaconConsensus::new(chain_spec).with_rules(FixedBaseFee(7));
+
+        let parent = Header {
+            number: 1,
+            timestamp: 1,
+            gas_limit: 1024 * 10,
+            base_fee_per_gas: Some(1000),
+            ..Default::default()
+        }
+        .seal_slow();
+        let child = Header {
+            number: 2,
+            parent_hash: parent.hash(),
+            timestamp: 2,
+            gas_limit: 1024 * 10,
+            base_fee_per_gas: Some(7),
+            extra_data: vec![0; 64].into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            consensus.validate_header_against_parent(&child.clone().seal_slow(), &parent),
+            Ok(())
+        );
+        assert_eq!(consensus.validate_header_with_total_difficulty(&child, U256::ZERO), Ok(()));
+
+        let header = Header { base_fee_per_gas: Some(8), ..child.clone() }.seal_slow();
+        assert_eq!(
+            consensus.validate_header_against_parent(&header, &parent),
+            Err(ConsensusError::BaseFeeDiff(GotExpected { got: 8, expected: 7 }))
+        );
+
+        // the remaining checks of Ethereum still apply
+        let header = Header { timestamp: 1, ..child.clone() }.seal_slow();
+        assert_eq!(
+            consensus.validate_header_against_parent(&header, &parent),
+            Err(ConsensusError::TimestampIsInPast { parent_timestamp: 1, timestamp: 1 })
+        );
+        let header = Header { gas_limit: 1024 * 20, ..child }.seal_slow();
+        assert!(matches!(
+            consensus.validate_header_against_parent(&header, &parent),
+            Err(ConsensusError::GasLimitInvalidIncrease { .. })
+        ));
+    }
+
     #[test]
     fn shanghai_block_zero_withdrawals() {
         // ensures that if shanghai is activated, and we include a block with a withdrawals root,
@@ -1,5 +1,6 @@
 use reth_chainspec::{ChainSpec, EthereumHardforks};
 use reth_consensus::ConsensusError;
+use reth_consensus_common::validation::validate_requests;
 use reth_primitives::{
     gas_spent_by_transactions, BlockWithSenders, Bloom, GotExpected, Receipt, Request, B256,
 };
@@ -8,6 +9,7 @@ use reth_primitives::{
 ///
 /// - Compares the receipts root in the block header to the block body
 /// - Compares the gas used in the block header to the actual gas usage after execution
+/// - Compares the requests root in the block header to the EIP-7685 requests of the execution
 pub fn validate_block_post_execution(
     block: &BlockWithSenders,
     chain_spec: &ChainSpec,
@@ -39,15 +41,7 @@ pub fn validate_block_post_execution(
 
     // Validate that the header requests root matches the calculated requests root
     if chain_spec.is_prague_active_at_timestamp(block.timestamp) {
-        let Some(header_requests_root) = block.header.requests_root else {
-            return Err(ConsensusError::RequestsRootMissing)
-        };
-        let requests_root = reth_primitives::proofs::calculate_requests_root(requests);
-        if requests_root != header_requests_root {
-            return Err(ConsensusError::BodyRequestsRootDiff(
-                GotExpected::new(requests_root, header_requests_root).into(),
-            ))
-        }
+        validate_requests(block, requests)?;
     }
 
     Ok(())
@@ -7,24 +7,25 @@ use crate::{
 use reth_chainspec::{ChainSpec, EthereumHardforks, MAINNET};
 use reth_ethereum_consensus::validate_block_post_execution;
 use reth_evm::{
+    events::{ExecutionEventsInspector, ExecutionEventsSender},
     execute::{
         BatchExecutor, BlockExecutionError, BlockExecutionInput, BlockExecutionOutput,
         BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
     },
+    inspector::InspectorPair,
+    profiling::GasProfilingInspector,
     ConfigureEvm,
 };
 use reth_execution_types::ExecutionOutcome;
 use reth_primitives::{
-    BlockNumber, BlockWithSenders, EthereumHardfork, Header, Receipt, Request, U256,
+    BlockNumHash, BlockNumber, BlockWithSenders, EthereumHardfork, Header, Receipt, Request, U256,
 };
 use reth_prune_types::PruneModes;
 use reth_revm::{
     batch::{BlockBatchRecord, BlockExecutorStats},
     db::states::bundle_state::BundleRetention,
-    state_change::{
-        apply_beacon_root_contract_call, apply_blockhashes_update,
-        apply_withdrawal_requests_contract_call, post_block_balance_increments,
-    },
+    state_change::post_block_balance_increments,
+    system_calls::{SystemCallBlock, SystemCallHook, SystemCaller},
     Evm, State,
 };
 use revm_primitives::{
@@ -39,6 +40,12 @@ use std::{fmt::Display, sync::Arc, vec, vec::Vec};
 pub struct EthExecutorProvider<EvmConfig = EthEvmConfig> {
     chain_spec: Arc<ChainSpec>,
     evm_config: EvmConfig,
+    /// Sender of the events of executed blocks, if enabled.
+    execution_events: Option<ExecutionEventsSender>,
+    /// Number of contracts reported by the gas profile of executed blocks, if enabled.
+    gas_profiling: Option<usize>,
+    /// Hooks applying additional system calls of the chain.
+    system_call_hooks: Vec<Arc<dyn SystemCallHook>>,
 }
 
 impl EthExecutorProvider {
@@ -56,7 +63,50 @@ impl EthExecutorProvider {
 impl<EvmConfig> EthExecutorProvider<EvmConfig> {
     /// Creates a new executor provider.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { chain_spec, evm_config }
+        Self {
+            chain_spec,
+            evm_config,
+            execution_events: None,
+            gas_profiling: None,
+            system_call_hooks: Vec::new(),
+        }
+    }
+
+    /// Records the events of blocks executed by the [executors](BlockExecutorProvider::executor)
+    /// of the provider in the given sender, which publishes them once the blocks are canonical.
+    ///
+    /// Blocks executed by [batch executors](BlockExecutorProvider::batch_executor), e.g. during
+    /// historical sync, are not published.
+    pub fn with_execution_events(mut self, execution_events: ExecutionEventsSender) -> Self {
+        self.execution_events = Some(execution_events);
+        self
+    }
+
+    /// Profiles the gas used by blocks executed by the
+    /// [executors](BlockExecutorProvider::executor) of the provider, and reports it together with
+    /// the `top_contracts` contracts using the most gas.
+    ///
+    /// See [`BlockGasProfile::report`](reth_evm::profiling::BlockGasProfile::report).
+    pub const fn with_gas_profiling(mut self, top_contracts: usize) -> Self {
+        self.gas_profiling = Some(top_contracts);
+        self
+    }
+
+    /// Adds a hook applying additional pre- and post-block system calls of the chain to the
+    /// executed blocks.
+    ///
+    /// See [`SystemCaller`].
+    pub fn with_system_call_hook(mut self, hook: Arc<dyn SystemCallHook>) -> Self {
+        self.system_call_hooks.push(hook);
+        self
+    }
+
+    /// Returns the system caller of the executed blocks.
+    fn system_caller(&self) -> SystemCaller {
+        self.system_call_hooks
+            .iter()
+            .cloned()
+            .fold(SystemCaller::new(self.chain_spec.clone()), SystemCaller::with_hook)
     }
 }
 
@@ -73,6 +123,7 @@ where
             self.evm_config.clone(),
             State::builder().with_database(db).with_bundle_update().without_state_clear().build(),
         )
+        .with_system_caller(self.system_caller())
     }
 }
 
@@ -91,6 +142,8 @@ where
         DB: Database<Error: Into<ProviderError> + Display>,
     {
         self.eth_executor(db)
+            .with_execution_events(self.execution_events.clone())
+            .with_gas_profiling(self.gas_profiling)
     }
 
     fn batch_executor<DB>(&self, db: DB, prune_modes: PruneModes) -> Self::BatchExecutor<DB>
@@ -121,6 +174,8 @@ struct EthEvmExecutor<EvmConfig> {
     chain_spec: Arc<ChainSpec>,
     /// How to create an EVM.
     evm_config: EvmConfig,
+    /// Applies the pre- and post-block system calls.
+    system_caller: SystemCaller,
 }
 
 impl<EvmConfig> EthEvmExecutor<EvmConfig>
@@ -147,20 +202,8 @@ where
         DB::Error: Into<ProviderError> + std::fmt::Display,
     {
         // apply pre execution changes
-        apply_beacon_root_contract_call(
-            &self.chain_spec,
-            block.timestamp,
-            block.number,
-            block.parent_beacon_block_root,
-            &mut evm,
-        )?;
-        apply_blockhashes_update(
-            evm.db_mut(),
-            &self.chain_spec,
-            block.timestamp,
-            block.number,
-            block.parent_hash,
-        )?;
+        let system_call_block = SystemCallBlock::from(&block.header);
+        self.system_caller.apply_pre_block(&system_call_block, &mut evm)?;
 
         // execute transactions
         let mut cumulative_gas_used = 0;
@@ -215,19 +258,16 @@ where
             );
         }
 
-        let requests = if self.chain_spec.is_prague_active_at_timestamp(block.timestamp) {
+        let mut requests = if self.chain_spec.is_prague_active_at_timestamp(block.timestamp) {
             // Collect all EIP-6110 deposits
-            let deposit_requests =
-                crate::eip6110::parse_deposits_from_receipts(&self.chain_spec, &receipts)?;
-
-            // Collect all EIP-7685 requests
-            let withdrawal_requests = apply_withdrawal_requests_contract_call(&mut evm)?;
-
-            [deposit_requests, withdrawal_requests].concat()
+            crate::eip6110::parse_deposits_from_receipts(&self.chain_spec, &receipts)?
         } else {
             vec![]
         };
 
+        // apply post execution system calls, and collect their EIP-7685 requests
+        requests.extend(self.system_caller.apply_post_block(&system_call_block, &mut evm)?);
+
         Ok(EthExecuteOutput { receipts, requests, gas_used: cumulative_gas_used })
     }
 }
@@ -243,12 +283,50 @@ pub struct EthBlockExecutor<EvmConfig, DB> {
     executor: EthEvmExecutor<EvmConfig>,
     /// The state to use for execution
     state: State<DB>,
+    /// Sender of the events of executed blocks, if enabled.
+    execution_events: Option<ExecutionEventsSender>,
+    /// Number of contracts reported by the gas profile of executed blocks, if enabled.
+    gas_profiling: Option<usize>,
 }
 
 impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB> {
     /// Creates a new Ethereum block executor.
-    pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig, state: State<DB>) -> Self {
-        Self { executor: EthEvmExecutor { chain_spec, evm_config }, state }
+    ///
+    /// The executor applies the system calls of the ethereum hardforks, see
+    /// [`Self::with_system_caller`].
+    pub fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig, state: State<DB>) -> Self {
+        let system_caller = SystemCaller::new(chain_spec.clone());
+        Self {
+            executor: EthEvmExecutor { chain_spec, evm_config, system_caller },
+            state,
+            execution_events: None,
+            gas_profiling: None,
+        }
+    }
+
+    /// Sets the system caller applying the pre- and post-block system calls, e.g. with additional
+    /// [hooks](SystemCallHook) of the chain.
+    pub fn with_system_caller(mut self, system_caller: SystemCaller) -> Self {
+        self.executor.system_caller = system_caller;
+        self
+    }
+
+    /// Sets the sender the events of executed blocks are recorded in.
+    ///
+    /// Events are only recorded while the sender has subscribers.
+    pub fn with_execution_events(
+        mut self,
+        execution_events: Option<ExecutionEventsSender>,
+    ) -> Self {
+        self.execution_events = execution_events;
+        self
+    }
+
+    /// Sets the number of contracts reported by the gas profile of executed blocks, if gas
+    /// profiling is enabled.
+    pub const fn with_gas_profiling(mut self, gas_profiling: Option<usize>) -> Self {
+        self.gas_profiling = gas_profiling;
+        self
     }
 
     #[inline]
@@ -303,10 +381,34 @@ where
 
         // 2. configure the evm and execute
         let env = self.evm_env_for_block(&block.header, total_difficulty);
-        let output = {
+        let execution_events =
+            self.execution_events.clone().filter(|events| events.has_subscribers());
+        let mut inspector = InspectorPair::new(
+            execution_events.is_some().then(ExecutionEventsInspector::default),
+            self.gas_profiling.is_some().then(GasProfilingInspector::default),
+        );
+        let output = if !inspector.is_empty() {
+            let output = {
+                let evm = self.executor.evm_config.evm_with_env_and_inspector(
+                    &mut self.state,
+                    env,
+                    &mut inspector,
+                );
+                self.executor.execute_state_transitions(block, evm)
+            }?;
+            let events = inspector.first.map(|events| events.into_block_events(block));
+            if let (Some(execution_events), Some(events)) = (execution_events, events) {
+                execution_events.record(events);
+            }
+            if let (Some(top_contracts), Some(profile)) = (self.gas_profiling, inspector.second) {
+                let hash = block.header.hash_slow();
+                profile.into_profile().report(BlockNumHash::new(block.number, hash), top_contracts);
+            }
+            output
+        } else {
             let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
-            self.executor.execute_state_transitions(block, evm)
-        }?;
+            self.executor.execute_state_transitions(block, evm)?
+        };
 
         // 3. apply post execution changes
         self.post_execution(block, total_difficulty)?;
@@ -422,6 +524,9 @@ where
         // prepare the state according to the prune mode
         let retention = self.batch_record.bundle_retention(block.number);
         self.executor.state.merge_transitions(retention);
+        if let Some(reverts) = self.executor.state.bundle_state.reverts.last_mut() {
+            self.batch_record.prune_reverts(block.number, reverts);
+        }
 
         // store receipts in the set
         self.batch_record.save_receipts(receipts)?;
@@ -462,15 +567,22 @@ mod tests {
     use alloy_eips::{
         eip2935::HISTORY_STORAGE_ADDRESS,
         eip4788::{BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE, SYSTEM_ADDRESS},
-        eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_PREDEPLOY_CODE},
+        eip7002::{
+            WithdrawalRequest, WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+            WITHDRAWAL_REQUEST_PREDEPLOY_CODE,
+        },
     };
     use reth_chainspec::{ChainSpecBuilder, ForkCondition};
+    use reth_evm::events::ExecutionEvent;
+    use reth_execution_types::Chain;
     use reth_primitives::{
         constants::{EMPTY_ROOT_HASH, ETH_TO_WEI},
-        keccak256, public_key_to_address, Account, Block, Transaction, TxKind, TxLegacy, B256,
+        keccak256, public_key_to_address, Account, Address, Block, Transaction, TxKind, TxLegacy,
+        B256,
     };
     use reth_revm::{
-        database::StateProviderDatabase, test_utils::StateProviderTest, TransitionState,
+        database::StateProviderDatabase, interpreter::CallScheme, system_calls::SystemCallEvm,
+        test_utils::StateProviderTest, TransitionState,
     };
     use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
     use revm_primitives::{b256, fixed_bytes, Bytes, BLOCKHASH_SERVE_WINDOW};
@@ -516,7 +628,7 @@ mod tests {
     }
 
     fn executor_provider(chain_spec: Arc<ChainSpec>) -> EthExecutorProvider<EthEvmConfig> {
-        EthExecutorProvider { chain_spec, evm_config: Default::default() }
+        EthExecutorProvider::new(chain_spec, Default::default())
     }
 
     #[test]
@@ -1318,6 +1430,160 @@ mod tests {
         assert_eq!(withdrawal_request.amount, u64::from_be_bytes(withdrawal_amount.into()));
     }
 
+    #[test]
+    fn system_call_hook() {
+        /// Stores the block number in a contract before the block, and returns a request after it.
+        #[derive(Debug)]
+        struct BlockNumberHook;
+
+        impl SystemCallHook for BlockNumberHook {
+            fn apply_pre_block(
+                &self,
+                block: &SystemCallBlock,
+                evm: &mut dyn SystemCallEvm,
+            ) -> Result<(), BlockExecutionError> {
+                assert_eq!(evm.block_env().number, U256::from(block.number));
+                let result = evm
+                    .transact_system_call(SYSTEM_ADDRESS, BLOCK_NUMBER_CONTRACT, Bytes::new())
+                    .map_err(BlockExecutionError::msg)?;
+                assert!(result.is_success());
+                Ok(())
+            }
+
+            fn apply_post_block(
+                &self,
+                block: &SystemCallBlock,
+                _evm: &mut dyn SystemCallEvm,
+            ) -> Result<Vec<Request>, BlockExecutionError> {
+                Ok(vec![Request::WithdrawalRequest(WithdrawalRequest {
+                    amount: block.number,
+                    ..Default::default()
+                })])
+            }
+        }
+
+        const BLOCK_NUMBER_CONTRACT: Address = Address::repeat_byte(0x42);
+
+        let mut db = StateProviderTest::default();
+        // NUMBER PUSH1 0x00 SSTORE STOP
+        let code = Bytes::from_static(&[0x43, 0x60, 0x00, 0x55, 0x00]);
+        db.insert_account(
+            BLOCK_NUMBER_CONTRACT,
+            Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(keccak256(&code)) },
+            Some(code),
+            HashMap::new(),
+        );
+
+        let provider = executor_provider(Arc::new(ChainSpecBuilder::mainnet().build()))
+            .with_system_call_hook(Arc::new(BlockNumberHook));
+        let mut executor = provider.executor(StateProviderDatabase::new(&db));
+
+        let header = Header { number: 7, gas_limit: 1_000_000, ..Header::default() };
+        let output = executor
+            .execute_without_verification(
+                &BlockWithSenders {
+                    block: Block { header, ..Default::default() },
+                    senders: vec![],
+                },
+                U256::ZERO,
+            )
+            .unwrap();
+
+        assert_eq!(
+            executor.state_mut().storage(BLOCK_NUMBER_CONTRACT, U256::ZERO).unwrap(),
+            U256::from(7)
+        );
+        assert_eq!(output.requests.len(), 1);
+        assert_eq!(output.requests[0].as_withdrawal_request().unwrap().amount, 7);
+    }
+
+    #[test]
+    fn publish_execution_events() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(&*MAINNET)
+                .shanghai_activated()
+                .with_fork(EthereumHardfork::Prague, ForkCondition::Timestamp(0))
+                .build(),
+        );
+
+        let mut db = create_state_provider_with_withdrawal_requests_contract();
+
+        let secp = Secp256k1::new();
+        let sender_key_pair = Keypair::new(&secp, &mut generators::rng());
+        let sender_address = public_key_to_address(sender_key_pair.public_key());
+
+        db.insert_account(
+            sender_address,
+            Account { nonce: 1, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+            None,
+            HashMap::new(),
+        );
+
+        let validator_public_key = fixed_bytes!("111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111");
+        let withdrawal_amount = fixed_bytes!("2222222222222222");
+        let input: Bytes = [&validator_public_key[..], &withdrawal_amount[..]].concat().into();
+
+        let mut header = chain_spec.genesis_header();
+        header.gas_limit = 1_500_000;
+        header.gas_used = 134_807;
+
+        let tx = sign_tx_with_key_pair(
+            sender_key_pair,
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(chain_spec.chain.id()),
+                nonce: 1,
+                gas_price: header.base_fee_per_gas.unwrap().into(),
+                gas_limit: 134_807,
+                to: TxKind::Call(WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS),
+                value: U256::from(1),
+                input: input.clone(),
+            }),
+        );
+        let tx_hash = tx.hash();
+
+        let execution_events = ExecutionEventsSender::default();
+        let mut subscription = execution_events.subscribe();
+        let provider =
+            executor_provider(chain_spec).with_execution_events(execution_events.clone());
+
+        let block =
+            Block { header, body: vec![tx], ommers: vec![], withdrawals: None, requests: None }
+                .with_recovered_senders()
+                .unwrap();
+        provider
+            .executor(StateProviderDatabase::new(&db))
+            .execute((&block, U256::ZERO).into())
+            .unwrap();
+
+        // the events are published once the block is canonical
+        assert!(subscription.try_recv().is_err());
+        execution_events.publish_canonical(&Chain::from_block(
+            block.clone().seal_slow(),
+            Default::default(),
+            None,
+        ));
+
+        // the withdrawal requests system call after the transaction is not published
+        let events = subscription.try_recv().unwrap();
+        assert_eq!(events.block.number, block.number);
+        assert_eq!(events.transactions.len(), 1);
+        let transaction = &events.transactions[0];
+        assert_eq!(transaction.tx_hash, tx_hash);
+        assert!(transaction.success);
+        assert_eq!(
+            transaction.events[0],
+            ExecutionEvent::Call {
+                depth: 0,
+                scheme: CallScheme::Call,
+                from: sender_address,
+                to: WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+                value: U256::from(1),
+                input,
+                success: true,
+            }
+        );
+    }
+
     #[test]
     fn block_gas_limit_error() {
         // Create a chain specification with fork conditions set for Prague
@@ -15,9 +15,14 @@ extern crate alloc;
 use reth_chainspec::{ChainSpec, Head};
 use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
 use reth_primitives::{Header, U256};
-use reth_revm::{Database, EvmBuilder};
+use reth_revm::{inspector_handle_register, Database, EvmBuilder, GetInspector};
 use revm_primitives::{AnalysisKind, CfgEnvWithHandlerCfg};
 
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, sync::Arc};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
 mod config;
 pub use config::{revm_spec, revm_spec_by_timestamp_after_merge};
 
@@ -29,10 +34,29 @@ pub mod dao_fork;
 /// [EIP-6110](https://eips.ethereum.org/EIPS/eip-6110) handling.
 pub mod eip6110;
 
+pub mod precompiles;
+use precompiles::PrecompileExtensions;
+
 /// Ethereum-related EVM configuration.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Default)]
 #[non_exhaustive]
-pub struct EthEvmConfig;
+pub struct EthEvmConfig {
+    /// Precompiles installed on top of the precompiles of the active hardfork.
+    precompiles: Arc<PrecompileExtensions>,
+}
+
+impl EthEvmConfig {
+    /// Sets the precompiles added to or overriding the precompiles of the active hardfork.
+    pub fn with_precompiles(mut self, precompiles: PrecompileExtensions) -> Self {
+        self.precompiles = Arc::new(precompiles);
+        self
+    }
+
+    /// Returns the precompiles added to or overriding the precompiles of the active hardfork.
+    pub fn precompiles(&self) -> &PrecompileExtensions {
+        &self.precompiles
+    }
+}
 
 impl ConfigureEvmEnv for EthEvmConfig {
     fn fill_cfg_env(
@@ -66,7 +90,37 @@ impl ConfigureEvm for EthEvmConfig {
         &self,
         db: DB,
     ) -> reth_revm::Evm<'a, Self::DefaultExternalContext<'a>, DB> {
-        EvmBuilder::default().with_db(db).build()
+        if self.precompiles.is_empty() {
+            return EvmBuilder::default().with_db(db).build()
+        }
+
+        let precompiles = self.precompiles.clone();
+        EvmBuilder::default()
+            .with_db(db)
+            .append_handler_register_box(Box::new(move |handler| precompiles.register(handler)))
+            .build()
+    }
+
+    fn evm_with_inspector<'a, DB, I>(&self, db: DB, inspector: I) -> reth_revm::Evm<'a, I, DB>
+    where
+        DB: Database + 'a,
+        I: GetInspector<DB>,
+    {
+        if self.precompiles.is_empty() {
+            return EvmBuilder::default()
+                .with_db(db)
+                .with_external_context(inspector)
+                .append_handler_register(inspector_handle_register)
+                .build()
+        }
+
+        let precompiles = self.precompiles.clone();
+        EvmBuilder::default()
+            .with_db(db)
+            .with_external_context(inspector)
+            .append_handler_register_box(Box::new(move |handler| precompiles.register(handler)))
+            .append_handler_register(inspector_handle_register)
+            .build()
     }
 }
 
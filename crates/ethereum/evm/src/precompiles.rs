@@ -0,0 +1,350 @@
+//! Precompiles added to or overriding the precompiles of a hardfork.
+//!
+//! Chains embedding reth, e.g. L2s and app-chains, can install their own precompiles with
+//! [`EthEvmConfig::with_precompiles`](crate::EthEvmConfig::with_precompiles) instead of wiring
+//! them into the revm handler themselves.
+
+use core::fmt;
+use reth_primitives::{Address, Bytes, U256};
+use reth_revm::{
+    handler::register::EvmHandler,
+    precompile::{Precompile, PrecompileErrors, PrecompileResult},
+    primitives::{Env, SpecId},
+    ContextPrecompile, ContextStatefulPrecompile, Database, InnerEvmContext,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+/// Access of a [`StatefulPrecompile`] to the EVM it's called in.
+///
+/// State changes are journaled, so they are reverted if the call of the precompile reverts.
+pub trait PrecompileContext {
+    /// Returns the environment of the EVM, e.g. the block and transaction being executed.
+    fn env(&self) -> &Env;
+
+    /// Returns the balance of the account.
+    fn balance(&mut self, address: Address) -> Result<U256, PrecompileErrors>;
+
+    /// Returns the code of the account.
+    fn code(&mut self, address: Address) -> Result<Bytes, PrecompileErrors>;
+
+    /// Returns the value of the storage slot of the account.
+    fn sload(&mut self, address: Address, index: U256) -> Result<U256, PrecompileErrors>;
+
+    /// Sets the value of the storage slot of the account.
+    fn sstore(
+        &mut self,
+        address: Address,
+        index: U256,
+        value: U256,
+    ) -> Result<(), PrecompileErrors>;
+}
+
+/// Returns the fatal precompile error for a failed database access.
+fn database_error() -> PrecompileErrors {
+    PrecompileErrors::Fatal { msg: "database error in precompile".to_string() }
+}
+
+impl<DB: Database> PrecompileContext for InnerEvmContext<DB> {
+    fn env(&self) -> &Env {
+        &self.env
+    }
+
+    fn balance(&mut self, address: Address) -> Result<U256, PrecompileErrors> {
+        Self::balance(self, address).map(|(balance, _)| balance).map_err(|_| database_error())
+    }
+
+    fn code(&mut self, address: Address) -> Result<Bytes, PrecompileErrors> {
+        Self::code(self, address).map(|(code, _)| code).map_err(|_| database_error())
+    }
+
+    fn sload(&mut self, address: Address, index: U256) -> Result<U256, PrecompileErrors> {
+        // storage can only be accessed for accounts loaded into the journal
+        self.load_account(address).map_err(|_| database_error())?;
+        Self::sload(self, address, index).map(|(value, _)| value).map_err(|_| database_error())
+    }
+
+    fn sstore(
+        &mut self,
+        address: Address,
+        index: U256,
+        value: U256,
+    ) -> Result<(), PrecompileErrors> {
+        self.load_account(address).map_err(|_| database_error())?;
+        // state changes of accounts that aren't touched are not committed
+        self.journaled_state.touch(&address);
+        Self::sstore(self, address, index, value).map(|_| ()).map_err(|_| database_error())
+    }
+}
+
+/// A precompile with access to the state of the EVM through a [`PrecompileContext`].
+pub trait StatefulPrecompile: fmt::Debug + Send + Sync {
+    /// Executes the precompile with the given input and gas limit.
+    fn call(
+        &self,
+        input: &Bytes,
+        gas_limit: u64,
+        context: &mut dyn PrecompileContext,
+    ) -> PrecompileResult;
+}
+
+/// Adapts a [`StatefulPrecompile`] to the precompiles of revm, which are generic over the
+/// database.
+struct StatefulPrecompileAdapter(Arc<dyn StatefulPrecompile>);
+
+impl<DB: Database> ContextStatefulPrecompile<DB> for StatefulPrecompileAdapter {
+    fn call(
+        &self,
+        bytes: &Bytes,
+        gas_limit: u64,
+        evmctx: &mut InnerEvmContext<DB>,
+    ) -> PrecompileResult {
+        self.0.call(bytes, gas_limit, evmctx)
+    }
+}
+
+/// Implementation of a custom precompile.
+#[derive(Clone)]
+pub enum CustomPrecompile {
+    /// Precompile that only depends on its input and the environment, e.g. a cryptographic
+    /// primitive.
+    Ordinary(Precompile),
+    /// Precompile with access to the state of the EVM.
+    Stateful(Arc<dyn StatefulPrecompile>),
+}
+
+impl CustomPrecompile {
+    /// Returns the precompile for an EVM with the given database.
+    fn to_context_precompile<DB: Database>(&self) -> ContextPrecompile<DB> {
+        match self {
+            Self::Ordinary(precompile) => ContextPrecompile::Ordinary(precompile.clone()),
+            Self::Stateful(precompile) => ContextPrecompile::ContextStateful(Arc::new(
+                StatefulPrecompileAdapter(precompile.clone()),
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for CustomPrecompile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ordinary(_) => f.write_str("Ordinary"),
+            Self::Stateful(precompile) => f.debug_tuple("Stateful").field(precompile).finish(),
+        }
+    }
+}
+
+impl From<Precompile> for CustomPrecompile {
+    fn from(precompile: Precompile) -> Self {
+        Self::Ordinary(precompile)
+    }
+}
+
+impl<P: StatefulPrecompile + 'static> From<Arc<P>> for CustomPrecompile {
+    fn from(precompile: Arc<P>) -> Self {
+        Self::Stateful(precompile)
+    }
+}
+
+/// A custom precompile at an address, active from a hardfork onwards.
+#[derive(Debug, Clone)]
+struct PrecompileEntry {
+    address: Address,
+    activation: SpecId,
+    precompile: CustomPrecompile,
+}
+
+/// Precompiles installed on top of the precompiles of the active hardfork.
+///
+/// A precompile is active from its activation hardfork onwards, and replaces the precompile of the
+/// hardfork at the same address, if any. Several precompiles can be added at the same address,
+/// e.g. to change the gas schedule of a precompile in a later hardfork, in which case the one with
+/// the latest active activation hardfork is used.
+#[derive(Debug, Clone, Default)]
+pub struct PrecompileExtensions {
+    precompiles: Vec<PrecompileEntry>,
+}
+
+impl PrecompileExtensions {
+    /// Adds the precompile at the given address, active from the `activation` hardfork onwards.
+    pub fn with_precompile(
+        mut self,
+        address: Address,
+        activation: SpecId,
+        precompile: impl Into<CustomPrecompile>,
+    ) -> Self {
+        self.precompiles.push(PrecompileEntry {
+            address,
+            activation,
+            precompile: precompile.into(),
+        });
+        self
+    }
+
+    /// Returns `true` if no precompiles were added.
+    pub fn is_empty(&self) -> bool {
+        self.precompiles.is_empty()
+    }
+
+    /// Returns the precompiles active at the given hardfork, at most one per address.
+    pub fn active_at(
+        &self,
+        spec_id: SpecId,
+    ) -> impl Iterator<Item = (Address, &CustomPrecompile)> + '_ {
+        let mut active: Vec<&PrecompileEntry> = Vec::new();
+        for entry in
+            self.precompiles.iter().filter(|entry| SpecId::enabled(spec_id, entry.activation))
+        {
+            match active.iter_mut().find(|other| other.address == entry.address) {
+                Some(other) if other.activation <= entry.activation => *other = entry,
+                Some(_) => {}
+                None => active.push(entry),
+            }
+        }
+        active.into_iter().map(|entry| (entry.address, &entry.precompile))
+    }
+
+    /// Installs the precompiles active at the spec of the handler on top of the precompiles it
+    /// loads.
+    ///
+    /// The precompiles are determined when the handler is registered, which happens again if the
+    /// spec of the EVM is changed.
+    pub fn register<EXT, DB: Database>(self: &Arc<Self>, handler: &mut EvmHandler<'_, EXT, DB>) {
+        let precompiles = self
+            .active_at(handler.cfg.spec_id)
+            .map(|(address, precompile)| (address, precompile.to_context_precompile::<DB>()))
+            .collect::<Vec<_>>();
+        if precompiles.is_empty() {
+            return
+        }
+
+        let load_precompiles = handler.pre_execution.load_precompiles.clone();
+        handler.pre_execution.load_precompiles = Arc::new(move || {
+            let mut loaded = load_precompiles();
+            loaded.extend(precompiles.iter().cloned());
+            loaded
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_revm::{
+        db::{states::bundle_state::BundleRetention, EmptyDB, State},
+        precompile::{PrecompileOutput, PrecompileSpecId},
+        primitives::{AccountInfo, Bytes, TxKind},
+        EvmBuilder,
+    };
+
+    /// The account the [`Counter`] precompile stores its count in.
+    const COUNTER_ADDRESS: Address = Address::with_last_byte(0xee);
+
+    fn fixed_gas(_input: &Bytes, _gas_limit: u64) -> PrecompileResult {
+        Ok(PrecompileOutput::new(100, Bytes::new()))
+    }
+
+    fn discounted_gas(_input: &Bytes, _gas_limit: u64) -> PrecompileResult {
+        Ok(PrecompileOutput::new(50, Bytes::new()))
+    }
+
+    #[derive(Debug)]
+    struct Counter;
+
+    impl StatefulPrecompile for Counter {
+        fn call(
+            &self,
+            _input: &Bytes,
+            _gas_limit: u64,
+            context: &mut dyn PrecompileContext,
+        ) -> PrecompileResult {
+            let count = context.sload(COUNTER_ADDRESS, U256::ZERO)? + U256::from(1);
+            context.sstore(COUNTER_ADDRESS, U256::ZERO, count)?;
+            Ok(PrecompileOutput::new(0, count.to_be_bytes_vec().into()))
+        }
+    }
+
+    #[test]
+    fn latest_active_precompile_per_address() {
+        let address = Address::with_last_byte(0xff);
+        let extensions = PrecompileExtensions::default()
+            .with_precompile(address, SpecId::CANCUN, Precompile::Standard(discounted_gas))
+            .with_precompile(address, SpecId::BERLIN, Precompile::Standard(fixed_gas));
+
+        assert_eq!(extensions.active_at(SpecId::LONDON).count(), 1);
+        let (_, precompile) = extensions.active_at(SpecId::LONDON).next().unwrap();
+        let CustomPrecompile::Ordinary(precompile) = precompile else { panic!("ordinary") };
+        assert_eq!(
+            precompile.call_ref(&Bytes::new(), 1000, &Env::default()).unwrap().gas_used,
+            100
+        );
+
+        let (_, precompile) = extensions.active_at(SpecId::CANCUN).next().unwrap();
+        let CustomPrecompile::Ordinary(precompile) = precompile else { panic!("ordinary") };
+        assert_eq!(precompile.call_ref(&Bytes::new(), 1000, &Env::default()).unwrap().gas_used, 50);
+
+        assert_eq!(extensions.active_at(SpecId::ISTANBUL).count(), 0);
+    }
+
+    #[test]
+    fn register_extends_hardfork_precompiles() {
+        let address = Address::with_last_byte(0xff);
+        let extensions = Arc::new(PrecompileExtensions::default().with_precompile(
+            address,
+            SpecId::BERLIN,
+            Arc::new(Counter),
+        ));
+
+        let evm = EvmBuilder::default()
+            .with_db(EmptyDB::default())
+            .with_spec_id(SpecId::CANCUN)
+            .append_handler_register_box(Box::new(move |handler| extensions.register(handler)))
+            .build();
+        let precompiles = (evm.handler.pre_execution.load_precompiles)();
+        assert!(precompiles.contains(&address));
+        let default = PrecompileSpecId::from_spec_id(SpecId::CANCUN);
+        assert_eq!(
+            precompiles.addresses().count(),
+            reth_revm::precompile::Precompiles::new(default).len() + 1
+        );
+    }
+
+    #[test]
+    fn stateful_precompile_changes_state() {
+        let address = Address::with_last_byte(0xff);
+        let extensions = Arc::new(PrecompileExtensions::default().with_precompile(
+            address,
+            SpecId::BERLIN,
+            Arc::new(Counter),
+        ));
+
+        let mut state =
+            State::builder().with_database(EmptyDB::default()).with_bundle_update().build();
+        state.insert_account(COUNTER_ADDRESS, AccountInfo { nonce: 1, ..Default::default() });
+
+        let mut evm = EvmBuilder::default()
+            .with_db(&mut state)
+            .with_spec_id(SpecId::CANCUN)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(0x01);
+                tx.transact_to = TxKind::Call(address);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register_box(Box::new(move |handler| extensions.register(handler)))
+            .build();
+        let result = evm.transact_commit().unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.output(), Some(&Bytes::from(U256::from(1).to_be_bytes_vec())));
+        drop(evm);
+
+        // the storage of the counter account is changed, even though the transaction doesn't
+        // touch it otherwise
+        state.merge_transitions(BundleRetention::Reverts);
+        let bundle = state.take_bundle();
+        let counter = bundle.account(&COUNTER_ADDRESS).expect("counter account is changed");
+        assert_eq!(counter.storage_slot(U256::ZERO), Some(U256::from(1)));
+    }
+}
@@ -1,12 +1,17 @@
 //! Ethereum Node types config.
 
 use crate::{EthEngineTypes, EthEvmConfig};
+use futures_util::StreamExt;
 use reth_auto_seal_consensus::AutoSealConsensus;
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, ExternalBuilder,
+    ExternalBuilderSelection, ExternalBuilders, HttpExternalBuilder, PayloadBuildBudget,
+};
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_ethereum_engine_primitives::{
     EthBuiltPayload, EthPayloadAttributes, EthPayloadBuilderAttributes,
 };
+use reth_evm::{events::ExecutionEventsSender, profiling::DEFAULT_PROFILED_CONTRACTS};
 use reth_evm_ethereum::execute::EthExecutorProvider;
 use reth_network::NetworkHandle;
 use reth_node_builder::{
@@ -83,9 +88,25 @@ where
 }
 
 /// A regular ethereum evm and executor builder.
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct EthereumExecutorBuilder;
+pub struct EthereumExecutorBuilder {
+    /// Sender of the events of executed live blocks, if enabled.
+    execution_events: Option<ExecutionEventsSender>,
+}
+
+impl EthereumExecutorBuilder {
+    /// Publishes the events of live blocks executed by the node, e.g. new blocks of the
+    /// blockchain tree, to the subscribers of the given sender.
+    ///
+    /// The events of a block are published once the block becomes canonical, in the order of the
+    /// canonical state notifications of the node. Events of side chains are only published if
+    /// they're reorged to.
+    pub fn with_execution_events(mut self, execution_events: ExecutionEventsSender) -> Self {
+        self.execution_events = Some(execution_events);
+        self
+    }
+}
 
 impl<Node> ExecutorBuilder<Node> for EthereumExecutorBuilder
 where
@@ -100,7 +121,23 @@ where
     ) -> eyre::Result<(Self::EVM, Self::Executor)> {
         let chain_spec = ctx.chain_spec();
         let evm_config = EthEvmConfig::default();
-        let executor = EthExecutorProvider::new(chain_spec, evm_config);
+        let mut executor = EthExecutorProvider::new(chain_spec, evm_config.clone());
+        if let Some(execution_events) = self.execution_events {
+            let mut canon_state_stream = ctx.provider().canonical_state_stream();
+            let canonical_events = execution_events.clone();
+            ctx.task_executor().spawn_critical(
+                "execution events publisher",
+                Box::pin(async move {
+                    while let Some(notification) = canon_state_stream.next().await {
+                        canonical_events.publish_canonical(&notification.committed());
+                    }
+                }),
+            );
+            executor = executor.with_execution_events(execution_events);
+        }
+        if ctx.config().debug.gas_profile {
+            executor = executor.with_gas_profiling(DEFAULT_PROFILED_CONTRACTS);
+        }
 
         Ok((evm_config, executor))
     }
@@ -161,6 +198,19 @@ where
                 },
             );
 
+            // spawn the state prewarming task
+            if ctx.config().txpool.prewarm_state {
+                ctx.task_executor().spawn(
+                    reth_transaction_pool::prewarm::prewarm_state_cache_future(
+                        client.clone(),
+                        pool.clone(),
+                        ctx.shared_cached_reads().clone(),
+                        ctx.task_executor().clone(),
+                    ),
+                );
+                debug!(target: "reth::cli", "Spawned txpool state prewarming task");
+            }
+
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
                 "txpool maintenance task",
@@ -206,16 +256,48 @@ where
             .interval(conf.interval())
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
+            .max_iterations(conf.max_iterations())
+            .max_build_time(conf.max_build_time())
+            .budget(PayloadBuildBudget {
+                gas: conf.iteration_gas_budget(),
+                time: conf.iteration_time_budget(),
+            })
+            .incremental_rebuild_min_tip(conf.incremental_rebuild_min_tip())
+            .empty_payload_min_tip(conf.empty_payload_min_tip())
             .extradata(conf.extradata_bytes());
 
-        let payload_generator = BasicPayloadJobGenerator::with_builder(
+        let mut payload_generator = BasicPayloadJobGenerator::with_builder(
             ctx.provider().clone(),
             pool,
             ctx.task_executor().clone(),
             payload_job_config,
             ctx.chain_spec(),
             payload_builder,
-        );
+        )
+        .with_shared_cached_reads(ctx.shared_cached_reads().clone());
+
+        if !conf.external_builders().is_empty() {
+            let builders = conf
+                .external_builders()
+                .iter()
+                .map(|url| {
+                    Ok(Arc::new(HttpExternalBuilder::new(url.as_str())?)
+                        as Arc<dyn ExternalBuilder>)
+                })
+                .collect::<eyre::Result<Vec<_>>>()?;
+            let selection = if conf.external_fee_recipients().is_empty() {
+                ExternalBuilderSelection::All
+            } else {
+                ExternalBuilderSelection::FeeRecipients(
+                    conf.external_fee_recipients().iter().copied().collect(),
+                )
+            };
+            payload_generator = payload_generator.with_external_builders(
+                ExternalBuilders::new(builders)
+                    .with_selection(selection)
+                    .with_timeout(conf.external_builder_timeout()),
+            );
+        }
         let (payload_service, payload_builder) =
             PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
 
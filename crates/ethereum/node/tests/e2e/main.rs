@@ -2,6 +2,7 @@ mod blobs;
 mod dev;
 mod eth;
 mod p2p;
+mod sync;
 mod utils;
 
 const fn main() {}
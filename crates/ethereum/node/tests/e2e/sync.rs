@@ -0,0 +1,149 @@
+use crate::utils::{eth_payload_attributes, EthNode};
+use reth::primitives::StaticFileSegment;
+use reth_chainspec::{ChainSpecBuilder, MAINNET};
+use reth_db::tables;
+use reth_e2e_test_utils::{setup, transaction::TransactionTestContext, wallet::Wallet};
+use reth_node_ethereum::EthereumNode;
+use reth_payload_builder::{EthBuiltPayload, EthPayloadBuilderAttributes};
+use reth_provider::{
+    DatabaseProviderFactory, HeaderProvider, StageCheckpointReader, StateProviderFactory,
+    StateRootProvider, StaticFileProviderFactory, StatsReader,
+};
+use reth_stages_types::StageId;
+use std::sync::Arc;
+
+/// Environment variable overriding the number of blocks synced by the harness.
+const SYNC_BLOCKS_ENV: &str = "RETH_E2E_SYNC_BLOCKS";
+
+/// Default number of blocks synced by the harness.
+///
+/// Needs to be above the engine's pipeline run threshold, so the syncing node goes through the
+/// pipeline instead of the blockchain tree.
+const DEFAULT_SYNC_BLOCKS: u64 = 64;
+
+/// Syncs a chain produced by a local fixture peer from genesis through the full pipeline and
+/// checks the result against the fixture node.
+#[tokio::test]
+async fn can_sync_from_genesis() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let blocks = std::env::var(SYNC_BLOCKS_ENV)
+        .ok()
+        .map(|blocks| blocks.parse::<u64>())
+        .transpose()?
+        .unwrap_or(DEFAULT_SYNC_BLOCKS);
+
+    let (mut nodes, _tasks, wallet) = setup::<EthereumNode>(
+        2,
+        Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+                .cancun_activated()
+                .build(),
+        ),
+        false,
+    )
+    .await?;
+
+    let syncing_node = nodes.pop().unwrap();
+    let mut fixture_node = nodes.pop().unwrap();
+
+    // Build the fixture chain on the first node, with one transfer per block
+    let chain = advance_chain(blocks, &mut fixture_node, &wallet).await?;
+    let tip = chain.last().unwrap().0.block();
+
+    // Only tell the second node about the tip, so it has to sync everything from its peer
+    syncing_node.engine_api.update_optimistic_forkchoice(tip.hash()).await?;
+    syncing_node.wait_block(tip.number, tip.hash(), true).await?;
+
+    assert_synced(&syncing_node, &fixture_node, tip.number)?;
+
+    Ok(())
+}
+
+/// Advance the chain with deterministic transfers, returning the built payloads.
+async fn advance_chain(
+    length: u64,
+    node: &mut EthNode,
+    wallet: &Wallet,
+) -> eyre::Result<Vec<(EthBuiltPayload, EthPayloadBuilderAttributes)>> {
+    let chain_id = wallet.chain_id;
+    let signer = wallet.inner.clone();
+    node.advance(
+        length,
+        |nonce| {
+            Box::pin(TransactionTestContext::deterministic_transfer_tx_bytes(
+                chain_id,
+                signer.clone(),
+                nonce,
+            ))
+        },
+        eth_payload_attributes,
+    )
+    .await
+}
+
+/// Asserts that `node` was synced to `tip` by the pipeline and ended up with the same state as
+/// the `fixture` node.
+fn assert_synced(node: &EthNode, fixture: &EthNode, tip: u64) -> eyre::Result<()> {
+    let provider = &node.inner.provider;
+    let fixture_provider = &fixture.inner.provider;
+
+    // Every stage has reached the tip
+    for stage in StageId::ALL {
+        if stage == StageId::MerkleUnwind {
+            continue
+        }
+        let checkpoint = provider.get_stage_checkpoint(stage)?.unwrap_or_default();
+        assert_eq!(checkpoint.block_number, tip, "stage {stage} did not reach the tip");
+    }
+
+    // State root of the synced state matches the one of the fixture chain
+    let expected_state_root = fixture_provider.header_by_number(tip)?.unwrap().state_root;
+    assert_eq!(provider.header_by_number(tip)?.unwrap().state_root, expected_state_root);
+    assert_eq!(provider.latest()?.state_root(&Default::default())?, expected_state_root);
+
+    // Static files were written up to the tip
+    let static_file_provider = provider.static_file_provider();
+    for segment in
+        [StaticFileSegment::Headers, StaticFileSegment::Transactions, StaticFileSegment::Receipts]
+    {
+        assert_eq!(
+            static_file_provider.get_highest_static_file_block(segment),
+            Some(tip),
+            "static file segment {segment} is not at the tip"
+        );
+    }
+
+    // Tables that are written the same way by the pipeline and the blockchain tree
+    let db = provider.database_provider_ro()?;
+    let fixture_db = fixture_provider.database_provider_ro()?;
+    macro_rules! assert_entries {
+        ($($table:ident),+ $(,)?) => {$(
+            assert_eq!(
+                db.count_entries::<tables::$table>()?,
+                fixture_db.count_entries::<tables::$table>()?,
+                concat!("entry count mismatch in ", stringify!($table))
+            );
+        )+};
+    }
+    assert_entries!(
+        BlockBodyIndices,
+        TransactionBlocks,
+        TransactionSenders,
+        TransactionHashNumbers,
+        PlainAccountState,
+        PlainStorageState,
+        Bytecodes,
+        AccountChangeSets,
+        StorageChangeSets,
+        HashedAccounts,
+        HashedStorages,
+        AccountsHistory,
+        StoragesHistory,
+    );
+    assert_eq!(db.count_entries::<tables::TransactionSenders>()?, tip as usize);
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! Selection of the blob transactions of a payload.
+
+use reth_primitives::{Address, IntoRecoveredTransaction, TxHash};
+use reth_transaction_pool::{PoolTransaction, ValidPoolTransaction};
+use std::collections::{HashMap, HashSet};
+
+/// Maximum number of blob transactions of the pool considered for a payload.
+pub(crate) const MAX_BLOB_CANDIDATES: usize = 64;
+
+/// Maximum number of best transactions of the pool that are scanned for blob candidates, so that
+/// a pool full of other transactions isn't iterated for every payload.
+pub(crate) const MAX_BLOB_CANDIDATE_SCAN: usize = 1024;
+
+/// Intrinsic gas of a transaction.
+const TX_BASE_GAS: u64 = 21_000;
+
+/// A blob transaction of the pool that can be included in a payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlobCandidate {
+    /// Hash of the transaction.
+    pub(crate) hash: TxHash,
+    /// Sender of the transaction.
+    pub(crate) sender: Address,
+    /// Number of blobs of the transaction.
+    pub(crate) blobs: u64,
+    /// Estimated priority fees the transaction pays.
+    pub(crate) value: u128,
+}
+
+impl BlobCandidate {
+    /// Creates a candidate for the pool transaction, estimating its fees at the given base fee.
+    ///
+    /// Blob transactions usually only use the gas needed for their calldata, so the fees are
+    /// estimated with [`calldata_gas`] instead of the gas limit.
+    pub(crate) fn new<T: PoolTransaction>(
+        tx: &ValidPoolTransaction<T>,
+        base_fee: u64,
+        eip7623: bool,
+    ) -> Self {
+        let gas = calldata_gas(tx.transaction.input(), eip7623).min(tx.transaction.gas_limit());
+        let tip = tx.effective_tip_per_gas(base_fee).unwrap_or_default();
+        Self {
+            hash: *tx.hash(),
+            sender: tx.sender(),
+            blobs: tx
+                .to_recovered_transaction()
+                .blob_versioned_hashes()
+                .map_or(0, |hashes| hashes.len() as u64),
+            value: tip.saturating_mul(gas as u128),
+        }
+    }
+}
+
+/// Returns the minimum gas used by a transaction with the given calldata.
+///
+/// With [EIP-7623](https://eips.ethereum.org/EIPS/eip-7623), calldata costs at least 10 gas per
+/// zero byte and 40 gas per non-zero byte, instead of 4 and 16 gas.
+pub(crate) fn calldata_gas(input: &[u8], eip7623: bool) -> u64 {
+    let zero_bytes = input.iter().filter(|byte| **byte == 0).count() as u64;
+    let non_zero_bytes = input.len() as u64 - zero_bytes;
+    let (zero_byte_gas, non_zero_byte_gas) = if eip7623 { (10, 40) } else { (4, 16) };
+    TX_BASE_GAS + zero_bytes * zero_byte_gas + non_zero_bytes * non_zero_byte_gas
+}
+
+/// Chooses the blob transactions that pay the most fees without exceeding `max_blobs` blobs.
+///
+/// The candidates of a sender must be in nonce order, because a transaction can only be included
+/// together with all previous transactions of its sender. This makes the selection a knapsack
+/// problem over blob counts, where every sender contributes a prefix of its transactions.
+pub(crate) fn pack_blob_transactions(
+    candidates: impl IntoIterator<Item = BlobCandidate>,
+    max_blobs: u64,
+) -> HashSet<TxHash> {
+    // group the candidates by sender, keeping the nonce order
+    let mut senders = HashMap::new();
+    let mut groups: Vec<Vec<BlobCandidate>> = Vec::new();
+    for candidate in candidates {
+        let group = *senders.entry(candidate.sender).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(candidate);
+    }
+
+    let capacity = max_blobs as usize;
+    // the most fees with at most `blobs` blobs from the groups so far
+    let mut best = vec![0u128; capacity + 1];
+    // the number of transactions taken from each group for the best fees with at most `blobs`
+    let mut taken = Vec::with_capacity(groups.len());
+
+    for group in &groups {
+        let mut next = best.clone();
+        let mut group_taken = vec![0usize; capacity + 1];
+        for (blobs, group_best) in next.iter_mut().enumerate() {
+            let (mut prefix_blobs, mut prefix_value) = (0usize, 0u128);
+            for (index, candidate) in group.iter().enumerate() {
+                prefix_blobs += candidate.blobs as usize;
+                prefix_value = prefix_value.saturating_add(candidate.value);
+                if prefix_blobs > blobs {
+                    break
+                }
+                let value = best[blobs - prefix_blobs].saturating_add(prefix_value);
+                if value > *group_best {
+                    *group_best = value;
+                    group_taken[blobs] = index + 1;
+                }
+            }
+        }
+        best = next;
+        taken.push(group_taken);
+    }
+
+    // walk back through the groups to collect the chosen transactions
+    let mut selected = HashSet::new();
+    let mut blobs = capacity;
+    for (group, group_taken) in groups.iter().zip(taken).rev() {
+        let count = group_taken[blobs];
+        for candidate in &group[..count] {
+            selected.insert(candidate.hash);
+            blobs -= candidate.blobs as usize;
+        }
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(sender: u8, nonce: u8, blobs: u64, value: u128) -> BlobCandidate {
+        BlobCandidate {
+            hash: TxHash::with_last_byte(sender.wrapping_mul(16).wrapping_add(nonce)),
+            sender: Address::with_last_byte(sender),
+            blobs,
+            value,
+        }
+    }
+
+    #[test]
+    fn pack_most_valuable_blobs() {
+        // a greedy selection by value per blob would take the 4-blob transaction first, and then
+        // none of the 3-blob transactions fit
+        let big = candidate(1, 0, 4, 90);
+        let small_a = candidate(2, 0, 3, 60);
+        let small_b = candidate(3, 0, 3, 60);
+        let selected = pack_blob_transactions([big, small_a.clone(), small_b.clone()], 6);
+        assert_eq!(selected, HashSet::from([small_a.hash, small_b.hash]));
+    }
+
+    #[test]
+    fn pack_sender_transactions_in_nonce_order() {
+        // the valuable second transaction requires the cheap first one
+        let first = candidate(1, 0, 3, 1);
+        let second = candidate(1, 1, 3, 100);
+        let other = candidate(2, 0, 3, 50);
+        let selected = pack_blob_transactions([first.clone(), second.clone(), other], 6);
+        assert_eq!(selected, HashSet::from([first.hash, second.hash]));
+
+        // without room for both, the second transaction can't be included
+        let selected = pack_blob_transactions([first, second, candidate(2, 0, 3, 50)], 3);
+        assert_eq!(selected, HashSet::from([candidate(2, 0, 3, 50).hash]));
+    }
+
+    #[test]
+    fn calldata_gas_with_eip7623() {
+        let input = [0, 0, 1, 2];
+        assert_eq!(calldata_gas(&input, false), 21_000 + 2 * 4 + 2 * 16);
+        assert_eq!(calldata_gas(&input, true), 21_000 + 2 * 10 + 2 * 40);
+    }
+}
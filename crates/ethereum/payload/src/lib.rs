@@ -10,20 +10,28 @@
 #![allow(clippy::useless_let_if_seq)]
 
 use reth_basic_payload_builder::{
-    commit_withdrawals, is_better_payload, post_block_withdrawal_requests_contract_call,
-    pre_block_beacon_root_contract_call, BuildArguments, BuildOutcome, PayloadBuilder,
-    PayloadConfig, WithdrawalsOutcome,
+    commit_withdrawals, is_better_payload, post_block_system_calls, pre_block_system_calls,
+    BuildArguments, BuildOutcome, ExecutedPrefix, ExternalBid, ExternalPayloadError,
+    PayloadBuilder, PayloadConfig, WithdrawalsOutcome,
 };
+use reth_consensus::Consensus;
 use reth_errors::RethError;
-use reth_evm::ConfigureEvm;
-use reth_evm_ethereum::{eip6110::parse_deposits_from_receipts, EthEvmConfig};
+use reth_ethereum_consensus::EthBeaconConsensus;
+use reth_evm::{
+    execute::{BlockExecutionInput, BlockExecutorProvider, Executor},
+    ConfigureEvm,
+};
+use reth_evm_ethereum::{
+    eip6110::parse_deposits_from_receipts, execute::EthExecutorProvider, EthEvmConfig,
+};
 use reth_execution_types::ExecutionOutcome;
 use reth_payload_builder::{
     error::PayloadBuilderError, EthBuiltPayload, EthPayloadBuilderAttributes,
 };
 use reth_primitives::{
     constants::{
-        eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
+        eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK},
+        BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
     },
     eip4844::calculate_excess_blob_gas,
     proofs::{self, calculate_requests_root},
@@ -32,26 +40,52 @@ use reth_primitives::{
     U256,
 };
 use reth_provider::StateProviderFactory;
-use reth_revm::{database::StateProviderDatabase, state_change::apply_blockhashes_update};
+use reth_revm::{
+    database::StateProviderDatabase,
+    system_calls::{SystemCallBlock, SystemCaller},
+};
 use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use revm::{
     db::states::bundle_state::BundleRetention,
     primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
     DatabaseCommit, State,
 };
+use std::{collections::HashSet, time::Instant};
 use tracing::{debug, trace, warn};
 
+mod blobs;
+use blobs::{pack_blob_transactions, BlobCandidate, MAX_BLOB_CANDIDATES, MAX_BLOB_CANDIDATE_SCAN};
+
+mod metrics;
+use metrics::BlobPackingMetrics;
+
+mod selection;
+pub use selection::{TransactionSelection, TransactionSelector};
+
 /// Ethereum payload builder
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
+pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig, Selector = ()> {
     /// The type responsible for creating the evm.
     evm_config: EvmConfig,
+    /// The hook that selects the transactions of the payloads.
+    selector: Selector,
 }
 
 impl<EvmConfig> EthereumPayloadBuilder<EvmConfig> {
     /// `EthereumPayloadBuilder` constructor.
     pub const fn new(evm_config: EvmConfig) -> Self {
-        Self { evm_config }
+        Self { evm_config, selector: () }
+    }
+}
+
+impl<EvmConfig, Selector> EthereumPayloadBuilder<EvmConfig, Selector> {
+    /// Sets the [`TransactionSelector`] that customizes which transactions are included in the
+    /// payloads and in which order.
+    pub fn with_transaction_selector<S>(self, selector: S) -> EthereumPayloadBuilder<EvmConfig, S>
+    where
+        S: TransactionSelector,
+    {
+        EthereumPayloadBuilder { evm_config: self.evm_config, selector }
     }
 }
 
@@ -62,9 +96,11 @@ impl Default for EthereumPayloadBuilder {
 }
 
 // Default implementation of [PayloadBuilder] for unit type
-impl<EvmConfig, Pool, Client> PayloadBuilder<Pool, Client> for EthereumPayloadBuilder<EvmConfig>
+impl<EvmConfig, Selector, Pool, Client> PayloadBuilder<Pool, Client>
+    for EthereumPayloadBuilder<EvmConfig, Selector>
 where
     EvmConfig: ConfigureEvm,
+    Selector: TransactionSelector,
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
@@ -75,7 +111,7 @@ where
         &self,
         args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-        default_ethereum_payload_builder(self.evm_config.clone(), args)
+        ethereum_payload_builder_with_selector(self.evm_config.clone(), self.selector.clone(), args)
     }
 
     fn build_empty_payload(
@@ -112,36 +148,31 @@ where
         let block_number = initialized_block_env.number.to::<u64>();
         let block_gas_limit = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
 
-        // apply eip-4788 pre block contract call
-        pre_block_beacon_root_contract_call(
+        let system_caller = SystemCaller::new(chain_spec.clone());
+        let system_call_block = SystemCallBlock {
+            number: block_number,
+            timestamp: attributes.timestamp,
+            parent_hash: parent_block.hash(),
+            parent_beacon_block_root: attributes.parent_beacon_block_root,
+        };
+
+        // apply pre block system calls, e.g. eip-4788 and eip-2935
+        pre_block_system_calls(
             &mut db,
-            &chain_spec,
-            block_number,
+            &system_caller,
+            &system_call_block,
             &initialized_cfg,
             &initialized_block_env,
-            &attributes,
         )
         .map_err(|err| {
             warn!(target: "payload_builder",
                 parent_hash=%parent_block.hash(),
                 %err,
-                "failed to apply beacon root contract call for empty payload"
+                "failed to apply pre block system calls for empty payload"
             );
             err
         })?;
 
-        // apply eip-2935 blockhashes update
-        apply_blockhashes_update(
-            &mut db,
-            &chain_spec,
-            initialized_block_env.timestamp.to::<u64>(),
-            block_number,
-            parent_block.hash(),
-        ).map_err(|err| {
-            warn!(target: "payload_builder", parent_hash=%parent_block.hash(), %err, "failed to update blockhashes for empty payload");
-            PayloadBuilderError::Internal(err.into())
-        })?;
-
         let WithdrawalsOutcome { withdrawals_root, withdrawals } = commit_withdrawals(
             &mut db,
             &chain_spec,
@@ -194,13 +225,14 @@ where
             if chain_spec.is_prague_active_at_timestamp(attributes.timestamp) {
                 // We do not calculate the EIP-6110 deposit requests because there are no
                 // transactions in an empty payload.
-                let withdrawal_requests = post_block_withdrawal_requests_contract_call(
+                let requests = post_block_system_calls(
                     &mut db,
+                    &system_caller,
+                    &system_call_block,
                     &initialized_cfg,
                     &initialized_block_env,
                 )?;
 
-                let requests = withdrawal_requests;
                 let requests_root = calculate_requests_root(&requests);
                 (Some(requests.into()), Some(requests_root))
             } else {
@@ -236,6 +268,130 @@ where
 
         Ok(EthBuiltPayload::new(attributes.payload_id(), sealed_block, U256::ZERO))
     }
+
+    fn validate_external_payload(
+        &self,
+        client: &Client,
+        config: &PayloadConfig<Self::Attributes>,
+        bid: ExternalBid,
+    ) -> Result<EthBuiltPayload, PayloadBuilderError> {
+        validate_external_ethereum_payload(self.evm_config.clone(), client, config, bid)
+    }
+}
+
+/// Validates the payload of an external builder by executing it on top of its parent block.
+///
+/// The payload must match the payload attributes, its header must pass the consensus checks
+/// against the parent block, and the gas used, receipts root, requests root and state root of its
+/// header must match the outcome of the execution. Payloads with blob transactions are rejected,
+/// because the node doesn't have their sidecars.
+///
+/// Returns the payload with the priority fees paid by its transactions.
+pub fn validate_external_ethereum_payload<EvmConfig, Client>(
+    evm_config: EvmConfig,
+    client: &Client,
+    config: &PayloadConfig<EthPayloadBuilderAttributes>,
+    bid: ExternalBid,
+) -> Result<EthBuiltPayload, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    Client: StateProviderFactory,
+{
+    let PayloadConfig { parent_block, attributes, chain_spec, .. } = config;
+    let block = bid.block;
+    let mismatch = |err: ExternalPayloadError| Err(PayloadBuilderError::other(err));
+
+    if block.parent_hash != parent_block.hash() {
+        return mismatch(ExternalPayloadError::ParentMismatch {
+            got: block.parent_hash,
+            expected: parent_block.hash(),
+        })
+    }
+    if block.number != parent_block.number + 1 {
+        return mismatch(ExternalPayloadError::AttributesMismatch("block number"))
+    }
+    if block.timestamp != attributes.timestamp {
+        return mismatch(ExternalPayloadError::AttributesMismatch("timestamp"))
+    }
+    if block.beneficiary != attributes.suggested_fee_recipient {
+        return mismatch(ExternalPayloadError::AttributesMismatch("fee recipient"))
+    }
+    if block.mix_hash != attributes.prev_randao {
+        return mismatch(ExternalPayloadError::AttributesMismatch("prev randao"))
+    }
+    if block.parent_beacon_block_root != attributes.parent_beacon_block_root {
+        return mismatch(ExternalPayloadError::AttributesMismatch("parent beacon block root"))
+    }
+    if chain_spec.is_shanghai_active_at_timestamp(attributes.timestamp) &&
+        block.withdrawals.as_ref() != Some(&attributes.withdrawals)
+    {
+        return mismatch(ExternalPayloadError::AttributesMismatch("withdrawals"))
+    }
+    if block.body.iter().any(|tx| tx.is_eip4844()) {
+        return mismatch(ExternalPayloadError::BlobTransactions)
+    }
+
+    // the header must be valid on top of the parent like the header of any other block, e.g. its
+    // gas limit, base fee, extra data and blob gas fields
+    let consensus = EthBeaconConsensus::new(chain_spec.clone());
+    if let Err(err) = consensus
+        .validate_header(&block.header)
+        .and_then(|_| consensus.validate_header_against_parent(&block.header, &parent_block.header))
+        .and_then(|_| consensus.validate_block_pre_execution(&block))
+    {
+        return mismatch(ExternalPayloadError::InvalidHeader(err))
+    }
+
+    let Some(block_with_senders) = block.clone().unseal().with_recovered_senders() else {
+        return mismatch(ExternalPayloadError::InvalidSignature)
+    };
+
+    debug!(target: "payload_builder", builder = %bid.builder, parent_hash = ?parent_block.hash(), hash = ?block.hash(), "validating external payload");
+
+    let state_provider = client.state_by_block_hash(parent_block.hash())?;
+    let total_difficulty =
+        chain_spec.final_paris_total_difficulty(block.number).unwrap_or_default();
+    let output = EthExecutorProvider::new(chain_spec.clone(), evm_config)
+        .executor(StateProviderDatabase::new(state_provider))
+        .execute(BlockExecutionInput::new(&block_with_senders, total_difficulty))
+        .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
+
+    if output.gas_used != block.gas_used {
+        return mismatch(ExternalPayloadError::GasUsedMismatch {
+            got: output.gas_used,
+            expected: block.gas_used,
+        })
+    }
+
+    let receipts = output.receipts.iter().collect::<Vec<_>>();
+    if proofs::calculate_receipt_root_no_memo(&receipts) != block.receipts_root {
+        return mismatch(ExternalPayloadError::ReceiptsRootMismatch)
+    }
+
+    if chain_spec.is_prague_active_at_timestamp(attributes.timestamp) &&
+        Some(calculate_requests_root(&output.requests)) != block.requests_root
+    {
+        return mismatch(ExternalPayloadError::RequestsRootMismatch)
+    }
+
+    let state_root = client.state_by_block_hash(parent_block.hash())?.state_root(&output.state)?;
+    if state_root != block.state_root {
+        return mismatch(ExternalPayloadError::StateRootMismatch)
+    }
+
+    // the fees are the priority fees of the transactions, paid for the gas they used
+    let mut fees = U256::ZERO;
+    let mut cumulative_gas_used = 0;
+    for (tx, receipt) in block.body.iter().zip(&output.receipts) {
+        let gas_used = receipt.cumulative_gas_used - cumulative_gas_used;
+        cumulative_gas_used = receipt.cumulative_gas_used;
+        let miner_fee = tx
+            .effective_tip_per_gas(block.base_fee_per_gas)
+            .expect("fee is always valid; execution succeeded");
+        fees += U256::from(miner_fee) * U256::from(gas_used);
+    }
+
+    Ok(EthBuiltPayload::new(attributes.payload_id(), block, fees))
 }
 
 /// Constructs an Ethereum transaction payload using the best transactions from the pool.
@@ -253,12 +409,52 @@ where
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    ethereum_payload_builder_with_selector(evm_config, (), args)
+}
+
+/// Constructs an Ethereum transaction payload like [`default_ethereum_payload_builder`], but lets
+/// the given [`TransactionSelector`] decide which transactions are included and in which order.
+///
+/// The transactions of the [priority senders](TransactionSelector::priority_senders) are tried
+/// first, followed by the best transactions from the pool. Every candidate is passed to
+/// [`TransactionSelector::select`] before and to [`TransactionSelector::on_simulated`] after its
+/// execution.
+///
+/// If the arguments contain an [`ExecutedPrefix`], the attempt resumes from it, and only new
+/// transactions are executed on top of the prefix.
+#[inline]
+pub fn ethereum_payload_builder_with_selector<EvmConfig, Selector, Pool, Client>(
+    evm_config: EvmConfig,
+    mut selector: Selector,
+    args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    Selector: TransactionSelector,
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload, prefix } =
+        args;
+    let resumed = prefix.is_some();
+    let ExecutedPrefix {
+        bundle,
+        transactions: mut executed_txs,
+        receipts,
+        mut cumulative_gas_used,
+        blob_gas_used: mut sum_blob_gas_used,
+        mut blob_sidecars,
+        fees: mut total_fees,
+    } = prefix.unwrap_or_default();
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
     let state = StateProviderDatabase::new(state_provider);
-    let mut db =
-        State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update().build();
+    let mut state_builder =
+        State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update();
+    if resumed {
+        state_builder = state_builder.with_bundle_prestate(bundle);
+    }
+    let mut db = state_builder.build();
     let extra_data = config.extra_data();
     let PayloadConfig {
         initialized_block_env,
@@ -266,50 +462,120 @@ where
         parent_block,
         attributes,
         chain_spec,
+        budget,
+        resumable,
         ..
     } = config;
 
-    debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, "building new payload");
-    let mut cumulative_gas_used = 0;
-    let mut sum_blob_gas_used = 0;
+    debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, resumed_txs = executed_txs.len(), "building new payload");
     let block_gas_limit: u64 = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
     let base_fee = initialized_block_env.basefee.to::<u64>();
 
-    let mut executed_txs = Vec::new();
-
     let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
         base_fee,
         initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
     ));
 
-    let mut total_fees = U256::ZERO;
+    // transactions of the priority senders, which are tried before the best transactions
+    let priority_senders = selector.priority_senders(&attributes);
+    let mut priority_txs = priority_senders
+        .iter()
+        .flat_map(|sender| {
+            let mut txs = pool.get_transactions_by_sender(*sender);
+            txs.sort_unstable_by_key(|tx| tx.nonce());
+            txs
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+    // hashes of the executed and the priority transactions, which are skipped among the best
+    // transactions
+    let mut tried_txs = executed_txs.iter().map(|tx| tx.hash).collect::<HashSet<_>>();
+    if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+        best_txs.skip_blobs();
+    }
+
+    // choose the blob transactions of the pool that pay the most fees within the blob limit,
+    // instead of taking them greedily in the order of the pool
+    let blob_metrics = BlobPackingMetrics::default();
+    let packed_blob_txs = if chain_spec.is_cancun_active_at_timestamp(attributes.timestamp) {
+        let eip7623 = chain_spec.is_prague_active_at_timestamp(attributes.timestamp);
+        let candidates = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::new(
+                base_fee,
+                initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
+            ))
+            .take(MAX_BLOB_CANDIDATE_SCAN)
+            .filter(|tx| {
+                tx.is_eip4844() &&
+                    !tried_txs.contains(tx.hash()) &&
+                    !priority_senders.contains(&tx.sender())
+            })
+            .take(MAX_BLOB_CANDIDATES)
+            .map(|tx| BlobCandidate::new(&tx, base_fee, eip7623))
+            .collect::<Vec<_>>();
+        blob_metrics
+            .blobs_available
+            .set(candidates.iter().map(|candidate| candidate.blobs).sum::<u64>() as f64);
+        let available_blobs = (MAX_DATA_GAS_PER_BLOCK - sum_blob_gas_used) / DATA_GAS_PER_BLOB;
+        pack_blob_transactions(candidates, available_blobs)
+    } else {
+        HashSet::new()
+    };
 
     let block_number = initialized_block_env.number.to::<u64>();
+    let system_caller = SystemCaller::new(chain_spec.clone());
+    let system_call_block = SystemCallBlock {
+        number: block_number,
+        timestamp: attributes.timestamp,
+        parent_hash: parent_block.hash(),
+        parent_beacon_block_root: attributes.parent_beacon_block_root,
+    };
 
-    // apply eip-4788 pre block contract call
-    pre_block_beacon_root_contract_call(
-        &mut db,
-        &chain_spec,
-        block_number,
-        &initialized_cfg,
-        &initialized_block_env,
-        &attributes,
-    )?;
-
-    // apply eip-2935 blockhashes update
-    apply_blockhashes_update(
-        &mut db,
-        &chain_spec,
-        initialized_block_env.timestamp.to::<u64>(),
-        block_number,
-        parent_block.hash(),
-    )
-    .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
+    // the pre-block calls are part of the prefix the attempt resumes from
+    if !resumed {
+        // apply pre block system calls, e.g. eip-4788 and eip-2935
+        pre_block_system_calls(
+            &mut db,
+            &system_caller,
+            &system_call_block,
+            &initialized_cfg,
+            &initialized_block_env,
+        )?;
+    }
+
+    let mut receipts = receipts.into_iter().map(Some).collect::<Vec<_>>();
+    let transactions_gas_limit = budget.gas_limit(block_gas_limit);
+    let budget_deadline = budget.deadline(Instant::now());
+    loop {
+        let (pool_tx, prioritized) = if let Some(pool_tx) = priority_txs.next() {
+            if !tried_txs.insert(*pool_tx.hash()) {
+                continue
+            }
+            (pool_tx, true)
+        } else {
+            match best_txs.next() {
+                Some(pool_tx) if tried_txs.contains(pool_tx.hash()) => continue,
+                Some(pool_tx) => (pool_tx, false),
+                None => break,
+            }
+        };
+
+        // skip the blob transactions that didn't make it into the packed selection, the
+        // transactions of priority senders are always tried
+        if pool_tx.is_eip4844() && !prioritized && !packed_blob_txs.contains(pool_tx.hash()) {
+            trace!(target: "payload_builder", tx=?pool_tx.hash(), "skipping blob transaction not selected by blob packing");
+            blob_metrics.skipped_blob_transactions.increment(1);
+            continue
+        }
+
+        // stop adding transactions once the time budget of the attempt is used up
+        if budget_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            trace!(target: "payload_builder", "payload building time budget exhausted");
+            break
+        }
 
-    let mut receipts = Vec::new();
-    while let Some(pool_tx) = best_txs.next() {
         // ensure we still have capacity for this transaction
-        if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
+        if cumulative_gas_used + pool_tx.gas_limit() > transactions_gas_limit {
             // we can't fit this transaction into the block, so we need to mark it as invalid
             // which also removes all dependent transaction from the iterator before we can
             // continue
@@ -325,6 +591,19 @@ where
         // convert tx to a signed transaction
         let tx = pool_tx.to_recovered_transaction();
 
+        match selector.select(&tx) {
+            TransactionSelection::Include => {}
+            TransactionSelection::Skip => {
+                trace!(target: "payload_builder", ?tx, "skipping transaction rejected by selector");
+                continue
+            }
+            TransactionSelection::SkipWithDescendants => {
+                trace!(target: "payload_builder", ?tx, "skipping transaction and its descendants rejected by selector");
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+        }
+
         // There's only limited amount of blob space available per block, so we need to check if
         // the EIP-4844 can still fit in the block
         if let Some(blob_tx) = tx.transaction.as_eip4844() {
@@ -340,6 +619,21 @@ where
             }
         }
 
+        // fetch the sidecar of a blob transaction when it's selected, so that a transaction whose
+        // blobs are no longer in the blob store is skipped instead of failing the payload
+        let blob_sidecar = if tx.is_eip4844() {
+            match pool.get_blob(tx.hash)? {
+                Some(sidecar) => Some(sidecar),
+                None => {
+                    trace!(target: "payload_builder", tx=?tx.hash, "skipping blob transaction with missing sidecar");
+                    best_txs.mark_invalid(&pool_tx);
+                    continue
+                }
+            }
+        } else {
+            None
+        };
+
         let env = EnvWithHandlerCfg::new_with_cfg_env(
             initialized_cfg.clone(),
             initialized_block_env.clone(),
@@ -349,7 +643,7 @@ where
         // Configure the environment for the block.
         let mut evm = evm_config.evm_with_env(&mut db, env);
 
-        let ResultAndState { result, state } = match evm.transact() {
+        let result_and_state = match evm.transact() {
             Ok(res) => res,
             Err(err) => {
                 match err {
@@ -375,7 +669,22 @@ where
         };
         // drop evm so db is released.
         drop(evm);
+
+        match selector.on_simulated(&tx, &result_and_state) {
+            TransactionSelection::Include => {}
+            TransactionSelection::Skip => {
+                trace!(target: "payload_builder", ?tx, "skipping simulated transaction rejected by selector");
+                continue
+            }
+            TransactionSelection::SkipWithDescendants => {
+                trace!(target: "payload_builder", ?tx, "skipping simulated transaction and its descendants rejected by selector");
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+        }
+
         // commit changes
+        let ResultAndState { result, state } = result_and_state;
         db.commit(state);
 
         // add to the total blob gas used if the transaction successfully executed
@@ -388,6 +697,7 @@ where
                 best_txs.skip_blobs();
             }
         }
+        blob_sidecars.extend(blob_sidecar);
 
         let gas_used = result.gas_used();
 
@@ -420,19 +730,35 @@ where
         return Ok(BuildOutcome::Aborted { fees: total_fees, cached_reads })
     }
 
+    // keep the executed transactions, so that later attempts can resume from them
+    let prefix = resumable.then(|| {
+        db.merge_transitions(BundleRetention::PlainState);
+        ExecutedPrefix {
+            bundle: db.bundle_state.clone(),
+            transactions: executed_txs.clone(),
+            receipts: receipts.iter().flatten().cloned().collect(),
+            cumulative_gas_used,
+            blob_gas_used: sum_blob_gas_used,
+            blob_sidecars: blob_sidecars.clone(),
+            fees: total_fees,
+        }
+    });
+
     // calculate the requests and the requests root
     let (requests, requests_root) = if chain_spec
         .is_prague_active_at_timestamp(attributes.timestamp)
     {
         let deposit_requests = parse_deposits_from_receipts(&chain_spec, receipts.iter().flatten())
             .map_err(|err| PayloadBuilderError::Internal(RethError::Execution(err.into())))?;
-        let withdrawal_requests = post_block_withdrawal_requests_contract_call(
+        let system_call_requests = post_block_system_calls(
             &mut db,
+            &system_caller,
+            &system_call_block,
             &initialized_cfg,
             &initialized_block_env,
         )?;
 
-        let requests = [deposit_requests, withdrawal_requests].concat();
+        let requests = [deposit_requests, system_call_requests].concat();
         let requests_root = calculate_requests_root(&requests);
         (Some(requests.into()), Some(requests_root))
     } else {
@@ -465,18 +791,11 @@ where
     // create the block header
     let transactions_root = proofs::calculate_transaction_root(&executed_txs);
 
-    // initialize empty blob sidecars at first. If cancun is active then this will
-    let mut blob_sidecars = Vec::new();
     let mut excess_blob_gas = None;
     let mut blob_gas_used = None;
 
     // only determine cancun fields when active
     if chain_spec.is_cancun_active_at_timestamp(attributes.timestamp) {
-        // grab the blob sidecars from the executed txs
-        blob_sidecars = pool.get_all_blobs_exact(
-            executed_txs.iter().filter(|tx| tx.is_eip4844()).map(|tx| tx.hash).collect(),
-        )?;
-
         excess_blob_gas = if chain_spec.is_cancun_active_at_timestamp(parent_block.timestamp) {
             let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
             let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
@@ -520,10 +839,218 @@ where
     let sealed_block = block.seal_slow();
     debug!(target: "payload_builder", ?sealed_block, "sealed built block");
 
+    blob_metrics.blobs_used.record((sum_blob_gas_used / DATA_GAS_PER_BLOB) as f64);
+
     let mut payload = EthBuiltPayload::new(attributes.id, sealed_block, total_fees);
 
     // extend the payload with the blob sidecars from the executed txs
     payload.extend_sidecars(blob_sidecars);
 
-    Ok(BuildOutcome::Better { payload, cached_reads })
+    Ok(BuildOutcome::Better { payload, cached_reads, prefix })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_basic_payload_builder::test_utils::{ExpectedPayload, PayloadReplay};
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_primitives::{
+        Address, BlobTransactionSidecar, Header, SealedBlock, TransactionSignedEcRecovered, B256,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::engine::PayloadAttributes;
+    use reth_transaction_pool::{
+        blobstore::InMemoryBlobStore,
+        test_utils::{testing_pool, MockTransaction, TestPool, TestPoolBuilder},
+        BlobStore, PoolTransaction,
+    };
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn replay_orders_transactions_by_tip() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build());
+        let parent = Header { gas_limit: 30_000_000, timestamp: 1, ..Default::default() };
+        let parent = Arc::new(SealedBlock::new(parent.seal_slow(), Default::default()));
+        let attributes = EthPayloadBuilderAttributes::new(
+            parent.hash(),
+            PayloadAttributes {
+                timestamp: 2,
+                prev_randao: Default::default(),
+                suggested_fee_recipient: Default::default(),
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: None,
+            },
+        );
+
+        let cheap = MockTransaction::eip1559()
+            .with_gas_limit(21_000)
+            .with_max_fee(100)
+            .with_priority_fee(1);
+        let valuable = MockTransaction::eip1559()
+            .with_gas_limit(21_000)
+            .with_max_fee(100)
+            .with_priority_fee(2);
+        let client = MockEthProvider::default();
+        for tx in [&cheap, &valuable] {
+            client.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(u64::MAX)));
+        }
+
+        let replay = PayloadReplay::new(parent, attributes, vec![cheap.clone(), valuable.clone()]);
+        let payload = replay
+            .replay(client, testing_pool(), chain_spec, &EthereumPayloadBuilder::default())
+            .await;
+
+        // the parent has no base fee, so the transactions pay their full priority fee
+        let expected = ExpectedPayload {
+            transactions: vec![*valuable.hash(), *cheap.hash()],
+            gas_used: 42_000,
+            fees: U256::from(21_000 * 3),
+        };
+        expected.assert_matches(&payload);
+    }
+
+    #[tokio::test]
+    async fn skip_blob_transactions_without_sidecar() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let parent = Header {
+            gas_limit: 30_000_000,
+            timestamp: 1,
+            excess_blob_gas: Some(0),
+            blob_gas_used: Some(0),
+            ..Default::default()
+        };
+        let parent = Arc::new(SealedBlock::new(parent.seal_slow(), Default::default()));
+        let attributes = EthPayloadBuilderAttributes::new(
+            parent.hash(),
+            PayloadAttributes {
+                timestamp: 2,
+                prev_randao: Default::default(),
+                suggested_fee_recipient: Default::default(),
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: Some(B256::ZERO),
+            },
+        );
+
+        let blob_tx = MockTransaction::eip4844_with_sidecar(BlobTransactionSidecar {
+            blobs: vec![Default::default()],
+            commitments: vec![Default::default()],
+            proofs: vec![Default::default()],
+        })
+        .with_gas_limit(21_000)
+        .with_max_fee(100)
+        .with_priority_fee(2);
+        let tx = MockTransaction::eip1559()
+            .with_gas_limit(21_000)
+            .with_max_fee(100)
+            .with_priority_fee(1);
+        let client = MockEthProvider::default();
+        for tx in [&blob_tx, &tx] {
+            client.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(u64::MAX)));
+        }
+
+        // the sidecar of the blob transaction is removed from the blob store after the
+        // transaction was added to the pool
+        let blob_store = InMemoryBlobStore::default();
+        let pool: TestPool = TestPoolBuilder::default().with_blob_store(blob_store.clone()).into();
+        pool.add_external_transaction(blob_tx.clone()).await.unwrap();
+        blob_store.delete(*blob_tx.hash()).unwrap();
+
+        let replay = PayloadReplay::new(parent, attributes, vec![tx.clone()]);
+        let payload =
+            replay.replay(client, pool, chain_spec, &EthereumPayloadBuilder::default()).await;
+
+        let expected = ExpectedPayload {
+            transactions: vec![*tx.hash()],
+            gas_used: 21_000,
+            fees: U256::from(21_000),
+        };
+        expected.assert_matches(&payload);
+        assert!(payload.sidecars().is_empty());
+    }
+
+    /// Prioritizes the transactions of one sender, and skips the transactions of two others
+    /// before and after their execution.
+    #[derive(Debug, Clone)]
+    struct TestSelector {
+        priority: Address,
+        skipped: Address,
+        skipped_after_simulation: Address,
+    }
+
+    impl TransactionSelector for TestSelector {
+        fn priority_senders(&mut self, _attributes: &EthPayloadBuilderAttributes) -> Vec<Address> {
+            vec![self.priority]
+        }
+
+        fn select(&mut self, tx: &TransactionSignedEcRecovered) -> TransactionSelection {
+            if tx.signer() == self.skipped {
+                TransactionSelection::Skip
+            } else {
+                TransactionSelection::Include
+            }
+        }
+
+        fn on_simulated(
+            &mut self,
+            tx: &TransactionSignedEcRecovered,
+            _result: &ResultAndState,
+        ) -> TransactionSelection {
+            if tx.signer() == self.skipped_after_simulation {
+                TransactionSelection::SkipWithDescendants
+            } else {
+                TransactionSelection::Include
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_with_transaction_selector() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build());
+        let parent = Header { gas_limit: 30_000_000, timestamp: 1, ..Default::default() };
+        let parent = Arc::new(SealedBlock::new(parent.seal_slow(), Default::default()));
+        let attributes = EthPayloadBuilderAttributes::new(
+            parent.hash(),
+            PayloadAttributes {
+                timestamp: 2,
+                prev_randao: Default::default(),
+                suggested_fee_recipient: Default::default(),
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: None,
+            },
+        );
+
+        let [prioritized, valuable, skipped, skipped_after_simulation] =
+            [1, 2, 3, 4].map(|priority_fee| {
+                MockTransaction::eip1559()
+                    .with_gas_limit(21_000)
+                    .with_max_fee(100)
+                    .with_priority_fee(priority_fee)
+            });
+        let transactions = vec![
+            prioritized.clone(),
+            valuable.clone(),
+            skipped.clone(),
+            skipped_after_simulation.clone(),
+        ];
+        let client = MockEthProvider::default();
+        for tx in &transactions {
+            client.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(u64::MAX)));
+        }
+
+        let builder = EthereumPayloadBuilder::default().with_transaction_selector(TestSelector {
+            priority: prioritized.sender(),
+            skipped: skipped.sender(),
+            skipped_after_simulation: skipped_after_simulation.sender(),
+        });
+        let replay = PayloadReplay::new(parent, attributes, transactions);
+        let payload = replay.replay(client, testing_pool(), chain_spec, &builder).await;
+
+        // the prioritized transaction comes first, even though it pays the lowest tip
+        let expected = ExpectedPayload {
+            transactions: vec![*prioritized.hash(), *valuable.hash()],
+            gas_used: 42_000,
+            fees: U256::from(21_000 * 3),
+        };
+        expected.assert_matches(&payload);
+    }
 }
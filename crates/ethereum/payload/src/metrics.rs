@@ -0,0 +1,18 @@
+//! Metrics for the ethereum payload builder.
+
+use reth_metrics::{
+    metrics::{Counter, Gauge, Histogram},
+    Metrics,
+};
+
+/// Metrics of the blob transactions of built payloads.
+#[derive(Metrics)]
+#[metrics(scope = "payloads.blobs")]
+pub(crate) struct BlobPackingMetrics {
+    /// Number of blobs in built payloads
+    pub(crate) blobs_used: Histogram,
+    /// Number of blobs of the pool's blob transactions considered for the last payload
+    pub(crate) blobs_available: Gauge,
+    /// Number of blob transactions left out of payloads by the blob packing
+    pub(crate) skipped_blob_transactions: Counter,
+}
@@ -0,0 +1,53 @@
+//! Custom transaction selection for the ethereum payload builder.
+
+use reth_payload_builder::EthPayloadBuilderAttributes;
+use reth_primitives::{Address, TransactionSignedEcRecovered};
+use revm::primitives::ResultAndState;
+
+/// Whether a candidate transaction is included in the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionSelection {
+    /// Include the transaction.
+    Include,
+    /// Skip the transaction. Its descendants are still considered, but usually fail with a
+    /// nonce too high error.
+    Skip,
+    /// Skip the transaction and all of its descendants.
+    SkipWithDescendants,
+}
+
+/// Hook of the [`EthereumPayloadBuilder`](crate::EthereumPayloadBuilder) to customize which
+/// transactions are included in a payload and in which order, e.g. to exclude transactions or to
+/// prioritize the transactions of specific senders.
+///
+/// Every payload building attempt uses its own clone of the selector, so it can track state
+/// across the candidates of the attempt.
+///
+/// All methods default to including every transaction in the order of the pool, which is also
+/// what the `()` selector does.
+pub trait TransactionSelector: Clone + Send + Sync {
+    /// Returns the senders whose pool transactions are tried before the best transactions of the
+    /// pool, in order of priority. The transactions of a sender are tried in nonce order.
+    fn priority_senders(&mut self, _attributes: &EthPayloadBuilderAttributes) -> Vec<Address> {
+        Vec::new()
+    }
+
+    /// Decides whether the candidate transaction is executed, before it's executed.
+    fn select(&mut self, _tx: &TransactionSignedEcRecovered) -> TransactionSelection {
+        TransactionSelection::Include
+    }
+
+    /// Decides whether the candidate transaction is included, given the result of its execution
+    /// on top of the transactions included so far.
+    ///
+    /// The state changes of the transaction are only committed if it's included.
+    fn on_simulated(
+        &mut self,
+        _tx: &TransactionSignedEcRecovered,
+        _result: &ResultAndState,
+    ) -> TransactionSelection {
+        TransactionSelection::Include
+    }
+}
+
+impl TransactionSelector for () {}
@@ -0,0 +1,401 @@
+//! Structured events of executed blocks, e.g. for real-time monitoring and alerting.
+//!
+//! Executors that support it run the [`ExecutionEventsInspector`] while executing live blocks,
+//! and record the [`BlockExecutionEvents`] of every executed block in an
+//! [`ExecutionEventsSender`], so subscribers don't have to re-execute blocks to trace them. The
+//! events of a block are only published once the block is validated and becomes canonical.
+
+use alloy_eips::eip4788::SYSTEM_ADDRESS;
+use parking_lot::Mutex;
+use reth_execution_types::Chain;
+use reth_primitives::{
+    Address, BlockNumHash, BlockNumber, BlockWithSenders, Bytes, Log, TxHash, B256, U256,
+};
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome},
+    Database, EvmContext, Inspector,
+};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::broadcast;
+
+/// Default capacity of the [`ExecutionEventsSender`] channel, in blocks.
+pub const DEFAULT_EXECUTION_EVENTS_CAPACITY: usize = 64;
+
+/// An event of the execution of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionEvent {
+    /// A message call, including the top-level call of the transaction.
+    Call {
+        /// Depth of the call, `0` for the top-level call.
+        depth: usize,
+        /// Kind of the call.
+        scheme: CallScheme,
+        /// Caller of the call.
+        from: Address,
+        /// Account whose storage is accessed by the call.
+        to: Address,
+        /// Value of the call.
+        value: U256,
+        /// Input of the call.
+        input: Bytes,
+        /// Whether the call succeeded.
+        success: bool,
+    },
+    /// A log emitted by a call that wasn't reverted.
+    Log(Log),
+    /// A self-destruct of a call that wasn't reverted.
+    SelfDestruct {
+        /// The self-destructed account.
+        address: Address,
+        /// Account the balance of the self-destructed account is sent to.
+        beneficiary: Address,
+        /// Balance sent to the beneficiary.
+        value: U256,
+    },
+}
+
+/// Events of the execution of a transaction, in execution order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionExecutionEvents {
+    /// Hash of the transaction.
+    pub tx_hash: TxHash,
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// Events of the transaction.
+    pub events: Vec<ExecutionEvent>,
+}
+
+/// Events of the execution of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockExecutionEvents {
+    /// Number and hash of the block.
+    pub block: BlockNumHash,
+    /// Events of the transactions of the block, in block order.
+    pub transactions: Vec<TransactionExecutionEvents>,
+}
+
+/// Events of a top-level frame recorded by the [`ExecutionEventsInspector`].
+#[derive(Debug, Default)]
+struct FrameEvents {
+    /// Caller of the top-level frame.
+    caller: Address,
+    /// Whether the top-level frame succeeded.
+    success: bool,
+    /// Events of the top-level frame and its subcalls.
+    events: Vec<ExecutionEvent>,
+}
+
+/// A frame being executed.
+#[derive(Debug)]
+struct ActiveFrame {
+    /// Index of the first event of the frame.
+    first_event: usize,
+    /// Index of the call event of the frame, if it's a call.
+    call_event: Option<usize>,
+}
+
+/// [Inspector] that records the [`ExecutionEvent`]s of the transactions of a block.
+///
+/// Logs and self-destructs of reverted calls are discarded. Calls of the system contracts, e.g.
+/// the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) beacon root contract, are not recorded.
+#[derive(Debug, Default)]
+pub struct ExecutionEventsInspector {
+    /// Events of the top-level frames executed so far.
+    frames: Vec<FrameEvents>,
+    /// Frames being executed.
+    active: Vec<ActiveFrame>,
+}
+
+impl ExecutionEventsInspector {
+    /// Returns the events of the current top-level frame.
+    fn current(&mut self) -> Option<&mut FrameEvents> {
+        if self.active.is_empty() {
+            return None
+        }
+        self.frames.last_mut()
+    }
+
+    /// Records the start of a frame.
+    fn frame_start(&mut self, caller: Address, call: Option<ExecutionEvent>) {
+        if self.active.is_empty() {
+            self.frames.push(FrameEvents { caller, ..Default::default() });
+        }
+        let frame = self.frames.last_mut().expect("frame was pushed");
+        let first_event = frame.events.len();
+        let call_event = call.map(|call| {
+            frame.events.push(call);
+            frame.events.len() - 1
+        });
+        self.active.push(ActiveFrame { first_event, call_event });
+    }
+
+    /// Records the end of a frame.
+    fn frame_end(&mut self, success: bool) {
+        let Some(active) = self.active.pop() else { return };
+        let top_level = self.active.is_empty();
+        let frame = self.frames.last_mut().expect("active frame has events");
+
+        if let Some(ExecutionEvent::Call { success: call_success, .. }) =
+            active.call_event.map(|index| &mut frame.events[index])
+        {
+            *call_success = success;
+        }
+        if !success {
+            // the state changes of the frame are reverted, but its calls still happened
+            let mut index = 0;
+            frame.events.retain(|event| {
+                let keep =
+                    index < active.first_event || matches!(event, ExecutionEvent::Call { .. });
+                index += 1;
+                keep
+            });
+        }
+        if top_level {
+            frame.success = success;
+        }
+    }
+
+    /// Returns the recorded events of the transactions of the executed block.
+    ///
+    /// The top-level frames executed by the system, e.g. for the beacon root contract, are
+    /// skipped, so the remaining frames are the transactions of the block, in order.
+    pub fn into_block_events(self, block: &BlockWithSenders) -> BlockExecutionEvents {
+        let transactions = self
+            .frames
+            .into_iter()
+            .filter(|frame| frame.caller != SYSTEM_ADDRESS)
+            .zip(block.body.iter())
+            .map(|(frame, tx)| TransactionExecutionEvents {
+                tx_hash: tx.hash(),
+                success: frame.success,
+                events: frame.events,
+            })
+            .collect();
+        BlockExecutionEvents {
+            block: BlockNumHash::new(block.number, block.header.hash_slow()),
+            transactions,
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for ExecutionEventsInspector {
+    fn log(&mut self, _context: &mut EvmContext<DB>, log: &Log) {
+        if let Some(frame) = self.current() {
+            frame.events.push(ExecutionEvent::Log(log.clone()));
+        }
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let call = ExecutionEvent::Call {
+            depth: self.active.len(),
+            scheme: inputs.scheme,
+            from: inputs.caller,
+            to: inputs.target_address,
+            value: inputs.call_value(),
+            input: inputs.input.clone(),
+            success: false,
+        };
+        self.frame_start(inputs.caller, Some(call));
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.frame_end(outcome.result.is_ok());
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.frame_start(inputs.caller, None);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.frame_end(outcome.result.is_ok());
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if let Some(frame) = self.current() {
+            frame.events.push(ExecutionEvent::SelfDestruct {
+                address: contract,
+                beneficiary: target,
+                value,
+            });
+        }
+    }
+}
+
+/// Sender of the [`BlockExecutionEvents`] of canonical blocks.
+///
+/// The events of executed blocks are buffered until the blocks become canonical, see
+/// [`Self::publish_canonical`], so subscribers never see the events of invalid blocks or blocks
+/// of side chains.
+///
+/// Events are only recorded while there are subscribers, so an unused sender doesn't slow down
+/// block execution.
+#[derive(Debug, Clone)]
+pub struct ExecutionEventsSender {
+    sender: broadcast::Sender<Arc<BlockExecutionEvents>>,
+    /// Events of executed blocks that aren't canonical yet, by block number and hash.
+    pending: Arc<Mutex<BTreeMap<(BlockNumber, B256), BlockExecutionEvents>>>,
+    /// Maximum number of pending blocks.
+    capacity: usize,
+}
+
+impl ExecutionEventsSender {
+    /// Creates a new sender that buffers the events of up to `capacity` canonical blocks for slow
+    /// subscribers, and of up to `capacity` executed blocks that aren't canonical yet.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender, pending: Default::default(), capacity }
+    }
+
+    /// Returns a new subscription to the events of executed blocks.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<BlockExecutionEvents>> {
+        self.sender.subscribe()
+    }
+
+    /// Returns `true` if there are subscribers to the events.
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// Records the events of an executed block, to be published once the block is canonical.
+    ///
+    /// If there are more than `capacity` pending blocks, the events of the lowest ones are
+    /// discarded.
+    pub fn record(&self, events: BlockExecutionEvents) {
+        let mut pending = self.pending.lock();
+        pending.insert((events.block.number, events.block.hash), events);
+        while pending.len() > self.capacity {
+            pending.pop_first();
+        }
+    }
+
+    /// Sends the recorded events of the blocks of a chain that became canonical to all
+    /// subscribers, in block order.
+    ///
+    /// This should be called with the committed chain of every canonical state notification.
+    pub fn publish_canonical(&self, chain: &Chain) {
+        let mut pending = self.pending.lock();
+        for block in chain.blocks().values() {
+            if let Some(events) = pending.remove(&(block.number, block.hash())) {
+                let _ = self.sender.send(Arc::new(events));
+            }
+        }
+    }
+}
+
+impl Default for ExecutionEventsSender {
+    fn default() -> Self {
+        Self::new(DEFAULT_EXECUTION_EVENTS_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        db::EmptyDB,
+        interpreter::{Gas, InstructionResult, InterpreterResult},
+    };
+
+    fn call_inputs(caller: Address, target: Address) -> CallInputs {
+        CallInputs {
+            input: Bytes::new(),
+            return_memory_offset: 0..0,
+            gas_limit: 100_000,
+            bytecode_address: target,
+            target_address: target,
+            caller,
+            value: revm::interpreter::CallValue::Transfer(U256::ZERO),
+            scheme: CallScheme::Call,
+            is_static: false,
+            is_eof: false,
+        }
+    }
+
+    fn outcome(result: InstructionResult) -> CallOutcome {
+        CallOutcome::new(InterpreterResult { result, output: Bytes::new(), gas: Gas::new(0) }, 0..0)
+    }
+
+    #[test]
+    fn discard_logs_of_reverted_calls() {
+        let mut inspector = ExecutionEventsInspector::default();
+        let mut context = EvmContext::new(EmptyDB::default());
+        let (sender, contract) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let log = Log::new_unchecked(contract, Vec::new(), Bytes::new());
+
+        // the top-level call emits a log, and its reverted subcall emits another one
+        let mut top = call_inputs(sender, contract);
+        Inspector::<EmptyDB>::call(&mut inspector, &mut context, &mut top);
+        inspector.log(&mut context, &log);
+        let mut sub = call_inputs(contract, contract);
+        Inspector::<EmptyDB>::call(&mut inspector, &mut context, &mut sub);
+        inspector.log(&mut context, &log);
+        inspector.call_end(&mut context, &sub, outcome(InstructionResult::Revert));
+        inspector.call_end(&mut context, &top, outcome(InstructionResult::Stop));
+
+        let frame = &inspector.frames[0];
+        assert!(frame.success);
+        assert_eq!(frame.events.len(), 3);
+        assert!(matches!(frame.events[0], ExecutionEvent::Call { depth: 0, success: true, .. }));
+        assert_eq!(frame.events[1], ExecutionEvent::Log(log));
+        assert!(matches!(frame.events[2], ExecutionEvent::Call { depth: 1, success: false, .. }));
+    }
+
+    #[test]
+    fn publish_canonical_blocks_only() {
+        let execution_events = ExecutionEventsSender::new(2);
+        let mut subscription = execution_events.subscribe();
+
+        let blocks = (1..=3)
+            .map(|number| {
+                let block = reth_primitives::Block {
+                    header: reth_primitives::Header { number, ..Default::default() },
+                    ..Default::default()
+                };
+                BlockWithSenders { block, senders: vec![] }
+            })
+            .collect::<Vec<_>>();
+        for block in &blocks {
+            execution_events.record(ExecutionEventsInspector::default().into_block_events(block));
+        }
+        // nothing is published before the blocks are canonical
+        assert!(subscription.try_recv().is_err());
+
+        // the events of the first block were evicted, and the events of the other blocks are
+        // published once they are canonical
+        let chain = Chain::new(
+            blocks.into_iter().map(BlockWithSenders::seal_slow),
+            Default::default(),
+            None,
+        );
+        execution_events.publish_canonical(&chain);
+        assert_eq!(subscription.try_recv().unwrap().block.number, 2);
+        assert_eq!(subscription.try_recv().unwrap().block.number, 3);
+        assert!(subscription.try_recv().is_err());
+
+        // events are published only once
+        execution_events.publish_canonical(&chain);
+        assert!(subscription.try_recv().is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Helper to run optional inspectors during block execution.
+
+use reth_primitives::{Address, Log, U256};
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+
+/// [Inspector] that runs two inspectors, each only if it's set.
+///
+/// If the first inspector overrides the outcome of a call or create, the second inspector is not
+/// called for it.
+#[derive(Debug, Default)]
+pub struct InspectorPair<A, B> {
+    /// The first inspector.
+    pub first: Option<A>,
+    /// The second inspector.
+    pub second: Option<B>,
+}
+
+impl<A, B> InspectorPair<A, B> {
+    /// Creates a new pair of optional inspectors.
+    pub const fn new(first: Option<A>, second: Option<B>) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns `true` if neither inspector is set.
+    pub const fn is_empty(&self) -> bool {
+        self.first.is_none() && self.second.is_none()
+    }
+}
+
+impl<DB, A, B> Inspector<DB> for InspectorPair<A, B>
+where
+    DB: Database,
+    A: Inspector<DB>,
+    B: Inspector<DB>,
+{
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if let Some(first) = &mut self.first {
+            first.step(interp, context);
+        }
+        if let Some(second) = &mut self.second {
+            second.step(interp, context);
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if let Some(first) = &mut self.first {
+            first.step_end(interp, context);
+        }
+        if let Some(second) = &mut self.second {
+            second.step_end(interp, context);
+        }
+    }
+
+    fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
+        if let Some(first) = &mut self.first {
+            first.log(context, log);
+        }
+        if let Some(second) = &mut self.second {
+            second.log(context, log);
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        if let Some(outcome) = self.first.as_mut().and_then(|first| first.call(context, inputs)) {
+            return Some(outcome)
+        }
+        self.second.as_mut().and_then(|second| second.call(context, inputs))
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        mut outcome: CallOutcome,
+    ) -> CallOutcome {
+        if let Some(first) = &mut self.first {
+            outcome = first.call_end(context, inputs, outcome);
+        }
+        if let Some(second) = &mut self.second {
+            outcome = second.call_end(context, inputs, outcome);
+        }
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        if let Some(outcome) = self.first.as_mut().and_then(|first| first.create(context, inputs)) {
+            return Some(outcome)
+        }
+        self.second.as_mut().and_then(|second| second.create(context, inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        mut outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let Some(first) = &mut self.first {
+            outcome = first.create_end(context, inputs, outcome);
+        }
+        if let Some(second) = &mut self.second {
+            outcome = second.create_end(context, inputs, outcome);
+        }
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if let Some(first) = &mut self.first {
+            Inspector::<DB>::selfdestruct(first, contract, target, value);
+        }
+        if let Some(second) = &mut self.second {
+            Inspector::<DB>::selfdestruct(second, contract, target, value);
+        }
+    }
+}
@@ -21,8 +21,14 @@ use revm::{inspector_handle_register, Database, Evm, EvmBuilder, GetInspector};
 use revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, SpecId, TxEnv};
 
 pub mod either;
+#[cfg(feature = "std")]
+pub mod events;
 pub mod execute;
+#[cfg(feature = "std")]
+pub mod inspector;
 pub mod noop;
+#[cfg(feature = "std")]
+pub mod profiling;
 pub mod provider;
 
 #[cfg(any(test, feature = "test-utils"))]
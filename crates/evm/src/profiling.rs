@@ -0,0 +1,306 @@
+//! Gas profiling of executed blocks, to diagnose slow blocks, e.g. blocks dominated by
+//! state-heavy contracts.
+//!
+//! The [`GasProfilingInspector`] attributes the gas used by every executed opcode to its
+//! [`OpcodeFamily`] and to the contract executing it. The gas used per opcode family is exported as
+//! metrics, the contracts using the most gas are logged, since they would make for unbounded
+//! metric labels.
+
+use reth_metrics::{metrics::Counter, Metrics};
+use reth_primitives::{Address, BlockNumHash};
+use revm::{
+    interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Default number of contracts using the most gas reported per block.
+pub const DEFAULT_PROFILED_CONTRACTS: usize = 10;
+
+/// Family of EVM opcodes gas is attributed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpcodeFamily {
+    /// Arithmetic, comparison and bitwise opcodes.
+    Arithmetic,
+    /// `KECCAK256`.
+    Hashing,
+    /// Opcodes reading the environment of the call, block or transaction.
+    Environment,
+    /// Opcodes accessing other accounts, e.g. `BALANCE` and `EXTCODECOPY`, and `SELFDESTRUCT`.
+    StateAccess,
+    /// Storage and transient storage opcodes.
+    Storage,
+    /// Memory opcodes.
+    Memory,
+    /// Stack opcodes.
+    Stack,
+    /// Control flow opcodes.
+    ControlFlow,
+    /// `LOG*` opcodes.
+    Log,
+    /// Call opcodes, without the gas used by the called contract.
+    Call,
+    /// Create opcodes, without the gas used by the init code.
+    Create,
+    /// Any other opcode.
+    Other,
+}
+
+impl OpcodeFamily {
+    /// All opcode families.
+    pub const ALL: [Self; 12] = [
+        Self::Arithmetic,
+        Self::Hashing,
+        Self::Environment,
+        Self::StateAccess,
+        Self::Storage,
+        Self::Memory,
+        Self::Stack,
+        Self::ControlFlow,
+        Self::Log,
+        Self::Call,
+        Self::Create,
+        Self::Other,
+    ];
+
+    /// Returns the family of the opcode.
+    pub const fn of(op: u8) -> Self {
+        match op {
+            opcode::ADD..=opcode::SIGNEXTEND | opcode::LT..=opcode::SAR => Self::Arithmetic,
+            opcode::KECCAK256 => Self::Hashing,
+            opcode::BALANCE |
+            opcode::EXTCODESIZE |
+            opcode::EXTCODECOPY |
+            opcode::EXTCODEHASH |
+            opcode::SELFBALANCE |
+            opcode::SELFDESTRUCT => Self::StateAccess,
+            opcode::ADDRESS..=opcode::BLOBBASEFEE => Self::Environment,
+            opcode::SLOAD | opcode::SSTORE | opcode::TLOAD | opcode::TSTORE => Self::Storage,
+            opcode::MLOAD | opcode::MSTORE | opcode::MSTORE8 | opcode::MSIZE | opcode::MCOPY => {
+                Self::Memory
+            }
+            opcode::POP | opcode::PUSH0..=opcode::SWAP16 => Self::Stack,
+            opcode::STOP |
+            opcode::JUMP |
+            opcode::JUMPI |
+            opcode::PC |
+            opcode::GAS |
+            opcode::JUMPDEST |
+            opcode::RETURN |
+            opcode::REVERT |
+            opcode::INVALID => Self::ControlFlow,
+            opcode::LOG0..=opcode::LOG4 => Self::Log,
+            opcode::CALL |
+            opcode::CALLCODE |
+            opcode::DELEGATECALL |
+            opcode::STATICCALL |
+            opcode::EXTCALL |
+            opcode::EXTDELEGATECALL |
+            opcode::EXTSTATICCALL => Self::Call,
+            opcode::CREATE | opcode::CREATE2 | opcode::EOFCREATE => Self::Create,
+            _ => Self::Other,
+        }
+    }
+
+    /// Returns the name of the family, used as metric label.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Arithmetic => "arithmetic",
+            Self::Hashing => "hashing",
+            Self::Environment => "environment",
+            Self::StateAccess => "state_access",
+            Self::Storage => "storage",
+            Self::Memory => "memory",
+            Self::Stack => "stack",
+            Self::ControlFlow => "control_flow",
+            Self::Log => "log",
+            Self::Call => "call",
+            Self::Create => "create",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Gas used by the opcodes of a block, by opcode family and by contract.
+///
+/// Gas used outside of opcodes, e.g. the intrinsic gas of transactions and the gas used by
+/// precompiles, is not included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockGasProfile {
+    /// Gas used per opcode family.
+    families: HashMap<OpcodeFamily, u64>,
+    /// Gas used per contract whose code is executed, keyed by the account whose storage is
+    /// accessed, e.g. the proxy and not the implementation of a delegate call.
+    contracts: HashMap<Address, u64>,
+}
+
+impl BlockGasProfile {
+    /// Attributes gas to the opcode family and the contract.
+    fn record(&mut self, family: OpcodeFamily, contract: Address, gas: u64) {
+        *self.families.entry(family).or_default() += gas;
+        *self.contracts.entry(contract).or_default() += gas;
+    }
+
+    /// Removes gas attributed to the opcode family and the contract.
+    fn remove(&mut self, family: OpcodeFamily, contract: Address, gas: u64) {
+        if let Some(family_gas) = self.families.get_mut(&family) {
+            *family_gas = family_gas.saturating_sub(gas);
+        }
+        if let Some(contract_gas) = self.contracts.get_mut(&contract) {
+            *contract_gas = contract_gas.saturating_sub(gas);
+        }
+    }
+
+    /// Returns the gas used by the opcodes of the family.
+    pub fn family_gas(&self, family: OpcodeFamily) -> u64 {
+        self.families.get(&family).copied().unwrap_or_default()
+    }
+
+    /// Returns the gas used by the opcodes of all families.
+    pub fn total_gas(&self) -> u64 {
+        self.families.values().sum()
+    }
+
+    /// Returns the `count` contracts using the most gas, by descending gas.
+    pub fn top_contracts(&self, count: usize) -> Vec<(Address, u64)> {
+        let mut contracts = self.contracts.iter().map(|(a, gas)| (*a, *gas)).collect::<Vec<_>>();
+        contracts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        contracts.truncate(count);
+        contracts
+    }
+
+    /// Exports the gas used per opcode family as metrics, and logs it together with the
+    /// `top_contracts` contracts using the most gas.
+    pub fn report(&self, block: BlockNumHash, top_contracts: usize) {
+        for family in OpcodeFamily::ALL {
+            let gas = self.family_gas(family);
+            if gas > 0 {
+                OpcodeFamilyMetrics::new_with_labels(&[("family", family.as_str())])
+                    .gas_used
+                    .increment(gas);
+            }
+        }
+
+        let families = OpcodeFamily::ALL
+            .iter()
+            .map(|family| (family.as_str(), self.family_gas(*family)))
+            .filter(|(_, gas)| *gas > 0)
+            .collect::<Vec<_>>();
+        debug!(
+            target: "evm::profiling",
+            number = block.number,
+            hash = %block.hash,
+            total_gas = self.total_gas(),
+            ?families,
+            top_contracts = ?self.top_contracts(top_contracts),
+            "Profiled block gas"
+        );
+    }
+}
+
+/// Metrics of the gas used by an opcode family.
+#[derive(Metrics)]
+#[metrics(scope = "execution.gas_profile")]
+struct OpcodeFamilyMetrics {
+    /// Gas used by the opcodes of the family in profiled blocks.
+    gas_used: Counter,
+}
+
+/// [Inspector] that records the [`BlockGasProfile`] of the executed transactions.
+#[derive(Debug, Default)]
+pub struct GasProfilingInspector {
+    profile: BlockGasProfile,
+    /// Family, contract and remaining gas before the opcode being executed.
+    step: Option<(OpcodeFamily, Address, u64)>,
+    /// Family and contract of the last executed opcode, if it's a call or create.
+    ///
+    /// The gas used by these opcodes includes the gas forwarded to the new frame, which is removed
+    /// once the frame starts, since the opcodes of the frame are recorded separately.
+    forwarding: Option<(OpcodeFamily, Address)>,
+}
+
+impl GasProfilingInspector {
+    /// Returns the recorded profile.
+    pub fn into_profile(self) -> BlockGasProfile {
+        self.profile
+    }
+
+    /// Removes the gas forwarded to a new frame from the opcode that started it.
+    fn on_frame_start(&mut self, gas_limit: u64) {
+        if let Some((family, contract)) = self.forwarding.take() {
+            self.profile.remove(family, contract, gas_limit);
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for GasProfilingInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        self.forwarding = None;
+        self.step = Some((
+            OpcodeFamily::of(interp.current_opcode()),
+            interp.contract.target_address,
+            interp.gas().remaining(),
+        ));
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some((family, contract, remaining)) = self.step.take() {
+            let gas = remaining.saturating_sub(interp.gas().remaining());
+            self.profile.record(family, contract, gas);
+            if matches!(family, OpcodeFamily::Call | OpcodeFamily::Create) {
+                self.forwarding = Some((family, contract));
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.on_frame_start(inputs.gas_limit);
+        None
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.on_frame_start(inputs.gas_limit);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opcode_families() {
+        assert_eq!(OpcodeFamily::of(opcode::ADD), OpcodeFamily::Arithmetic);
+        assert_eq!(OpcodeFamily::of(opcode::SLOAD), OpcodeFamily::Storage);
+        assert_eq!(OpcodeFamily::of(opcode::EXTCODEHASH), OpcodeFamily::StateAccess);
+        assert_eq!(OpcodeFamily::of(opcode::CALLER), OpcodeFamily::Environment);
+        assert_eq!(OpcodeFamily::of(opcode::PUSH32), OpcodeFamily::Stack);
+        assert_eq!(OpcodeFamily::of(opcode::DELEGATECALL), OpcodeFamily::Call);
+    }
+
+    #[test]
+    fn forwarded_gas_is_not_attributed_to_calls() {
+        let (caller, callee) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let mut inspector = GasProfilingInspector::default();
+
+        // a call costing 2600 gas that forwards 10000 gas
+        inspector.profile.record(OpcodeFamily::Call, caller, 12_600);
+        inspector.forwarding = Some((OpcodeFamily::Call, caller));
+        inspector.on_frame_start(10_000);
+        inspector.profile.record(OpcodeFamily::Storage, callee, 2_100);
+
+        let profile = inspector.into_profile();
+        assert_eq!(profile.family_gas(OpcodeFamily::Call), 2_600);
+        assert_eq!(profile.total_gas(), 4_700);
+        assert_eq!(profile.top_contracts(1), vec![(caller, 2_600)]);
+    }
+}
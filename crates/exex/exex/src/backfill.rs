@@ -0,0 +1,293 @@
+//! Backfill of historical blocks for `ExEx`s.
+//!
+//! A new `ExEx` usually needs to catch up with blocks that were executed before it was installed.
+//! A [`BackfillJob`] re-executes a historical block range on top of the historical state of the
+//! node and yields the results as [`ExExNotification::ChainCommitted`] notifications, the same
+//! notifications the `ExEx` receives for live blocks.
+
+use crate::ExExNotification;
+use futures::{Stream, StreamExt};
+use reth_evm::execute::{BatchExecutor, BlockExecutionError, BlockExecutorProvider};
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    BlockReader, Chain, HeaderProvider, ProviderError, StateProviderFactory, TransactionVariant,
+};
+use reth_prune_types::PruneModes;
+use reth_revm::database::StateProviderDatabase;
+use reth_tracing::tracing::{debug, error};
+use std::{ops::RangeInclusive, sync::Arc};
+use tokio::sync::mpsc::{Receiver, Sender};
+
+/// Default for [`BackfillJobFactory::chunk_size`].
+pub const DEFAULT_BACKFILL_CHUNK_SIZE: u64 = 1_000;
+
+/// Factory for [`BackfillJob`]s.
+///
+/// Every job executes its block range in chunks of at most [`Self::chunk_size`] blocks. Each chunk
+/// is executed independently from the historical state at its first block, so chunks can be
+/// executed in parallel.
+#[derive(Debug, Clone)]
+pub struct BackfillJobFactory<E, P> {
+    executor: E,
+    provider: P,
+    chunk_size: u64,
+    parallelism: usize,
+}
+
+impl<E, P> BackfillJobFactory<E, P> {
+    /// Creates a new factory with the given block executor and provider.
+    pub fn new(executor: E, provider: P) -> Self {
+        Self {
+            executor,
+            provider,
+            chunk_size: DEFAULT_BACKFILL_CHUNK_SIZE,
+            parallelism: std::thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+
+    /// Sets the maximum number of blocks executed in one chunk, i.e. contained in one
+    /// notification.
+    pub const fn with_chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = if chunk_size == 0 { 1 } else { chunk_size };
+        self
+    }
+
+    /// Sets the maximum number of chunks executed in parallel by [`BackfillJob::into_stream`].
+    ///
+    /// Defaults to the available parallelism of the machine.
+    pub const fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = if parallelism == 0 { 1 } else { parallelism };
+        self
+    }
+
+    /// Returns the maximum number of blocks executed in one chunk.
+    pub const fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    /// Returns the maximum number of chunks executed in parallel.
+    pub const fn parallelism(&self) -> usize {
+        self.parallelism
+    }
+}
+
+impl<E: Clone, P: Clone> BackfillJobFactory<E, P> {
+    /// Creates a new job that executes the given block range.
+    ///
+    /// The genesis block is never executed, so a range starting at block `0` is executed from
+    /// block `1`.
+    pub fn backfill(&self, range: RangeInclusive<BlockNumber>) -> BackfillJob<E, P> {
+        BackfillJob {
+            executor: self.executor.clone(),
+            provider: self.provider.clone(),
+            range: (*range.start()).max(1)..=*range.end(),
+            chunk_size: self.chunk_size,
+            parallelism: self.parallelism,
+        }
+    }
+}
+
+impl<E, P> BackfillJobFactory<E, P>
+where
+    E: BlockExecutorProvider + Clone,
+    P: BlockReader + HeaderProvider + StateProviderFactory + Clone + 'static,
+{
+    /// Forwards the notifications to an `ExEx` that was added to a running node, after
+    /// backfilling the blocks from `from` up to and including `tip`.
+    ///
+    /// `live` receives the notifications of the `ExEx` from the
+    /// [`ExExManager`](crate::ExExManager), which start after `tip`, and `sender` is the
+    /// notification channel given to the `ExEx`. If a committed chain doesn't directly follow the
+    /// blocks forwarded before, the missing blocks are backfilled first, so the `ExEx` doesn't
+    /// miss blocks that were committed without a notification.
+    ///
+    /// If backfilling fails, the error is logged and `sender` is dropped. Either way, `live` is
+    /// drained until it's closed, i.e. until the `ExEx` is removed from the manager, because the
+    /// manager can't send notifications to a closed channel.
+    pub async fn forward_with_backfill(
+        &self,
+        from: BlockNumber,
+        tip: BlockNumber,
+        mut live: Receiver<ExExNotification>,
+        sender: Sender<ExExNotification>,
+    ) {
+        if let Err(err) = self.forward(from, tip, &mut live, &sender).await {
+            error!(target: "exex::backfill", %err, "Failed to backfill blocks for ExEx");
+        }
+
+        drop(sender);
+        while live.recv().await.is_some() {}
+    }
+
+    /// Backfills and forwards the notifications until either `live` is closed or the `ExEx`
+    /// dropped its notification receiver.
+    async fn forward(
+        &self,
+        from: BlockNumber,
+        tip: BlockNumber,
+        live: &mut Receiver<ExExNotification>,
+        sender: &Sender<ExExNotification>,
+    ) -> Result<(), BlockExecutionError> {
+        if !self.backfill_into(from..=tip, sender).await? {
+            return Ok(())
+        }
+
+        let mut next_block = from.max(tip + 1);
+        while let Some(notification) = live.recv().await {
+            if let Some(chain) = notification.committed_chain() {
+                let first_block = chain.first().number;
+                if first_block > next_block &&
+                    !self.backfill_into(next_block..=first_block - 1, sender).await?
+                {
+                    return Ok(())
+                }
+                next_block = chain.tip().number + 1;
+            } else if let Some(chain) = notification.reverted_chain() {
+                next_block = next_block.min(chain.first().number);
+            }
+
+            if sender.send(notification).await.is_err() {
+                return Ok(())
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Backfills the block range and sends the notifications to the `ExEx`. Returns `false` if
+    /// the `ExEx` dropped its notification receiver.
+    async fn backfill_into(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        sender: &Sender<ExExNotification>,
+    ) -> Result<bool, BlockExecutionError> {
+        if range.is_empty() {
+            return Ok(true)
+        }
+
+        debug!(target: "exex::backfill", ?range, "Backfilling blocks for ExEx");
+        let mut notifications = std::pin::pin!(self.backfill(range).into_stream());
+        while let Some(notification) = notifications.next().await {
+            if sender.send(notification?).await.is_err() {
+                return Ok(false)
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Job that executes a historical block range, created by [`BackfillJobFactory::backfill`].
+///
+/// The job is an [`Iterator`] that executes the chunks one after another on the current thread.
+/// Use [`BackfillJob::into_stream`] to execute the chunks in parallel.
+#[derive(Debug)]
+pub struct BackfillJob<E, P> {
+    executor: E,
+    provider: P,
+    /// Blocks that are left to be executed.
+    range: RangeInclusive<BlockNumber>,
+    chunk_size: u64,
+    parallelism: usize,
+}
+
+impl<E, P> BackfillJob<E, P>
+where
+    E: BlockExecutorProvider,
+    P: BlockReader + HeaderProvider + StateProviderFactory + Clone + 'static,
+{
+    /// Converts the job into a stream of [`ExExNotification::ChainCommitted`] notifications, one
+    /// per chunk.
+    ///
+    /// Up to [`BackfillJobFactory::parallelism`] chunks are executed in parallel on blocking
+    /// threads, and the notifications are yielded in block order.
+    pub fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<ExExNotification, BlockExecutionError>> + Send {
+        let Self { executor, provider, range, chunk_size, parallelism } = self;
+
+        futures::stream::iter(chunks(range, chunk_size))
+            .map(move |chunk| {
+                let (executor, provider) = (executor.clone(), provider.clone());
+                tokio::task::spawn_blocking(move || execute_range(&executor, &provider, chunk))
+            })
+            .buffered(parallelism)
+            .map(|result| {
+                let chain = result.map_err(BlockExecutionError::other)??;
+                Ok(ExExNotification::ChainCommitted { new: Arc::new(chain) })
+            })
+    }
+}
+
+impl<E, P> Iterator for BackfillJob<E, P>
+where
+    E: BlockExecutorProvider,
+    P: BlockReader + HeaderProvider + StateProviderFactory,
+{
+    type Item = Result<Chain, BlockExecutionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = chunks(self.range.clone(), self.chunk_size).next()?;
+        self.range = chunk.end() + 1..=*self.range.end();
+        Some(execute_range(&self.executor, &self.provider, chunk))
+    }
+}
+
+/// Splits the block range into consecutive chunks of at most `chunk_size` blocks.
+fn chunks(
+    range: RangeInclusive<BlockNumber>,
+    chunk_size: u64,
+) -> impl Iterator<Item = RangeInclusive<BlockNumber>> {
+    let end = *range.end();
+    range.step_by(chunk_size as usize).map(move |start| start..=end.min(start + chunk_size - 1))
+}
+
+/// Executes the block range on top of the historical state before its first block.
+fn execute_range<E, P>(
+    executor: &E,
+    provider: &P,
+    range: RangeInclusive<BlockNumber>,
+) -> Result<Chain, BlockExecutionError>
+where
+    E: BlockExecutorProvider,
+    P: BlockReader + HeaderProvider + StateProviderFactory,
+{
+    debug!(target: "exex::backfill", ?range, "Executing block range");
+
+    let db = StateProviderDatabase::new(provider.history_by_block_number(range.start() - 1)?);
+    // Receipts and changesets have to be kept in full, as they're part of the notification.
+    let mut executor = executor.batch_executor(db, PruneModes::none());
+    executor.set_tip(*range.end());
+
+    let mut blocks = Vec::new();
+    for block_number in range {
+        let td = provider
+            .header_td_by_number(block_number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+
+        // we need the block's transactions but we don't need the transaction hashes
+        let block = provider
+            .block_with_senders(block_number.into(), TransactionVariant::NoHash)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+
+        executor.execute_and_verify_one((&block, td).into())?;
+
+        let hash = block.header.hash_slow();
+        blocks.push(block.seal(hash));
+    }
+
+    Ok(Chain::new(blocks, executor.finalize(), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backfill_chunks() {
+        assert_eq!(chunks(1..=10, 4).collect::<Vec<_>>(), vec![1..=4, 5..=8, 9..=10]);
+        assert_eq!(chunks(1..=8, 4).collect::<Vec<_>>(), vec![1..=4, 5..=8]);
+        assert_eq!(chunks(5..=5, 4).collect::<Vec<_>>(), vec![5..=5]);
+        assert_eq!(chunks(RangeInclusive::new(5, 4), 4).count(), 0);
+    }
+}
@@ -0,0 +1,191 @@
+//! Persistent checkpoints of `ExEx`'s.
+
+use reth_fs_util::FsPathError;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Key of the checkpoint with the last height an `ExEx` reported with an
+/// [`ExExEvent::FinishedHeight`](crate::ExExEvent::FinishedHeight) event, which is set by the node.
+pub const FINISHED_HEIGHT_CHECKPOINT_KEY: &str = "finished_height";
+
+/// File extension of the checkpoint files.
+const CHECKPOINT_FILE_EXTENSION: &str = "json";
+
+/// Persistent key-value store of `ExEx` checkpoints, namespaced by `ExEx` ID.
+///
+/// The checkpoints of every `ExEx` are stored as a JSON object in their own file
+/// `<ID>.json` in the store directory, and are available to the `ExEx` via
+/// [`ExExContext::checkpoints`](crate::ExExContext::checkpoints). This way, `ExEx`'s don't need
+/// their own files to persist their progress or configuration, and node tooling can report the
+/// progress of all `ExEx`'s.
+#[derive(Debug, Clone)]
+pub struct ExExCheckpointStore {
+    /// Directory of the checkpoint files.
+    directory: PathBuf,
+}
+
+impl ExExCheckpointStore {
+    /// Opens the store in the given directory, creating the directory if it doesn't exist.
+    pub fn new(directory: impl AsRef<Path>) -> eyre::Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        reth_fs_util::create_dir_all(&directory)?;
+        Ok(Self { directory })
+    }
+
+    /// Loads the checkpoints of the `ExEx` with the given ID.
+    pub fn checkpoints(&self, exex_id: &str) -> eyre::Result<ExExCheckpoints> {
+        let path =
+            self.directory.join(format!("{}.{CHECKPOINT_FILE_EXTENSION}", encode_exex_id(exex_id)));
+        let entries = if path.exists() {
+            serde_json::from_slice(&reth_fs_util::read(&path)?)?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(ExExCheckpoints { path, entries: Arc::new(Mutex::new(entries)) })
+    }
+
+    /// Returns the IDs of all `ExEx`'s with checkpoints in the store, in lexicographic order.
+    pub fn exex_ids(&self) -> eyre::Result<Vec<String>> {
+        let mut ids = Vec::new();
+        for entry in reth_fs_util::read_dir(&self.directory)? {
+            let path = entry.map_err(|err| FsPathError::read_dir(err, &self.directory))?.path();
+            if path.extension().is_some_and(|extension| extension == CHECKPOINT_FILE_EXTENSION) {
+                if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    ids.push(decode_exex_id(id));
+                }
+            }
+        }
+        ids.sort_unstable();
+        Ok(ids)
+    }
+}
+
+/// Checkpoints of a single `ExEx`, loaded from an [`ExExCheckpointStore`].
+///
+/// Every update is written to disk before it returns. Clones share the same checkpoints.
+#[derive(Debug, Clone)]
+pub struct ExExCheckpoints {
+    /// Path of the checkpoint file.
+    path: PathBuf,
+    /// The checkpoints, keyed by name.
+    entries: Arc<Mutex<BTreeMap<String, Value>>>,
+}
+
+impl ExExCheckpoints {
+    /// Returns the checkpoint with the given key, if it exists.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> eyre::Result<Option<T>> {
+        let entries = self.entries.lock().expect("ExEx checkpoints lock poisoned");
+        Ok(entries.get(key).map(T::deserialize).transpose()?)
+    }
+
+    /// Sets the checkpoint with the given key.
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) -> eyre::Result<()> {
+        let mut entries = self.entries.lock().expect("ExEx checkpoints lock poisoned");
+        entries.insert(key.to_string(), serde_json::to_value(value)?);
+        self.persist(&entries)
+    }
+
+    /// Removes the checkpoint with the given key.
+    pub fn remove(&self, key: &str) -> eyre::Result<()> {
+        let mut entries = self.entries.lock().expect("ExEx checkpoints lock poisoned");
+        if entries.remove(key).is_some() {
+            self.persist(&entries)?;
+        }
+        Ok(())
+    }
+
+    /// Returns all checkpoints, keyed by name.
+    pub fn entries(&self) -> BTreeMap<String, Value> {
+        self.entries.lock().expect("ExEx checkpoints lock poisoned").clone()
+    }
+
+    /// Writes the checkpoints to disk, replacing the previous file only once the new one is
+    /// complete.
+    fn persist(&self, entries: &BTreeMap<String, Value>) -> eyre::Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        let data = serde_json::to_vec_pretty(entries)?;
+        let mut file =
+            File::create(&tmp_path).map_err(|err| FsPathError::create_file(err, &tmp_path))?;
+        file.write_all(&data)
+            .and_then(|()| file.sync_all())
+            .map_err(|err| FsPathError::write(err, &tmp_path))?;
+        reth_fs_util::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Encodes the `ExEx` ID as a file name, escaping all characters except ASCII alphanumerics, `-`
+/// and `_` as `%XX`.
+fn encode_exex_id(id: &str) -> String {
+    let mut encoded = String::with_capacity(id.len());
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decodes a file name created by [`encode_exex_id`].
+fn decode_exex_id(encoded: &str) -> String {
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                bytes.push(byte);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoints_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ExExCheckpointStore::new(dir.path()).unwrap();
+
+        let checkpoints = store.checkpoints("OP Bridge/v1").unwrap();
+        assert_eq!(checkpoints.get::<u64>(FINISHED_HEIGHT_CHECKPOINT_KEY).unwrap(), None);
+        checkpoints.set(FINISHED_HEIGHT_CHECKPOINT_KEY, &100u64).unwrap();
+        checkpoints.set("contracts", &vec!["a", "b"]).unwrap();
+        store.checkpoints("Indexer").unwrap().set("cursor", &"abc").unwrap();
+
+        // Checkpoints are loaded again from disk, and are separate per ExEx
+        let checkpoints = store.checkpoints("OP Bridge/v1").unwrap();
+        assert_eq!(checkpoints.get::<u64>(FINISHED_HEIGHT_CHECKPOINT_KEY).unwrap(), Some(100));
+        assert_eq!(checkpoints.get::<Vec<String>>("contracts").unwrap().unwrap(), ["a", "b"]);
+        assert_eq!(checkpoints.get::<String>("cursor").unwrap(), None);
+        assert_eq!(store.exex_ids().unwrap(), ["Indexer", "OP Bridge/v1"]);
+
+        checkpoints.remove("contracts").unwrap();
+        assert_eq!(
+            store.checkpoints("OP Bridge/v1").unwrap().entries().into_keys().collect::<Vec<_>>(),
+            [FINISHED_HEIGHT_CHECKPOINT_KEY]
+        );
+    }
+}
@@ -1,9 +1,13 @@
-use crate::{ExExEvent, ExExNotification};
+use crate::{BackfillJobFactory, ExExCheckpoints, ExExEvent, ExExNotification, ExExRpcModules};
+use jsonrpsee::Methods;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::node_config::NodeConfig;
 use reth_primitives::Head;
+use reth_provider::{
+    providers::BundleStateProvider, Chain, ProviderResult, StateProviderBox, StateProviderFactory,
+};
 use reth_tasks::TaskExecutor;
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
 /// Captures the context that an `ExEx` has access to.
@@ -32,6 +36,12 @@ pub struct ExExContext<Node: FullNodeComponents> {
 
     /// node components
     pub components: Node,
+
+    /// RPC modules registered by the `ExEx`'s of the node, see [`Self::register_rpc_module`].
+    pub rpc_modules: ExExRpcModules,
+
+    /// Persistent checkpoints of the `ExEx`, which survive node restarts.
+    pub checkpoints: ExExCheckpoints,
 }
 
 impl<Node: FullNodeComponents> Debug for ExExContext<Node> {
@@ -43,6 +53,8 @@ impl<Node: FullNodeComponents> Debug for ExExContext<Node> {
             .field("events", &self.events)
             .field("notifications", &self.notifications)
             .field("components", &"...")
+            .field("rpc_modules", &self.rpc_modules)
+            .field("checkpoints", &self.checkpoints)
             .finish()
     }
 }
@@ -82,4 +94,42 @@ impl<Node: FullNodeComponents> ExExContext<Node> {
     pub fn task_executor(&self) -> &TaskExecutor {
         self.components.task_executor()
     }
+
+    /// Returns a provider of the state before the first block of the chain, e.g. the committed or
+    /// reverted chain of an [`ExExNotification`].
+    ///
+    /// The provider reads from a consistent snapshot of the database, so it isn't affected by
+    /// blocks the node writes while the `ExEx` processes the chain. It holds a read transaction
+    /// open, so it shouldn't be kept around longer than necessary.
+    pub fn chain_pre_state(&self, chain: &Chain) -> ProviderResult<StateProviderBox> {
+        self.provider().history_by_block_hash(chain.first().parent_hash)
+    }
+
+    /// Returns a provider of the state after the last block of the chain, e.g. the committed or
+    /// reverted chain of an [`ExExNotification`].
+    ///
+    /// The state is the [pre-state](Self::chain_pre_state) of the chain with the execution outcome
+    /// of the chain applied on top, so it's consistent with the notification even if the chain
+    /// was reverted or is not written to the database yet.
+    pub fn chain_post_state(&self, chain: Arc<Chain>) -> ProviderResult<StateProviderBox> {
+        let pre_state = self.chain_pre_state(&chain)?;
+        Ok(Box::new(BundleStateProvider::new(pre_state, chain)))
+    }
+
+    /// Returns a factory for jobs that execute historical blocks and yield them as
+    /// [`ExExNotification`]s, e.g. to catch up with blocks executed before the `ExEx` was
+    /// installed.
+    pub fn backfill_job_factory(&self) -> BackfillJobFactory<Node::Executor, Node::Provider> {
+        BackfillJobFactory::new(self.block_executor().clone(), self.provider().clone())
+    }
+
+    /// Registers an RPC module, whose methods the node serves on its configured transports (http,
+    /// ws, ipc) while the `ExEx` is running.
+    ///
+    /// Modules must be registered while the `ExEx` is initialized, because the node starts its RPC
+    /// server afterwards. Once all clones of [`Self::events`] are dropped, i.e. the `ExEx` shut
+    /// down, calls to the methods of the module fail.
+    pub fn register_rpc_module(&self, module: impl Into<Methods>) -> eyre::Result<()> {
+        self.rpc_modules.register(module, &self.events)
+    }
 }
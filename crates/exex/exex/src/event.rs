@@ -1,7 +1,8 @@
+use crate::ExExNotificationFilter;
 use reth_primitives::BlockNumber;
 
 /// Events emitted by an `ExEx`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExExEvent {
     /// Highest block processed by the `ExEx`.
     ///
@@ -10,4 +11,8 @@ pub enum ExExEvent {
     ///
     /// On reorgs, it's possible for the height to go down.
     FinishedHeight(BlockNumber),
+    /// Filter of the notifications sent to the `ExEx` from now on, replacing the previous one.
+    ///
+    /// An empty filter removes the previous filter.
+    NotificationFilter(ExExNotificationFilter),
 }
@@ -0,0 +1,166 @@
+use crate::ExExNotification;
+use reth_primitives::{Address, B256};
+use reth_provider::{Chain, ExecutionOutcome};
+use reth_revm::db::BundleAccount;
+use std::{collections::HashSet, sync::Arc};
+
+/// Filter of the notifications sent to an `ExEx`, declared with an
+/// [`ExExEvent::NotificationFilter`](crate::ExExEvent::NotificationFilter) event.
+///
+/// The `ExEx` still receives a notification for every chain, so that it can track its finished
+/// height, but the chains are trimmed down to the data the `ExEx` is interested in. Trie updates
+/// are never included in filtered notifications.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExExNotificationFilter {
+    /// If not empty, only blocks with a log emitted by one of these addresses keep their
+    /// transactions and receipts. Other blocks only keep their header.
+    pub log_addresses: HashSet<Address>,
+    /// If not empty, only the state changes of these accounts are kept in the execution outcome.
+    pub state_addresses: HashSet<Address>,
+}
+
+impl ExExNotificationFilter {
+    /// Returns `true` if the filter doesn't remove anything from the notifications.
+    pub fn is_empty(&self) -> bool {
+        self.log_addresses.is_empty() && self.state_addresses.is_empty()
+    }
+
+    /// Returns the notification with its chains trimmed down according to the filter.
+    pub fn apply(&self, notification: &ExExNotification) -> ExExNotification {
+        let apply = |chain: &Arc<Chain>| Arc::new(self.apply_to_chain(chain));
+        match notification {
+            ExExNotification::ChainCommitted { new } => {
+                ExExNotification::ChainCommitted { new: apply(new) }
+            }
+            ExExNotification::ChainReorged { old, new } => {
+                ExExNotification::ChainReorged { old: apply(old), new: apply(new) }
+            }
+            ExExNotification::ChainReverted { old } => {
+                ExExNotification::ChainReverted { old: apply(old) }
+            }
+        }
+    }
+
+    /// Returns the chain trimmed down according to the filter.
+    pub fn apply_to_chain(&self, chain: &Chain) -> Chain {
+        let execution_outcome = chain.execution_outcome();
+
+        let mut receipts = execution_outcome.receipts.clone();
+        let mut blocks = Vec::with_capacity(chain.len());
+        for (block, block_receipts) in chain.blocks_iter().zip(receipts.receipt_vec.iter_mut()) {
+            let mut block = block.clone();
+            if !self.log_addresses.is_empty() &&
+                !block_receipts.iter().flatten().any(|receipt| {
+                    receipt.logs.iter().any(|log| self.log_addresses.contains(&log.address))
+                })
+            {
+                block.block.body.clear();
+                block.block.ommers.clear();
+                block.senders.clear();
+                block_receipts.clear();
+            }
+            blocks.push(block);
+        }
+
+        let mut bundle = execution_outcome.bundle.clone();
+        if !self.state_addresses.is_empty() {
+            bundle.state.retain(|address, _| self.state_addresses.contains(address));
+            for block_reverts in bundle.reverts.iter_mut() {
+                block_reverts.retain(|(address, _)| self.state_addresses.contains(address));
+            }
+
+            let code_hashes = bundle
+                .state
+                .values()
+                .filter_map(|account| account.info.as_ref().map(|info| info.code_hash))
+                .collect::<HashSet<B256>>();
+            bundle.contracts.retain(|code_hash, _| code_hashes.contains(code_hash));
+
+            bundle.state_size = bundle.state.values().map(BundleAccount::size_hint).sum();
+            bundle.reverts_size =
+                bundle.reverts.iter().flatten().map(|(_, revert)| revert.size_hint()).sum();
+        }
+
+        Chain::new(
+            blocks,
+            ExecutionOutcome {
+                bundle,
+                receipts,
+                first_block: execution_outcome.first_block,
+                requests: execution_outcome.requests.clone(),
+            },
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        Header, Log, Receipt, Receipts, SealedBlockWithSenders, TransactionSigned,
+    };
+    use reth_revm::db::{AccountStatus, BundleState};
+    use std::collections::HashMap;
+
+    fn block(number: u64) -> SealedBlockWithSenders {
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header = Header { number, ..Default::default() }.seal_slow();
+        block.block.body = vec![TransactionSigned::default()];
+        block.senders = vec![Address::ZERO];
+        block
+    }
+
+    fn receipt(log_address: Address) -> Option<Receipt> {
+        Some(Receipt {
+            logs: vec![Log { address: log_address, ..Default::default() }],
+            ..Default::default()
+        })
+    }
+
+    fn account() -> BundleAccount {
+        BundleAccount {
+            info: None,
+            original_info: None,
+            storage: HashMap::default(),
+            status: AccountStatus::Changed,
+        }
+    }
+
+    #[test]
+    fn apply_filter() {
+        let (a, b) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let chain = Chain::new(
+            [block(1), block(2)],
+            ExecutionOutcome {
+                bundle: BundleState {
+                    state: HashMap::from([(a, account()), (b, account())]),
+                    ..Default::default()
+                },
+                receipts: Receipts { receipt_vec: vec![vec![receipt(a)], vec![receipt(b)]] },
+                first_block: 1,
+                requests: Vec::new(),
+            },
+            None,
+        );
+
+        let filter = ExExNotificationFilter {
+            log_addresses: HashSet::from([a]),
+            state_addresses: HashSet::from([b]),
+        };
+        let filtered = filter.apply_to_chain(&chain);
+
+        // Both blocks are kept, but only the first one with its transactions and receipts
+        assert_eq!(filtered.range(), 1..=2);
+        assert_eq!(filtered.blocks()[&1].body.len(), 1);
+        assert!(filtered.blocks()[&2].body.is_empty());
+        assert!(filtered.blocks()[&2].senders.is_empty());
+        assert_eq!(
+            filtered.execution_outcome().receipts.receipt_vec,
+            vec![vec![receipt(a)], vec![]]
+        );
+
+        // Only the state changes of the second account are kept
+        assert_eq!(filtered.execution_outcome().bundle.state.keys().collect::<Vec<_>>(), vec![&b]);
+    }
+}
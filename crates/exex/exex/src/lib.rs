@@ -14,6 +14,22 @@
 //! in [`ExExContext`]. A new notification is emitted whenever blocks are executed in live and
 //! historical sync.
 //!
+//! To read the state around a notification, use [`ExExContext::chain_pre_state`] and
+//! [`ExExContext::chain_post_state`] instead of the latest state of the provider, which may already
+//! include blocks the `ExEx` hasn't received yet.
+//!
+//! `ExEx`'s that only need part of the data, e.g. indexers of a few contracts, can emit an
+//! `ExExEvent::NotificationFilter` event to receive trimmed-down notifications, see
+//! [`ExExNotificationFilter`].
+//!
+//! `ExEx`'s can serve their own RPC namespaces by registering modules with
+//! [`ExExContext::register_rpc_module`] during initialization. The node merges them into its
+//! configured RPC transports, and stops serving them once the `ExEx` shuts down.
+//!
+//! `ExEx`'s can persist their progress and configuration in [`ExExContext::checkpoints`], a small
+//! key-value store namespaced by `ExEx` ID. The node also records the last finished height of every
+//! `ExEx` there, under [`FINISHED_HEIGHT_CHECKPOINT_KEY`].
+//!
 //! # Pruning
 //!
 //! `ExEx`'s **SHOULD** emit an `ExExEvent::FinishedHeight` event to signify what blocks have been
@@ -23,6 +39,28 @@
 //! event. To clarify: if the `ExEx` emits `ExExEvent::FinishedHeight(0)` it will receive
 //! notifications for any `block_number > 0`.
 //!
+//! # Write-ahead log
+//!
+//! Notifications are written to a [`Wal`] before they're sent to the `ExEx`'s, and replayed on
+//! restart if not all `ExEx`'s have emitted a `FinishedHeight` event for them yet.
+//!
+//! # Remote `ExEx`'s
+//!
+//! Extensions can also run in a separate process, possibly written in another language. A
+//! [`RemoteExEx`] forwards the notifications to such an extension over a unix socket, and reports
+//! the notifications it acknowledges as its finished height.
+//!
+//! # Backfill
+//!
+//! Blocks executed before an `ExEx` was installed can be re-executed with a [`BackfillJob`],
+//! created by the [`BackfillJobFactory`] returned from [`ExExContext::backfill_job_factory`]. The
+//! job yields the same [`ExExNotification`]s the `ExEx` receives for new blocks.
+//!
+//! `ExEx`'s can also be added to and removed from a running node with
+//! [`ExExManagerHandle::add_exex`] and [`ExExManagerHandle::remove_exex`]. An added `ExEx` is
+//! backfilled with [`BackfillJobFactory::forward_with_backfill`] before it receives the
+//! notifications of new blocks.
+//!
 //! [`Future`]: std::future::Future
 //! [`ExExContext`]: crate::ExExContext
 //! [`CanonStateNotification`]: reth_provider::CanonStateNotification
@@ -34,18 +72,38 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod backfill;
+pub use backfill::*;
+
+mod checkpoint;
+pub use checkpoint::*;
+
 mod context;
 pub use context::*;
 
 mod event;
 pub use event::*;
 
+mod filter;
+pub use filter::*;
+
 mod manager;
 pub use manager::*;
 
+mod rpc;
+pub use rpc::*;
+
 mod notification;
 pub use notification::*;
 
+#[cfg(unix)]
+mod remote;
+#[cfg(unix)]
+pub use remote::*;
+
+mod wal;
+pub use wal::*;
+
 // Re-export exex types
 #[doc(inline)]
 pub use reth_exex_types::*;
@@ -1,8 +1,15 @@
-use crate::{ExExEvent, ExExNotification, FinishedExExHeight};
+use crate::{
+    ExExCheckpoints, ExExEvent, ExExNotification, ExExNotificationFilter, FinishedExExHeight, Wal,
+    FINISHED_HEIGHT_CHECKPOINT_KEY,
+};
 use metrics::Gauge;
-use reth_metrics::{metrics::Counter, Metrics};
+use reth_config::config::ExExLagPolicy;
+use reth_metrics::{
+    metrics::{Counter, Histogram},
+    Metrics,
+};
 use reth_primitives::BlockNumber;
-use reth_tracing::tracing::debug;
+use reth_tracing::tracing::{debug, error, warn};
 use std::{
     collections::VecDeque,
     future::{poll_fn, Future},
@@ -12,10 +19,11 @@ use std::{
         Arc,
     },
     task::{ready, Context, Poll},
+    time::Instant,
 };
 use tokio::sync::{
     mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
-    watch,
+    oneshot, watch,
 };
 use tokio_util::sync::{PollSendError, PollSender, ReusableBoxFuture};
 
@@ -27,13 +35,26 @@ struct ExExMetrics {
     notifications_sent_total: Counter,
     /// The total number of events an `ExEx` has sent to the manager.
     events_sent_total: Counter,
+    /// Number of notifications buffered by the manager that were not sent to the `ExEx` yet.
+    queue_depth: Gauge,
+    /// Time between sending a notification to the `ExEx` and the `ExEx` emitting a finished
+    /// height that includes it.
+    processing_latency: Histogram,
+    /// Number of blocks between the tip of the latest notification and the finished height of
+    /// the `ExEx`.
+    finished_height_lag: Gauge,
 }
 
+/// Maximum number of sent notifications an [`ExExHandle`] keeps track of to measure the
+/// processing latency of the `ExEx`.
+const MAX_TRACKED_NOTIFICATIONS: usize = 1024;
+
 /// A handle to an `ExEx` used by the [`ExExManager`] to communicate with `ExEx`'s.
 ///
 /// A handle should be created for each `ExEx` with a unique ID. The channels returned by
 /// [`ExExHandle::new`] should be given to the `ExEx`, while the handle itself should be given to
-/// the manager in [`ExExManager::new`].
+/// the manager in [`ExExManager::new`], or [`ExExManagerHandle::add_exex`] once the manager is
+/// running.
 #[derive(Debug)]
 pub struct ExExHandle {
     /// The execution extension's ID.
@@ -52,6 +73,16 @@ pub struct ExExHandle {
     ///
     /// If this is `None`, the `ExEx` has not emitted a `FinishedHeight` event.
     finished_height: Option<BlockNumber>,
+    /// Tips of the committed chains sent to the `ExEx` that are above its finished height, and
+    /// when they were sent.
+    sent_commits: VecDeque<(BlockNumber, Instant)>,
+    /// Whether the `ExEx` was detached because it fell too far behind. Detached `ExEx`'s don't
+    /// receive notifications anymore and are ignored for the finished height.
+    detached: bool,
+    /// Filter of the notifications sent to the `ExEx`, if any.
+    filter: Option<ExExNotificationFilter>,
+    /// Checkpoints of the `ExEx`, where the finished height is recorded, if any.
+    checkpoints: Option<ExExCheckpoints>,
 }
 
 impl ExExHandle {
@@ -71,12 +102,23 @@ impl ExExHandle {
                 receiver: event_rx,
                 next_notification_id: 0,
                 finished_height: None,
+                sent_commits: VecDeque::new(),
+                detached: false,
+                filter: None,
+                checkpoints: None,
             },
             event_tx,
             notification_rx,
         )
     }
 
+    /// Sets the checkpoints of the `ExEx`, where every finished height the `ExEx` reports is
+    /// recorded under [`FINISHED_HEIGHT_CHECKPOINT_KEY`].
+    pub fn with_checkpoints(mut self, checkpoints: ExExCheckpoints) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
     /// Reserves a slot in the `PollSender` channel and sends the notification if the slot was
     /// successfully reserved.
     ///
@@ -128,15 +170,49 @@ impl ExExHandle {
             %notification_id,
             "Sending notification"
         );
-        match self.sender.send_item(notification.clone()) {
+        let item = match &self.filter {
+            Some(filter) => filter.apply(notification),
+            None => notification.clone(),
+        };
+        match self.sender.send_item(item) {
             Ok(()) => {
                 self.next_notification_id = notification_id + 1;
                 self.metrics.notifications_sent_total.increment(1);
+                if let Some(chain) = notification.committed_chain() {
+                    if self.sent_commits.len() == MAX_TRACKED_NOTIFICATIONS {
+                        self.sent_commits.pop_front();
+                    }
+                    self.sent_commits.push_back((chain.tip().number, Instant::now()));
+                }
                 Poll::Ready(Ok(()))
             }
             Err(err) => Poll::Ready(Err(err)),
         }
     }
+
+    /// Updates the finished height of the `ExEx`, recording the processing latency of the
+    /// notifications it covers.
+    fn update_finished_height(&mut self, height: BlockNumber) {
+        self.finished_height = Some(height);
+        if let Some(checkpoints) = &self.checkpoints {
+            if let Err(err) = checkpoints.set(FINISHED_HEIGHT_CHECKPOINT_KEY, &height) {
+                warn!(exex_id = %self.id, %err, "Failed to record finished height checkpoint");
+            }
+        }
+        while let Some(&(tip, sent_at)) = self.sent_commits.front() {
+            if tip > height {
+                break
+            }
+            self.metrics.processing_latency.record(sent_at.elapsed());
+            self.sent_commits.pop_front();
+        }
+    }
+
+    /// Returns the number of blocks between the given tip and the finished height of the `ExEx`,
+    /// if both are known.
+    fn lag(&self, tip: Option<BlockNumber>) -> Option<u64> {
+        Some(tip?.saturating_sub(self.finished_height?))
+    }
 }
 
 /// Metrics for the `ExEx` manager.
@@ -153,6 +229,21 @@ pub struct ExExManagerMetrics {
     buffer_size: Gauge,
     /// Current number of `ExEx`'s on the node.
     num_exexs: Gauge,
+    /// Number of `ExEx`'s that were detached because they fell too far behind.
+    num_detached_exexs: Gauge,
+    /// Number of notifications that didn't fit into the buffer and are only kept in the
+    /// write-ahead log.
+    spilled_notifications: Gauge,
+}
+
+/// Commands to add and remove `ExEx`'s while the [`ExExManager`] is running, sent over
+/// [`ExExManagerHandle`]s.
+#[derive(Debug)]
+enum ExExManagerCommand {
+    /// Add the `ExEx`, responding with the tip of the latest received notification.
+    Add { handle: ExExHandle, tx: oneshot::Sender<eyre::Result<Option<BlockNumber>>> },
+    /// Remove the `ExEx` with the given ID, responding with whether it was installed.
+    Remove { id: String, tx: oneshot::Sender<bool> },
 }
 
 /// The execution extension manager.
@@ -171,6 +262,10 @@ pub struct ExExManager {
 
     /// [`ExExNotification`] channel from the [`ExExManagerHandle`]s.
     handle_rx: UnboundedReceiver<ExExNotification>,
+    /// [`ExExManagerCommand`] channel from the [`ExExManagerHandle`]s.
+    command_rx: UnboundedReceiver<ExExManagerCommand>,
+    /// The number of `ExEx`'s, shared with the [`ExExManagerHandle`]s.
+    num_exexs: Arc<AtomicUsize>,
 
     /// The minimum notification ID currently present in the buffer.
     min_id: usize,
@@ -194,6 +289,20 @@ pub struct ExExManager {
     /// The finished height of all `ExEx`'s.
     finished_height: watch::Sender<FinishedExExHeight>,
 
+    /// Write-ahead log of the notifications that were not processed by all `ExEx`'s yet.
+    wal: Option<Wal>,
+    /// IDs and WAL IDs of the notifications that didn't fit into the buffer, and are only kept in
+    /// the WAL until there is capacity again.
+    spilled: VecDeque<(usize, u64)>,
+
+    /// What the manager does when an `ExEx` falls too far behind.
+    lag_policy: ExExLagPolicy,
+    /// Number of blocks an `ExEx` can fall behind [`Self::latest_tip`] before the lag policy
+    /// applies.
+    max_lag_blocks: Option<u64>,
+    /// Tip of the chain after the latest received notification.
+    latest_tip: Option<BlockNumber>,
+
     /// A handle to the `ExEx` manager.
     handle: ExExManagerHandle,
     /// Metrics for the `ExEx` manager.
@@ -212,6 +321,7 @@ impl ExExManager {
         let num_exexs = handles.len();
 
         let (handle_tx, handle_rx) = mpsc::unbounded_channel();
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
         let (is_ready_tx, is_ready_rx) = watch::channel(true);
         let (finished_height_tx, finished_height_rx) = watch::channel(if num_exexs == 0 {
             FinishedExExHeight::NoExExs
@@ -225,10 +335,14 @@ impl ExExManager {
         metrics.max_capacity.set(max_capacity as f64);
         metrics.num_exexs.set(num_exexs as f64);
 
+        let num_exexs = Arc::new(AtomicUsize::new(num_exexs));
+
         Self {
             exex_handles: handles,
 
             handle_rx,
+            command_rx,
+            num_exexs: Arc::clone(&num_exexs),
 
             min_id: 0,
             next_id: 0,
@@ -239,8 +353,16 @@ impl ExExManager {
             is_ready: is_ready_tx,
             finished_height: finished_height_tx,
 
+            wal: None,
+            spilled: VecDeque::new(),
+
+            lag_policy: ExExLagPolicy::default(),
+            max_lag_blocks: None,
+            latest_tip: None,
+
             handle: ExExManagerHandle {
                 exex_tx: handle_tx,
+                command_tx,
                 num_exexs,
                 is_ready_receiver: is_ready_rx.clone(),
                 is_ready: ReusableBoxFuture::new(make_wait_future(is_ready_rx)),
@@ -251,6 +373,35 @@ impl ExExManager {
         }
     }
 
+    /// Sets the write-ahead log that every notification is written to before it's sent to the
+    /// `ExEx`'s.
+    ///
+    /// The notifications left in the WAL from a previous run are sent to the `ExEx`'s before any
+    /// new notifications.
+    pub fn with_wal(mut self, wal: Wal) -> eyre::Result<Self> {
+        for notification in wal.iter_notifications() {
+            self.push_notification(notification?);
+        }
+        self.wal = Some(wal);
+        Ok(self)
+    }
+
+    /// Sets what the manager does when an `ExEx` falls too far behind, i.e. more than
+    /// `max_lag_blocks` behind the tip of the latest notification, or so far that the notification
+    /// buffer is full.
+    ///
+    /// [`ExExLagPolicy::Buffer`] requires a [WAL](Self::with_wal), and otherwise behaves like
+    /// [`ExExLagPolicy::Pause`].
+    pub const fn with_lag_policy(
+        mut self,
+        lag_policy: ExExLagPolicy,
+        max_lag_blocks: Option<u64>,
+    ) -> Self {
+        self.lag_policy = lag_policy;
+        self.max_lag_blocks = max_lag_blocks;
+        self
+    }
+
     /// Returns the handle to the manager.
     pub fn handle(&self) -> ExExManagerHandle {
         self.handle.clone()
@@ -266,7 +417,106 @@ impl ExExManager {
 
         // we can safely ignore if the channel is closed, since the manager always holds it open
         // internally
-        let _ = self.is_ready.send(capacity > 0);
+        let _ = self.is_ready.send((capacity > 0 || self.can_spill()) && !self.is_lagging());
+    }
+
+    /// Returns `true` if notifications that don't fit into the buffer can be kept in the WAL only.
+    fn can_spill(&self) -> bool {
+        self.lag_policy == ExExLagPolicy::Buffer && self.wal.is_some()
+    }
+
+    /// Returns `true` if the manager should pause new notifications until an `ExEx` that fell
+    /// more than the maximum lag behind catches up.
+    fn is_lagging(&self) -> bool {
+        self.lag_policy == ExExLagPolicy::Pause &&
+            self.max_lag_blocks.is_some_and(|max_lag_blocks| {
+                self.exex_handles
+                    .iter()
+                    .filter(|exex| !exex.detached)
+                    .any(|exex| exex.lag(self.latest_tip).is_some_and(|lag| lag > max_lag_blocks))
+            })
+    }
+
+    /// Detaches the `ExEx`'s that fell too far behind, if the lag policy is
+    /// [`ExExLagPolicy::Detach`]. Returns the number of detached `ExEx`'s.
+    fn detach_lagging_exexs(&mut self) -> usize {
+        if self.lag_policy != ExExLagPolicy::Detach {
+            return 0
+        }
+
+        let buffer_full = self.buffer.len() >= self.max_capacity;
+        let mut detached = 0;
+        for exex in self.exex_handles.iter_mut().filter(|exex| !exex.detached) {
+            let lag = exex.lag(self.latest_tip);
+            let lagging = self
+                .max_lag_blocks
+                .is_some_and(|max_lag_blocks| lag.is_some_and(|lag| lag > max_lag_blocks));
+            // The oldest notification in a full buffer wasn't sent to the `ExEx` yet
+            let holds_buffer = buffer_full && exex.next_notification_id == self.min_id;
+
+            if lagging || holds_buffer {
+                error!(
+                    exex_id = %exex.id,
+                    ?lag,
+                    finished_height = ?exex.finished_height,
+                    "ExEx fell too far behind and was detached, it won't receive notifications anymore"
+                );
+                exex.detached = true;
+                detached += 1;
+            }
+        }
+
+        if detached > 0 {
+            self.metrics.num_detached_exexs.increment(detached as f64);
+        }
+        detached
+    }
+
+    /// Handles the commands to add and remove `ExEx`'s.
+    ///
+    /// An added `ExEx` receives the notifications received by the manager after it was added.
+    fn poll_commands(&mut self, cx: &mut Context<'_>) {
+        while let Poll::Ready(Some(command)) = self.command_rx.poll_recv(cx) {
+            match command {
+                ExExManagerCommand::Add { mut handle, tx } => {
+                    let result = if self.exex_handles.iter().any(|exex| exex.id == handle.id) {
+                        Err(eyre::eyre!("ExEx {} is already installed", handle.id))
+                    } else {
+                        debug!(exex_id = %handle.id, "Adding ExEx");
+                        // no other exex needs the buffered notifications anymore
+                        if self.exex_handles.iter().all(|exex| exex.detached) {
+                            self.buffer.clear();
+                            self.spilled.clear();
+                            self.min_id = self.next_id;
+                        }
+                        handle.next_notification_id = self.next_id;
+                        self.exex_handles.push(handle);
+                        self.update_num_exexs();
+                        // the added exex didn't finish any height yet, so nothing can be pruned
+                        // until it does
+                        let _ = self.finished_height.send(FinishedExExHeight::NotReady);
+                        Ok(self.latest_tip)
+                    };
+                    let _ = tx.send(result);
+                }
+                ExExManagerCommand::Remove { id, tx } => {
+                    let num_exexs = self.exex_handles.len();
+                    self.exex_handles.retain(|exex| exex.id != id);
+                    let removed = self.exex_handles.len() < num_exexs;
+                    if removed {
+                        debug!(exex_id = %id, "Removed ExEx");
+                        self.update_num_exexs();
+                    }
+                    let _ = tx.send(removed);
+                }
+            }
+        }
+    }
+
+    /// Updates the number of `ExEx`'s shared with the [`ExExManagerHandle`]s.
+    fn update_num_exexs(&self) {
+        self.num_exexs.store(self.exex_handles.len(), Ordering::Relaxed);
+        self.metrics.num_exexs.set(self.exex_handles.len() as f64);
     }
 
     /// Pushes a new notification into the managers internal buffer, assigning the notification a
@@ -282,15 +532,45 @@ impl Future for ExExManager {
     type Output = eyre::Result<()>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // add and remove exexs before draining notifications, so that added exexs receive all
+        // notifications after the tip they were added at
+        self.poll_commands(cx);
+
         // drain handle notifications
-        while self.buffer.len() < self.max_capacity {
+        while self.buffer.len() < self.max_capacity || self.can_spill() {
             if let Poll::Ready(Some(notification)) = self.handle_rx.poll_recv(cx) {
                 debug!(
                     committed_tip = ?notification.committed_chain().map(|chain| chain.tip().number),
                     reverted_tip = ?notification.reverted_chain().map(|chain| chain.tip().number),
                     "Received new notification"
                 );
-                self.push_notification(notification);
+                if let Some(chain) = notification.committed_chain() {
+                    self.latest_tip = Some(chain.tip().number);
+                } else if let Some(chain) = notification.reverted_chain() {
+                    self.latest_tip = Some(chain.first().number.saturating_sub(1));
+                }
+
+                // no exex would receive the notification
+                if self.exex_handles.iter().all(|exex| exex.detached) {
+                    continue
+                }
+
+                let wal_id = match self.wal.as_mut().map(|wal| wal.commit(&notification)) {
+                    Some(Ok(wal_id)) => Some(wal_id),
+                    Some(Err(err)) => return Poll::Ready(Err(err)),
+                    None => None,
+                };
+                match wal_id {
+                    // keep the notification in the WAL only, until there is capacity again
+                    Some(wal_id)
+                        if self.buffer.len() >= self.max_capacity || !self.spilled.is_empty() =>
+                    {
+                        let id = self.next_id;
+                        self.spilled.push_back((id, wal_id));
+                        self.next_id += 1;
+                    }
+                    _ => self.push_notification(notification),
+                }
                 continue
             }
             break
@@ -303,6 +583,10 @@ impl Future for ExExManager {
         let mut min_id = usize::MAX;
         for idx in (0..self.exex_handles.len()).rev() {
             let mut exex = self.exex_handles.swap_remove(idx);
+            if exex.detached {
+                self.exex_handles.push(exex);
+                continue
+            }
 
             // it is a logic error for this to ever underflow since the manager manages the
             // notification IDs
@@ -325,8 +609,20 @@ impl Future for ExExManager {
         self.buffer.retain(|&(id, _)| id >= min_id);
         self.min_id = min_id;
 
-        // update capacity
-        self.update_capacity();
+        // load the notifications kept in the WAL only back into the buffer
+        let mut loaded = false;
+        while self.buffer.len() < self.max_capacity {
+            let Some(&(id, wal_id)) = self.spilled.front() else { break };
+            let notification =
+                self.wal.as_ref().expect("notifications are only spilled to the WAL").read(wal_id);
+            match notification {
+                Ok(notification) => self.buffer.push_back((id, notification)),
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+            self.spilled.pop_front();
+            loaded = true;
+        }
+        self.metrics.spilled_notifications.set(self.spilled.len() as f64);
 
         // handle incoming exex events
         for exex in &mut self.exex_handles {
@@ -334,26 +630,59 @@ impl Future for ExExManager {
                 debug!(exex_id = %exex.id, ?event, "Received event from exex");
                 exex.metrics.events_sent_total.increment(1);
                 match event {
-                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+                    ExExEvent::FinishedHeight(height) => exex.update_finished_height(height),
+                    ExExEvent::NotificationFilter(filter) => {
+                        exex.filter = (!filter.is_empty()).then_some(filter)
+                    }
                 }
             }
         }
 
+        // update exex metrics
+        let (next_id, latest_tip) = (self.next_id, self.latest_tip);
+        for exex in &self.exex_handles {
+            exex.metrics.queue_depth.set(next_id.saturating_sub(exex.next_notification_id) as f64);
+            if let Some(lag) = exex.lag(latest_tip) {
+                exex.metrics.finished_height_lag.set(lag as f64);
+            }
+        }
+
+        let detached = self.detach_lagging_exexs();
+
+        // update capacity
+        self.update_capacity();
+
         // update watch channel block number
-        let finished_height = self.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
-            let height = match exex.finished_height {
-                None => return Err(()),
-                Some(height) => height,
-            };
-
-            if height < curr {
-                Ok(height)
-            } else {
-                Ok(curr)
+        let finished_height = if self.exex_handles.iter().all(|exex| exex.detached) {
+            FinishedExExHeight::NoExExs
+        } else {
+            self.exex_handles
+                .iter()
+                .filter(|exex| !exex.detached)
+                .try_fold(u64::MAX, |curr, exex| {
+                    exex.finished_height.map(|height| height.min(curr))
+                })
+                .map_or(FinishedExExHeight::NotReady, FinishedExExHeight::Height)
+        };
+        let _ = self.finished_height.send(finished_height);
+
+        // notifications kept in the WAL only are still needed to refill the buffer
+        let finalize_height = match finished_height {
+            FinishedExExHeight::Height(height) if self.spilled.is_empty() => Some(height),
+            // no exex needs the notifications anymore, e.g. because all of them were removed
+            FinishedExExHeight::NoExExs if self.spilled.is_empty() => self.latest_tip,
+            _ => None,
+        };
+        if let (Some(height), Some(wal)) = (finalize_height, &mut self.wal) {
+            if let Err(err) = wal.finalize(height) {
+                return Poll::Ready(Err(err))
             }
-        });
-        if let Ok(finished_height) = finished_height {
-            let _ = self.finished_height.send(FinishedExExHeight::Height(finished_height));
+        }
+
+        // poll again to send the loaded notifications, and to release the buffer of detached
+        // exexs
+        if loaded || detached > 0 {
+            cx.waker().wake_by_ref();
         }
 
         Poll::Pending
@@ -365,8 +694,10 @@ impl Future for ExExManager {
 pub struct ExExManagerHandle {
     /// Channel to send notifications to the `ExEx` manager.
     exex_tx: UnboundedSender<ExExNotification>,
+    /// Channel to send commands to the `ExEx` manager.
+    command_tx: UnboundedSender<ExExManagerCommand>,
     /// The number of `ExEx`'s running on the node.
-    num_exexs: usize,
+    num_exexs: Arc<AtomicUsize>,
     /// A watch channel denoting whether the manager is ready for new notifications or not.
     ///
     /// This is stored internally alongside a `ReusableBoxFuture` representation of the same value.
@@ -390,12 +721,14 @@ impl ExExManagerHandle {
     /// The handle will always be ready, and have a capacity of 0.
     pub fn empty() -> Self {
         let (exex_tx, _) = mpsc::unbounded_channel();
+        let (command_tx, _) = mpsc::unbounded_channel();
         let (_, is_ready_rx) = watch::channel(true);
         let (_, finished_height_rx) = watch::channel(FinishedExExHeight::NoExExs);
 
         Self {
             exex_tx,
-            num_exexs: 0,
+            command_tx,
+            num_exexs: Arc::new(AtomicUsize::new(0)),
             is_ready_receiver: is_ready_rx.clone(),
             is_ready: ReusableBoxFuture::new(make_wait_future(is_ready_rx)),
             current_capacity: Arc::new(AtomicUsize::new(0)),
@@ -436,8 +769,38 @@ impl ExExManagerHandle {
     }
 
     /// Returns `true` if there are `ExEx`'s installed in the node.
-    pub const fn has_exexs(&self) -> bool {
-        self.num_exexs > 0
+    pub fn has_exexs(&self) -> bool {
+        self.num_exexs.load(Ordering::Relaxed) > 0
+    }
+
+    /// Adds an `ExEx` to the running manager. The channels returned by [`ExExHandle::new`] should
+    /// be given to the `ExEx`.
+    ///
+    /// The `ExEx` receives the notifications the manager receives after it was added. Returns the
+    /// tip of the chain after the latest notification the manager received before, i.e. the
+    /// height the `ExEx` needs to be backfilled up to, or `None` if the manager didn't receive any
+    /// notifications yet.
+    ///
+    /// Returns an error if an `ExEx` with the same ID is already installed, or if the manager is
+    /// not running.
+    pub async fn add_exex(&self, handle: ExExHandle) -> eyre::Result<Option<BlockNumber>> {
+        let (tx, rx) = oneshot::channel();
+        self.command_tx
+            .send(ExExManagerCommand::Add { handle, tx })
+            .map_err(|_| eyre::eyre!("ExEx manager is not running"))?;
+        rx.await?
+    }
+
+    /// Removes the `ExEx` with the given ID from the running manager, which closes the
+    /// notification channel of the `ExEx`.
+    ///
+    /// Returns `false` if there is no `ExEx` with the given ID.
+    pub async fn remove_exex(&self, id: impl Into<String>) -> eyre::Result<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.command_tx
+            .send(ExExManagerCommand::Remove { id: id.into(), tx })
+            .map_err(|_| eyre::eyre!("ExEx manager is not running"))?;
+        Ok(rx.await?)
     }
 
     /// The finished height of all `ExEx`'s.
@@ -470,7 +833,8 @@ impl Clone for ExExManagerHandle {
     fn clone(&self) -> Self {
         Self {
             exex_tx: self.exex_tx.clone(),
-            num_exexs: self.num_exexs,
+            command_tx: self.command_tx.clone(),
+            num_exexs: self.num_exexs.clone(),
             is_ready_receiver: self.is_ready_receiver.clone(),
             is_ready: ReusableBoxFuture::new(make_wait_future(self.is_ready_receiver.clone())),
             current_capacity: self.current_capacity.clone(),
@@ -481,6 +845,28 @@ impl Clone for ExExManagerHandle {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use reth_primitives::{Header, SealedBlockWithSenders};
+    use reth_provider::{Chain, ExecutionOutcome};
+    use tokio::sync::mpsc::error::TryRecvError;
+
+    fn committed(number: BlockNumber) -> ExExNotification {
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header = Header { number, ..Default::default() }.seal_slow();
+        ExExNotification::ChainCommitted {
+            new: Arc::new(Chain::new(vec![block], ExecutionOutcome::default(), None)),
+        }
+    }
+
+    /// Polls the manager once, which must not resolve.
+    async fn poll_once(mut manager: Pin<&mut ExExManager>) {
+        poll_fn(|cx| {
+            assert!(manager.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn delivers_events() {}
 
@@ -495,4 +881,112 @@ mod tests {
 
     #[tokio::test]
     async fn is_ready() {}
+
+    #[tokio::test]
+    async fn add_and_remove_exexs() {
+        let manager = ExExManager::new(Vec::new(), 1);
+        let handle = manager.handle();
+        tokio::spawn(manager);
+        assert!(!handle.has_exexs());
+
+        let (exex, events, _notifications) = ExExHandle::new("test".to_string());
+        assert_eq!(handle.add_exex(exex).await.unwrap(), None);
+        assert!(handle.has_exexs());
+
+        // the added exex holds back pruning until it finished a height
+        let finished_height = handle.finished_height();
+        assert!(matches!(*finished_height.borrow(), FinishedExExHeight::NotReady));
+        events.send(ExExEvent::FinishedHeight(5)).unwrap();
+        tokio::task::yield_now().await;
+        assert!(matches!(*finished_height.borrow(), FinishedExExHeight::Height(5)));
+
+        // IDs are unique
+        let (exex, _events, _notifications) = ExExHandle::new("test".to_string());
+        assert!(handle.add_exex(exex).await.is_err());
+
+        assert!(handle.remove_exex("test").await.unwrap());
+        assert!(!handle.remove_exex("test").await.unwrap());
+        assert!(!handle.has_exexs());
+        assert!(matches!(*finished_height.borrow(), FinishedExExHeight::NoExExs));
+    }
+
+    #[tokio::test]
+    async fn lag_policy_pause() {
+        let (exex, events, mut notifications) = ExExHandle::new("test".to_string());
+        let manager =
+            ExExManager::new(vec![exex], 4).with_lag_policy(ExExLagPolicy::Pause, Some(5));
+        let handle = manager.handle();
+        let mut manager = std::pin::pin!(manager);
+
+        events.send(ExExEvent::FinishedHeight(0)).unwrap();
+        handle.send(committed(10)).unwrap();
+        poll_once(manager.as_mut()).await;
+        assert_eq!(notifications.try_recv().unwrap(), committed(10));
+
+        // the exex is 10 blocks behind the tip, so new notifications are paused
+        assert!(!*manager.is_ready.borrow());
+
+        // the exex caught up
+        events.send(ExExEvent::FinishedHeight(10)).unwrap();
+        poll_once(manager.as_mut()).await;
+        assert!(*manager.is_ready.borrow());
+    }
+
+    #[tokio::test]
+    async fn lag_policy_detach() {
+        let (lagging, lagging_events, mut lagging_notifications) =
+            ExExHandle::new("lagging".to_string());
+        let (fast, fast_events, mut fast_notifications) = ExExHandle::new("fast".to_string());
+        let manager = ExExManager::new(vec![lagging, fast], 4)
+            .with_lag_policy(ExExLagPolicy::Detach, Some(5));
+        let handle = manager.handle();
+        let finished_height = handle.finished_height();
+        let mut manager = std::pin::pin!(manager);
+
+        lagging_events.send(ExExEvent::FinishedHeight(0)).unwrap();
+        fast_events.send(ExExEvent::FinishedHeight(10)).unwrap();
+        handle.send(committed(10)).unwrap();
+        poll_once(manager.as_mut()).await;
+        assert_eq!(lagging_notifications.try_recv().unwrap(), committed(10));
+        assert_eq!(fast_notifications.try_recv().unwrap(), committed(10));
+
+        // the lagging exex is detached, and ignored for the finished height
+        assert!(*manager.is_ready.borrow());
+        assert!(matches!(*finished_height.borrow(), FinishedExExHeight::Height(10)));
+
+        // the detached exex doesn't receive notifications anymore
+        handle.send(committed(11)).unwrap();
+        poll_once(manager.as_mut()).await;
+        assert_eq!(fast_notifications.try_recv().unwrap(), committed(11));
+        assert_eq!(lagging_notifications.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[tokio::test]
+    async fn lag_policy_buffer() {
+        let dir = tempfile::tempdir().unwrap();
+        let (exex, _events, mut notifications) = ExExHandle::new("test".to_string());
+        let manager = ExExManager::new(vec![exex], 1)
+            .with_wal(Wal::new(dir.path()).unwrap())
+            .unwrap()
+            .with_lag_policy(ExExLagPolicy::Buffer, None);
+        let handle = manager.handle();
+        let mut manager = std::pin::pin!(manager);
+
+        for number in 1..=4 {
+            handle.send(committed(number)).unwrap();
+        }
+        poll_once(manager.as_mut()).await;
+
+        // the notifications that don't fit into the buffer are kept in the WAL only, and new
+        // notifications are still accepted
+        assert!(!manager.spilled.is_empty());
+        assert!(*manager.is_ready.borrow());
+
+        // the notifications are loaded back from the WAL as the exex catches up
+        for number in 1..=4 {
+            assert_eq!(notifications.recv().await.unwrap(), committed(number));
+            poll_once(manager.as_mut()).await;
+        }
+        assert!(manager.spilled.is_empty());
+    }
 }
@@ -3,8 +3,7 @@ use std::sync::Arc;
 use reth_provider::{CanonStateNotification, Chain};
 
 /// Notifications sent to an `ExEx`.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ExExNotification {
     /// Chain got committed without a reorg, and only the new chain is returned.
     ChainCommitted {
@@ -43,6 +42,29 @@ impl ExExNotification {
             Self::ChainCommitted { .. } => None,
         }
     }
+
+    /// Returns the notification with the trie updates of its chains cleared.
+    ///
+    /// Trie updates are keyed by enums, which can't be used as JSON object keys, so they're
+    /// cleared before a notification is serialized to JSON.
+    pub(crate) fn without_trie_updates(&self) -> Self {
+        let clear = |chain: &mut Arc<Chain>| {
+            if chain.trie_updates().is_some() {
+                Arc::make_mut(chain).clear_trie_updates();
+            }
+        };
+
+        let mut notification = self.clone();
+        match &mut notification {
+            Self::ChainCommitted { new } => clear(new),
+            Self::ChainReorged { old, new } => {
+                clear(old);
+                clear(new);
+            }
+            Self::ChainReverted { old } => clear(old),
+        }
+        notification
+    }
 }
 
 impl From<CanonStateNotification> for ExExNotification {
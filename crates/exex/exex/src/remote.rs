@@ -0,0 +1,283 @@
+//! Transport for `ExEx`'s running outside of the node process.
+//!
+//! A [`RemoteExEx`] is installed like any other `ExEx`, and forwards the notifications it receives
+//! to an extension process connected over a unix socket. This way, extensions can be written in
+//! other languages, and can crash or restart without affecting the node.
+//!
+//! # Protocol
+//!
+//! Every message is a JSON object, prefixed with its length in bytes as a 4 byte big-endian
+//! integer.
+//!
+//! - The node sends [`RemoteExExNotification`]s, each with an ID that increases by one with every
+//!   notification.
+//! - The extension sends a [`RemoteExExAck`] once it has processed a notification, which
+//!   acknowledges that notification and all notifications before it. The tip of the committed chain
+//!   of the last acknowledged notification is reported to the node as the finished height of the
+//!   `ExEx`.
+//!
+//! The node sends at most [`RemoteExEx::with_max_in_flight`] notifications that were not
+//! acknowledged yet, and doesn't receive new notifications from the node until the extension
+//! acknowledges them. Unacknowledged notifications are sent again when the extension reconnects.
+
+use crate::{ExExContext, ExExEvent, ExExNotification};
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use reth_node_api::FullNodeComponents;
+use reth_tracing::tracing::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
+use tokio::{
+    net::{UnixListener, UnixStream},
+    sync::mpsc::{Receiver, UnboundedSender},
+};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// Default for [`RemoteExEx::with_max_in_flight`].
+pub const DEFAULT_MAX_IN_FLIGHT_NOTIFICATIONS: usize = 16;
+
+/// Notification sent to a remote `ExEx`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteExExNotification {
+    /// ID of the notification, used to acknowledge it.
+    ///
+    /// IDs start at `0` every time the node starts.
+    pub id: u64,
+    /// The notification. Trie updates of the chains are not sent.
+    pub notification: ExExNotification,
+}
+
+/// Acknowledgement of a [`RemoteExExNotification`], sent by a remote `ExEx`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteExExAck {
+    /// ID of the last processed notification.
+    pub id: u64,
+}
+
+/// An `ExEx` that forwards notifications to an extension in another process over a unix socket.
+///
+/// See the [module documentation](self) for the protocol.
+#[derive(Debug)]
+pub struct RemoteExEx<Node: FullNodeComponents> {
+    ctx: ExExContext<Node>,
+    /// Path of the unix socket the extension connects to.
+    socket_path: PathBuf,
+    /// Notifications of the extension, kept across its connections.
+    state: RemoteExExState,
+}
+
+impl<Node: FullNodeComponents> RemoteExEx<Node> {
+    /// Creates a new remote `ExEx` that listens for the extension on the given socket path.
+    pub fn new(ctx: ExExContext<Node>, socket_path: impl AsRef<Path>) -> Self {
+        Self {
+            ctx,
+            socket_path: socket_path.as_ref().to_path_buf(),
+            state: RemoteExExState::new(DEFAULT_MAX_IN_FLIGHT_NOTIFICATIONS),
+        }
+    }
+
+    /// Sets the maximum number of notifications sent to the extension that were not acknowledged
+    /// yet.
+    pub const fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.state.max_in_flight = if max_in_flight == 0 { 1 } else { max_in_flight };
+        self
+    }
+
+    /// Runs the `ExEx`, accepting one extension connection at a time.
+    ///
+    /// Returns once the node stops sending notifications.
+    pub async fn run(mut self) -> eyre::Result<()> {
+        // Remove the socket left behind by a previous run
+        if self.socket_path.exists() {
+            reth_fs_util::remove_file(&self.socket_path)?;
+        }
+        let listener = UnixListener::bind(&self.socket_path)?;
+        info!(target: "exex::remote", socket_path = ?self.socket_path, "Listening for remote ExEx");
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            info!(target: "exex::remote", pending = self.state.pending.len(), "Remote ExEx connected");
+
+            match self.state.serve(stream, &mut self.ctx.notifications, &self.ctx.events).await {
+                Ok(true) => {}
+                Ok(false) => return Ok(()),
+                Err(err) => warn!(target: "exex::remote", %err, "Remote ExEx connection failed"),
+            }
+            info!(target: "exex::remote", "Remote ExEx disconnected");
+        }
+    }
+}
+
+/// Notifications of a [`RemoteExEx`] that are sent to the extension.
+#[derive(Debug)]
+struct RemoteExExState {
+    /// Maximum number of notifications sent to the extension that were not acknowledged yet.
+    max_in_flight: usize,
+    /// Notifications sent to the extension that were not acknowledged yet.
+    pending: VecDeque<RemoteExExNotification>,
+    /// ID of the next notification.
+    next_id: u64,
+}
+
+impl RemoteExExState {
+    const fn new(max_in_flight: usize) -> Self {
+        Self { max_in_flight, pending: VecDeque::new(), next_id: 0 }
+    }
+
+    /// Serves the connection of an extension, forwarding the notifications of the node and
+    /// reporting the finished heights the extension acknowledges.
+    ///
+    /// Returns `false` if the node stopped sending notifications, and `true` if the extension
+    /// disconnected.
+    async fn serve(
+        &mut self,
+        stream: UnixStream,
+        notifications: &mut Receiver<ExExNotification>,
+        events: &UnboundedSender<ExExEvent>,
+    ) -> eyre::Result<bool> {
+        let mut framed = Framed::new(
+            stream,
+            LengthDelimitedCodec::builder().max_frame_length(u32::MAX as usize).new_codec(),
+        );
+
+        // Send the notifications the previous connection didn't acknowledge again
+        for notification in &self.pending {
+            framed.send(Bytes::from(serde_json::to_vec(notification)?)).await?;
+        }
+
+        loop {
+            tokio::select! {
+                notification = notifications.recv(), if self.pending.len() < self.max_in_flight => {
+                    let Some(notification) = notification else { return Ok(false) };
+
+                    let notification = RemoteExExNotification {
+                        id: self.next_id,
+                        notification: notification.without_trie_updates(),
+                    };
+                    self.next_id += 1;
+
+                    let data = serde_json::to_vec(&notification)?;
+                    self.pending.push_back(notification);
+                    framed.send(Bytes::from(data)).await?;
+                }
+                frame = framed.next() => {
+                    let Some(frame) = frame else { return Ok(true) };
+                    let ack: RemoteExExAck = serde_json::from_slice(&frame?)?;
+                    debug!(target: "exex::remote", id = ack.id, "Received acknowledgement");
+
+                    let mut finished_height = None;
+                    while self.pending.front().is_some_and(|notification| notification.id <= ack.id) {
+                        let notification = self.pending.pop_front().expect("pending notification exists");
+                        if let Some(chain) = notification.notification.committed_chain() {
+                            finished_height = Some(chain.tip().number);
+                        }
+                    }
+
+                    if let Some(height) = finished_height {
+                        if events.send(ExExEvent::FinishedHeight(height)).is_err() {
+                            return Ok(false)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{BlockNumber, Header, SealedBlockWithSenders};
+    use reth_provider::{Chain, ExecutionOutcome};
+    use std::sync::Arc;
+    use tokio::{sync::mpsc, task::JoinHandle};
+
+    /// Connection of the extension.
+    type Extension = Framed<UnixStream, LengthDelimitedCodec>;
+
+    /// Task serving the extension, returning the state and notifications for the next connection.
+    type ServeTask = JoinHandle<(eyre::Result<bool>, RemoteExExState, Receiver<ExExNotification>)>;
+
+    fn committed(number: BlockNumber) -> ExExNotification {
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header = Header { number, ..Default::default() }.seal_slow();
+        ExExNotification::ChainCommitted {
+            new: Arc::new(Chain::new(vec![block], ExecutionOutcome::default(), None)),
+        }
+    }
+
+    /// Connects an extension to the state, and serves it in the background.
+    fn connect(
+        mut state: RemoteExExState,
+        mut notifications: Receiver<ExExNotification>,
+        events: UnboundedSender<ExExEvent>,
+    ) -> (Extension, ServeTask) {
+        let (node, extension) = UnixStream::pair().unwrap();
+        let task = tokio::spawn(async move {
+            let res = state.serve(node, &mut notifications, &events).await;
+            (res, state, notifications)
+        });
+        (Framed::new(extension, LengthDelimitedCodec::new()), task)
+    }
+
+    async fn receive(extension: &mut Extension) -> RemoteExExNotification {
+        serde_json::from_slice(&extension.next().await.unwrap().unwrap()).unwrap()
+    }
+
+    async fn ack(extension: &mut Extension, id: u64) {
+        let ack = serde_json::to_vec(&RemoteExExAck { id }).unwrap();
+        extension.send(Bytes::from(ack)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn forwards_notifications_and_resends_unacknowledged() {
+        let (notifications_tx, notifications) = mpsc::channel(4);
+        let (events, mut events_rx) = mpsc::unbounded_channel();
+        notifications_tx.send(committed(1)).await.unwrap();
+        notifications_tx.send(committed(2)).await.unwrap();
+
+        let (mut extension, task) = connect(RemoteExExState::new(1), notifications, events);
+
+        let notification = receive(&mut extension).await;
+        assert_eq!(notification.id, 0);
+        assert_eq!(notification.notification, committed(1));
+
+        // The second notification isn't received from the node before the first one is
+        // acknowledged
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(notifications_tx.capacity(), 3);
+
+        ack(&mut extension, 0).await;
+        let notification = receive(&mut extension).await;
+        assert_eq!(notification.id, 1);
+        assert_eq!(notification.notification, committed(2));
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(1)));
+
+        // The extension disconnects before acknowledging the second notification
+        drop(extension);
+        let (res, state, notifications) = task.await.unwrap();
+        assert!(res.unwrap());
+        assert_eq!(state.pending.len(), 1);
+
+        // The unacknowledged notification is sent again on reconnect
+        let (events, mut events_rx) = mpsc::unbounded_channel();
+        let (mut extension, task) = connect(state, notifications, events);
+        let notification = receive(&mut extension).await;
+        assert_eq!(notification.id, 1);
+        assert_eq!(notification.notification, committed(2));
+
+        ack(&mut extension, 1).await;
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(2)));
+
+        // Serving stops once the node stops sending notifications
+        drop(notifications_tx);
+        let (res, state, _) = task.await.unwrap();
+        assert!(!res.unwrap());
+        assert!(state.pending.is_empty());
+    }
+}
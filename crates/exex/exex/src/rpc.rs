@@ -0,0 +1,156 @@
+//! RPC modules contributed by `ExEx`'s.
+
+use crate::ExExEvent;
+use futures::FutureExt;
+use jsonrpsee::{
+    core::RegisterMethodError,
+    types::{error::METHOD_NOT_FOUND_CODE, ErrorObject, Id, Params},
+    Extensions, MaxResponseSize, MethodCallback, MethodResponse, MethodSink, Methods,
+    SubscriptionState,
+};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{UnboundedSender, WeakUnboundedSender};
+
+/// RPC modules registered by `ExEx`'s with
+/// [`ExExContext::register_rpc_module`](crate::ExExContext::register_rpc_module), which the node
+/// merges into its configured RPC transports when it starts the RPC server.
+///
+/// The methods of a module are only served while the `ExEx` that registered it is running, i.e.
+/// while its [`ExExContext::events`](crate::ExExContext::events) sender or a clone of it is alive.
+/// Once the `ExEx` shuts down, calls fail with a "method not found" error. Unsubscribing from
+/// existing subscriptions keeps working.
+#[derive(Debug, Clone)]
+pub struct ExExRpcModules {
+    /// Methods registered so far, or `None` once they were taken by the node.
+    methods: Arc<Mutex<Option<Methods>>>,
+}
+
+impl Default for ExExRpcModules {
+    fn default() -> Self {
+        Self { methods: Arc::new(Mutex::new(Some(Methods::new()))) }
+    }
+}
+
+impl ExExRpcModules {
+    /// Registers the methods of an `ExEx`, which are only served while the given events sender of
+    /// the `ExEx` is alive.
+    ///
+    /// Returns an error if a method with the same name was already registered by another `ExEx`,
+    /// or if the modules were already taken by the node.
+    pub(crate) fn register(
+        &self,
+        methods: impl Into<Methods>,
+        events: &UnboundedSender<ExExEvent>,
+    ) -> eyre::Result<()> {
+        let mut registered = self.methods.lock().expect("ExEx RPC modules lock poisoned");
+        let Some(registered) = registered.as_mut() else {
+            eyre::bail!("RPC server is already started, ExEx RPC modules must be registered during initialization")
+        };
+
+        registered.merge(gate(methods.into(), events.downgrade())?)?;
+        Ok(())
+    }
+
+    /// Takes all methods registered so far. `ExEx`'s can't register methods afterwards.
+    pub fn take(&self) -> Methods {
+        self.methods.lock().expect("ExEx RPC modules lock poisoned").take().unwrap_or_default()
+    }
+}
+
+/// Wraps the methods, so that they return a "method not found" error once all strong senders of
+/// the `ExEx` events channel are dropped.
+fn gate(
+    methods: Methods,
+    events: WeakUnboundedSender<ExExEvent>,
+) -> Result<Methods, RegisterMethodError> {
+    let mut gated = Methods::new();
+
+    for name in methods.method_names() {
+        let events = events.clone();
+        let is_alive = move || events.strong_count() > 0;
+
+        let callback = match methods.method(name).expect("method exists").clone() {
+            MethodCallback::Sync(method) => MethodCallback::Sync(Arc::new(
+                move |id: Id<'_>,
+                      params: Params<'_>,
+                      max_response_size: MaxResponseSize,
+                      extensions: Extensions| {
+                    if !is_alive() {
+                        return exex_shut_down(id)
+                    }
+                    method(id, params, max_response_size, extensions)
+                },
+            )),
+            MethodCallback::Async(method) => MethodCallback::Async(Arc::new(
+                move |id, params, connection_id, max_response_size, extensions| {
+                    if !is_alive() {
+                        return futures::future::ready(exex_shut_down(id)).boxed()
+                    }
+                    method(id, params, connection_id, max_response_size, extensions)
+                },
+            )),
+            MethodCallback::Subscription(method) => MethodCallback::Subscription(Arc::new(
+                move |id: Id<'_>,
+                      params: Params<'_>,
+                      sink: MethodSink,
+                      state: SubscriptionState<'_>,
+                      extensions: Extensions| {
+                    if !is_alive() {
+                        return futures::future::ready(exex_shut_down(id)).boxed()
+                    }
+                    method(id, params, sink, state, extensions)
+                },
+            )),
+            unsubscription @ MethodCallback::Unsubscription(_) => unsubscription,
+        };
+
+        gated.verify_and_insert(name, callback)?;
+    }
+
+    Ok(gated)
+}
+
+/// Returns the response to a call of a method of an `ExEx` that shut down.
+fn exex_shut_down(id: Id<'_>) -> MethodResponse {
+    MethodResponse::error(
+        id,
+        ErrorObject::owned(METHOD_NOT_FOUND_CODE, "ExEx serving the method shut down", None::<()>),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::{core::params::ArrayParams, RpcModule};
+
+    #[tokio::test]
+    async fn gate_methods_on_exex_shut_down() {
+        let modules = ExExRpcModules::default();
+        let (events, _events_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut module = RpcModule::new(());
+        module.register_method("exex_ping", |_, _, _| "pong").unwrap();
+        module.register_async_method("exex_asyncPing", |_, _, _| async { "pong" }).unwrap();
+        modules.register(module, &events).unwrap();
+
+        let methods = modules.take();
+        assert_eq!(
+            methods.call::<_, String>("exex_ping", ArrayParams::new()).await.unwrap(),
+            "pong"
+        );
+        assert_eq!(
+            methods.call::<_, String>("exex_asyncPing", ArrayParams::new()).await.unwrap(),
+            "pong"
+        );
+
+        // Methods can't be registered after the node took them
+        let mut module = RpcModule::new(());
+        module.register_method("exex_late", |_, _, _| "pong").unwrap();
+        assert!(modules.register(module, &events).is_err());
+
+        // The methods are gone once the ExEx drops its events sender
+        drop(events);
+        assert!(methods.call::<_, String>("exex_ping", ArrayParams::new()).await.is_err());
+        assert!(methods.call::<_, String>("exex_asyncPing", ArrayParams::new()).await.is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Write-ahead log of [`ExExNotification`]s.
+
+use crate::ExExNotification;
+use reth_fs_util::FsPathError;
+use reth_primitives::BlockNumber;
+use reth_tracing::tracing::debug;
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// File extension of the WAL files.
+const WAL_FILE_EXTENSION: &str = "wal";
+
+/// Write-ahead log (WAL) of the [`ExExNotification`]s sent to `ExEx`s.
+///
+/// Every notification is written to its own file `<ID>.wal` in the WAL directory before it's sent
+/// to the `ExEx`s, and removed once all `ExEx`s have finished processing it. On restart, the
+/// notifications left in the WAL are replayed, so that notifications that were sent but not
+/// processed before a crash or shutdown (including reverts) don't get lost.
+///
+/// The trie updates of the chains are not persisted, so replayed notifications don't contain them.
+#[derive(Debug)]
+pub struct Wal {
+    /// Directory of the WAL files.
+    directory: PathBuf,
+    /// Notifications in the WAL, in the order they were committed.
+    entries: VecDeque<WalEntry>,
+    /// ID of the next committed notification.
+    next_id: u64,
+}
+
+/// A notification in the [`Wal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WalEntry {
+    /// ID of the notification, which is also the name of its file.
+    id: u64,
+    /// Tip of the committed chain of the notification, if any.
+    committed_tip: Option<BlockNumber>,
+}
+
+impl Wal {
+    /// Opens the WAL in the given directory, creating the directory if it doesn't exist.
+    pub fn new(directory: impl AsRef<Path>) -> eyre::Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        reth_fs_util::create_dir_all(&directory)?;
+
+        let mut ids = Vec::new();
+        for entry in reth_fs_util::read_dir(&directory)? {
+            let path = entry.map_err(|err| FsPathError::read_dir(err, &directory))?.path();
+            if path.extension().is_some_and(|extension| extension == WAL_FILE_EXTENSION) {
+                if let Some(id) =
+                    path.file_stem().and_then(|stem| stem.to_str()).and_then(|s| s.parse().ok())
+                {
+                    ids.push(id);
+                }
+            } else if path.extension().is_some_and(|extension| extension == "tmp") {
+                // Leftover of a commit that was interrupted before the file was complete
+                reth_fs_util::remove_file(&path)?;
+            }
+        }
+        ids.sort_unstable();
+
+        let mut entries = VecDeque::with_capacity(ids.len());
+        for id in ids {
+            let notification = read_notification(&directory, id)?;
+            entries.push_back(WalEntry {
+                id,
+                committed_tip: notification.committed_chain().map(|chain| chain.tip().number),
+            });
+        }
+        let next_id = entries.back().map_or(0, |entry| entry.id + 1);
+
+        debug!(target: "exex::wal", ?directory, notifications = entries.len(), "Opened WAL");
+
+        Ok(Self { directory, entries, next_id })
+    }
+
+    /// Returns the number of notifications in the WAL.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no notifications in the WAL.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the notification to the WAL and returns its ID.
+    ///
+    /// The notification is durable once this returns.
+    pub fn commit(&mut self, notification: &ExExNotification) -> eyre::Result<u64> {
+        let id = self.next_id;
+        let path = file_path(&self.directory, id);
+        let tmp_path = path.with_extension("tmp");
+
+        // Write to a temporary file first, so that a crash can't leave an incomplete notification
+        // in the WAL.
+        let data = serde_json::to_vec(&notification.without_trie_updates())?;
+        let mut file =
+            File::create(&tmp_path).map_err(|err| FsPathError::create_file(err, &tmp_path))?;
+        file.write_all(&data)
+            .and_then(|()| file.sync_all())
+            .map_err(|err| FsPathError::write(err, &tmp_path))?;
+        reth_fs_util::rename(&tmp_path, &path)?;
+
+        self.entries.push_back(WalEntry {
+            id,
+            committed_tip: notification.committed_chain().map(|chain| chain.tip().number),
+        });
+        self.next_id += 1;
+
+        Ok(id)
+    }
+
+    /// Reads the notification with the given ID from the WAL.
+    pub fn read(&self, id: u64) -> eyre::Result<ExExNotification> {
+        read_notification(&self.directory, id)
+    }
+
+    /// Removes the notifications that were processed by all `ExEx`s, given the lowest height
+    /// finished by all of them.
+    ///
+    /// Notifications are removed in the order they were committed, up to and including the last
+    /// notification whose committed chain tip is at most the finished height. Reverts are only
+    /// removed together with a later notification, as they don't move the finished height
+    /// forward.
+    pub fn finalize(&mut self, finished_height: BlockNumber) -> eyre::Result<()> {
+        let mut finalized = 0;
+        for (index, entry) in self.entries.iter().enumerate() {
+            match entry.committed_tip {
+                Some(tip) if tip <= finished_height => finalized = index + 1,
+                Some(_) => break,
+                None => {}
+            }
+        }
+
+        for _ in 0..finalized {
+            let entry = self.entries.front().expect("finalized entry exists");
+            reth_fs_util::remove_file(file_path(&self.directory, entry.id))?;
+            self.entries.pop_front();
+        }
+
+        if finalized > 0 {
+            debug!(target: "exex::wal", %finished_height, finalized, remaining = self.entries.len(), "Finalized WAL");
+        }
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the notifications in the WAL, in the order they were committed.
+    pub fn iter_notifications(&self) -> impl Iterator<Item = eyre::Result<ExExNotification>> + '_ {
+        self.entries.iter().map(|entry| read_notification(&self.directory, entry.id))
+    }
+}
+
+/// Returns the path of the WAL file with the given ID.
+fn file_path(directory: &Path, id: u64) -> PathBuf {
+    directory.join(format!("{id}.{WAL_FILE_EXTENSION}"))
+}
+
+/// Reads the notification with the given ID from the WAL directory.
+fn read_notification(directory: &Path, id: u64) -> eyre::Result<ExExNotification> {
+    let data = reth_fs_util::read(file_path(directory, id))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, SealedBlockWithSenders};
+    use reth_provider::{Chain, ExecutionOutcome};
+    use std::sync::Arc;
+
+    fn chain(number: BlockNumber) -> Arc<Chain> {
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header = Header { number, ..Default::default() }.seal_slow();
+        Arc::new(Chain::new(vec![block], ExecutionOutcome::default(), None))
+    }
+
+    #[test]
+    fn wal_commit_finalize_replay() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let notifications = vec![
+            ExExNotification::ChainCommitted { new: chain(1) },
+            ExExNotification::ChainCommitted { new: chain(2) },
+            ExExNotification::ChainReverted { old: chain(2) },
+            ExExNotification::ChainCommitted { new: chain(2) },
+        ];
+
+        let mut wal = Wal::new(dir.path()).unwrap();
+        for notification in &notifications {
+            wal.commit(notification).unwrap();
+        }
+        assert_eq!(wal.len(), 4);
+
+        wal.finalize(1).unwrap();
+        assert_eq!(wal.len(), 3);
+
+        // Notifications are replayed after reopening the WAL
+        let mut wal = Wal::new(dir.path()).unwrap();
+        assert_eq!(
+            wal.iter_notifications().collect::<eyre::Result<Vec<_>>>().unwrap(),
+            notifications[1..]
+        );
+
+        // The revert is removed together with the notification after it
+        wal.finalize(2).unwrap();
+        assert!(wal.is_empty());
+        assert!(Wal::new(dir.path()).unwrap().is_empty());
+    }
+}
@@ -16,7 +16,7 @@ use reth_db::{test_utils::TempDatabase, DatabaseEnv};
 use reth_db_common::init::init_genesis;
 use reth_evm::test_utils::MockExecutorProvider;
 use reth_execution_types::Chain;
-use reth_exex::{ExExContext, ExExEvent, ExExNotification};
+use reth_exex::{ExExCheckpointStore, ExExContext, ExExEvent, ExExNotification, ExExRpcModules};
 use reth_network::{config::SecretKey, NetworkConfigBuilder, NetworkManager};
 use reth_node_api::{FullNodeTypes, FullNodeTypesAdapter, NodeTypes};
 use reth_node_builder::{
@@ -277,6 +277,11 @@ pub async fn test_exex_context_with_chain_spec(
     let (events_tx, events_rx) = tokio::sync::mpsc::unbounded_channel();
     let (notifications_tx, notifications_rx) = tokio::sync::mpsc::channel(1);
 
+    // store the checkpoints next to the temporary database, so they're removed together with it
+    let checkpoints =
+        ExExCheckpointStore::new(provider_factory.db_ref().path().join("exex-checkpoints"))?
+            .checkpoints("test")?;
+
     let ctx = ExExContext {
         head,
         config: NodeConfig::test(),
@@ -284,6 +289,8 @@ pub async fn test_exex_context_with_chain_spec(
         events: events_tx,
         notifications: notifications_rx,
         components,
+        rpc_modules: ExExRpcModules::default(),
+        checkpoints,
     };
 
     Ok((ctx, TestExExHandle { genesis, provider_factory, events_rx, notifications_tx, tasks }))
@@ -331,3 +338,45 @@ impl<F: Future<Output = eyre::Result<()>> + Unpin + Send> PollOnce for F {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_execution_types::ExecutionOutcome;
+    use reth_primitives::{Account, Address, Header, U256};
+    use reth_provider::{AccountReader, BlockHashReader};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn chain_pre_and_post_state() -> eyre::Result<()> {
+        let (ctx, handle) = test_exex_context().await?;
+
+        // A chain on top of genesis that isn't written to the database
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header =
+            Header { number: 1, parent_hash: handle.genesis.hash(), ..Default::default() }
+                .seal_slow();
+        let address = Address::with_last_byte(0x42);
+        let account = Account { balance: U256::from(10), ..Default::default() };
+        let execution_outcome = ExecutionOutcome::new_init(
+            HashMap::from([(address, (None, Some(account), HashMap::new()))]),
+            HashMap::new(),
+            Vec::new(),
+            Default::default(),
+            1,
+            Vec::new(),
+        );
+        let chain = Arc::new(Chain::new(vec![block.clone()], execution_outcome, None));
+
+        let pre_state = ctx.chain_pre_state(&chain)?;
+        assert_eq!(pre_state.basic_account(address)?, None);
+        assert_eq!(pre_state.block_hash(1)?, None);
+
+        let post_state = ctx.chain_post_state(chain)?;
+        assert_eq!(post_state.basic_account(address)?, Some(account));
+        assert_eq!(post_state.block_hash(1)?, Some(block.hash()));
+        assert_eq!(post_state.block_hash(0)?, Some(handle.genesis.hash()));
+
+        Ok(())
+    }
+}
@@ -10,8 +10,8 @@ use reth_network_p2p::{
 };
 use reth_network_peers::PeerId;
 use reth_primitives::{
-    BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Header, HeadersDirection, SealedHeader,
-    B256,
+    Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Header, HeadersDirection,
+    SealedHeader, B256,
 };
 use std::{collections::HashMap, io, path::Path};
 use thiserror::Error;
@@ -261,6 +261,32 @@ impl FromReader for FileClient {
     }
 }
 
+impl FromIterator<Block> for FileClient {
+    /// Initialize the [`FileClient`] from blocks that have been decoded from other file formats.
+    fn from_iter<I: IntoIterator<Item = Block>>(blocks: I) -> Self {
+        let mut headers = HashMap::new();
+        let mut hash_to_number = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        for block in blocks {
+            let block_hash = block.header.hash_slow();
+            hash_to_number.insert(block_hash, block.header.number);
+            bodies.insert(
+                block_hash,
+                BlockBody {
+                    transactions: block.body,
+                    ommers: block.ommers,
+                    withdrawals: block.withdrawals,
+                    requests: block.requests,
+                },
+            );
+            headers.insert(block.header.number, block.header);
+        }
+
+        Self { headers, hash_to_number, bodies }
+    }
+}
+
 impl HeadersClient for FileClient {
     type Output = HeadersFut;
 
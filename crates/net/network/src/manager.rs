@@ -574,6 +574,9 @@ where
                 // connection.
                 self.swarm.on_network_state_change(net_state);
             }
+            NetworkHandleMessage::SetConnectionsConfig(config) => {
+                self.swarm.state_mut().peers_mut().set_connections_config(config);
+            }
 
             NetworkHandleMessage::Shutdown(tx) => {
                 // Set connection status to `Shutdown`. Stops node to accept
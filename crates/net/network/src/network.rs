@@ -13,6 +13,7 @@ use reth_network_api::{
 };
 use reth_network_p2p::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_network_peers::{NodeRecord, PeerId};
+use reth_network_types::ConnectionsConfig;
 use reth_primitives::{Head, TransactionSigned, B256};
 use reth_tokio_util::{EventSender, EventStream};
 use secp256k1::SecretKey;
@@ -179,6 +180,13 @@ impl NetworkHandle {
         self.send_message(NetworkHandleMessage::SetNetworkState(network_conn));
     }
 
+    /// Sets the restrictions on the number of connections.
+    ///
+    /// Existing connections are kept, the new limits apply to new connections.
+    pub fn set_connections_config(&self, config: ConnectionsConfig) {
+        self.send_message(NetworkHandleMessage::SetConnectionsConfig(config));
+    }
+
     /// Whether tx gossip is disabled
     pub fn tx_gossip_disabled(&self) -> bool {
         self.inner.tx_gossip_disabled
@@ -475,6 +483,8 @@ pub(crate) enum NetworkHandleMessage {
     Shutdown(oneshot::Sender<()>),
     /// Sets the network state between hibernation and active.
     SetNetworkState(NetworkConnectionState),
+    /// Sets the restrictions on the number of connections.
+    SetConnectionsConfig(ConnectionsConfig),
     /// Adds a new listener for `DiscoveryEvent`.
     DiscoveryListener(UnboundedSender<DiscoveryEvent>),
     /// Adds an additional `RlpxSubProtocol`.
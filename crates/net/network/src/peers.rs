@@ -838,6 +838,13 @@ impl PeersManager {
         self.net_connection_state = state;
     }
 
+    /// Sets the restrictions on the number of connections.
+    ///
+    /// Existing connections are kept, the new limits apply to new connections.
+    pub fn set_connections_config(&mut self, config: ConnectionsConfig) {
+        self.connection_info.config = config;
+    }
+
     /// Returns the current network connection state.
     pub const fn connection_state(&self) -> &NetworkConnectionState {
         &self.net_connection_state
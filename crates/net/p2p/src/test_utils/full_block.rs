@@ -91,6 +91,43 @@ impl HeadersClient for NoopFullBlockClient {
     }
 }
 
+/// A headers+bodies client whose requests never complete, like those of a node without peers.
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct PendingFullBlockClient;
+
+impl DownloadClient for PendingFullBlockClient {
+    fn report_bad_message(&self, _peer_id: PeerId) {}
+
+    fn num_connected_peers(&self) -> usize {
+        0
+    }
+}
+
+impl BodiesClient for PendingFullBlockClient {
+    type Output = futures::future::Pending<PeerRequestResult<Vec<BlockBody>>>;
+
+    fn get_block_bodies_with_priority(
+        &self,
+        _hashes: Vec<B256>,
+        _priority: Priority,
+    ) -> Self::Output {
+        futures::future::pending()
+    }
+}
+
+impl HeadersClient for PendingFullBlockClient {
+    type Output = futures::future::Pending<PeerRequestResult<Vec<Header>>>;
+
+    fn get_headers_with_priority(
+        &self,
+        _request: HeadersRequest,
+        _priority: Priority,
+    ) -> Self::Output {
+        futures::future::pending()
+    }
+}
+
 /// A headers+bodies client that stores the headers and bodies in memory, with an artificial soft
 /// bodies response limit that is set to 20 by default.
 ///
@@ -18,6 +18,15 @@ pub struct DebugArgs {
     #[arg(long = "debug.tip", help_heading = "Debug")]
     pub tip: Option<B256>,
 
+    /// Runs the sync pipeline continuously, following the latest known tip without requiring
+    /// forkchoice updates from a consensus client.
+    ///
+    /// This is intended for read-only deployments (e.g. indexers) that track the chain using a
+    /// trusted tip source such as `--debug.tip`, `--debug.etherscan` or
+    /// `--debug.rpc-consensus-ws`. All new targets are synced via the pipeline.
+    #[arg(long = "debug.continuous", help_heading = "Debug")]
+    pub continuous: bool,
+
     /// Runs the sync only up to the specified block.
     #[arg(long = "debug.max-block", help_heading = "Debug")]
     pub max_block: Option<u64>,
@@ -52,9 +61,25 @@ pub struct DebugArgs {
 
     /// The path to store engine API messages at.
     /// If specified, all of the intercepted engine API messages
-    /// will be written to specified location.
+    /// will be written to specified location, named by the time they were received at.
+    /// They can be replayed with `reth debug replay-engine`.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
+
+    /// Profiles the gas used by executed live blocks by opcode family and by contract.
+    ///
+    /// The gas used per opcode family is exported as metrics, and logged together with the
+    /// contracts using the most gas on the `debug` level of the `evm::profiling` target.
+    #[arg(long = "debug.gas-profile", help_heading = "Debug")]
+    pub gas_profile: bool,
+
+    /// The directory to dump blocks that fail validation to.
+    ///
+    /// Every invalid block is written to a subdirectory together with a report of the error, the
+    /// execution witness of the block and the differences of the receipts and trie updates of a
+    /// re-execution of the block.
+    #[arg(long = "debug.invalid-block-dir", help_heading = "Debug", value_name = "PATH")]
+    pub invalid_block_dir: Option<PathBuf>,
 }
 
 #[cfg(test)]
@@ -75,4 +100,24 @@ mod tests {
         let args = CommandParser::<DebugArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_parse_continuous() {
+        let args = CommandParser::<DebugArgs>::parse_from(["reth", "--debug.continuous"]).args;
+        assert!(args.continuous);
+    }
+
+    #[test]
+    fn test_parse_gas_profile() {
+        let args = CommandParser::<DebugArgs>::parse_from(["reth", "--debug.gas-profile"]).args;
+        assert!(args.gas_profile);
+    }
+
+    #[test]
+    fn test_parse_invalid_block_dir() {
+        let args =
+            CommandParser::<DebugArgs>::parse_from(["reth", "--debug.invalid-block-dir", "dumps"])
+                .args;
+        assert_eq!(args.invalid_block_dir, Some(PathBuf::from("dumps")));
+    }
 }
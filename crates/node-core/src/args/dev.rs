@@ -1,12 +1,14 @@
 //! clap [Args](clap::Args) for Dev testnet configuration
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use clap::Args;
 use humantime::parse_duration;
+use reth_chainspec::ChainSpec;
+use reth_primitives::{constants::ETH_TO_WEI, Address, U256};
 
 /// Parameters for Dev testnet configuration
-#[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
+#[derive(Debug, Args, PartialEq, Eq, Default, Clone)]
 #[command(next_help_heading = "Dev testnet")]
 pub struct DevArgs {
     /// Start the node in dev mode
@@ -16,6 +18,8 @@ pub struct DevArgs {
     /// Disables network discovery and enables local http server.
     /// Prefunds 20 accounts derived by mnemonic "test test test test test test test test test test
     /// test junk" with 10 000 ETH each.
+    /// Blocks are final as soon as they are mined, and can be mined on demand with the `evm_mine`
+    /// and `evm_setNextBlockTimestamp` RPC methods.
     #[arg(long = "dev", alias = "auto-mine", help_heading = "Dev testnet", verbatim_doc_comment)]
     pub dev: bool,
 
@@ -39,6 +43,59 @@ pub struct DevArgs {
         verbatim_doc_comment
     )]
     pub block_time: Option<Duration>,
+
+    /// The balance in ETH of each prefunded account of the dev genesis.
+    #[arg(long = "dev.balance", help_heading = "Dev testnet", value_name = "ETH")]
+    pub balance: Option<u64>,
+
+    /// Additional genesis account with its balance in ETH, can be specified multiple times.
+    ///
+    /// Overrides the balance of a prefunded account of the dev genesis.
+    /// --dev.alloc 0x0000000000000000000000000000000000000001=100
+    #[arg(
+        long = "dev.alloc",
+        help_heading = "Dev testnet",
+        value_name = "ADDRESS=ETH",
+        value_parser = parse_genesis_account,
+        verbatim_doc_comment
+    )]
+    pub alloc: Vec<(Address, u64)>,
+}
+
+impl DevArgs {
+    /// Applies the configured genesis allocation to the chain spec.
+    ///
+    /// Returns the chain spec unchanged if the allocation is not configured, otherwise the genesis
+    /// hash is recomputed from the new genesis.
+    pub fn apply_genesis_alloc(&self, chain: Arc<ChainSpec>) -> Arc<ChainSpec> {
+        if self.balance.is_none() && self.alloc.is_empty() {
+            return chain
+        }
+
+        let mut chain = Arc::unwrap_or_clone(chain);
+        if let Some(balance) = self.balance {
+            for account in chain.genesis.alloc.values_mut() {
+                account.balance = eth_to_wei(balance);
+            }
+        }
+        for (address, balance) in &self.alloc {
+            chain.genesis.alloc.entry(*address).or_default().balance = eth_to_wei(*balance);
+        }
+        chain.genesis_hash = None;
+        Arc::new(chain)
+    }
+}
+
+/// Converts an amount of ETH to wei.
+fn eth_to_wei(eth: u64) -> U256 {
+    U256::from(eth) * U256::from(ETH_TO_WEI)
+}
+
+/// Parses a genesis account of the form `ADDRESS=ETH`.
+fn parse_genesis_account(value: &str) -> eyre::Result<(Address, u64)> {
+    let (address, balance) =
+        value.split_once('=').ok_or_else(|| eyre::eyre!("expected ADDRESS=ETH, got {value}"))?;
+    Ok((address.trim().parse()?, balance.trim().parse()?))
 }
 
 #[cfg(test)]
@@ -56,13 +113,13 @@ mod tests {
     #[test]
     fn test_parse_dev_args() {
         let args = CommandParser::<DevArgs>::parse_from(["reth"]).args;
-        assert_eq!(args, DevArgs { dev: false, block_max_transactions: None, block_time: None });
+        assert_eq!(args, DevArgs { dev: false, ..Default::default() });
 
         let args = CommandParser::<DevArgs>::parse_from(["reth", "--dev"]).args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: None, block_time: None });
+        assert_eq!(args, DevArgs { dev: true, ..Default::default() });
 
         let args = CommandParser::<DevArgs>::parse_from(["reth", "--auto-mine"]).args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: None, block_time: None });
+        assert_eq!(args, DevArgs { dev: true, ..Default::default() });
 
         let args = CommandParser::<DevArgs>::parse_from([
             "reth",
@@ -71,7 +128,10 @@ mod tests {
             "2",
         ])
         .args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: Some(2), block_time: None });
+        assert_eq!(
+            args,
+            DevArgs { dev: true, block_max_transactions: Some(2), ..Default::default() }
+        );
 
         let args =
             CommandParser::<DevArgs>::parse_from(["reth", "--dev", "--dev.block-time", "1s"]).args;
@@ -79,12 +139,55 @@ mod tests {
             args,
             DevArgs {
                 dev: true,
-                block_max_transactions: None,
-                block_time: Some(std::time::Duration::from_secs(1))
+                block_time: Some(std::time::Duration::from_secs(1)),
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn test_parse_dev_args_genesis_alloc() {
+        let address = Address::with_last_byte(1);
+        let alloc = format!("{address}=5");
+        let args = CommandParser::<DevArgs>::parse_from([
+            "reth",
+            "--dev",
+            "--dev.balance",
+            "100",
+            "--dev.alloc",
+            alloc.as_str(),
+        ])
+        .args;
+        assert_eq!(args.balance, Some(100));
+        assert_eq!(args.alloc, vec![(address, 5)]);
+
+        let args =
+            CommandParser::<DevArgs>::try_parse_from(["reth", "--dev", "--dev.alloc", "0x01"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_apply_genesis_alloc() {
+        let dev = reth_chainspec::DEV.clone();
+        assert_eq!(
+            DevArgs::default().apply_genesis_alloc(dev.clone()).genesis_hash(),
+            dev.genesis_hash()
+        );
+
+        let address = Address::with_last_byte(1);
+        let args = DevArgs { balance: Some(1), alloc: vec![(address, 2)], ..Default::default() };
+        let chain = args.apply_genesis_alloc(dev.clone());
+        assert_eq!(chain.genesis.alloc.len(), dev.genesis.alloc.len() + 1);
+        assert_eq!(chain.genesis.alloc[&address].balance, eth_to_wei(2));
+        assert!(chain
+            .genesis
+            .alloc
+            .iter()
+            .filter(|(account, _)| **account != address)
+            .all(|(_, account)| account.balance == eth_to_wei(1)));
+        assert_ne!(chain.genesis_hash(), dev.genesis_hash());
+    }
+
     #[test]
     fn test_parse_dev_args_conflicts() {
         let args = CommandParser::<DevArgs>::try_parse_from([
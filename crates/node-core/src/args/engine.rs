@@ -0,0 +1,39 @@
+//! clap [Args](clap::Args) for engine configuration
+
+use clap::Args;
+
+/// Parameters for configuring the engine
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq, Default)]
+#[command(next_help_heading = "Engine")]
+pub struct EngineArgs {
+    /// Maximum memory in bytes the state of executed sidechain blocks can use.
+    ///
+    /// When exceeded, the state of the oldest sidechains is spilled to disk, and read back when
+    /// they are extended or become canonical. The memory is unbounded by default.
+    #[arg(long = "engine.memory-budget", value_name = "BYTES")]
+    pub memory_budget: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_engine_args() {
+        let args = CommandParser::<EngineArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, EngineArgs::default());
+
+        let args =
+            CommandParser::<EngineArgs>::parse_from(["reth", "--engine.memory-budget", "1000"])
+                .args;
+        assert_eq!(args.memory_budget, Some(1000));
+    }
+}
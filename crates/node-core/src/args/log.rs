@@ -4,7 +4,7 @@ use crate::dirs::{LogsDir, PlatformPath};
 use clap::{ArgAction, Args, ValueEnum};
 use reth_tracing::{
     tracing_subscriber::filter::Directive, FileInfo, FileWorkerGuard, LayerInfo, LogFormat,
-    RethTracer, Tracer,
+    OtlpInfo, RethTracer, Tracer,
 };
 use std::{fmt, fmt::Display};
 use tracing::{level_filters::LevelFilter, Level};
@@ -57,6 +57,26 @@ pub struct LogArgs {
     )]
     pub journald_filter: String,
 
+    /// Export spans of engine messages, stage runs and RPC requests to the OTLP/HTTP receiver of
+    /// an OpenTelemetry collector at the given endpoint, e.g. `http://localhost:4318`.
+    ///
+    /// Requires reth to be built with the `otlp` feature.
+    #[arg(long = "log.otlp", value_name = "URL", global = true)]
+    pub otlp: Option<String>,
+
+    /// The filter to use for spans exported to the OpenTelemetry collector.
+    #[arg(long = "log.otlp.filter", value_name = "FILTER", global = true, default_value = "debug")]
+    pub otlp_filter: String,
+
+    /// The service name the spans are exported with.
+    #[arg(
+        long = "log.otlp.service-name",
+        value_name = "NAME",
+        global = true,
+        default_value = "reth"
+    )]
+    pub otlp_service_name: String,
+
     /// Sets whether or not the formatter emits ANSI terminal escape codes for colors and other
     /// text formatting.
     #[arg(
@@ -110,6 +130,14 @@ impl LogArgs {
             tracer = tracer.with_file(file, info);
         }
 
+        if let Some(endpoint) = &self.otlp {
+            tracer = tracer.with_otlp(OtlpInfo::new(
+                endpoint.clone(),
+                self.otlp_service_name.clone(),
+                self.otlp_filter.clone(),
+            ));
+        }
+
         let guard = tracer.init()?;
         Ok(guard)
     }
@@ -51,6 +51,10 @@ pub use dev::DevArgs;
 mod pruning;
 pub use pruning::PruningArgs;
 
+/// EngineArgs for configuring the engine
+mod engine;
+pub use engine::EngineArgs;
+
 /// DatadirArgs for configuring data storage paths
 mod datadir_args;
 pub use datadir_args::DatadirArgs;
@@ -1,6 +1,6 @@
 //! clap [Args](clap::Args) for network related arguments.
 
-use crate::version::P2P_CLIENT_VERSION;
+use crate::{bootnodes::DEFAULT_BOOTNODES_MANIFEST_INTERVAL, version::P2P_CLIENT_VERSION};
 use clap::Args;
 use reth_chainspec::ChainSpec;
 use reth_config::Config;
@@ -19,12 +19,14 @@ use reth_network::{
     HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
 };
 use reth_network_peers::{mainnet_nodes, TrustedPeer};
+use reth_primitives::Address;
 use secp256k1::SecretKey;
 use std::{
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
     ops::Not,
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
 /// Parameters for configuring the network more granularity via CLI
@@ -52,6 +54,23 @@ pub struct NetworkArgs {
     #[arg(long, value_delimiter = ',')]
     pub bootnodes: Option<Vec<TrustedPeer>>,
 
+    /// URL of a signed bootnodes manifest, which is fetched periodically to update the bootnodes
+    /// of the chain.
+    #[arg(long = "bootnodes.manifest", value_name = "URL", requires = "bootnodes_manifest_signer")]
+    pub bootnodes_manifest: Option<String>,
+
+    /// The address of the trusted signer of the bootnodes manifest.
+    #[arg(
+        long = "bootnodes.manifest-signer",
+        value_name = "ADDRESS",
+        requires = "bootnodes_manifest"
+    )]
+    pub bootnodes_manifest_signer: Option<Address>,
+
+    /// The interval between two fetches of the bootnodes manifest.
+    #[arg(long = "bootnodes.manifest-interval", value_name = "DURATION", value_parser = humantime::parse_duration, default_value = "1h")]
+    pub bootnodes_manifest_interval: Duration,
+
     /// Amount of DNS resolution requests retries to perform when peering.
     #[arg(long, default_value_t = 0)]
     pub dns_retries: usize,
@@ -225,6 +244,9 @@ impl Default for NetworkArgs {
             trusted_peers: vec![],
             trusted_only: false,
             bootnodes: None,
+            bootnodes_manifest: None,
+            bootnodes_manifest_signer: None,
+            bootnodes_manifest_interval: DEFAULT_BOOTNODES_MANIFEST_INTERVAL,
             dns_retries: 0,
             peers_file: None,
             identity: P2P_CLIENT_VERSION.to_string(),
@@ -472,6 +494,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_bootnodes_manifest_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--bootnodes.manifest",
+            "https://example.com/bootnodes.json",
+            "--bootnodes.manifest-signer",
+            "0x00000000219ab540356cBB839Cbe05303d7705Fa",
+            "--bootnodes.manifest-interval",
+            "10m",
+        ])
+        .args;
+        assert_eq!(args.bootnodes_manifest.as_deref(), Some("https://example.com/bootnodes.json"));
+        assert!(args.bootnodes_manifest_signer.is_some());
+        assert_eq!(args.bootnodes_manifest_interval, Duration::from_secs(600));
+
+        // the manifest is only trusted with a signer
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--bootnodes.manifest",
+            "https://example.com/bootnodes.json",
+        ])
+        .is_err());
+    }
+
     #[test]
     fn parse_retry_strategy_args() {
         let tests = vec![0, 10];
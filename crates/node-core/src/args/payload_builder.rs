@@ -6,8 +6,10 @@ use clap::{
     builder::{RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
-use reth_primitives::constants::{
-    ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION,
+use humantime::parse_duration;
+use reth_primitives::{
+    constants::{ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION},
+    Address,
 };
 use std::{borrow::Cow, ffi::OsStr, time::Duration};
 
@@ -34,6 +36,59 @@ pub struct PayloadBuilderArgs {
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
+
+    /// Maximum number of payloads to build per payload job, after which the best payload is kept
+    /// until it's requested. By default, a new payload is built every interval until the
+    /// deadline.
+    #[arg(long = "builder.max-iterations", value_parser = RangedU64ValueParser::<usize>::new().range(1..), conflicts_with = "build_once")]
+    pub max_iterations: Option<usize>,
+
+    /// Build a single best-effort payload per payload job, e.g. on low-power machines.
+    ///
+    /// This is the same as `--builder.max-iterations 1`.
+    #[arg(long = "builder.build-once")]
+    pub build_once: bool,
+
+    /// Maximum total time a payload job spends building payloads, e.g. `2s`, after which the best
+    /// payload is kept until it's requested. Only the time spent building counts, not the time
+    /// waiting for a building task.
+    #[arg(long = "builder.max-build-time", value_parser = parse_duration, value_name = "DURATION")]
+    pub max_build_time: Option<Duration>,
+
+    /// Maximum gas used by the transactions of a payload, if lower than the block gas limit.
+    #[arg(long = "builder.iteration-gas", value_name = "GAS")]
+    pub iteration_gas_budget: Option<u64>,
+
+    /// Maximum time spent adding transactions to a payload per building attempt, e.g. `500ms`.
+    #[arg(long = "builder.iteration-time", value_parser = parse_duration, value_name = "DURATION")]
+    pub iteration_time_budget: Option<Duration>,
+
+    /// Rebuild the payload right away when a new pending transaction with at least this priority
+    /// fee per gas (in wei) arrives, by executing only the new tail of the block on top of the
+    /// transactions of the best payload.
+    #[arg(long = "builder.incremental-rebuild-tip", value_name = "WEI")]
+    pub incremental_rebuild_min_tip: Option<u128>,
+
+    /// Build an empty payload right away, without executing any transactions, while the pool has
+    /// no transaction paying at least this effective priority fee per gas (in wei).
+    #[arg(long = "builder.empty-block-min-tip", value_name = "WEI")]
+    pub empty_payload_min_tip: Option<u128>,
+
+    /// URLs of external builders to request payloads from with `reth_builderGetPayload`.
+    ///
+    /// The most valuable external payload is validated against local execution, and used instead
+    /// of the locally built payload if it pays more fees.
+    #[arg(long = "builder.external", value_name = "URL", value_delimiter = ',')]
+    pub external_builders: Vec<String>,
+
+    /// How long to wait for the payloads of external builders, e.g. `2s`.
+    #[arg(long = "builder.external-timeout", value_parser = parse_duration, default_value = "2s", value_name = "DURATION")]
+    pub external_builder_timeout: Duration,
+
+    /// Only request payloads from external builders for payload jobs with one of the given
+    /// suggested fee recipients. By default, payloads are requested for all payload jobs.
+    #[arg(long = "builder.external-fee-recipient", value_name = "ADDRESS", value_delimiter = ',')]
+    pub external_fee_recipients: Vec<Address>,
 }
 
 impl Default for PayloadBuilderArgs {
@@ -44,6 +99,16 @@ impl Default for PayloadBuilderArgs {
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            max_iterations: None,
+            build_once: false,
+            max_build_time: None,
+            iteration_gas_budget: None,
+            iteration_time_budget: None,
+            incremental_rebuild_min_tip: None,
+            empty_payload_min_tip: None,
+            external_builders: Vec::new(),
+            external_builder_timeout: Duration::from_secs(2),
+            external_fee_recipients: Vec::new(),
         }
     }
 }
@@ -68,6 +133,46 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
     fn max_payload_tasks(&self) -> usize {
         self.max_payload_tasks
     }
+
+    fn max_iterations(&self) -> Option<usize> {
+        if self.build_once {
+            Some(1)
+        } else {
+            self.max_iterations
+        }
+    }
+
+    fn max_build_time(&self) -> Option<Duration> {
+        self.max_build_time
+    }
+
+    fn iteration_gas_budget(&self) -> Option<u64> {
+        self.iteration_gas_budget
+    }
+
+    fn iteration_time_budget(&self) -> Option<Duration> {
+        self.iteration_time_budget
+    }
+
+    fn incremental_rebuild_min_tip(&self) -> Option<u128> {
+        self.incremental_rebuild_min_tip
+    }
+
+    fn empty_payload_min_tip(&self) -> Option<u128> {
+        self.empty_payload_min_tip
+    }
+
+    fn external_builders(&self) -> &[String] {
+        &self.external_builders
+    }
+
+    fn external_builder_timeout(&self) -> Duration {
+        self.external_builder_timeout
+    }
+
+    fn external_fee_recipients(&self) -> &[Address] {
+        &self.external_fee_recipients
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -127,6 +232,75 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn test_args_with_build_once() {
+        let args =
+            CommandParser::<PayloadBuilderArgs>::parse_from(["reth", "--builder.build-once"]).args;
+        assert_eq!(args.max_iterations(), Some(1));
+
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.build-once",
+            "--builder.max-iterations",
+            "2"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_args_with_iteration_budget() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.iteration-gas",
+            "15000000",
+            "--builder.iteration-time",
+            "500ms",
+        ])
+        .args;
+        assert_eq!(args.iteration_gas_budget(), Some(15_000_000));
+        assert_eq!(args.iteration_time_budget(), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_args_with_max_build_time() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.max-build-time",
+            "2s",
+        ])
+        .args;
+        assert_eq!(args.max_build_time(), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_args_with_empty_payload_min_tip() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.empty-block-min-tip",
+            "1000000000",
+        ])
+        .args;
+        assert_eq!(args.empty_payload_min_tip(), Some(1_000_000_000));
+    }
+
+    #[test]
+    fn test_args_with_external_builders() {
+        let fee_recipient = Address::with_last_byte(1);
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.external",
+            "http://relay-a:8545,http://relay-b:8545",
+            "--builder.external-timeout",
+            "1500ms",
+            "--builder.external-fee-recipient",
+            &fee_recipient.to_string(),
+        ])
+        .args;
+        assert_eq!(args.external_builders(), ["http://relay-a:8545", "http://relay-b:8545"]);
+        assert_eq!(args.external_builder_timeout(), Duration::from_millis(1500));
+        assert_eq!(args.external_fee_recipients(), [fee_recipient]);
+    }
+
     #[test]
     fn test_default_extradata() {
         let extradata = default_extradata();
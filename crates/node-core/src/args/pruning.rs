@@ -3,7 +3,10 @@
 use clap::Args;
 use reth_chainspec::ChainSpec;
 use reth_config::config::PruneConfig;
-use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig, MINIMUM_PRUNING_DISTANCE};
+use reth_prune_types::{
+    PruneMode, PruneModes, PruneSchedule, ReceiptsLogPruneConfig, ReceiptsLogPruneRule,
+    MINIMUM_PRUNING_DISTANCE,
+};
 
 /// Parameters for pruning and full node
 #[derive(Debug, Clone, Args, PartialEq, Eq, Default)]
@@ -36,11 +39,19 @@ impl PruningArgs {
                     chain_spec
                         .deposit_contract
                         .as_ref()
-                        .map(|contract| (contract.address, PruneMode::Before(contract.block)))
+                        .map(|contract| {
+                            ReceiptsLogPruneRule::address(
+                                contract.address,
+                                PruneMode::Before(contract.block),
+                            )
+                        })
                         .into_iter()
                         .collect(),
                 ),
+                retained_addresses: Default::default(),
             },
+            schedule: PruneSchedule::default(),
+            history_expiry: None,
         })
     }
 }
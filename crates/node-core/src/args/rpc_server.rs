@@ -4,6 +4,7 @@ use std::{
     ffi::OsStr,
     net::{IpAddr, Ipv4Addr},
     path::PathBuf,
+    time::Duration,
 };
 
 use alloy_rpc_types_engine::JwtSecret;
@@ -11,7 +12,9 @@ use clap::{
     builder::{PossibleValue, RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
+use humantime::parse_duration;
 use rand::Rng;
+use reth_primitives::Address;
 use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
 
 use crate::args::{
@@ -33,6 +36,9 @@ pub(crate) const RPC_DEFAULT_MAX_RESPONSE_SIZE_MB: u32 = 160;
 /// Default number of incoming connections.
 pub(crate) const RPC_DEFAULT_MAX_CONNECTIONS: u32 = 500;
 
+/// Default minimum size of the HTTP responses to compress, in bytes.
+pub(crate) const RPC_DEFAULT_HTTP_COMPRESSION_THRESHOLD: u16 = 1024;
+
 /// Parameters for configuring the rpc more granularity via CLI
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "RPC")]
@@ -54,9 +60,20 @@ pub struct RpcServerArgs {
     pub http_api: Option<RpcModuleSelection>,
 
     /// Http Corsdomain to allow request from
+    ///
+    /// Comma separated list of origins, e.g. `https://example.com,https://*.example.com`, where
+    /// `*.` matches all subdomains of a domain, or `*` to allow all origins.
     #[arg(long = "http.corsdomain")]
     pub http_corsdomain: Option<String>,
 
+    /// Compress HTTP responses with gzip or brotli if the client accepts them
+    #[arg(long = "http.compression")]
+    pub http_compression: bool,
+
+    /// Minimum size of the HTTP responses to compress in bytes, see `--http.compression`
+    #[arg(long = "http.compression-threshold", value_name = "BYTES", default_value_t = RPC_DEFAULT_HTTP_COMPRESSION_THRESHOLD)]
+    pub http_compression_threshold: u16,
+
     /// Enable the WS-RPC server
     #[arg(long)]
     pub ws: bool,
@@ -70,6 +87,9 @@ pub struct RpcServerArgs {
     pub ws_port: u16,
 
     /// Origins from which to accept `WebSocket` requests
+    ///
+    /// Comma separated list of origins, e.g. `https://example.com,https://*.example.com`, where
+    /// `*.` matches all subdomains of a domain, or `*` to allow all origins.
     #[arg(id = "ws.origins", long = "ws.origins")]
     pub ws_allowed_origins: Option<String>,
 
@@ -82,9 +102,16 @@ pub struct RpcServerArgs {
     pub ipcdisable: bool,
 
     /// Filename for IPC socket/pipe within the datadir
+    ///
+    /// On Linux, a name prefixed with `@` is a socket in the abstract namespace, e.g. `@reth.ipc`.
     #[arg(long, default_value_t = constants::DEFAULT_IPC_ENDPOINT.to_string())]
     pub ipcpath: String,
 
+    /// Octal file mode of the IPC socket, e.g. `660` to restrict access to the user and group of
+    /// the node.
+    #[arg(long = "ipc.permissions", value_name = "MODE", value_parser = parse_socket_mode)]
+    pub ipc_permissions: Option<u32>,
+
     /// Auth server address to listen on
     #[arg(long = "authrpc.addr", default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
     pub auth_addr: IpAddr,
@@ -99,10 +126,18 @@ pub struct RpcServerArgs {
     ///
     /// If no path is provided, a secret will be generated and stored in the datadir under
     /// `<DIR>/<CHAIN_ID>/jwt.hex`. For mainnet this would be `~/.reth/mainnet/jwt.hex` by default.
+    ///
+    /// The file may contain multiple secrets, one per line, which are all accepted to rotate the
+    /// secret. The file is reloaded when it changes or when `admin_reloadJwtSecret` is called, so
+    /// the secret can be rotated without a restart.
     #[arg(long = "authrpc.jwtsecret", value_name = "PATH", global = true, required = false)]
     pub auth_jwtsecret: Option<PathBuf>,
 
     /// Enable auth engine API over IPC
+    ///
+    /// Requests over IPC are not authenticated with the JWT secret, so that a consensus layer
+    /// client running on the same machine can connect without it. Access is restricted by the
+    /// permissions of the socket instead, see `--auth-ipc.permissions`.
     #[arg(long)]
     pub auth_ipc: bool,
 
@@ -110,6 +145,11 @@ pub struct RpcServerArgs {
     #[arg(long = "auth-ipc.path", default_value_t = constants::DEFAULT_ENGINE_API_IPC_ENDPOINT.to_string())]
     pub auth_ipc_path: String,
 
+    /// Octal file mode of the auth IPC socket, e.g. `660` to allow a consensus layer client
+    /// running as another user of the group of the node to connect.
+    #[arg(long = "auth-ipc.permissions", value_name = "MODE", default_value = "600", value_parser = parse_socket_mode)]
+    pub auth_ipc_permissions: u32,
+
     /// Hex encoded JWT secret to authenticate the regular RPC server(s), see `--http.api` and
     /// `--ws.api`.
     ///
@@ -118,6 +158,17 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.jwtsecret", value_name = "HEX", global = true, required = false)]
     pub rpc_jwtsecret: Option<JwtSecret>,
 
+    /// Path to a PEM file with the TLS certificate chain of the HTTP and WS servers.
+    ///
+    /// If set, the servers only accept TLS connections. The file is reloaded when it changes, see
+    /// `--rpc.tls-key`.
+    #[arg(long = "rpc.tls-cert", value_name = "PATH", requires = "rpc_tls_key")]
+    pub rpc_tls_cert: Option<PathBuf>,
+
+    /// Path to a PEM file with the private key of the TLS certificate, see `--rpc.tls-cert`.
+    #[arg(long = "rpc.tls-key", value_name = "PATH", requires = "rpc_tls_cert")]
+    pub rpc_tls_key: Option<PathBuf>,
+
     /// Set the maximum RPC request payload size for both HTTP and WS in megabytes.
     #[arg(long = "rpc.max-request-size", alias = "rpc-max-request-size", default_value_t = RPC_DEFAULT_MAX_REQUEST_SIZE_MB.into())]
     pub rpc_max_request_size: MaxU32,
@@ -130,6 +181,40 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.max-subscriptions-per-connection", alias = "rpc-max-subscriptions-per-connection", default_value_t = RPC_DEFAULT_MAX_SUBS_PER_CONN.into())]
     pub rpc_max_subscriptions_per_connection: MaxU32,
 
+    /// Maximum number of active subscriptions across all connections of the ws and ipc servers.
+    ///
+    /// Subscriptions are not limited across connections if not set.
+    #[arg(
+        long = "rpc.max-subscriptions",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u32>::new().range(1..)
+    )]
+    pub rpc_max_subscriptions: Option<u32>,
+
+    /// Maximum number of calls of a batch request.
+    ///
+    /// Batch requests with more calls are rejected as a whole. Batches are not limited in length
+    /// if not set.
+    #[arg(
+        long = "rpc.max-batch-size",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u32>::new().range(1..)
+    )]
+    pub rpc_max_batch_size: Option<u32>,
+
+    /// Maximum cumulative cost of the calls of a http batch request.
+    ///
+    /// Calls cost 1, except for expensive methods like `eth_call` and `eth_getLogs`, which cost
+    /// 10 unless configured otherwise in `[rpc.method_costs]` of the config file. Calls that
+    /// exceed the budget are rejected, and so are the remaining calls of the batch. Batches are
+    /// not limited in cost if not set.
+    #[arg(
+        long = "rpc.batch-cost-budget",
+        value_name = "COST",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_batch_cost_budget: Option<u64>,
+
     /// Maximum number of RPC server connections.
     #[arg(long = "rpc.max-connections", alias = "rpc-max-connections", value_name = "COUNT", default_value_t = RPC_DEFAULT_MAX_CONNECTIONS.into())]
     pub rpc_max_connections: MaxU32,
@@ -156,6 +241,98 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// Maximum memory in bytes an EVM execution of `eth_call` and tracing RPC methods can
+    /// allocate.
+    #[arg(
+        long = "rpc.evm-memory-limit",
+        value_name = "BYTES",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        default_value_t = constants::DEFAULT_EVM_MEMORY_LIMIT
+    )]
+    pub rpc_evm_memory_limit: u64,
+
+    /// Abort running `eth_call` and `eth_estimateGas` executions once their request is cancelled,
+    /// e.g. because the client disconnected or `admin_cancelExecutions` was called.
+    ///
+    /// This checks for cancellation between opcodes, which slows down these executions. Tracing
+    /// executions can always be aborted.
+    #[arg(long = "rpc.evm-interruptible")]
+    pub rpc_evm_interruptible: bool,
+
+    /// Maximum number of block witnesses returned by a single `reth_getWitnessRange` call.
+    #[arg(
+        long = "rpc.max-witnesses-per-response",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_WITNESSES_PER_RESPONSE
+    )]
+    pub rpc_max_witnesses_per_response: u64,
+
+    /// Maximum size in bytes of the compressed block witnesses returned by a single
+    /// `reth_getWitnessRange` call.
+    ///
+    /// A response always contains at least one witness, even if it exceeds the limit.
+    #[arg(
+        long = "rpc.max-witness-response-size",
+        value_name = "BYTES",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_WITNESS_RESPONSE_SIZE
+    )]
+    pub rpc_max_witness_response_size: u64,
+
+    /// Comma separated list of accounts whose balance and nonce history is materialized, to be
+    /// served by `reth_accountHistory`.
+    #[arg(long = "rpc.account-history", value_name = "ADDRESS", value_delimiter = ',')]
+    pub rpc_account_history: Vec<Address>,
+
+    /// Number of blocks before the head to backfill the account history from on startup.
+    ///
+    /// The history starts at the head by default.
+    #[arg(long = "rpc.account-history-backfill", value_name = "BLOCKS", default_value_t = 0)]
+    pub rpc_account_history_backfill: u64,
+
+    /// Deadline of RPC calls, after which they fail with a timeout error, e.g. `30s`.
+    ///
+    /// Applies to all namespaces without a deadline set with `--rpc.namespace-timeout`. Calls
+    /// are not limited by default.
+    #[arg(long = "rpc.timeout", value_name = "DURATION", value_parser = parse_duration)]
+    pub rpc_timeout: Option<Duration>,
+
+    /// Deadline of the RPC calls of a namespace, e.g. `debug=2m` or `eth=10s`.
+    ///
+    /// Overrides `--rpc.timeout` for the namespace. Can be specified multiple times.
+    #[arg(
+        long = "rpc.namespace-timeout",
+        value_name = "NAMESPACE=DURATION",
+        value_parser = parse_namespace_timeout
+    )]
+    pub rpc_namespace_timeouts: Vec<(String, Duration)>,
+
+    /// Maximum number of cached responses of idempotent RPC calls, e.g. `eth_chainId` or
+    /// `eth_call` at a historical block.
+    ///
+    /// Cached responses are invalidated when the canonical chain changes. Responses are not cached
+    /// by default.
+    #[arg(
+        long = "rpc.response-cache-size",
+        value_name = "ENTRIES",
+        value_parser = RangedU64ValueParser::<u32>::new().range(1..)
+    )]
+    pub rpc_response_cache_size: Option<u32>,
+
+    /// Logs RPC calls with their method, params size, connection and latency.
+    ///
+    /// Optionally takes a sampling rate `N` to log only one in `N` calls, e.g. `100`. Calls are
+    /// logged with the `rpc::request` target. Calls are not logged by default.
+    #[arg(
+        long = "rpc.log-requests",
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_log_requests: Option<u64>,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -265,6 +442,8 @@ impl Default for RpcServerArgs {
             http_port: constants::DEFAULT_HTTP_RPC_PORT,
             http_api: None,
             http_corsdomain: None,
+            http_compression: false,
+            http_compression_threshold: RPC_DEFAULT_HTTP_COMPRESSION_THRESHOLD,
             ws: false,
             ws_addr: Ipv4Addr::LOCALHOST.into(),
             ws_port: constants::DEFAULT_WS_RPC_PORT,
@@ -272,26 +451,64 @@ impl Default for RpcServerArgs {
             ws_api: None,
             ipcdisable: false,
             ipcpath: constants::DEFAULT_IPC_ENDPOINT.to_string(),
+            ipc_permissions: None,
             auth_addr: Ipv4Addr::LOCALHOST.into(),
             auth_port: constants::DEFAULT_AUTH_PORT,
             auth_jwtsecret: None,
             auth_ipc: false,
             auth_ipc_path: constants::DEFAULT_ENGINE_API_IPC_ENDPOINT.to_string(),
+            auth_ipc_permissions: 0o600,
             rpc_jwtsecret: None,
+            rpc_tls_cert: None,
+            rpc_tls_key: None,
             rpc_max_request_size: RPC_DEFAULT_MAX_REQUEST_SIZE_MB.into(),
             rpc_max_response_size: RPC_DEFAULT_MAX_RESPONSE_SIZE_MB.into(),
             rpc_max_subscriptions_per_connection: RPC_DEFAULT_MAX_SUBS_PER_CONN.into(),
+            rpc_max_subscriptions: None,
+            rpc_max_batch_size: None,
+            rpc_batch_cost_budget: None,
             rpc_max_connections: RPC_DEFAULT_MAX_CONNECTIONS.into(),
             rpc_max_tracing_requests: constants::default_max_tracing_requests(),
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: constants::gas_oracle::RPC_DEFAULT_GAS_CAP,
+            rpc_evm_memory_limit: constants::DEFAULT_EVM_MEMORY_LIMIT,
+            rpc_evm_interruptible: false,
+            rpc_max_witnesses_per_response: constants::DEFAULT_MAX_WITNESSES_PER_RESPONSE,
+            rpc_max_witness_response_size: constants::DEFAULT_MAX_WITNESS_RESPONSE_SIZE,
+            rpc_account_history: Vec::new(),
+            rpc_account_history_backfill: 0,
+            rpc_timeout: None,
+            rpc_namespace_timeouts: Vec::new(),
+            rpc_response_cache_size: None,
+            rpc_log_requests: None,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
         }
     }
 }
 
+/// Parses a `<namespace>=<duration>` deadline of the calls of a namespace.
+fn parse_namespace_timeout(value: &str) -> eyre::Result<(String, Duration)> {
+    let (namespace, timeout) = value
+        .split_once('=')
+        .ok_or_else(|| eyre::eyre!("expected `<namespace>=<duration>`, e.g. `debug=2m`"))?;
+    let namespace = namespace.trim();
+    if namespace.is_empty() || namespace.contains('_') {
+        eyre::bail!("invalid namespace `{namespace}`")
+    }
+    Ok((namespace.to_string(), parse_duration(timeout.trim())?))
+}
+
+/// Parses an octal file mode, e.g. `660` or `0o660`.
+fn parse_socket_mode(value: &str) -> eyre::Result<u32> {
+    let mode = u32::from_str_radix(value.strip_prefix("0o").unwrap_or(value), 8)?;
+    if mode > 0o777 {
+        eyre::bail!("invalid file mode `{value}`, expected at most `777`")
+    }
+    Ok(mode)
+}
+
 /// clap value parser for [`RpcModuleSelection`].
 #[derive(Clone, Debug, Default)]
 #[non_exhaustive]
@@ -373,6 +590,141 @@ mod tests {
         assert_eq!(apis, expected);
     }
 
+    #[test]
+    fn test_rpc_server_timeouts() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.timeout",
+            "30s",
+            "--rpc.namespace-timeout",
+            "debug=2m",
+            "--rpc.namespace-timeout",
+            "trace=90s",
+        ])
+        .args;
+
+        assert_eq!(args.rpc_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(
+            args.rpc_namespace_timeouts,
+            vec![
+                ("debug".to_string(), Duration::from_secs(120)),
+                ("trace".to_string(), Duration::from_secs(90))
+            ]
+        );
+
+        for invalid in ["debug", "debug=", "=2m", "debug_trace=2m", "debug=fortnight"] {
+            assert!(CommandParser::<RpcServerArgs>::try_parse_from([
+                "reth",
+                "--rpc.namespace-timeout",
+                invalid
+            ])
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_rpc_log_requests() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.rpc_log_requests, None);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.log-requests"]).args;
+        assert_eq!(args.rpc_log_requests, Some(1));
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.log-requests", "100"]).args;
+        assert_eq!(args.rpc_log_requests, Some(100));
+
+        assert!(CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.log-requests",
+            "0"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_http_compression() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(!args.http_compression);
+        assert_eq!(args.http_compression_threshold, RPC_DEFAULT_HTTP_COMPRESSION_THRESHOLD);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http.compression",
+            "--http.compression-threshold",
+            "4096",
+        ])
+        .args;
+        assert!(args.http_compression);
+        assert_eq!(args.http_compression_threshold, 4096);
+    }
+
+    #[test]
+    fn test_ipc_permissions() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.ipc_permissions, None);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--ipc.permissions", "660"]).args;
+        assert_eq!(args.ipc_permissions, Some(0o660));
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--ipc.permissions", "0o600"]).args;
+        assert_eq!(args.ipc_permissions, Some(0o600));
+
+        for invalid in ["888", "1777", "rw"] {
+            assert!(CommandParser::<RpcServerArgs>::try_parse_from([
+                "reth",
+                "--ipc.permissions",
+                invalid
+            ])
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_auth_ipc_permissions() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.auth_ipc_permissions, 0o600);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--auth-ipc",
+            "--auth-ipc.permissions",
+            "660",
+        ])
+        .args;
+        assert!(args.auth_ipc);
+        assert_eq!(args.auth_ipc_permissions, 0o660);
+    }
+
+    #[test]
+    fn test_rpc_tls() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.tls-cert",
+            "cert.pem",
+            "--rpc.tls-key",
+            "key.pem",
+        ])
+        .args;
+        assert_eq!(args.rpc_tls_cert, Some(PathBuf::from("cert.pem")));
+        assert_eq!(args.rpc_tls_key, Some(PathBuf::from("key.pem")));
+
+        // the certificate requires a key and vice versa
+        assert!(CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.tls-cert",
+            "cert.pem"
+        ])
+        .is_err());
+        assert!(CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.tls-key",
+            "key.pem"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn rpc_server_args_default_sanity_test() {
         let default_args = RpcServerArgs::default();
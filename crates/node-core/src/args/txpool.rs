@@ -63,6 +63,10 @@ pub struct TxPoolArgs {
     /// Flag to toggle local transaction propagation.
     #[arg(long = "txpool.no-local-transactions-propagation")]
     pub no_local_transactions_propagation: bool,
+    /// Pre-load the state referenced by new pending transactions, i.e. their senders, recipients
+    /// and access lists, to speed up the execution of the next block.
+    #[arg(long = "txpool.prewarm-state")]
+    pub prewarm_state: bool,
 }
 
 impl Default for TxPoolArgs {
@@ -82,6 +86,7 @@ impl Default for TxPoolArgs {
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
+            prewarm_state: false,
         }
     }
 }
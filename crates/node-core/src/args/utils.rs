@@ -3,10 +3,11 @@
 use alloy_genesis::Genesis;
 use reth_chainspec::ChainSpec;
 use reth_fs_util as fs;
+use reth_network_peers::NodeRecord;
 use reth_primitives::{BlockHashOrNumber, B256};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs},
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
     time::Duration,
@@ -35,7 +36,7 @@ pub fn parse_duration_from_secs(arg: &str) -> eyre::Result<Duration, std::num::P
 
 /// The help info for the --chain flag
 pub fn chain_help() -> String {
-    format!("The chain this node is running.\nPossible values are either a built-in chain or the path to a chain specification file.\n\nBuilt-in chains:\n    {}", SUPPORTED_CHAINS.join(", "))
+    format!("The chain this node is running.\nPossible values are either a built-in chain, the path to a chain specification file or the path to a testnet configuration directory.\n\nBuilt-in chains:\n    {}", SUPPORTED_CHAINS.join(", "))
 }
 
 /// Clap value parser for [`ChainSpec`]s.
@@ -62,8 +63,13 @@ pub fn chain_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Error>
         #[cfg(feature = "optimism")]
         "base_sepolia" | "base-sepolia" => BASE_SEPOLIA.clone(),
         _ => {
+            let path = PathBuf::from(shellexpand::full(s)?.into_owned());
+            if path.is_dir() {
+                return Ok(Arc::new(parse_testnet_dir(&path)?))
+            }
+
             // try to read json from path first
-            let raw = match fs::read_to_string(PathBuf::from(shellexpand::full(s)?.into_owned())) {
+            let raw = match fs::read_to_string(path) {
                 Ok(raw) => raw,
                 Err(io_err) => {
                     // valid json may start with "\n", but must contain "{"
@@ -78,11 +84,58 @@ pub fn chain_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Error>
             // both serialized Genesis and ChainSpec structs supported
             let genesis: Genesis = serde_json::from_str(&raw)?;
 
-            Arc::new(genesis.into())
+            let chain_spec = ChainSpec::from(genesis);
+            chain_spec.validate_hardfork_order()?;
+            Arc::new(chain_spec)
         }
     })
 }
 
+/// Parses the [`ChainSpec`] of a testnet from a configuration directory, in the layout of the
+/// public testnet repositories (e.g. the `metadata` directory of `eth-clients/hoodi`):
+///
+/// - `genesis.json`: the genesis of the chain
+/// - `enodes.yaml` (optional): the list of the bootnodes of the chain
+/// - `deposit_contract.txt` and `deposit_contract_block.txt` (optional): the address and the
+///   deployment block of the deposit contract
+pub fn parse_testnet_dir(dir: &Path) -> eyre::Result<ChainSpec> {
+    let mut genesis: Genesis =
+        serde_json::from_str(&fs::read_to_string(dir.join("genesis.json"))?)?;
+
+    let deposit_contract = dir.join("deposit_contract.txt");
+    if deposit_contract.exists() {
+        genesis.config.deposit_contract_address =
+            Some(fs::read_to_string(deposit_contract)?.trim().parse()?);
+    }
+
+    let mut chain_spec = ChainSpec::from(genesis);
+    chain_spec.validate_hardfork_order()?;
+
+    let deposit_contract_block = dir.join("deposit_contract_block.txt");
+    if let Some(deposit_contract) = &mut chain_spec.deposit_contract {
+        if deposit_contract_block.exists() {
+            deposit_contract.block = fs::read_to_string(deposit_contract_block)?.trim().parse()?;
+        }
+    }
+
+    let enodes = dir.join("enodes.yaml");
+    if enodes.exists() {
+        let bootnodes = fs::read_to_string(enodes)?
+            .lines()
+            .map(|line| line.trim().trim_start_matches('-').trim().trim_matches(['"', '\'']))
+            .filter(|enode| !enode.is_empty() && !enode.starts_with('#'))
+            .map(|enode| {
+                enode
+                    .parse::<NodeRecord>()
+                    .map_err(|err| eyre::eyre!("invalid enode {enode}: {err}"))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        chain_spec.bootnodes = Some(bootnodes);
+    }
+
+    Ok(chain_spec)
+}
+
 /// Parse [`BlockHashOrNumber`]
 pub fn hash_or_num_value_parser(value: &str) -> eyre::Result<BlockHashOrNumber, eyre::Error> {
     match B256::from_str(value) {
@@ -149,6 +202,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_testnet_directory() {
+        let dir = std::env::temp_dir().join(format!("reth-testnet-{}", thread_rng().gen::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("genesis.json"),
+            r#"{"config":{"chainId":560048,"homesteadBlock":0,"londonBlock":0,"terminalTotalDifficulty":0,"shanghaiTime":0},"difficulty":"0x1","gasLimit":"0x2255100","alloc":{}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("enodes.yaml"),
+            "# bootnodes\n- enode://ac906289e4b7f12df423d654c5a962b6ebe5b3a74cc9e06292a85221f9a64a6f1cfdd6b714ed6dacef51578f92b34c60ee91e9ede9c7f8fadc4d347326d95e2b@146.190.13.128:30303\n",
+        )
+        .unwrap();
+        fs::write(dir.join("deposit_contract.txt"), "0x00000000219ab540356cBB839Cbe05303d7705Fa\n")
+            .unwrap();
+        fs::write(dir.join("deposit_contract_block.txt"), "42\n").unwrap();
+
+        let chain_spec = chain_value_parser(dir.to_str().unwrap()).unwrap();
+        assert_eq!(chain_spec.chain.id(), 560048);
+        assert_eq!(chain_spec.bootnodes().unwrap().len(), 1);
+        let deposit_contract = chain_spec.deposit_contract.clone().unwrap();
+        assert_eq!(deposit_contract.block, 42);
+        assert_eq!(
+            deposit_contract.address,
+            "0x00000000219ab540356cBB839Cbe05303d7705Fa"
+                .parse::<reth_primitives::Address>()
+                .unwrap()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn parse_socket_addresses() {
         for value in ["localhost:9000", ":9000", "9000"] {
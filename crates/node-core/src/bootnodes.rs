@@ -0,0 +1,235 @@
+//! Signed bootnodes manifests.
+//!
+//! A manifest lists the bootnodes of a chain and is signed by a trusted publisher, so the
+//! bootnodes of test networks can be rotated without a new release.
+
+use reth_network_api::{PeerKind, Peers};
+use reth_network_peers::NodeRecord;
+use reth_primitives::{keccak256, sign_message, Address, Bytes, Signature, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, time::Duration};
+use tracing::{info, warn};
+
+/// The default interval between two fetches of the bootnodes manifest.
+pub const DEFAULT_BOOTNODES_MANIFEST_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Errors of fetching and verifying a bootnodes manifest.
+#[derive(Debug, thiserror::Error)]
+pub enum BootnodesManifestError {
+    /// Failed to fetch the manifest.
+    #[error("failed to fetch bootnodes manifest: {0}")]
+    Fetch(#[from] reqwest::Error),
+    /// The manifest is not valid JSON.
+    #[error("invalid bootnodes manifest: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The signature is malformed.
+    #[error("invalid bootnodes manifest signature")]
+    InvalidSignature,
+    /// The manifest is not signed by the trusted signer.
+    #[error("bootnodes manifest is signed by {actual}, expected {expected}")]
+    UnexpectedSigner {
+        /// The trusted signer.
+        expected: Address,
+        /// The signer of the manifest.
+        actual: Address,
+    },
+    /// The manifest is for another chain.
+    #[error("bootnodes manifest is for chain {actual}, expected {expected}")]
+    ChainMismatch {
+        /// The chain of the node.
+        expected: u64,
+        /// The chain of the manifest.
+        actual: u64,
+    },
+}
+
+/// The bootnodes of a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootnodesManifest {
+    /// The chain the bootnodes belong to.
+    pub chain_id: u64,
+    /// The version of the manifest, increased with every update.
+    pub version: u64,
+    /// The bootnodes of the chain.
+    pub bootnodes: Vec<NodeRecord>,
+}
+
+/// A [`BootnodesManifest`] signed by its publisher.
+///
+/// The manifest is kept as the JSON string that was signed. The signature is a recoverable
+/// secp256k1 signature (`r || s || v`) of the keccak256 hash of that string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedBootnodesManifest {
+    /// The JSON encoded [`BootnodesManifest`].
+    pub manifest: String,
+    /// The signature of the manifest.
+    pub signature: Bytes,
+}
+
+impl SignedBootnodesManifest {
+    /// Signs the manifest with the given secret key.
+    pub fn sign(
+        manifest: &BootnodesManifest,
+        secret: B256,
+    ) -> Result<Self, BootnodesManifestError> {
+        let manifest = serde_json::to_string(manifest)?;
+        let signature = sign_message(secret, keccak256(&manifest))
+            .map_err(|_| BootnodesManifestError::InvalidSignature)?;
+        Ok(Self { manifest, signature: signature.to_hex_bytes() })
+    }
+
+    /// Verifies that the manifest is signed by the given signer and returns the decoded manifest.
+    pub fn verify(&self, signer: Address) -> Result<BootnodesManifest, BootnodesManifestError> {
+        let [signature @ .., v]: [u8; 65] = self
+            .signature
+            .as_ref()
+            .try_into()
+            .map_err(|_| BootnodesManifestError::InvalidSignature)?;
+        let odd_y_parity = match v {
+            0 | 27 => false,
+            1 | 28 => true,
+            _ => return Err(BootnodesManifestError::InvalidSignature),
+        };
+        let signature = Signature {
+            r: U256::from_be_slice(&signature[..32]),
+            s: U256::from_be_slice(&signature[32..]),
+            odd_y_parity,
+        };
+
+        let actual = signature
+            .recover_signer(keccak256(&self.manifest))
+            .ok_or(BootnodesManifestError::InvalidSignature)?;
+        if actual != signer {
+            return Err(BootnodesManifestError::UnexpectedSigner { expected: signer, actual })
+        }
+        Ok(serde_json::from_str(&self.manifest)?)
+    }
+}
+
+/// Periodically fetches a signed bootnodes manifest and applies new versions to the peer set of
+/// the network.
+///
+/// Bootnodes of a new version are added to the peer set, and bootnodes that were dropped from
+/// the previous version are removed from it.
+#[derive(Debug)]
+pub struct BootnodesManifestUpdater<N> {
+    network: N,
+    client: reqwest::Client,
+    url: String,
+    signer: Address,
+    chain_id: u64,
+    interval: Duration,
+}
+
+impl<N: Peers> BootnodesManifestUpdater<N> {
+    /// Creates a new updater for the manifest at the given url, signed by the given signer.
+    pub fn new(network: N, url: String, signer: Address, chain_id: u64) -> Self {
+        Self {
+            network,
+            client: reqwest::Client::new(),
+            url,
+            signer,
+            chain_id,
+            interval: DEFAULT_BOOTNODES_MANIFEST_INTERVAL,
+        }
+    }
+
+    /// Sets the interval between two fetches of the manifest.
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Fetches and verifies the manifest.
+    pub async fn fetch(&self) -> Result<BootnodesManifest, BootnodesManifestError> {
+        let signed: SignedBootnodesManifest =
+            self.client.get(&self.url).send().await?.error_for_status()?.json().await?;
+        let manifest = signed.verify(self.signer)?;
+        if manifest.chain_id != self.chain_id {
+            return Err(BootnodesManifestError::ChainMismatch {
+                expected: self.chain_id,
+                actual: manifest.chain_id,
+            })
+        }
+        Ok(manifest)
+    }
+
+    /// Fetches the manifest on every interval, starting immediately, and applies new versions.
+    pub async fn run(self) {
+        let mut current: Option<BootnodesManifest> = None;
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            match self.fetch().await {
+                Ok(manifest) => {
+                    if current.as_ref().map_or(true, |current| manifest.version > current.version) {
+                        self.apply(current.as_ref(), &manifest);
+                        current = Some(manifest);
+                    }
+                }
+                Err(err) => {
+                    warn!(target: "reth::cli", %err, url = %self.url, "Failed to update bootnodes from manifest")
+                }
+            }
+        }
+    }
+
+    /// Applies the manifest to the peer set of the network.
+    fn apply(&self, previous: Option<&BootnodesManifest>, manifest: &BootnodesManifest) {
+        let listed = manifest.bootnodes.iter().map(|node| node.id).collect::<HashSet<_>>();
+        for node in previous.into_iter().flat_map(|previous| &previous.bootnodes) {
+            if !listed.contains(&node.id) {
+                self.network.remove_peer(node.id, PeerKind::Basic);
+            }
+        }
+        for node in &manifest.bootnodes {
+            self.network.add_peer(node.id, node.tcp_addr());
+        }
+        info!(target: "reth::cli", version = manifest.version, bootnodes = manifest.bootnodes.len(), "Updated bootnodes from manifest");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::public_key_to_address;
+    use secp256k1::{SecretKey, SECP256K1};
+
+    fn manifest() -> BootnodesManifest {
+        BootnodesManifest {
+            chain_id: 17000,
+            version: 1,
+            bootnodes: vec!["enode://ac906289e4b7f12df423d654c5a962b6ebe5b3a74cc9e06292a85221f9a64a6f1cfdd6b714ed6dacef51578f92b34c60ee91e9ede9c7f8fadc4d347326d95e2b@146.190.13.128:30303".parse().unwrap()],
+        }
+    }
+
+    #[test]
+    fn verify_signed_manifest() {
+        let secret = SecretKey::new(&mut rand::thread_rng());
+        let signer = public_key_to_address(secret.public_key(SECP256K1));
+        let signed =
+            SignedBootnodesManifest::sign(&manifest(), B256::from(secret.secret_bytes())).unwrap();
+
+        let json = serde_json::to_string(&signed).unwrap();
+        let signed: SignedBootnodesManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(signed.verify(signer).unwrap(), manifest());
+
+        // another signer
+        let err = signed.verify(Address::ZERO).unwrap_err();
+        assert!(matches!(err, BootnodesManifestError::UnexpectedSigner { .. }));
+
+        // a tampered manifest
+        let mut tampered = signed.clone();
+        tampered.manifest = tampered.manifest.replace("17000", "1");
+        assert!(tampered.verify(signer).is_err());
+
+        // a malformed signature
+        let mut malformed = signed;
+        malformed.signature = Bytes::from_static(&[0; 64]);
+        assert!(matches!(
+            malformed.verify(signer).unwrap_err(),
+            BootnodesManifestError::InvalidSignature
+        ));
+    }
+}
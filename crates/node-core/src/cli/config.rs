@@ -1,7 +1,7 @@
 //! Config traits for various node components.
 
 use reth_network::protocol::IntoRlpxSubProtocol;
-use reth_primitives::Bytes;
+use reth_primitives::{Address, Bytes};
 use reth_transaction_pool::PoolConfig;
 use std::{borrow::Cow, time::Duration};
 
@@ -29,6 +29,36 @@ pub trait PayloadBuilderConfig {
 
     /// Maximum number of tasks to spawn for building a payload.
     fn max_payload_tasks(&self) -> usize;
+
+    /// Maximum number of payloads to build per payload job, if limited.
+    fn max_iterations(&self) -> Option<usize>;
+
+    /// Maximum total time a payload job spends building payloads, if limited.
+    fn max_build_time(&self) -> Option<Duration>;
+
+    /// Maximum gas used by the transactions of a payload, if lower than the block gas limit.
+    fn iteration_gas_budget(&self) -> Option<u64>;
+
+    /// Maximum time spent adding transactions to a payload per building attempt, if limited.
+    fn iteration_time_budget(&self) -> Option<Duration>;
+
+    /// Minimum priority fee per gas of new pending transactions that trigger an incremental
+    /// payload rebuild, if enabled.
+    fn incremental_rebuild_min_tip(&self) -> Option<u128>;
+
+    /// Minimum effective priority fee per gas of a pool transaction for building a non-empty
+    /// payload, if the empty payload fast path is enabled.
+    fn empty_payload_min_tip(&self) -> Option<u128>;
+
+    /// URLs of external builders to request payloads from.
+    fn external_builders(&self) -> &[String];
+
+    /// How long to wait for the payloads of external builders.
+    fn external_builder_timeout(&self) -> Duration;
+
+    /// Suggested fee recipients of the payload jobs to request external payloads for, or empty to
+    /// request them for all payload jobs.
+    fn external_fee_recipients(&self) -> &[Address];
 }
 
 /// A trait that represents the configured network and can be used to apply additional configuration
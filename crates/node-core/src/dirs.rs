@@ -301,6 +301,28 @@ impl<D> ChainPath<D> {
         }
     }
 
+    /// Returns the path to the write-ahead log directory of the `ExEx` notifications for this
+    /// chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/wal`
+    pub fn exex_wal(&self) -> PathBuf {
+        self.data_dir().join("exex").join("wal")
+    }
+
+    /// Returns the path to the checkpoints of the execution extensions for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/checkpoints`
+    pub fn exex_checkpoints(&self) -> PathBuf {
+        self.data_dir().join("exex").join("checkpoints")
+    }
+
+    /// Returns the path to the directory the blockchain tree spills the state of sidechains to.
+    ///
+    /// `<DIR>/<CHAIN_ID>/spilled-state`
+    pub fn spilled_state(&self) -> PathBuf {
+        self.data_dir().join("spilled-state")
+    }
+
     /// Returns the path to the reth p2p secret key for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/discovery-secret`
@@ -315,6 +337,13 @@ impl<D> ChainPath<D> {
         self.data_dir().join("known-peers.json")
     }
 
+    /// Returns the path to the marker file that persists a paused sync pipeline.
+    ///
+    /// `<DIR>/<CHAIN_ID>/pipeline.paused`
+    pub fn pipeline_paused(&self) -> PathBuf {
+        self.data_dir().join("pipeline.paused")
+    }
+
     /// Returns the path to the blobstore directory for this chain where blobs of unfinalized
     /// transactions are stored.
     ///
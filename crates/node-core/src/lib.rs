@@ -9,6 +9,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod args;
+pub mod bootnodes;
 pub mod cli;
 pub mod dirs;
 pub mod exit;
@@ -26,14 +26,17 @@ use reth_network::{
 use reth_node_api::{FullNodeTypes, FullNodeTypesAdapter, NodeTypes};
 use reth_node_core::{
     args::{get_secret_key, DatadirArgs},
+    bootnodes::BootnodesManifestUpdater,
     cli::config::{PayloadBuilderConfig, RethTransactionPoolConfig},
     dirs::{ChainPath, DataDirPath, MaybePlatformPath},
     node_config::NodeConfig,
     primitives::Head,
     utils::write_peers_to_file,
 };
+use reth_payload_builder::database::SharedCachedReads;
 use reth_primitives::revm_primitives::EnvKzgSettings;
 use reth_provider::{providers::BlockchainProvider, ChainSpecProvider};
+use reth_rpc_builder::HttpMiddlewareLayer;
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::{PoolConfig, TransactionPool};
 use secp256k1::SecretKey;
@@ -97,8 +100,10 @@ pub type RethFullAdapter<DB, Types> = FullNodeTypesAdapter<Types, DB, Blockchain
 /// is launched [`NodeBuilderWithComponents::on_component_initialized`], or once the rpc server(s)
 /// are launched [`NodeBuilderWithComponents::on_rpc_started`]. The
 /// [`NodeBuilderWithComponents::extend_rpc_modules`] can be used to inject custom rpc modules into
-/// the rpc server before it is launched. See also [`RpcContext`] All hooks accept a closure that is
-/// then invoked at the appropriate time in the node's launch process.
+/// the rpc server before it is launched. See also [`RpcContext`]. Custom tower layers of the http
+/// requests, e.g. for authentication, can be added with
+/// [`NodeBuilderWithComponents::extend_rpc_middleware`]. All hooks accept a closure that is then
+/// invoked at the appropriate time in the node's launch process.
 ///
 /// ## Flow
 ///
@@ -364,6 +369,23 @@ where
         Self { builder: self.builder.extend_rpc_modules(hook), task_executor: self.task_executor }
     }
 
+    /// Sets the hook that is run to add custom http middleware to the rpc servers, e.g. tower
+    /// layers for authentication, rate limiting or metrics.
+    pub fn extend_rpc_middleware<F>(self, hook: F) -> Self
+    where
+        F: FnOnce(
+                &NodeAdapter<RethFullAdapter<DB, T>, CB::Components>,
+                &mut HttpMiddlewareLayer,
+            ) -> eyre::Result<()>
+            + Send
+            + 'static,
+    {
+        Self {
+            builder: self.builder.extend_rpc_middleware(hook),
+            task_executor: self.task_executor,
+        }
+    }
+
     /// Installs an `ExEx` (Execution Extension) in the node.
     ///
     /// # Note
@@ -411,17 +433,25 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs,
+    /// Reads of the state of recent blocks shared between payload building and block execution.
+    pub(crate) shared_cached_reads: SharedCachedReads,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// Create a new instance of [`BuilderContext`]
-    pub const fn new(
+    pub fn new(
         head: Head,
         provider: Node::Provider,
         executor: TaskExecutor,
         config_container: WithConfigs,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self {
+            head,
+            provider,
+            executor,
+            config_container,
+            shared_cached_reads: SharedCachedReads::default(),
+        }
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -451,6 +481,15 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         &self.executor
     }
 
+    /// Returns the reads of the state of recent blocks that are shared between payload building
+    /// and the execution of new blocks.
+    ///
+    /// Payload builders can use them to warm the state the blockchain tree reads when the built
+    /// payload comes back via `engine_newPayload`.
+    pub const fn shared_cached_reads(&self) -> &SharedCachedReads {
+        &self.shared_cached_reads
+    }
+
     /// Returns the chain spec of the node.
     pub fn chain_spec(&self) -> Arc<ChainSpec> {
         self.provider().chain_spec()
@@ -514,6 +553,20 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
             },
         );
 
+        let network = &self.config().network;
+        if let (Some(url), Some(signer)) =
+            (network.bootnodes_manifest.clone(), network.bootnodes_manifest_signer)
+        {
+            let updater = BootnodesManifestUpdater::new(
+                handle.clone(),
+                url,
+                signer,
+                self.config().chain.chain.id(),
+            )
+            .with_interval(network.bootnodes_manifest_interval);
+            self.executor.spawn(updater.run());
+        }
+
         handle
     }
 
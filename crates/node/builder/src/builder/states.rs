@@ -18,6 +18,7 @@ use reth_network::NetworkHandle;
 use reth_node_api::{FullNodeComponents, FullNodeTypes, NodeTypes};
 use reth_node_core::node_config::NodeConfig;
 use reth_payload_builder::PayloadBuilderHandle;
+use reth_rpc_builder::HttpMiddlewareLayer;
 use reth_tasks::TaskExecutor;
 use std::{fmt, future::Future};
 
@@ -197,6 +198,18 @@ impl<T: FullNodeTypes, CB: NodeComponentsBuilder<T>> NodeBuilderWithComponents<T
         self
     }
 
+    /// Sets the hook that is run to add custom http middleware to the rpc servers, e.g. tower
+    /// layers for authentication, rate limiting or metrics.
+    pub fn extend_rpc_middleware<F>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(&NodeAdapter<T, CB::Components>, &mut HttpMiddlewareLayer) -> eyre::Result<()>
+            + Send
+            + 'static,
+    {
+        self.add_ons.rpc.set_extend_rpc_middleware(hook);
+        self
+    }
+
     /// Installs an `ExEx` (Execution Extension) in the node.
     ///
     /// # Note
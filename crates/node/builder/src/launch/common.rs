@@ -21,6 +21,7 @@ use reth_db_api::{database::Database, database_metrics::DatabaseMetrics};
 use reth_db_common::init::{init_genesis, InitDatabaseError};
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
 use reth_evm::noop::NoopBlockExecutorProvider;
+use reth_invalid_block_hooks::InvalidBlockWitnessHook;
 use reth_network_p2p::headers::client::HeadersClient;
 use reth_node_api::FullNodeTypes;
 use reth_node_core::{
@@ -89,6 +90,14 @@ impl LaunchContext {
         let mut toml_config = confy::load_path::<reth_config::Config>(&config_path)
             .wrap_err_with(|| format!("Could not load config file {config_path:?}"))?;
 
+        if let Some(prune_config) = &toml_config.prune {
+            prune_config
+                .segments
+                .receipts_log_filter
+                .validate()
+                .wrap_err("Invalid prune config")?;
+        }
+
         Self::save_pruning_config_if_full_node(&mut toml_config, config, &config_path)?;
 
         info!(target: "reth::cli", path = ?config_path, "Configuration loaded");
@@ -326,6 +335,9 @@ impl<R> LaunchContextWith<Attached<WithConfigs, R>> {
     }
 
     /// Returns an initialized [`PrunerBuilder`] based on the configured [`PruneConfig`]
+    ///
+    /// The finished height of the `ExEx`'s and the persisted height that hold back the pruner
+    /// are not set, they're only known once the node is launched.
     pub fn pruner_builder(&self) -> PrunerBuilder {
         PrunerBuilder::new(self.prune_config().unwrap_or_default())
             .prune_delete_limit(self.chain_spec().prune_delete_limit)
@@ -525,7 +537,8 @@ where
     where
         T: FullNodeTypes<Provider = BlockchainProvider<<T as FullNodeTypes>::DB>>,
     {
-        let tree_config = BlockchainTreeConfig::default();
+        let tree_config = BlockchainTreeConfig::default()
+            .with_memory_budget(self.node_config().engine.memory_budget);
 
         // NOTE: This is a temporary workaround to provide the canon state notification sender to the components builder because there's a cyclic dependency between the blockchain provider and the tree component. This will be removed once the Blockchain provider no longer depends on an instance of the tree: <https://github.com/paradigmxyz/reth/issues/7154>
         let (canon_state_notification_sender, _receiver) =
@@ -629,13 +642,24 @@ where
 
         let consensus: Arc<dyn Consensus> = Arc::new(components.consensus().clone());
 
-        let tree_externals = TreeExternals::new(
+        let mut tree_externals = TreeExternals::new(
             self.provider_factory().clone(),
             consensus.clone(),
             components.block_executor().clone(),
-        );
+        )
+        .with_shared_cached_reads(builder_ctx.shared_cached_reads().clone());
+        if let Some(dir) = &self.node_config().debug.invalid_block_dir {
+            info!(target: "reth::cli", ?dir, "Dumping invalid blocks");
+            tree_externals =
+                tree_externals.with_invalid_block_hook(Arc::new(InvalidBlockWitnessHook::new(
+                    self.provider_factory().clone(),
+                    components.block_executor().clone(),
+                    dir.clone(),
+                )));
+        }
         let tree = BlockchainTree::new(tree_externals, *self.tree_config(), self.prune_modes())?
             .with_sync_metrics_tx(self.sync_metrics_tx())
+            .with_spill_dir(self.data_dir().spilled_state())
             // Note: This is required because we need to ensure that both the components and the
             // tree are using the same channel for canon state notifications. This will be removed
             // once the Blockchain provider no longer depends on an instance of the tree
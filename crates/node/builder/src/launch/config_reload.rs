@@ -0,0 +1,111 @@
+//! Reloading of the configuration file of a running node.
+
+use reth_config::{Config, ConfigReloadReport, ConfigReloader, PruneConfig};
+use reth_network::NetworkHandle;
+use reth_node_core::node_config::NodeConfig;
+use reth_tracing::tracing::{info, warn};
+use std::{error::Error, path::PathBuf, sync::Mutex};
+use tokio::sync::watch;
+
+/// Reloads the `reth.toml` configuration file of a running node.
+///
+/// The peer connection limits are applied to the network, and the block interval and schedule of
+/// the pruning configuration are sent to the pruner. Changes to all other settings are reported as
+/// requiring a restart. Settings that are configured by command line arguments, like the RPC rate
+/// limits and the log filters, can't be reloaded.
+#[derive(Debug)]
+pub struct NodeConfigReloader {
+    /// The path of the configuration file.
+    config_path: PathBuf,
+    /// The currently applied configuration.
+    current: Mutex<Config>,
+    /// Whether only trusted peers are allowed, overrides the configuration file.
+    trusted_only: bool,
+    /// The maximum number of inbound peers, overrides the configuration file.
+    max_inbound_peers: Option<usize>,
+    /// The maximum number of outbound peers, overrides the configuration file.
+    max_outbound_peers: Option<usize>,
+    /// Handle to the network the connection limits are applied to.
+    network: NetworkHandle,
+    /// Sender of the pruning configuration updates.
+    prune_config_tx: watch::Sender<PruneConfig>,
+}
+
+impl NodeConfigReloader {
+    /// Creates a new [`NodeConfigReloader`] for the configuration file at the given path, that was
+    /// loaded into the given configuration.
+    pub fn new(
+        config_path: PathBuf,
+        current: Config,
+        node_config: &NodeConfig,
+        network: NetworkHandle,
+        prune_config_tx: watch::Sender<PruneConfig>,
+    ) -> Self {
+        Self {
+            config_path,
+            current: Mutex::new(current),
+            trusted_only: node_config.network.trusted_only,
+            max_inbound_peers: node_config.network.max_inbound_peers,
+            max_outbound_peers: node_config.network.max_outbound_peers,
+            network,
+            prune_config_tx,
+        }
+    }
+
+    /// Loads the configuration file and applies the command line overrides.
+    fn load(&self) -> Result<Config, Box<dyn Error + Send + Sync>> {
+        let mut config = confy::load_path::<Config>(&self.config_path).map_err(|err| {
+            format!("could not load config file {}: {err}", self.config_path.display())
+        })?;
+
+        if let Some(prune_config) = &config.prune {
+            prune_config
+                .segments
+                .receipts_log_filter
+                .validate()
+                .map_err(|err| format!("invalid prune config: {err}"))?;
+        }
+
+        config.peers.trusted_nodes_only = self.trusted_only;
+
+        Ok(config)
+    }
+}
+
+impl ConfigReloader for NodeConfigReloader {
+    fn reload_config(&self) -> Result<ConfigReloadReport, Box<dyn Error + Send + Sync>> {
+        let reloaded = self.load()?;
+
+        let mut current = self.current.lock().map_err(|_| "config reloader lock poisoned")?;
+        let report = ConfigReloadReport::new(&current, &reloaded);
+
+        if report.applied.iter().any(|setting| setting == "peers.connection_info") {
+            let peers_config = reloaded
+                .peers
+                .clone()
+                .with_max_inbound_opt(self.max_inbound_peers)
+                .with_max_outbound_opt(self.max_outbound_peers);
+            self.network.set_connections_config(peers_config.connection_info);
+            current.peers.connection_info = reloaded.peers.connection_info;
+        }
+
+        if let (Some(current_prune), Some(reloaded_prune)) = (&mut current.prune, reloaded.prune) {
+            if report.applied.iter().any(|setting| setting.starts_with("prune.")) {
+                current_prune.block_interval = reloaded_prune.block_interval;
+                current_prune.schedule = reloaded_prune.schedule;
+                self.prune_config_tx.send_replace(current_prune.clone());
+            }
+        }
+
+        if report.is_empty() {
+            info!(target: "reth::cli", path = ?self.config_path, "Configuration reloaded, no settings changed");
+        } else {
+            info!(target: "reth::cli", path = ?self.config_path, applied = ?report.applied, "Configuration reloaded");
+        }
+        if !report.requires_restart.is_empty() {
+            warn!(target: "reth::cli", settings = ?report.requires_restart, "Changed settings only take effect after a restart");
+        }
+
+        Ok(report)
+    }
+}
@@ -1,13 +1,25 @@
 //! Support for launching execution extensions.
 
-use crate::{common::WithConfigs, exex::BoxedLaunchExEx};
+use crate::{
+    common::WithConfigs,
+    exex::{BoxedLaunchExEx, LaunchExEx},
+};
 use futures::future;
-use reth_exex::{ExExContext, ExExHandle, ExExManager, ExExManagerHandle};
+use reth_exex::{
+    BackfillJobFactory, ExExCheckpointStore, ExExContext, ExExHandle, ExExManager,
+    ExExManagerHandle, ExExRpcModules, Wal, FINISHED_HEIGHT_CHECKPOINT_KEY,
+};
 use reth_node_api::FullNodeComponents;
-use reth_primitives::Head;
-use reth_provider::CanonStateSubscriptions;
-use reth_tracing::tracing::{debug, info};
-use std::{fmt, fmt::Debug};
+use reth_primitives::{BlockNumber, Head};
+use reth_provider::{BlockNumReader, CanonStateSubscriptions, HeaderProvider, ProviderError};
+use reth_tracing::tracing::{debug, error, info, Instrument};
+use std::{
+    collections::HashMap,
+    fmt,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+use tokio::{sync::mpsc, task::JoinHandle};
 
 /// Can launch execution extensions.
 pub struct ExExLauncher<Node: FullNodeComponents> {
@@ -15,6 +27,7 @@ pub struct ExExLauncher<Node: FullNodeComponents> {
     extensions: Vec<(String, Box<dyn BoxedLaunchExEx<Node>>)>,
     components: Node,
     config_container: WithConfigs,
+    rpc_modules: ExExRpcModules,
 }
 
 impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
@@ -24,29 +37,34 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
         components: Node,
         extensions: Vec<(String, Box<dyn BoxedLaunchExEx<Node>>)>,
         config_container: WithConfigs,
+        rpc_modules: ExExRpcModules,
     ) -> Self {
-        Self { head, extensions, components, config_container }
+        Self { head, extensions, components, config_container, rpc_modules }
     }
 
     /// Launches all execution extensions.
     ///
-    /// Spawns all extensions and returns the handle to the exex manager if any extensions are
-    /// installed.
-    pub async fn launch(self) -> Option<ExExManagerHandle> {
-        let Self { head, extensions, components, config_container } = self;
-
-        if extensions.is_empty() {
-            // nothing to launch
-            return None
-        }
+    /// Spawns all extensions and the exex manager, and returns the [`ExExRegistry`] to install
+    /// and remove extensions while the node is running.
+    ///
+    /// The manager and its WAL are always created, so that extensions can be installed at runtime.
+    /// While no extensions are installed, the manager drops notifications without writing them to
+    /// the WAL, and doesn't hold back the pruner.
+    pub async fn launch(self) -> eyre::Result<ExExRegistry<Node>> {
+        let Self { head, extensions, components, config_container, rpc_modules } = self;
 
         let mut exex_handles = Vec::with_capacity(extensions.len());
         let mut exexs = Vec::with_capacity(extensions.len());
 
+        let checkpoint_store =
+            ExExCheckpointStore::new(config_container.config.datadir().exex_checkpoints())?;
+
         for (id, exex) in extensions {
+            let checkpoints = checkpoint_store.checkpoints(&id)?;
+
             // create a new exex handle
             let (handle, events, notifications) = ExExHandle::new(id.clone());
-            exex_handles.push(handle);
+            exex_handles.push(handle.with_checkpoints(checkpoints.clone()));
 
             // create the launch context for the exex
             let context = ExExContext {
@@ -56,6 +74,8 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
                 components: components.clone(),
                 events,
                 notifications,
+                rpc_modules: rpc_modules.clone(),
+                checkpoints,
             };
 
             let executor = components.task_executor().clone();
@@ -82,8 +102,11 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
 
         // spawn exex manager
         debug!(target: "reth::cli", "spawning exex manager");
-        // todo(onbjerg): rm magic number
-        let exex_manager = ExExManager::new(exex_handles, 1024);
+        let exex_config = config_container.toml_config.exex;
+        let wal = Wal::new(config_container.config.datadir().exex_wal())?;
+        let exex_manager = ExExManager::new(exex_handles, exex_config.max_buffered_notifications)
+            .with_lag_policy(exex_config.lag_policy, exex_config.max_lag_blocks)
+            .with_wal(wal)?;
         let exex_manager_handle = exex_manager.handle();
         components.task_executor().spawn_critical("exex manager", async move {
             exex_manager.await.expect("exex manager crashed");
@@ -106,7 +129,14 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
 
         info!(target: "reth::cli", "ExEx Manager started");
 
-        Some(exex_manager_handle)
+        Ok(ExExRegistry {
+            components,
+            config_container,
+            rpc_modules,
+            checkpoint_store,
+            manager: exex_manager_handle,
+            exexs: Default::default(),
+        })
     }
 }
 
@@ -117,6 +147,204 @@ impl<Node: FullNodeComponents> Debug for ExExLauncher<Node> {
             .field("extensions", &self.extensions.iter().map(|(id, _)| id).collect::<Vec<_>>())
             .field("components", &"...")
             .field("config_container", &self.config_container)
+            .field("rpc_modules", &self.rpc_modules)
+            .finish()
+    }
+}
+
+/// Installs and removes execution extensions while the node is running, so long-running nodes can
+/// gain new extensions without a restart.
+///
+/// Extensions installed at runtime can't register RPC modules, because the RPC server is already
+/// started.
+pub struct ExExRegistry<Node: FullNodeComponents> {
+    components: Node,
+    config_container: WithConfigs,
+    rpc_modules: ExExRpcModules,
+    checkpoint_store: ExExCheckpointStore,
+    manager: ExExManagerHandle,
+    /// Tasks of the extensions installed at runtime, by ID.
+    exexs: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
+}
+
+impl<Node: FullNodeComponents + Clone> ExExRegistry<Node> {
+    /// Returns the handle to the exex manager.
+    pub fn manager_handle(&self) -> &ExExManagerHandle {
+        &self.manager
+    }
+
+    /// Returns the IDs of the extensions installed with [`Self::install_exex`] that are still
+    /// running.
+    pub fn installed_exexs(&self) -> Vec<String> {
+        let exexs = self.exexs.lock().expect("ExEx registry lock poisoned");
+        exexs.iter().filter(|(_, task)| !task.is_finished()).map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Installs and launches an execution extension while the node is running.
+    ///
+    /// Before the extension receives notifications of new blocks, it's sent notifications for the
+    /// blocks from `backfill_from` up to the current tip, which are re-executed with a
+    /// [`BackfillJobFactory`]. If `backfill_from` is `None`, blocks after the
+    /// [finished height checkpoint](FINISHED_HEIGHT_CHECKPOINT_KEY) of the extension are
+    /// backfilled, and no blocks if there is no checkpoint. Backfilled notifications are not
+    /// filtered by the [`ExExNotificationFilter`](reth_exex::ExExNotificationFilter) of the
+    /// extension.
+    ///
+    /// The pruner is held back from the moment the extension is added, until it reports its first
+    /// finished height.
+    ///
+    /// Unlike extensions installed before the node is launched, an extension that finishes or
+    /// crashes is removed, without shutting down the node.
+    pub async fn install_exex<E>(
+        &self,
+        id: impl Into<String>,
+        exex: E,
+        backfill_from: Option<BlockNumber>,
+    ) -> eyre::Result<()>
+    where
+        E: LaunchExEx<Node> + 'static,
+    {
+        let id = id.into();
+        let checkpoints = self.checkpoint_store.checkpoints(&id)?;
+        let backfill_from = match backfill_from {
+            Some(block) => Some(block),
+            None => checkpoints
+                .get::<BlockNumber>(FINISHED_HEIGHT_CHECKPOINT_KEY)?
+                .map(|finished_height| finished_height + 1),
+        };
+
+        // add the exex to the manager first, so it receives all notifications after the tip
+        let head = self.head()?;
+        let (handle, events, live_notifications) = ExExHandle::new(id.clone());
+        let tip = self
+            .manager
+            .add_exex(handle.with_checkpoints(checkpoints.clone()))
+            .await?
+            .unwrap_or(head.number);
+
+        let (notifications_tx, notifications) = mpsc::channel(1);
+        let context = ExExContext {
+            head,
+            config: self.config_container.config.clone(),
+            reth_config: self.config_container.toml_config.clone(),
+            components: self.components.clone(),
+            events,
+            notifications,
+            rpc_modules: self.rpc_modules.clone(),
+            checkpoints,
+        };
+
+        let span = reth_tracing::tracing::info_span!("exex", id);
+        debug!(target: "reth::cli", id, ?backfill_from, tip, "installing exex");
+        let launch = BoxedLaunchExEx::launch(Box::new(exex), context);
+        let exex = match launch.instrument(span.clone()).await {
+            Ok(exex) => exex,
+            Err(err) => {
+                self.manager.remove_exex(id).await?;
+                return Err(err)
+            }
+        };
+
+        let executor = self.components.task_executor();
+        let backfill = BackfillJobFactory::new(
+            self.components.block_executor().clone(),
+            self.components.provider().clone(),
+        );
+        executor.spawn(
+            async move {
+                backfill
+                    .forward_with_backfill(
+                        backfill_from.unwrap_or(tip + 1),
+                        tip,
+                        live_notifications,
+                        notifications_tx,
+                    )
+                    .await
+            }
+            .instrument(span.clone()),
+        );
+
+        let mut exexs = self.exexs.lock().expect("ExEx registry lock poisoned");
+        let (manager, registry) = (self.manager.clone(), Arc::downgrade(&self.exexs));
+        let task_id = id.clone();
+        let task = executor.spawn(
+            async move {
+                info!(target: "reth::cli", "ExEx started");
+                match exex.await {
+                    Ok(()) => info!(target: "reth::cli", "ExEx finished"),
+                    Err(err) => error!(target: "reth::cli", %err, "ExEx crashed"),
+                }
+
+                if let Some(registry) = registry.upgrade() {
+                    registry.lock().expect("ExEx registry lock poisoned").remove(&task_id);
+                }
+                let _ = manager.remove_exex(task_id).await;
+            }
+            .instrument(span),
+        );
+        exexs.insert(id, task);
+
+        Ok(())
+    }
+
+    /// Removes an execution extension installed with [`Self::install_exex`], aborting its task.
+    ///
+    /// Returns `false` if there is no such extension. Extensions installed before the node is
+    /// launched can't be removed.
+    pub async fn remove_exex(&self, id: &str) -> eyre::Result<bool> {
+        let Some(task) = self.exexs.lock().expect("ExEx registry lock poisoned").remove(id) else {
+            return Ok(false)
+        };
+
+        task.abort();
+        self.manager.remove_exex(id).await?;
+        info!(target: "reth::cli", id, "ExEx removed");
+        Ok(true)
+    }
+
+    /// Returns the current head of the node.
+    fn head(&self) -> eyre::Result<Head> {
+        let provider = self.components.provider();
+        let number = provider.best_block_number()?;
+        let header = provider
+            .sealed_header(number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+        let total_difficulty = provider
+            .header_td_by_number(number)?
+            .ok_or(ProviderError::TotalDifficultyNotFound(number))?;
+
+        Ok(Head {
+            number,
+            hash: header.hash(),
+            difficulty: header.difficulty,
+            total_difficulty,
+            timestamp: header.timestamp,
+        })
+    }
+}
+
+impl<Node: FullNodeComponents + Clone> Clone for ExExRegistry<Node> {
+    fn clone(&self) -> Self {
+        Self {
+            components: self.components.clone(),
+            config_container: self.config_container.clone(),
+            rpc_modules: self.rpc_modules.clone(),
+            checkpoint_store: self.checkpoint_store.clone(),
+            manager: self.manager.clone(),
+            exexs: Arc::clone(&self.exexs),
+        }
+    }
+}
+
+impl<Node: FullNodeComponents> Debug for ExExRegistry<Node> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExExRegistry")
+            .field("components", &"...")
+            .field("config_container", &self.config_container)
+            .field("rpc_modules", &self.rpc_modules)
+            .field("checkpoint_store", &self.checkpoint_store)
+            .field("manager", &self.manager)
+            .field("exexs", &self.exexs)
             .finish()
     }
 }
@@ -12,9 +12,10 @@ use reth_beacon_consensus::{
     hooks::{EngineHooks, PruneHook, StaticFileHook},
     BeaconConsensusEngine,
 };
+use reth_config::ConfigReloader;
 use reth_consensus_debug_client::{DebugConsensusClient, EtherscanBlockProvider, RpcBlockProvider};
 use reth_engine_util::EngineMessageStreamExt;
-use reth_exex::ExExManagerHandle;
+use reth_exex::ExExRpcModules;
 use reth_network::NetworkEvents;
 use reth_node_api::FullNodeTypes;
 use reth_node_core::{
@@ -24,20 +25,23 @@ use reth_node_core::{
 };
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
 use reth_primitives::format_ether;
-use reth_provider::providers::BlockchainProvider;
+use reth_provider::{providers::BlockchainProvider, CanonStateSubscriptions};
 use reth_rpc_engine_api::EngineApi;
 use reth_rpc_types::engine::ClientVersionV1;
+use reth_stages::PipelinePauseHandle;
 use reth_tasks::TaskExecutor;
-use reth_tracing::tracing::{debug, info};
+use reth_tracing::tracing::{debug, info, warn};
 use reth_transaction_pool::TransactionPool;
 use std::{future::Future, sync::Arc};
-use tokio::sync::{mpsc::unbounded_channel, oneshot};
+use tokio::sync::{mpsc::unbounded_channel, oneshot, watch};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 pub mod common;
 pub use common::LaunchContext;
+mod config_reload;
+pub use config_reload::NodeConfigReloader;
 mod exex;
-pub use exex::ExExLauncher;
+pub use exex::{ExExLauncher, ExExRegistry};
 
 /// A general purpose trait that launches a new node of any kind.
 ///
@@ -131,14 +135,16 @@ where
             .with_components(components_builder, on_component_initialized).await?;
 
         // spawn exexs
-        let exex_manager_handle = ExExLauncher::new(
+        let exex_rpc_modules = ExExRpcModules::default();
+        let exex_registry = ExExLauncher::new(
             ctx.head(),
             ctx.node_adapter().clone(),
             installed_exex,
             ctx.configs().clone(),
+            exex_rpc_modules.clone(),
         )
         .launch()
-        .await;
+        .await?;
 
         // create pipeline
         let network_client = ctx.components().network().fetch_client().await?;
@@ -165,9 +171,8 @@ where
         info!(target: "reth::cli", "StaticFileProducer initialized");
 
         // Configure the pipeline
-        let pipeline_exex_handle =
-            exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty);
-        let (pipeline, client) = if ctx.is_dev() {
+        let pipeline_exex_handle = exex_registry.manager_handle().clone();
+        let (mut pipeline, client, mining_handle) = if ctx.is_dev() {
             info!(target: "reth::cli", "Starting Reth in dev mode");
 
             for (idx, (address, alloc)) in ctx.chain_spec().genesis.alloc.iter().enumerate() {
@@ -207,9 +212,10 @@ where
             let pipeline_events = pipeline.events();
             task.set_pipeline_events(pipeline_events);
             debug!(target: "reth::cli", "Spawning auto mine task");
+            let mining_handle = task.handle();
             ctx.task_executor().spawn(Box::pin(task));
 
-            (pipeline, Either::Left(client))
+            (pipeline, Either::Left(client), Some(mining_handle))
         } else {
             let pipeline = crate::setup::build_networked_pipeline(
                 &ctx.toml_config().stages,
@@ -226,19 +232,40 @@ where
             )
             .await?;
 
-            (pipeline, Either::Right(network_client.clone()))
+            (pipeline, Either::Right(network_client.clone()), None)
         };
 
+        // the pipeline can be paused and resumed via the admin namespace, the paused state is
+        // persisted in the data dir
+        let pipeline_pause_handle =
+            PipelinePauseHandle::persistent(ctx.data_dir().pipeline_paused());
+        if pipeline_pause_handle.is_paused() {
+            warn!(target: "reth::cli", "Pipeline is paused, call `admin_resumePipeline` to resume syncing");
+        }
+        pipeline.set_pause_handle(pipeline_pause_handle.clone());
         let pipeline_events = pipeline.events();
 
         let initial_target = ctx.node_config().debug.tip;
 
-        let mut pruner_builder =
-            ctx.pruner_builder().max_reorg_depth(ctx.tree_config().max_reorg_depth() as usize);
-        if let Some(exex_manager_handle) = &exex_manager_handle {
-            pruner_builder =
-                pruner_builder.finished_exex_height(exex_manager_handle.finished_height());
-        }
+        let (prune_config_tx, prune_config_rx) =
+            watch::channel(ctx.prune_config().unwrap_or_default());
+
+        // blocks are written to the database once they're canonical, so the tip of the canonical
+        // state notifications is the highest persisted block
+        let (persisted_height_tx, persisted_height_rx) = watch::channel(Some(ctx.head().number));
+        let mut canon_state_notifications = ctx.blockchain_db().canonical_state_stream();
+        ctx.task_executor().spawn_critical("pruner persisted height", async move {
+            while let Some(notification) = canon_state_notifications.next().await {
+                let _ = persisted_height_tx.send(Some(notification.tip().number));
+            }
+        });
+
+        let pruner_builder = ctx
+            .pruner_builder()
+            .max_reorg_depth(ctx.tree_config().max_reorg_depth() as usize)
+            .finished_exex_height(exex_registry.manager_handle().finished_height())
+            .persisted_height(persisted_height_rx)
+            .config_updates(prune_config_rx);
 
         let pruner = pruner_builder.build(ctx.provider_factory().clone());
 
@@ -247,27 +274,37 @@ where
         hooks.add(PruneHook::new(pruner, Box::new(ctx.task_executor().clone())));
 
         // Configure the consensus engine
-        let (beacon_consensus_engine, beacon_engine_handle) = BeaconConsensusEngine::with_channel(
-            client,
-            pipeline,
-            ctx.blockchain_db().clone(),
-            Box::new(ctx.task_executor().clone()),
-            Box::new(ctx.components().network().clone()),
-            max_block,
-            ctx.components().payload_builder().clone(),
-            initial_target,
-            reth_beacon_consensus::MIN_BLOCKS_FOR_PIPELINE_RUN,
-            consensus_engine_tx,
-            Box::pin(consensus_engine_stream),
-            hooks,
-        )?;
+        // in continuous mode, all new targets are synced via the pipeline
+        let continuous = ctx.node_config().debug.continuous;
+        let pipeline_run_threshold =
+            if continuous { 0 } else { reth_beacon_consensus::MIN_BLOCKS_FOR_PIPELINE_RUN };
+
+        let (mut beacon_consensus_engine, beacon_engine_handle) =
+            BeaconConsensusEngine::with_channel(
+                client,
+                pipeline,
+                ctx.blockchain_db().clone(),
+                Box::new(ctx.task_executor().clone()),
+                Box::new(ctx.components().network().clone()),
+                max_block,
+                ctx.components().payload_builder().clone(),
+                initial_target,
+                pipeline_run_threshold,
+                consensus_engine_tx,
+                Box::pin(consensus_engine_stream),
+                hooks,
+            )?;
+        if continuous {
+            info!(target: "reth::cli", "Running the pipeline continuously");
+            beacon_consensus_engine.set_pipeline_run_continuously(true);
+        }
         info!(target: "reth::cli", "Consensus engine initialized");
 
         let events = stream_select!(
             ctx.components().network().event_listener().map(Into::into),
             beacon_engine_handle.event_listener().map(Into::into),
             pipeline_events.map(Into::into),
-            if ctx.node_config().debug.tip.is_none() && !ctx.is_dev() {
+            if ctx.node_config().debug.tip.is_none() && !continuous && !ctx.is_dev() {
                 Either::Left(
                     ConsensusLayerHealthEvents::new(Box::new(ctx.blockchain_db().clone()))
                         .map(Into::into),
@@ -307,12 +344,41 @@ where
         // extract the jwt secret from the args if possible
         let jwt_secret = ctx.auth_jwt_secret()?;
 
+        // the configuration file can be reloaded with SIGHUP or `admin_reloadConfig`
+        let config_reloader = Arc::new(NodeConfigReloader::new(
+            ctx.node_config().config.clone().unwrap_or_else(|| ctx.data_dir().config()),
+            ctx.toml_config().clone(),
+            ctx.node_config(),
+            ctx.components().network().clone(),
+            prune_config_tx,
+        ));
+        #[cfg(unix)]
+        {
+            let mut hangup =
+                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+            let config_reloader = config_reloader.clone();
+            ctx.task_executor().spawn(Box::pin(async move {
+                while hangup.recv().await.is_some() {
+                    info!(target: "reth::cli", "Received SIGHUP, reloading configuration");
+                    if let Err(err) = config_reloader.reload_config() {
+                        warn!(target: "reth::cli", %err, "Failed to reload configuration");
+                    }
+                }
+            }));
+        }
+
         // Start RPC servers
         let (rpc_server_handles, mut rpc_registry) = crate::rpc::launch_rpc_servers(
             ctx.node_adapter().clone(),
             engine_api,
             ctx.node_config(),
+            &ctx.toml_config().rpc,
             jwt_secret,
+            ctx.data_dir().jwt(),
+            pipeline_pause_handle,
+            mining_handle,
+            config_reloader,
+            exex_rpc_modules,
             rpc,
         )
         .await?;
@@ -384,6 +450,7 @@ where
             rpc_registry,
             config: ctx.node_config().clone(),
             data_dir: ctx.data_dir().clone(),
+            exexs: exex_registry,
         };
         // Notify on node started
         on_node_started.on_event(full_node.clone())?;
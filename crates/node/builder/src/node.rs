@@ -1,4 +1,7 @@
-use crate::rpc::{RethRpcServerHandles, RpcRegistry};
+use crate::{
+    rpc::{RethRpcServerHandles, RpcRegistry},
+    ExExRegistry,
+};
 use reth_chainspec::ChainSpec;
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
@@ -93,6 +96,8 @@ pub struct FullNode<Node: FullNodeComponents> {
     pub config: NodeConfig,
     /// The data dir of the node.
     pub data_dir: ChainPath<DataDirPath>,
+    /// Registry to install and remove execution extensions while the node is running.
+    pub exexs: ExExRegistry<Node>,
 }
 
 impl<Node: FullNodeComponents> FullNode<Node> {
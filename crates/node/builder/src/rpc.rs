@@ -1,21 +1,41 @@
 //! Builder support for rpc components.
 
 use futures::TryFutureExt;
+use reth_auto_seal_consensus::MiningHandle;
+use reth_config::{ConfigReloader, RpcConfig};
+use reth_exex::ExExRpcModules;
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
-use reth_node_core::{node_config::NodeConfig, rpc::api::EngineApiServer};
+use reth_node_core::{
+    node_config::NodeConfig,
+    rpc::api::{
+        AdminConfigApiServer, AdminExecutionApiServer, AdminJwtApiServer, AdminPipelineApiServer,
+        AdminRpcModulesApiServer, EngineApiServer, EvmApiServer, RethAccountHistoryApiServer,
+        RethWitnessApiServer,
+    },
+};
 use reth_payload_builder::PayloadBuilderHandle;
+use reth_provider::CanonStateSubscriptions;
+use reth_rpc::{
+    account_history_views_task, AccountHistoryViews, AdminConfigApi, AdminExecutionApi,
+    AdminJwtApi, AdminPipelineApi, AdminRpcModulesApi, EvmApi, RethAccountHistoryApi,
+    RethWitnessApi,
+};
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
     config::RethRpcServerConfig,
-    RethModuleRegistry, RpcModuleBuilder, RpcServerHandle, TransportRpcModules,
+    ApiKeys, BatchCostBudget, HealthLayer, HttpMiddlewareLayer, NodeHealthCheck, ResponseCache,
+    RethModuleRegistry, RethRpcModule, RpcModuleBuilder, RpcServerHandle, TransportRpcModules,
 };
 use reth_rpc_layer::JwtSecret;
+use reth_stages::PipelinePauseHandle;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
 use std::{
     fmt,
     ops::{Deref, DerefMut},
+    path::PathBuf,
+    sync::Arc,
 };
 
 /// Contains the handles to the spawned RPC servers.
@@ -33,12 +53,17 @@ pub struct RethRpcServerHandles {
 pub(crate) struct RpcHooks<Node: FullNodeComponents> {
     pub(crate) on_rpc_started: Box<dyn OnRpcStarted<Node>>,
     pub(crate) extend_rpc_modules: Box<dyn ExtendRpcModules<Node>>,
+    pub(crate) extend_rpc_middleware: Box<dyn ExtendRpcMiddleware<Node>>,
 }
 
 impl<Node: FullNodeComponents> RpcHooks<Node> {
     /// Creates a new, empty [`RpcHooks`] instance for the given node type.
     pub(crate) fn new() -> Self {
-        Self { on_rpc_started: Box::<()>::default(), extend_rpc_modules: Box::<()>::default() }
+        Self {
+            on_rpc_started: Box::<()>::default(),
+            extend_rpc_modules: Box::<()>::default(),
+            extend_rpc_middleware: Box::<()>::default(),
+        }
     }
 
     /// Sets the hook that is run once the rpc server is started.
@@ -78,6 +103,25 @@ impl<Node: FullNodeComponents> RpcHooks<Node> {
         self.set_extend_rpc_modules(hook);
         self
     }
+
+    /// Sets the hook that is run to add custom http middleware to the rpc servers.
+    pub(crate) fn set_extend_rpc_middleware<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: ExtendRpcMiddleware<Node> + 'static,
+    {
+        self.extend_rpc_middleware = Box::new(hook);
+        self
+    }
+
+    /// Sets the hook that is run to add custom http middleware to the rpc servers.
+    #[allow(unused)]
+    pub(crate) fn extend_rpc_middleware<F>(mut self, hook: F) -> Self
+    where
+        F: ExtendRpcMiddleware<Node> + 'static,
+    {
+        self.set_extend_rpc_middleware(hook);
+        self
+    }
 }
 
 impl<Node: FullNodeComponents> fmt::Debug for RpcHooks<Node> {
@@ -85,6 +129,7 @@ impl<Node: FullNodeComponents> fmt::Debug for RpcHooks<Node> {
         f.debug_struct("RpcHooks")
             .field("on_rpc_started", &"...")
             .field("extend_rpc_modules", &"...")
+            .field("extend_rpc_middleware", &"...")
             .finish()
     }
 }
@@ -145,6 +190,42 @@ impl<Node: FullNodeComponents> ExtendRpcModules<Node> for () {
     }
 }
 
+/// Event hook that is called to add custom http middleware to the rpc servers before they are
+/// started.
+pub trait ExtendRpcMiddleware<Node: FullNodeComponents>: Send {
+    /// The hook that is called to stack tower layers on the http requests of the http and ws
+    /// servers, see [`HttpMiddlewareLayer`].
+    fn extend_rpc_middleware(
+        self: Box<Self>,
+        node: &Node,
+        middleware: &mut HttpMiddlewareLayer,
+    ) -> eyre::Result<()>;
+}
+
+impl<Node, F> ExtendRpcMiddleware<Node> for F
+where
+    F: FnOnce(&Node, &mut HttpMiddlewareLayer) -> eyre::Result<()> + Send,
+    Node: FullNodeComponents,
+{
+    fn extend_rpc_middleware(
+        self: Box<Self>,
+        node: &Node,
+        middleware: &mut HttpMiddlewareLayer,
+    ) -> eyre::Result<()> {
+        (*self)(node, middleware)
+    }
+}
+
+impl<Node: FullNodeComponents> ExtendRpcMiddleware<Node> for () {
+    fn extend_rpc_middleware(
+        self: Box<Self>,
+        _: &Node,
+        _: &mut HttpMiddlewareLayer,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+}
+
 /// Helper wrapper type to encapsulate the [`RethModuleRegistry`] over components trait.
 #[derive(Debug)]
 pub struct RpcRegistry<Node: FullNodeComponents> {
@@ -211,7 +292,9 @@ pub struct RpcContext<'a, Node: FullNodeComponents> {
     pub modules: &'a mut TransportRpcModules,
     /// Holds jwt authenticated rpc module.
     ///
-    /// This can be used to merge additional modules into the configured authenticated methods
+    /// This can be used to merge additional modules into the configured authenticated methods,
+    /// e.g. privileged namespaces that should only be served on the auth port. See
+    /// [`AuthRpcModule::merge_configured`]
     pub auth_module: &'a mut AuthRpcModule,
 }
 
@@ -248,24 +331,31 @@ impl<'a, Node: FullNodeComponents> RpcContext<'a, Node> {
 }
 
 /// Launch the rpc servers.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn launch_rpc_servers<Node, Engine>(
     node: Node,
     engine_api: Engine,
     config: &NodeConfig,
+    rpc_config: &RpcConfig,
     jwt_secret: JwtSecret,
+    default_jwt_path: PathBuf,
+    pipeline_pause_handle: PipelinePauseHandle,
+    mining_handle: Option<MiningHandle>,
+    config_reloader: Arc<dyn ConfigReloader>,
+    exex_rpc_modules: ExExRpcModules,
     hooks: RpcHooks<Node>,
 ) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node>)>
 where
     Node: FullNodeComponents + Clone,
     Engine: EngineApiServer<Node::Engine>,
 {
-    let RpcHooks { on_rpc_started, extend_rpc_modules } = hooks;
+    let RpcHooks { on_rpc_started, extend_rpc_modules, extend_rpc_middleware } = hooks;
 
-    let auth_config = config.rpc.auth_server_config(jwt_secret)?;
+    let auth_config = config.rpc.auth_server_config(jwt_secret, default_jwt_path)?;
     let module_config = config.rpc.transport_rpc_module_config();
     debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-    let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
+    let (mut modules, mut auth_module, mut registry) = RpcModuleBuilder::default()
         .with_provider(node.provider().clone())
         .with_pool(node.pool().clone())
         .with_network(node.network().clone())
@@ -274,6 +364,70 @@ where
         .with_evm_config(node.evm_config().clone())
         .build_with_auth_server(module_config, engine_api);
 
+    // pipeline control, config reloads, JWT secret rotation, aborting executions and namespace
+    // toggles are part of the admin namespace
+    modules.merge_if_module_configured(
+        RethRpcModule::Admin,
+        AdminPipelineApi::new(pipeline_pause_handle).into_rpc(),
+    )?;
+    modules.merge_if_module_configured(
+        RethRpcModule::Admin,
+        AdminConfigApi::new(config_reloader).into_rpc(),
+    )?;
+    if let Some(secret_file) = auth_config.secret_file() {
+        modules.merge_if_module_configured(
+            RethRpcModule::Admin,
+            AdminJwtApi::new(secret_file.clone()).into_rpc(),
+        )?;
+    }
+    modules.merge_if_module_configured(
+        RethRpcModule::Admin,
+        AdminExecutionApi::new(registry.eth_api().execution_limits().clone()).into_rpc(),
+    )?;
+    modules.merge_if_module_configured(
+        RethRpcModule::Admin,
+        AdminRpcModulesApi::new(modules.module_toggle().clone()).into_rpc(),
+    )?;
+
+    // witnesses are generated by re-executing blocks with the executor of the node
+    modules.merge_if_module_configured(
+        RethRpcModule::Reth,
+        RethWitnessApi::new(
+            node.provider().clone(),
+            node.block_executor().clone(),
+            Box::new(node.task_executor().clone()),
+            config.rpc.rpc_max_witnesses_per_response,
+            config.rpc.rpc_max_witness_response_size,
+        )
+        .into_rpc(),
+    )?;
+
+    // the history of the configured accounts is materialized from the canonical notifications
+    if !config.rpc.rpc_account_history.is_empty() {
+        let views = AccountHistoryViews::new(config.rpc.rpc_account_history.iter().copied());
+        node.task_executor().spawn_critical(
+            "account history views task",
+            Box::pin(account_history_views_task(
+                views.clone(),
+                node.provider().clone(),
+                config.rpc.rpc_account_history_backfill,
+                node.provider().canonical_state_stream(),
+            )),
+        );
+        modules.merge_if_module_configured(
+            RethRpcModule::Reth,
+            RethAccountHistoryApi::new(node.provider().clone(), views).into_rpc(),
+        )?;
+    }
+
+    // block production of the dev mode is controlled via the evm namespace on all transports
+    if let Some(mining_handle) = mining_handle {
+        modules.merge_configured(EvmApi::new(mining_handle).into_rpc())?;
+    }
+
+    // methods registered by the exexs during their initialization
+    modules.merge_configured(exex_rpc_modules.take())?;
+
     let mut registry = RpcRegistry { registry };
     let ctx = RpcContext {
         node: node.clone(),
@@ -285,7 +439,47 @@ where
 
     extend_rpc_modules.extend_rpc_modules(ctx)?;
 
-    let server_config = config.rpc.rpc_server_config();
+    let mut server_config = config
+        .rpc
+        .rpc_server_config()
+        .with_method_rate_limits(rpc_config.method_rate_limits.clone());
+    if let Some(budget) = config.rpc.rpc_batch_cost_budget {
+        debug!(target: "reth::cli", budget, costs=?rpc_config.method_costs, "Limiting the cost of RPC batch requests");
+        server_config = server_config.with_batch_cost_budget(Some(
+            BatchCostBudget::new(budget).with_method_costs(rpc_config.method_costs.clone()),
+        ));
+    }
+    if let Some(proxy) = &rpc_config.proxy {
+        debug!(target: "reth::cli", trusted_proxies=?proxy.trusted_proxies, header=%proxy.header, proxy_protocol=proxy.proxy_protocol, "Reading RPC client addresses from trusted proxies");
+        server_config = server_config.with_proxy(proxy)?;
+    }
+    if let Some(ip_rate_limit) = &rpc_config.ip_rate_limit {
+        debug!(target: "reth::cli", quota=%ip_rate_limit.quota, ipv6_prefix_len=ip_rate_limit.ipv6_prefix_len, "Limiting RPC requests per client");
+        server_config = server_config.with_ip_rate_limit(ip_rate_limit);
+    }
+    if !rpc_config.api_keys.is_empty() {
+        debug!(target: "reth::cli", keys=?rpc_config.api_keys.keys(), "Authenticating RPC clients with API keys");
+        server_config = server_config.with_api_keys(ApiKeys::new(&rpc_config.api_keys));
+    }
+    debug!(target: "reth::cli", health=?rpc_config.health, "Serving RPC health endpoints");
+    server_config = server_config.with_health(HealthLayer::new(
+        NodeHealthCheck::new(node.provider().clone(), node.network().clone()),
+        rpc_config.health.clone(),
+    ));
+    if let Some(max_entries) = config.rpc.rpc_response_cache_size {
+        debug!(target: "reth::cli", max_entries, "Caching RPC responses");
+        let cache = ResponseCache::new(max_entries);
+        node.task_executor().spawn(Box::pin(
+            cache.clone().invalidate_on(node.provider().subscribe_to_canonical_state()),
+        ));
+        server_config = server_config.with_response_cache(cache);
+    }
+    let mut http_middleware = HttpMiddlewareLayer::default();
+    extend_rpc_middleware.extend_rpc_middleware(&node, &mut http_middleware)?;
+    if !http_middleware.is_empty() {
+        debug!(target: "reth::cli", layers=http_middleware.len(), "Adding custom RPC http middleware");
+        server_config = server_config.with_http_middleware(http_middleware);
+    }
     let launch_rpc = modules.clone().start_server(server_config).map_ok(|handle| {
         if let Some(path) = handle.ipc_endpoint() {
             info!(target: "reth::cli", %path, "RPC IPC server started");
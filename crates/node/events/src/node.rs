@@ -228,6 +228,12 @@ impl<DB> NodeState<DB> {
 
                 self.current_stage = Some(current_stage);
             }
+            PipelineEvent::Paused { stage_id } => {
+                info!(stage = %stage_id, "Pipeline paused, waiting to be resumed");
+            }
+            PipelineEvent::Resumed { stage_id } => {
+                info!(stage = %stage_id, "Pipeline resumed");
+            }
             _ => (),
         }
     }
@@ -409,6 +409,9 @@ where
         // prepare the state according to the prune mode
         let retention = self.batch_record.bundle_retention(block.number);
         self.executor.state.merge_transitions(retention);
+        if let Some(reverts) = self.executor.state.bundle_state.reverts.last_mut() {
+            self.batch_record.prune_reverts(block.number, reverts);
+        }
 
         // store receipts in the set
         self.batch_record.save_receipts(receipts)?;
@@ -5,7 +5,9 @@ use crate::{
     txpool::{OpTransactionPool, OpTransactionValidator},
     OptimismEngineTypes,
 };
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, PayloadBuildBudget,
+};
 use reth_evm::ConfigureEvm;
 use reth_evm_optimism::{OpExecutorProvider, OptimismEvmConfig};
 use reth_network::{NetworkHandle, NetworkManager};
@@ -174,6 +176,19 @@ where
                 },
             );
 
+            // spawn the state prewarming task
+            if ctx.config().txpool.prewarm_state {
+                ctx.task_executor().spawn(
+                    reth_transaction_pool::prewarm::prewarm_state_cache_future(
+                        client.clone(),
+                        pool.clone(),
+                        ctx.shared_cached_reads().clone(),
+                        ctx.task_executor().clone(),
+                    ),
+                );
+                debug!(target: "reth::cli", "Spawned txpool state prewarming task");
+            }
+
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
                 "txpool maintenance task",
@@ -237,6 +252,13 @@ where
             .interval(conf.interval())
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
+            .max_iterations(conf.max_iterations())
+            .max_build_time(conf.max_build_time())
+            .budget(PayloadBuildBudget {
+                gas: conf.iteration_gas_budget(),
+                time: conf.iteration_time_budget(),
+            })
+            .incremental_rebuild_min_tip(conf.incremental_rebuild_min_tip())
             // no extradata for OP
             .extradata(Default::default());
 
@@ -247,7 +269,8 @@ where
             payload_job_config,
             ctx.chain_spec(),
             payload_builder,
-        );
+        )
+        .with_shared_cached_reads(ctx.shared_cached_reads().clone());
         let (payload_service, payload_builder) =
             PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
 
@@ -24,7 +24,7 @@ use revm::{
     primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
     DatabaseCommit, State,
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use tracing::{debug, trace, warn};
 
 /// Optimism's payload builder
@@ -245,7 +245,7 @@ where
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload, .. } = args;
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
     let state = StateProviderDatabase::new(state_provider);
@@ -258,6 +258,7 @@ where
         parent_block,
         attributes,
         chain_spec,
+        budget,
         ..
     } = config;
 
@@ -405,9 +406,17 @@ where
     }
 
     if !attributes.no_tx_pool {
+        let transactions_gas_limit = budget.gas_limit(block_gas_limit);
+        let budget_deadline = budget.deadline(Instant::now());
         while let Some(pool_tx) = best_txs.next() {
+            // stop adding transactions once the time budget of the attempt is used up
+            if budget_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                trace!(target: "payload_builder", "payload building time budget exhausted");
+                break
+            }
+
             // ensure we still have capacity for this transaction
-            if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
+            if cumulative_gas_used + pool_tx.gas_limit() > transactions_gas_limit {
                 // we can't fit this transaction into the block, so we need to mark it as
                 // invalid which also removes all dependent transaction from
                 // the iterator before we can continue
@@ -590,5 +599,5 @@ where
     // extend the payload with the blob sidecars from the executed txs
     payload.extend_sidecars(blob_sidecars);
 
-    Ok(BuildOutcome::Better { payload, cached_reads })
+    Ok(BuildOutcome::Better { payload, cached_reads, prefix: None })
 }
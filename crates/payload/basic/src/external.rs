@@ -0,0 +1,295 @@
+//! Payloads built by external builders speaking the reth builder protocol, see
+//! [`HttpExternalBuilder`].
+//!
+//! If configured, a [`BasicPayloadJob`](crate::BasicPayloadJob) requests a payload from all
+//! [`ExternalBuilders`] concurrently when it's created, validates the most valuable one against
+//! local execution with [`PayloadBuilder::validate_external_payload`](crate::PayloadBuilder), and
+//! uses it instead of the locally built payload if it pays more fees. Otherwise, the job falls back
+//! to the locally built payload.
+
+use futures_util::future::{self, BoxFuture, FutureExt};
+use jsonrpsee::{
+    core::client::ClientT,
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use reth_chainspec::{ChainSpec, EthereumHardforks};
+use reth_consensus::ConsensusError;
+use reth_payload_builder::error::PayloadBuilderError;
+use reth_payload_primitives::PayloadBuilderAttributes;
+use reth_primitives::{Address, SealedBlock, B256, U256};
+use reth_rpc_types::engine::{ExecutionPayload, ExecutionPayloadEnvelopeV3, PayloadAttributes};
+use reth_rpc_types_compat::engine::payload::try_into_sealed_block;
+use std::{collections::HashSet, fmt, sync::Arc, time::Duration};
+use tracing::{debug, warn};
+
+/// The reth-specific JSON-RPC method [`HttpExternalBuilder`] requests payloads with.
+pub const EXTERNAL_BUILDER_GET_PAYLOAD_METHOD: &str = "reth_builderGetPayload";
+
+/// Default for [`ExternalBuilders::timeout`].
+pub const DEFAULT_EXTERNAL_BUILDER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A payload offered by an external builder.
+#[derive(Debug, Clone)]
+pub struct ExternalBid {
+    /// Name of the builder that built the payload.
+    pub builder: String,
+    /// The built block.
+    pub block: SealedBlock,
+    /// Value of the payload to the fee recipient claimed by the builder.
+    ///
+    /// Only used to rank the bids, the fees of the payload are determined by executing it.
+    pub value: U256,
+}
+
+/// Reasons for rejecting a payload of an external builder.
+#[derive(Debug, thiserror::Error)]
+pub enum ExternalPayloadError {
+    /// The payload builder doesn't support payloads of external builders.
+    #[error("external payloads are not supported by the payload builder")]
+    Unsupported,
+    /// The payload isn't built on top of the requested parent block.
+    #[error("external payload parent {got} doesn't match the requested parent {expected}")]
+    ParentMismatch {
+        /// Parent of the payload.
+        got: B256,
+        /// Requested parent.
+        expected: B256,
+    },
+    /// The payload doesn't match the requested payload attributes.
+    #[error("external payload doesn't match the payload attributes: {0}")]
+    AttributesMismatch(&'static str),
+    /// The header of the payload is invalid on top of its parent.
+    #[error("external payload header is invalid: {0}")]
+    InvalidHeader(#[from] ConsensusError),
+    /// The payload contains blob transactions, whose sidecars are not available to the node.
+    #[error("external payloads with blob transactions are not supported")]
+    BlobTransactions,
+    /// A transaction signature of the payload is invalid.
+    #[error("external payload contains a transaction with an invalid signature")]
+    InvalidSignature,
+    /// The gas used by the payload doesn't match its header.
+    #[error("external payload gas used {got} doesn't match the header gas used {expected}")]
+    GasUsedMismatch {
+        /// Gas used by executing the payload.
+        got: u64,
+        /// Gas used in the header of the payload.
+        expected: u64,
+    },
+    /// The receipts root of the payload doesn't match its header.
+    #[error("external payload receipts root doesn't match its header")]
+    ReceiptsRootMismatch,
+    /// The requests root of the payload doesn't match its header.
+    #[error("external payload requests root doesn't match its header")]
+    RequestsRootMismatch,
+    /// The state root of the payload doesn't match its header.
+    #[error("external payload state root doesn't match its header")]
+    StateRootMismatch,
+}
+
+/// A builder outside of the node that builds payloads on request.
+pub trait ExternalBuilder: fmt::Debug + Send + Sync {
+    /// Returns the name of the builder, used in logs.
+    fn name(&self) -> &str;
+
+    /// Requests a payload on top of the `parent` block for the payload attributes of a fcU.
+    ///
+    /// Returns `None` if the builder has no payload to offer.
+    fn request_payload(
+        &self,
+        parent: B256,
+        attributes: PayloadAttributes,
+    ) -> BoxFuture<'static, Result<Option<ExternalBid>, PayloadBuilderError>>;
+}
+
+/// [`ExternalBuilder`] that requests payloads over JSON-RPC via HTTP.
+///
+/// The builder-API of relays (`/eth/v1/builder/header` and `/eth/v1/builder/blinded_blocks`) only
+/// reveals a payload for a blinded block signed by the proposer, which the execution client can't
+/// sign. Payloads are therefore requested with the reth-specific
+/// [`EXTERNAL_BUILDER_GET_PAYLOAD_METHOD`]:
+///
+/// ```text
+/// request:  {"jsonrpc": "2.0", "id": 1, "method": "reth_builderGetPayload",
+///            "params": [<parent block hash>, <PayloadAttributesV3>]}
+/// response: {"jsonrpc": "2.0", "id": 1, "result": <ExecutionPayloadEnvelopeV3 | null>}
+/// ```
+///
+/// The [`PayloadAttributes`] are the ones of the fcU, encoded like in
+/// `engine_forkchoiceUpdatedV3`. The [`ExecutionPayloadEnvelopeV3`] is encoded like the result of
+/// `engine_getPayloadV3`, and its `blockValue` is only used to rank the payloads of several
+/// builders. A builder without a payload to offer returns `null`.
+#[derive(Debug, Clone)]
+pub struct HttpExternalBuilder {
+    /// URL of the builder, used as its name.
+    url: String,
+    client: HttpClient,
+}
+
+impl HttpExternalBuilder {
+    /// Creates a new builder that requests payloads from the given URL.
+    pub fn new(url: impl Into<String>) -> Result<Self, PayloadBuilderError> {
+        let url = url.into();
+        let client =
+            HttpClientBuilder::default().build(&url).map_err(PayloadBuilderError::other)?;
+        Ok(Self { url, client })
+    }
+}
+
+impl ExternalBuilder for HttpExternalBuilder {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    fn request_payload(
+        &self,
+        parent: B256,
+        attributes: PayloadAttributes,
+    ) -> BoxFuture<'static, Result<Option<ExternalBid>, PayloadBuilderError>> {
+        let (builder, client) = (self.url.clone(), self.client.clone());
+        async move {
+            let parent_beacon_block_root = attributes.parent_beacon_block_root;
+            let envelope: Option<ExecutionPayloadEnvelopeV3> = client
+                .request(EXTERNAL_BUILDER_GET_PAYLOAD_METHOD, rpc_params![parent, attributes])
+                .await
+                .map_err(PayloadBuilderError::other)?;
+            let Some(envelope) = envelope else { return Ok(None) };
+
+            let block = try_into_sealed_block(
+                ExecutionPayload::V3(envelope.execution_payload),
+                parent_beacon_block_root,
+            )
+            .map_err(PayloadBuilderError::other)?;
+            Ok(Some(ExternalBid { builder, block, value: envelope.block_value }))
+        }
+        .boxed()
+    }
+}
+
+/// Selects the fcUs for which payloads are requested from external builders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ExternalBuilderSelection {
+    /// Request payloads for all fcUs.
+    #[default]
+    All,
+    /// Request payloads only for fcUs with one of the given suggested fee recipients, e.g. the
+    /// validators that opted into external builders.
+    FeeRecipients(HashSet<Address>),
+}
+
+impl ExternalBuilderSelection {
+    /// Returns `true` if payloads should be requested for a fcU with the given suggested fee
+    /// recipient.
+    pub fn is_selected(&self, fee_recipient: Address) -> bool {
+        match self {
+            Self::All => true,
+            Self::FeeRecipients(fee_recipients) => fee_recipients.contains(&fee_recipient),
+        }
+    }
+}
+
+/// The external builders payloads are requested from by the
+/// [`BasicPayloadJobGenerator`](crate::BasicPayloadJobGenerator).
+#[derive(Debug, Clone)]
+pub struct ExternalBuilders {
+    builders: Vec<Arc<dyn ExternalBuilder>>,
+    selection: ExternalBuilderSelection,
+    timeout: Duration,
+}
+
+impl ExternalBuilders {
+    /// Creates a new set of external builders, which are requested for all fcUs.
+    pub fn new(builders: Vec<Arc<dyn ExternalBuilder>>) -> Self {
+        Self {
+            builders,
+            selection: ExternalBuilderSelection::default(),
+            timeout: DEFAULT_EXTERNAL_BUILDER_TIMEOUT,
+        }
+    }
+
+    /// Sets the fcUs for which payloads are requested.
+    pub fn with_selection(mut self, selection: ExternalBuilderSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets how long to wait for the payloads of the builders.
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns how long to wait for the payloads of the builders.
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns `true` if payloads should be requested for a fcU with the given attributes.
+    pub fn is_selected<Attributes: PayloadBuilderAttributes>(
+        &self,
+        attributes: &Attributes,
+    ) -> bool {
+        !self.builders.is_empty() &&
+            self.selection.is_selected(attributes.suggested_fee_recipient())
+    }
+
+    /// Requests payloads for the fcU from all builders concurrently, and returns the bids received
+    /// within the [timeout](Self::timeout), ordered by descending value.
+    pub async fn request_bids<Attributes: PayloadBuilderAttributes>(
+        &self,
+        chain_spec: &ChainSpec,
+        attributes: &Attributes,
+    ) -> Vec<ExternalBid> {
+        let request = PayloadAttributes {
+            timestamp: attributes.timestamp(),
+            prev_randao: attributes.prev_randao(),
+            suggested_fee_recipient: attributes.suggested_fee_recipient(),
+            withdrawals: chain_spec
+                .is_shanghai_active_at_timestamp(attributes.timestamp())
+                .then(|| attributes.withdrawals().to_vec()),
+            parent_beacon_block_root: attributes.parent_beacon_block_root(),
+        };
+
+        let requests = self.builders.iter().map(|builder| {
+            let payload = builder.request_payload(attributes.parent(), request.clone());
+            tokio::time::timeout(self.timeout, payload).map(move |result| (builder.name(), result))
+        });
+
+        let mut bids = Vec::new();
+        for (builder, result) in future::join_all(requests).await {
+            match result {
+                Ok(Ok(Some(bid))) => {
+                    debug!(target: "payload_builder", %builder, value = %bid.value, "received external payload");
+                    bids.push(bid);
+                }
+                Ok(Ok(None)) => {
+                    debug!(target: "payload_builder", %builder, "no external payload offered");
+                }
+                Ok(Err(err)) => {
+                    warn!(target: "payload_builder", %builder, %err, "failed to request external payload");
+                }
+                Err(_) => {
+                    warn!(target: "payload_builder", %builder, "external payload request timed out");
+                }
+            }
+        }
+
+        bids.sort_unstable_by(|a, b| b.value.cmp(&a.value));
+        bids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_fcus_by_fee_recipient() {
+        let fee_recipient = Address::random();
+        assert!(ExternalBuilderSelection::All.is_selected(fee_recipient));
+
+        let selection = ExternalBuilderSelection::FeeRecipients(HashSet::from([fee_recipient]));
+        assert!(selection.is_selected(fee_recipient));
+        assert!(!selection.is_selected(Address::random()));
+    }
+}
@@ -9,48 +9,63 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use crate::metrics::PayloadBuilderMetrics;
+pub use external::{
+    ExternalBid, ExternalBuilder, ExternalBuilderSelection, ExternalBuilders, ExternalPayloadError,
+    HttpExternalBuilder, DEFAULT_EXTERNAL_BUILDER_TIMEOUT, EXTERNAL_BUILDER_GET_PAYLOAD_METHOD,
+};
 use futures_core::ready;
 use futures_util::FutureExt;
 use reth_chainspec::{ChainSpec, EthereumHardforks};
 use reth_payload_builder::{
-    database::CachedReads, error::PayloadBuilderError, KeepPayloadJobAlive, PayloadId, PayloadJob,
-    PayloadJobGenerator,
+    database::{CachedReads, SharedCachedReads},
+    error::PayloadBuilderError,
+    KeepPayloadJobAlive, PayloadId, PayloadJob, PayloadJobGenerator,
 };
 use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
 use reth_primitives::{
     constants::{EMPTY_WITHDRAWALS, RETH_CLIENT_VERSION, SLOT_DURATION},
-    proofs, BlockNumberOrTag, Bytes, Request, SealedBlock, Withdrawals, B256, U256,
+    proofs, Address, BlobTransactionSidecar, BlockNumberOrTag, Bytes, Receipt, Request,
+    SealedBlock, TransactionSigned, TxHash, Withdrawals, B256, U256,
 };
 use reth_provider::{
     BlockReaderIdExt, BlockSource, CanonStateNotification, ProviderError, StateProviderFactory,
 };
-use reth_revm::state_change::{
-    apply_beacon_root_contract_call, apply_withdrawal_requests_contract_call,
-    post_block_withdrawals_balance_increments,
+use reth_revm::{
+    state_change::{apply_beacon_root_contract_call, post_block_withdrawals_balance_increments},
+    system_calls::{SystemCallBlock, SystemCaller},
 };
 use reth_tasks::TaskSpawner;
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{BestTransactionsAttributes, TransactionListenerKind, TransactionPool};
 use revm::{
+    db::BundleState,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg},
     Database, DatabaseCommit, Evm, State,
 };
 use std::{
+    collections::HashMap,
     fmt,
     future::Future,
     ops::Deref,
     pin::Pin,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tokio::{
-    sync::{oneshot, Semaphore},
+    sync::{mpsc::Receiver, oneshot, Semaphore},
     time::{Interval, Sleep},
 };
 use tracing::{debug, trace, warn};
 
+mod external;
 mod metrics;
 
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 /// The [`PayloadJobGenerator`] that creates [`BasicPayloadJob`]s.
 #[derive(Debug)]
 pub struct BasicPayloadJobGenerator<Client, Pool, Tasks, Builder> {
@@ -72,6 +87,10 @@ pub struct BasicPayloadJobGenerator<Client, Pool, Tasks, Builder> {
     builder: Builder,
     /// Stored `cached_reads` for new payload jobs.
     pre_cached: Option<PrecachedState>,
+    /// External builders payloads are requested from, if any.
+    external_builders: Option<Arc<ExternalBuilders>>,
+    /// Reads of the state of recent blocks shared with other tasks, e.g. block validation.
+    shared_cached_reads: Option<SharedCachedReads>,
 }
 
 // === impl BasicPayloadJobGenerator ===
@@ -96,9 +115,32 @@ impl<Client, Pool, Tasks, Builder> BasicPayloadJobGenerator<Client, Pool, Tasks,
             chain_spec,
             builder,
             pre_cached: None,
+            external_builders: None,
+            shared_cached_reads: None,
         }
     }
 
+    /// Requests payloads from the given external builders for every new payload job whose
+    /// attributes they're [selected](ExternalBuilders::is_selected) for.
+    ///
+    /// The best external payload that passes
+    /// [validation](PayloadBuilder::validate_external_payload) replaces the locally built payload
+    /// if it pays more fees.
+    pub fn with_external_builders(mut self, external_builders: ExternalBuilders) -> Self {
+        self.external_builders = Some(Arc::new(external_builders));
+        self
+    }
+
+    /// Shares the reads of the state of recent blocks with other tasks.
+    ///
+    /// New payload jobs start with the shared reads of their parent, and add their own reads to
+    /// the cache once they're finalized, so that they're reused when the payload, or a competing
+    /// one, is validated.
+    pub fn with_shared_cached_reads(mut self, shared_cached_reads: SharedCachedReads) -> Self {
+        self.shared_cached_reads = Some(shared_cached_reads);
+        self
+    }
+
     /// Returns the maximum duration a job should be allowed to run.
     ///
     /// This adheres to the following specification:
@@ -129,9 +171,18 @@ impl<Client, Pool, Tasks, Builder> BasicPayloadJobGenerator<Client, Pool, Tasks,
     }
 
     /// Returns the pre-cached reads for the given parent block if it matches the cached state's
-    /// block.
+    /// block, merged with the shared reads of the parent block, if any.
     fn maybe_pre_cached(&self, parent: B256) -> Option<CachedReads> {
-        self.pre_cached.as_ref().filter(|pc| pc.block == parent).map(|pc| pc.cached.clone())
+        let pre_cached =
+            self.pre_cached.as_ref().filter(|pc| pc.block == parent).map(|pc| pc.cached.clone());
+        let shared = self.shared_cached_reads.as_ref().and_then(|shared| shared.get(parent));
+        match (pre_cached, shared) {
+            (Some(mut pre_cached), Some(shared)) => {
+                pre_cached.extend(shared);
+                Some(pre_cached)
+            }
+            (pre_cached, shared) => pre_cached.or(shared),
+        }
     }
 }
 
@@ -169,12 +220,15 @@ where
             block.seal(attributes.parent())
         };
 
+        let budget = self.config.job_budget(attributes.suggested_fee_recipient());
         let config = PayloadConfig::new(
             Arc::new(parent_block),
             self.config.extradata.clone(),
             attributes,
             Arc::clone(&self.chain_spec),
-        );
+        )
+        .with_budget(budget.attempt)
+        .with_resumable(self.config.incremental_rebuild_min_tip.is_some());
 
         let until = self.job_deadline(config.attributes.timestamp());
         let deadline = Box::pin(tokio::time::sleep_until(until));
@@ -188,17 +242,38 @@ where
             executor: self.executor.clone(),
             deadline,
             interval: tokio::time::interval(self.config.interval),
+            budget,
+            iterations: 0,
+            build_time: BuildTime::default(),
             best_payload: None,
             pending_block: None,
             cached_reads,
             payload_task_guard: self.payload_task_guard.clone(),
             metrics: Default::default(),
             builder: self.builder.clone(),
+            external_payload: None,
+            new_transactions: self.config.incremental_rebuild_min_tip.map(|min_tip| {
+                (min_tip, self.pool.pending_transactions_listener_for(TransactionListenerKind::All))
+            }),
+            rebuild_requested: false,
+            prefix: None,
+            started_at: Instant::now(),
+            improvements: Vec::new(),
+            shared_cached_reads: self.shared_cached_reads.clone(),
+            empty_payload_min_tip: self.config.empty_payload_min_tip,
         };
 
         // start the first job right away
         job.spawn_build_job();
 
+        if let Some(external_builders) = self
+            .external_builders
+            .as_ref()
+            .filter(|builders| builders.is_selected(&job.config.attributes))
+        {
+            job.spawn_external_payload_job(Arc::clone(external_builders));
+        }
+
         Ok(job)
     }
 
@@ -267,6 +342,17 @@ pub struct BasicPayloadJobGeneratorConfig {
     deadline: Duration,
     /// Maximum number of tasks to spawn for building a payload.
     max_payload_tasks: usize,
+    /// Resource limits of every payload job.
+    job_budget: PayloadJobBudget,
+    /// Resource limits of the payload jobs of specific suggested fee recipients, which replace
+    /// the default limits for these jobs.
+    fee_recipient_budgets: HashMap<Address, PayloadJobBudget>,
+    /// Minimum priority fee per gas of new pending transactions that trigger an incremental
+    /// rebuild, or `None` to only rebuild on the interval.
+    incremental_rebuild_min_tip: Option<u128>,
+    /// Minimum effective priority fee per gas of a pool transaction for building a non-empty
+    /// payload, or `None` to always build payloads with the transactions of the pool.
+    empty_payload_min_tip: Option<u128>,
 }
 
 // === impl BasicPayloadJobGeneratorConfig ===
@@ -302,6 +388,94 @@ impl BasicPayloadJobGeneratorConfig {
         self.extradata = extradata;
         self
     }
+
+    /// Sets the maximum number of payloads a job builds, after which it keeps the best payload
+    /// until it's resolved.
+    ///
+    /// Defaults to `None`, i.e. a new payload is built every [interval](Self::interval) until the
+    /// deadline.
+    ///
+    /// # Panics
+    ///
+    /// If `max_iterations` is `Some(0)`.
+    pub fn max_iterations(mut self, max_iterations: Option<usize>) -> Self {
+        assert!(max_iterations != Some(0), "max_iterations must be greater than 0");
+        self.job_budget.max_iterations = max_iterations;
+        self
+    }
+
+    /// Builds a single best-effort payload per job, e.g. on low-power machines.
+    ///
+    /// This is the same as [`Self::max_iterations`] with `Some(1)`.
+    pub fn build_once(self) -> Self {
+        self.max_iterations(Some(1))
+    }
+
+    /// Sets the limits of every payload building attempt.
+    pub const fn budget(mut self, budget: PayloadBuildBudget) -> Self {
+        self.job_budget.attempt = budget;
+        self
+    }
+
+    /// Sets the maximum total time a job spends building payloads, after which it keeps the best
+    /// payload until it's resolved.
+    ///
+    /// Defaults to `None`, i.e. a job builds payloads until the deadline.
+    pub const fn max_build_time(mut self, build_time: Option<Duration>) -> Self {
+        self.job_budget.build_time = build_time;
+        self
+    }
+
+    /// Sets the resource limits of the payload jobs with the given suggested fee recipient, e.g.
+    /// to give the validators served by the node, or a local builder sidecar, different budgets.
+    ///
+    /// These replace the limits configured with [`Self::max_iterations`], [`Self::budget`] and
+    /// [`Self::max_build_time`] for these jobs.
+    ///
+    /// # Panics
+    ///
+    /// If the maximum number of iterations of the budget is `Some(0)`.
+    pub fn fee_recipient_budget(
+        mut self,
+        fee_recipient: Address,
+        budget: PayloadJobBudget,
+    ) -> Self {
+        assert!(budget.max_iterations != Some(0), "max_iterations must be greater than 0");
+        self.fee_recipient_budgets.insert(fee_recipient, budget);
+        self
+    }
+
+    /// Returns the resource limits of a payload job with the given suggested fee recipient.
+    pub fn job_budget(&self, fee_recipient: Address) -> PayloadJobBudget {
+        self.fee_recipient_budgets.get(&fee_recipient).copied().unwrap_or(self.job_budget)
+    }
+
+    /// Subscribes payload jobs to new pending transactions of the pool, and rebuilds the payload
+    /// right away when a transaction with at least the given priority fee per gas arrives.
+    ///
+    /// These rebuilds are incremental: they resume from the transactions already executed for the
+    /// best payload, see [`ExecutedPrefix`], and only execute the new tail of the block. The
+    /// payload is still rebuilt from scratch every [interval](Self::interval).
+    ///
+    /// Defaults to `None`, i.e. payloads are only rebuilt on the interval.
+    pub const fn incremental_rebuild_min_tip(mut self, min_tip: Option<u128>) -> Self {
+        self.incremental_rebuild_min_tip = min_tip;
+        self
+    }
+
+    /// Skips the full payload build while the pool has no transaction with at least the given
+    /// effective priority fee per gas, e.g. on quiet devnets or for latency-sensitive setups.
+    ///
+    /// Instead, the job builds an empty payload right away with
+    /// [`PayloadBuilder::build_empty_payload`] and keeps it as its best payload, until a
+    /// transaction above the threshold is in the pool. This should only be used with builders
+    /// whose empty payloads are complete, i.e. not with attributes that mandate transactions.
+    ///
+    /// Defaults to `None`, i.e. payloads are always built with the transactions of the pool.
+    pub const fn empty_payload_min_tip(mut self, min_tip: Option<u128>) -> Self {
+        self.empty_payload_min_tip = min_tip;
+        self
+    }
 }
 
 impl Default for BasicPayloadJobGeneratorConfig {
@@ -312,10 +486,72 @@ impl Default for BasicPayloadJobGeneratorConfig {
             // 12s slot time
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            job_budget: PayloadJobBudget::default(),
+            fee_recipient_budgets: HashMap::new(),
+            incremental_rebuild_min_tip: None,
+            empty_payload_min_tip: None,
         }
     }
 }
 
+/// Limits of a single payload building attempt, which builders apply when they fill a payload with
+/// transactions from the pool.
+///
+/// By default, attempts are only limited by the block gas limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadBuildBudget {
+    /// Maximum gas used by the transactions of a payload, if lower than the block gas limit.
+    pub gas: Option<u64>,
+    /// Maximum time spent adding transactions from the pool to a payload.
+    pub time: Option<Duration>,
+}
+
+impl PayloadBuildBudget {
+    /// Returns the gas available for transactions in a block with the given gas limit.
+    pub fn gas_limit(&self, block_gas_limit: u64) -> u64 {
+        self.gas.map_or(block_gas_limit, |gas| gas.min(block_gas_limit))
+    }
+
+    /// Returns the instant after which an attempt that starts adding transactions at `start`
+    /// should stop, if the time is limited.
+    pub fn deadline(&self, start: Instant) -> Option<Instant> {
+        self.time.map(|time| start + time)
+    }
+}
+
+/// Limits of the resources a payload job uses across all of its payload building attempts.
+///
+/// Every job has its own budget, so that concurrent jobs with different attributes, e.g. of
+/// different validators, don't exhaust each other's share of the payload building tasks.
+///
+/// By default, jobs build a new payload every interval until the deadline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadJobBudget {
+    /// Maximum number of payloads the job builds, if limited.
+    pub max_iterations: Option<usize>,
+    /// Maximum total time the job spends building payloads, if limited.
+    pub build_time: Option<Duration>,
+    /// Limits of every payload building attempt of the job.
+    pub attempt: PayloadBuildBudget,
+}
+
+/// The time a payload job spent building payloads, updated by its build tasks.
+#[derive(Debug, Clone, Default)]
+struct BuildTime(Arc<AtomicU64>);
+
+impl BuildTime {
+    /// Adds the duration of a finished build attempt.
+    fn record(&self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.0.fetch_add(micros, Ordering::Relaxed);
+    }
+
+    /// Returns the total time spent building payloads.
+    fn get(&self) -> Duration {
+        Duration::from_micros(self.0.load(Ordering::Relaxed))
+    }
+}
+
 /// A basic payload job that continuously builds a payload with the best transactions from the pool.
 #[derive(Debug)]
 pub struct BasicPayloadJob<Client, Pool, Tasks, Builder>
@@ -334,6 +570,12 @@ where
     deadline: Pin<Box<Sleep>>,
     /// The interval at which the job should build a new payload after the last.
     interval: Interval,
+    /// Resource limits of the job.
+    budget: PayloadJobBudget,
+    /// Number of payload build tasks the job spawned.
+    iterations: usize,
+    /// Time the build tasks of the job spent building payloads.
+    build_time: BuildTime,
     /// The best payload so far.
     best_payload: Option<Builder::BuiltPayload>,
     /// Receiver for the block that is currently being built.
@@ -351,6 +593,27 @@ where
     ///
     /// See [`PayloadBuilder`]
     builder: Builder,
+    /// Receiver for the best validated payload of the external builders, if requested.
+    external_payload: Option<oneshot::Receiver<Option<Builder::BuiltPayload>>>,
+    /// The minimum priority fee per gas of new pending transactions that trigger an incremental
+    /// rebuild, and the receiver of their hashes, if incremental rebuilds are enabled.
+    new_transactions: Option<(u128, Receiver<TxHash>)>,
+    /// Whether a new transaction requested an incremental rebuild.
+    rebuild_requested: bool,
+    /// The executed transactions of the best locally built payload, which incremental rebuilds
+    /// resume from.
+    prefix: Option<ExecutedPrefix>,
+    /// When the job was created.
+    started_at: Instant,
+    /// The fees of every improvement of the best payload, and when it happened relative to the
+    /// start of the job.
+    improvements: Vec<(Duration, U256)>,
+    /// Reads of the state of recent blocks shared with other tasks, which the reads of the job
+    /// are added to once it's finalized.
+    shared_cached_reads: Option<SharedCachedReads>,
+    /// Minimum effective priority fee per gas of a pool transaction for building a non-empty
+    /// payload, if the empty payload fast path is enabled.
+    empty_payload_min_tip: Option<u128>,
 }
 
 impl<Client, Pool, Tasks, Builder> BasicPayloadJob<Client, Pool, Tasks, Builder>
@@ -362,9 +625,68 @@ where
     <Builder as PayloadBuilder<Pool, Client>>::Attributes: Unpin + Clone,
     <Builder as PayloadBuilder<Pool, Client>>::BuiltPayload: Unpin + Clone,
 {
-    /// Spawns a new payload build task.
+    /// Returns `true` if the job can build another payload without exceeding its
+    /// [budget](PayloadJobBudget).
+    fn can_build(&self) -> bool {
+        self.budget.max_iterations.map_or(true, |max_iterations| self.iterations < max_iterations) &&
+            self.budget.build_time.map_or(true, |build_time| self.build_time.get() < build_time)
+    }
+
+    /// Spawns a new payload build task that builds the payload from scratch.
+    ///
+    /// If the pool has no transactions worth including, see
+    /// [`BasicPayloadJobGeneratorConfig::empty_payload_min_tip`], an empty payload is built
+    /// instead, unless the job already has a best payload.
     fn spawn_build_job(&mut self) {
-        trace!(target: "payload_builder", "spawn new payload build task");
+        if !self.has_valuable_transactions() {
+            if self.best_payload.is_none() {
+                self.spawn_empty_payload_job();
+            } else {
+                trace!(target: "payload_builder", "no valuable transactions in the pool, keeping best payload");
+            }
+            return
+        }
+        self.spawn_build_job_from(None)
+    }
+
+    /// Returns `true` if the pool has a transaction paying at least the minimum effective priority
+    /// fee per gas for a non-empty payload, or if the empty payload fast path is disabled.
+    fn has_valuable_transactions(&self) -> bool {
+        let Some(min_tip) = self.empty_payload_min_tip else { return true };
+        let block_env = &self.config.initialized_block_env;
+        let base_fee = block_env.basefee.to::<u64>();
+        let attributes = BestTransactionsAttributes::new(
+            base_fee,
+            block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
+        );
+        self.pool
+            .best_transactions_with_attributes(attributes)
+            .any(|tx| tx.effective_tip_per_gas(base_fee).is_some_and(|tip| tip >= min_tip))
+    }
+
+    /// Spawns a task that builds an empty payload, without executing any transactions of the pool.
+    fn spawn_empty_payload_job(&mut self) {
+        trace!(target: "payload_builder", "no valuable transactions in the pool, spawn empty payload build task");
+        let (tx, rx) = oneshot::channel();
+        let client = self.client.clone();
+        let config = self.config.clone();
+        let cached_reads = self.cached_reads.take().unwrap_or_default();
+        let builder = self.builder.clone();
+        self.metrics.inc_initiated_payload_builds();
+        self.executor.spawn_blocking(Box::pin(async move {
+            let result = builder
+                .build_empty_payload(&client, config)
+                .map(|payload| BuildOutcome::Better { payload, cached_reads, prefix: None });
+            let _ = tx.send(result);
+        }));
+
+        self.pending_block = Some(PendingPayload::new(Cancelled::default(), rx));
+    }
+
+    /// Spawns a new payload build task, which resumes from the given executed prefix, if any.
+    fn spawn_build_job_from(&mut self, prefix: Option<ExecutedPrefix>) {
+        trace!(target: "payload_builder", incremental = prefix.is_some(), "spawn new payload build task");
+        self.iterations += 1;
         let (tx, rx) = oneshot::channel();
         let client = self.client.clone();
         let pool = self.pool.clone();
@@ -376,9 +698,11 @@ where
         self.metrics.inc_initiated_payload_builds();
         let cached_reads = self.cached_reads.take().unwrap_or_default();
         let builder = self.builder.clone();
+        let build_time = self.build_time.clone();
         self.executor.spawn_blocking(Box::pin(async move {
             // acquire the permit for executing the task
             let _permit = guard.acquire().await;
+            let started_at = Instant::now();
             let args = BuildArguments {
                 client,
                 pool,
@@ -386,13 +710,136 @@ where
                 config: payload_config,
                 cancel,
                 best_payload,
+                prefix,
             };
             let result = builder.try_build(args);
+            // only the time spent building counts against the budget, not waiting for the permit
+            build_time.record(started_at.elapsed());
             let _ = tx.send(result);
         }));
 
         self.pending_block = Some(PendingPayload { _cancel, payload: rx });
     }
+
+    /// Spawns a task that requests payloads from the external builders and validates them, most
+    /// valuable first, until one is valid.
+    fn spawn_external_payload_job(&mut self, external_builders: Arc<ExternalBuilders>) {
+        trace!(target: "payload_builder", "spawn external payload request task");
+        let (tx, rx) = oneshot::channel();
+        let client = self.client.clone();
+        let config = self.config.clone();
+        let builder = self.builder.clone();
+        self.executor.spawn_blocking(Box::pin(async move {
+            let bids = external_builders.request_bids(&config.chain_spec, &config.attributes).await;
+            let payload = bids.into_iter().find_map(|bid| {
+                let external_builder = bid.builder.clone();
+                match builder.validate_external_payload(&client, &config, bid) {
+                    Ok(payload) => {
+                        debug!(target: "payload_builder", builder = %external_builder, value = %payload.fees(), "validated external payload");
+                        Some(payload)
+                    }
+                    Err(error) => {
+                        warn!(target: "payload_builder", builder = %external_builder, %error, "rejected external payload");
+                        None
+                    }
+                }
+            });
+            let _ = tx.send(payload);
+        }));
+
+        self.external_payload = Some(rx);
+    }
+
+    /// Drains the new pending transactions of the pool, and returns `true` if any of them pays at
+    /// least the minimum priority fee per gas for an incremental rebuild.
+    fn poll_new_transactions(&mut self, cx: &mut Context<'_>) -> bool {
+        let base_fee = self.config.initialized_block_env.basefee.to::<u64>();
+        let Some((min_tip, new_transactions)) = self.new_transactions.as_mut() else {
+            return false
+        };
+
+        let mut rebuild = false;
+        let mut closed = false;
+        while let Poll::Ready(hash) = new_transactions.poll_recv(cx) {
+            let Some(hash) = hash else {
+                closed = true;
+                break
+            };
+            rebuild |= self
+                .pool
+                .get(&hash)
+                .and_then(|tx| tx.effective_tip_per_gas(base_fee))
+                .is_some_and(|tip| tip >= *min_tip);
+        }
+
+        if closed {
+            self.new_transactions = None;
+        }
+        rebuild
+    }
+
+    /// Replaces the best payload with the given external payload if it pays more fees.
+    fn on_external_payload(&mut self, payload: Builder::BuiltPayload) {
+        if self.best_payload.as_ref().map_or(true, |best| payload.fees() > best.fees()) {
+            debug!(target: "payload_builder", value = %payload.fees(), "using external payload");
+            self.set_best_payload(payload);
+        } else {
+            debug!(target: "payload_builder", worse_fees = %payload.fees(), "skipped worse external payload");
+        }
+    }
+
+    /// Sets the new best payload and records the improvement.
+    fn set_best_payload(&mut self, payload: Builder::BuiltPayload) {
+        let elapsed = self.started_at.elapsed();
+        let fees = payload.fees();
+        debug!(target: "payload_builder", id=%self.config.payload_id(), ?elapsed, value = %fees, "improved best payload");
+        self.metrics.best_payload_fees.set(f64::from(fees));
+        self.improvements.push((elapsed, fees));
+        self.best_payload = Some(payload);
+    }
+
+    /// Records the metrics of the job once it ends for the given reason, and shares its cached
+    /// reads.
+    fn on_finalized(&self, reason: PayloadJobFinalization) {
+        if let (Some(shared), Some(cached_reads)) = (&self.shared_cached_reads, &self.cached_reads)
+        {
+            shared.insert(self.config.parent_block.hash(), cached_reads.clone());
+        }
+
+        self.metrics.job_iterations.record(self.iterations as f64);
+        match reason {
+            PayloadJobFinalization::Deadline => {
+                self.metrics.jobs_finalized_by_deadline.increment(1)
+            }
+            PayloadJobFinalization::Resolve => self.metrics.jobs_finalized_by_resolve.increment(1),
+        }
+
+        let last_improvement = self.improvements.last().map(|(elapsed, _)| *elapsed);
+        if let Some(last_improvement) = last_improvement {
+            self.metrics.last_improvement_seconds.record(last_improvement.as_secs_f64());
+        }
+
+        debug!(
+            target: "payload_builder",
+            id=%self.config.payload_id(),
+            ?reason,
+            iterations = self.iterations,
+            build_time = ?self.build_time.get(),
+            best_value = ?self.improvements.last().map(|(_, fees)| fees),
+            ?last_improvement,
+            improvements = ?self.improvements,
+            "payload job finalized"
+        );
+    }
+}
+
+/// The reason a [`BasicPayloadJob`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PayloadJobFinalization {
+    /// The deadline of the job was reached.
+    Deadline,
+    /// The payload of the job was resolved.
+    Resolve,
 }
 
 impl<Client, Pool, Tasks, Builder> Future for BasicPayloadJob<Client, Pool, Tasks, Builder>
@@ -412,27 +859,50 @@ where
         // check if the deadline is reached
         if this.deadline.as_mut().poll(cx).is_ready() {
             trace!(target: "payload_builder", "payload building deadline reached");
+            this.on_finalized(PayloadJobFinalization::Deadline);
             return Poll::Ready(Ok(()))
         }
 
         // check if the interval is reached
         while this.interval.poll_tick(cx).is_ready() {
-            // start a new job if there is no pending block and we haven't reached the deadline
-            if this.pending_block.is_none() {
+            // start a new job if there is no pending block and we haven't reached the deadline or
+            // the maximum number of iterations
+            if this.pending_block.is_none() && this.can_build() {
                 this.spawn_build_job();
             }
         }
 
+        // rebuild the tail of the payload right away if a new valuable transaction arrived
+        if this.poll_new_transactions(cx) {
+            this.rebuild_requested = true;
+        }
+        if this.rebuild_requested && this.pending_block.is_none() && this.can_build() {
+            trace!(target: "payload_builder", "new transactions arrived, rebuilding payload");
+            this.rebuild_requested = false;
+            let prefix = this.prefix.clone();
+            this.spawn_build_job_from(prefix);
+        }
+
+        // poll the external payload
+        if let Some(mut rx) = this.external_payload.take() {
+            match rx.poll_unpin(cx) {
+                Poll::Ready(Ok(Some(payload))) => this.on_external_payload(payload),
+                Poll::Ready(_) => {}
+                Poll::Pending => this.external_payload = Some(rx),
+            }
+        }
+
         // poll the pending block
         if let Some(mut fut) = this.pending_block.take() {
             match fut.poll_unpin(cx) {
                 Poll::Ready(Ok(outcome)) => {
                     this.interval.reset();
                     match outcome {
-                        BuildOutcome::Better { payload, cached_reads } => {
+                        BuildOutcome::Better { payload, cached_reads, prefix } => {
                             this.cached_reads = Some(cached_reads);
                             debug!(target: "payload_builder", value = %payload.fees(), "built better payload");
-                            this.best_payload = Some(payload);
+                            this.set_best_payload(payload);
+                            this.prefix = prefix;
                         }
                         BuildOutcome::Aborted { fees, cached_reads } => {
                             this.cached_reads = Some(cached_reads);
@@ -452,6 +922,11 @@ where
                     this.pending_block = Some(fut);
                 }
             }
+
+            // start the requested rebuild now that the pending one finished
+            if this.rebuild_requested && this.pending_block.is_none() && this.can_build() {
+                cx.waker().wake_by_ref();
+            }
         }
 
         Poll::Pending
@@ -490,6 +965,14 @@ where
     }
 
     fn resolve(&mut self) -> (Self::ResolvePayloadFuture, KeepPayloadJobAlive) {
+        // use the external payload if it's already validated, but never wait for it
+        if let Some(payload) =
+            self.external_payload.take().and_then(|mut rx| rx.try_recv().ok().flatten())
+        {
+            self.on_external_payload(payload);
+        }
+        self.on_finalized(PayloadJobFinalization::Resolve);
+
         let best_payload = self.best_payload.take();
 
         if best_payload.is_none() && self.pending_block.is_none() {
@@ -510,6 +993,7 @@ where
                 config: self.config.clone(),
                 cancel: Cancelled::default(),
                 best_payload: None,
+                prefix: None,
             };
 
             match self.builder.on_missing_payload(args) {
@@ -690,6 +1174,11 @@ pub struct PayloadConfig<Attributes> {
     pub attributes: Attributes,
     /// The chain spec.
     pub chain_spec: Arc<ChainSpec>,
+    /// Limits of every attempt to build the payload.
+    pub budget: PayloadBuildBudget,
+    /// Whether build attempts return the [`ExecutedPrefix`] of their payload, so that later
+    /// attempts can resume from it.
+    pub resumable: bool,
 }
 
 impl<Attributes> PayloadConfig<Attributes> {
@@ -697,6 +1186,18 @@ impl<Attributes> PayloadConfig<Attributes> {
     pub fn extra_data(&self) -> Bytes {
         self.extra_data.clone()
     }
+
+    /// Sets the limits of every attempt to build the payload.
+    pub const fn with_budget(mut self, budget: PayloadBuildBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Sets whether build attempts return the [`ExecutedPrefix`] of their payload.
+    pub const fn with_resumable(mut self, resumable: bool) -> Self {
+        self.resumable = resumable;
+        self
+    }
 }
 
 impl<Attributes> PayloadConfig<Attributes>
@@ -721,6 +1222,8 @@ where
             extra_data,
             attributes,
             chain_spec,
+            budget: PayloadBuildBudget::default(),
+            resumable: false,
         }
     }
 
@@ -739,6 +1242,9 @@ pub enum BuildOutcome<Payload> {
         payload: Payload,
         /// The cached reads that were used to build the payload.
         cached_reads: CachedReads,
+        /// The executed transactions of the payload, if the
+        /// [config](PayloadConfig::resumable) asked for them.
+        prefix: Option<ExecutedPrefix>,
     },
     /// Aborted payload building because resulted in worse block wrt. fees.
     Aborted {
@@ -770,6 +1276,9 @@ pub struct BuildArguments<Pool, Client, Attributes, Payload> {
     pub cancel: Cancelled,
     /// The best payload achieved so far.
     pub best_payload: Option<Payload>,
+    /// The executed transactions of a previous attempt to resume from, instead of building the
+    /// payload from scratch.
+    pub prefix: Option<ExecutedPrefix>,
 }
 
 impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes, Payload> {
@@ -782,10 +1291,39 @@ impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes,
         cancel: Cancelled,
         best_payload: Option<Payload>,
     ) -> Self {
-        Self { client, pool, cached_reads, config, cancel, best_payload }
+        Self { client, pool, cached_reads, config, cancel, best_payload, prefix: None }
+    }
+
+    /// Resumes the attempt from the executed transactions of a previous attempt.
+    pub fn with_prefix(mut self, prefix: ExecutedPrefix) -> Self {
+        self.prefix = Some(prefix);
+        self
     }
 }
 
+/// The transactions executed for a payload, before the post-block changes like withdrawals are
+/// applied.
+///
+/// Later build attempts of the same payload job can resume from the prefix, and only execute new
+/// transactions on top of it instead of re-executing the whole block.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutedPrefix {
+    /// The state changes of the pre-block calls and the executed transactions.
+    pub bundle: BundleState,
+    /// The executed transactions, in order.
+    pub transactions: Vec<TransactionSigned>,
+    /// The receipts of the executed transactions.
+    pub receipts: Vec<Receipt>,
+    /// The gas used by the executed transactions.
+    pub cumulative_gas_used: u64,
+    /// The blob gas used by the executed transactions.
+    pub blob_gas_used: u64,
+    /// The sidecars of the executed blob transactions, in order.
+    pub blob_sidecars: Vec<BlobTransactionSidecar>,
+    /// The fees paid by the executed transactions.
+    pub fees: U256,
+}
+
 /// A trait for building payloads that encapsulate Ethereum transactions.
 ///
 /// This trait provides the `try_build` method to construct a transaction payload
@@ -833,6 +1371,20 @@ pub trait PayloadBuilder<Pool, Client>: Send + Sync + Clone {
         client: &Client,
         config: PayloadConfig<Self::Attributes>,
     ) -> Result<Self::BuiltPayload, PayloadBuilderError>;
+
+    /// Validates the payload of an external builder against local execution on top of the parent
+    /// block, and returns it as a built payload with the fees it pays.
+    ///
+    /// The payload must match the attributes of the job. By default, external payloads are not
+    /// supported and always rejected.
+    fn validate_external_payload(
+        &self,
+        _client: &Client,
+        _config: &PayloadConfig<Self::Attributes>,
+        _bid: ExternalBid,
+    ) -> Result<Self::BuiltPayload, PayloadBuilderError> {
+        Err(PayloadBuilderError::other(ExternalPayloadError::Unsupported))
+    }
 }
 
 /// Tells the payload builder how to react to payload request if there's no payload available yet.
@@ -966,22 +1518,53 @@ where
     .map_err(|err| PayloadBuilderError::Internal(err.into()))
 }
 
-/// Apply the [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) post block contract call.
+/// Apply the pre block system calls of the [`SystemCaller`], e.g. the
+/// [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) beacon root contract call and the
+/// [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) blockhashes update.
 ///
 /// This constructs a new [Evm] with the given DB, and environment
-/// ([`CfgEnvWithHandlerCfg`] and [`BlockEnv`]) to execute the post block contract call.
+/// ([`CfgEnvWithHandlerCfg`] and [`BlockEnv`]) to execute the pre block system calls.
+pub fn pre_block_system_calls<DB>(
+    db: &mut DB,
+    system_caller: &SystemCaller,
+    block: &SystemCallBlock,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+) -> Result<(), PayloadBuilderError>
+where
+    DB: Database<Error: Into<ProviderError> + std::fmt::Display> + DatabaseCommit,
+{
+    let mut evm_pre_block = Evm::builder()
+        .with_db(db)
+        .with_env_with_handler_cfg(EnvWithHandlerCfg::new_with_cfg_env(
+            initialized_cfg.clone(),
+            initialized_block_env.clone(),
+            Default::default(),
+        ))
+        .build();
+
+    system_caller
+        .apply_pre_block(block, &mut evm_pre_block)
+        .map_err(|err| PayloadBuilderError::Internal(err.into()))
+}
+
+/// Apply the post block system calls of the [`SystemCaller`], e.g. the
+/// [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) withdrawal requests and
+/// [EIP-7251](https://eips.ethereum.org/EIPS/eip-7251) consolidation requests contract calls.
 ///
-/// This uses [`apply_withdrawal_requests_contract_call`] to ultimately calculate the
-/// [requests](Request).
-pub fn post_block_withdrawal_requests_contract_call<DB: Database + DatabaseCommit>(
+/// This constructs a new [Evm] with the given DB, and environment
+/// ([`CfgEnvWithHandlerCfg`] and [`BlockEnv`]) to execute the post block system calls, and
+/// returns their [requests](Request).
+pub fn post_block_system_calls<DB: Database + DatabaseCommit>(
     db: &mut DB,
+    system_caller: &SystemCaller,
+    block: &SystemCallBlock,
     initialized_cfg: &CfgEnvWithHandlerCfg,
     initialized_block_env: &BlockEnv,
 ) -> Result<Vec<Request>, PayloadBuilderError>
 where
     DB::Error: std::fmt::Display,
 {
-    // apply post-block EIP-7002 contract call
     let mut evm_post_block = Evm::builder()
         .with_db(db)
         .with_env_with_handler_cfg(EnvWithHandlerCfg::new_with_cfg_env(
@@ -991,8 +1574,8 @@ where
         ))
         .build();
 
-    // initialize a block from the env, because the post block call needs the block itself
-    apply_withdrawal_requests_contract_call(&mut evm_post_block)
+    system_caller
+        .apply_post_block(block, &mut evm_post_block)
         .map_err(|err| PayloadBuilderError::Internal(err.into()))
 }
 
@@ -1016,3 +1599,175 @@ fn duration_until(unix_timestamp_secs: u64) -> Duration {
     let timestamp = Duration::from_secs(unix_timestamp_secs);
     timestamp.saturating_sub(unix_now)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::MAINNET;
+    use reth_payload_builder::{EthBuiltPayload, EthPayloadBuilderAttributes};
+    use reth_primitives::{Block, Header};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_rpc_types::engine::PayloadAttributes;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction, TestPool};
+
+    /// Builds payloads without transactions, with fees that tell full and empty payloads apart.
+    #[derive(Debug, Clone)]
+    struct TestPayloadBuilder;
+
+    impl TestPayloadBuilder {
+        /// Fees of the payloads built from the pool.
+        const FULL_PAYLOAD_FEES: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+        fn payload(
+            config: &PayloadConfig<EthPayloadBuilderAttributes>,
+            fees: U256,
+        ) -> EthBuiltPayload {
+            EthBuiltPayload::new(config.attributes.id, SealedBlock::default(), fees)
+        }
+    }
+
+    impl<Pool, Client> PayloadBuilder<Pool, Client> for TestPayloadBuilder {
+        type Attributes = EthPayloadBuilderAttributes;
+        type BuiltPayload = EthBuiltPayload;
+
+        fn try_build(
+            &self,
+            args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
+        ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
+            Ok(BuildOutcome::Better {
+                payload: Self::payload(&args.config, Self::FULL_PAYLOAD_FEES),
+                cached_reads: args.cached_reads,
+                prefix: None,
+            })
+        }
+
+        fn build_empty_payload(
+            &self,
+            _client: &Client,
+            config: PayloadConfig<Self::Attributes>,
+        ) -> Result<Self::BuiltPayload, PayloadBuilderError> {
+            Ok(Self::payload(&config, U256::ZERO))
+        }
+    }
+
+    type TestJob =
+        BasicPayloadJob<MockEthProvider, TestPool, TokioTaskExecutor, TestPayloadBuilder>;
+
+    /// Creates a job on top of a parent block with a base fee of 7.
+    fn payload_job(pool: TestPool, empty_payload_min_tip: Option<u128>) -> TestJob {
+        let parent = Header {
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(7),
+            timestamp: 1,
+            ..Default::default()
+        };
+        let parent_hash = parent.hash_slow();
+        let client = MockEthProvider::default();
+        client.add_block(parent_hash, Block { header: parent, ..Default::default() });
+
+        let generator = BasicPayloadJobGenerator::with_builder(
+            client,
+            pool,
+            TokioTaskExecutor::default(),
+            BasicPayloadJobGeneratorConfig::default().empty_payload_min_tip(empty_payload_min_tip),
+            MAINNET.clone(),
+            TestPayloadBuilder,
+        );
+        let attributes = EthPayloadBuilderAttributes::new(
+            parent_hash,
+            PayloadAttributes {
+                timestamp: 13,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: Address::ZERO,
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: None,
+            },
+        );
+        generator.new_payload_job(attributes).unwrap()
+    }
+
+    /// Polls the job until it has a best payload.
+    async fn best_payload(job: &mut TestJob) -> EthBuiltPayload {
+        loop {
+            assert!(futures_util::poll!(&mut *job).is_pending());
+            if let Some(payload) = &job.best_payload {
+                return payload.clone()
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn has_valuable_transactions() {
+        let pool = testing_pool();
+        let job = payload_job(pool.clone(), Some(10));
+        assert!(!job.has_valuable_transactions());
+
+        // the tip is capped by the max fee above the base fee of 7
+        let capped = MockTransaction::eip1559().with_max_fee(15).with_priority_fee(20);
+        pool.add_external_transaction(capped).await.unwrap();
+        assert!(!job.has_valuable_transactions());
+
+        let valuable = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(10);
+        pool.add_external_transaction(valuable).await.unwrap();
+        assert!(job.has_valuable_transactions());
+
+        // without a minimum tip, the pool is always used
+        let job = payload_job(testing_pool(), None);
+        assert!(job.has_valuable_transactions());
+    }
+
+    #[tokio::test]
+    async fn spawn_empty_payload_job() {
+        // the pool has no valuable transactions, so the empty payload is built right away
+        let pool = testing_pool();
+        let mut job = payload_job(pool.clone(), Some(10));
+        assert_eq!(best_payload(&mut job).await.fees(), U256::ZERO);
+
+        // the empty payload is kept until a valuable transaction is in the pool
+        job.spawn_build_job();
+        assert!(job.pending_block.is_none());
+
+        let valuable = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(10);
+        pool.add_external_transaction(valuable).await.unwrap();
+        job.spawn_build_job();
+        assert!(job.pending_block.is_some());
+
+        // the pool is used right away without a minimum tip
+        let mut job = payload_job(testing_pool(), None);
+        assert_eq!(best_payload(&mut job).await.fees(), TestPayloadBuilder::FULL_PAYLOAD_FEES);
+    }
+
+    #[test]
+    fn job_budget_per_fee_recipient() {
+        let validator = Address::new([1; 20]);
+        let sidecar = Address::new([2; 20]);
+        let sidecar_budget = PayloadJobBudget {
+            max_iterations: Some(1),
+            build_time: Some(Duration::from_millis(500)),
+            attempt: PayloadBuildBudget { gas: Some(15_000_000), time: None },
+        };
+        let config = BasicPayloadJobGeneratorConfig::default()
+            .max_iterations(Some(4))
+            .max_build_time(Some(Duration::from_secs(2)))
+            .fee_recipient_budget(sidecar, sidecar_budget);
+
+        let default_budget = PayloadJobBudget {
+            max_iterations: Some(4),
+            build_time: Some(Duration::from_secs(2)),
+            attempt: PayloadBuildBudget::default(),
+        };
+        assert_eq!(config.job_budget(validator), default_budget);
+        assert_eq!(config.job_budget(sidecar), sidecar_budget);
+    }
+
+    #[test]
+    fn record_build_time() {
+        let build_time = BuildTime::default();
+        build_time.clone().record(Duration::from_millis(3));
+        build_time.record(Duration::from_micros(500));
+        assert_eq!(build_time.get(), Duration::from_micros(3500));
+    }
+}
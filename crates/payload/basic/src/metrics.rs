@@ -1,6 +1,9 @@
 //! Metrics for the payload builder impl
 
-use reth_metrics::{metrics::Counter, Metrics};
+use reth_metrics::{
+    metrics::{Counter, Gauge, Histogram},
+    Metrics,
+};
 
 /// Transaction pool metrics
 #[derive(Metrics)]
@@ -12,6 +15,16 @@ pub(crate) struct PayloadBuilderMetrics {
     pub(crate) initiated_payload_builds: Counter,
     /// Total number of failed payload build attempts
     pub(crate) failed_payload_builds: Counter,
+    /// Number of payload build attempts per job
+    pub(crate) job_iterations: Histogram,
+    /// Fees of the best payload of the most recent job, in wei
+    pub(crate) best_payload_fees: Gauge,
+    /// Time from the start of a job until its best payload was found, in seconds
+    pub(crate) last_improvement_seconds: Histogram,
+    /// Total number of jobs that ended because their deadline was reached
+    pub(crate) jobs_finalized_by_deadline: Counter,
+    /// Total number of jobs that ended because their payload was resolved
+    pub(crate) jobs_finalized_by_resolve: Counter,
 }
 
 impl PayloadBuilderMetrics {
@@ -0,0 +1,123 @@
+//! Utilities for testing payload builders.
+
+use crate::{BuildArguments, BuildOutcome, Cancelled, PayloadBuilder, PayloadConfig};
+use reth_chainspec::ChainSpec;
+use reth_payload_builder::database::CachedReads;
+use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
+use reth_primitives::{Bytes, SealedBlock, TxHash, U256};
+use reth_transaction_pool::{TransactionOrigin, TransactionPool};
+use std::sync::Arc;
+
+/// A recorded payload build that is replayed through a [`PayloadBuilder`], e.g. for regression
+/// tests of the transaction ordering or of custom builder hooks.
+///
+/// A replay is deterministic: the recorded transactions are added to the given pool, which should
+/// be empty, and a single payload is built on top of the recorded parent block, without a time
+/// budget.
+#[derive(Debug, Clone)]
+pub struct PayloadReplay<Attributes, Tx> {
+    /// The parent block of the payload.
+    pub parent: Arc<SealedBlock>,
+    /// The payload attributes of the forkchoice update.
+    pub attributes: Attributes,
+    /// The transactions of the pool when the payload was built.
+    pub transactions: Vec<Tx>,
+    /// The extra data of the payload.
+    pub extra_data: Bytes,
+}
+
+impl<Attributes, Tx> PayloadReplay<Attributes, Tx>
+where
+    Attributes: PayloadBuilderAttributes + Clone,
+    Tx: Clone,
+{
+    /// Creates a new replay of a payload built on top of `parent` for the given attributes and
+    /// pool transactions.
+    pub fn new(parent: Arc<SealedBlock>, attributes: Attributes, transactions: Vec<Tx>) -> Self {
+        Self { parent, attributes, transactions, extra_data: Bytes::default() }
+    }
+
+    /// Sets the extra data of the payload.
+    pub fn with_extra_data(mut self, extra_data: Bytes) -> Self {
+        self.extra_data = extra_data;
+        self
+    }
+
+    /// Adds the recorded transactions to the pool and builds the payload with the builder.
+    ///
+    /// # Panics
+    ///
+    /// If the pool rejects a transaction, or the builder fails or doesn't return a payload.
+    pub async fn replay<Pool, Client, Builder>(
+        &self,
+        client: Client,
+        pool: Pool,
+        chain_spec: Arc<ChainSpec>,
+        builder: &Builder,
+    ) -> Builder::BuiltPayload
+    where
+        Pool: TransactionPool<Transaction = Tx>,
+        Builder: PayloadBuilder<Pool, Client, Attributes = Attributes>,
+    {
+        let results =
+            pool.add_transactions(TransactionOrigin::External, self.transactions.clone()).await;
+        for result in results {
+            result.expect("failed to add recorded transaction to the pool");
+        }
+
+        let config = PayloadConfig::new(
+            Arc::clone(&self.parent),
+            self.extra_data.clone(),
+            self.attributes.clone(),
+            chain_spec,
+        );
+        let args = BuildArguments::new(
+            client,
+            pool,
+            CachedReads::default(),
+            config,
+            Cancelled::default(),
+            None,
+        );
+        match builder.try_build(args).expect("failed to build replayed payload") {
+            BuildOutcome::Better { payload, .. } => payload,
+            BuildOutcome::Aborted { fees, .. } => {
+                panic!("replayed payload build aborted with fees {fees}")
+            }
+            BuildOutcome::Cancelled => panic!("replayed payload build cancelled"),
+        }
+    }
+}
+
+/// The recorded result of a payload build, which a [`PayloadReplay`] is checked against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedPayload {
+    /// The hashes of the transactions of the payload, in order.
+    pub transactions: Vec<TxHash>,
+    /// The gas used by the payload.
+    pub gas_used: u64,
+    /// The fees of the payload.
+    pub fees: U256,
+}
+
+impl ExpectedPayload {
+    /// Records the result of the given payload.
+    pub fn from_payload(payload: &impl BuiltPayload) -> Self {
+        let block = payload.block();
+        Self {
+            transactions: block.body.iter().map(|tx| tx.hash()).collect(),
+            gas_used: block.gas_used,
+            fees: payload.fees(),
+        }
+    }
+
+    /// Asserts that the given payload matches the recorded result.
+    #[track_caller]
+    pub fn assert_matches(&self, payload: &impl BuiltPayload) {
+        assert_eq!(
+            &Self::from_payload(payload),
+            self,
+            "replayed payload doesn't match the recorded payload"
+        );
+    }
+}
@@ -19,6 +19,35 @@
 //! and start polling it until the payload is requested by the CL and the payload job is resolved
 //! (see [`PayloadJob::resolve`]).
 //!
+//! ## Validating payload attributes
+//!
+//! Nodes that enforce additional rules on the payload attributes, e.g. a withdrawal policy or
+//! custom fields of L2 payload attributes, can install a [`PayloadAttributesValidator`] with
+//! [`PayloadBuilderService::with_attributes_validator`]. Attributes are validated before a
+//! [`PayloadJob`] is created for them, and rejected attributes are returned to the CL as an
+//! `Invalid payload attributes` error of the forkchoice update.
+//!
+//! ```
+//! use reth_payload_builder::{EthPayloadBuilderAttributes, PayloadAttributesValidator};
+//!
+//! /// Rejects payload attributes with withdrawals.
+//! #[derive(Debug)]
+//! pub struct NoWithdrawalsValidator;
+//!
+//! impl PayloadAttributesValidator<EthPayloadBuilderAttributes> for NoWithdrawalsValidator {
+//!     fn validate_payload_attributes(
+//!         &self,
+//!         attributes: &EthPayloadBuilderAttributes,
+//!     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!         if attributes.withdrawals.is_empty() {
+//!             Ok(())
+//!         } else {
+//!             Err("withdrawals not allowed".into())
+//!         }
+//!     }
+//! }
+//! ```
+//!
 //! ## Example
 //!
 //! A simple example of a [`PayloadJobGenerator`] that creates empty blocks:
@@ -116,7 +145,9 @@ pub mod test_utils;
 pub use events::Events;
 pub use reth_rpc_types::engine::PayloadId;
 pub use service::{PayloadBuilderHandle, PayloadBuilderService, PayloadStore};
-pub use traits::{KeepPayloadJobAlive, PayloadJob, PayloadJobGenerator};
+pub use traits::{
+    KeepPayloadJobAlive, PayloadAttributesValidator, PayloadJob, PayloadJobGenerator,
+};
 
 // re-export the Ethereum engine primitives for convenience
 #[doc(inline)]
@@ -7,7 +7,7 @@ use crate::{
     error::PayloadBuilderError,
     events::{Events, PayloadEvents},
     metrics::PayloadBuilderServiceMetrics,
-    traits::PayloadJobGenerator,
+    traits::{PayloadAttributesValidator, PayloadJobGenerator},
     KeepPayloadJobAlive, PayloadJob,
 };
 use futures_util::{future::FutureExt, Stream, StreamExt};
@@ -227,6 +227,9 @@ where
     chain_events: St,
     /// Payload events handler, used to broadcast and subscribe to payload events.
     payload_events: broadcast::Sender<Events<Engine>>,
+    /// Validates the payload attributes of new payload jobs, if configured.
+    attributes_validator:
+        Option<Box<dyn PayloadAttributesValidator<Engine::PayloadBuilderAttributes>>>,
 }
 
 const PAYLOAD_EVENTS_BUFFER_SIZE: usize = 20;
@@ -258,12 +261,23 @@ where
             metrics: Default::default(),
             chain_events,
             payload_events,
+            attributes_validator: None,
         };
 
         let handle = service.handle();
         (service, handle)
     }
 
+    /// Sets the validator that payload attributes must pass before a payload job is created for
+    /// them.
+    pub fn with_attributes_validator<V>(mut self, validator: V) -> Self
+    where
+        V: PayloadAttributesValidator<Engine::PayloadBuilderAttributes> + 'static,
+    {
+        self.attributes_validator = Some(Box::new(validator));
+        self
+    }
+
     /// Returns a handle to the service.
     pub fn handle(&self) -> PayloadBuilderHandle<Engine> {
         PayloadBuilderHandle::new(self.service_tx.clone())
@@ -274,6 +288,17 @@ where
         self.payload_jobs.iter().any(|(_, job_id)| *job_id == id)
     }
 
+    /// Validates the payload attributes with the configured validator, if any.
+    fn validate_payload_attributes(
+        &self,
+        attributes: &Engine::PayloadBuilderAttributes,
+    ) -> Result<(), PayloadBuilderError> {
+        let Some(validator) = &self.attributes_validator else { return Ok(()) };
+        validator
+            .validate_payload_attributes(attributes)
+            .map_err(PayloadBuilderError::InvalidPayloadAttributes)
+    }
+
     /// Returns the best payload for the given identifier that has been built so far.
     fn best_payload(
         &self,
@@ -405,6 +430,10 @@ where
 
                         if this.contains_payload(id) {
                             debug!(%id, parent = %attr.parent(), "Payload job already in progress, ignoring.");
+                        } else if let Err(err) = this.validate_payload_attributes(&attr) {
+                            this.metrics.inc_rejected_attributes();
+                            warn!(%err, %id, "Payload attributes rejected");
+                            res = Err(err);
                         } else {
                             // no job for this payload yet, create one
                             let parent = attr.parent();
@@ -494,3 +523,62 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::test_payload_service, EthPayloadBuilderAttributes};
+    use reth_ethereum_engine_primitives::EthEngineTypes;
+    use reth_primitives::B256;
+    use reth_rpc_types::{engine::PayloadAttributes, Withdrawal};
+
+    /// Rejects payload attributes with withdrawals.
+    #[derive(Debug)]
+    struct NoWithdrawalsValidator;
+
+    impl PayloadAttributesValidator<EthPayloadBuilderAttributes> for NoWithdrawalsValidator {
+        fn validate_payload_attributes(
+            &self,
+            attributes: &EthPayloadBuilderAttributes,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if attributes.withdrawals.is_empty() {
+                Ok(())
+            } else {
+                Err("withdrawals not allowed".into())
+            }
+        }
+    }
+
+    fn payload_attributes(withdrawals: Vec<Withdrawal>) -> EthPayloadBuilderAttributes {
+        EthPayloadBuilderAttributes::new(
+            B256::random(),
+            PayloadAttributes {
+                timestamp: 1,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: Default::default(),
+                withdrawals: Some(withdrawals),
+                parent_beacon_block_root: None,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_payload_attributes() {
+        let (service, handle) = test_payload_service::<EthEngineTypes>();
+        tokio::spawn(service.with_attributes_validator(NoWithdrawalsValidator));
+
+        let rejected = payload_attributes(vec![Withdrawal::default()]);
+        let rejected_id = rejected.id;
+        assert!(matches!(
+            handle.new_payload(rejected).await,
+            Err(PayloadBuilderError::InvalidPayloadAttributes(_))
+        ));
+        // no job was created for the rejected attributes
+        assert!(handle.best_payload(rejected_id).await.is_none());
+
+        let accepted = payload_attributes(Vec::new());
+        let accepted_id = accepted.id;
+        assert_eq!(handle.new_payload(accepted).await.unwrap(), accepted_id);
+        assert!(handle.best_payload(accepted_id).await.is_some());
+    }
+}
@@ -3,7 +3,7 @@
 use crate::error::PayloadBuilderError;
 use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
 use reth_provider::CanonStateNotification;
-use std::future::Future;
+use std::{fmt, future::Future};
 
 /// A type that can build a payload.
 ///
@@ -95,3 +95,18 @@ pub trait PayloadJobGenerator: Send + Sync {
         let _ = new_state;
     }
 }
+
+/// Validates the payload attributes of a forkchoice update before the
+/// [`PayloadBuilderService`](crate::PayloadBuilderService) creates a payload job for them.
+///
+/// This is an extension point for policies that go beyond the checks of the engine API, e.g.
+/// enforcing a withdrawal policy or validating custom fields of L2 payload attributes. Rejected
+/// attributes fail with [`PayloadBuilderError::InvalidPayloadAttributes`], which is returned as an
+/// `Invalid payload attributes` error by the engine API.
+pub trait PayloadAttributesValidator<Attributes>: fmt::Debug + Send + Sync {
+    /// Returns an error if no payload should be built for the given attributes.
+    fn validate_payload_attributes(
+        &self,
+        attributes: &Attributes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
@@ -26,6 +26,9 @@ pub enum PayloadBuilderError {
     /// Thrown if the payload requests withdrawals before Shanghai activation.
     #[error("withdrawals set before Shanghai activation")]
     WithdrawalsBeforeShanghai,
+    /// Thrown if the payload attributes were rejected before a payload job was created for them.
+    #[error("invalid payload attributes: {0}")]
+    InvalidPayloadAttributes(Box<dyn std::error::Error + Send + Sync>),
     /// Any other payload building errors.
     #[error(transparent)]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -39,6 +42,14 @@ impl PayloadBuilderError {
     {
         Self::Other(Box::new(error))
     }
+
+    /// Create a new [`PayloadBuilderError::InvalidPayloadAttributes`] error.
+    pub fn invalid_payload_attributes<E>(error: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self::InvalidPayloadAttributes(error.into())
+    }
 }
 
 impl From<ProviderError> for PayloadBuilderError {
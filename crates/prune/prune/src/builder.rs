@@ -1,10 +1,11 @@
 use crate::{segments::SegmentSet, Pruner};
+use alloy_primitives::BlockNumber;
 use reth_chainspec::MAINNET;
 use reth_config::PruneConfig;
 use reth_db_api::database::Database;
 use reth_exex_types::FinishedExExHeight;
 use reth_provider::ProviderFactory;
-use reth_prune_types::PruneModes;
+use reth_prune_types::{PruneMode, PruneModes, PruneSchedule};
 use std::time::Duration;
 use tokio::sync::watch;
 
@@ -23,8 +24,16 @@ pub struct PrunerBuilder {
     prune_delete_limit: usize,
     /// Time a pruner job can run before timing out.
     timeout: Option<Duration>,
+    /// Per-segment delete limits, chunking and time windows of pruner runs.
+    schedule: PruneSchedule,
+    /// Retention boundary of headers, transactions and receipts static files.
+    history_expiry: Option<PruneMode>,
     /// The finished height of all `ExEx`'s.
     finished_exex_height: watch::Receiver<FinishedExExHeight>,
+    /// The highest block number that is durably persisted to the database.
+    persisted_height: watch::Receiver<Option<BlockNumber>>,
+    /// Updates of the pruning configuration, from reloads of the configuration file.
+    config_updates: Option<watch::Receiver<PruneConfig>>,
 }
 
 impl PrunerBuilder {
@@ -36,6 +45,8 @@ impl PrunerBuilder {
         Self::default()
             .block_interval(pruner_config.block_interval)
             .segments(pruner_config.segments)
+            .schedule(pruner_config.schedule)
+            .history_expiry(pruner_config.history_expiry)
     }
 
     /// Sets the minimum pruning interval measured in blocks.
@@ -71,6 +82,19 @@ impl PrunerBuilder {
         self
     }
 
+    /// Sets the per-segment delete limits, chunking and time windows of pruner runs.
+    pub fn schedule(mut self, schedule: PruneSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Sets the retention boundary of headers, transactions and receipts static files. Whole static
+    /// files below the boundary are deleted.
+    pub const fn history_expiry(mut self, history_expiry: Option<PruneMode>) -> Self {
+        self.history_expiry = history_expiry;
+        self
+    }
+
     /// Sets the receiver for the finished height of all `ExEx`'s.
     pub fn finished_exex_height(
         mut self,
@@ -80,6 +104,28 @@ impl PrunerBuilder {
         self
     }
 
+    /// Sets the receiver for the highest block number that is durably persisted to the database.
+    ///
+    /// The pruner never prunes relative to a tip above this height, so the data needed by blocks
+    /// that are only kept in memory is retained. By default, all blocks the pruner is called with
+    /// are considered persisted.
+    pub fn persisted_height(
+        mut self,
+        persisted_height: watch::Receiver<Option<BlockNumber>>,
+    ) -> Self {
+        self.persisted_height = persisted_height;
+        self
+    }
+
+    /// Sets the receiver for updates of the pruning configuration.
+    ///
+    /// The block interval and the schedule of an update are applied before the next pruner run.
+    /// The other settings only take effect after a restart.
+    pub fn config_updates(mut self, config_updates: watch::Receiver<PruneConfig>) -> Self {
+        self.config_updates = Some(config_updates);
+        self
+    }
+
     /// Builds a [Pruner] from the current configuration.
     pub fn build<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> Pruner<DB> {
         let segments = SegmentSet::<DB>::from_prune_modes(self.segments);
@@ -91,7 +137,11 @@ impl PrunerBuilder {
             self.prune_delete_limit,
             self.max_reorg_depth,
             self.timeout,
+            self.schedule,
+            self.history_expiry,
             self.finished_exex_height,
+            self.persisted_height,
+            self.config_updates,
         )
     }
 }
@@ -104,7 +154,11 @@ impl Default for PrunerBuilder {
             max_reorg_depth: 64,
             prune_delete_limit: MAINNET.prune_delete_limit,
             timeout: None,
+            schedule: PruneSchedule::default(),
+            history_expiry: None,
             finished_exex_height: watch::channel(FinishedExExHeight::NoExExs).1,
+            persisted_height: watch::channel(None).1,
+            config_updates: None,
         }
     }
 }
@@ -1,5 +1,6 @@
+use crate::segments::PruneOutput;
 use reth_metrics::{
-    metrics::{Gauge, Histogram},
+    metrics::{Counter, Gauge, Histogram},
     Metrics,
 };
 use reth_prune_types::PruneSegment;
@@ -10,6 +11,12 @@ use std::collections::HashMap;
 pub(crate) struct Metrics {
     /// Pruning duration
     pub(crate) duration_seconds: Histogram,
+    /// Duration of the last pruner run
+    pub(crate) last_run_duration_seconds: Gauge,
+    /// Number of blocks the pruner tip is held back by the finished height of `ExEx`s
+    pub(crate) exex_held_back_blocks: Gauge,
+    /// Number of blocks the pruner tip is held back by the persisted height
+    pub(crate) persistence_held_back_blocks: Gauge,
     #[metric(skip)]
     prune_segments: HashMap<PruneSegment, PrunerSegmentMetrics>,
 }
@@ -34,4 +41,23 @@ pub(crate) struct PrunerSegmentMetrics {
     pub(crate) duration_seconds: Histogram,
     /// Highest pruned block per segment
     pub(crate) highest_pruned_block: Gauge,
+    /// Highest pruned transaction per segment
+    pub(crate) highest_pruned_tx: Gauge,
+    /// Number of entries deleted per segment
+    pub(crate) deleted_entries: Counter,
+}
+
+impl PrunerSegmentMetrics {
+    /// Records the checkpoint and the number of deleted entries of a segment pruning.
+    pub(crate) fn record_output(&self, output: &PruneOutput) {
+        if let Some(checkpoint) = output.checkpoint {
+            if let Some(block_number) = checkpoint.block_number {
+                self.highest_pruned_block.set(block_number as f64);
+            }
+            if let Some(tx_number) = checkpoint.tx_number {
+                self.highest_pruned_tx.set(tx_number as f64);
+            }
+        }
+        self.deleted_entries.increment(output.pruned as u64);
+    }
 }
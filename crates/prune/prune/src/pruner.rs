@@ -6,20 +6,24 @@ use crate::{
     Metrics, PrunerError, PrunerEvent,
 };
 use alloy_primitives::BlockNumber;
+use reth_config::PruneConfig;
 use reth_db_api::database::Database;
 use reth_exex_types::FinishedExExHeight;
 use reth_provider::{
     DatabaseProviderRW, ProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
 };
-use reth_prune_types::{PruneLimiter, PruneMode, PruneProgress, PrunePurpose, PruneSegment};
+use reth_prune_types::{
+    PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PrunePurpose, PruneSchedule,
+    PruneSegment,
+};
 use reth_static_file_types::StaticFileSegment;
 use reth_tokio_util::{EventSender, EventStream};
 use std::{
     collections::BTreeMap,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::sync::watch;
-use tracing::debug;
+use tracing::{debug, info};
 
 /// Result of [`Pruner::run`] execution.
 pub type PrunerResult = Result<PruneProgress, PrunerError>;
@@ -27,7 +31,8 @@ pub type PrunerResult = Result<PruneProgress, PrunerError>;
 /// The pruner type itself with the result of [`Pruner::run`]
 pub type PrunerWithResult<DB> = (Pruner<DB>, PrunerResult);
 
-type PrunerStats = BTreeMap<PruneSegment, (PruneProgress, usize)>;
+/// Progress and number of pruned entries per segment of a pruner run.
+pub type PrunerStats = BTreeMap<PruneSegment, (PruneProgress, usize)>;
 
 /// Pruning routine. Main pruning logic happens in [`Pruner::run`].
 #[derive(Debug)]
@@ -48,8 +53,19 @@ pub struct Pruner<DB> {
     prune_max_blocks_per_run: usize,
     /// Maximum time for a one pruner run.
     timeout: Option<Duration>,
+    /// Per-segment delete limits, chunking and time windows of pruner runs.
+    schedule: PruneSchedule,
+    /// Retention boundary of headers, transactions and receipts static files. Whole static files
+    /// below the boundary are deleted.
+    history_expiry: Option<PruneMode>,
     /// The finished height of all `ExEx`'s.
     finished_exex_height: watch::Receiver<FinishedExExHeight>,
+    /// The highest block number that is durably persisted to the database. `None` if all blocks
+    /// the pruner is called with are persisted.
+    persisted_height: watch::Receiver<Option<BlockNumber>>,
+    /// Updates of the pruning configuration, from reloads of the configuration file. Only the
+    /// block interval and the schedule are applied.
+    config_updates: Option<watch::Receiver<PruneConfig>>,
     #[doc(hidden)]
     metrics: Metrics,
     event_sender: EventSender<PrunerEvent>,
@@ -57,6 +73,7 @@ pub struct Pruner<DB> {
 
 impl<DB: Database> Pruner<DB> {
     /// Creates a new [Pruner].
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         provider_factory: ProviderFactory<DB>,
         segments: Vec<Box<dyn Segment<DB>>>,
@@ -64,7 +81,11 @@ impl<DB: Database> Pruner<DB> {
         delete_limit: usize,
         prune_max_blocks_per_run: usize,
         timeout: Option<Duration>,
+        schedule: PruneSchedule,
+        history_expiry: Option<PruneMode>,
         finished_exex_height: watch::Receiver<FinishedExExHeight>,
+        persisted_height: watch::Receiver<Option<BlockNumber>>,
+        config_updates: Option<watch::Receiver<PruneConfig>>,
     ) -> Self {
         Self {
             provider_factory,
@@ -74,7 +95,11 @@ impl<DB: Database> Pruner<DB> {
             delete_limit_per_block: delete_limit,
             prune_max_blocks_per_run,
             timeout,
+            schedule,
+            history_expiry,
             finished_exex_height,
+            persisted_height,
+            config_updates,
             metrics: Metrics::default(),
             event_sender: Default::default(),
         }
@@ -87,9 +112,9 @@ impl<DB: Database> Pruner<DB> {
 
     /// Run the pruner
     pub fn run(&mut self, tip_block_number: BlockNumber) -> PrunerResult {
-        let Some(tip_block_number) =
-            self.adjust_tip_block_number_to_finished_exex_height(tip_block_number)
-        else {
+        self.apply_config_updates();
+
+        let Some(tip_block_number) = self.adjust_tip_block_number(tip_block_number) else {
             return Ok(PruneProgress::Finished)
         };
         if tip_block_number == 0 {
@@ -127,15 +152,52 @@ impl<DB: Database> Pruner<DB> {
             limiter = limiter.set_time_limit(timeout);
         };
 
-        let provider = self.provider_factory.provider_rw()?;
-        let (stats, deleted_entries, progress) =
-            self.prune_segments(&provider, tip_block_number, &mut limiter)?;
-        provider.commit()?;
+        let mut stats = PrunerStats::new();
+        let mut deleted_entries = 0;
+        let progress = loop {
+            // Each chunk is pruned and committed in a separate transaction
+            let mut chunk_limiter = match self.schedule.chunk_size {
+                Some(chunk_size) => limiter.clone().cap_deleted_entries_limit_left(chunk_size),
+                None => limiter.clone(),
+            };
+
+            let provider = self.provider_factory.provider_rw()?;
+            let (chunk_deleted_entries, progress) =
+                self.prune_segments(&provider, tip_block_number, &mut chunk_limiter, &mut stats)?;
+            provider.commit()?;
+
+            limiter.increment_deleted_entries_count_by(chunk_deleted_entries);
+            deleted_entries += chunk_deleted_entries;
+
+            if self.schedule.chunk_size.is_none() ||
+                progress.is_finished() ||
+                chunk_deleted_entries == 0 ||
+                limiter.is_limit_reached()
+            {
+                break progress
+            }
+
+            // Yield to other database users before pruning the next chunk
+            if let Some(chunk_interval) = self.schedule.chunk_interval {
+                debug!(target: "pruner", %tip_block_number, %deleted_entries, ?chunk_interval, "Pruned chunk, yielding");
+                std::thread::sleep(chunk_interval);
+            }
+        };
+
+        // Segments that lag behind their prune mode, e.g. because it was tightened, would only
+        // catch up within the regular limits shared by all segments, so their backlog is pruned
+        // separately
+        if self.schedule.backlog_chunk_size > 0 {
+            deleted_entries += self.prune_backlog(tip_block_number, &mut stats)?;
+        }
+
+        self.expire_static_files(tip_block_number)?;
 
         self.previous_tip_block_number = Some(tip_block_number);
 
         let elapsed = start.elapsed();
         self.metrics.duration_seconds.record(elapsed);
+        self.metrics.last_run_duration_seconds.set(elapsed.as_secs_f64());
 
         let message = match progress {
             PruneProgress::HasMoreData(_) => "Pruner interrupted and has more data to prune",
@@ -158,24 +220,75 @@ impl<DB: Database> Pruner<DB> {
         Ok(progress)
     }
 
+    /// Counts the entries that a pruner run without any limits would delete per segment, using a
+    /// read-only database transaction.
+    ///
+    /// Segments that are pruned both for static files and by the user are counted up to the
+    /// highest of their target blocks.
+    pub fn dry_run(&self, tip_block_number: BlockNumber) -> Result<PrunerStats, PrunerError> {
+        let mut stats = PrunerStats::new();
+        let Some(tip_block_number) = self.adjust_tip_block_number(tip_block_number) else {
+            return Ok(stats)
+        };
+
+        debug!(target: "pruner", %tip_block_number, "Pruner dry run started");
+
+        let static_file_segments = self.static_file_segments();
+        let mut targets = BTreeMap::<PruneSegment, (BlockNumber, &dyn Segment<DB>)>::new();
+        for (segment, purpose) in static_file_segments
+            .iter()
+            .map(|segment| (segment, PrunePurpose::StaticFile))
+            .chain(self.segments.iter().map(|segment| (segment, PrunePurpose::User)))
+        {
+            if let Some((to_block, _)) = segment
+                .mode()
+                .map(|mode| mode.prune_target_block(tip_block_number, segment.segment(), purpose))
+                .transpose()?
+                .flatten()
+            {
+                let target = targets.entry(segment.segment()).or_insert((to_block, &**segment));
+                if to_block > target.0 {
+                    *target = (to_block, &**segment);
+                }
+            }
+        }
+
+        let provider = self.provider_factory.provider()?;
+        for (prune_segment, (to_block, segment)) in targets {
+            let previous_checkpoint = provider.get_prune_checkpoint(prune_segment)?;
+            let count = segment.count(
+                &provider,
+                PruneInput { previous_checkpoint, to_block, limiter: PruneLimiter::default() },
+            )?;
+            if count > 0 {
+                stats.insert(prune_segment, (PruneProgress::Finished, count));
+            }
+        }
+
+        debug!(target: "pruner", %tip_block_number, ?stats, "Pruner dry run finished");
+
+        Ok(stats)
+    }
+
     /// Prunes the segments that the [Pruner] was initialized with, and the segments that needs to
     /// be pruned according to the highest `static_files`. Segments are parts of the database that
     /// represent one or more tables.
     ///
-    /// Returns [`PrunerStats`], total number of entries pruned, and [`PruneProgress`].
+    /// Adds the pruned entries to the [`PrunerStats`] of the run, and returns the number of
+    /// entries pruned and [`PruneProgress`].
     fn prune_segments(
         &mut self,
         provider: &DatabaseProviderRW<DB>,
         tip_block_number: BlockNumber,
         limiter: &mut PruneLimiter,
-    ) -> Result<(PrunerStats, usize, PruneProgress), PrunerError> {
+        stats: &mut PrunerStats,
+    ) -> Result<(usize, PruneProgress), PrunerError> {
         let static_file_segments = self.static_file_segments();
         let segments = static_file_segments
             .iter()
             .map(|segment| (segment, PrunePurpose::StaticFile))
             .chain(self.segments.iter().map(|segment| (segment, PrunePurpose::User)));
 
-        let mut stats = PrunerStats::new();
         let mut pruned = 0;
         let mut progress = PruneProgress::Finished;
 
@@ -199,28 +312,32 @@ impl<DB: Database> Pruner<DB> {
                     "Segment pruning started"
                 );
 
+                // Restrict the segment to its own delete limit of the run
+                let mut segment_limiter = limiter.clone();
+                if let Some(left) = self.segment_delete_limit_left(segment.segment(), stats) {
+                    if left == 0 {
+                        debug!(target: "pruner", segment = ?segment.segment(), ?purpose, "Segment delete limit reached");
+                        progress = PruneProgress::HasMoreData(
+                            PruneInterruptReason::DeletedEntriesLimitReached,
+                        );
+                        continue
+                    }
+                    segment_limiter = segment_limiter.cap_deleted_entries_limit_left(left);
+                }
+
                 let segment_start = Instant::now();
                 let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
                 let output = segment.prune(
                     provider,
-                    PruneInput { previous_checkpoint, to_block, limiter: limiter.clone() },
+                    PruneInput { previous_checkpoint, to_block, limiter: segment_limiter },
                 )?;
                 if let Some(checkpoint) = output.checkpoint {
                     segment
                         .save_checkpoint(provider, checkpoint.as_prune_checkpoint(prune_mode))?;
                 }
-                self.metrics
-                    .get_prune_segment_metrics(segment.segment())
-                    .duration_seconds
-                    .record(segment_start.elapsed());
-                if let Some(highest_pruned_block) =
-                    output.checkpoint.and_then(|checkpoint| checkpoint.block_number)
-                {
-                    self.metrics
-                        .get_prune_segment_metrics(segment.segment())
-                        .highest_pruned_block
-                        .set(highest_pruned_block as f64);
-                }
+                let segment_metrics = self.metrics.get_prune_segment_metrics(segment.segment());
+                segment_metrics.duration_seconds.record(segment_start.elapsed());
+                segment_metrics.record_output(&output);
 
                 progress = output.progress;
 
@@ -237,14 +354,166 @@ impl<DB: Database> Pruner<DB> {
                 if output.pruned > 0 {
                     limiter.increment_deleted_entries_count_by(output.pruned);
                     pruned += output.pruned;
-                    stats.insert(segment.segment(), (output.progress, output.pruned));
+                    let segment_stats =
+                        stats.entry(segment.segment()).or_insert((output.progress, 0));
+                    *segment_stats = (output.progress, segment_stats.1 + output.pruned);
                 }
             } else {
                 debug!(target: "pruner", segment = ?segment.segment(), ?purpose, "Nothing to prune for the segment");
             }
         }
 
-        Ok((stats, pruned, progress))
+        Ok((pruned, progress))
+    }
+
+    /// Returns the number of entries the segment may still delete in this run according to its
+    /// delete limit, or `None` if the segment has no delete limit.
+    fn segment_delete_limit_left(
+        &self,
+        segment: PruneSegment,
+        stats: &PrunerStats,
+    ) -> Option<usize> {
+        let delete_limit = self.schedule.delete_limits.get(segment)?;
+        let segment_pruned = stats.get(&segment).map_or(0, |(_, pruned)| *pruned);
+        Some(delete_limit.saturating_sub(segment_pruned))
+    }
+
+    /// Prunes up to [`PruneSchedule::backlog_chunk_size`] entries of every segment that lags
+    /// behind the target block of its prune mode by more than one regular run can catch up with,
+    /// committing each segment separately.
+    ///
+    /// The backlog counts towards the delete limit of the segment in this run, and the pruner
+    /// yields for the [`PruneSchedule::chunk_interval`] between the commits of the segments.
+    ///
+    /// Adds the pruned entries to the [`PrunerStats`] of the run, and returns the number of
+    /// entries pruned.
+    fn prune_backlog(
+        &mut self,
+        tip_block_number: BlockNumber,
+        stats: &mut PrunerStats,
+    ) -> Result<usize, PrunerError> {
+        let mut pruned = 0;
+
+        for segment in &self.segments {
+            let delete_limit_left = self.segment_delete_limit_left(segment.segment(), stats);
+            if delete_limit_left == Some(0) {
+                debug!(target: "pruner", segment = ?segment.segment(), "Segment delete limit reached, skipping backlog");
+                continue
+            }
+
+            let Some((to_block, prune_mode)) = segment
+                .mode()
+                .map(|mode| {
+                    mode.prune_target_block(tip_block_number, segment.segment(), PrunePurpose::User)
+                })
+                .transpose()?
+                .flatten()
+            else {
+                continue
+            };
+
+            let provider = self.provider_factory.provider_rw()?;
+            let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
+            let next_block = previous_checkpoint
+                .and_then(|checkpoint| checkpoint.block_number)
+                .map_or(0, |block_number| block_number + 1);
+            let backlog = (to_block + 1).saturating_sub(next_block);
+            if backlog <= self.prune_max_blocks_per_run as u64 {
+                continue
+            }
+
+            match previous_checkpoint {
+                Some(checkpoint) if checkpoint.prune_mode != prune_mode => {
+                    info!(
+                        target: "pruner",
+                        segment = ?segment.segment(),
+                        previous_prune_mode = ?checkpoint.prune_mode,
+                        ?prune_mode,
+                        %backlog,
+                        "Prune mode changed, pruning backlog"
+                    );
+                }
+                _ => {
+                    debug!(target: "pruner", segment = ?segment.segment(), ?prune_mode, %backlog, "Pruning backlog");
+                }
+            }
+
+            // Yield to other database users before pruning the next chunk
+            if pruned > 0 {
+                if let Some(chunk_interval) = self.schedule.chunk_interval {
+                    debug!(target: "pruner", segment = ?segment.segment(), ?chunk_interval, "Pruned backlog chunk, yielding");
+                    std::thread::sleep(chunk_interval);
+                }
+            }
+
+            let limiter = PruneLimiter::default().set_deleted_entries_limit(
+                delete_limit_left.map_or(self.schedule.backlog_chunk_size, |left| {
+                    left.min(self.schedule.backlog_chunk_size)
+                }),
+            );
+            let output =
+                segment.prune(&provider, PruneInput { previous_checkpoint, to_block, limiter })?;
+            if let Some(checkpoint) = output.checkpoint {
+                segment.save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))?;
+            }
+            provider.commit()?;
+            self.metrics.get_prune_segment_metrics(segment.segment()).record_output(&output);
+
+            debug!(
+                target: "pruner",
+                segment = ?segment.segment(),
+                %to_block,
+                ?prune_mode,
+                %output.pruned,
+                ?output.progress,
+                "Pruned segment backlog"
+            );
+
+            if output.pruned > 0 {
+                pruned += output.pruned;
+                let segment_stats = stats.entry(segment.segment()).or_insert((output.progress, 0));
+                *segment_stats = (output.progress, segment_stats.1 + output.pruned);
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// Deletes headers, transactions and receipts static files that are entirely below the
+    /// target block of the history expiry prune mode.
+    fn expire_static_files(&self, tip_block_number: BlockNumber) -> Result<(), PrunerError> {
+        let Some((to_block, prune_mode)) = self
+            .history_expiry
+            .map(|mode| {
+                mode.prune_target_block(tip_block_number, PruneSegment::Headers, PrunePurpose::User)
+            })
+            .transpose()?
+            .flatten()
+        else {
+            return Ok(())
+        };
+
+        let static_file_provider = self.provider_factory.static_file_provider();
+        for segment in [
+            StaticFileSegment::Headers,
+            StaticFileSegment::Transactions,
+            StaticFileSegment::Receipts,
+        ] {
+            let expired = static_file_provider.expire_jars_before(segment, to_block + 1)?;
+            if !expired.is_empty() {
+                info!(
+                    target: "pruner",
+                    %segment,
+                    %to_block,
+                    ?prune_mode,
+                    ?expired,
+                    lowest_block = ?static_file_provider.get_lowest_static_file_block(segment),
+                    "Deleted expired static files"
+                );
+            }
+        }
+
+        Ok(())
     }
 
     /// Returns pre-configured segments that needs to be pruned according to the highest
@@ -277,15 +546,34 @@ impl<DB: Database> Pruner<DB> {
         segments
     }
 
+    /// Applies the block interval and the schedule of the latest pruning configuration update, if
+    /// it changed since the last update.
+    fn apply_config_updates(&mut self) {
+        let Some(config_updates) = &mut self.config_updates else { return };
+        if !config_updates.has_changed().unwrap_or_default() {
+            return
+        }
+
+        let config = config_updates.borrow_and_update();
+        debug!(target: "pruner", block_interval = config.block_interval, schedule = ?config.schedule, "Applying pruning configuration update");
+        self.min_block_interval = config.block_interval;
+        self.schedule = config.schedule.clone();
+    }
+
     /// Returns `true` if the pruning is needed at the provided tip block number.
     /// This determined by the check against minimum pruning interval and last pruned block number.
-    pub fn is_pruning_needed(&self, tip_block_number: BlockNumber) -> bool {
-        let Some(tip_block_number) =
-            self.adjust_tip_block_number_to_finished_exex_height(tip_block_number)
-        else {
+    pub fn is_pruning_needed(&mut self, tip_block_number: BlockNumber) -> bool {
+        self.apply_config_updates();
+
+        let Some(tip_block_number) = self.adjust_tip_block_number(tip_block_number) else {
             return false
         };
 
+        if !self.schedule.is_pruning_allowed(SystemTime::now()) {
+            debug!(target: "pruner", windows = ?self.schedule.windows, "Outside of the pruning windows");
+            return false
+        }
+
         // Saturating subtraction is needed for the case when the chain was reverted, meaning
         // current block number might be less than the previous tip block number.
         // If that's the case, no pruning is needed as outdated data is also reverted.
@@ -304,39 +592,73 @@ impl<DB: Database> Pruner<DB> {
         }
     }
 
-    /// Adjusts the tip block number to the finished `ExEx` height. This is needed to not prune more
-    /// data than `ExExs` have processed. Depending on the height:
-    /// - [`FinishedExExHeight::NoExExs`] returns the tip block number as is as no adjustment for
-    ///   `ExExs` is needed.
+    /// Adjusts the tip block number to the finished `ExEx` height and the persisted height. This
+    /// is needed to not prune more data than `ExExs` have processed, or data that is still needed
+    /// for the blocks that aren't persisted yet. Depending on the finished `ExEx` height:
+    /// - [`FinishedExExHeight::NoExExs`] doesn't adjust the tip block number for `ExExs`.
     /// - [`FinishedExExHeight::NotReady`] returns `None` as not all `ExExs` have emitted a
     ///   `FinishedHeight` event yet.
-    /// - [`FinishedExExHeight::Height`] returns the finished `ExEx` height.
-    fn adjust_tip_block_number_to_finished_exex_height(
-        &self,
-        tip_block_number: BlockNumber,
-    ) -> Option<BlockNumber> {
+    /// - [`FinishedExExHeight::Height`] caps the tip block number at the finished `ExEx` height.
+    ///
+    /// Then, the tip block number is capped at the persisted height, if any.
+    ///
+    /// The number of blocks the tip is held back by each of them is recorded in the metrics.
+    fn adjust_tip_block_number(&self, tip_block_number: BlockNumber) -> Option<BlockNumber> {
+        let mut adjusted_tip_block_number = tip_block_number;
+
         match *self.finished_exex_height.borrow() {
-            FinishedExExHeight::NoExExs => Some(tip_block_number),
+            FinishedExExHeight::NoExExs => self.metrics.exex_held_back_blocks.set(0.0),
             FinishedExExHeight::NotReady => {
                 debug!(target: "pruner", %tip_block_number, "Not all ExExs have emitted a `FinishedHeight` event yet, can't prune");
-                None
+                self.metrics.exex_held_back_blocks.set(tip_block_number as f64);
+                return None
             }
             FinishedExExHeight::Height(finished_exex_height) => {
                 debug!(target: "pruner", %tip_block_number, %finished_exex_height, "Adjusting tip block number to the finished ExEx height");
-                Some(finished_exex_height)
+                self.metrics
+                    .exex_held_back_blocks
+                    .set(tip_block_number.saturating_sub(finished_exex_height) as f64);
+                adjusted_tip_block_number = adjusted_tip_block_number.min(finished_exex_height);
             }
         }
+
+        match *self.persisted_height.borrow() {
+            Some(persisted_height) => {
+                debug!(target: "pruner", %tip_block_number, %persisted_height, "Adjusting tip block number to the persisted height");
+                self.metrics
+                    .persistence_held_back_blocks
+                    .set(tip_block_number.saturating_sub(persisted_height) as f64);
+                adjusted_tip_block_number = adjusted_tip_block_number.min(persisted_height);
+            }
+            None => self.metrics.persistence_held_back_blocks.set(0.0),
+        }
+
+        Some(adjusted_tip_block_number)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::Pruner;
+    use crate::{segments, Pruner, PrunerBuilder};
+    use alloy_primitives::{BlockNumber, B256};
     use reth_chainspec::MAINNET;
-    use reth_db::test_utils::{create_test_rw_db, create_test_static_files_dir};
+    use reth_config::PruneConfig;
+    use reth_db::{
+        tables,
+        test_utils::{create_test_rw_db, create_test_static_files_dir},
+    };
     use reth_exex_types::FinishedExExHeight;
-    use reth_provider::{providers::StaticFileProvider, ProviderFactory};
+    use reth_provider::{providers::StaticFileProvider, ProviderFactory, PruneCheckpointReader};
+    use reth_prune_types::{
+        PruneDeleteLimits, PruneMode, PruneModes, PruneSchedule, PruneSegment,
+        MINIMUM_PRUNING_DISTANCE,
+    };
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::{
+        generators,
+        generators::{random_block_range, random_changeset_range, random_eoa_accounts},
+    };
 
     #[test]
     fn is_pruning_needed() {
@@ -350,9 +672,23 @@ mod tests {
 
         let (finished_exex_height_tx, finished_exex_height_rx) =
             tokio::sync::watch::channel(FinishedExExHeight::NoExExs);
+        let (persisted_height_tx, persisted_height_rx) = tokio::sync::watch::channel(None);
+        let (config_updates_tx, config_updates_rx) =
+            tokio::sync::watch::channel(PruneConfig::default());
 
-        let mut pruner =
-            Pruner::new(provider_factory, vec![], 5, 0, 5, None, finished_exex_height_rx);
+        let mut pruner = Pruner::new(
+            provider_factory,
+            vec![],
+            5,
+            0,
+            5,
+            None,
+            PruneSchedule::default(),
+            None,
+            finished_exex_height_rx,
+            persisted_height_rx,
+            Some(config_updates_rx),
+        );
 
         // No last pruned block number was set before
         let first_block_number = 1;
@@ -382,5 +718,266 @@ mod tests {
         // Adjust tip block number to the finished ExEx height that reaches the threshold
         finished_exex_height_tx.send(FinishedExExHeight::Height(third_block_number)).unwrap();
         assert!(pruner.is_pruning_needed(third_block_number));
+
+        // The finished ExEx height is above the tip block number
+        assert_eq!(pruner.adjust_tip_block_number(second_block_number), Some(second_block_number));
+
+        // Adjust tip block number to the persisted height that doesn't reach the threshold
+        persisted_height_tx.send(Some(second_block_number)).unwrap();
+        assert!(!pruner.is_pruning_needed(third_block_number));
+
+        // Adjust tip block number to the persisted height that reaches the threshold
+        persisted_height_tx.send(Some(third_block_number)).unwrap();
+        assert!(pruner.is_pruning_needed(third_block_number));
+
+        // Reloaded configuration with a min block interval that isn't reached
+        config_updates_tx.send(PruneConfig { block_interval: 10, ..Default::default() }).unwrap();
+        assert!(!pruner.is_pruning_needed(third_block_number));
+        assert_eq!(pruner.min_block_interval, 10);
+    }
+
+    #[test]
+    fn prune_backlog() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=20, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let mut transaction_senders = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                transaction_senders.push((
+                    transaction_senders.len() as u64,
+                    transaction.recover_signer().expect("recover signer"),
+                ));
+            }
+        }
+        db.insert_transaction_senders(transaction_senders.clone())
+            .expect("insert transaction senders");
+
+        // The regular run isn't allowed to delete anything, so only the backlog is pruned
+        let mut pruner = Pruner::new(
+            db.factory.clone(),
+            vec![Box::new(segments::SenderRecovery::new(PruneMode::Full))],
+            5,
+            0,
+            5,
+            None,
+            PruneSchedule { backlog_chunk_size: 10, ..Default::default() },
+            None,
+            tokio::sync::watch::channel(FinishedExExHeight::NoExExs).1,
+            tokio::sync::watch::channel(None).1,
+            None,
+        );
+
+        pruner.run(20).unwrap();
+        assert_eq!(
+            db.table::<tables::TransactionSenders>().unwrap().len(),
+            transaction_senders.len() - 10
+        );
+        let checkpoint = db
+            .factory
+            .provider()
+            .unwrap()
+            .get_prune_checkpoint(PruneSegment::SenderRecovery)
+            .unwrap()
+            .unwrap();
+        assert_eq!(checkpoint.tx_number, Some(9));
+        assert_eq!(checkpoint.prune_mode, PruneMode::Full);
+
+        // Backlog is pruned in chunks until the segment lags behind its prune mode by no more
+        // than a regular run can catch up with
+        for _ in 0..transaction_senders.len() / 10 {
+            pruner.run(20).unwrap();
+        }
+        let checkpoint = db
+            .factory
+            .provider()
+            .unwrap()
+            .get_prune_checkpoint(PruneSegment::SenderRecovery)
+            .unwrap()
+            .unwrap();
+        assert!(20 - checkpoint.block_number.unwrap() <= pruner.prune_max_blocks_per_run as u64);
+    }
+
+    #[test]
+    fn prune_backlog_within_delete_limit() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=20, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let mut transaction_senders = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                transaction_senders.push((
+                    transaction_senders.len() as u64,
+                    transaction.recover_signer().expect("recover signer"),
+                ));
+            }
+        }
+        db.insert_transaction_senders(transaction_senders.clone())
+            .expect("insert transaction senders");
+
+        // The backlog chunk is capped by the delete limit of the segment
+        let mut pruner = Pruner::new(
+            db.factory.clone(),
+            vec![Box::new(segments::SenderRecovery::new(PruneMode::Full))],
+            5,
+            0,
+            5,
+            None,
+            PruneSchedule {
+                backlog_chunk_size: 10,
+                delete_limits: PruneDeleteLimits { sender_recovery: Some(4), ..Default::default() },
+                ..Default::default()
+            },
+            None,
+            tokio::sync::watch::channel(FinishedExExHeight::NoExExs).1,
+            tokio::sync::watch::channel(None).1,
+            None,
+        );
+
+        pruner.run(20).unwrap();
+        assert_eq!(
+            db.table::<tables::TransactionSenders>().unwrap().len(),
+            transaction_senders.len() - 4
+        );
+    }
+
+    #[test]
+    fn prune_up_to_persisted_height() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=20, B256::ZERO, 1..2);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let mut transaction_senders = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                transaction_senders.push((
+                    transaction_senders.len() as u64,
+                    transaction.recover_signer().expect("recover signer"),
+                ));
+            }
+        }
+        db.insert_transaction_senders(transaction_senders).expect("insert transaction senders");
+
+        let (persisted_height_tx, persisted_height_rx) = tokio::sync::watch::channel(Some(10));
+        let mut pruner = PrunerBuilder::new(PruneConfig {
+            segments: PruneModes { sender_recovery: Some(PruneMode::Full), ..PruneModes::none() },
+            ..Default::default()
+        })
+        .persisted_height(persisted_height_rx)
+        .build(db.factory.clone());
+        let checkpoint = || {
+            db.factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::SenderRecovery)
+                .unwrap()
+                .unwrap()
+        };
+
+        // The blocks above the persisted height are retained
+        pruner.run(20).unwrap();
+        assert_eq!(checkpoint().block_number, Some(10));
+        assert_eq!(db.table::<tables::TransactionSenders>().unwrap().len(), 10);
+
+        // The rest of the blocks are pruned once they're persisted
+        persisted_height_tx.send(Some(20)).unwrap();
+        pruner.run(20).unwrap();
+        assert_eq!(checkpoint().block_number, Some(20));
+        assert!(db.table::<tables::TransactionSenders>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn dry_run() {
+        // History can only be pruned beyond the minimum pruning distance from the tip
+        const TIP: BlockNumber = MINIMUM_PRUNING_DISTANCE + 100;
+
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=100, B256::ZERO, 1..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let mut transaction_senders = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                transaction_senders.push((
+                    transaction_senders.len() as u64,
+                    transaction.recover_signer().expect("recover signer"),
+                ));
+            }
+        }
+        db.insert_transaction_senders(transaction_senders).expect("insert transaction senders");
+
+        let accounts = random_eoa_accounts(&mut rng, 2);
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, vec![]))),
+            0..0,
+            0..0,
+        );
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets, None).expect("insert history");
+
+        let mut pruner = Pruner::new(
+            db.factory.clone(),
+            vec![
+                Box::new(segments::SenderRecovery::new(PruneMode::Before(50))),
+                Box::new(segments::AccountHistory::new(PruneMode::Before(50))),
+            ],
+            5,
+            usize::MAX,
+            100,
+            None,
+            PruneSchedule::default(),
+            None,
+            tokio::sync::watch::channel(FinishedExExHeight::NoExExs).1,
+            tokio::sync::watch::channel(None).1,
+            None,
+        );
+
+        let senders = db.table::<tables::TransactionSenders>().unwrap();
+        let changesets = db.table::<tables::AccountChangeSets>().unwrap();
+        let history = db.table::<tables::AccountsHistory>().unwrap();
+
+        // The dry run doesn't delete entries or save checkpoints
+        let stats = pruner.dry_run(TIP).unwrap();
+        assert_eq!(db.table::<tables::TransactionSenders>().unwrap(), senders);
+        assert_eq!(db.table::<tables::AccountChangeSets>().unwrap(), changesets);
+        assert_eq!(db.table::<tables::AccountsHistory>().unwrap(), history);
+        assert_eq!(
+            db.factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::SenderRecovery)
+                .unwrap(),
+            None
+        );
+
+        // The counted entries are the ones that are pruned by a run
+        pruner.run(TIP).unwrap();
+        let deleted_senders =
+            senders.len() - db.table::<tables::TransactionSenders>().unwrap().len();
+        let deleted_history = changesets.len() + history.len() -
+            db.table::<tables::AccountChangeSets>().unwrap().len() -
+            db.table::<tables::AccountsHistory>().unwrap().len();
+        assert!(deleted_senders > 0);
+        assert!(deleted_history > 0);
+        assert_eq!(
+            stats.get(&PruneSegment::SenderRecovery).map(|(_, count)| *count),
+            Some(deleted_senders)
+        );
+        assert_eq!(
+            stats.get(&PruneSegment::AccountHistory).map(|(_, count)| *count),
+            Some(deleted_history)
+        );
     }
 }
@@ -1,13 +1,17 @@
 use crate::{
     segments::{
-        history::prune_history_indices, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
+        count_table_with_range,
+        history::{count_history_indices, prune_history_indices},
+        PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
     },
     PrunerError,
 };
+use alloy_primitives::Address;
 use reth_db::tables;
 use reth_db_api::{database::Database, models::ShardedKey};
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW};
 use reth_prune_types::{PruneInterruptReason, PruneMode, PruneProgress, PruneSegment};
+use std::collections::BTreeSet;
 use tracing::{instrument, trace};
 
 /// Number of account history tables to prune in one step.
@@ -19,11 +23,19 @@ const ACCOUNT_HISTORY_TABLES_TO_PRUNE: usize = 2;
 #[derive(Debug)]
 pub struct AccountHistory {
     mode: PruneMode,
+    /// Accounts whose changesets and history indices are never pruned.
+    retained_addresses: BTreeSet<Address>,
 }
 
 impl AccountHistory {
     pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+        Self { mode, retained_addresses: BTreeSet::new() }
+    }
+
+    /// Sets the accounts whose changesets and history indices are never pruned.
+    pub fn with_retained_addresses(mut self, retained_addresses: BTreeSet<Address>) -> Self {
+        self.retained_addresses = retained_addresses;
+        self
     }
 }
 
@@ -64,16 +76,25 @@ impl<DB: Database> Segment<DB> for AccountHistory {
         }
 
         let mut last_changeset_pruned_block = None;
+        let mut last_changeset_skipped_block = None;
         let (pruned_changesets, done) = provider
             .prune_table_with_range::<tables::AccountChangeSets>(
                 range,
                 &mut limiter,
-                |_| false,
+                |row| {
+                    let skip = self.retained_addresses.contains(&row.1.address);
+                    if skip {
+                        last_changeset_skipped_block = Some(row.0);
+                    }
+                    skip
+                },
                 |row| last_changeset_pruned_block = Some(row.0),
             )?;
         trace!(target: "pruner", pruned = %pruned_changesets, %done, "Pruned account history (changesets)");
 
+        // Retained changesets are walked over as well, so they count towards the checkpoint.
         let last_changeset_pruned_block = last_changeset_pruned_block
+            .max(last_changeset_skipped_block)
             // If there's more account account changesets to prune, set the checkpoint block number
             // to previous, so we could finish pruning its account changesets on the next run.
             .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
@@ -84,6 +105,7 @@ impl<DB: Database> Segment<DB> for AccountHistory {
             last_changeset_pruned_block,
             |a, b| a.key == b.key,
             |key| ShardedKey::last(key.key),
+            |key| self.retained_addresses.contains(&key.key),
         )?;
         trace!(target: "pruner", %processed, pruned = %pruned_indices, %done, "Pruned account history (history)");
 
@@ -98,6 +120,29 @@ impl<DB: Database> Segment<DB> for AccountHistory {
             }),
         })
     }
+
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError> {
+        let Some(range) = input.get_next_block_range() else { return Ok(0) };
+        let range_end = *range.end();
+
+        let changesets = count_table_with_range::<tables::AccountChangeSets, _>(
+            provider.tx_ref(),
+            range,
+            |row| self.retained_addresses.contains(&row.1.address),
+        )?;
+        let indices = count_history_indices::<_, tables::AccountsHistory, _>(
+            provider,
+            range_end,
+            |key| ShardedKey::last(key.key),
+            |key| self.retained_addresses.contains(&key.key),
+        )?;
+
+        Ok(changesets + indices)
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 use std::num::NonZeroUsize;
 
 use crate::{
-    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    segments::{count_table_with_range, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
     PrunerError,
 };
 use itertools::Itertools;
@@ -13,7 +13,7 @@ use reth_db_api::{
 };
 
 use alloy_primitives::BlockNumber;
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW};
 use reth_prune_types::{PruneLimiter, PruneMode, PruneProgress, PruneSegment};
 use tracing::{instrument, trace};
 
@@ -97,6 +97,23 @@ impl<DB: Database> Segment<DB> for Headers {
             }),
         })
     }
+
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError> {
+        let Some(range) = input.get_next_block_range() else { return Ok(0) };
+
+        let tx = provider.tx_ref();
+        Ok(count_table_with_range::<tables::Headers, _>(tx, range.clone(), |_| false)? +
+            count_table_with_range::<tables::HeaderTerminalDifficulties, _>(
+                tx,
+                range.clone(),
+                |_| false,
+            )? +
+            count_table_with_range::<tables::CanonicalHeaders, _>(tx, range, |_| false)?)
+    }
 }
 
 type Walker<'a, DB, T> = RangeWalker<'a, T, <<DB as Database>::TXMut as DbTxMut>::CursorMut<T>>;
@@ -5,12 +5,13 @@ use reth_db_api::{
     database::Database,
     models::ShardedKey,
     table::Table,
-    transaction::DbTxMut,
+    transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProvider, DatabaseProviderRW};
 
-/// Prune history indices up to the provided block, inclusive.
+/// Prune history indices up to the provided block, inclusive. Shards of keys for which
+/// `retain_key` returns `true` are kept untouched.
 ///
 /// Returns total number of processed (walked) and deleted entities.
 pub(crate) fn prune_history_indices<DB, T, SK>(
@@ -18,6 +19,7 @@ pub(crate) fn prune_history_indices<DB, T, SK>(
     to_block: BlockNumber,
     key_matches: impl Fn(&T::Key, &T::Key) -> bool,
     last_key: impl Fn(&T::Key) -> T::Key,
+    retain_key: impl Fn(&T::Key) -> bool,
 ) -> Result<(usize, usize), DatabaseError>
 where
     DB: Database,
@@ -37,6 +39,16 @@ where
     while let Some(result) = cursor.next()? {
         let (key, blocks): (T::Key, BlockNumberList) = result;
 
+        // If the key is retained, skip to the last shard for this key, leaving all its shards
+        // untouched.
+        if retain_key(&key) {
+            if key.as_ref().highest_block_number != u64::MAX {
+                cursor.seek_exact(last_key(&key))?;
+            }
+            processed += 1;
+            continue
+        }
+
         // If shard consists only of block numbers less than the target one, delete shard
         // completely.
         if key.as_ref().highest_block_number <= to_block {
@@ -110,3 +122,40 @@ where
 
     Ok((processed, deleted))
 }
+
+/// Counts the history indices that [`prune_history_indices`] would delete when pruning up to the
+/// provided block, inclusive.
+pub(crate) fn count_history_indices<TX, T, SK>(
+    provider: &DatabaseProvider<TX>,
+    to_block: BlockNumber,
+    last_key: impl Fn(&T::Key) -> T::Key,
+    retain_key: impl Fn(&T::Key) -> bool,
+) -> Result<usize, DatabaseError>
+where
+    TX: DbTx,
+    T: Table<Value = BlockNumberList>,
+    T::Key: AsRef<ShardedKey<SK>>,
+{
+    let mut count = 0;
+    let mut cursor = provider.tx_ref().cursor_read::<T>()?;
+
+    while let Some((key, blocks)) = cursor.next()? {
+        let highest_block_number = key.as_ref().highest_block_number;
+        let retained = retain_key(&key);
+
+        // Shards without block numbers higher than the target one are deleted, or replaced by
+        // their previous shard if they're the last shard for the sharded key.
+        if !retained &&
+            (highest_block_number <= to_block || blocks.iter().all(|block| block <= to_block))
+        {
+            count += 1;
+        }
+
+        // Further shards for this sharded key don't contain block numbers up to the target one.
+        if highest_block_number != u64::MAX && (retained || highest_block_number >= to_block) {
+            cursor.seek_exact(last_key(&key))?;
+        }
+    }
+
+    Ok(count)
+}
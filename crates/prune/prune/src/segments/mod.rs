@@ -15,16 +15,26 @@ use alloy_primitives::{BlockNumber, TxNumber};
 pub use headers::Headers;
 pub use receipts::Receipts;
 pub use receipts_by_logs::ReceiptsByLogs;
-use reth_db_api::database::Database;
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    table::{Table, TableRow},
+    transaction::DbTx,
+    DatabaseError,
+};
 use reth_provider::{
-    errors::provider::ProviderResult, BlockReader, DatabaseProviderRW, PruneCheckpointWriter,
+    errors::provider::ProviderResult, BlockReader, DatabaseProvider, DatabaseProviderRO,
+    DatabaseProviderRW, PruneCheckpointWriter,
 };
 use reth_prune_types::{
     PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
 };
 pub use sender_recovery::SenderRecovery;
 pub use set::SegmentSet;
-use std::{fmt::Debug, ops::RangeInclusive};
+use std::{
+    fmt::Debug,
+    ops::{RangeBounds, RangeInclusive},
+};
 pub use storage_history::StorageHistory;
 use tracing::error;
 pub use transaction_lookup::TransactionLookup;
@@ -51,6 +61,14 @@ pub trait Segment<DB: Database>: Debug + Send + Sync {
         input: PruneInput,
     ) -> Result<PruneOutput, PrunerError>;
 
+    /// Counts the entries that [`Segment::prune`] would delete for the provided input, ignoring
+    /// its limiter, without modifying the database.
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError>;
+
     /// Save checkpoint for [`Self::segment`] to the database.
     fn save_checkpoint(
         &self,
@@ -81,9 +99,9 @@ impl PruneInput {
     /// 2. If checkpoint doesn't exist, return 0.
     ///
     /// To get the range end: get last tx number for `to_block`.
-    pub(crate) fn get_next_tx_num_range<DB: Database>(
+    pub(crate) fn get_next_tx_num_range<TX: DbTx>(
         &self,
-        provider: &DatabaseProviderRW<DB>,
+        provider: &DatabaseProvider<TX>,
     ) -> ProviderResult<Option<RangeInclusive<TxNumber>>> {
         let from_tx_number = self.previous_checkpoint
             // Checkpoint exists, prune from the next transaction after the highest pruned one
@@ -151,6 +169,23 @@ impl PruneInput {
     }
 }
 
+/// Counts the entries of the table within the range that aren't skipped by `skip_filter`, i.e. the
+/// entries that pruning the range would delete.
+pub(crate) fn count_table_with_range<T: Table, TX: DbTx>(
+    tx: &TX,
+    keys: impl RangeBounds<T::Key>,
+    mut skip_filter: impl FnMut(&TableRow<T>) -> bool,
+) -> Result<usize, DatabaseError> {
+    let mut cursor = tx.cursor_read::<T>()?;
+    let mut count = 0;
+    for row in cursor.walk_range(keys)? {
+        if !skip_filter(&row?) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
 /// Segment pruning output, see [`Segment::prune`].
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct PruneOutput {
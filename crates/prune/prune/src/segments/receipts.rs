@@ -1,24 +1,40 @@
 use crate::{
-    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    segments::{count_table_with_range, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
     PrunerError,
 };
+use alloy_primitives::{Address, Log};
 use reth_db::tables;
 use reth_db_api::database::Database;
 use reth_provider::{
-    errors::provider::ProviderResult, DatabaseProviderRW, PruneCheckpointWriter,
-    TransactionsProvider,
+    errors::provider::ProviderResult, DatabaseProviderRO, DatabaseProviderRW,
+    PruneCheckpointWriter, TransactionsProvider,
 };
 use reth_prune_types::{PruneCheckpoint, PruneMode, PruneProgress, PruneSegment};
+use std::collections::BTreeSet;
 use tracing::{instrument, trace};
 
 #[derive(Debug)]
 pub struct Receipts {
     mode: PruneMode,
+    /// Addresses whose receipts, i.e. receipts with logs emitted by them, are never pruned.
+    retained_addresses: BTreeSet<Address>,
 }
 
 impl Receipts {
     pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+        Self { mode, retained_addresses: BTreeSet::new() }
+    }
+
+    /// Sets the addresses whose receipts, i.e. receipts with logs emitted by them, are never
+    /// pruned.
+    pub fn with_retained_addresses(mut self, retained_addresses: BTreeSet<Address>) -> Self {
+        self.retained_addresses = retained_addresses;
+        self
+    }
+
+    /// Returns `true` if one of the logs of a receipt was emitted by a retained address.
+    fn is_retained(&self, logs: &[Log]) -> bool {
+        logs.iter().any(|log| self.retained_addresses.contains(&log.address))
     }
 }
 
@@ -48,15 +64,26 @@ impl<DB: Database> Segment<DB> for Receipts {
 
         let mut limiter = input.limiter;
 
-        let mut last_pruned_transaction = tx_range_end;
+        let mut last_pruned_transaction = None;
+        let mut last_skipped_transaction = None;
         let (pruned, done) = provider.prune_table_with_range::<tables::Receipts>(
             tx_range,
             &mut limiter,
-            |_| false,
-            |row| last_pruned_transaction = row.0,
+            |(tx_num, receipt)| {
+                let skip = self.is_retained(&receipt.logs);
+                if skip {
+                    last_skipped_transaction = Some(*tx_num);
+                }
+                skip
+            },
+            |row| last_pruned_transaction = Some(row.0),
         )?;
         trace!(target: "pruner", %pruned, %done, "Pruned receipts");
 
+        // Retained receipts are walked over as well, so they count towards the checkpoint.
+        let last_pruned_transaction =
+            last_pruned_transaction.max(last_skipped_transaction).unwrap_or(tx_range_end);
+
         let last_pruned_block = provider
             .transaction_block(last_pruned_transaction)?
             .ok_or(PrunerError::InconsistentData("Block for transaction is not found"))?
@@ -76,6 +103,19 @@ impl<DB: Database> Segment<DB> for Receipts {
         })
     }
 
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError> {
+        let Some(tx_range) = input.get_next_tx_num_range(provider)? else { return Ok(0) };
+        Ok(count_table_with_range::<tables::Receipts, _>(
+            provider.tx_ref(),
+            tx_range,
+            |(_, receipt)| self.is_retained(&receipt.logs),
+        )?)
+    }
+
     fn save_checkpoint(
         &self,
         provider: &DatabaseProviderRW<DB>,
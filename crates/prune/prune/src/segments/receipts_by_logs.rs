@@ -1,42 +1,51 @@
 use crate::{
-    segments::{PruneInput, PruneOutput, Segment},
+    segments::{count_table_with_range, PruneInput, PruneOutput, Segment},
     PrunerError,
 };
+use alloy_primitives::{Address, BlockNumber, Log};
 use reth_db::tables;
 use reth_db_api::database::Database;
-use reth_provider::{BlockReader, DatabaseProviderRW, PruneCheckpointWriter, TransactionsProvider};
+use reth_provider::{
+    BlockReader, DatabaseProviderRO, DatabaseProviderRW, PruneCheckpointWriter,
+    TransactionsProvider,
+};
 use reth_prune_types::{
     PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsLogPruneConfig,
-    MINIMUM_PRUNING_DISTANCE,
+    ReceiptsLogPruneRule, MINIMUM_PRUNING_DISTANCE,
 };
+use std::collections::BTreeSet;
 use tracing::{instrument, trace};
 
 #[derive(Debug)]
 pub struct ReceiptsByLogs {
     config: ReceiptsLogPruneConfig,
+    /// Addresses whose receipts, i.e. receipts with logs emitted by them, are never pruned.
+    retained_addresses: BTreeSet<Address>,
 }
 
 impl ReceiptsByLogs {
     pub const fn new(config: ReceiptsLogPruneConfig) -> Self {
-        Self { config }
-    }
-}
-
-impl<DB: Database> Segment<DB> for ReceiptsByLogs {
-    fn segment(&self) -> PruneSegment {
-        PruneSegment::ContractLogs
+        Self { config, retained_addresses: BTreeSet::new() }
     }
 
-    fn mode(&self) -> Option<PruneMode> {
-        None
+    /// Sets the addresses whose receipts, i.e. receipts with logs emitted by them, are never
+    /// pruned.
+    pub fn with_retained_addresses(mut self, retained_addresses: BTreeSet<Address>) -> Self {
+        self.retained_addresses = retained_addresses;
+        self
     }
 
-    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
-    fn prune(
+    /// Returns the target block of receipts without logs matching any rule, and the block ranges
+    /// to prune starting after the last pruned block, together with the number of the returned
+    /// rules whose matching receipts are retained within each range.
+    #[allow(clippy::type_complexity)]
+    fn block_ranges(
         &self,
-        provider: &DatabaseProviderRW<DB>,
-        input: PruneInput,
-    ) -> Result<PruneOutput, PrunerError> {
+        input: &PruneInput,
+    ) -> Result<
+        (BlockNumber, Vec<(BlockNumber, BlockNumber, usize)>, Vec<&ReceiptsLogPruneRule>),
+        PrunerError,
+    > {
         // Contract log filtering removes every receipt possible except the ones in the list. So,
         // for the other receipts it's as if they had a `PruneMode::Distance()` of
         // `MINIMUM_PRUNING_DISTANCE`.
@@ -45,50 +54,39 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
             .map(|(bn, _)| bn)
             .unwrap_or_default();
 
-        // Get status checkpoint from latest run
-        let mut last_pruned_block =
+        let last_pruned_block =
             input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
 
-        let initial_last_pruned_block = last_pruned_block;
-
-        let mut from_tx_number = match initial_last_pruned_block {
-            Some(block) => provider
-                .block_body_indices(block)?
-                .map(|block| block.last_tx_num() + 1)
-                .unwrap_or(0),
-            None => 0,
-        };
-
         // Figure out what receipts have already been pruned, so we can have an accurate
-        // `address_filter`
-        let address_filter = self.config.group_by_block(input.to_block, last_pruned_block)?;
+        // `log_filter`
+        let log_filter = self.config.group_by_block(input.to_block, last_pruned_block)?;
 
         // Splits all transactions in different block ranges. Each block range will have its own
-        // filter address list and will check it while going through the table
+        // filter rule list and will check it while going through the table
         //
         // Example:
-        // For an `address_filter` such as:
-        // { block9: [a1, a2], block20: [a3, a4, a5] }
+        // For a `log_filter` such as:
+        // { block9: [r1, r2], block20: [r3, r4, r5] }
         //
         // The following structures will be created in the exact order as showed:
         // `block_ranges`: [
-        //    (block0, block8, 0 addresses),
-        //    (block9, block19, 2 addresses),
-        //    (block20, to_block, 5 addresses)
+        //    (block0, block8, 0 rules),
+        //    (block9, block19, 2 rules),
+        //    (block20, to_block, 5 rules)
         //  ]
-        // `filtered_addresses`: [a1, a2, a3, a4, a5]
+        // `filtered_rules`: [r1, r2, r3, r4, r5]
         //
         // The first range will delete all receipts between block0 - block8
         // The second range will delete all receipts between block9 - 19, except the ones with
-        //     emitter logs from these addresses: [a1, a2].
+        //     logs matching these rules: [r1, r2].
         // The third range will delete all receipts between block20 - to_block, except the ones with
-        //     emitter logs from these addresses: [a1, a2, a3, a4, a5]
+        //     logs matching these rules: [r1, r2, r3, r4, r5]
         let mut block_ranges = vec![];
-        let mut blocks_iter = address_filter.iter().peekable();
-        let mut filtered_addresses = vec![];
+        let mut blocks_iter = log_filter.iter().peekable();
+        let mut filtered_rules = vec![];
 
-        while let Some((start_block, addresses)) = blocks_iter.next() {
-            filtered_addresses.extend_from_slice(addresses);
+        while let Some((start_block, rules)) = blocks_iter.next() {
+            filtered_rules.extend_from_slice(rules);
 
             // This will clear all receipts before the first  appearance of a contract log or since
             // the block after the last pruned one.
@@ -102,24 +100,68 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
             let end_block =
                 blocks_iter.peek().map(|(next_block, _)| *next_block - 1).unwrap_or(to_block);
 
-            // Addresses in lower block ranges, are still included in the inclusion list for future
+            // Rules in lower block ranges, are still included in the inclusion list for future
             // ranges.
-            block_ranges.push((*start_block, end_block, filtered_addresses.len()));
+            block_ranges.push((*start_block, end_block, filtered_rules.len()));
         }
 
         trace!(
             target: "pruner",
             ?block_ranges,
-            ?filtered_addresses,
-            "Calculated block ranges and filtered addresses",
+            ?filtered_rules,
+            "Calculated block ranges and filtered rules",
         );
 
+        Ok((to_block, block_ranges, filtered_rules))
+    }
+
+    /// Returns `true` if one of the logs of a receipt matches one of the rules or was emitted by a
+    /// retained address.
+    fn is_retained(&self, logs: &[Log], rules: &[&ReceiptsLogPruneRule]) -> bool {
+        logs.iter().any(|log| {
+            rules.iter().any(|rule| rule.matches(log)) ||
+                self.retained_addresses.contains(&log.address)
+        })
+    }
+}
+
+impl<DB: Database> Segment<DB> for ReceiptsByLogs {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::ContractLogs
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        None
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        // Get status checkpoint from latest run
+        let mut last_pruned_block =
+            input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
+
+        let initial_last_pruned_block = last_pruned_block;
+
+        let mut from_tx_number = match initial_last_pruned_block {
+            Some(block) => provider
+                .block_body_indices(block)?
+                .map(|block| block.last_tx_num() + 1)
+                .unwrap_or(0),
+            None => 0,
+        };
+
+        let (to_block, block_ranges, filtered_rules) = self.block_ranges(&input)?;
+
         let mut limiter = input.limiter;
 
         let mut done = true;
         let mut pruned = 0;
         let mut last_pruned_transaction = None;
-        for (start_block, end_block, num_addresses) in block_ranges {
+        for (start_block, end_block, num_rules) in block_ranges {
             let block_range = start_block..=end_block;
 
             // Calculate the transaction range from this block range
@@ -143,10 +185,7 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
                 tx_range,
                 &mut limiter,
                 |(tx_num, receipt)| {
-                    let skip = num_addresses > 0 &&
-                        receipt.logs.iter().any(|log| {
-                            filtered_addresses[..num_addresses].contains(&&log.address)
-                        });
+                    let skip = self.is_retained(&receipt.logs, &filtered_rules[..num_rules]);
 
                     if skip {
                         last_skipped_transaction = *tx_num;
@@ -211,6 +250,42 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
 
         Ok(PruneOutput { progress, pruned, checkpoint: None })
     }
+
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError> {
+        let mut from_tx_number =
+            match input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number) {
+                Some(block) => provider
+                    .block_body_indices(block)?
+                    .map(|block| block.last_tx_num() + 1)
+                    .unwrap_or(0),
+                None => 0,
+            };
+
+        let (_, block_ranges, filtered_rules) = self.block_ranges(&input)?;
+
+        let mut count = 0;
+        for (_, end_block, num_rules) in block_ranges {
+            let Some(tx_range_end) =
+                provider.block_body_indices(end_block)?.map(|body| body.last_tx_num())
+            else {
+                continue
+            };
+
+            count += count_table_with_range::<tables::Receipts, _>(
+                provider.tx_ref(),
+                from_tx_number..=tx_range_end,
+                |(_, receipt)| self.is_retained(&receipt.logs, &filtered_rules[..num_rules]),
+            )?;
+
+            from_tx_number = tx_range_end + 1;
+        }
+
+        Ok(count)
+    }
 }
 
 #[cfg(test)]
@@ -221,13 +296,14 @@ mod tests {
     use reth_db::tables;
     use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
     use reth_provider::{PruneCheckpointReader, TransactionsProvider};
-    use reth_prune_types::{PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig};
+    use reth_prune_types::{
+        PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig, ReceiptsLogPruneRule,
+    };
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_testing_utils::{
         generators,
         generators::{random_block_range, random_eoa_account, random_log, random_receipt},
     };
-    use std::collections::BTreeMap;
 
     #[test]
     fn prune_receipts_by_logs() {
@@ -275,8 +351,10 @@ mod tests {
 
             let prune_before_block: usize = 20;
             let prune_mode = PruneMode::Before(prune_before_block as u64);
-            let receipts_log_filter =
-                ReceiptsLogPruneConfig(BTreeMap::from([(deposit_contract_addr, prune_mode)]));
+            let receipts_log_filter = ReceiptsLogPruneConfig(vec![ReceiptsLogPruneRule::address(
+                deposit_contract_addr,
+                prune_mode,
+            )]);
 
             let limiter = PruneLimiter::default().set_deleted_entries_limit(10);
 
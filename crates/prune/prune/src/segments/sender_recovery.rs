@@ -1,10 +1,10 @@
 use crate::{
-    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    segments::{count_table_with_range, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
     PrunerError,
 };
 use reth_db::tables;
 use reth_db_api::database::Database;
-use reth_provider::{DatabaseProviderRW, TransactionsProvider};
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW, TransactionsProvider};
 use reth_prune_types::{PruneMode, PruneProgress, PruneSegment};
 use tracing::{instrument, trace};
 
@@ -72,6 +72,19 @@ impl<DB: Database> Segment<DB> for SenderRecovery {
             }),
         })
     }
+
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError> {
+        let Some(tx_range) = input.get_next_tx_num_range(provider)? else { return Ok(0) };
+        Ok(count_table_with_range::<tables::TransactionSenders, _>(
+            provider.tx_ref(),
+            tx_range,
+            |_| false,
+        )?)
+    }
 }
 
 #[cfg(test)]
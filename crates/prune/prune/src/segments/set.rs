@@ -45,20 +45,27 @@ impl<DB: Database> SegmentSet<DB> {
             account_history,
             storage_history,
             receipts_log_filter,
+            retained_addresses,
         } = prune_modes;
 
         Self::default()
             // Account history
-            .segment_opt(account_history.map(AccountHistory::new))
+            .segment_opt(account_history.map(|mode| {
+                AccountHistory::new(mode).with_retained_addresses(retained_addresses.clone())
+            }))
             // Storage history
-            .segment_opt(storage_history.map(StorageHistory::new))
+            .segment_opt(storage_history.map(|mode| {
+                StorageHistory::new(mode).with_retained_addresses(retained_addresses.clone())
+            }))
             // Receipts
-            .segment_opt(receipts.map(Receipts::new))
+            .segment_opt(receipts.map(|mode| {
+                Receipts::new(mode).with_retained_addresses(retained_addresses.clone())
+            }))
             // Receipts by logs
-            .segment_opt(
-                (!receipts_log_filter.is_empty())
-                    .then(|| ReceiptsByLogs::new(receipts_log_filter.clone())),
-            )
+            .segment_opt((!receipts_log_filter.is_empty()).then(|| {
+                ReceiptsByLogs::new(receipts_log_filter.clone())
+                    .with_retained_addresses(retained_addresses.clone())
+            }))
             // Transaction lookup
             .segment_opt(transaction_lookup.map(TransactionLookup::new))
             // Sender recovery
@@ -1,16 +1,20 @@
 use crate::{
     segments::{
-        history::prune_history_indices, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
+        count_table_with_range,
+        history::{count_history_indices, prune_history_indices},
+        PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
     },
     PrunerError,
 };
+use alloy_primitives::Address;
 use reth_db::tables;
 use reth_db_api::{
     database::Database,
     models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress},
 };
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW};
 use reth_prune_types::{PruneInterruptReason, PruneMode, PruneProgress, PruneSegment};
+use std::collections::BTreeSet;
 use tracing::{instrument, trace};
 
 /// Number of storage history tables to prune in one step
@@ -22,11 +26,19 @@ const STORAGE_HISTORY_TABLES_TO_PRUNE: usize = 2;
 #[derive(Debug)]
 pub struct StorageHistory {
     mode: PruneMode,
+    /// Accounts whose storage changesets and history indices are never pruned.
+    retained_addresses: BTreeSet<Address>,
 }
 
 impl StorageHistory {
     pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+        Self { mode, retained_addresses: BTreeSet::new() }
+    }
+
+    /// Sets the accounts whose storage changesets and history indices are never pruned.
+    pub fn with_retained_addresses(mut self, retained_addresses: BTreeSet<Address>) -> Self {
+        self.retained_addresses = retained_addresses;
+        self
     }
 }
 
@@ -67,16 +79,25 @@ impl<DB: Database> Segment<DB> for StorageHistory {
         }
 
         let mut last_changeset_pruned_block = None;
+        let mut last_changeset_skipped_block = None;
         let (pruned_changesets, done) = provider
             .prune_table_with_range::<tables::StorageChangeSets>(
                 BlockNumberAddress::range(range),
                 &mut limiter,
-                |_| false,
+                |row| {
+                    let skip = self.retained_addresses.contains(&row.0.address());
+                    if skip {
+                        last_changeset_skipped_block = Some(row.0.block_number());
+                    }
+                    skip
+                },
                 |row| last_changeset_pruned_block = Some(row.0.block_number()),
             )?;
         trace!(target: "pruner", deleted = %pruned_changesets, %done, "Pruned storage history (changesets)");
 
+        // Retained changesets are walked over as well, so they count towards the checkpoint.
         let last_changeset_pruned_block = last_changeset_pruned_block
+            .max(last_changeset_skipped_block)
             // If there's more storage storage changesets to prune, set the checkpoint block number
             // to previous, so we could finish pruning its storage changesets on the next run.
             .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
@@ -87,6 +108,7 @@ impl<DB: Database> Segment<DB> for StorageHistory {
             last_changeset_pruned_block,
             |a, b| a.address == b.address && a.sharded_key.key == b.sharded_key.key,
             |key| StorageShardedKey::last(key.address, key.sharded_key.key),
+            |key| self.retained_addresses.contains(&key.address),
         )?;
         trace!(target: "pruner", %processed, deleted = %pruned_indices, %done, "Pruned storage history (history)");
 
@@ -101,6 +123,29 @@ impl<DB: Database> Segment<DB> for StorageHistory {
             }),
         })
     }
+
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError> {
+        let Some(range) = input.get_next_block_range() else { return Ok(0) };
+        let range_end = *range.end();
+
+        let changesets = count_table_with_range::<tables::StorageChangeSets, _>(
+            provider.tx_ref(),
+            BlockNumberAddress::range(range),
+            |row| self.retained_addresses.contains(&row.0.address()),
+        )?;
+        let indices = count_history_indices::<_, tables::StoragesHistory, _>(
+            provider,
+            range_end,
+            |key| StorageShardedKey::last(key.address, key.sharded_key.key),
+            |key| self.retained_addresses.contains(&key.address),
+        )?;
+
+        Ok(changesets + indices)
+    }
 }
 
 #[cfg(test)]
@@ -4,8 +4,8 @@ use crate::{
 };
 use rayon::prelude::*;
 use reth_db::tables;
-use reth_db_api::database::Database;
-use reth_provider::{DatabaseProviderRW, TransactionsProvider};
+use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
+use reth_provider::{DatabaseProviderRO, DatabaseProviderRW, TransactionsProvider};
 use reth_prune_types::{PruneMode, PruneProgress, PruneSegment};
 use tracing::{instrument, trace};
 
@@ -99,6 +99,29 @@ impl<DB: Database> Segment<DB> for TransactionLookup {
             }),
         })
     }
+
+    fn count(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        input: PruneInput,
+    ) -> Result<usize, PrunerError> {
+        let Some(tx_range) = input.get_next_tx_num_range(provider)? else { return Ok(0) };
+
+        let hashes = provider
+            .transactions_by_tx_range(tx_range)?
+            .into_par_iter()
+            .map(|transaction| transaction.hash())
+            .collect::<Vec<_>>();
+
+        let mut cursor = provider.tx_ref().cursor_read::<tables::TransactionHashNumbers>()?;
+        let mut count = 0;
+        for hash in hashes {
+            if cursor.seek_exact(hash)?.is_some() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
 }
 
 #[cfg(test)]
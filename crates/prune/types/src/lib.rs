@@ -11,22 +11,120 @@
 mod checkpoint;
 mod limiter;
 mod mode;
+mod schedule;
 mod segment;
 mod target;
 
 pub use checkpoint::PruneCheckpoint;
 pub use limiter::PruneLimiter;
 pub use mode::PruneMode;
+pub use schedule::{
+    PruneDeleteLimits, PruneSchedule, PruneWindow, PruneWindowError, DEFAULT_BACKLOG_CHUNK_SIZE,
+};
 pub use segment::{PrunePurpose, PruneSegment, PruneSegmentError};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::BTreeMap;
 pub use target::{PruneModes, MINIMUM_PRUNING_DISTANCE};
+use thiserror::Error;
 
-use alloy_primitives::{Address, BlockNumber};
+use alloy_primitives::{Address, BlockNumber, Log, B256};
 
-/// Configuration for pruning receipts not associated with logs emitted by the specified contracts.
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
-pub struct ReceiptsLogPruneConfig(pub BTreeMap<Address, PruneMode>);
+/// Configuration for pruning receipts not associated with logs matching any of the specified
+/// rules.
+///
+/// Besides a list of rules, the legacy format of a map from contract addresses to prune modes is
+/// accepted on deserialization, e.g. `{ "0xa0b8..eb48" = { before = 17000000 } }`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(transparent)]
+pub struct ReceiptsLogPruneConfig(pub Vec<ReceiptsLogPruneRule>);
+
+impl<'de> Deserialize<'de> for ReceiptsLogPruneConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Rules(Vec<ReceiptsLogPruneRule>),
+            Addresses(BTreeMap<Address, PruneMode>),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Rules(rules) => Self(rules),
+            Repr::Addresses(addresses) => Self(
+                addresses
+                    .into_iter()
+                    .map(|(address, mode)| ReceiptsLogPruneRule::address(address, mode))
+                    .collect(),
+            ),
+        })
+    }
+}
+
+/// Rule of the [`ReceiptsLogPruneConfig`]. Receipts containing a log that matches the rule are
+/// retained according to the prune mode of the rule.
+///
+/// A log matches the rule if it's emitted by the rule's address and has the rule's topics at the
+/// same positions. Fields that aren't set match any log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptsLogPruneRule {
+    /// Address of the contract emitting the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// First topic of the log, i.e. the event signature for non-anonymous events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic0: Option<B256>,
+    /// Second topic of the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic1: Option<B256>,
+    /// Third topic of the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic2: Option<B256>,
+    /// Fourth topic of the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic3: Option<B256>,
+    /// Prune mode of receipts with logs matching the rule.
+    pub mode: PruneMode,
+}
+
+impl ReceiptsLogPruneRule {
+    /// Creates a rule matching all logs emitted by the given address.
+    pub const fn address(address: Address, mode: PruneMode) -> Self {
+        Self {
+            address: Some(address),
+            topic0: None,
+            topic1: None,
+            topic2: None,
+            topic3: None,
+            mode,
+        }
+    }
+
+    /// Returns the topics of the rule, by position.
+    pub const fn topics(&self) -> [Option<B256>; 4] {
+        [self.topic0, self.topic1, self.topic2, self.topic3]
+    }
+
+    /// Returns `true` if the log matches the rule.
+    pub fn matches(&self, log: &Log) -> bool {
+        self.address.map_or(true, |address| address == log.address) &&
+            self.topics().iter().enumerate().all(|(i, topic)| {
+                topic.map_or(true, |topic| log.topics().get(i) == Some(&topic))
+            })
+    }
+}
+
+/// Error of an invalid [`ReceiptsLogPruneConfig`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ReceiptsLogPruneConfigError {
+    /// The rule matches logs of any contract, so it would retain all receipts with logs.
+    #[error("receipts log filter rule {0} has neither an address nor topics")]
+    EmptyRule(usize),
+    /// The prune mode of the rule leaves fewer blocks than required by
+    /// [`PruneSegment::ContractLogs`].
+    #[error(
+        "receipts log filter rule {0} has prune mode {1:?}, expected prune mode that leaves at least {MINIMUM_PRUNING_DISTANCE} blocks in the database"
+    )]
+    InvalidMode(usize, PruneMode),
+}
 
 impl ReceiptsLogPruneConfig {
     /// Checks if the configuration is empty
@@ -34,16 +132,34 @@ impl ReceiptsLogPruneConfig {
         self.0.is_empty()
     }
 
+    /// Validates the rules of the configuration.
+    pub fn validate(&self) -> Result<(), ReceiptsLogPruneConfigError> {
+        for (i, rule) in self.0.iter().enumerate() {
+            if rule.address.is_none() && rule.topics().iter().all(Option::is_none) {
+                return Err(ReceiptsLogPruneConfigError::EmptyRule(i))
+            }
+
+            let min_blocks = PruneSegment::ContractLogs.min_blocks(PrunePurpose::User);
+            if matches!(rule.mode, PruneMode::Full) ||
+                matches!(rule.mode, PruneMode::Distance(distance) if distance < min_blocks)
+            {
+                return Err(ReceiptsLogPruneConfigError::InvalidMode(i, rule.mode))
+            }
+        }
+
+        Ok(())
+    }
+
     /// Given the `tip` block number, consolidates the structure so it can easily be queried for
     /// filtering across a range of blocks.
     ///
     /// Example:
     ///
-    /// `{ addrA: Before(872), addrB: Before(500), addrC: Distance(128) }`
+    /// `[ruleA: Before(872), ruleB: Before(500), ruleC: Distance(128)]`
     ///
     ///    for `tip: 1000`, gets transformed to a map such as:
     ///
-    /// `{ 500: [addrB], 872: [addrA, addrC] }`
+    /// `{ 500: [ruleB], 872: [ruleA, ruleC] }`
     ///
     /// The [`BlockNumber`] key of the new map should be viewed as `PruneMode::Before(block)`, which
     /// makes the previous result equivalent to
@@ -53,11 +169,11 @@ impl ReceiptsLogPruneConfig {
         &self,
         tip: BlockNumber,
         pruned_block: Option<BlockNumber>,
-    ) -> Result<BTreeMap<BlockNumber, Vec<&Address>>, PruneSegmentError> {
+    ) -> Result<BTreeMap<BlockNumber, Vec<&ReceiptsLogPruneRule>>, PruneSegmentError> {
         let mut map = BTreeMap::new();
         let pruned_block = pruned_block.unwrap_or_default();
 
-        for (address, mode) in &self.0 {
+        for rule in &self.0 {
             // Getting `None`, means that there is nothing to prune yet, so we need it to include in
             // the BTreeMap (block = 0), otherwise it will be excluded.
             // Reminder that this BTreeMap works as an inclusion list that excludes (prunes) all
@@ -66,13 +182,14 @@ impl ReceiptsLogPruneConfig {
             // Reminder, that we increment because the [`BlockNumber`] key of the new map should be
             // viewed as `PruneMode::Before(block)`
             let block = (pruned_block + 1).max(
-                mode.prune_target_block(tip, PruneSegment::ContractLogs, PrunePurpose::User)?
+                rule.mode
+                    .prune_target_block(tip, PruneSegment::ContractLogs, PrunePurpose::User)?
                     .map(|(block, _)| block)
                     .unwrap_or_default() +
                     1,
             );
 
-            map.entry(block).or_insert_with(Vec::new).push(address)
+            map.entry(block).or_insert_with(Vec::new).push(rule)
         }
         Ok(map)
     }
@@ -86,7 +203,7 @@ impl ReceiptsLogPruneConfig {
         let pruned_block = pruned_block.unwrap_or_default();
         let mut lowest = None;
 
-        for mode in self.0.values() {
+        for ReceiptsLogPruneRule { mode, .. } in &self.0 {
             if let PruneMode::Distance(_) = mode {
                 if let Some((block, _)) =
                     mode.prune_target_block(tip, PruneSegment::ContractLogs, PrunePurpose::User)?
@@ -162,3 +279,103 @@ impl PruneProgress {
         matches!(self, Self::Finished)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256, LogData};
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn deserialize_receipts_log_prune_config() {
+        #[derive(Debug, Deserialize)]
+        struct Parts {
+            receipts_log_filter: ReceiptsLogPruneConfig,
+        }
+
+        let usdc = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        let transfer = b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+        let parts: Parts = toml::from_str(
+            r#"
+            [[receipts_log_filter]]
+            address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+            topic0 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+            mode = { distance = 100000 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            parts.receipts_log_filter,
+            ReceiptsLogPruneConfig(vec![ReceiptsLogPruneRule {
+                topic0: Some(transfer),
+                ..ReceiptsLogPruneRule::address(usdc, PruneMode::Distance(100000))
+            }])
+        );
+
+        // Legacy format
+        let parts: Parts = toml::from_str(
+            r#"
+            [receipts_log_filter]
+            "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48" = { before = 17000000 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            parts.receipts_log_filter,
+            ReceiptsLogPruneConfig(vec![ReceiptsLogPruneRule::address(
+                usdc,
+                PruneMode::Before(17000000)
+            )])
+        );
+    }
+
+    #[test]
+    fn receipts_log_prune_rule_matches() {
+        let usdc = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        let transfer = b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+        let log =
+            |address, topics| Log { address, data: LogData::new_unchecked(topics, vec![].into()) };
+
+        let rule = ReceiptsLogPruneRule {
+            topic0: Some(transfer),
+            ..ReceiptsLogPruneRule::address(usdc, PruneMode::Before(1))
+        };
+        assert!(rule.matches(&log(usdc, vec![transfer, B256::ZERO])));
+        assert!(!rule.matches(&log(usdc, vec![B256::ZERO])));
+        assert!(!rule.matches(&log(usdc, vec![])));
+        assert!(!rule.matches(&log(Address::ZERO, vec![transfer])));
+
+        let rule = ReceiptsLogPruneRule { address: None, topic1: Some(transfer), ..rule };
+        assert!(rule.matches(&log(Address::ZERO, vec![transfer, transfer])));
+        assert!(!rule.matches(&log(Address::ZERO, vec![transfer])));
+    }
+
+    #[test]
+    fn validate_receipts_log_prune_config() {
+        let rule = ReceiptsLogPruneRule::address(Address::ZERO, PruneMode::Before(1));
+        assert_eq!(ReceiptsLogPruneConfig(vec![rule.clone()]).validate(), Ok(()));
+
+        assert_matches!(
+            ReceiptsLogPruneConfig(vec![
+                rule.clone(),
+                ReceiptsLogPruneRule { address: None, ..rule }
+            ])
+            .validate(),
+            Err(ReceiptsLogPruneConfigError::EmptyRule(1))
+        );
+        assert_matches!(
+            ReceiptsLogPruneConfig(vec![ReceiptsLogPruneRule {
+                mode: PruneMode::Distance(1000),
+                ..rule
+            }])
+            .validate(),
+            Err(ReceiptsLogPruneConfigError::InvalidMode(0, PruneMode::Distance(1000)))
+        );
+        assert_matches!(
+            ReceiptsLogPruneConfig(vec![ReceiptsLogPruneRule { mode: PruneMode::Full, ..rule }])
+                .validate(),
+            Err(ReceiptsLogPruneConfigError::InvalidMode(0, PruneMode::Full))
+        );
+    }
+}
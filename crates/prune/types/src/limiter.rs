@@ -62,6 +62,19 @@ impl PruneLimiter {
         self
     }
 
+    /// Limits the number of entries (rows in the database) that can still be deleted to at most
+    /// `left`. Entries deleted before are still counted towards the existing limit.
+    pub fn cap_deleted_entries_limit_left(mut self, left: usize) -> Self {
+        if let Some(deleted_entries_limit) = self.deleted_entries_limit.as_mut() {
+            deleted_entries_limit.limit =
+                deleted_entries_limit.limit.min(deleted_entries_limit.deleted + left);
+        } else {
+            self.deleted_entries_limit = Some(PruneDeletedEntriesLimit::new(left));
+        }
+
+        self
+    }
+
     /// Sets the limit on the number of deleted entries (rows in the database) to a biggest
     /// multiple of the given denominator that is smaller than the existing limit.
     ///
@@ -0,0 +1,257 @@
+use crate::PruneSegment;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+/// Default for [`PruneSchedule::backlog_chunk_size`].
+pub const DEFAULT_BACKLOG_CHUNK_SIZE: usize = 10_000;
+
+/// Limits how much data the pruner deletes at once and when it's allowed to run, to spread the
+/// database load of pruning over time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruneSchedule {
+    /// Maximum number of entries (rows in the database) deleted per pruner run, per segment.
+    pub delete_limits: PruneDeleteLimits,
+    /// Maximum number of entries (rows in the database) deleted in one database transaction. The
+    /// pruner commits after every chunk and yields for [`Self::chunk_interval`] before continuing
+    /// with the next one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_size: Option<usize>,
+    /// Time the pruner yields for between two chunks.
+    #[serde(with = "humantime_serde", skip_serializing_if = "Option::is_none")]
+    pub chunk_interval: Option<Duration>,
+    /// UTC time-of-day windows the pruner is restricted to, e.g. `02:00-06:00`. If empty, the
+    /// pruner can run at any time.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<PruneWindow>,
+    /// Maximum number of entries (rows in the database) deleted per pruner run from the backlog of
+    /// a segment that lags behind its configured prune mode, e.g. after the prune mode was
+    /// tightened on an existing node. The backlog is pruned in addition to the regular limits of
+    /// the run. Set to `0` to only prune the backlog within the regular limits.
+    pub backlog_chunk_size: usize,
+}
+
+impl Default for PruneSchedule {
+    fn default() -> Self {
+        Self {
+            delete_limits: PruneDeleteLimits::default(),
+            chunk_size: None,
+            chunk_interval: None,
+            windows: Vec::new(),
+            backlog_chunk_size: DEFAULT_BACKLOG_CHUNK_SIZE,
+        }
+    }
+}
+
+impl PruneSchedule {
+    /// Returns `true` if pruning is allowed at the given time according to the configured
+    /// windows.
+    pub fn is_pruning_allowed(&self, now: SystemTime) -> bool {
+        if self.windows.is_empty() {
+            return true
+        }
+
+        let minute_of_day = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| (since_epoch.as_secs() % 86400 / 60) as u16);
+        self.windows.iter().any(|window| window.contains(minute_of_day))
+    }
+}
+
+/// Maximum number of entries (rows in the database) deleted per pruner run, per segment. Segments
+/// without a limit are only restricted by the overall limit of the run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruneDeleteLimits {
+    /// Delete limit of [`PruneSegment::SenderRecovery`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_recovery: Option<usize>,
+    /// Delete limit of [`PruneSegment::TransactionLookup`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_lookup: Option<usize>,
+    /// Delete limit of [`PruneSegment::Receipts`] and [`PruneSegment::ContractLogs`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipts: Option<usize>,
+    /// Delete limit of [`PruneSegment::AccountHistory`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_history: Option<usize>,
+    /// Delete limit of [`PruneSegment::StorageHistory`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_history: Option<usize>,
+}
+
+impl PruneDeleteLimits {
+    /// Returns the delete limit of the given segment, if any.
+    pub const fn get(&self, segment: PruneSegment) -> Option<usize> {
+        match segment {
+            PruneSegment::SenderRecovery => self.sender_recovery,
+            PruneSegment::TransactionLookup => self.transaction_lookup,
+            PruneSegment::Receipts | PruneSegment::ContractLogs => self.receipts,
+            PruneSegment::AccountHistory => self.account_history,
+            PruneSegment::StorageHistory => self.storage_history,
+            PruneSegment::Headers | PruneSegment::Transactions => None,
+        }
+    }
+}
+
+/// A UTC time-of-day window, formatted as `HH:MM-HH:MM`.
+///
+/// The window includes its start and excludes its end. If the end is before the start, the window
+/// wraps around midnight, and if both are equal, it covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneWindow {
+    /// Start of the window, in minutes since midnight.
+    start: u16,
+    /// End of the window, in minutes since midnight.
+    end: u16,
+}
+
+impl PruneWindow {
+    /// Creates a new window from the start and end times, in minutes since midnight.
+    ///
+    /// Returns `None` if any of the times is not within a day.
+    pub const fn new(start: u16, end: u16) -> Option<Self> {
+        if start >= 24 * 60 || end >= 24 * 60 {
+            return None
+        }
+        Some(Self { start, end })
+    }
+
+    /// Returns `true` if the given time, in minutes since midnight, is within the window.
+    pub const fn contains(&self, minute_of_day: u16) -> bool {
+        if self.start < self.end {
+            self.start <= minute_of_day && minute_of_day < self.end
+        } else if self.start > self.end {
+            self.start <= minute_of_day || minute_of_day < self.end
+        } else {
+            true
+        }
+    }
+}
+
+/// Error while parsing a [`PruneWindow`].
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid prune window `{0}`, expected `HH:MM-HH:MM`")]
+pub struct PruneWindowError(String);
+
+impl FromStr for PruneWindow {
+    type Err = PruneWindowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_time = |time: &str| {
+            let (hours, minutes) = time.trim().split_once(':')?;
+            let (hours, minutes) = (hours.parse::<u16>().ok()?, minutes.parse::<u16>().ok()?);
+            (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+        };
+
+        s.split_once('-')
+            .and_then(|(start, end)| Self::new(parse_time(start)?, parse_time(end)?))
+            .ok_or_else(|| PruneWindowError(s.to_string()))
+    }
+}
+
+impl fmt::Display for PruneWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 60,
+            self.start % 60,
+            self.end / 60,
+            self.end % 60
+        )
+    }
+}
+
+impl Serialize for PruneWindow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PruneWindow {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_prune_window() {
+        let window = "02:00-06:30".parse::<PruneWindow>().unwrap();
+        assert_eq!(window, PruneWindow::new(2 * 60, 6 * 60 + 30).unwrap());
+        assert_eq!(window.to_string(), "02:00-06:30");
+
+        assert!("2:00".parse::<PruneWindow>().is_err());
+        assert!("24:00-06:00".parse::<PruneWindow>().is_err());
+        assert!("02:60-06:00".parse::<PruneWindow>().is_err());
+    }
+
+    #[test]
+    fn prune_window_contains() {
+        let window = "02:00-06:00".parse::<PruneWindow>().unwrap();
+        assert!(!window.contains(60 + 59));
+        assert!(window.contains(2 * 60));
+        assert!(window.contains(5 * 60 + 59));
+        assert!(!window.contains(6 * 60));
+
+        // Wraps around midnight
+        let window = "22:00-02:00".parse::<PruneWindow>().unwrap();
+        assert!(window.contains(23 * 60));
+        assert!(window.contains(60));
+        assert!(!window.contains(12 * 60));
+
+        // Whole day
+        let window = "00:00-00:00".parse::<PruneWindow>().unwrap();
+        assert!(window.contains(12 * 60));
+    }
+
+    #[test]
+    fn schedule_pruning_allowed() {
+        let at = |hours: u64| UNIX_EPOCH + Duration::from_secs(hours * 3600);
+
+        let schedule = PruneSchedule::default();
+        assert!(schedule.is_pruning_allowed(at(12)));
+
+        let schedule =
+            PruneSchedule { windows: vec!["02:00-06:00".parse().unwrap()], ..Default::default() };
+        assert!(!schedule.is_pruning_allowed(at(1)));
+        assert!(schedule.is_pruning_allowed(at(24 + 3)));
+    }
+
+    #[test]
+    fn schedule_toml() {
+        let s = r#"
+            chunk_size = 10000
+            chunk_interval = "100ms"
+            windows = ["02:00-06:00"]
+            backlog_chunk_size = 1000
+
+            [delete_limits]
+            receipts = 50000
+        "#;
+        let schedule: PruneSchedule = toml::from_str(s).unwrap();
+        assert_eq!(
+            schedule,
+            PruneSchedule {
+                delete_limits: PruneDeleteLimits { receipts: Some(50000), ..Default::default() },
+                chunk_size: Some(10000),
+                chunk_interval: Some(Duration::from_millis(100)),
+                windows: vec![PruneWindow::new(2 * 60, 6 * 60).unwrap()],
+                backlog_chunk_size: 1000,
+            }
+        );
+        assert_eq!(schedule.delete_limits.get(PruneSegment::ContractLogs), Some(50000));
+
+        let serialized = toml::to_string(&schedule).unwrap();
+        assert_eq!(toml::from_str::<PruneSchedule>(&serialized).unwrap(), schedule);
+    }
+}
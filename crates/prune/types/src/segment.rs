@@ -27,6 +27,18 @@ pub enum PruneSegment {
 }
 
 impl PruneSegment {
+    /// All segments of the data that can be pruned.
+    pub const ALL: [Self; 8] = [
+        Self::SenderRecovery,
+        Self::TransactionLookup,
+        Self::Receipts,
+        Self::ContractLogs,
+        Self::AccountHistory,
+        Self::StorageHistory,
+        Self::Headers,
+        Self::Transactions,
+    ];
+
     /// Returns minimum number of blocks to left in the database for this segment.
     pub const fn min_blocks(&self, purpose: PrunePurpose) -> u64 {
         match self {
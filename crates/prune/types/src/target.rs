@@ -1,5 +1,7 @@
 use crate::{PruneMode, ReceiptsLogPruneConfig};
+use alloy_primitives::Address;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeSet;
 
 /// Minimum distance from the tip necessary for the node to work correctly:
 /// 1. Minimum 2 epochs (32 blocks per epoch) required to handle any reorg according to the
@@ -43,6 +45,12 @@ pub struct PruneModes {
     /// The [`BlockNumber`](`crate::BlockNumber`) represents the starting block from which point
     /// onwards the receipts are preserved.
     pub receipts_log_filter: ReceiptsLogPruneConfig,
+    /// Addresses exempt from history pruning. Their account and storage changesets and history
+    /// indices, and the receipts with logs emitted by them, are retained forever regardless of
+    /// the `account_history`, `storage_history`, `receipts` and `receipts_log_filter`
+    /// configuration.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub retained_addresses: BTreeSet<Address>,
 }
 
 impl PruneModes {
@@ -60,6 +68,7 @@ impl PruneModes {
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
             receipts_log_filter: Default::default(),
+            retained_addresses: Default::default(),
         }
     }
 }
@@ -123,4 +132,25 @@ mod tests {
             Err(err) if err.to_string() == "invalid value: string \"full\", expected prune mode that leaves at least 10 blocks in the database"
         );
     }
+
+    #[test]
+    fn deserialize_retained_addresses() {
+        let modes: PruneModes = toml::from_str(
+            r#"
+            account_history = { distance = 10064 }
+            retained_addresses = ["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            modes.retained_addresses,
+            BTreeSet::from([alloy_primitives::address!(
+                "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+            )])
+        );
+
+        let serialized = toml::to_string(&modes).unwrap();
+        assert_eq!(toml::from_str::<PruneModes>(&serialized).unwrap(), modes);
+        assert!(!toml::to_string(&PruneModes::none()).unwrap().contains("retained_addresses"));
+    }
 }
@@ -1,12 +1,16 @@
 //! Helper for handling execution of multiple blocks.
 
-use crate::{precompile::Address, primitives::alloy_primitives::BlockNumber};
+use crate::primitives::alloy_primitives::{Address, BlockNumber};
 use core::time::Duration;
 use reth_execution_errors::BlockExecutionError;
 use reth_primitives::{Receipt, Receipts, Request, Requests};
-use reth_prune_types::{PruneMode, PruneModes, PruneSegmentError, MINIMUM_PRUNING_DISTANCE};
-use revm::db::states::bundle_state::BundleRetention;
-use std::collections::HashSet;
+use reth_prune_types::{
+    PruneMode, PruneModes, PruneSegmentError, ReceiptsLogPruneRule, MINIMUM_PRUNING_DISTANCE,
+};
+use revm::db::states::{
+    bundle_state::BundleRetention,
+    reverts::{AccountInfoRevert, AccountRevert},
+};
 use tracing::debug;
 
 #[cfg(not(feature = "std"))]
@@ -33,11 +37,11 @@ pub struct BlockBatchRecord {
     /// A transaction may have zero or more requests, so the length of the inner vector is not
     /// guaranteed to be the same as the number of transactions.
     requests: Vec<Requests>,
-    /// Memoized address pruning filter.
+    /// Memoized log pruning filter, with the next block to collect the rules from.
     ///
-    /// Empty implies that there is going to be addresses to include in the filter in a future
+    /// Empty implies that there is going to be rules to include in the filter in a future
     /// block. None means there isn't any kind of configuration.
-    pruning_address_filter: Option<(u64, HashSet<Address>)>,
+    pruning_log_filter: Option<(u64, Vec<ReceiptsLogPruneRule>)>,
     /// First block will be initialized to `None`
     /// and be set to the block number of first block executed.
     first_block: Option<BlockNumber>,
@@ -97,21 +101,59 @@ impl BlockBatchRecord {
     }
 
     /// Returns the [`BundleRetention`] for the given block based on the configured prune modes.
+    ///
+    /// Reverts are retained if either the account or the storage changesets of the block, or the
+    /// changesets of the addresses exempt from history pruning, are written. The reverts that are
+    /// not written are dropped by [`Self::prune_reverts`].
     pub fn bundle_retention(&self, block_number: BlockNumber) -> BundleRetention {
-        if self.tip.map_or(true, |tip| {
-            !self
-                .prune_modes
-                .account_history
-                .map_or(false, |mode| mode.should_prune(block_number, tip)) &&
-                !self
-                    .prune_modes
-                    .storage_history
-                    .map_or(false, |mode| mode.should_prune(block_number, tip))
-        }) {
-            BundleRetention::Reverts
-        } else {
+        if self.prune_modes.retained_addresses.is_empty() &&
+            self.should_prune_history(self.prune_modes.account_history, block_number) &&
+            self.should_prune_history(self.prune_modes.storage_history, block_number)
+        {
             BundleRetention::PlainState
+        } else {
+            BundleRetention::Reverts
+        }
+    }
+
+    /// Prunes the reverts of the given block, i.e. its account and storage changesets, according
+    /// to the account and storage history prune modes. Reverts of the addresses exempt from
+    /// history pruning are kept.
+    pub fn prune_reverts(
+        &self,
+        block_number: BlockNumber,
+        reverts: &mut Vec<(Address, AccountRevert)>,
+    ) {
+        let prune_accounts =
+            self.should_prune_history(self.prune_modes.account_history, block_number);
+        let prune_storage =
+            self.should_prune_history(self.prune_modes.storage_history, block_number);
+        if !prune_accounts && !prune_storage {
+            return
         }
+
+        reverts.retain_mut(|(address, revert)| {
+            if self.prune_modes.retained_addresses.contains(address) {
+                return true
+            }
+
+            if prune_accounts {
+                revert.account = AccountInfoRevert::DoNothing;
+            }
+            if prune_storage {
+                revert.storage.clear();
+                revert.wipe_storage = false;
+            }
+            revert.account != AccountInfoRevert::DoNothing ||
+                revert.wipe_storage ||
+                !revert.storage.is_empty()
+        });
+    }
+
+    /// Returns `true` if the history of the given block should be pruned with the prune mode.
+    fn should_prune_history(&self, mode: Option<PruneMode>, block_number: BlockNumber) -> bool {
+        self.tip
+            .map_or(false, |tip| mode.map_or(false, |mode| mode.should_prune(block_number, tip)))
     }
 
     /// Save receipts to the executor.
@@ -138,7 +180,21 @@ impl BlockBatchRecord {
             // [`PruneSegment::Receipts`] takes priority over [`PruneSegment::ContractLogs`]
             self.prune_modes.receipts.map_or(false, |mode| mode.should_prune(block_number, tip))
         {
-            receipts.clear();
+            if self.prune_modes.retained_addresses.is_empty() {
+                receipts.clear();
+            } else {
+                // Only keep receipts with logs emitted by the addresses exempt from pruning.
+                for receipt in receipts.iter_mut() {
+                    let inner_receipt = receipt.as_ref().expect("receipts have not been pruned");
+                    if !inner_receipt
+                        .logs
+                        .iter()
+                        .any(|log| self.prune_modes.retained_addresses.contains(&log.address))
+                    {
+                        receipt.take();
+                    }
+                }
+            }
             return Ok(())
         }
 
@@ -153,19 +209,25 @@ impl BlockBatchRecord {
         let contract_log_pruner = self.prune_modes.receipts_log_filter.group_by_block(tip, None)?;
 
         if !contract_log_pruner.is_empty() {
-            let (prev_block, filter) =
-                self.pruning_address_filter.get_or_insert_with(|| (0, HashSet::new()));
-            for (_, addresses) in contract_log_pruner.range(*prev_block..=block_number) {
-                filter.extend(addresses.iter().copied());
+            let (next_block, filter) =
+                self.pruning_log_filter.get_or_insert_with(|| (0, Vec::new()));
+            if *next_block <= block_number {
+                for (_, rules) in contract_log_pruner.range(*next_block..=block_number) {
+                    filter.extend(rules.iter().copied().cloned());
+                }
+                *next_block = block_number + 1;
             }
         }
 
-        if let Some((_, filter)) = &self.pruning_address_filter {
+        if let Some((_, filter)) = &self.pruning_log_filter {
             for receipt in receipts.iter_mut() {
-                // If there is an address_filter, it does not contain any of the
-                // contract addresses, then remove this receipt.
+                // If there is a log filter and none of the logs match any of its rules, then
+                // remove this receipt.
                 let inner_receipt = receipt.as_ref().expect("receipts have not been pruned");
-                if !inner_receipt.logs.iter().any(|log| filter.contains(&log.address)) {
+                if !inner_receipt.logs.iter().any(|log| {
+                    filter.iter().any(|rule| rule.matches(log)) ||
+                        self.prune_modes.retained_addresses.contains(&log.address)
+                }) {
                     receipt.take();
                 }
             }
@@ -210,3 +272,75 @@ impl BlockExecutorStats {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::U256;
+    use revm::{
+        db::states::{reverts::RevertToSlot, AccountStatus},
+        primitives::AccountInfo,
+    };
+    use std::collections::HashMap;
+
+    fn revert() -> AccountRevert {
+        AccountRevert {
+            account: AccountInfoRevert::RevertTo(AccountInfo::default()),
+            storage: HashMap::from([(U256::from(1), RevertToSlot::Some(U256::from(2)))]),
+            previous_status: AccountStatus::Loaded,
+            wipe_storage: false,
+        }
+    }
+
+    #[test]
+    fn prune_reverts() {
+        let (address, retained_address) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let reverts = vec![(address, revert()), (retained_address, revert())];
+
+        let mut batch_record = BlockBatchRecord::new(PruneModes {
+            account_history: Some(PruneMode::Before(10)),
+            ..Default::default()
+        });
+        batch_record.set_tip(20);
+
+        // Only the account changesets of blocks before the prune mode are dropped
+        let mut block_reverts = reverts.clone();
+        batch_record.prune_reverts(9, &mut block_reverts);
+        assert_eq!(
+            block_reverts,
+            vec![
+                (address, AccountRevert { account: AccountInfoRevert::DoNothing, ..revert() }),
+                (
+                    retained_address,
+                    AccountRevert { account: AccountInfoRevert::DoNothing, ..revert() }
+                )
+            ]
+        );
+        assert!(matches!(batch_record.bundle_retention(9), BundleRetention::Reverts));
+
+        let mut block_reverts = reverts.clone();
+        batch_record.prune_reverts(10, &mut block_reverts);
+        assert_eq!(block_reverts, reverts);
+
+        // Reverts without any changesets left are dropped, unless the address is retained
+        batch_record.set_prune_modes(PruneModes {
+            account_history: Some(PruneMode::Before(10)),
+            storage_history: Some(PruneMode::Before(10)),
+            retained_addresses: [retained_address].into(),
+            ..Default::default()
+        });
+        let mut block_reverts = reverts;
+        batch_record.prune_reverts(9, &mut block_reverts);
+        assert_eq!(block_reverts, vec![(retained_address, revert())]);
+        assert!(matches!(batch_record.bundle_retention(9), BundleRetention::Reverts));
+
+        // Without retained addresses, no reverts are created in the first place
+        batch_record.set_prune_modes(PruneModes {
+            account_history: Some(PruneMode::Before(10)),
+            storage_history: Some(PruneMode::Before(10)),
+            ..Default::default()
+        });
+        assert!(matches!(batch_record.bundle_retention(9), BundleRetention::PlainState));
+        assert!(matches!(batch_record.bundle_retention(10), BundleRetention::Reverts));
+    }
+}
@@ -0,0 +1,346 @@
+//! Database adapters that cache reads of the state.
+
+use crate::{
+    db::BundleState,
+    primitives::{
+        db::{Database, DatabaseRef},
+        AccountInfo, Address, Bytecode, B256, U256,
+    },
+};
+use parking_lot::Mutex;
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    sync::Arc,
+};
+
+/// A container type that caches reads from an underlying [`DatabaseRef`].
+///
+/// This is intended to be used in conjunction with `revm::db::State`
+/// during payload building which repeatedly accesses the same data.
+///
+/// # Example
+///
+/// ```
+/// use reth_revm::{
+///     cached::CachedReads,
+///     db::{DatabaseRef, State},
+/// };
+///
+/// fn build_payload<DB: DatabaseRef>(db: DB) {
+///     let mut cached_reads = CachedReads::default();
+///     let db_ref = cached_reads.as_db(db);
+///     // this is `Database` and can be used to build a payload, it never writes to `CachedReads` or the underlying database, but all reads from the underlying database are cached in `CachedReads`.
+///     // Subsequent payload build attempts can use cached reads and avoid hitting the underlying database.
+///     let db = State::builder().with_database_ref(db_ref).build();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CachedReads {
+    accounts: HashMap<Address, CachedAccount>,
+    contracts: HashMap<B256, Bytecode>,
+    block_hashes: HashMap<U256, B256>,
+}
+
+// === impl CachedReads ===
+
+impl CachedReads {
+    /// Gets a [`DatabaseRef`] that will cache reads from the given database.
+    pub fn as_db<DB>(&mut self, db: DB) -> CachedReadsDBRef<'_, DB> {
+        CachedReadsDBRef { inner: RefCell::new(self.as_db_mut(db)) }
+    }
+
+    /// Gets a [`Database`] that will cache reads from the given database.
+    pub fn as_db_mut<DB>(&mut self, db: DB) -> CachedReadsDbMut<'_, DB> {
+        CachedReadsDbMut { cached: self, db }
+    }
+
+    /// Inserts an account info into the cache.
+    pub fn insert_account(
+        &mut self,
+        address: Address,
+        info: AccountInfo,
+        storage: HashMap<U256, U256>,
+    ) {
+        self.accounts.insert(address, CachedAccount { info: Some(info), storage });
+    }
+
+    /// Extends the cache with the reads of another cache of the same state.
+    ///
+    /// Entries of `other` take precedence, storage slots of accounts present in both caches are
+    /// merged.
+    pub fn extend(&mut self, other: Self) {
+        for (address, account) in other.accounts {
+            match self.accounts.entry(address) {
+                Entry::Occupied(mut entry) => {
+                    let cached = entry.get_mut();
+                    cached.info = account.info;
+                    cached.storage.extend(account.storage);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(account);
+                }
+            }
+        }
+        self.contracts.extend(other.contracts);
+        self.block_hashes.extend(other.block_hashes);
+    }
+
+    /// Applies the changes of a block executed on top of the cached state, turning the reads into
+    /// reads of the state after the block.
+    ///
+    /// The storage of destroyed accounts is dropped, since slots that are not cached are read
+    /// from the database of the state after the block.
+    pub fn apply_bundle(&mut self, bundle: &BundleState) {
+        for (address, account) in &bundle.state {
+            let cached = self.accounts.entry(*address).or_insert_with(|| CachedAccount::new(None));
+            cached.info = account.info.clone();
+            if account.was_destroyed() {
+                cached.storage.clear();
+            }
+            cached
+                .storage
+                .extend(account.storage.iter().map(|(slot, value)| (*slot, value.present_value)));
+        }
+        self.contracts.extend(bundle.contracts.iter().map(|(hash, code)| (*hash, code.clone())));
+    }
+}
+
+/// A [Database] that caches reads inside [`CachedReads`].
+#[derive(Debug)]
+pub struct CachedReadsDbMut<'a, DB> {
+    /// The cache of reads.
+    pub cached: &'a mut CachedReads,
+    /// The underlying database.
+    pub db: DB,
+}
+
+impl<'a, DB: DatabaseRef> Database for CachedReadsDbMut<'a, DB> {
+    type Error = <DB as DatabaseRef>::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let basic = match self.cached.accounts.entry(address) {
+            Entry::Occupied(entry) => entry.get().info.clone(),
+            Entry::Vacant(entry) => {
+                entry.insert(CachedAccount::new(self.db.basic_ref(address)?)).info.clone()
+            }
+        };
+        Ok(basic)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = match self.cached.contracts.entry(code_hash) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => entry.insert(self.db.code_by_hash_ref(code_hash)?).clone(),
+        };
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        match self.cached.accounts.entry(address) {
+            Entry::Occupied(mut acc_entry) => match acc_entry.get_mut().storage.entry(index) {
+                Entry::Occupied(entry) => Ok(*entry.get()),
+                Entry::Vacant(entry) => Ok(*entry.insert(self.db.storage_ref(address, index)?)),
+            },
+            Entry::Vacant(acc_entry) => {
+                // acc needs to be loaded for us to access slots.
+                let info = self.db.basic_ref(address)?;
+                let (account, value) = if info.is_some() {
+                    let value = self.db.storage_ref(address, index)?;
+                    let mut account = CachedAccount::new(info);
+                    account.storage.insert(index, value);
+                    (account, value)
+                } else {
+                    (CachedAccount::new(info), U256::ZERO)
+                };
+                acc_entry.insert(account);
+                Ok(value)
+            }
+        }
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        let code = match self.cached.block_hashes.entry(number) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => *entry.insert(self.db.block_hash_ref(number)?),
+        };
+        Ok(code)
+    }
+}
+
+/// A [`DatabaseRef`] that caches reads inside [`CachedReads`].
+///
+/// This is intended to be used as the [`DatabaseRef`] for
+/// `revm::db::State` for repeated payload build jobs.
+#[derive(Debug)]
+pub struct CachedReadsDBRef<'a, DB> {
+    /// The inner cache reads db mut.
+    pub inner: RefCell<CachedReadsDbMut<'a, DB>>,
+}
+
+impl<'a, DB: DatabaseRef> DatabaseRef for CachedReadsDBRef<'a, DB> {
+    type Error = <DB as DatabaseRef>::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.inner.borrow_mut().basic(address)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.inner.borrow_mut().code_by_hash(code_hash)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.inner.borrow_mut().storage(address, index)
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        self.inner.borrow_mut().block_hash(number)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedAccount {
+    info: Option<AccountInfo>,
+    storage: HashMap<U256, U256>,
+}
+
+impl CachedAccount {
+    fn new(info: Option<AccountInfo>) -> Self {
+        Self { info, storage: HashMap::new() }
+    }
+}
+
+/// Default number of blocks kept by [`SharedCachedReads`].
+pub const DEFAULT_SHARED_CACHED_BLOCKS: usize = 2;
+
+/// [`CachedReads`] of the state of recent blocks, shared between tasks.
+///
+/// The reads are keyed by the hash of the block whose state they were read from. Reads cached
+/// while building a payload on top of a block are reused when another block with the same parent
+/// is executed, e.g. the built payload when it comes back via `engine_newPayload`, or a competing
+/// payload. The reads of a validated block, updated with its changes, are cached as the state of
+/// that block for the validation of its child, see [`CachedReads::apply_bundle`].
+///
+/// Only the reads of the most recently updated blocks are kept.
+#[derive(Debug, Clone)]
+pub struct SharedCachedReads {
+    inner: Arc<Mutex<VecDeque<(B256, CachedReads)>>>,
+    max_blocks: usize,
+}
+
+impl SharedCachedReads {
+    /// Creates a new cache that keeps the reads of at most `max_blocks` blocks.
+    pub fn new(max_blocks: usize) -> Self {
+        Self { inner: Default::default(), max_blocks: max_blocks.max(1) }
+    }
+
+    /// Returns a copy of the cached reads of the state of the given block, if any.
+    pub fn get(&self, block_hash: B256) -> Option<CachedReads> {
+        let blocks = self.inner.lock();
+        blocks.iter().find(|(hash, _)| *hash == block_hash).map(|(_, cached)| cached.clone())
+    }
+
+    /// Adds reads of the state of the given block to the cache.
+    ///
+    /// If the block is not cached yet, the reads of the least recently updated block are evicted
+    /// once the cache is full.
+    pub fn insert(&self, block_hash: B256, cached: CachedReads) {
+        let mut blocks = self.inner.lock();
+        if let Some(index) = blocks.iter().position(|(hash, _)| *hash == block_hash) {
+            let (_, mut existing) = blocks.remove(index).expect("index exists");
+            existing.extend(cached);
+            blocks.push_back((block_hash, existing));
+        } else {
+            if blocks.len() >= self.max_blocks {
+                blocks.pop_front();
+            }
+            blocks.push_back((block_hash, cached));
+        }
+    }
+}
+
+impl Default for SharedCachedReads {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHARED_CACHED_BLOCKS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{states::StorageSlot, AccountStatus, BundleAccount};
+
+    fn cached_account(address: Address, slot: u64) -> CachedReads {
+        let mut cached = CachedReads::default();
+        cached.insert_account(
+            address,
+            AccountInfo::default(),
+            HashMap::from([(U256::from(slot), U256::from(slot))]),
+        );
+        cached
+    }
+
+    #[test]
+    fn shared_cached_reads_merge_and_evict() {
+        let shared = SharedCachedReads::new(2);
+        let (first, second, third) =
+            (B256::with_last_byte(1), B256::with_last_byte(2), B256::with_last_byte(3));
+        let address = Address::with_last_byte(1);
+
+        shared.insert(first, cached_account(address, 1));
+        shared.insert(second, cached_account(address, 1));
+        // merging reads of the first block makes it the most recently updated one
+        shared.insert(first, cached_account(address, 2));
+        let merged = shared.get(first).unwrap();
+        assert_eq!(merged.accounts[&address].storage.len(), 2);
+
+        shared.insert(third, CachedReads::default());
+        assert!(shared.get(first).is_some());
+        assert!(shared.get(second).is_none());
+        assert!(shared.get(third).is_some());
+    }
+
+    #[test]
+    fn apply_bundle() {
+        let (changed, destroyed, untouched) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let mut cached = cached_account(changed, 1);
+        cached.extend(cached_account(destroyed, 1));
+        cached.extend(cached_account(untouched, 1));
+
+        let changed_info = AccountInfo { nonce: 1, ..Default::default() };
+        let mut bundle = BundleState::default();
+        bundle.state.insert(
+            changed,
+            BundleAccount::new(
+                Some(AccountInfo::default()),
+                Some(changed_info.clone()),
+                [(U256::from(2), StorageSlot::new_changed(U256::ZERO, U256::from(3)))]
+                    .into_iter()
+                    .collect(),
+                AccountStatus::Changed,
+            ),
+        );
+        bundle.state.insert(
+            destroyed,
+            BundleAccount::new(
+                Some(AccountInfo::default()),
+                None,
+                Default::default(),
+                AccountStatus::Destroyed,
+            ),
+        );
+        cached.apply_bundle(&bundle);
+
+        let changed = &cached.accounts[&changed];
+        assert_eq!(changed.info, Some(changed_info));
+        assert_eq!(
+            changed.storage,
+            HashMap::from([(U256::from(1), U256::from(1)), (U256::from(2), U256::from(3))])
+        );
+        let destroyed = &cached.accounts[&destroyed];
+        assert_eq!(destroyed.info, None);
+        assert!(destroyed.storage.is_empty());
+        assert_eq!(cached.accounts[&untouched].storage.len(), 1);
+    }
+}
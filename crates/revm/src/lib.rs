@@ -17,9 +17,16 @@ pub mod database;
 
 pub mod batch;
 
+/// Caching of state reads, e.g. across payload building attempts.
+#[cfg(feature = "std")]
+pub mod cached;
+
 /// State changes that are not related to transactions.
 pub mod state_change;
 
+/// Pre- and post-block system calls.
+pub mod system_calls;
+
 /// Common test helpers
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
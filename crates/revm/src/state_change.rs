@@ -1,24 +1,22 @@
+use crate::system_calls::SystemCallEvm;
 use alloy_eips::{
     eip2935::{HISTORY_STORAGE_ADDRESS, HISTORY_STORAGE_CODE},
-    eip7002::WithdrawalRequest,
+    eip4788::BEACON_ROOTS_ADDRESS,
+    eip7002::{WithdrawalRequest, WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS},
+    eip7251::ConsolidationRequest,
 };
 use alloy_rlp::Buf;
 use reth_chainspec::{ChainSpec, EthereumHardforks};
 use reth_consensus_common::calc;
 use reth_execution_errors::{BlockExecutionError, BlockValidationError};
 use reth_primitives::{
-    revm::env::{
-        fill_tx_env_with_beacon_root_contract_call,
-        fill_tx_env_with_withdrawal_requests_contract_call,
-    },
-    Address, Block, Request, Withdrawal, Withdrawals, B256, U256,
+    address, Address, Block, Bytes, Request, Withdrawal, Withdrawals, B256, U256,
 };
 use reth_storage_errors::provider::ProviderError;
 use revm::{
-    interpreter::Host,
     primitives::{
         Account, AccountInfo, Bytecode, EvmStorageSlot, ExecutionResult, FixedBytes,
-        ResultAndState, BLOCKHASH_SERVE_WINDOW,
+        BLOCKHASH_SERVE_WINDOW,
     },
     Database, DatabaseCommit, Evm,
 };
@@ -27,11 +25,25 @@ use revm::{
 #[cfg(not(feature = "std"))]
 use crate::precompile::HashMap;
 #[cfg(not(feature = "std"))]
-use alloc::{boxed::Box, format, string::ToString, vec::Vec};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 #[cfg(feature = "std")]
 use std::collections::HashMap;
 
+/// The address of the [EIP-7251] consolidation requests predeploy contract.
+///
+/// Defined here because the `eip7251` module of the pinned `alloy-eips` version only provides the
+/// [`ConsolidationRequest`] type. Should be imported from there once it's exported.
+///
+/// [EIP-7251]: https://eips.ethereum.org/EIPS/eip-7251
+pub const CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS: Address =
+    address!("00b42dbf2194e931e80326d950320f7d9dbeac02");
+
 /// Collect all balance changes at the end of the block.
 ///
 /// Balance changes might include the block reward, uncle rewards, withdrawals, or irregular
@@ -176,31 +188,15 @@ where
         return Ok(())
     }
 
-    // get previous env
-    let previous_env = Box::new(evm.context.env().clone());
-
-    // modify env for pre block call
-    fill_tx_env_with_beacon_root_contract_call(&mut evm.context.evm.env, parent_beacon_block_root);
-
-    let mut state = match evm.transact() {
-        Ok(res) => res.state,
-        Err(e) => {
-            evm.context.evm.env = previous_env;
-            return Err(BlockValidationError::BeaconRootContractCall {
-                parent_beacon_block_root: Box::new(parent_beacon_block_root),
-                message: e.to_string(),
-            }
-            .into())
-        }
-    };
-
-    state.remove(&alloy_eips::eip4788::SYSTEM_ADDRESS);
-    state.remove(&evm.block().coinbase);
-
-    evm.context.evm.db.commit(state);
-
-    // re-set the previous env
-    evm.context.evm.env = previous_env;
+    evm.transact_system_call(
+        alloy_eips::eip4788::SYSTEM_ADDRESS,
+        BEACON_ROOTS_ADDRESS,
+        parent_beacon_block_root.0.into(),
+    )
+    .map_err(|message| BlockValidationError::BeaconRootContractCall {
+        parent_beacon_block_root: Box::new(parent_beacon_block_root),
+        message,
+    })?;
 
     Ok(())
 }
@@ -249,10 +245,12 @@ pub fn insert_post_block_withdrawals_balance_increments(
     }
 }
 
-/// Applies the post-block call to the EIP-7002 withdrawal requests contract.
+/// Applies the post-block call to the [EIP-7002] withdrawal requests contract.
 ///
 /// If Prague is not active at the given timestamp, then this is a no-op, and an empty vector is
 /// returned. Otherwise, the withdrawal requests are returned.
+///
+/// [EIP-7002]: https://eips.ethereum.org/EIPS/eip-7002
 #[inline]
 pub fn apply_withdrawal_requests_contract_call<EXT, DB: Database + DatabaseCommit>(
     evm: &mut Evm<'_, EXT, DB>,
@@ -260,44 +258,12 @@ pub fn apply_withdrawal_requests_contract_call<EXT, DB: Database + DatabaseCommi
 where
     DB::Error: core::fmt::Display,
 {
-    // get previous env
-    let previous_env = Box::new(evm.context.env().clone());
-
-    // modify env for pre block call
-    fill_tx_env_with_withdrawal_requests_contract_call(&mut evm.context.evm.env);
-
-    let ResultAndState { result, mut state } = match evm.transact() {
-        Ok(res) => res,
-        Err(e) => {
-            evm.context.evm.env = previous_env;
-            return Err(BlockValidationError::WithdrawalRequestsContractCall {
-                message: format!("execution failed: {e}"),
-            }
-            .into())
-        }
-    };
-
-    // cleanup the state
-    state.remove(&alloy_eips::eip7002::SYSTEM_ADDRESS);
-    state.remove(&evm.block().coinbase);
-    evm.context.evm.db.commit(state);
-
-    // re-set the previous env
-    evm.context.evm.env = previous_env;
-
-    let mut data = match result {
-        ExecutionResult::Success { output, .. } => Ok(output.into_data()),
-        ExecutionResult::Revert { output, .. } => {
-            Err(BlockValidationError::WithdrawalRequestsContractCall {
-                message: format!("execution reverted: {output}"),
-            })
-        }
-        ExecutionResult::Halt { reason, .. } => {
-            Err(BlockValidationError::WithdrawalRequestsContractCall {
-                message: format!("execution halted: {reason:?}"),
-            })
-        }
-    }?;
+    let mut data = system_call_output(
+        evm,
+        alloy_eips::eip7002::SYSTEM_ADDRESS,
+        WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+    )
+    .map_err(|message| BlockValidationError::WithdrawalRequestsContractCall { message })?;
 
     // Withdrawals are encoded as a series of withdrawal requests, each with the following
     // format:
@@ -334,3 +300,134 @@ where
 
     Ok(withdrawal_requests)
 }
+
+/// Applies the post-block call to the [EIP-7251] consolidation requests contract.
+///
+/// Returns the consolidation requests of the block.
+///
+/// [EIP-7251]: https://eips.ethereum.org/EIPS/eip-7251
+#[inline]
+pub fn apply_consolidation_requests_contract_call<EXT, DB: Database + DatabaseCommit>(
+    evm: &mut Evm<'_, EXT, DB>,
+) -> Result<Vec<Request>, BlockExecutionError>
+where
+    DB::Error: core::fmt::Display,
+{
+    let mut data = system_call_output(
+        evm,
+        alloy_eips::eip7002::SYSTEM_ADDRESS,
+        CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+    )
+    .map_err(|message| BlockValidationError::ConsolidationRequestsContractCall { message })?;
+
+    // Consolidations are encoded as a series of consolidation requests, each with the following
+    // format:
+    //
+    // +------+--------+---------------+
+    // | addr | pubkey | target pubkey |
+    // +------+--------+---------------+
+    //    20      48           48
+
+    const CONSOLIDATION_REQUEST_SIZE: usize = 20 + 48 + 48;
+    let mut consolidation_requests = Vec::with_capacity(data.len() / CONSOLIDATION_REQUEST_SIZE);
+    while data.has_remaining() {
+        if data.remaining() < CONSOLIDATION_REQUEST_SIZE {
+            return Err(BlockValidationError::ConsolidationRequestsContractCall {
+                message: "invalid consolidation request length".to_string(),
+            }
+            .into())
+        }
+
+        let mut source_address = Address::ZERO;
+        data.copy_to_slice(source_address.as_mut_slice());
+
+        let mut source_pubkey = FixedBytes::<48>::ZERO;
+        data.copy_to_slice(source_pubkey.as_mut_slice());
+
+        let mut target_pubkey = FixedBytes::<48>::ZERO;
+        data.copy_to_slice(target_pubkey.as_mut_slice());
+
+        consolidation_requests.push(Request::ConsolidationRequest(ConsolidationRequest {
+            source_address,
+            source_pubkey,
+            target_pubkey,
+        }));
+    }
+
+    Ok(consolidation_requests)
+}
+
+/// Executes the system call of the contract without input, and returns its output if the call
+/// succeeded.
+fn system_call_output<EXT, DB: Database + DatabaseCommit>(
+    evm: &mut Evm<'_, EXT, DB>,
+    caller: Address,
+    contract: Address,
+) -> Result<Bytes, String>
+where
+    DB::Error: core::fmt::Display,
+{
+    match evm
+        .transact_system_call(caller, contract, Bytes::new())
+        .map_err(|err| format!("execution failed: {err}"))?
+    {
+        ExecutionResult::Success { output, .. } => Ok(output.into_data()),
+        ExecutionResult::Revert { output, .. } => Err(format!("execution reverted: {output}")),
+        ExecutionResult::Halt { reason, .. } => Err(format!("execution halted: {reason:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::keccak256;
+    use revm::db::{CacheDB, EmptyDB};
+
+    /// Returns an EVM with a consolidation requests contract that returns the given output.
+    fn evm_with_consolidation_requests_output(output: &[u8]) -> Evm<'static, (), CacheDB<EmptyDB>> {
+        // CODECOPY the output appended to the code into memory, and RETURN it
+        let len = output.len() as u8;
+        // PUSH1 len, PUSH1 12, PUSH1 0, CODECOPY, PUSH1 len, PUSH1 0, RETURN
+        let mut code = vec![0x60, len, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xf3];
+        code.extend_from_slice(output);
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+            AccountInfo {
+                code_hash: keccak256(&code),
+                code: Some(Bytecode::new_raw(code.into())),
+                ..Default::default()
+            },
+        );
+        Evm::builder().with_db(db).build()
+    }
+
+    #[test]
+    fn parse_consolidation_requests() {
+        let request = ConsolidationRequest {
+            source_address: Address::with_last_byte(1),
+            source_pubkey: FixedBytes::repeat_byte(2),
+            target_pubkey: FixedBytes::repeat_byte(3),
+        };
+        let mut output = Vec::new();
+        output.extend_from_slice(request.source_address.as_slice());
+        output.extend_from_slice(request.source_pubkey.as_slice());
+        output.extend_from_slice(request.target_pubkey.as_slice());
+
+        let mut evm = evm_with_consolidation_requests_output(&output);
+        assert_eq!(
+            apply_consolidation_requests_contract_call(&mut evm).unwrap(),
+            vec![Request::ConsolidationRequest(request)]
+        );
+
+        // Truncated requests are rejected
+        let mut evm = evm_with_consolidation_requests_output(&output[..output.len() - 1]);
+        assert!(matches!(
+            apply_consolidation_requests_contract_call(&mut evm),
+            Err(BlockExecutionError::Validation(
+                BlockValidationError::ConsolidationRequestsContractCall { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,239 @@
+use crate::state_change::{
+    apply_beacon_root_contract_call, apply_blockhashes_update,
+    apply_consolidation_requests_contract_call, apply_withdrawal_requests_contract_call,
+};
+use core::fmt::{Debug, Display};
+use reth_chainspec::{ChainSpec, EthereumHardforks};
+use reth_execution_errors::BlockExecutionError;
+use reth_primitives::{
+    revm::env::fill_tx_env_with_system_contract_call, Address, Bytes, Header, Request, B256,
+};
+use reth_storage_errors::provider::ProviderError;
+use revm::{
+    interpreter::Host,
+    primitives::{BlockEnv, ExecutionResult, ResultAndState},
+    Database, DatabaseCommit, Evm,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+/// The block that system calls are applied for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemCallBlock {
+    /// Number of the block.
+    pub number: u64,
+    /// Timestamp of the block.
+    pub timestamp: u64,
+    /// Hash of the parent block.
+    pub parent_hash: B256,
+    /// Root of the parent beacon block, if any.
+    pub parent_beacon_block_root: Option<B256>,
+}
+
+impl From<&Header> for SystemCallBlock {
+    fn from(header: &Header) -> Self {
+        Self {
+            number: header.number,
+            timestamp: header.timestamp,
+            parent_hash: header.parent_hash,
+            parent_beacon_block_root: header.parent_beacon_block_root,
+        }
+    }
+}
+
+/// An EVM that can execute system calls.
+///
+/// This is object safe, so that [`SystemCallHook`]s don't depend on the type of the EVM.
+pub trait SystemCallEvm {
+    /// Returns the block environment of the EVM.
+    fn block_env(&self) -> &BlockEnv;
+
+    /// Executes a call of the contract with the given data as the caller, and commits its state
+    /// changes.
+    ///
+    /// The call is a system operation, see
+    /// [`fill_tx_env_with_system_contract_call`]. The state changes of the caller and the
+    /// beneficiary of the block are discarded, and the environment of the EVM is restored
+    /// afterwards.
+    ///
+    /// Returns the error message if the call can't be executed.
+    fn transact_system_call(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> Result<ExecutionResult, String>;
+}
+
+impl<EXT, DB> SystemCallEvm for Evm<'_, EXT, DB>
+where
+    DB: Database + DatabaseCommit,
+    DB::Error: Display,
+{
+    fn block_env(&self) -> &BlockEnv {
+        self.block()
+    }
+
+    fn transact_system_call(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> Result<ExecutionResult, String> {
+        // get previous env
+        let previous_env = Box::new(self.context.env().clone());
+
+        // modify env for the system call
+        fill_tx_env_with_system_contract_call(&mut self.context.evm.env, caller, contract, data);
+
+        let res = match self.transact() {
+            Ok(ResultAndState { result, mut state }) => {
+                // cleanup the state
+                state.remove(&caller);
+                state.remove(&self.block().coinbase);
+                self.context.evm.db.commit(state);
+                Ok(result)
+            }
+            Err(err) => Err(err.to_string()),
+        };
+
+        // re-set the previous env
+        self.context.evm.env = previous_env;
+
+        res
+    }
+}
+
+/// Hook of a chain applying its own system calls, in addition to the system calls of the
+/// ethereum hardforks applied by the [`SystemCaller`].
+///
+/// The hooks are called after the system calls of the ethereum hardforks.
+pub trait SystemCallHook: Debug + Send + Sync {
+    /// Applies the system calls before the transactions of the block are executed.
+    fn apply_pre_block(
+        &self,
+        block: &SystemCallBlock,
+        evm: &mut dyn SystemCallEvm,
+    ) -> Result<(), BlockExecutionError> {
+        let _ = block;
+        let _ = evm;
+        Ok(())
+    }
+
+    /// Applies the system calls after the transactions of the block are executed, and returns the
+    /// [EIP-7685] requests of the calls.
+    ///
+    /// [EIP-7685]: https://eips.ethereum.org/EIPS/eip-7685
+    fn apply_post_block(
+        &self,
+        block: &SystemCallBlock,
+        evm: &mut dyn SystemCallEvm,
+    ) -> Result<Vec<Request>, BlockExecutionError> {
+        let _ = block;
+        let _ = evm;
+        Ok(Vec::new())
+    }
+}
+
+/// Applies the pre- and post-block system calls of a chain.
+///
+/// Depending on the active hardforks, this applies:
+///  * before the transactions: the [EIP-4788] beacon root contract call and the [EIP-2935]
+///    blockhashes update.
+///  * after the transactions: the [EIP-7002] withdrawal requests and the [EIP-7251] consolidation
+///    requests contract calls.
+///
+/// Chains can add their own system calls with [`SystemCallHook`]s.
+///
+/// [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+/// [EIP-2935]: https://eips.ethereum.org/EIPS/eip-2935
+/// [EIP-7002]: https://eips.ethereum.org/EIPS/eip-7002
+/// [EIP-7251]: https://eips.ethereum.org/EIPS/eip-7251
+#[derive(Debug, Clone)]
+pub struct SystemCaller {
+    /// The chain spec of the chain.
+    chain_spec: Arc<ChainSpec>,
+    /// Hooks applying the system calls specific to the chain.
+    hooks: Vec<Arc<dyn SystemCallHook>>,
+}
+
+impl SystemCaller {
+    /// Creates a new system caller for the chain.
+    pub const fn new(chain_spec: Arc<ChainSpec>) -> Self {
+        Self { chain_spec, hooks: Vec::new() }
+    }
+
+    /// Adds a hook applying additional system calls.
+    pub fn with_hook(mut self, hook: Arc<dyn SystemCallHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Returns the chain spec of the chain.
+    pub fn chain_spec(&self) -> &ChainSpec {
+        &self.chain_spec
+    }
+
+    /// Applies the system calls before the transactions of the block are executed.
+    pub fn apply_pre_block<EXT, DB>(
+        &self,
+        block: &SystemCallBlock,
+        evm: &mut Evm<'_, EXT, DB>,
+    ) -> Result<(), BlockExecutionError>
+    where
+        DB: Database<Error: Into<ProviderError> + Display> + DatabaseCommit,
+    {
+        apply_beacon_root_contract_call(
+            &self.chain_spec,
+            block.timestamp,
+            block.number,
+            block.parent_beacon_block_root,
+            evm,
+        )?;
+        apply_blockhashes_update(
+            evm.db_mut(),
+            &self.chain_spec,
+            block.timestamp,
+            block.number,
+            block.parent_hash,
+        )?;
+
+        for hook in &self.hooks {
+            hook.apply_pre_block(block, evm)?;
+        }
+        Ok(())
+    }
+
+    /// Applies the system calls after the transactions of the block are executed, and returns the
+    /// [EIP-7685] requests of the calls.
+    ///
+    /// [EIP-7685]: https://eips.ethereum.org/EIPS/eip-7685
+    pub fn apply_post_block<EXT, DB>(
+        &self,
+        block: &SystemCallBlock,
+        evm: &mut Evm<'_, EXT, DB>,
+    ) -> Result<Vec<Request>, BlockExecutionError>
+    where
+        DB: Database + DatabaseCommit,
+        DB::Error: Display,
+    {
+        let mut requests = Vec::new();
+        if self.chain_spec.is_prague_active_at_timestamp(block.timestamp) {
+            requests.extend(apply_withdrawal_requests_contract_call(evm)?);
+            requests.extend(apply_consolidation_requests_contract_call(evm)?);
+        }
+
+        for hook in &self.hooks {
+            requests.extend(hook.apply_post_block(block, evm)?);
+        }
+        Ok(requests)
+    }
+}
@@ -0,0 +1,114 @@
+//! Names of IPC endpoints.
+
+use interprocess::local_socket::{GenericFilePath, Name, ToFsName};
+use std::io;
+
+/// The prefix of endpoints in the Linux abstract socket namespace, e.g. `@reth.ipc`.
+///
+/// Abstract sockets have no file, so they don't need to be cleaned up and have no file
+/// permissions.
+pub(crate) const ABSTRACT_SOCKET_PREFIX: char = '@';
+
+/// Returns `true` if the endpoint is in the Linux abstract socket namespace.
+pub(crate) fn is_abstract(endpoint: &str) -> bool {
+    endpoint.starts_with(ABSTRACT_SOCKET_PREFIX)
+}
+
+/// Returns the socket name of the endpoint, a file path or, if prefixed with
+/// [`ABSTRACT_SOCKET_PREFIX`], a name in the abstract namespace.
+pub(crate) fn socket_name(endpoint: &str) -> io::Result<Name<'_>> {
+    if let Some(name) = endpoint.strip_prefix(ABSTRACT_SOCKET_PREFIX) {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            use interprocess::{
+                local_socket::ToNsName, os::unix::local_socket::AbstractNsUdSocket,
+            };
+            return name.to_ns_name::<AbstractNsUdSocket>()
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            let _ = name;
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "abstract sockets are only supported on Linux",
+            ))
+        }
+    }
+    endpoint.to_fs_name::<GenericFilePath>()
+}
+
+/// Removes the socket file of the endpoint if it is stale, i.e. if no server is listening on it,
+/// which happens if a previous server was not shut down gracefully.
+///
+/// Fails if another server is listening on the socket, or if the file is not a socket.
+#[cfg(unix)]
+pub(crate) async fn remove_stale_socket(endpoint: &str) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(endpoint) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, "file exists and is not a socket"))
+    }
+
+    match tokio::net::UnixStream::connect(endpoint).await {
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "another server is listening on the socket",
+        )),
+        Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => {
+            std::fs::remove_file(endpoint)?;
+            tracing::debug!(endpoint, "removed stale IPC socket");
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abstract_endpoints() {
+        assert!(is_abstract("@reth.ipc"));
+        assert!(!is_abstract("/tmp/reth.ipc"));
+        assert!(socket_name("/tmp/reth.ipc").is_ok());
+        assert_eq!(
+            socket_name("@reth.ipc").is_ok(),
+            cfg!(any(target_os = "linux", target_os = "android"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn remove_stale_sockets() {
+        let dir = std::env::temp_dir();
+        let num: u64 = rand::Rng::gen(&mut rand::thread_rng());
+
+        // a socket without listener is removed
+        let stale = dir.join(format!("reth-stale-{num}.ipc"));
+        let stale = stale.to_str().unwrap();
+        drop(std::os::unix::net::UnixListener::bind(stale).unwrap());
+        remove_stale_socket(stale).await.unwrap();
+        assert!(std::fs::metadata(stale).is_err());
+
+        // a socket with listener is kept
+        let active = dir.join(format!("reth-active-{num}.ipc"));
+        let active = active.to_str().unwrap();
+        let _listener = std::os::unix::net::UnixListener::bind(active).unwrap();
+        let err = remove_stale_socket(active).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        std::fs::remove_file(active).unwrap();
+
+        // other files are kept
+        let file = dir.join(format!("reth-file-{num}.ipc"));
+        std::fs::write(&file, "").unwrap();
+        let err = remove_stale_socket(file.to_str().unwrap()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        std::fs::remove_file(file).unwrap();
+    }
+}
@@ -15,5 +15,7 @@
 pub mod client;
 pub mod server;
 
+mod endpoint;
+
 /// Json codec implementation
 pub mod stream_codec;
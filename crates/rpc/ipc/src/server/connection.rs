@@ -59,7 +59,8 @@ where
 /// This forwards received requests from the connection to the service and sends responses to the
 /// connection.
 ///
-/// This future terminates when the connection is closed.
+/// This future terminates when the connection is closed, or once all in-flight calls have been
+/// answered after [`Self::drain`] was called.
 #[pin_project::pin_project]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub(crate) struct IpcConnDriver<T, S, Fut> {
@@ -70,6 +71,8 @@ pub(crate) struct IpcConnDriver<T, S, Fut> {
     #[pin]
     pub(crate) pending_calls: FuturesUnordered<Fut>,
     pub(crate) items: VecDeque<String>,
+    /// Whether the connection no longer reads requests, and only answers the in-flight calls.
+    pub(crate) draining: bool,
 }
 
 impl<T, S, Fut> IpcConnDriver<T, S, Fut> {
@@ -77,6 +80,12 @@ impl<T, S, Fut> IpcConnDriver<T, S, Fut> {
     pub(crate) fn push_back(&mut self, item: String) {
         self.items.push_back(item);
     }
+
+    /// Stops reading requests from the connection, so that it terminates once all in-flight calls
+    /// have been answered.
+    pub(crate) fn drain(&mut self) {
+        self.draining = true;
+    }
 }
 
 impl<T, S> Future for IpcConnDriver<T, S, S::Future>
@@ -134,6 +143,14 @@ where
                     }
                 }
 
+                if *this.draining {
+                    if this.pending_calls.is_empty() && this.items.is_empty() {
+                        // all in-flight calls have been answered
+                        return this.conn.as_mut().poll_flush(cx).map(|_| ())
+                    }
+                    return Poll::Pending
+                }
+
                 // read from the stream
                 match this.conn.as_mut().poll_next(cx) {
                     Poll::Ready(res) => match res {
@@ -8,9 +8,12 @@ use jsonrpsee::{
         tracing::server::{rx_log_from_json, tx_log_from_str},
         JsonRawValue,
     },
-    server::middleware::rpc::RpcServiceT,
+    server::{middleware::rpc::RpcServiceT, BatchRequestConfig},
     types::{
-        error::{reject_too_big_request, ErrorCode},
+        error::{
+            reject_too_big_batch_request, reject_too_big_request, ErrorCode,
+            BATCHES_NOT_SUPPORTED_CODE, BATCHES_NOT_SUPPORTED_MSG,
+        },
         ErrorObject, Id, InvalidRequest, Notification, Request,
     },
     BatchResponseBuilder, MethodResponse, ResponsePayload,
@@ -35,13 +38,28 @@ pub(crate) struct Batch<S> {
 pub(crate) async fn process_batch_request<S>(
     b: Batch<S>,
     max_response_body_size: usize,
+    batch_config: BatchRequestConfig,
 ) -> Option<String>
 where
     for<'a> S: RpcServiceT<'a> + Send,
 {
     let Batch { data, rpc_service } = b;
 
+    let max_len = match batch_config {
+        BatchRequestConfig::Disabled => {
+            let err =
+                ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, BATCHES_NOT_SUPPORTED_MSG, None);
+            return Some(batch_response_error(Id::Null, err))
+        }
+        BatchRequestConfig::Limit(limit) => limit as usize,
+        BatchRequestConfig::Unlimited => usize::MAX,
+    };
+
     if let Ok(batch) = serde_json::from_slice::<Vec<&JsonRawValue>>(&data) {
+        if batch.len() > max_len {
+            return Some(batch_response_error(Id::Null, reject_too_big_batch_request(max_len)))
+        }
+
         let mut got_notif = false;
         let mut batch_response = BatchResponseBuilder::new_with_limit(max_response_body_size);
 
@@ -127,6 +145,7 @@ pub(crate) async fn call_with_service<S>(
     rpc_service: S,
     max_response_body_size: usize,
     max_request_body_size: usize,
+    batch_config: BatchRequestConfig,
     conn: Arc<OwnedSemaphorePermit>,
 ) -> Option<String>
 where
@@ -166,7 +185,8 @@ where
             }
         }
     } else {
-        process_batch_request(Batch { data, rpc_service }, max_response_body_size).await
+        process_batch_request(Batch { data, rpc_service }, max_response_body_size, batch_config)
+            .await
     };
 
     drop(conn);
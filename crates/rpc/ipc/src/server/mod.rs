@@ -1,17 +1,20 @@
 //! JSON-RPC IPC server implementation
 
-use crate::server::connection::{IpcConn, JsonRpcStream};
+use crate::{
+    endpoint,
+    server::connection::{IpcConn, JsonRpcStream},
+};
 use futures::StreamExt;
 use futures_util::future::Either;
 use interprocess::local_socket::{
     tokio::prelude::{LocalSocketListener, LocalSocketStream},
     traits::tokio::{Listener, Stream},
-    GenericFilePath, ListenerOptions, ToFsName,
+    ListenerOptions,
 };
 use jsonrpsee::{
     core::TEN_MB_SIZE_BYTES,
     server::{
-        middleware::rpc::{RpcLoggerLayer, RpcServiceT},
+        middleware::rpc::{either::Either as EitherLayer, RpcLoggerLayer, RpcServiceT},
         stop_channel, ConnectionGuard, ConnectionPermit, IdProvider, RandomIntegerIdProvider,
         ServerHandle, StopHandle,
     },
@@ -29,7 +32,7 @@ use tokio::{
     sync::oneshot,
 };
 use tower::{layer::util::Identity, Layer, Service};
-use tracing::{debug, instrument, trace, warn, Instrument};
+use tracing::{instrument, trace, warn, Instrument};
 // re-export so can be used during builder setup
 use crate::{
     server::{
@@ -46,6 +49,8 @@ mod connection;
 mod ipc;
 mod rpc_service;
 
+pub use jsonrpsee::server::BatchRequestConfig;
+
 /// Ipc Server implementation
 ///
 /// This is an adapted `jsonrpsee` Server, but for `Ipc` connections.
@@ -128,19 +133,7 @@ where
     ) {
         trace!(endpoint = ?self.endpoint, "starting ipc server");
 
-        if cfg!(unix) {
-            // ensure the file does not exist
-            if std::fs::remove_file(&self.endpoint).is_ok() {
-                debug!(endpoint = ?self.endpoint, "removed existing IPC endpoint file");
-            }
-        }
-
-        let listener = match self
-            .endpoint
-            .as_str()
-            .to_fs_name::<GenericFilePath>()
-            .and_then(|name| ListenerOptions::new().name(name).create_tokio())
-        {
+        let listener = match listen(&self.endpoint, self.cfg.socket_permissions).await {
             Ok(listener) => listener,
             Err(err) => {
                 on_ready
@@ -218,6 +211,38 @@ where
     }
 }
 
+/// Creates the listener of the endpoint.
+///
+/// On Unix, a stale socket file of a previous server is removed first, and the permissions are
+/// applied to the new socket file.
+async fn listen(endpoint: &str, permissions: SocketPermissions) -> io::Result<LocalSocketListener> {
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::local_socket::ListenerOptionsExt;
+
+        if endpoint::is_abstract(endpoint) {
+            return ListenerOptions::new().name(endpoint::socket_name(endpoint)?).create_tokio()
+        }
+
+        endpoint::remove_stale_socket(endpoint).await?;
+        let mut options = ListenerOptions::new().name(endpoint::socket_name(endpoint)?);
+        if let Some(mode) = permissions.mode {
+            options = options.mode(mode as _);
+        }
+        let listener = options.create_tokio()?;
+        if permissions.uid.is_some() || permissions.gid.is_some() {
+            std::os::unix::fs::chown(endpoint, permissions.uid, permissions.gid)?;
+        }
+        Ok(listener)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = permissions;
+        ListenerOptions::new().name(endpoint::socket_name(endpoint)?).create_tokio()
+    }
+}
+
 enum AcceptConnection<S> {
     Shutdown,
     Established { local_socket_stream: LocalSocketStream, stop: S },
@@ -305,11 +330,11 @@ impl<L> RpcServiceBuilder<L> {
     pub fn option_layer<T>(
         self,
         layer: Option<T>,
-    ) -> RpcServiceBuilder<Stack<Either<T, Identity>, L>> {
+    ) -> RpcServiceBuilder<Stack<EitherLayer<T, Identity>, L>> {
         let layer = if let Some(layer) = layer {
-            Either::Left(layer)
+            EitherLayer::Left(layer)
         } else {
-            Either::Right(Identity::new())
+            EitherLayer::Right(Identity::new())
         };
         self.layer(layer)
     }
@@ -347,12 +372,38 @@ impl<L> RpcServiceBuilder<L> {
 
 /// `JsonRPSee` service compatible with `tower`.
 ///
+/// The rpc middleware is built once per connection and shared by all requests of the connection,
+/// so that middlewares and the subscription limit of the connection can keep state across
+/// requests.
+///
 /// # Note
 /// This is similar to [`hyper::service::service_fn`](https://docs.rs/hyper/latest/hyper/service/fn.service_fn.html).
-#[derive(Debug, Clone)]
-pub struct TowerServiceNoHttp<L> {
+pub struct TowerServiceNoHttp<L: Layer<RpcService>> {
     inner: ServiceData,
-    rpc_middleware: RpcServiceBuilder<L>,
+    rpc_service: Arc<L::Service>,
+}
+
+impl<L: Layer<RpcService>> Clone for TowerServiceNoHttp<L> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), rpc_service: self.rpc_service.clone() }
+    }
+}
+
+impl<L: Layer<RpcService>> std::fmt::Debug for TowerServiceNoHttp<L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TowerServiceNoHttp").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+/// The rpc service of a connection, shared by the requests of the connection.
+struct SharedRpcService<S>(Arc<S>);
+
+impl<'a, S: RpcServiceT<'a>> RpcServiceT<'a> for SharedRpcService<S> {
+    type Future = S::Future;
+
+    fn call(&self, request: jsonrpsee::types::Request<'a>) -> Self::Future {
+        self.0.call(request)
+    }
 }
 
 impl<RpcMiddleware> Service<String> for TowerServiceNoHttp<RpcMiddleware>
@@ -379,23 +430,11 @@ where
     fn call(&mut self, request: String) -> Self::Future {
         trace!("{:?}", request);
 
-        let cfg = RpcServiceCfg::CallsAndSubscriptions {
-            bounded_subscriptions: BoundedSubscriptions::new(
-                self.inner.server_cfg.max_subscriptions_per_connection,
-            ),
-            id_provider: self.inner.id_provider.clone(),
-            sink: self.inner.method_sink.clone(),
-        };
-
         let max_response_body_size = self.inner.server_cfg.max_response_body_size as usize;
         let max_request_body_size = self.inner.server_cfg.max_request_body_size as usize;
+        let batch_config = self.inner.server_cfg.batch_requests_config;
         let conn = self.inner.conn_permit.clone();
-        let rpc_service = self.rpc_middleware.service(RpcService::new(
-            self.inner.methods.clone(),
-            max_response_body_size,
-            self.inner.conn_id.into(),
-            cfg,
-        ));
+        let rpc_service = SharedRpcService(self.rpc_service.clone());
         // an ipc connection needs to handle read+write concurrently
         // even if the underlying rpc handler spawns the actual work or is does a lot of async any
         // additional overhead performed by `handle_request` can result in I/O latencies, for
@@ -408,6 +447,7 @@ where
                 rpc_service,
                 max_response_body_size,
                 max_request_body_size,
+                batch_config,
                 conn,
             )
             .await
@@ -467,20 +507,30 @@ fn process_connection<'b, RpcMiddleware, HttpMiddleware>(
 
     let (tx, rx) = mpsc::channel::<String>(server_cfg.message_buffer_capacity as usize);
     let method_sink = MethodSink::new_with_limit(tx, server_cfg.max_response_body_size);
+    let bounded_subscriptions =
+        BoundedSubscriptions::new(server_cfg.max_subscriptions_per_connection);
+    let rpc_service = rpc_middleware.service(RpcService::new(
+        methods.clone(),
+        server_cfg.max_response_body_size as usize,
+        conn_id.into(),
+        RpcServiceCfg::CallsAndSubscriptions {
+            bounded_subscriptions: bounded_subscriptions.clone(),
+            id_provider: id_provider.clone(),
+            sink: method_sink.clone(),
+        },
+    ));
     let tower_service = TowerServiceNoHttp {
         inner: ServiceData {
             methods,
             id_provider,
             stop_handle: stop_handle.clone(),
-            server_cfg: server_cfg.clone(),
+            server_cfg,
             conn_id,
             conn_permit,
-            bounded_subscriptions: BoundedSubscriptions::new(
-                server_cfg.max_subscriptions_per_connection,
-            ),
+            bounded_subscriptions,
             method_sink,
         },
-        rpc_middleware,
+        rpc_service: Arc::new(rpc_service),
     };
 
     let service = http_middleware.service(tower_service);
@@ -507,6 +557,7 @@ async fn to_ipc_service<S, T>(
         service,
         pending_calls: Default::default(),
         items: Default::default(),
+        draining: false,
     };
     let stopped = stop_handle.shutdown();
 
@@ -524,9 +575,9 @@ async fn to_ipc_service<S, T>(
                     conn.push_back(item);
                 }
             }
-            _ = &mut stopped => {
-                // shutdown
-                break
+            _ = &mut stopped, if !conn.draining => {
+                // shutdown gracefully, answering the in-flight calls before closing the connection
+                conn.drain();
             }
         }
     }
@@ -551,6 +602,25 @@ pub struct Settings {
     message_buffer_capacity: u32,
     /// Custom tokio runtime to run the server on.
     tokio_runtime: Option<tokio::runtime::Handle>,
+    /// Permissions of the socket file.
+    socket_permissions: SocketPermissions,
+    /// Whether batch requests are supported, and their maximum length.
+    batch_requests_config: BatchRequestConfig,
+}
+
+/// Permissions of the socket file of an IPC server.
+///
+/// These only apply to socket files on Unix, not to sockets in the Linux abstract namespace or
+/// Windows named pipes. Unset permissions are left at their defaults, i.e. the mode is derived
+/// from the umask of the process and the socket is owned by the user and group of the process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SocketPermissions {
+    /// The mode of the socket file, e.g. `0o660` to restrict access to the owner and group.
+    pub mode: Option<u32>,
+    /// The user id of the owner of the socket file.
+    pub uid: Option<u32>,
+    /// The group id of the owner of the socket file.
+    pub gid: Option<u32>,
 }
 
 impl Default for Settings {
@@ -563,6 +633,8 @@ impl Default for Settings {
             max_subscriptions_per_connection: 1024,
             message_buffer_capacity: 1024,
             tokio_runtime: None,
+            socket_permissions: SocketPermissions::default(),
+            batch_requests_config: BatchRequestConfig::Unlimited,
         }
     }
 }
@@ -649,6 +721,23 @@ impl<HttpMiddleware, RpcMiddleware> Builder<HttpMiddleware, RpcMiddleware> {
         self
     }
 
+    /// Configure the permissions of the socket file, see [`SocketPermissions`].
+    ///
+    /// Changing the owner of the socket file requires the corresponding privileges.
+    pub const fn socket_permissions(mut self, permissions: SocketPermissions) -> Self {
+        self.settings.socket_permissions = permissions;
+        self
+    }
+
+    /// Configure how [batch requests](https://www.jsonrpc.org/specification#batch) are handled by
+    /// the server.
+    ///
+    /// Default: batch requests are allowed and unlimited in length, only their size is limited.
+    pub const fn set_batch_request_config(mut self, config: BatchRequestConfig) -> Self {
+        self.settings.batch_requests_config = config;
+        self
+    }
+
     /// Configure custom `subscription ID` provider for the server to use
     /// to when getting new subscription calls.
     ///
@@ -779,6 +868,10 @@ impl<HttpMiddleware, RpcMiddleware> Builder<HttpMiddleware, RpcMiddleware> {
     }
 
     /// Finalize the configuration of the server. Consumes the [`Builder`].
+    ///
+    /// The endpoint is the path of the socket file on Unix, or the name of the named pipe on
+    /// Windows. On Linux, endpoints prefixed with `@` are sockets in the abstract namespace,
+    /// e.g. `@reth.ipc`, which have no socket file.
     pub fn build(self, endpoint: String) -> IpcServer<HttpMiddleware, RpcMiddleware> {
         IpcServer {
             endpoint,
@@ -878,6 +971,69 @@ mod tests {
         assert!(response.unwrap_err().to_string().contains("Exceeded max limit of"));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_socket_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        init_test_tracing();
+        let endpoint = &dummy_name();
+        let server = Builder::default()
+            .socket_permissions(SocketPermissions { mode: Some(0o600), ..Default::default() })
+            .build(endpoint.clone());
+        let mut module = RpcModule::new(());
+        module.register_method("anything", |_, _, _| "succeed").unwrap();
+        let handle = server.start(module).await.unwrap();
+        tokio::spawn(handle.stopped());
+
+        let mode = std::fs::metadata(endpoint).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let response: String = client.request("anything", rpc_params![]).await.unwrap();
+        assert_eq!(response, "succeed");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stale_socket_cleanup() {
+        init_test_tracing();
+        let endpoint = &dummy_name();
+        let mut module = RpcModule::new(());
+        module.register_method("anything", |_, _, _| "succeed").unwrap();
+
+        // the socket file of a server that didn't shut down gracefully is removed
+        drop(std::os::unix::net::UnixListener::bind(endpoint).unwrap());
+        let handle =
+            Builder::default().build(endpoint.clone()).start(module.clone()).await.unwrap();
+
+        // the socket of a running server is not
+        let err = Builder::default().build(endpoint.clone()).start(module).await.unwrap_err();
+        assert_eq!(err.source.kind(), io::ErrorKind::AddrInUse);
+
+        let client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let response: String = client.request("anything", rpc_params![]).await.unwrap();
+        assert_eq!(response, "succeed");
+        handle.stop().unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_abstract_socket() {
+        init_test_tracing();
+        let endpoint = &format!("@{}", dummy_name());
+        let server = Builder::default().build(endpoint.clone());
+        let mut module = RpcModule::new(());
+        module.register_method("anything", |_, _, _| "succeed").unwrap();
+        let handle = server.start(module).await.unwrap();
+        tokio::spawn(handle.stopped());
+
+        // abstract sockets have no file
+        assert!(std::fs::metadata(&endpoint[1..]).is_err());
+        let client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let response: String = client.request("anything", rpc_params![]).await.unwrap();
+        assert_eq!(response, "succeed");
+    }
+
     #[tokio::test]
     async fn can_set_the_max_request_body_size() {
         init_test_tracing();
@@ -979,6 +1135,57 @@ mod tests {
         assert_eq!(result, vec!["ok", "ok", "ok"]);
     }
 
+    #[tokio::test]
+    async fn test_batch_request_limit() {
+        let endpoint = &dummy_name();
+        let server = Builder::default()
+            .set_batch_request_config(BatchRequestConfig::Limit(2))
+            .build(endpoint.clone());
+        let mut module = RpcModule::new(());
+        module.register_method("anything", |_, _, _| "ok").unwrap();
+        let handle = server.start(module).await.unwrap();
+        tokio::spawn(handle.stopped());
+
+        let client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let mut batch_request_builder = BatchRequestBuilder::new();
+        let _ = batch_request_builder.insert("anything", rpc_params![]);
+        let _ = batch_request_builder.insert("anything", rpc_params![]);
+        let result = client.batch_request::<String>(batch_request_builder.clone()).await.unwrap();
+        assert_eq!(result.num_successful_calls(), 2);
+
+        // the batch is rejected with an error without id
+        let _ = batch_request_builder.insert("anything", rpc_params![]);
+        assert!(client.batch_request::<String>(batch_request_builder).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown() {
+        init_test_tracing();
+        let endpoint = &dummy_name();
+        let server = Builder::default().build(endpoint.clone());
+        let mut module = RpcModule::new(());
+        module
+            .register_async_method("slow", |_, _, _| async {
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                "done"
+            })
+            .unwrap();
+        let handle = server.start(module).await.unwrap();
+
+        let client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let call =
+            tokio::spawn(async move { client.request::<String, _>("slow", rpc_params![]).await });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        // the in-flight call is answered before the server stops
+        handle.stop().unwrap();
+        handle.stopped().await;
+        assert_eq!(call.await.unwrap().unwrap(), "done");
+
+        // and no new connections are accepted
+        assert!(IpcClientBuilder::default().build(endpoint).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_ipc_modules() {
         reth_tracing::init_test_tracing();
@@ -1029,6 +1236,45 @@ mod tests {
         assert_eq!(items.len(), 16);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_max_subscriptions_per_connection() {
+        let endpoint = &dummy_name();
+        let server = Builder::default().max_subscriptions_per_connection(1).build(endpoint.clone());
+
+        let mut module = RpcModule::new(());
+        module
+            .register_subscription(
+                "subscribe_hello",
+                "s_hello",
+                "unsubscribe_hello",
+                |_, pending, _, _| async move {
+                    let sink = pending.accept().await?;
+                    sink.closed().await;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+        let handle = server.start(module).await.unwrap();
+        tokio::spawn(handle.stopped());
+
+        // the limit holds across the requests of a connection
+        let client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let _sub: Subscription<usize> =
+            client.subscribe("subscribe_hello", rpc_params![], "unsubscribe_hello").await.unwrap();
+        let result = client
+            .subscribe::<usize, _>("subscribe_hello", rpc_params![], "unsubscribe_hello")
+            .await;
+        assert!(result.is_err());
+
+        // but not across connections
+        let other_client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let _other_sub: Subscription<usize> = other_client
+            .subscribe("subscribe_hello", rpc_params![], "unsubscribe_hello")
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_rpc_middleware() {
         #[derive(Clone)]
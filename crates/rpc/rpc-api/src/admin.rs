@@ -1,6 +1,8 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_config::ConfigReloadReport;
 use reth_network_peers::{AnyNode, NodeRecord};
 use reth_rpc_types::{admin::NodeInfo, PeerInfo};
+use std::collections::BTreeMap;
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
@@ -46,3 +48,89 @@ pub trait AdminApi {
     #[method(name = "nodeInfo")]
     async fn node_info(&self) -> RpcResult<NodeInfo>;
 }
+
+/// Admin namespace rpc interface to control the sync pipeline.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminPipelineApi {
+    /// Pauses the sync pipeline before it runs its next stage.
+    ///
+    /// The stage that is currently running is committed first. Live sync through the engine API
+    /// is paused as well. The node stays paused across restarts until `admin_resumePipeline` is
+    /// called.
+    ///
+    /// Returns false if the pipeline was already paused.
+    #[method(name = "pausePipeline")]
+    fn pause_pipeline(&self) -> RpcResult<bool>;
+
+    /// Resumes a paused sync pipeline.
+    ///
+    /// Returns false if the pipeline wasn't paused.
+    #[method(name = "resumePipeline")]
+    fn resume_pipeline(&self) -> RpcResult<bool>;
+
+    /// Returns true if the sync pipeline is paused.
+    #[method(name = "pipelinePaused")]
+    fn pipeline_paused(&self) -> RpcResult<bool>;
+}
+
+/// Admin namespace rpc interface to reload the configuration file.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminConfigApi {
+    /// Re-reads the configuration file and applies the changed settings that are safe to change at
+    /// runtime: the peer connection limits, and the block interval and schedule of the pruner.
+    ///
+    /// Returns the changed settings that were applied, and the changed settings that only take
+    /// effect after a restart.
+    #[method(name = "reloadConfig")]
+    fn reload_config(&self) -> RpcResult<ConfigReloadReport>;
+}
+
+/// Admin namespace rpc interface to rotate the JWT secrets of the auth server.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminJwtApi {
+    /// Re-reads the JWT secret file of the auth server, so that the consensus client can switch
+    /// to a new secret without restarting the node.
+    ///
+    /// The file contains one secret per line, all of which are accepted. The previous secrets are
+    /// kept if the file is invalid.
+    ///
+    /// Returns the number of accepted secrets.
+    #[method(name = "reloadJwtSecret")]
+    fn reload_jwt_secret(&self) -> RpcResult<usize>;
+}
+
+/// Admin namespace rpc interface to abort the EVM executions of rpc requests.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminExecutionApi {
+    /// Aborts all running EVM executions of `eth_call`, gas estimation and tracing requests, e.g.
+    /// to recover from expensive calls slowing down the node. The aborted requests fail with an
+    /// execution cancelled error.
+    ///
+    /// Running `eth_call` and `eth_estimateGas` executions are only aborted with
+    /// `--rpc.evm-interruptible`, otherwise they complete. Requests started afterwards are not
+    /// affected.
+    #[method(name = "cancelExecutions")]
+    fn cancel_executions(&self) -> RpcResult<()>;
+}
+
+/// Admin namespace rpc interface to enable and disable rpc namespaces of the running servers.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminRpcModulesApi {
+    /// Enables or disables the given namespace, e.g. `debug`, on all transports.
+    ///
+    /// Calls of a disabled namespace are rejected as if the namespace was not installed. The
+    /// `admin` namespace can't be disabled.
+    ///
+    /// Returns false if the namespace was already in the requested state.
+    #[method(name = "toggleRpcModule")]
+    fn toggle_rpc_module(&self, module: String, enabled: bool) -> RpcResult<bool>;
+
+    /// Returns the installed namespaces and whether they are enabled.
+    #[method(name = "rpcModules")]
+    fn rpc_modules(&self) -> RpcResult<BTreeMap<String, bool>>;
+}
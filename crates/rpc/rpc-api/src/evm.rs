@@ -0,0 +1,23 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::U64;
+use reth_rpc_types::anvil::MineOptions;
+
+/// Block production rpc interface of the dev mode, compatible with the `evm_` methods of Anvil and
+/// Hardhat.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "evm"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "evm"))]
+pub trait EvmApi {
+    /// Mines a single block with all ready transactions, or an empty block if there are none.
+    ///
+    /// If the options contain a timestamp, the block is mined with it. If they contain a number
+    /// of blocks, that many blocks are mined.
+    ///
+    /// Returns "0x0" once the blocks are canonical.
+    #[method(name = "mine")]
+    async fn evm_mine(&self, opts: Option<MineOptions>) -> RpcResult<String>;
+
+    /// Sets the timestamp of the next block, which must be greater than the timestamp of the
+    /// latest block.
+    #[method(name = "setNextBlockTimestamp")]
+    async fn evm_set_next_block_timestamp(&self, timestamp: U64) -> RpcResult<()>;
+}
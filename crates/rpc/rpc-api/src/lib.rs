@@ -18,6 +18,7 @@ mod admin;
 mod anvil;
 mod debug;
 mod engine;
+mod evm;
 mod ganache;
 mod hardhat;
 mod mev;
@@ -37,13 +38,17 @@ pub use servers::*;
 /// Aggregates all server traits.
 pub mod servers {
     pub use crate::{
-        admin::AdminApiServer,
+        admin::{
+            AdminApiServer, AdminConfigApiServer, AdminExecutionApiServer, AdminJwtApiServer,
+            AdminPipelineApiServer, AdminRpcModulesApiServer,
+        },
         debug::DebugApiServer,
         engine::{EngineApiServer, EngineEthApiServer},
+        evm::EvmApiServer,
         mev::MevApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
-        reth::RethApiServer,
+        reth::{RethAccountHistoryApiServer, RethApiServer, RethWitnessApiServer},
         rpc::RpcApiServer,
         trace::TraceApiServer,
         txpool::TxPoolApiServer,
@@ -64,15 +69,20 @@ pub use clients::*;
 #[cfg(feature = "client")]
 pub mod clients {
     pub use crate::{
-        admin::AdminApiClient,
+        admin::{
+            AdminApiClient, AdminConfigApiClient, AdminExecutionApiClient, AdminJwtApiClient,
+            AdminPipelineApiClient, AdminRpcModulesApiClient,
+        },
         anvil::AnvilApiClient,
         debug::DebugApiClient,
         engine::{EngineApiClient, EngineEthApiClient},
+        evm::EvmApiClient,
         ganache::GanacheApiClient,
         hardhat::HardhatApiClient,
         mev::MevApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
+        reth::{RethAccountHistoryApiClient, RethApiClient, RethWitnessApiClient},
         rpc::RpcApiServer,
         trace::TraceApiClient,
         txpool::TxPoolApiClient,
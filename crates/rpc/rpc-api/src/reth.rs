@@ -1,6 +1,8 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, U256};
-use std::collections::HashMap;
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, U256};
+use reth_prune_types::{PruneCheckpoint, PruneSegment};
+use reth_rpc_types::{AccountHistoryEntry, WitnessRange};
+use std::collections::{BTreeMap, HashMap};
 
 /// Reth API namespace for reth-specific methods
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
@@ -12,4 +14,44 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the prune checkpoints of all segments that have been pruned, i.e. the highest
+    /// block and transaction up to which their data is no longer available.
+    #[method(name = "pruneStatus")]
+    async fn reth_prune_status(&self) -> RpcResult<BTreeMap<PruneSegment, PruneCheckpoint>>;
+}
+
+/// Reth API namespace serving the execution witnesses of blocks to stateless clients.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "reth"))]
+pub trait RethWitnessApi {
+    /// Returns the compressed execution witnesses of the blocks in the given inclusive range.
+    ///
+    /// The witnesses are returned in pages that are limited in the number of blocks and in size.
+    /// If the range doesn't fit into a page, the response contains the number of the next block,
+    /// to request the remaining witnesses with. A page always contains at least one witness.
+    #[method(name = "getWitnessRange")]
+    async fn reth_get_witness_range(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> RpcResult<WitnessRange>;
+}
+
+/// Reth API namespace serving the materialized balance and nonce history of configured accounts.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "reth"))]
+pub trait RethAccountHistoryApi {
+    /// Returns the balance and nonce history of the account in the given inclusive block range.
+    ///
+    /// The first entry is the value of the account at the start of the range, followed by an
+    /// entry for every block in the range that changed the balance or nonce of the account.
+    /// Only the history of the accounts configured with `--rpc.account-history` is available.
+    #[method(name = "accountHistory")]
+    async fn reth_account_history(
+        &self,
+        address: Address,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> RpcResult<Vec<AccountHistoryEntry>>;
 }
@@ -0,0 +1,383 @@
+//! Middlewares authenticating the clients of the http and ws servers with API keys.
+
+use hmac::{Hmac, Mac};
+use http::StatusCode;
+use jsonrpsee::{
+    server::{
+        middleware::rpc::{ResponseFuture, RpcServiceT},
+        HttpBody, HttpResponse,
+    },
+    types::{ErrorObject, Request},
+    MethodResponse,
+};
+use pin_project::pin_project;
+use reth_config::{ApiKeyConfig, ApiKeyKind};
+use reth_primitives::hex;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::{Layer, Service};
+
+/// The header clients send their API key in.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The error code of calls of methods that the API key of the client is not allowed to call.
+pub const METHOD_NOT_ALLOWED_CODE: i32 = -32004;
+
+/// The API keys of the http and ws servers.
+///
+/// Keys are either static, where clients send the secret of the key itself, or signed, where
+/// clients send a token of the form `<name>.<expiry>.<signature>` that is valid until the expiry,
+/// see [`ApiKeyKind`].
+#[derive(Default)]
+pub struct ApiKeys {
+    /// The static keys, by the SHA-256 hash of their secret.
+    static_keys: HashMap<[u8; 32], Arc<ApiKey>>,
+    /// The signed keys with their secret, by their name.
+    signed_keys: HashMap<String, (Vec<u8>, Arc<ApiKey>)>,
+}
+
+impl ApiKeys {
+    /// Creates the keys from the given configs, keyed by the name of the key.
+    pub fn new(keys: &BTreeMap<String, ApiKeyConfig>) -> Self {
+        let mut api_keys = Self::default();
+        for (name, config) in keys {
+            let key = Arc::new(ApiKey { name: name.clone(), methods: config.methods.clone() });
+            match config.kind {
+                ApiKeyKind::Static => {
+                    api_keys.static_keys.insert(Sha256::digest(&config.secret).into(), key);
+                }
+                ApiKeyKind::Hmac => {
+                    api_keys.signed_keys.insert(name.clone(), (config.secret.clone().into(), key));
+                }
+            }
+        }
+        api_keys
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.static_keys.len() + self.signed_keys.len()
+    }
+
+    /// Returns `true` if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the key of the given secret or token, if it is valid at the given unix timestamp.
+    pub fn authenticate(&self, token: &str, now: u64) -> Result<Arc<ApiKey>, ApiKeyError> {
+        if let Some(key) = self.static_keys.get(&<[u8; 32]>::from(Sha256::digest(token))) {
+            return Ok(key.clone())
+        }
+
+        let mut parts = token.splitn(3, '.');
+        let (Some(name), Some(expiry), Some(signature)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ApiKeyError::Invalid)
+        };
+        let (secret, key) = self.signed_keys.get(name).ok_or(ApiKeyError::Invalid)?;
+        let signature = hex::decode(signature).map_err(|_| ApiKeyError::Invalid)?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("any key size is valid");
+        mac.update(name.as_bytes());
+        mac.update(b".");
+        mac.update(expiry.as_bytes());
+        mac.verify_slice(&signature).map_err(|_| ApiKeyError::Invalid)?;
+
+        let expiry = expiry.parse::<u64>().map_err(|_| ApiKeyError::Invalid)?;
+        if expiry <= now {
+            return Err(ApiKeyError::Expired)
+        }
+        Ok(key.clone())
+    }
+}
+
+impl fmt::Debug for ApiKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // don't print the secrets
+        f.debug_struct("ApiKeys").field("len", &self.len()).finish_non_exhaustive()
+    }
+}
+
+/// An API key a client authenticated with.
+///
+/// The [`ApiKeyAuthLayer`] inserts the key into the extensions of the requests of the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey {
+    /// The name of the key.
+    name: String,
+    /// The methods the key is allowed to call, all if empty.
+    methods: Vec<String>,
+}
+
+impl ApiKey {
+    /// Returns the name of the key.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the key is allowed to call the method.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        self.methods.is_empty() ||
+            self.methods.iter().any(|allowed| match allowed.strip_suffix('*') {
+                Some(prefix) => method.starts_with(prefix),
+                None => allowed == method,
+            })
+    }
+}
+
+/// Why a client could not be authenticated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ApiKeyError {
+    /// The request has no API key.
+    #[error("missing API key")]
+    Missing,
+    /// The API key is unknown or its signature is invalid.
+    #[error("invalid API key")]
+    Invalid,
+    /// The token of a signed API key expired.
+    #[error("expired API key")]
+    Expired,
+}
+
+/// A [`Layer`] for http requests that authenticates clients with the API key in their
+/// [`API_KEY_HEADER`].
+///
+/// Requests without a valid key are rejected with `401 Unauthorized`. The [`ApiKey`] of accepted
+/// requests is inserted into their extensions, where the [`ApiKeyAllowlistLayer`] checks the
+/// methods the key is allowed to call.
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuthLayer {
+    keys: Arc<ApiKeys>,
+}
+
+impl ApiKeyAuthLayer {
+    /// Creates a new layer accepting the given keys.
+    pub fn new(keys: ApiKeys) -> Self {
+        Self { keys: Arc::new(keys) }
+    }
+}
+
+impl<S> Layer<S> for ApiKeyAuthLayer {
+    type Service = ApiKeyAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyAuth { inner, keys: self.keys.clone() }
+    }
+}
+
+/// A http middleware that authenticates clients with their API key.
+///
+/// See [`ApiKeyAuthLayer`].
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuth<S> {
+    inner: S,
+    keys: Arc<ApiKeys>,
+}
+
+impl<S, B> Service<http::Request<B>> for ApiKeyAuth<S>
+where
+    S: Service<http::Request<B>, Response = HttpResponse>,
+{
+    type Response = HttpResponse;
+    type Error = S::Error;
+    type Future = ApiKeyAuthFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let key = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .ok_or(ApiKeyError::Missing)
+            .and_then(|value| value.to_str().map_err(|_| ApiKeyError::Invalid))
+            .and_then(|token| self.keys.authenticate(token.trim(), now));
+
+        match key {
+            Ok(key) => {
+                req.extensions_mut().insert(key);
+                ApiKeyAuthFuture { kind: Kind::Future(self.inner.call(req)) }
+            }
+            Err(err) => {
+                let response = http::Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .body(HttpBody::from(err.to_string()))
+                    .expect("response is valid");
+                ApiKeyAuthFuture { kind: Kind::Unauthorized(Some(response)) }
+            }
+        }
+    }
+}
+
+/// Response future of [`ApiKeyAuth`].
+#[pin_project]
+pub struct ApiKeyAuthFuture<F> {
+    #[pin]
+    kind: Kind<F>,
+}
+
+impl<F> fmt::Debug for ApiKeyAuthFuture<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyAuthFuture").finish_non_exhaustive()
+    }
+}
+
+#[pin_project(project = KindProj)]
+enum Kind<F> {
+    /// The request was authenticated and is processed.
+    Future(#[pin] F),
+    /// The request was rejected.
+    Unauthorized(Option<HttpResponse>),
+}
+
+impl<F, E> Future for ApiKeyAuthFuture<F>
+where
+    F: Future<Output = Result<HttpResponse, E>>,
+{
+    type Output = Result<HttpResponse, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::Future(future) => future.poll(cx),
+            KindProj::Unauthorized(response) => {
+                Poll::Ready(Ok(response.take().expect("polled after completion")))
+            }
+        }
+    }
+}
+
+/// A [`Layer`] that rejects calls of methods that the [`ApiKey`] of the client is not allowed to
+/// call with [`METHOD_NOT_ALLOWED_CODE`].
+///
+/// Calls without an [`ApiKey`], i.e. of servers without API keys, are not checked.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct ApiKeyAllowlistLayer;
+
+impl ApiKeyAllowlistLayer {
+    /// Creates a new layer.
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for ApiKeyAllowlistLayer {
+    type Service = ApiKeyAllowlist<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyAllowlist { inner }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that rejects calls of methods that are not allowed for the
+/// [`ApiKey`] of the client.
+///
+/// See [`ApiKeyAllowlistLayer`].
+#[derive(Debug, Clone)]
+pub struct ApiKeyAllowlist<S> {
+    inner: S,
+}
+
+impl<'a, S> RpcServiceT<'a> for ApiKeyAllowlist<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if let Some(key) = req.extensions().get::<Arc<ApiKey>>() {
+            if !key.is_allowed(req.method_name()) {
+                let err = ErrorObject::owned(
+                    METHOD_NOT_ALLOWED_CODE,
+                    format!("method {} is not allowed for API key {}", req.method_name(), key.name),
+                    None::<()>,
+                );
+                return ResponseFuture::ready(MethodResponse::error(req.id, err))
+            }
+        }
+
+        ResponseFuture::future(self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> ApiKeys {
+        ApiKeys::new(&BTreeMap::from([
+            (
+                "wallet".to_string(),
+                ApiKeyConfig {
+                    secret: "wallet-secret".to_string(),
+                    kind: ApiKeyKind::Static,
+                    methods: vec![],
+                },
+            ),
+            (
+                "indexer".to_string(),
+                ApiKeyConfig {
+                    secret: "indexer-secret".to_string(),
+                    kind: ApiKeyKind::Hmac,
+                    methods: vec!["eth_getLogs".to_string(), "debug_*".to_string()],
+                },
+            ),
+        ]))
+    }
+
+    fn sign(name: &str, expiry: u64, secret: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{name}.{expiry}").as_bytes());
+        format!("{name}.{expiry}.{}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn authenticate_static_keys() {
+        let keys = keys();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys.authenticate("wallet-secret", 0).unwrap().name(), "wallet");
+        assert_eq!(keys.authenticate("wallet", 0), Err(ApiKeyError::Invalid));
+        // signed keys can't be used as static keys
+        assert_eq!(keys.authenticate("indexer-secret", 0), Err(ApiKeyError::Invalid));
+    }
+
+    #[test]
+    fn authenticate_signed_keys() {
+        let keys = keys();
+        let token = sign("indexer", 100, "indexer-secret");
+        assert_eq!(keys.authenticate(&token, 99).unwrap().name(), "indexer");
+        assert_eq!(keys.authenticate(&token, 100), Err(ApiKeyError::Expired));
+
+        let forged = sign("indexer", 100, "wrong-secret");
+        assert_eq!(keys.authenticate(&forged, 99), Err(ApiKeyError::Invalid));
+        let extended = token.replacen(".100.", ".200.", 1);
+        assert_eq!(keys.authenticate(&extended, 99), Err(ApiKeyError::Invalid));
+        // static keys can't sign tokens
+        let token = sign("wallet", 100, "wallet-secret");
+        assert_eq!(keys.authenticate(&token, 99), Err(ApiKeyError::Invalid));
+    }
+
+    #[test]
+    fn method_allowlist() {
+        let keys = keys();
+        let wallet = keys.authenticate("wallet-secret", 0).unwrap();
+        assert!(wallet.is_allowed("eth_sendRawTransaction"));
+
+        let indexer = keys.authenticate(&sign("indexer", 100, "indexer-secret"), 0).unwrap();
+        assert!(indexer.is_allowed("eth_getLogs"));
+        assert!(indexer.is_allowed("debug_traceTransaction"));
+        assert!(!indexer.is_allowed("eth_getLogsByHash"));
+        assert!(!indexer.is_allowed("eth_sendRawTransaction"));
+    }
+}
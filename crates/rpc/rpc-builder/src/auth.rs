@@ -1,4 +1,8 @@
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
 use crate::error::{RpcError, ServerKind};
 use http::header::AUTHORIZATION;
@@ -13,21 +17,29 @@ use reth_rpc_api::*;
 use reth_rpc_eth_types::EthSubscriptionIdProvider;
 use reth_rpc_layer::{
     secret_to_bearer_header, AuthClientLayer, AuthClientService, AuthLayer, JwtAuthValidator,
-    JwtSecret,
+    JwtSecret, JwtSecrets, JwtSecretsFile,
 };
 use reth_rpc_server_types::constants;
 use tower::layer::util::Identity;
+use tracing::{debug, info, warn};
 
 pub use jsonrpsee::server::ServerBuilder;
 pub use reth_ipc::server::Builder as IpcServerBuilder;
 
+/// The default interval at which the JWT secret file of the auth server is checked for changes.
+pub const DEFAULT_JWT_SECRET_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Server configuration for the auth server.
 #[derive(Debug)]
 pub struct AuthServerConfig {
     /// Where the server should listen.
     pub(crate) socket_addr: SocketAddr,
-    /// The secret for the auth layer of the server.
-    pub(crate) secret: JwtSecret,
+    /// The secrets of the auth layer of the server.
+    pub(crate) secrets: JwtSecrets,
+    /// The file the secrets of the auth layer are reloaded from when it changes.
+    pub(crate) secret_file: Option<JwtSecretsFile>,
+    /// How often the secret file is checked for changes.
+    pub(crate) secret_reload_interval: Duration,
     /// Configs for JSON-RPC Http.
     pub(crate) server_config: ServerBuilder<Identity, Identity>,
     /// Configs for IPC server
@@ -49,13 +61,33 @@ impl AuthServerConfig {
         self.socket_addr
     }
 
+    /// Returns the file the secrets of the server are read from, if configured.
+    ///
+    /// Besides the periodic check for changes of the file, the secrets can be rotated on demand
+    /// by [reloading](JwtSecretsFile::reload) it, e.g. with `admin_reloadJwtSecret`.
+    pub const fn secret_file(&self) -> Option<&JwtSecretsFile> {
+        self.secret_file.as_ref()
+    }
+
     /// Convenience function to start a server in one step.
     pub async fn start(self, module: AuthRpcModule) -> Result<AuthServerHandle, RpcError> {
-        let Self { socket_addr, secret, server_config, ipc_server_config, ipc_endpoint } = self;
+        let Self {
+            socket_addr,
+            secrets,
+            secret_file,
+            secret_reload_interval,
+            server_config,
+            ipc_server_config,
+            mut ipc_endpoint,
+        } = self;
 
         // Create auth middleware.
-        let middleware =
-            tower::ServiceBuilder::new().layer(AuthLayer::new(JwtAuthValidator::new(secret)));
+        let mut reloader = secret_file.map(JwtSecretsReloader::new);
+        if let Some(reloader) = &mut reloader {
+            reloader.reload_if_changed();
+        }
+        let middleware = tower::ServiceBuilder::new()
+            .layer(AuthLayer::new(JwtAuthValidator::with_secrets(secrets.clone())));
 
         // By default, both http and ws are enabled.
         let server = server_config
@@ -69,12 +101,28 @@ impl AuthServerConfig {
             .map_err(|err| RpcError::server_error(err, ServerKind::Auth(socket_addr)))?;
 
         let handle = server.start(module.inner.clone());
+
+        if let Some(mut reloader) = reloader {
+            // the secrets are reloaded until the server stopped
+            let stopped = handle.clone().stopped();
+            tokio::spawn(async move {
+                tokio::pin!(stopped);
+                let mut interval = tokio::time::interval(secret_reload_interval);
+                loop {
+                    tokio::select! {
+                        _ = &mut stopped => break,
+                        _ = interval.tick() => reloader.reload_if_changed(),
+                    }
+                }
+            });
+        }
+
         let mut ipc_handle: Option<jsonrpsee::server::ServerHandle> = None;
 
         if let Some(ipc_server_config) = ipc_server_config {
             let ipc_endpoint_str = ipc_endpoint
-                .clone()
-                .unwrap_or_else(|| constants::DEFAULT_ENGINE_API_IPC_ENDPOINT.to_string());
+                .get_or_insert_with(|| constants::DEFAULT_ENGINE_API_IPC_ENDPOINT.to_string())
+                .clone();
             let ipc_server = ipc_server_config.build(ipc_endpoint_str);
             let res = ipc_server
                 .start(module.inner)
@@ -83,7 +131,51 @@ impl AuthServerConfig {
             ipc_handle = Some(res);
         }
 
-        Ok(AuthServerHandle { handle, local_addr, secret, ipc_endpoint, ipc_handle })
+        Ok(AuthServerHandle {
+            handle,
+            local_addr,
+            secret: secrets.primary(),
+            ipc_endpoint,
+            ipc_handle,
+        })
+    }
+}
+
+/// Replaces the secrets of the auth layer with the secrets of their file when it changes.
+#[derive(Debug)]
+struct JwtSecretsReloader {
+    file: JwtSecretsFile,
+    /// The modification time of the file the secrets were last read at.
+    modified: Option<SystemTime>,
+}
+
+impl JwtSecretsReloader {
+    const fn new(file: JwtSecretsFile) -> Self {
+        Self { file, modified: None }
+    }
+
+    fn reload_if_changed(&mut self) {
+        let path = self.file.path();
+        let modified = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .inspect_err(
+                |err| debug!(target: "rpc", %err, ?path, "Failed to read modification time"),
+            )
+            .ok();
+        if modified.is_none() || modified == self.modified {
+            return
+        }
+        self.modified = modified;
+
+        match self.file.reload() {
+            Ok(secrets) => {
+                info!(target: "rpc", ?path, secrets, "Loaded JWT secrets");
+            }
+            Err(err) => {
+                // keep accepting the previous secrets, the file might be written partially
+                warn!(target: "rpc", %err, ?path, "Failed to reload JWT secrets");
+            }
+        }
     }
 }
 
@@ -92,6 +184,8 @@ impl AuthServerConfig {
 pub struct AuthServerConfigBuilder {
     socket_addr: Option<SocketAddr>,
     secret: JwtSecret,
+    secret_file: Option<PathBuf>,
+    secret_reload_interval: Duration,
     server_config: Option<ServerBuilder<Identity, Identity>>,
     ipc_server_config: Option<IpcServerBuilder<Identity, Identity>>,
     ipc_endpoint: Option<String>,
@@ -105,6 +199,8 @@ impl AuthServerConfigBuilder {
         Self {
             socket_addr: None,
             secret,
+            secret_file: None,
+            secret_reload_interval: DEFAULT_JWT_SECRET_RELOAD_INTERVAL,
             server_config: None,
             ipc_server_config: None,
             ipc_endpoint: None,
@@ -129,6 +225,26 @@ impl AuthServerConfigBuilder {
         self
     }
 
+    /// Set the file the secrets of the server are read from.
+    ///
+    /// The file contains one hex encoded secret per line, and all of them are accepted, the first
+    /// one being the primary secret. The file is checked for changes periodically, and the
+    /// secrets are reloaded if it was modified, so that secrets can be rotated without restarting
+    /// the server, see also [`AuthServerConfig::secret_file`]. The `secret` of the builder is only
+    /// used if the file can't be read.
+    pub fn secret_file(mut self, secret_file: PathBuf) -> Self {
+        self.secret_file = Some(secret_file);
+        self
+    }
+
+    /// Set how often the secret file is checked for changes.
+    ///
+    /// Defaults to [`DEFAULT_JWT_SECRET_RELOAD_INTERVAL`].
+    pub const fn secret_reload_interval(mut self, interval: Duration) -> Self {
+        self.secret_reload_interval = interval;
+        self
+    }
+
     /// Configures the JSON-RPC server
     ///
     /// Note: this always configures an [`EthSubscriptionIdProvider`]
@@ -139,6 +255,11 @@ impl AuthServerConfigBuilder {
     }
 
     /// Set the ipc endpoint for the server.
+    ///
+    /// The IPC server is only started if it is configured with
+    /// [`AuthServerConfigBuilder::with_ipc_config`], and listens on
+    /// [`DEFAULT_ENGINE_API_IPC_ENDPOINT`](constants::DEFAULT_ENGINE_API_IPC_ENDPOINT) if no
+    /// endpoint is set.
     pub fn ipc_endpoint(mut self, ipc_endpoint: String) -> Self {
         self.ipc_endpoint = Some(ipc_endpoint);
         self
@@ -146,6 +267,9 @@ impl AuthServerConfigBuilder {
 
     /// Configures the IPC server
     ///
+    /// Requests over IPC are not authenticated with the JWT secret, so access to the socket
+    /// should be restricted with [`IpcServerBuilder::socket_permissions`] instead.
+    ///
     /// Note: this always configures an [`EthSubscriptionIdProvider`]
     pub fn with_ipc_config(mut self, config: IpcServerBuilder<Identity, Identity>) -> Self {
         self.ipc_server_config = Some(config.set_id_provider(EthSubscriptionIdProvider::default()));
@@ -154,11 +278,14 @@ impl AuthServerConfigBuilder {
 
     /// Build the `AuthServerConfig`.
     pub fn build(self) -> AuthServerConfig {
+        let secrets = JwtSecrets::from(self.secret);
         AuthServerConfig {
             socket_addr: self.socket_addr.unwrap_or_else(|| {
                 SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), constants::DEFAULT_AUTH_PORT)
             }),
-            secret: self.secret,
+            secret_file: self.secret_file.map(|path| JwtSecretsFile::new(path, secrets.clone())),
+            secrets,
+            secret_reload_interval: self.secret_reload_interval,
             server_config: self.server_config.unwrap_or_else(|| {
                 ServerBuilder::new()
                     // This needs to large enough to handle large eth_getLogs responses and maximum
@@ -222,6 +349,21 @@ impl AuthRpcModule {
         self.module_mut().merge(other.into()).map(|_| true)
     }
 
+    /// Merge the given [Methods] in all transports of the auth server (http, ws and ipc).
+    ///
+    /// This is the authenticated counterpart of
+    /// [`TransportRpcModules::merge_configured`](crate::TransportRpcModules::merge_configured):
+    /// the methods are only served on the JWT protected auth port, so privileged custom
+    /// namespaces can be exposed without adding them to the public server.
+    ///
+    /// Fails if any of the methods in other is present already.
+    pub fn merge_configured(
+        &mut self,
+        other: impl Into<Methods>,
+    ) -> Result<(), RegisterMethodError> {
+        self.merge_auth_methods(other).map(|_| ())
+    }
+
     /// Convenience function for starting a server
     pub async fn start_server(
         self,
@@ -254,7 +396,12 @@ impl AuthServerHandle {
     }
 
     /// Tell the server to stop without waiting for the server to stop.
+    ///
+    /// This stops the IPC server as well, if started.
     pub fn stop(self) -> Result<(), AlreadyStoppedError> {
+        if let Some(ipc_handle) = self.ipc_handle {
+            ipc_handle.stop()?;
+        }
         self.handle.stop()
     }
 
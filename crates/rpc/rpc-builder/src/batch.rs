@@ -0,0 +1,249 @@
+//! Middlewares limiting the cumulative cost of the calls of batch requests.
+
+use jsonrpsee::{
+    server::{
+        middleware::rpc::{ResponseFuture, RpcServiceT},
+        ws::is_upgrade_request,
+    },
+    types::{error::TOO_BIG_BATCH_REQUEST_CODE, ErrorObject, Request},
+    MethodResponse,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// The methods that are expensive by default, see [`BatchCostBudget`].
+pub const DEFAULT_EXPENSIVE_METHODS: &[&str] = &[
+    "eth_call",
+    "eth_callMany",
+    "eth_createAccessList",
+    "eth_estimateGas",
+    "eth_getLogs",
+    "debug_traceBlockByHash",
+    "debug_traceBlockByNumber",
+    "debug_traceCall",
+    "debug_traceCallMany",
+    "debug_traceTransaction",
+    "trace_block",
+    "trace_call",
+    "trace_callMany",
+    "trace_filter",
+    "trace_replayBlockTransactions",
+    "trace_replayTransaction",
+];
+
+/// The default cost of the [`DEFAULT_EXPENSIVE_METHODS`].
+pub const DEFAULT_EXPENSIVE_METHOD_COST: u64 = 10;
+
+/// The cost budget of batch requests and the costs of the methods they call.
+///
+/// Every call costs 1, except for calls of methods with a configured cost. By default, the
+/// [`DEFAULT_EXPENSIVE_METHODS`] cost [`DEFAULT_EXPENSIVE_METHOD_COST`].
+#[derive(Debug, Clone)]
+pub struct BatchCostBudget {
+    /// The maximum cumulative cost of the calls of a batch.
+    budget: u64,
+    /// The costs of the methods that don't cost 1, keyed by method name.
+    costs: Arc<HashMap<String, u64>>,
+}
+
+impl BatchCostBudget {
+    /// Creates a new budget of the given cumulative cost per batch, with the default method costs.
+    pub fn new(budget: u64) -> Self {
+        let costs = DEFAULT_EXPENSIVE_METHODS
+            .iter()
+            .map(|method| (method.to_string(), DEFAULT_EXPENSIVE_METHOD_COST))
+            .collect();
+        Self { budget, costs: Arc::new(costs) }
+    }
+
+    /// Sets the costs of the given methods, keyed by method name, overriding their defaults.
+    pub fn with_method_costs(mut self, costs: impl IntoIterator<Item = (String, u64)>) -> Self {
+        Arc::make_mut(&mut self.costs).extend(costs);
+        self
+    }
+
+    /// Returns the maximum cumulative cost of the calls of a batch.
+    pub const fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Returns the cost of a call of the given method.
+    pub fn method_cost(&self, method: &str) -> u64 {
+        self.costs.get(method).copied().unwrap_or(1)
+    }
+}
+
+/// The calls of a http request processed so far, and their cumulative cost.
+///
+/// Inserted into the extensions of every http request by the [`BatchCostTrackerLayer`], and
+/// shared by all calls of a batch request.
+#[derive(Debug, Clone, Default)]
+pub struct BatchCost(Arc<Mutex<BatchCostState>>);
+
+#[derive(Debug, Default)]
+struct BatchCostState {
+    /// The number of calls processed so far.
+    calls: usize,
+    /// The cumulative cost of the calls processed so far.
+    cost: u64,
+}
+
+impl BatchCost {
+    /// Adds a call of the given cost, and returns its index in the batch and the cumulative cost
+    /// of the batch including the call.
+    fn charge(&self, cost: u64) -> (usize, u64) {
+        let mut state = self.0.lock();
+        let index = state.calls;
+        state.calls += 1;
+        state.cost = state.cost.saturating_add(cost);
+        (index, state.cost)
+    }
+}
+
+/// The error data of a call of a batch request that exceeds the [`BatchCostBudget`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchCostExceeded {
+    /// The index of the call in the batch.
+    pub index: usize,
+    /// The method of the call.
+    pub method: String,
+    /// The cumulative cost of the batch up to and including the call.
+    pub cost: u64,
+    /// The budget of the batch.
+    pub budget: u64,
+}
+
+/// A [`Layer`] for http requests that inserts a fresh [`BatchCost`] into every request, which is
+/// charged by the [`BatchCostLimitLayer`].
+///
+/// Websocket upgrade requests are skipped, since all messages of a connection share the extensions
+/// of its upgrade request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchCostTrackerLayer;
+
+impl<S> Layer<S> for BatchCostTrackerLayer {
+    type Service = BatchCostTracker<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchCostTracker { inner }
+    }
+}
+
+/// A http middleware that inserts a fresh [`BatchCost`] into every request.
+///
+/// See [`BatchCostTrackerLayer`].
+#[derive(Debug, Clone)]
+pub struct BatchCostTracker<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for BatchCostTracker<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        if !is_upgrade_request(&req) {
+            req.extensions_mut().insert(BatchCost::default());
+        }
+        self.inner.call(req)
+    }
+}
+
+/// A [`Layer`] that rejects the calls of batch requests that exceed the [`BatchCostBudget`].
+///
+/// The calls of a batch are charged in order, and the first call of a batch is always processed,
+/// so a single call is never rejected. Once a call exceeds the budget, it and all remaining calls
+/// of the batch are rejected with [`TOO_BIG_BATCH_REQUEST_CODE`], the same error code as batches
+/// that exceed the maximum length, with a [`BatchCostExceeded`] as error data.
+///
+/// Only calls with a [`BatchCost`] are limited, i.e. the calls of http requests with the
+/// [`BatchCostTrackerLayer`] applied.
+#[derive(Debug, Clone)]
+pub struct BatchCostLimitLayer {
+    budget: BatchCostBudget,
+}
+
+impl BatchCostLimitLayer {
+    /// Creates a new layer enforcing the given budget.
+    pub const fn new(budget: BatchCostBudget) -> Self {
+        Self { budget }
+    }
+}
+
+impl<S> Layer<S> for BatchCostLimitLayer {
+    type Service = BatchCostLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchCostLimit { inner, budget: self.budget.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that rejects the calls of batch requests that exceed their budget.
+///
+/// See [`BatchCostLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct BatchCostLimit<S> {
+    inner: S,
+    budget: BatchCostBudget,
+}
+
+impl<'a, S> RpcServiceT<'a> for BatchCostLimit<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if let Some(batch) = req.extensions().get::<BatchCost>() {
+            let (index, cost) = batch.charge(self.budget.method_cost(req.method_name()));
+            if index > 0 && cost > self.budget.budget() {
+                let err = ErrorObject::owned(
+                    TOO_BIG_BATCH_REQUEST_CODE,
+                    "batch request exceeds its cost budget",
+                    Some(BatchCostExceeded {
+                        index,
+                        method: req.method_name().to_string(),
+                        cost,
+                        budget: self.budget.budget(),
+                    }),
+                );
+                return ResponseFuture::ready(MethodResponse::error(req.id, err))
+            }
+        }
+
+        ResponseFuture::future(self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_cost() {
+        let budget = BatchCostBudget::new(25).with_method_costs([("eth_getLogs".to_string(), 20)]);
+        assert_eq!(budget.method_cost("eth_blockNumber"), 1);
+        assert_eq!(budget.method_cost("eth_call"), DEFAULT_EXPENSIVE_METHOD_COST);
+        assert_eq!(budget.method_cost("eth_getLogs"), 20);
+
+        let batch = BatchCost::default();
+        assert_eq!(batch.charge(budget.method_cost("eth_getLogs")), (0, 20));
+        assert_eq!(batch.charge(budget.method_cost("eth_blockNumber")), (1, 21));
+        assert_eq!(batch.charge(budget.method_cost("eth_call")), (2, 31));
+    }
+}
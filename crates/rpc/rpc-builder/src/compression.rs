@@ -0,0 +1,98 @@
+//! Compression of http responses.
+
+use jsonrpsee::server::{HttpBody, HttpResponse};
+use pin_project::pin_project;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tower::{Layer, Service};
+use tower_http::compression::{
+    predicate::SizeAbove, Compression, CompressionLayer, ResponseFuture,
+};
+
+/// A [`Layer`] that compresses http responses of at least a minimum size with gzip or brotli,
+/// depending on the `Accept-Encoding` header of the request.
+///
+/// Responses that are smaller than the minimum size, or whose request doesn't accept any of these
+/// encodings, are sent uncompressed.
+#[derive(Debug, Clone)]
+pub struct HttpCompressionLayer {
+    inner: CompressionLayer<SizeAbove>,
+}
+
+impl HttpCompressionLayer {
+    /// Creates a new layer compressing responses of at least `min_size` bytes.
+    pub fn new(min_size: u16) -> Self {
+        Self {
+            inner: CompressionLayer::new()
+                .no_deflate()
+                .no_zstd()
+                .compress_when(SizeAbove::new(min_size)),
+        }
+    }
+}
+
+impl<S> Layer<S> for HttpCompressionLayer {
+    type Service = HttpCompression<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpCompression { inner: self.inner.layer(inner) }
+    }
+}
+
+/// A http middleware that compresses responses.
+///
+/// Unlike [`Compression`], the compressed body is boxed into a [`HttpBody`], so the middleware can
+/// be used in the http middleware of the servers.
+///
+/// See [`HttpCompressionLayer`].
+#[derive(Clone)]
+pub struct HttpCompression<S> {
+    inner: Compression<S, SizeAbove>,
+}
+
+impl<S, B> Service<http::Request<B>> for HttpCompression<S>
+where
+    S: Service<http::Request<B>, Response = HttpResponse>,
+{
+    type Response = HttpResponse;
+    type Error = S::Error;
+    type Future = HttpCompressionFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        HttpCompressionFuture { inner: self.inner.call(req) }
+    }
+}
+
+impl<S> fmt::Debug for HttpCompression<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpCompression").finish_non_exhaustive()
+    }
+}
+
+/// Response future of [`HttpCompression`].
+#[pin_project]
+#[derive(Debug)]
+pub struct HttpCompressionFuture<F> {
+    #[pin]
+    inner: ResponseFuture<F, SizeAbove>,
+}
+
+impl<F, E> Future for HttpCompressionFuture<F>
+where
+    F: Future<Output = Result<HttpResponse, E>>,
+{
+    type Output = Result<HttpResponse, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let res = ready!(self.project().inner.poll(cx))?;
+        Poll::Ready(Ok(res.map(HttpBody::new)))
+    }
+}
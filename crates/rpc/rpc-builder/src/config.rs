@@ -1,13 +1,14 @@
 use crate::{
-    auth::AuthServerConfig, error::RpcError, EthConfig, IpcServerBuilder, RpcModuleConfig,
-    RpcServerConfig, TransportRpcModuleConfig,
+    auth::AuthServerConfig, cors::CorsConfig, error::RpcError, BatchCostBudget, EthConfig,
+    IpcServerBuilder, IpcSocketPermissions, RequestTimeouts, RpcModuleConfig,
+    RpcRequestLoggerLayer, RpcServerConfig, TransportRpcModuleConfig,
 };
 use jsonrpsee::server::ServerBuilder;
 use reth_node_core::{args::RpcServerArgs, utils::get_or_create_jwt_secret_from_path};
 use reth_rpc_eth_types::{EthStateCacheConfig, GasPriceOracleConfig};
-use reth_rpc_layer::{JwtError, JwtSecret};
+use reth_rpc_layer::{read_jwt_secrets, JwtError, JwtSecret};
 use reth_rpc_server_types::RpcModuleSelection;
-use std::{net::SocketAddr, path::PathBuf};
+use std::{net::SocketAddr, num::NonZeroU64, path::PathBuf};
 use tower::layer::util::Identity;
 use tracing::debug;
 
@@ -37,6 +38,9 @@ pub trait RethRpcServerConfig {
     /// Extracts the gas price oracle config from the args.
     fn gas_price_oracle_config(&self) -> GasPriceOracleConfig;
 
+    /// Returns the deadlines of RPC calls.
+    fn request_timeouts(&self) -> RequestTimeouts;
+
     /// Creates the [`TransportRpcModuleConfig`] from cli args.
     ///
     /// This sets all the api modules, and configures additional settings like gas price oracle
@@ -53,7 +57,14 @@ pub trait RethRpcServerConfig {
     fn rpc_server_config(&self) -> RpcServerConfig;
 
     /// Creates the [`AuthServerConfig`] from cli args.
-    fn auth_server_config(&self, jwt_secret: JwtSecret) -> Result<AuthServerConfig, RpcError>;
+    ///
+    /// The secrets of the auth server are reloaded from the file of the `auth_jwtsecret`
+    /// argument, or from the `default_jwt_path` if the argument is not provided.
+    fn auth_server_config(
+        &self,
+        jwt_secret: JwtSecret,
+        default_jwt_path: PathBuf,
+    ) -> Result<AuthServerConfig, RpcError>;
 
     /// The execution layer and consensus layer clients SHOULD accept a configuration parameter:
     /// jwt-secret, which designates a file containing the hex-encoded 256 bit secret key to be used
@@ -67,7 +78,8 @@ pub trait RethRpcServerConfig {
     /// the filesystem. This file can then be used to provision the counterpart client.
     ///
     /// The `default_jwt_path` provided as an argument will be used as the default location for the
-    /// jwt secret in case the `auth_jwtsecret` argument is not provided.
+    /// jwt secret in case the `auth_jwtsecret` argument is not provided. If the file of the
+    /// argument contains multiple secrets, the primary secret is returned.
     fn auth_jwt_secret(&self, default_jwt_path: PathBuf) -> Result<JwtSecret, JwtError>;
 
     /// Returns the configured jwt secret key for the regular rpc servers, if any.
@@ -92,6 +104,8 @@ impl RethRpcServerConfig for RpcServerArgs {
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .rpc_evm_memory_limit(self.rpc_evm_memory_limit)
+            .rpc_evm_interruptible(self.rpc_evm_interruptible)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
     }
@@ -117,9 +131,21 @@ impl RethRpcServerConfig for RpcServerArgs {
         self.gas_price_oracle.gas_price_oracle_config()
     }
 
+    fn request_timeouts(&self) -> RequestTimeouts {
+        let mut timeouts = RequestTimeouts::default();
+        if let Some(timeout) = self.rpc_timeout {
+            timeouts = timeouts.with_default(timeout);
+        }
+        for (namespace, timeout) in &self.rpc_namespace_timeouts {
+            timeouts = timeouts.with_namespace(namespace.clone(), *timeout);
+        }
+        timeouts
+    }
+
     fn transport_rpc_module_config(&self) -> TransportRpcModuleConfig {
         let mut config = TransportRpcModuleConfig::default()
-            .with_config(RpcModuleConfig::new(self.eth_config()));
+            .with_config(RpcModuleConfig::new(self.eth_config()))
+            .with_request_timeouts(self.request_timeouts());
 
         if self.http {
             config = config.with_http(
@@ -161,38 +187,69 @@ impl RethRpcServerConfig for RpcServerArgs {
     }
 
     fn rpc_server_config(&self) -> RpcServerConfig {
-        let mut config = RpcServerConfig::default().with_jwt_secret(self.rpc_secret_key());
+        let mut config = RpcServerConfig::default()
+            .with_jwt_secret(self.rpc_secret_key())
+            .with_max_subscriptions(self.rpc_max_subscriptions.map(|max| max as usize))
+            .with_max_batch_size(self.rpc_max_batch_size)
+            .with_batch_cost_budget(self.rpc_batch_cost_budget.map(BatchCostBudget::new));
 
         if self.http {
             let socket_address = SocketAddr::new(self.http_addr, self.http_port);
             config = config
                 .with_http_address(socket_address)
                 .with_http(self.http_ws_server_builder())
-                .with_http_cors(self.http_corsdomain.clone())
-                .with_ws_cors(self.ws_allowed_origins.clone());
+                .with_http_cors(self.http_corsdomain.as_deref().map(CorsConfig::from_domains))
+                .with_http_compression(
+                    self.http_compression.then_some(self.http_compression_threshold),
+                );
         }
 
         if self.ws {
             let socket_address = SocketAddr::new(self.ws_addr, self.ws_port);
-            config = config.with_ws_address(socket_address).with_ws(self.http_ws_server_builder());
+            config = config
+                .with_ws_address(socket_address)
+                .with_ws(self.http_ws_server_builder())
+                .with_ws_cors(self.ws_allowed_origins.as_deref().map(CorsConfig::from_domains));
         }
 
         if self.is_ipc_enabled() {
-            config =
-                config.with_ipc(self.ipc_server_builder()).with_ipc_endpoint(self.ipcpath.clone());
+            config = config
+                .with_ipc(self.ipc_server_builder())
+                .with_ipc_endpoint(self.ipcpath.clone())
+                .with_ipc_socket_permissions(IpcSocketPermissions {
+                    mode: self.ipc_permissions,
+                    ..Default::default()
+                });
+        }
+
+        if let (Some(cert), Some(key)) = (&self.rpc_tls_cert, &self.rpc_tls_key) {
+            config = config.with_tls(cert, key);
+        }
+
+        if let Some(every) = self.rpc_log_requests.and_then(NonZeroU64::new) {
+            config = config.with_request_logger(RpcRequestLoggerLayer::new().with_sampling(every));
         }
 
         config
     }
 
-    fn auth_server_config(&self, jwt_secret: JwtSecret) -> Result<AuthServerConfig, RpcError> {
+    fn auth_server_config(
+        &self,
+        jwt_secret: JwtSecret,
+        default_jwt_path: PathBuf,
+    ) -> Result<AuthServerConfig, RpcError> {
         let address = SocketAddr::new(self.auth_addr, self.auth_port);
 
-        let mut builder = AuthServerConfig::builder(jwt_secret).socket_addr(address);
+        let secret_file = self.auth_jwtsecret.clone().unwrap_or(default_jwt_path);
+        let mut builder =
+            AuthServerConfig::builder(jwt_secret).socket_addr(address).secret_file(secret_file);
         if self.auth_ipc {
-            builder = builder
-                .ipc_endpoint(self.auth_ipc_path.clone())
-                .with_ipc_config(self.ipc_server_builder());
+            builder = builder.ipc_endpoint(self.auth_ipc_path.clone()).with_ipc_config(
+                self.ipc_server_builder().socket_permissions(IpcSocketPermissions {
+                    mode: Some(self.auth_ipc_permissions),
+                    ..Default::default()
+                }),
+            );
         }
         Ok(builder.build())
     }
@@ -201,7 +258,7 @@ impl RethRpcServerConfig for RpcServerArgs {
         match self.auth_jwtsecret.as_ref() {
             Some(fpath) => {
                 debug!(target: "reth::cli", user_path=?fpath, "Reading JWT auth secret file");
-                JwtSecret::from_file(fpath)
+                read_jwt_secrets(fpath).map(|secrets| secrets[0])
             }
             None => get_or_create_jwt_secret_from_path(&default_jwt_path),
         }
@@ -214,7 +271,10 @@ impl RethRpcServerConfig for RpcServerArgs {
 
 #[cfg(test)]
 mod tests {
-    use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+    use std::{
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+        time::Duration,
+    };
 
     use clap::{Args, Parser};
     use reth_node_core::args::RpcServerArgs;
@@ -265,6 +325,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_request_timeouts() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.timeout",
+            "30s",
+            "--rpc.namespace-timeout",
+            "debug=2m",
+        ])
+        .args;
+        let config = args.transport_rpc_module_config();
+        let timeouts = config.request_timeouts();
+        assert_eq!(timeouts.timeout("eth_call"), Some(Duration::from_secs(30)));
+        assert_eq!(timeouts.timeout("debug_traceCall"), Some(Duration::from_secs(120)));
+    }
+
     #[test]
     fn test_transport_rpc_module_trim_config() {
         let args = CommandParser::<RpcServerArgs>::parse_from([
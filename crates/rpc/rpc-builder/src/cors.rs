@@ -1,44 +1,216 @@
-use http::{HeaderValue, Method};
-use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+//! CORS configuration of the http and ws servers.
+
+use http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowOrigin, Any, CorsLayer};
 
 /// Error thrown when parsing cors domains went wrong
 #[derive(Debug, thiserror::Error)]
 pub enum CorsDomainError {
+    /// An origin is not a valid header value.
     #[error("{domain} is an invalid header value")]
-    InvalidHeader { domain: String },
+    InvalidHeader {
+        /// The invalid origin.
+        domain: String,
+    },
+    /// The wildcard origin is combined with other origins.
     #[error("wildcard origin (`*`) cannot be passed as part of a list: {input}")]
-    WildCardNotAllowed { input: String },
+    WildCardNotAllowed {
+        /// The list of origins.
+        input: String,
+    },
+    /// An origin contains a wildcard that doesn't stand for the subdomains of a domain.
+    #[error("{domain} is an invalid wildcard origin, only subdomains like `https://*.example.com` can be matched")]
+    InvalidWildcard {
+        /// The invalid origin.
+        domain: String,
+    },
 }
 
-/// Creates a [`CorsLayer`] from the given domains
-pub(crate) fn create_cors_layer(http_cors_domains: &str) -> Result<CorsLayer, CorsDomainError> {
-    let cors = match http_cors_domains.trim() {
-        "*" => CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST])
-            .allow_origin(Any)
-            .allow_headers(Any),
-        _ => {
-            let iter = http_cors_domains.split(',');
-            if iter.clone().any(|o| o == "*") {
-                return Err(CorsDomainError::WildCardNotAllowed {
-                    input: http_cors_domains.to_string(),
-                })
-            }
+/// The CORS config of a http or ws server.
+///
+/// By default, requests with any headers and the `GET` and `POST` methods are allowed.
+///
+/// Origins are either exact, e.g. `https://example.com`, or match all subdomains of a domain,
+/// e.g. `https://*.example.com` matches `https://app.example.com` but not `https://example.com`.
+/// Wildcard origins without a scheme, e.g. `*.example.com`, match the subdomains with any scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+    /// The allowed origins, all origins are allowed if this is `*`.
+    origins: Vec<String>,
+    /// The allowed request headers, all headers are allowed if `None`.
+    allowed_headers: Option<Vec<HeaderName>>,
+    /// The allowed methods.
+    allowed_methods: Vec<Method>,
+}
+
+impl CorsConfig {
+    /// Creates a config allowing requests from the given origins.
+    pub fn new(origins: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            origins: origins.into_iter().map(Into::into).collect(),
+            allowed_headers: None,
+            allowed_methods: vec![Method::GET, Method::POST],
+        }
+    }
+
+    /// Creates a config allowing requests from all origins.
+    pub fn any() -> Self {
+        Self::new(["*"])
+    }
+
+    /// Creates a config from a comma separated list of origins, e.g. the value of
+    /// `--http.corsdomain`.
+    pub fn from_domains(domains: &str) -> Self {
+        let domains = domains.trim();
+        if domains == "*" {
+            return Self::any()
+        }
+        Self::new(domains.split(',').map(str::trim))
+    }
+
+    /// Only allows requests with the given headers.
+    ///
+    /// All headers are allowed by default.
+    pub fn with_allowed_headers(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        self.allowed_headers = Some(headers.into_iter().collect());
+        self
+    }
+
+    /// Only allows requests with the given methods.
+    ///
+    /// Defaults to `GET` and `POST`.
+    pub fn with_allowed_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.allowed_methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Returns the allowed origins.
+    pub fn origins(&self) -> &[String] {
+        &self.origins
+    }
 
-            let origins = iter
-                .map(|domain| {
-                    domain
+    /// Creates the [`CorsLayer`] of the config.
+    pub(crate) fn layer(&self) -> Result<CorsLayer, CorsDomainError> {
+        let headers = match &self.allowed_headers {
+            Some(headers) => AllowHeaders::list(headers.iter().cloned()),
+            None => Any.into(),
+        };
+        let cors = CorsLayer::new()
+            .allow_methods(self.allowed_methods.clone())
+            .allow_origin(self.allow_origin()?)
+            .allow_headers(headers);
+        Ok(cors)
+    }
+
+    fn allow_origin(&self) -> Result<AllowOrigin, CorsDomainError> {
+        if matches!(self.origins.as_slice(), [origin] if origin.trim() == "*") {
+            return Ok(Any.into())
+        }
+
+        let mut exact = Vec::new();
+        let mut wildcards = Vec::new();
+        for origin in &self.origins {
+            if origin == "*" {
+                return Err(CorsDomainError::WildCardNotAllowed { input: self.origins.join(",") })
+            }
+            if origin.contains('*') {
+                wildcards.push(WildcardOrigin::parse(origin)?);
+            } else {
+                exact.push(
+                    origin
                         .parse::<HeaderValue>()
-                        .map_err(|_| CorsDomainError::InvalidHeader { domain: domain.to_string() })
+                        .map_err(|_| CorsDomainError::InvalidHeader { domain: origin.clone() })?,
+                );
+            }
+        }
+
+        if wildcards.is_empty() {
+            return Ok(AllowOrigin::list(exact))
+        }
+        Ok(AllowOrigin::predicate(move |origin, _| {
+            exact.contains(origin) ||
+                origin.to_str().is_ok_and(|origin| {
+                    wildcards.iter().any(|wildcard| wildcard.matches(origin))
                 })
-                .collect::<Result<Vec<HeaderValue>, _>>()?;
+        }))
+    }
+}
+
+/// An origin matching all subdomains of a domain, e.g. `https://*.example.com`.
+#[derive(Debug)]
+struct WildcardOrigin {
+    /// The scheme of the origin, any scheme if `None`.
+    scheme: Option<String>,
+    /// The domain, including the port if any.
+    domain: String,
+}
 
-            let origin = AllowOrigin::list(origins);
-            CorsLayer::new()
-                .allow_methods([Method::GET, Method::POST])
-                .allow_origin(origin)
-                .allow_headers(Any)
+impl WildcardOrigin {
+    fn parse(origin: &str) -> Result<Self, CorsDomainError> {
+        let invalid = || CorsDomainError::InvalidWildcard { domain: origin.to_string() };
+        let (scheme, host) = match origin.split_once("://") {
+            Some((scheme, host)) => (Some(scheme.to_ascii_lowercase()), host),
+            None => (None, origin),
+        };
+        let domain = host.strip_prefix("*.").ok_or_else(invalid)?;
+        if domain.is_empty() || domain.contains(['*', '/']) {
+            return Err(invalid())
         }
-    };
-    Ok(cors)
+        Ok(Self { scheme, domain: domain.to_ascii_lowercase() })
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        let Some((scheme, host)) = origin.split_once("://") else { return false };
+        if self.scheme.as_ref().is_some_and(|expected| !expected.eq_ignore_ascii_case(scheme)) {
+            return false
+        }
+        let host = host.to_ascii_lowercase();
+        host.strip_suffix(self.domain.as_str())
+            .and_then(|subdomain| subdomain.strip_suffix('.'))
+            .is_some_and(|subdomain| !subdomain.is_empty() && !subdomain.contains('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_origins() {
+        assert_eq!(CorsConfig::from_domains(" * "), CorsConfig::any());
+        assert_eq!(
+            CorsConfig::from_domains("https://a.com,https://*.b.com").origins(),
+            ["https://a.com", "https://*.b.com"]
+        );
+        assert!(CorsConfig::from_domains("https://a.com,https://*.b.com").layer().is_ok());
+
+        assert!(matches!(
+            CorsConfig::from_domains("https://a.com,*").layer(),
+            Err(CorsDomainError::WildCardNotAllowed { .. })
+        ));
+        assert!(matches!(
+            CorsConfig::from_domains("https://a*.com").layer(),
+            Err(CorsDomainError::InvalidWildcard { .. })
+        ));
+        assert!(matches!(
+            CorsConfig::from_domains("https://*.").layer(),
+            Err(CorsDomainError::InvalidWildcard { .. })
+        ));
+    }
+
+    #[test]
+    fn wildcard_origins() {
+        let wildcard = WildcardOrigin::parse("https://*.example.com").unwrap();
+        assert!(wildcard.matches("https://app.example.com"));
+        assert!(wildcard.matches("https://a.b.Example.com"));
+        assert!(!wildcard.matches("https://example.com"));
+        assert!(!wildcard.matches("http://app.example.com"));
+        assert!(!wildcard.matches("https://appexample.com"));
+        assert!(!wildcard.matches("https://app.example.com:8080"));
+
+        let wildcard = WildcardOrigin::parse("*.example.com:8080").unwrap();
+        assert!(wildcard.matches("http://app.example.com:8080"));
+        assert!(wildcard.matches("https://app.example.com:8080"));
+        assert!(!wildcard.matches("https://app.example.com"));
+    }
 }
@@ -1,9 +1,14 @@
-use crate::{cors::CorsDomainError, RethRpcModule};
+use crate::{
+    cors::{CorsConfig, CorsDomainError},
+    RethRpcModule,
+};
+use jsonrpsee::server::AlreadyStoppedError;
 use reth_ipc::server::IpcServerStartError;
 use std::{
     collections::HashSet,
     io::{self, ErrorKind},
     net::SocketAddr,
+    time::Duration,
 };
 
 /// Rpc server kind.
@@ -70,6 +75,9 @@ pub enum RpcError {
     /// Thrown when IPC server fails to start.
     #[error(transparent)]
     IpcServerError(#[from] IpcServerStartError),
+    /// Thrown when the TLS certificate or private key can't be loaded.
+    #[error("Failed to load TLS certificate: {0}")]
+    Tls(io::Error),
     /// Custom error.
     #[error("{0}")]
     Custom(String),
@@ -110,6 +118,18 @@ impl std::fmt::Display for ConflictingModules {
     }
 }
 
+/// Errors of a graceful shutdown of the rpc servers, see
+/// [`RpcServerHandle::shutdown_graceful`](crate::RpcServerHandle::shutdown_graceful).
+#[derive(Debug, thiserror::Error)]
+pub enum GracefulShutdownError {
+    /// The servers have already been stopped.
+    #[error(transparent)]
+    AlreadyStopped(#[from] AlreadyStoppedError),
+    /// Connections were still open when the timeout elapsed.
+    #[error("connections still open after {0:?}")]
+    Timeout(Duration),
+}
+
 /// Errors when trying to launch ws and http server on the same port.
 #[derive(Debug, thiserror::Error)]
 pub enum WsHttpSamePortError {
@@ -119,10 +139,10 @@ pub enum WsHttpSamePortError {
          HTTP: {http_cors_domains:?}, WS: {ws_cors_domains:?}"
     )]
     ConflictingCorsDomains {
-        /// Http cors domains.
-        http_cors_domains: Option<String>,
-        /// Ws cors domains.
-        ws_cors_domains: Option<String>,
+        /// Http cors config.
+        http_cors_domains: Option<CorsConfig>,
+        /// Ws cors config.
+        ws_cors_domains: Option<CorsConfig>,
     },
     /// Ws and http server configured on same port but with different modules.
     #[error("{0}")]
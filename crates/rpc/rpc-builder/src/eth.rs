@@ -4,17 +4,17 @@ use reth_evm::ConfigureEvm;
 use reth_network_api::{NetworkInfo, Peers};
 use reth_provider::{
     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, StateProviderFactory,
+    EvmEnvProvider, PruneCheckpointReader, StateProviderFactory,
 };
 use reth_rpc::eth::{EthApi, EthFilter, EthFilterConfig, EthPubSub, RawTransactionForwarder};
 use reth_rpc_eth_types::{
     cache::cache_new_blocks_task, fee_history::fee_history_cache_new_blocks_task, EthStateCache,
-    EthStateCacheConfig, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    EthStateCacheConfig, ExecutionLimits, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
     GasPriceOracleConfig,
 };
 use reth_rpc_server_types::constants::{
-    default_max_tracing_requests, gas_oracle::RPC_DEFAULT_GAS_CAP, DEFAULT_MAX_BLOCKS_PER_FILTER,
-    DEFAULT_MAX_LOGS_PER_RESPONSE,
+    default_max_tracing_requests, gas_oracle::RPC_DEFAULT_GAS_CAP, DEFAULT_EVM_MEMORY_LIMIT,
+    DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE,
 };
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner};
 use reth_transaction_pool::TransactionPool;
@@ -74,6 +74,7 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static,
@@ -199,6 +200,8 @@ where
             cache.clone(),
             gas_oracle,
             self.rpc_config.eth.rpc_gas_cap,
+            ExecutionLimits::new(self.rpc_config.eth.rpc_evm_memory_limit)
+                .with_interruptible(self.rpc_config.eth.rpc_evm_interruptible),
             Box::new(self.eth_handlers_config.executor.clone()),
             blocking_task_pool.clone(),
             fee_history_cache.clone(),
@@ -247,6 +250,14 @@ pub struct EthConfig {
     ///
     /// Defaults to [`RPC_DEFAULT_GAS_CAP`]
     pub rpc_gas_cap: u64,
+    /// Memory limit in bytes of the EVM executions of `eth_call` and call tracing RPC methods.
+    ///
+    /// Defaults to [`DEFAULT_EVM_MEMORY_LIMIT`]
+    pub rpc_evm_memory_limit: u64,
+    /// Whether running `eth_call` and `eth_estimateGas` executions are aborted once cancelled.
+    ///
+    /// Defaults to `false`
+    pub rpc_evm_interruptible: bool,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: std::time::Duration,
@@ -276,6 +287,8 @@ impl Default for EthConfig {
             max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP,
+            rpc_evm_memory_limit: DEFAULT_EVM_MEMORY_LIMIT,
+            rpc_evm_interruptible: false,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
         }
@@ -318,4 +331,18 @@ impl EthConfig {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures the memory limit of the EVM executions of `eth_call` and call tracing RPC
+    /// methods
+    pub const fn rpc_evm_memory_limit(mut self, memory_limit: u64) -> Self {
+        self.rpc_evm_memory_limit = memory_limit;
+        self
+    }
+
+    /// Configures whether running `eth_call` and `eth_estimateGas` executions are aborted once
+    /// cancelled, e.g. by `admin_cancelExecutions`
+    pub const fn rpc_evm_interruptible(mut self, interruptible: bool) -> Self {
+        self.rpc_evm_interruptible = interruptible;
+        self
+    }
 }
@@ -0,0 +1,246 @@
+//! Health endpoints of the http server, e.g. for the liveness and readiness probes of Kubernetes.
+
+use http::{header, Method, StatusCode};
+use jsonrpsee::server::{HttpBody, HttpResponse};
+use pin_project::pin_project;
+use reth_config::HealthConfig;
+use reth_network_api::{NetworkInfo, PeersInfo};
+use reth_provider::{BlockNumReader, HeaderProvider};
+use serde::Serialize;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::{Layer, Service};
+
+/// Path of the liveness endpoint, which succeeds if the database of the node is accessible.
+pub const HEALTH_PATH: &str = "/health";
+
+/// Path of the readiness endpoint, which succeeds if the node is [ready](NodeHealth::is_ready).
+pub const READY_PATH: &str = "/ready";
+
+/// The state of the node reported by the health endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeHealth {
+    /// Whether the node is syncing.
+    pub syncing: bool,
+    /// Number of the latest block, if the database is accessible.
+    pub latest_block: Option<u64>,
+    /// Seconds since the timestamp of the latest block, if the database is accessible.
+    pub latest_block_age: Option<u64>,
+    /// Number of connected peers.
+    pub peers: usize,
+    /// Whether the latest block could be read from the database.
+    pub database: bool,
+}
+
+impl NodeHealth {
+    /// Returns `true` if the node is ready to serve requests, i.e. it is not syncing, its
+    /// database is accessible and it satisfies the criteria of the config.
+    pub fn is_ready(&self, config: &HealthConfig) -> bool {
+        let block_age_ok = match config.max_block_age {
+            Some(max_age) => self.latest_block_age.is_some_and(|age| age <= max_age.as_secs()),
+            None => true,
+        };
+        !self.syncing && self.database && self.peers >= config.min_peers && block_age_ok
+    }
+}
+
+/// Reports the [`NodeHealth`] of the node.
+pub trait HealthCheck: Send + Sync + 'static {
+    /// Returns the current state of the node.
+    fn health(&self) -> NodeHealth;
+}
+
+/// A [`HealthCheck`] of the provider and network of the node.
+#[derive(Debug, Clone)]
+pub struct NodeHealthCheck<Provider, Network> {
+    provider: Provider,
+    network: Network,
+}
+
+impl<Provider, Network> NodeHealthCheck<Provider, Network> {
+    /// Creates a new health check of the given provider and network.
+    pub const fn new(provider: Provider, network: Network) -> Self {
+        Self { provider, network }
+    }
+}
+
+impl<Provider, Network> HealthCheck for NodeHealthCheck<Provider, Network>
+where
+    Provider: BlockNumReader + HeaderProvider + 'static,
+    Network: NetworkInfo + PeersInfo + 'static,
+{
+    fn health(&self) -> NodeHealth {
+        let latest_block = self.provider.best_block_number().and_then(|number| {
+            self.provider.header_by_number(number).map(|header| (number, header))
+        });
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        NodeHealth {
+            syncing: self.network.is_syncing(),
+            latest_block: latest_block.as_ref().ok().map(|(number, _)| *number),
+            latest_block_age: latest_block
+                .as_ref()
+                .ok()
+                .and_then(|(_, header)| header.as_ref())
+                .map(|header| now.saturating_sub(header.timestamp)),
+            peers: self.network.num_connected_peers(),
+            database: latest_block.is_ok(),
+        }
+    }
+}
+
+/// A [`Layer`] for http requests that answers `GET` requests of the [`HEALTH_PATH`] and
+/// [`READY_PATH`] with the [`NodeHealth`] as JSON, without a JSON-RPC call.
+///
+/// The endpoints respond with `200 OK` if the node is alive or ready respectively and with
+/// `503 Service Unavailable` otherwise. All other requests are passed on.
+#[derive(Clone)]
+pub struct HealthLayer {
+    check: Arc<dyn HealthCheck>,
+    config: Arc<HealthConfig>,
+}
+
+impl HealthLayer {
+    /// Creates a new layer reporting the health of the given check, ready according to the
+    /// config.
+    pub fn new(check: impl HealthCheck, config: HealthConfig) -> Self {
+        Self { check: Arc::new(check), config: Arc::new(config) }
+    }
+}
+
+impl fmt::Debug for HealthLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HealthLayer").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl<S> Layer<S> for HealthLayer {
+    type Service = Health<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Health { inner, layer: self.clone() }
+    }
+}
+
+/// A http middleware that answers the requests of the health endpoints.
+///
+/// See [`HealthLayer`].
+#[derive(Debug, Clone)]
+pub struct Health<S> {
+    inner: S,
+    layer: HealthLayer,
+}
+
+impl<S> Health<S> {
+    /// Returns the response of the health endpoint of the path, if any.
+    fn respond(&self, path: &str) -> Option<HttpResponse> {
+        let health = match path {
+            HEALTH_PATH | READY_PATH => self.layer.check.health(),
+            _ => return None,
+        };
+        let ok =
+            if path == READY_PATH { health.is_ready(&self.layer.config) } else { health.database };
+        let response = http::Response::builder()
+            .status(if ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE })
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(HttpBody::from(serde_json::to_string(&health).expect("serializable")))
+            .expect("response is valid");
+        Some(response)
+    }
+}
+
+impl<S, B> Service<http::Request<B>> for Health<S>
+where
+    S: Service<http::Request<B>, Response = HttpResponse>,
+{
+    type Response = HttpResponse;
+    type Error = S::Error;
+    type Future = HealthFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if req.method() == Method::GET {
+            if let Some(response) = self.respond(req.uri().path()) {
+                return HealthFuture { kind: Kind::Health(Some(response)) }
+            }
+        }
+        HealthFuture { kind: Kind::Future(self.inner.call(req)) }
+    }
+}
+
+/// Response future of [`Health`].
+#[pin_project]
+pub struct HealthFuture<F> {
+    #[pin]
+    kind: Kind<F>,
+}
+
+impl<F> fmt::Debug for HealthFuture<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HealthFuture").finish_non_exhaustive()
+    }
+}
+
+#[pin_project(project = KindProj)]
+enum Kind<F> {
+    /// The request is processed by the inner service.
+    Future(#[pin] F),
+    /// The request was answered by a health endpoint.
+    Health(Option<HttpResponse>),
+}
+
+impl<F, E> Future for HealthFuture<F>
+where
+    F: Future<Output = Result<HttpResponse, E>>,
+{
+    type Output = Result<HttpResponse, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::Future(future) => future.poll(cx),
+            KindProj::Health(response) => {
+                Poll::Ready(Ok(response.take().expect("polled after completion")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn readiness() {
+        let health = NodeHealth {
+            syncing: false,
+            latest_block: Some(10),
+            latest_block_age: Some(30),
+            peers: 2,
+            database: true,
+        };
+        assert!(health.is_ready(&HealthConfig::default()));
+        assert!(health.is_ready(&HealthConfig {
+            min_peers: 2,
+            max_block_age: Some(Duration::from_secs(30)),
+        }));
+        assert!(!health.is_ready(&HealthConfig { min_peers: 3, max_block_age: None }));
+        assert!(!health.is_ready(&HealthConfig {
+            min_peers: 0,
+            max_block_age: Some(Duration::from_secs(29)),
+        }));
+
+        assert!(!NodeHealth { syncing: true, ..health.clone() }.is_ready(&HealthConfig::default()));
+        assert!(!NodeHealth { database: false, latest_block_age: None, ..health }
+            .is_ready(&HealthConfig::default()));
+    }
+}
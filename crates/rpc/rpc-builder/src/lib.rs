@@ -21,7 +21,7 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{
 //!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     ChangeSetReader, EvmEnvProvider, PruneCheckpointReader, StateProviderFactory,
 //! };
 //! use reth_rpc_builder::{
 //!     RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
@@ -39,6 +39,7 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + PruneCheckpointReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -82,7 +83,7 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{
 //!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     ChangeSetReader, EvmEnvProvider, PruneCheckpointReader, StateProviderFactory,
 //! };
 //! use reth_rpc_api::EngineApiServer;
 //! use reth_rpc_builder::{
@@ -105,6 +106,7 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + PruneCheckpointReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -160,22 +162,30 @@ use crate::{
     cors::CorsDomainError,
     error::WsHttpSamePortError,
     eth::{EthHandlersBuilder, EthHandlersConfig},
-    metrics::RpcRequestMetrics,
+    proxy::{read_proxy_protocol_header, PROXY_PROTOCOL_HEADER_TIMEOUT},
+};
+use error::{ConflictingModules, GracefulShutdownError, RpcError, ServerKind};
+use http::{
+    header::{InvalidHeaderName, AUTHORIZATION},
+    HeaderMap, HeaderName,
 };
-use error::{ConflictingModules, RpcError, ServerKind};
-use http::{header::AUTHORIZATION, HeaderMap};
 use jsonrpsee::{
     core::RegisterMethodError,
-    server::{AlreadyStoppedError, IdProvider, RpcServiceBuilder, Server, ServerHandle},
+    server::{
+        middleware::rpc::either::Either, serve_with_graceful_shutdown, stop_channel,
+        AlreadyStoppedError, BatchRequestConfig, HttpBody, IdProvider, RpcServiceBuilder,
+        ServerHandle, StopHandle, TowerServiceBuilder,
+    },
     Methods, RpcModule,
 };
+use reth_config::{IpRateLimitConfig, ProxyConfig, RateQuota};
 use reth_engine_primitives::EngineTypes;
 use reth_evm::ConfigureEvm;
 use reth_ipc::server::IpcServer;
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_provider::{
     AccountReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+    ChangeSetReader, EvmEnvProvider, PruneCheckpointReader, StateProviderFactory,
 };
 use reth_rpc::{
     eth::{EthApi, EthBundle, RawTransactionForwarder},
@@ -192,18 +202,22 @@ use std::{
     collections::HashMap,
     fmt,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tower_http::cors::CorsLayer;
-use tracing::{instrument, trace};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use tower_http::{add_extension::AddExtension, cors::CorsLayer};
+use tracing::{debug, instrument, trace};
 
 // re-export for convenience
 pub use jsonrpsee::server::ServerBuilder;
 pub use reth_ipc::server::{
     Builder as IpcServerBuilder, RpcServiceBuilder as IpcRpcServiceBuilder,
+    SocketPermissions as IpcSocketPermissions,
 };
-pub use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
+pub use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection, RpcModuleToggle};
 pub use tower::layer::util::{Identity, Stack};
 
 /// Auth server utilities.
@@ -212,8 +226,27 @@ pub mod auth;
 /// RPC server utilities.
 pub mod config;
 
+/// Rpc client authentication with API keys.
+pub mod api_key;
+pub use api_key::{
+    ApiKey, ApiKeyAllowlist, ApiKeyAllowlistLayer, ApiKeyAuth, ApiKeyAuthFuture, ApiKeyAuthLayer,
+    ApiKeys,
+};
+
+/// Rpc batch request limits.
+pub mod batch;
+pub use batch::{
+    BatchCost, BatchCostBudget, BatchCostExceeded, BatchCostLimit, BatchCostLimitLayer,
+    BatchCostTracker, BatchCostTrackerLayer,
+};
+
+/// Http response compression.
+pub mod compression;
+pub use compression::{HttpCompression, HttpCompressionFuture, HttpCompressionLayer};
+
 /// Cors utilities.
-mod cors;
+pub mod cors;
+pub use cors::CorsConfig;
 
 /// Rpc error utilities.
 pub mod error;
@@ -224,6 +257,50 @@ pub use eth::{EthConfig, EthHandlers};
 
 // Rpc server metrics
 mod metrics;
+pub use metrics::{MeteredRequestFuture, RpcRequestMetrics, RpcRequestMetricsService};
+
+/// Rpc request rate limiting.
+pub mod rate_limit;
+pub use rate_limit::{
+    IpRateLimit, IpRateLimitLayer, MethodRateLimit, MethodRateLimitLayer, RateLimit, RateLimitLayer,
+};
+
+/// Custom http middleware of the http and ws servers.
+pub mod middleware;
+pub use middleware::{BoxedHttpService, HttpMiddlewareLayer};
+
+/// Reverse proxy support.
+pub mod proxy;
+pub use proxy::{ClientIp, ProxyClientIp, ProxyClientIpLayer, TrustedProxies};
+
+/// Health endpoints of the http server.
+pub mod health;
+pub use health::{HealthCheck, HealthLayer, NodeHealth, NodeHealthCheck};
+
+/// Rpc request timeouts.
+pub mod timeout;
+pub use timeout::{RequestTimeout, RequestTimeoutLayer, RequestTimeouts};
+
+/// Rpc request logging.
+pub mod logger;
+pub use logger::{RpcRequestLogger, RpcRequestLoggerLayer};
+
+/// Rpc response caching.
+pub mod response_cache;
+pub use response_cache::{CachedResponses, ResponseCache, ResponseCacheLayer};
+
+/// Runtime toggling of rpc namespaces.
+pub mod toggle;
+pub use toggle::{RpcModuleToggleLayer, RpcModuleToggleService};
+
+/// Limits of the active subscriptions of the ws and ipc servers.
+pub mod subscriptions;
+pub use subscriptions::{SubscriptionBudget, SubscriptionLimit, SubscriptionLimitLayer};
+
+/// TLS termination of the http and ws servers.
+pub mod tls;
+use tls::TlsAcceptor;
+pub use tls::{TlsConfig, DEFAULT_TLS_RELOAD_INTERVAL};
 
 /// Convenience function for starting a server in one step.
 #[allow(clippy::too_many_arguments)]
@@ -244,6 +321,7 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static,
@@ -435,6 +513,7 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static,
@@ -523,7 +602,7 @@ where
         let Self { provider, pool, network, executor, events, evm_config } = self;
 
         if !module_config.is_empty() {
-            let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
+            let TransportRpcModuleConfig { http, ws, ipc, config, .. } = module_config.clone();
 
             let mut registry = RethModuleRegistry::new(
                 provider,
@@ -760,6 +839,7 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static,
@@ -1114,22 +1194,64 @@ where
 pub struct RpcServerConfig {
     /// Configs for JSON-RPC Http.
     http_server_config: Option<ServerBuilder<Identity, Identity>>,
-    /// Allowed CORS Domains for http
-    http_cors_domains: Option<String>,
+    /// CORS config of the http server
+    http_cors: Option<CorsConfig>,
+    /// Minimum size of the http responses to compress, if compression is enabled
+    http_compression: Option<u16>,
     /// Address where to bind the http server to
     http_addr: Option<SocketAddr>,
+    /// Addresses where to bind the http server to in addition to its main address
+    http_additional_addrs: Vec<RpcServerAddress>,
     /// Configs for WS server
     ws_server_config: Option<ServerBuilder<Identity, Identity>>,
-    /// Allowed CORS Domains for ws.
-    ws_cors_domains: Option<String>,
+    /// CORS config of the ws server, applied to its upgrade requests
+    ws_cors: Option<CorsConfig>,
     /// Address where to bind the ws server to
     ws_addr: Option<SocketAddr>,
+    /// Addresses where to bind the ws server to in addition to its main address
+    ws_additional_addrs: Vec<RpcServerAddress>,
     /// Configs for JSON-RPC IPC server
     ipc_server_config: Option<IpcServerBuilder<Identity, Identity>>,
     /// The Endpoint where to launch the ipc server
     ipc_endpoint: Option<String>,
+    /// Permissions of the socket file of the ipc server
+    ipc_socket_permissions: IpcSocketPermissions,
     /// JWT secret for authentication
     jwt_secret: Option<JwtSecret>,
+    /// Maximum number of concurrently processed requests of the http server
+    http_max_concurrent_requests: Option<usize>,
+    /// Maximum number of concurrently processed requests of the ws server
+    ws_max_concurrent_requests: Option<usize>,
+    /// Maximum number of concurrently processed requests of the ipc server
+    ipc_max_concurrent_requests: Option<usize>,
+    /// Rate limits of individual methods, shared by all servers
+    method_rate_limit: Option<MethodRateLimitLayer>,
+    /// Maximum number of active subscriptions, shared by all servers
+    subscription_budget: Option<SubscriptionBudget>,
+    /// Rate limit of individual clients, shared by the http and ws servers
+    ip_rate_limit: Option<IpRateLimitLayer>,
+    /// Reads the client address from the header of a trusted proxy, if configured
+    proxy_client_ip: Option<ProxyClientIpLayer>,
+    /// Proxies whose connections start with a PROXY protocol header
+    proxy_protocol: Option<TrustedProxies>,
+    /// API keys authenticating the clients of the http and ws servers
+    api_keys: Option<ApiKeyAuthLayer>,
+    /// Health endpoints of the http server
+    health: Option<HealthLayer>,
+    /// TLS config of the http and ws servers, if they terminate TLS themselves
+    tls: Option<TlsConfig>,
+    /// Cache of the responses of idempotent calls, shared by all servers
+    response_cache: Option<ResponseCacheLayer>,
+    /// Logs the calls of all servers
+    request_logger: Option<RpcRequestLoggerLayer>,
+    /// Whether the servers don't record metrics of their connections and calls
+    disable_metrics: bool,
+    /// Maximum number of calls of a batch request, shared by all servers
+    max_batch_size: Option<u32>,
+    /// Cost budget of the batch requests of the http server
+    batch_cost_limit: Option<BatchCostLimitLayer>,
+    /// Custom layers of the http requests of the http and ws servers
+    http_middleware: HttpMiddlewareLayer,
 }
 
 // === impl RpcServerConfig ===
@@ -1160,20 +1282,29 @@ impl RpcServerConfig {
         self
     }
 
-    /// Configure the cors domains for http _and_ ws
-    pub fn with_cors(self, cors_domain: Option<String>) -> Self {
-        self.with_http_cors(cors_domain.clone()).with_ws_cors(cors_domain)
+    /// Configure the CORS config of http _and_ ws
+    pub fn with_cors(self, cors: Option<CorsConfig>) -> Self {
+        self.with_http_cors(cors.clone()).with_ws_cors(cors)
     }
 
-    /// Configure the cors domains for HTTP
-    pub fn with_http_cors(mut self, cors_domain: Option<String>) -> Self {
-        self.http_cors_domains = cors_domain;
+    /// Configure the CORS config of HTTP
+    pub fn with_http_cors(mut self, cors: Option<CorsConfig>) -> Self {
+        self.http_cors = cors;
         self
     }
 
-    /// Configure the cors domains for WS
-    pub fn with_ws_cors(mut self, cors_domain: Option<String>) -> Self {
-        self.ws_cors_domains = cors_domain;
+    /// Configures the http server to compress responses of at least `min_size` bytes with gzip or
+    /// brotli, depending on the `Accept-Encoding` header of the request.
+    ///
+    /// Compression is disabled by default.
+    pub const fn with_http_compression(mut self, min_size: Option<u16>) -> Self {
+        self.http_compression = min_size;
+        self
+    }
+
+    /// Configure the CORS config of WS, which applies to the upgrade requests of its connections
+    pub fn with_ws_cors(mut self, cors: Option<CorsConfig>) -> Self {
+        self.ws_cors = cors;
         self
     }
 
@@ -1195,6 +1326,31 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures all [`SocketAddr`]s of the http server, e.g. `127.0.0.1:8545` and `[::1]:8545`
+    /// for a dual-stack server.
+    ///
+    /// The first address is the main address of the server, see [`Self::with_http_address`], and
+    /// all modules of the server are served on every address.
+    pub fn with_http_addresses(mut self, addrs: impl IntoIterator<Item = SocketAddr>) -> Self {
+        let mut addrs = addrs.into_iter();
+        self.http_addr = addrs.next();
+        self.http_additional_addrs = addrs.map(RpcServerAddress::new).collect();
+        self
+    }
+
+    /// Binds the http server to the address in addition to its main address.
+    ///
+    /// Only the methods of the given modules are served on the address, if set. Modules that are
+    /// not configured for the http server are not served.
+    pub fn with_additional_http_address(
+        mut self,
+        addr: SocketAddr,
+        modules: Option<RpcModuleSelection>,
+    ) -> Self {
+        self.http_additional_addrs.push(RpcServerAddress { addr, modules });
+        self
+    }
+
     /// Configures the [`SocketAddr`] of the ws server
     ///
     /// Default is [`Ipv4Addr::LOCALHOST`] and
@@ -1204,6 +1360,31 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures all [`SocketAddr`]s of the ws server, e.g. `127.0.0.1:8546` and `[::1]:8546` for
+    /// a dual-stack server.
+    ///
+    /// The first address is the main address of the server, see [`Self::with_ws_address`], and
+    /// all modules of the server are served on every address.
+    pub fn with_ws_addresses(mut self, addrs: impl IntoIterator<Item = SocketAddr>) -> Self {
+        let mut addrs = addrs.into_iter();
+        self.ws_addr = addrs.next();
+        self.ws_additional_addrs = addrs.map(RpcServerAddress::new).collect();
+        self
+    }
+
+    /// Binds the ws server to the address in addition to its main address.
+    ///
+    /// Only the methods of the given modules are served on the address, if set. Modules that are
+    /// not configured for the ws server are not served.
+    pub fn with_additional_ws_address(
+        mut self,
+        addr: SocketAddr,
+        modules: Option<RpcModuleSelection>,
+    ) -> Self {
+        self.ws_additional_addrs.push(RpcServerAddress { addr, modules });
+        self
+    }
+
     /// Configures the ipc server
     ///
     /// Note: this always configures an [`EthSubscriptionIdProvider`] [`IdProvider`] for
@@ -1241,12 +1422,225 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the mode and owner of the socket file of the ipc server.
+    ///
+    /// On startup, the socket file of a previous server that was not shut down gracefully is
+    /// removed. On Linux, endpoints prefixed with `@` are sockets in the abstract namespace, which
+    /// have no socket file and therefore no permissions.
+    pub const fn with_ipc_socket_permissions(mut self, permissions: IpcSocketPermissions) -> Self {
+        self.ipc_socket_permissions = permissions;
+        self
+    }
+
     /// Configures the JWT secret for authentication.
     pub const fn with_jwt_secret(mut self, secret: Option<JwtSecret>) -> Self {
         self.jwt_secret = secret;
         self
     }
 
+    /// Configures the maximum number of concurrently processed requests for http _and_ ws
+    ///
+    /// See also [`RateLimitLayer`].
+    pub const fn with_max_concurrent_requests(
+        self,
+        max_concurrent_requests: Option<usize>,
+    ) -> Self {
+        self.with_http_max_concurrent_requests(max_concurrent_requests)
+            .with_ws_max_concurrent_requests(max_concurrent_requests)
+    }
+
+    /// Configures the maximum number of concurrently processed requests of the http server.
+    ///
+    /// Requests beyond the limit wait until a request in flight has been processed. If http and ws
+    /// are served on the same port, this limit applies to both.
+    ///
+    /// Default is unlimited.
+    pub const fn with_http_max_concurrent_requests(
+        mut self,
+        max_concurrent_requests: Option<usize>,
+    ) -> Self {
+        self.http_max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Configures the maximum number of concurrently processed requests of the ws server.
+    ///
+    /// Requests beyond the limit wait until a request in flight has been processed.
+    ///
+    /// Default is unlimited.
+    pub const fn with_ws_max_concurrent_requests(
+        mut self,
+        max_concurrent_requests: Option<usize>,
+    ) -> Self {
+        self.ws_max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Configures the maximum number of concurrently processed requests of the ipc server.
+    ///
+    /// Requests beyond the limit wait until a request in flight has been processed.
+    ///
+    /// Default is unlimited.
+    pub const fn with_ipc_max_concurrent_requests(
+        mut self,
+        max_concurrent_requests: Option<usize>,
+    ) -> Self {
+        self.ipc_max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Configures the rate limits of individual methods, keyed by method name.
+    ///
+    /// Calls of a method that exceed its quota are rejected. The quotas are shared by all servers,
+    /// methods without a quota are not limited.
+    ///
+    /// See also [`MethodRateLimitLayer`].
+    pub fn with_method_rate_limits(
+        mut self,
+        quotas: impl IntoIterator<Item = (String, RateQuota)>,
+    ) -> Self {
+        let layer = MethodRateLimitLayer::new(quotas);
+        self.method_rate_limit = (!layer.is_empty()).then_some(layer);
+        self
+    }
+
+    /// Configures the maximum number of subscriptions that are active at the same time across all
+    /// connections of the ws and ipc servers.
+    ///
+    /// This complements the limit of subscriptions per connection of the server builders.
+    /// Subscriptions that exceed the budget are rejected.
+    ///
+    /// See also [`SubscriptionLimitLayer`].
+    pub fn with_max_subscriptions(mut self, max_subscriptions: Option<usize>) -> Self {
+        self.subscription_budget = max_subscriptions.map(SubscriptionBudget::new);
+        self
+    }
+
+    /// Configures the rate limit of individual clients of the http and ws servers, identified by
+    /// their IP address.
+    ///
+    /// Requests of clients that exceed their quota are rejected, and clients that keep exceeding
+    /// it are banned temporarily. The client address is the address of the connection, or the one
+    /// sent by a trusted proxy configured with [`RpcServerConfig::with_proxy`].
+    ///
+    /// See also [`IpRateLimitLayer`].
+    pub fn with_ip_rate_limit(mut self, config: &IpRateLimitConfig) -> Self {
+        self.ip_rate_limit = Some(IpRateLimitLayer::new(config));
+        self
+    }
+
+    /// Configures the trusted reverse proxies of the http and ws servers.
+    ///
+    /// The [`ClientIp`] of requests made by a trusted proxy is the client address the proxy sent,
+    /// either in the configured header, e.g. `X-Forwarded-For`, or in a PROXY protocol v2 header
+    /// at the start of the connection if enabled. Connections of trusted proxies without a valid
+    /// PROXY protocol header are closed.
+    ///
+    /// Returns an error if the header is not a valid header name.
+    ///
+    /// See also [`ProxyClientIpLayer`].
+    pub fn with_proxy(mut self, config: &ProxyConfig) -> Result<Self, InvalidHeaderName> {
+        let trusted_proxies = TrustedProxies::new(config.trusted_proxies.iter().copied());
+        self.proxy_client_ip = Some(
+            ProxyClientIpLayer::new(HeaderName::try_from(config.header.as_str())?)
+                .with_trusted_proxies(trusted_proxies.clone()),
+        );
+        self.proxy_protocol = config.proxy_protocol.then_some(trusted_proxies);
+        Ok(self)
+    }
+
+    /// Configures API keys that authenticate the clients of the http and ws servers.
+    ///
+    /// Requests without a valid key are rejected, and calls of methods that the key of the client
+    /// is not allowed to call fail. Clients are not authenticated if there are no keys.
+    ///
+    /// See also [`ApiKeyAuthLayer`] and [`ApiKeyAllowlistLayer`].
+    pub fn with_api_keys(mut self, keys: ApiKeys) -> Self {
+        self.api_keys = (!keys.is_empty()).then(|| ApiKeyAuthLayer::new(keys));
+        self
+    }
+
+    /// Configures the `/health` and `/ready` endpoints of the http server, which report the
+    /// state of the node without a JSON-RPC call.
+    ///
+    /// See also [`HealthLayer`].
+    pub fn with_health(mut self, health: HealthLayer) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Configures the http and ws servers to only accept TLS connections, with the certificate
+    /// chain and private key of the given PEM files.
+    ///
+    /// The files are reloaded when they change, see [`TlsConfig`].
+    pub fn with_tls(self, cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        self.with_tls_config(TlsConfig::new(cert_path, key_path))
+    }
+
+    /// Configures the TLS config of the http and ws servers.
+    ///
+    /// See also [`RpcServerConfig::with_tls`].
+    pub fn with_tls_config(mut self, config: TlsConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Configures custom tower layers of the http requests of the http and ws servers, e.g. for
+    /// authentication, rate limiting or metrics.
+    ///
+    /// The layers are applied after the builtin http middleware, see [`HttpMiddlewareLayer`].
+    pub fn with_http_middleware(mut self, middleware: HttpMiddlewareLayer) -> Self {
+        self.http_middleware = middleware;
+        self
+    }
+
+    /// Configures a cache of the responses of idempotent calls, shared by all servers.
+    ///
+    /// The cache must be invalidated on canonical state changes, see
+    /// [`ResponseCache::invalidate_on`].
+    pub fn with_response_cache(mut self, cache: ResponseCache) -> Self {
+        self.response_cache = Some(ResponseCacheLayer::new(cache));
+        self
+    }
+
+    /// Configures whether the servers record metrics of their connections and calls, enabled by
+    /// default.
+    ///
+    /// The call count, error count and latency histogram of every method are labeled with the
+    /// method, see also [`RpcRequestMetrics`].
+    pub const fn with_metrics(mut self, enabled: bool) -> Self {
+        self.disable_metrics = !enabled;
+        self
+    }
+
+    /// Configures logging of the calls of all servers.
+    ///
+    /// See also [`RpcRequestLoggerLayer`].
+    pub fn with_request_logger(mut self, logger: RpcRequestLoggerLayer) -> Self {
+        self.request_logger = Some(logger);
+        self
+    }
+
+    /// Configures the maximum number of calls of a batch request of all servers.
+    ///
+    /// Batch requests with more calls are rejected as a whole. Default is unlimited.
+    pub const fn with_max_batch_size(mut self, max_batch_size: Option<u32>) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Configures the cumulative cost budget of the calls of a batch request of the http server.
+    ///
+    /// Calls that exceed the budget are rejected with an error identifying the call, and so are
+    /// all remaining calls of the batch. Websocket and ipc batches are only limited by
+    /// [`RpcServerConfig::with_max_batch_size`].
+    ///
+    /// See also [`BatchCostLimitLayer`].
+    pub fn with_batch_cost_budget(mut self, budget: Option<BatchCostBudget>) -> Self {
+        self.batch_cost_limit = budget.map(BatchCostLimitLayer::new);
+        self
+    }
+
     /// Returns true if any server is configured.
     ///
     /// If no server is configured, no server will be launched on [`RpcServerConfig::start`].
@@ -1266,6 +1660,16 @@ impl RpcServerConfig {
         self.ws_addr
     }
 
+    /// Returns the addresses of the http server in addition to its main address
+    pub fn http_additional_addresses(&self) -> &[RpcServerAddress] {
+        &self.http_additional_addrs
+    }
+
+    /// Returns the addresses of the ws server in addition to its main address
+    pub fn ws_additional_addresses(&self) -> &[RpcServerAddress] {
+        &self.ws_additional_addrs
+    }
+
     /// Returns the endpoint of the ipc server
     pub fn ipc_endpoint(&self) -> Option<String> {
         self.ipc_endpoint.clone()
@@ -1277,8 +1681,18 @@ impl RpcServerConfig {
     }
 
     /// Creates the [`CorsLayer`] if any
-    fn maybe_cors_layer(cors: Option<String>) -> Result<Option<CorsLayer>, CorsDomainError> {
-        cors.as_deref().map(cors::create_cors_layer).transpose()
+    fn maybe_cors_layer(cors: Option<&CorsConfig>) -> Result<Option<CorsLayer>, CorsDomainError> {
+        cors.map(CorsConfig::layer).transpose()
+    }
+
+    /// Creates the [`HttpCompressionLayer`] of the http server if compression is enabled
+    fn maybe_compression_layer(&self) -> Option<HttpCompressionLayer> {
+        self.http_compression.map(HttpCompressionLayer::new)
+    }
+
+    /// Returns the [`HttpMiddlewareLayer`] if any custom layers are configured
+    fn maybe_http_middleware(&self) -> Option<HttpMiddlewareLayer> {
+        (!self.http_middleware.is_empty()).then(|| self.http_middleware.clone())
     }
 
     /// Creates the [`AuthLayer`] if any
@@ -1286,6 +1700,50 @@ impl RpcServerConfig {
         self.jwt_secret.map(|secret| AuthLayer::new(JwtAuthValidator::new(secret)))
     }
 
+    /// Creates the [`ApiKeyAllowlistLayer`] if API keys are configured
+    fn maybe_api_key_allowlist_layer(&self) -> Option<ApiKeyAllowlistLayer> {
+        self.api_keys.as_ref().map(|_| ApiKeyAllowlistLayer::new())
+    }
+
+    /// Returns the [`ProxyClientIpLayer`] if a trusted proxy header is configured
+    fn maybe_proxy_client_ip_layer(&self) -> Option<ProxyClientIpLayer> {
+        self.proxy_client_ip.clone()
+    }
+
+    /// Creates the [`RpcRequestMetrics`] of the module if metrics are enabled
+    fn maybe_metrics_layer(
+        &self,
+        module: Option<&RpcModule<()>>,
+        metrics: fn(&RpcModule<()>) -> RpcRequestMetrics,
+    ) -> Option<RpcRequestMetrics> {
+        (!self.disable_metrics).then(|| module.map(metrics).unwrap_or_default())
+    }
+
+    /// Creates the [`SubscriptionLimitLayer`] for the subscriptions of the given module if a
+    /// budget is configured
+    fn maybe_subscription_limit_layer(
+        &self,
+        module: Option<&RpcModule<()>>,
+    ) -> Option<SubscriptionLimitLayer> {
+        let budget = self.subscription_budget.clone()?;
+        Some(SubscriptionLimitLayer::new(budget, module?))
+    }
+
+    /// Returns the [`BatchRequestConfig`] of the servers
+    fn batch_request_config(&self) -> BatchRequestConfig {
+        self.max_batch_size.map_or(BatchRequestConfig::Unlimited, BatchRequestConfig::Limit)
+    }
+
+    /// Creates the [`BatchCostTrackerLayer`] if a batch cost budget is configured
+    fn maybe_batch_cost_tracker_layer(&self) -> Option<BatchCostTrackerLayer> {
+        self.batch_cost_limit.as_ref().map(|_| BatchCostTrackerLayer)
+    }
+
+    /// Creates the [`RateLimitLayer`] if a limit is configured
+    fn maybe_rate_limit_layer(max_concurrent_requests: Option<usize>) -> Option<RateLimitLayer> {
+        max_concurrent_requests.map(RateLimitLayer::new)
+    }
+
     /// Builds the ws and http server(s).
     ///
     /// If both are on the same port, they are combined into one server.
@@ -1303,14 +1761,17 @@ impl RpcServerConfig {
             constants::DEFAULT_WS_RPC_PORT,
         )));
 
+        let tls = self.tls.as_ref().map(TlsAcceptor::new).transpose().map_err(RpcError::Tls)?;
+        let toggle = modules.module_toggle_layer();
+
         // If both are configured on the same port, we combine them into one server.
         if self.http_addr == self.ws_addr &&
             self.http_server_config.is_some() &&
             self.ws_server_config.is_some()
         {
-            let cors = match (self.ws_cors_domains.as_ref(), self.http_cors_domains.as_ref()) {
+            let cors = match (self.ws_cors.as_ref(), self.http_cors.as_ref()) {
                 (Some(ws_cors), Some(http_cors)) => {
-                    if ws_cors.trim() != http_cors.trim() {
+                    if ws_cors != http_cors {
                         return Err(WsHttpSamePortError::ConflictingCorsDomains {
                             http_cors_domains: Some(http_cors.clone()),
                             ws_cors_domains: Some(ws_cors.clone()),
@@ -1320,99 +1781,192 @@ impl RpcServerConfig {
                     Some(ws_cors)
                 }
                 (a, b) => a.or(b),
-            }
-            .cloned();
+            };
 
             // we merge this into one server using the http setup
             self.ws_server_config.take();
 
             modules.config.ensure_ws_http_identical()?;
 
-            let builder = self.http_server_config.take().expect("http_server_config is Some");
-            let server = builder
+            let builder = self
+                .http_server_config
+                .take()
+                .expect("http_server_config is Some")
+                .set_batch_request_config(self.batch_request_config())
                 .set_http_middleware(
                     tower::ServiceBuilder::new()
+                        .option_layer(self.maybe_compression_layer())
+                        .option_layer(self.maybe_proxy_client_ip_layer())
+                        .option_layer(self.health.clone())
                         .option_layer(Self::maybe_cors_layer(cors)?)
-                        .option_layer(self.maybe_jwt_layer()),
+                        .option_layer(self.maybe_jwt_layer())
+                        .option_layer(self.api_keys.clone())
+                        .option_layer(self.maybe_batch_cost_tracker_layer())
+                        .option_layer(self.maybe_http_middleware()),
                 )
                 .set_rpc_middleware(
-                    RpcServiceBuilder::new().layer(
-                        modules
-                            .http
-                            .as_ref()
-                            .or(modules.ws.as_ref())
-                            .map(RpcRequestMetrics::same_port)
-                            .unwrap_or_default(),
-                    ),
-                )
-                .build(http_socket_addr)
-                .await
-                .map_err(|err| RpcError::server_error(err, ServerKind::WsHttp(http_socket_addr)))?;
-            let addr = server
-                .local_addr()
+                    RpcServiceBuilder::new()
+                        .option_layer(self.request_logger.clone())
+                        .option_layer(self.maybe_metrics_layer(
+                            modules.http.as_ref().or(modules.ws.as_ref()),
+                            RpcRequestMetrics::same_port,
+                        ))
+                        .layer(toggle.clone())
+                        .option_layer(self.method_rate_limit.clone())
+                        .option_layer(self.maybe_subscription_limit_layer(
+                            modules.http.as_ref().or(modules.ws.as_ref()),
+                        ))
+                        .option_layer(Self::maybe_rate_limit_layer(
+                            self.http_max_concurrent_requests.or(self.ws_max_concurrent_requests),
+                        ))
+                        .option_layer(modules.config.maybe_request_timeout_layer())
+                        .option_layer(self.ip_rate_limit.clone())
+                        .option_layer(self.batch_cost_limit.clone())
+                        .option_layer(self.maybe_api_key_allowlist_layer())
+                        .option_layer(self.response_cache.clone()),
+                );
+            // the combined server is bound to the additional addresses of both
+            let mut additional_addrs = std::mem::take(&mut self.http_additional_addrs);
+            for addr in std::mem::take(&mut self.ws_additional_addrs) {
+                if !additional_addrs.iter().any(|other| other.addr == addr.addr) {
+                    additional_addrs.push(addr);
+                }
+            }
+            let server = WsHttpServerKind::bind(
+                builder,
+                http_socket_addr,
+                additional_addrs,
+                ServerKind::WsHttp,
+                tls.clone(),
+                self.proxy_protocol.clone(),
+            )
+            .await?;
+            let (addr, additional_addrs) = server
+                .local_addrs()
                 .map_err(|err| RpcError::server_error(err, ServerKind::WsHttp(http_socket_addr)))?;
             return Ok(WsHttpServer {
                 http_local_addr: Some(addr),
+                http_additional_local_addrs: additional_addrs.clone(),
                 ws_local_addr: Some(addr),
+                ws_additional_local_addrs: additional_addrs,
                 server: WsHttpServers::SamePort(server),
                 jwt_secret: self.jwt_secret,
             })
         }
 
         let mut http_local_addr = None;
+        let mut http_additional_local_addrs = Vec::new();
         let mut http_server = None;
 
         let mut ws_local_addr = None;
+        let mut ws_additional_local_addrs = Vec::new();
         let mut ws_server = None;
         if let Some(builder) = self.ws_server_config.take() {
-            let server = builder
+            let builder = builder
                 .ws_only()
+                .set_batch_request_config(self.batch_request_config())
                 .set_http_middleware(
                     tower::ServiceBuilder::new()
-                        .option_layer(Self::maybe_cors_layer(self.ws_cors_domains.clone())?)
-                        .option_layer(self.maybe_jwt_layer()),
+                        // only http responses are compressed
+                        .option_layer(None::<HttpCompressionLayer>)
+                        .option_layer(self.maybe_proxy_client_ip_layer())
+                        // the health endpoints are served by the http server
+                        .option_layer(None::<HealthLayer>)
+                        .option_layer(Self::maybe_cors_layer(self.ws_cors.as_ref())?)
+                        .option_layer(self.maybe_jwt_layer())
+                        .option_layer(self.api_keys.clone())
+                        // only the costs of http batches are limited
+                        .option_layer(None::<BatchCostTrackerLayer>)
+                        .option_layer(self.maybe_http_middleware()),
                 )
                 .set_rpc_middleware(
                     RpcServiceBuilder::new()
-                        .layer(modules.ws.as_ref().map(RpcRequestMetrics::ws).unwrap_or_default()),
-                )
-                .build(ws_socket_addr)
-                .await
-                .map_err(|err| RpcError::server_error(err, ServerKind::WS(ws_socket_addr)))?;
-            let addr = server
-                .local_addr()
+                        .option_layer(self.request_logger.clone())
+                        .option_layer(
+                            self.maybe_metrics_layer(modules.ws.as_ref(), RpcRequestMetrics::ws),
+                        )
+                        .layer(toggle.clone())
+                        .option_layer(self.method_rate_limit.clone())
+                        .option_layer(self.maybe_subscription_limit_layer(modules.ws.as_ref()))
+                        .option_layer(Self::maybe_rate_limit_layer(self.ws_max_concurrent_requests))
+                        .option_layer(modules.config.maybe_request_timeout_layer())
+                        .option_layer(self.ip_rate_limit.clone())
+                        .option_layer(self.batch_cost_limit.clone())
+                        .option_layer(self.maybe_api_key_allowlist_layer())
+                        .option_layer(self.response_cache.clone()),
+                );
+            let server = WsHttpServerKind::bind(
+                builder,
+                ws_socket_addr,
+                std::mem::take(&mut self.ws_additional_addrs),
+                ServerKind::WS,
+                tls.clone(),
+                self.proxy_protocol.clone(),
+            )
+            .await?;
+            let (addr, additional_addrs) = server
+                .local_addrs()
                 .map_err(|err| RpcError::server_error(err, ServerKind::WS(ws_socket_addr)))?;
 
             ws_local_addr = Some(addr);
+            ws_additional_local_addrs = additional_addrs;
             ws_server = Some(server);
         }
 
         if let Some(builder) = self.http_server_config.take() {
-            let server = builder
+            let metrics = self.maybe_metrics_layer(modules.http.as_ref(), RpcRequestMetrics::http);
+            let builder = builder
                 .http_only()
+                .set_batch_request_config(self.batch_request_config())
                 .set_http_middleware(
                     tower::ServiceBuilder::new()
-                        .option_layer(Self::maybe_cors_layer(self.http_cors_domains.clone())?)
-                        .option_layer(self.maybe_jwt_layer()),
+                        .option_layer(self.maybe_compression_layer())
+                        .option_layer(self.maybe_proxy_client_ip_layer())
+                        .option_layer(self.health.clone())
+                        .option_layer(Self::maybe_cors_layer(self.http_cors.as_ref())?)
+                        .option_layer(self.maybe_jwt_layer())
+                        .option_layer(self.api_keys.clone())
+                        .option_layer(self.maybe_batch_cost_tracker_layer())
+                        .option_layer(self.maybe_http_middleware()),
                 )
                 .set_rpc_middleware(
-                    RpcServiceBuilder::new().layer(
-                        modules.http.as_ref().map(RpcRequestMetrics::http).unwrap_or_default(),
-                    ),
-                )
-                .build(http_socket_addr)
-                .await
-                .map_err(|err| RpcError::server_error(err, ServerKind::Http(http_socket_addr)))?;
-            let local_addr = server
-                .local_addr()
+                    RpcServiceBuilder::new()
+                        .option_layer(self.request_logger.clone())
+                        .option_layer(metrics)
+                        .layer(toggle)
+                        .option_layer(self.method_rate_limit.clone())
+                        .option_layer(self.maybe_subscription_limit_layer(modules.http.as_ref()))
+                        .option_layer(Self::maybe_rate_limit_layer(
+                            self.http_max_concurrent_requests,
+                        ))
+                        .option_layer(modules.config.maybe_request_timeout_layer())
+                        .option_layer(self.ip_rate_limit.clone())
+                        .option_layer(self.batch_cost_limit.clone())
+                        .option_layer(self.maybe_api_key_allowlist_layer())
+                        .option_layer(self.response_cache.clone()),
+                );
+            let server = WsHttpServerKind::bind(
+                builder,
+                http_socket_addr,
+                std::mem::take(&mut self.http_additional_addrs),
+                ServerKind::Http,
+                tls.clone(),
+                self.proxy_protocol.clone(),
+            )
+            .await?;
+            let (local_addr, additional_addrs) = server
+                .local_addrs()
                 .map_err(|err| RpcError::server_error(err, ServerKind::Http(http_socket_addr)))?;
             http_local_addr = Some(local_addr);
+            http_additional_local_addrs = additional_addrs;
             http_server = Some(server);
         }
 
         Ok(WsHttpServer {
             http_local_addr,
+            http_additional_local_addrs,
             ws_local_addr,
+            ws_additional_local_addrs,
             server: WsHttpServers::DifferentPort { http: http_server, ws: ws_server },
             jwt_secret: self.jwt_secret,
         })
@@ -1428,12 +1982,28 @@ impl RpcServerConfig {
         let mut server = RpcServer::empty();
         server.ws_http = self.build_ws_http(modules).await?;
 
-        if let Some(builder) = self.ipc_server_config {
-            let metrics = modules.ipc.as_ref().map(RpcRequestMetrics::ipc).unwrap_or_default();
+        if let Some(builder) = self.ipc_server_config.take() {
+            let metrics = self.maybe_metrics_layer(modules.ipc.as_ref(), RpcRequestMetrics::ipc);
+            let subscription_limit = self.maybe_subscription_limit_layer(modules.ipc.as_ref());
+            let batch_request_config = self.batch_request_config();
             let ipc_path =
                 self.ipc_endpoint.unwrap_or_else(|| constants::DEFAULT_IPC_ENDPOINT.into());
             let ipc = builder
-                .set_rpc_middleware(IpcRpcServiceBuilder::new().layer(metrics))
+                .socket_permissions(self.ipc_socket_permissions)
+                .set_batch_request_config(batch_request_config)
+                .set_rpc_middleware(
+                    IpcRpcServiceBuilder::new()
+                        .option_layer(self.request_logger)
+                        .option_layer(metrics)
+                        .layer(modules.module_toggle_layer())
+                        .option_layer(self.method_rate_limit)
+                        .option_layer(subscription_limit)
+                        .option_layer(Self::maybe_rate_limit_layer(
+                            self.ipc_max_concurrent_requests,
+                        ))
+                        .option_layer(modules.config.maybe_request_timeout_layer())
+                        .option_layer(self.response_cache),
+                )
                 .build(ipc_path);
             server.ipc = Some(ipc);
         }
@@ -1463,6 +2033,8 @@ pub struct TransportRpcModuleConfig {
     ipc: Option<RpcModuleSelection>,
     /// Config for the modules
     config: Option<RpcModuleConfig>,
+    /// Deadlines of the calls of the modules
+    request_timeouts: RequestTimeouts,
 }
 
 // === impl TransportRpcModuleConfig ===
@@ -1507,6 +2079,16 @@ impl TransportRpcModuleConfig {
         self
     }
 
+    /// Sets the deadlines of calls of the configured modules, per namespace.
+    ///
+    /// Calls that exceed their deadline fail with a timeout error on all transports.
+    ///
+    /// See also [`RequestTimeoutLayer`].
+    pub fn with_request_timeouts(mut self, request_timeouts: RequestTimeouts) -> Self {
+        self.request_timeouts = request_timeouts;
+        self
+    }
+
     /// Get a mutable reference to the
     pub fn http_mut(&mut self) -> &mut Option<RpcModuleSelection> {
         &mut self.http
@@ -1552,6 +2134,17 @@ impl TransportRpcModuleConfig {
         self.config.as_ref()
     }
 
+    /// Returns the deadlines of calls of the configured modules
+    pub const fn request_timeouts(&self) -> &RequestTimeouts {
+        &self.request_timeouts
+    }
+
+    /// Creates the [`RequestTimeoutLayer`] if any deadline is configured
+    fn maybe_request_timeout_layer(&self) -> Option<RequestTimeoutLayer> {
+        (!self.request_timeouts.is_empty())
+            .then(|| RequestTimeoutLayer::new(self.request_timeouts.clone()))
+    }
+
     /// Ensures that both http and ws are configured and that they are configured to use the same
     /// port.
     fn ensure_ws_http_identical(&self) -> Result<(), WsHttpSamePortError> {
@@ -1587,6 +2180,8 @@ pub struct TransportRpcModules<Context = ()> {
     ws: Option<RpcModule<Context>>,
     /// rpcs module for ipc
     ipc: Option<RpcModule<Context>>,
+    /// Enables and disables namespaces of the running servers
+    toggle: RpcModuleToggle,
 }
 
 // === impl TransportRpcModules ===
@@ -1597,6 +2192,23 @@ impl TransportRpcModules {
         &self.config
     }
 
+    /// Returns the handle to enable and disable whole namespaces of the servers started with these
+    /// modules at runtime.
+    ///
+    /// The namespaces of all methods merged before the servers are built can be toggled.
+    pub const fn module_toggle(&self) -> &RpcModuleToggle {
+        &self.toggle
+    }
+
+    /// Registers the namespaces of all transports with the [`RpcModuleToggle`] and returns the
+    /// layer that rejects the calls of disabled namespaces.
+    fn module_toggle_layer(&self) -> RpcModuleToggleLayer {
+        for module in [&self.http, &self.ws, &self.ipc].into_iter().flatten() {
+            self.toggle.register_methods(module.method_names());
+        }
+        RpcModuleToggleLayer::new(self.toggle.clone())
+    }
+
     /// Merge the given [Methods] in the configured http methods.
     ///
     /// Fails if any of the methods in other is present already.
@@ -1647,6 +2259,34 @@ impl TransportRpcModules {
         Ok(())
     }
 
+    /// Merge the given [Methods] in all transports that have the given module configured.
+    ///
+    /// Fails if any of the methods in other is present already.
+    ///
+    /// Returns [Ok(false)] if the module isn't configured for any transport.
+    pub fn merge_if_module_configured(
+        &mut self,
+        module: RethRpcModule,
+        other: impl Into<Methods>,
+    ) -> Result<bool, RegisterMethodError> {
+        let other = other.into();
+        let is_configured = |selection: Option<&RpcModuleSelection>| {
+            selection.map_or(false, |s| s.contains(&module))
+        };
+
+        let mut merged = false;
+        if is_configured(self.config.http()) {
+            merged |= self.merge_http(other.clone())?;
+        }
+        if is_configured(self.config.ws()) {
+            merged |= self.merge_ws(other.clone())?;
+        }
+        if is_configured(self.config.ipc()) {
+            merged |= self.merge_ipc(other)?;
+        }
+        Ok(merged)
+    }
+
     /// Convenience function for starting a server
     pub async fn start_server(self, builder: RpcServerConfig) -> Result<RpcServerHandle, RpcError> {
         builder.start(self).await
@@ -1658,23 +2298,275 @@ impl TransportRpcModules {
 struct WsHttpServer {
     /// The address of the http server
     http_local_addr: Option<SocketAddr>,
+    /// The addresses of the http server in addition to its main address
+    http_additional_local_addrs: Vec<SocketAddr>,
     /// The address of the ws server
     ws_local_addr: Option<SocketAddr>,
+    /// The addresses of the ws server in addition to its main address
+    ws_additional_local_addrs: Vec<SocketAddr>,
     /// Configured ws,http servers
     server: WsHttpServers,
     /// The jwt secret.
     jwt_secret: Option<JwtSecret>,
 }
 
-// Define the type alias with detailed type complexity
-type WsHttpServerKind = Server<
+/// The http middleware of the ws and http servers.
+type WsHttpMiddleware = Stack<
+    tower::util::Either<HttpMiddlewareLayer, Identity>,
     Stack<
-        tower::util::Either<AuthLayer<JwtAuthValidator>, Identity>,
-        Stack<tower::util::Either<CorsLayer, Identity>, Identity>,
+        tower::util::Either<BatchCostTrackerLayer, Identity>,
+        Stack<
+            tower::util::Either<ApiKeyAuthLayer, Identity>,
+            Stack<
+                tower::util::Either<AuthLayer<JwtAuthValidator>, Identity>,
+                Stack<
+                    tower::util::Either<CorsLayer, Identity>,
+                    Stack<
+                        tower::util::Either<HealthLayer, Identity>,
+                        Stack<
+                            tower::util::Either<ProxyClientIpLayer, Identity>,
+                            Stack<tower::util::Either<HttpCompressionLayer, Identity>, Identity>,
+                        >,
+                    >,
+                >,
+            >,
+        >,
     >,
-    Stack<RpcRequestMetrics, Identity>,
 >;
 
+/// The rpc middleware of the ws and http servers.
+type WsHttpRpcMiddleware = Stack<
+    Either<ResponseCacheLayer, Identity>,
+    Stack<
+        Either<ApiKeyAllowlistLayer, Identity>,
+        Stack<
+            Either<BatchCostLimitLayer, Identity>,
+            Stack<Either<IpRateLimitLayer, Identity>, RpcMiddleware>,
+        >,
+    >,
+>;
+
+/// The rpc middleware of the ipc server.
+type IpcRpcMiddleware = Stack<Either<ResponseCacheLayer, Identity>, RpcMiddleware>;
+
+/// The rpc middleware shared by the ws, http and ipc servers.
+type RpcMiddleware = Stack<
+    Either<RequestTimeoutLayer, Identity>,
+    Stack<
+        Either<RateLimitLayer, Identity>,
+        Stack<
+            Either<SubscriptionLimitLayer, Identity>,
+            Stack<
+                Either<MethodRateLimitLayer, Identity>,
+                Stack<
+                    RpcModuleToggleLayer,
+                    Stack<
+                        Either<RpcRequestMetrics, Identity>,
+                        Stack<Either<RpcRequestLoggerLayer, Identity>, Identity>,
+                    >,
+                >,
+            >,
+        >,
+    >,
+>;
+
+/// An address of the http or ws server in addition to its main address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcServerAddress {
+    /// The address to bind to.
+    pub addr: SocketAddr,
+    /// The modules served on the address, if only a subset of the modules of the server.
+    pub modules: Option<RpcModuleSelection>,
+}
+
+impl RpcServerAddress {
+    /// Creates an address serving all modules of the server.
+    pub const fn new(addr: SocketAddr) -> Self {
+        Self { addr, modules: None }
+    }
+}
+
+/// A ws and/or http server.
+///
+/// Unlike [`Server`](jsonrpsee::server::Server), this accepts connections itself to make the
+/// address of every connection available to the middlewares as the [`ClientIp`] of its requests.
+struct WsHttpServerKind {
+    /// The listeners accepting connections, starting with the listener of the main address, and
+    /// the modules they serve if only a subset of the modules of the server.
+    listeners: Vec<(TcpListener, Option<RpcModuleSelection>)>,
+    /// Builds the service of every connection.
+    service_builder: TowerServiceBuilder<WsHttpRpcMiddleware, WsHttpMiddleware>,
+    /// Performs the TLS handshake of every connection, if the server terminates TLS.
+    tls: Option<TlsAcceptor>,
+    /// The proxies whose connections start with a PROXY protocol header, if enabled.
+    proxy_protocol: Option<TrustedProxies>,
+}
+
+impl WsHttpServerKind {
+    /// Binds the server to the given main address and the additional addresses.
+    async fn bind(
+        builder: ServerBuilder<WsHttpMiddleware, WsHttpRpcMiddleware>,
+        addr: SocketAddr,
+        additional_addrs: Vec<RpcServerAddress>,
+        kind: fn(SocketAddr) -> ServerKind,
+        tls: Option<TlsAcceptor>,
+        proxy_protocol: Option<TrustedProxies>,
+    ) -> Result<Self, RpcError> {
+        let mut listeners = Vec::with_capacity(additional_addrs.len() + 1);
+        for RpcServerAddress { addr, modules } in
+            std::iter::once(RpcServerAddress::new(addr)).chain(additional_addrs)
+        {
+            let listener = TcpListener::bind(addr)
+                .await
+                .map_err(|err| RpcError::server_error(err, kind(addr)))?;
+            listeners.push((listener, modules));
+        }
+        Ok(Self { listeners, service_builder: builder.to_service_builder(), tls, proxy_protocol })
+    }
+
+    /// Returns the main address and the additional addresses the server is bound to.
+    fn local_addrs(&self) -> std::io::Result<(SocketAddr, Vec<SocketAddr>)> {
+        let mut addrs = self.listeners.iter().map(|(listener, _)| listener.local_addr());
+        let addr = addrs.next().expect("main address is bound")?;
+        Ok((addr, addrs.collect::<Result<_, _>>()?))
+    }
+
+    /// Spawns the server, serving the methods of the module until it is stopped.
+    fn start(self, module: RpcModule<()>) -> ServerHandle {
+        let Self { listeners, service_builder, tls, proxy_protocol } = self;
+        let methods = Methods::from(module);
+        let (stop_handle, server_handle) = stop_channel();
+
+        if let Some(tls) = tls.clone() {
+            let stopped = stop_handle.clone().shutdown();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tls.reload_interval());
+                let reload = async {
+                    loop {
+                        interval.tick().await;
+                        tls.reload_if_changed();
+                    }
+                };
+                tokio::select! {
+                    _ = reload => {}
+                    _ = stopped => {}
+                }
+            });
+        }
+
+        for (listener, modules) in listeners {
+            let methods = match modules {
+                Some(modules) => select_methods(&methods, &modules),
+                None => methods.clone(),
+            };
+            tokio::spawn(accept_connections(
+                listener,
+                methods,
+                service_builder.clone(),
+                stop_handle.clone(),
+                tls.clone(),
+                proxy_protocol.clone(),
+            ));
+        }
+
+        server_handle
+    }
+}
+
+/// Accepts the connections of the listener and serves the methods on them until the server is
+/// stopped.
+async fn accept_connections(
+    listener: TcpListener,
+    methods: Methods,
+    service_builder: TowerServiceBuilder<WsHttpRpcMiddleware, WsHttpMiddleware>,
+    stop_handle: StopHandle,
+    tls: Option<TlsAcceptor>,
+    proxy_protocol: Option<TrustedProxies>,
+) {
+    loop {
+        let (mut socket, remote_addr) = tokio::select! {
+            res = listener.accept() => match res {
+                Ok(conn) => conn,
+                Err(err) => {
+                    debug!(target: "rpc", %err, "Failed to accept connection");
+                    continue
+                }
+            },
+            _ = stop_handle.clone().shutdown() => break,
+        };
+        if let Err(err) = socket.set_nodelay(true) {
+            debug!(target: "rpc", %err, "Failed to set TCP_NODELAY");
+        }
+
+        let service_builder = service_builder.clone();
+        let methods = methods.clone();
+        let stop_handle = stop_handle.clone();
+        let tls = tls.clone();
+        let proxy_protocol = proxy_protocol.clone();
+        tokio::spawn(async move {
+            let mut client_ip = remote_addr.ip();
+            if proxy_protocol.is_some_and(|proxies| proxies.contains(client_ip)) {
+                let header = tokio::time::timeout(
+                    PROXY_PROTOCOL_HEADER_TIMEOUT,
+                    read_proxy_protocol_header(&mut socket),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out"))
+                });
+                match header {
+                    Ok(Some(client_addr)) => client_ip = client_addr.ip(),
+                    Ok(None) => {}
+                    Err(err) => {
+                        debug!(target: "rpc", %err, %remote_addr, "Failed to read PROXY protocol header");
+                        return
+                    }
+                }
+            }
+
+            // the middlewares of the servers expect the body type of jsonrpsee
+            let service = AddExtension::new(
+                service_builder.build(methods, stop_handle.clone()),
+                ClientIp(client_ip),
+            )
+            .map_request(|req: http::Request<hyper::body::Incoming>| req.map(HttpBody::new));
+            let stopped = stop_handle.shutdown();
+            let res = match tls {
+                Some(tls) => match tls.accept(socket).await {
+                    Ok(stream) => serve_with_graceful_shutdown(stream, service, stopped).await,
+                    Err(err) => {
+                        debug!(target: "rpc", %err, %remote_addr, "Failed TLS handshake");
+                        return
+                    }
+                },
+                None => serve_with_graceful_shutdown(socket, service, stopped).await,
+            };
+            if let Err(err) = res {
+                debug!(target: "rpc", %err, %remote_addr, "Failed to serve connection");
+            }
+        });
+    }
+}
+
+/// Returns the methods of the selected modules.
+///
+/// The module of a method is given by its namespace, e.g. `eth` for `eth_call`, except for
+/// methods that are modules of their own, like `eth_callBundle`.
+fn select_methods(methods: &Methods, modules: &RpcModuleSelection) -> Methods {
+    let mut selected = Methods::new();
+    for name in methods.method_names() {
+        let module = name
+            .parse::<RethRpcModule>()
+            .ok()
+            .or_else(|| name.split_once('_').and_then(|(namespace, _)| namespace.parse().ok()));
+        if module.is_some_and(|module| modules.contains(&module)) {
+            let callback = methods.method(name).expect("method is registered").clone();
+            selected.verify_and_insert(name, callback).expect("method names are unique");
+        }
+    }
+    selected
+}
+
 /// Enum for holding the http and ws servers in all possible combinations.
 enum WsHttpServers {
     /// Both servers are on the same port
@@ -1736,7 +2628,7 @@ pub struct RpcServer {
     /// Configured ws,http servers
     ws_http: WsHttpServer,
     /// ipc server
-    ipc: Option<IpcServer<Identity, Stack<RpcRequestMetrics, Identity>>>,
+    ipc: Option<IpcServer<Identity, IpcRpcMiddleware>>,
 }
 
 // === impl RpcServer ===
@@ -1750,6 +2642,11 @@ impl RpcServer {
     pub const fn http_local_addr(&self) -> Option<SocketAddr> {
         self.ws_http.http_local_addr
     }
+
+    /// Returns the [`SocketAddr`]s of the http server in addition to its main address.
+    pub fn http_additional_local_addrs(&self) -> &[SocketAddr] {
+        &self.ws_http.http_additional_local_addrs
+    }
     /// Return the `JwtSecret` of the server
     pub const fn jwt(&self) -> Option<JwtSecret> {
         self.ws_http.jwt_secret
@@ -1760,6 +2657,11 @@ impl RpcServer {
         self.ws_http.ws_local_addr
     }
 
+    /// Returns the [`SocketAddr`]s of the ws server in addition to its main address.
+    pub fn ws_additional_local_addrs(&self) -> &[SocketAddr] {
+        &self.ws_http.ws_additional_local_addrs
+    }
+
     /// Returns the endpoint of the ipc server if started.
     pub fn ipc_endpoint(&self) -> Option<String> {
         self.ipc.as_ref().map(|ipc| ipc.endpoint())
@@ -1773,10 +2675,12 @@ impl RpcServer {
     pub async fn start(self, modules: TransportRpcModules) -> Result<RpcServerHandle, RpcError> {
         trace!(target: "rpc", "staring RPC server");
         let Self { ws_http, ipc: ipc_server } = self;
-        let TransportRpcModules { config, http, ws, ipc } = modules;
+        let TransportRpcModules { config, http, ws, ipc, .. } = modules;
         let mut handle = RpcServerHandle {
             http_local_addr: ws_http.http_local_addr,
+            http_additional_local_addrs: ws_http.http_additional_local_addrs,
             ws_local_addr: ws_http.ws_local_addr,
+            ws_additional_local_addrs: ws_http.ws_additional_local_addrs,
             http: None,
             ws: None,
             ipc_endpoint: None,
@@ -1818,7 +2722,9 @@ impl fmt::Debug for RpcServer {
 pub struct RpcServerHandle {
     /// The address of the http/ws server
     http_local_addr: Option<SocketAddr>,
+    http_additional_local_addrs: Vec<SocketAddr>,
     ws_local_addr: Option<SocketAddr>,
+    ws_additional_local_addrs: Vec<SocketAddr>,
     http: Option<ServerHandle>,
     ws: Option<ServerHandle>,
     ipc_endpoint: Option<String>,
@@ -1850,11 +2756,21 @@ impl RpcServerHandle {
         self.http_local_addr
     }
 
+    /// Returns the [`SocketAddr`]s of the http server in addition to its main address.
+    pub fn http_additional_local_addrs(&self) -> &[SocketAddr] {
+        &self.http_additional_local_addrs
+    }
+
     /// Returns the [`SocketAddr`] of the ws server if started.
     pub const fn ws_local_addr(&self) -> Option<SocketAddr> {
         self.ws_local_addr
     }
 
+    /// Returns the [`SocketAddr`]s of the ws server in addition to its main address.
+    pub fn ws_additional_local_addrs(&self) -> &[SocketAddr] {
+        &self.ws_additional_local_addrs
+    }
+
     /// Tell the server to stop without waiting for the server to stop.
     pub fn stop(self) -> Result<(), AlreadyStoppedError> {
         if let Some(handle) = self.http {
@@ -1872,6 +2788,38 @@ impl RpcServerHandle {
         Ok(())
     }
 
+    /// Stops the servers gracefully, waiting at most `timeout` for them to stop.
+    ///
+    /// The servers immediately stop accepting new connections, but the calls in flight are
+    /// completed and their responses sent before the connections are closed. Websocket and ipc
+    /// connections are closed once all their in-flight calls have been answered, which ends their
+    /// subscriptions; websocket clients receive a close frame.
+    ///
+    /// Returns [`GracefulShutdownError::Timeout`] if connections are still open after the timeout,
+    /// these are closed once their remaining calls complete.
+    pub async fn shutdown_graceful(self, timeout: Duration) -> Result<(), GracefulShutdownError> {
+        // http and ws servers on the same port share their handle
+        let same_port = self.http.is_some() && self.http_local_addr == self.ws_local_addr;
+        let handles = self
+            .http
+            .into_iter()
+            .chain(self.ws.filter(|_| !same_port))
+            .chain(self.ipc)
+            .collect::<Vec<_>>();
+        for handle in &handles {
+            handle.stop()?;
+        }
+
+        debug!(target: "rpc", ?timeout, "Waiting for RPC servers to shut down");
+        tokio::time::timeout(timeout, async {
+            for handle in handles {
+                handle.stopped().await;
+            }
+        })
+        .await
+        .map_err(|_| GracefulShutdownError::Timeout(timeout))
+    }
+
     /// Returns the endpoint of the launched IPC server, if any
     pub fn ipc_endpoint(&self) -> Option<String> {
         self.ipc_endpoint.clone()
@@ -2052,6 +3000,7 @@ mod tests {
                 ws: None,
                 ipc: None,
                 config: None,
+                request_timeouts: Default::default(),
             }
         )
     }
@@ -2066,6 +3015,7 @@ mod tests {
                 ws: None,
                 ipc: None,
                 config: None,
+                request_timeouts: Default::default(),
             }
         )
     }
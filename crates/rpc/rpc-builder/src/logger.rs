@@ -0,0 +1,151 @@
+//! Middleware logging RPC calls with a tracing span per call.
+
+use crate::ClientIp;
+use jsonrpsee::{
+    server::{middleware::rpc::RpcServiceT, ConnectionId},
+    types::Request,
+    MethodResponse,
+};
+use std::{
+    future::Future,
+    num::NonZeroU64,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use tower::Layer;
+use tracing::{field, info, info_span, Instrument};
+
+/// A [`Layer`] that logs RPC calls.
+///
+/// Every logged call is processed in an `rpc_call` span with the method, the size of the params in
+/// bytes, and the id of the connection and the [`ClientIp`], if the transport provides them. Once
+/// the call completes, an event with its latency and whether it succeeded is emitted in the span,
+/// with the `rpc::request` target.
+///
+/// A sample of the calls can be logged, see [`RpcRequestLoggerLayer::with_sampling`].
+#[derive(Debug, Clone)]
+pub struct RpcRequestLoggerLayer {
+    sampler: Arc<Sampler>,
+}
+
+impl RpcRequestLoggerLayer {
+    /// Creates a new layer logging all calls.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logs only one in `every` calls, e.g. `100` to log the first call and every hundredth
+    /// call after it.
+    pub fn with_sampling(self, every: NonZeroU64) -> Self {
+        Self { sampler: Arc::new(Sampler { every, calls: AtomicU64::new(0) }) }
+    }
+
+    /// Returns how many calls are processed per logged call.
+    pub fn sampling(&self) -> NonZeroU64 {
+        self.sampler.every
+    }
+}
+
+impl Default for RpcRequestLoggerLayer {
+    fn default() -> Self {
+        Self { sampler: Arc::new(Sampler { every: NonZeroU64::MIN, calls: AtomicU64::new(0) }) }
+    }
+}
+
+impl<S> Layer<S> for RpcRequestLoggerLayer {
+    type Service = RpcRequestLogger<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcRequestLogger { inner, sampler: self.sampler.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that logs calls.
+///
+/// See [`RpcRequestLoggerLayer`].
+#[derive(Debug, Clone)]
+pub struct RpcRequestLogger<S> {
+    inner: S,
+    sampler: Arc<Sampler>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcRequestLogger<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if !self.sampler.sample() {
+            return Box::pin(self.inner.call(req))
+        }
+
+        let span = info_span!(
+            target: "rpc::request",
+            "rpc_call",
+            method = %req.method_name(),
+            params_size = req.params.as_ref().map_or(0, |params| params.get().len()),
+            conn_id = field::Empty,
+            client_ip = field::Empty,
+        );
+        if let Some(ConnectionId(conn_id)) = req.extensions().get::<ConnectionId>() {
+            span.record("conn_id", conn_id);
+        }
+        if let Some(ClientIp(client_ip)) = req.extensions().get::<ClientIp>() {
+            span.record("client_ip", field::display(client_ip));
+        }
+
+        let call = self.inner.call(req);
+        Box::pin(
+            async move {
+                let started_at = Instant::now();
+                let response = call.await;
+                info!(
+                    target: "rpc::request",
+                    elapsed = ?started_at.elapsed(),
+                    success = response.is_success(),
+                    "Processed RPC call"
+                );
+                response
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Picks the calls to log.
+#[derive(Debug)]
+struct Sampler {
+    /// How many calls are processed per logged call.
+    every: NonZeroU64,
+    /// The number of calls seen so far.
+    calls: AtomicU64,
+}
+
+impl Sampler {
+    /// Returns `true` if the next call should be logged.
+    fn sample(&self) -> bool {
+        let every = self.every.get();
+        every == 1 || self.calls.fetch_add(1, Ordering::Relaxed) % every == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_calls() {
+        let sampled = |layer: RpcRequestLoggerLayer, calls: usize| {
+            (0..calls).filter(|_| layer.sampler.sample()).count()
+        };
+        let every = |n: u64| NonZeroU64::new(n).unwrap();
+        assert_eq!(sampled(RpcRequestLoggerLayer::new(), 10), 10);
+        assert_eq!(sampled(RpcRequestLoggerLayer::new().with_sampling(every(10)), 100), 10);
+        assert_eq!(sampled(RpcRequestLoggerLayer::new().with_sampling(every(4)), 9), 3);
+    }
+}
@@ -12,6 +12,7 @@ use std::{
     time::Instant,
 };
 use tower::Layer;
+use tracing::{debug_span, instrument::Instrumented, Instrument};
 
 /// Metrics for the RPC server.
 ///
@@ -20,8 +21,11 @@ use tower::Layer;
 ///   for WS and IPC)
 /// - Request metrics: metrics for each RPC method (e.g. number of calls started, time taken to
 ///   process a call)
+///
+/// Request metrics are labeled with the method and are only recorded for the methods of the
+/// module the metrics are created for.
 #[derive(Default, Debug, Clone)]
-pub(crate) struct RpcRequestMetrics {
+pub struct RpcRequestMetrics {
     inner: Arc<RpcServerMetricsInner>,
 }
 
@@ -38,25 +42,24 @@ impl RpcRequestMetrics {
     }
 
     /// Creates a new instance of the metrics layer for HTTP.
-    pub(crate) fn http(module: &RpcModule<()>) -> Self {
+    pub fn http(module: &RpcModule<()>) -> Self {
         Self::new(module, RpcTransport::Http)
     }
 
     /// Creates a new instance of the metrics layer for same port.
     ///
     /// Note: currently it's not possible to track transport specific metrics for a server that runs http and ws on the same port: <https://github.com/paritytech/jsonrpsee/issues/1345> until we have this feature we will use the http metrics for this case.
-    pub(crate) fn same_port(module: &RpcModule<()>) -> Self {
+    pub fn same_port(module: &RpcModule<()>) -> Self {
         Self::http(module)
     }
 
     /// Creates a new instance of the metrics layer for Ws.
-    pub(crate) fn ws(module: &RpcModule<()>) -> Self {
+    pub fn ws(module: &RpcModule<()>) -> Self {
         Self::new(module, RpcTransport::WebSocket)
     }
 
-    /// Creates a new instance of the metrics layer for Ws.
-    #[allow(unused)]
-    pub(crate) fn ipc(module: &RpcModule<()>) -> Self {
+    /// Creates a new instance of the metrics layer for Ipc.
+    pub fn ipc(module: &RpcModule<()>) -> Self {
         Self::new(module, RpcTransport::Ipc)
     }
 }
@@ -80,9 +83,10 @@ struct RpcServerMetricsInner {
 
 /// A [`RpcServiceT`] middleware that captures RPC metrics for the server.
 ///
-/// This is created per connection and captures metrics for each request.
-#[derive(Clone)]
-pub(crate) struct RpcRequestMetricsService<S> {
+/// This is created per connection and captures metrics for each request. Every request is handled
+/// in an `rpc_request` span.
+#[derive(Clone, Debug)]
+pub struct RpcRequestMetricsService<S> {
     metrics: RpcRequestMetrics,
     inner: S,
 }
@@ -99,7 +103,7 @@ impl<'a, S> RpcServiceT<'a> for RpcRequestMetricsService<S>
 where
     S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
 {
-    type Future = MeteredRequestFuture<S::Future>;
+    type Future = MeteredRequestFuture<Instrumented<S::Future>>;
 
     fn call(&self, req: Request<'a>) -> Self::Future {
         self.metrics.inner.connection_metrics.requests_started_total.increment(1);
@@ -107,8 +111,10 @@ where
         if let Some((_, call_metrics)) = &call_metrics {
             call_metrics.started_total.increment(1);
         }
+        let span = debug_span!(target: "rpc", "rpc_request", method = %req.method_name());
+        let fut = span.in_scope(|| self.inner.call(req));
         MeteredRequestFuture {
-            fut: self.inner.call(req),
+            fut: fut.instrument(span),
             started_at: Instant::now(),
             metrics: self.metrics.clone(),
             method: call_metrics.map(|(method, _)| *method),
@@ -125,7 +131,7 @@ impl<S> Drop for RpcRequestMetricsService<S> {
 
 /// Response future to update the metrics for a single request/response pair.
 #[pin_project::pin_project]
-pub(crate) struct MeteredRequestFuture<F> {
+pub struct MeteredRequestFuture<F> {
     #[pin]
     fut: F,
     /// time when the request started
@@ -177,7 +183,6 @@ impl<F: Future<Output = MethodResponse>> Future for MeteredRequestFuture<F> {
 pub(crate) enum RpcTransport {
     Http,
     WebSocket,
-    #[allow(unused)]
     Ipc,
 }
 
@@ -0,0 +1,83 @@
+//! Custom http middleware of the http and ws servers.
+
+use jsonrpsee::server::{HttpRequest, HttpResponse};
+use std::{fmt, sync::Arc};
+use tower::{util::BoxCloneService, BoxError, Layer, Service};
+
+/// The type-erased http service of the http and ws servers that is wrapped by the layers of a
+/// [`HttpMiddlewareLayer`].
+pub type BoxedHttpService = BoxCloneService<HttpRequest, HttpResponse, BoxError>;
+
+/// Wraps a [`BoxedHttpService`] in a layer.
+type BoxedHttpLayer = Arc<dyn Fn(BoxedHttpService) -> BoxedHttpService + Send + Sync>;
+
+/// A [`Layer`] stacking custom tower layers on the http requests of the http and ws servers, e.g.
+/// for authentication, rate limiting or metrics.
+///
+/// The layers are applied after the builtin http middleware of the servers, i.e. they only see
+/// the requests that passed it. Like with [`tower::ServiceBuilder`], the first added layer is the
+/// outermost one.
+#[derive(Clone, Default)]
+pub struct HttpMiddlewareLayer {
+    layers: Vec<BoxedHttpLayer>,
+}
+
+impl HttpMiddlewareLayer {
+    /// Adds the layer inside of the previously added layers, which see the requests before it.
+    pub fn push_layer<L>(&mut self, layer: L) -> &mut Self
+    where
+        L: Layer<BoxedHttpService> + Send + Sync + 'static,
+        L::Service: Service<HttpRequest, Response = HttpResponse, Error = BoxError>
+            + Clone
+            + Send
+            + 'static,
+        <L::Service as Service<HttpRequest>>::Future: Send + 'static,
+    {
+        self.layers.push(Arc::new(move |service| BoxCloneService::new(layer.layer(service))));
+        self
+    }
+
+    /// Adds the layer inside of the previously added layers, which see the requests before it.
+    ///
+    /// See also [`HttpMiddlewareLayer::push_layer`].
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<BoxedHttpService> + Send + Sync + 'static,
+        L::Service: Service<HttpRequest, Response = HttpResponse, Error = BoxError>
+            + Clone
+            + Send
+            + 'static,
+        <L::Service as Service<HttpRequest>>::Future: Send + 'static,
+    {
+        self.push_layer(layer);
+        self
+    }
+
+    /// Returns the number of layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if no layers were added.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl fmt::Debug for HttpMiddlewareLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpMiddlewareLayer").field("layers", &self.layers.len()).finish()
+    }
+}
+
+impl<S> Layer<S> for HttpMiddlewareLayer
+where
+    S: Service<HttpRequest, Response = HttpResponse, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Service = BoxedHttpService;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        self.layers.iter().rev().fold(BoxCloneService::new(inner), |service, layer| layer(service))
+    }
+}
@@ -0,0 +1,304 @@
+//! Support for reverse proxies and load balancers in front of the http and ws servers.
+
+use http::{HeaderName, HeaderValue};
+use reth_config::IpNetwork;
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tower::{Layer, Service};
+
+/// How long a trusted proxy has to send the PROXY protocol header of a connection.
+pub(crate) const PROXY_PROTOCOL_HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The signature at the start of a PROXY protocol v2 header.
+const PROXY_PROTOCOL_V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The IP address of the client of a request.
+///
+/// The http and ws servers insert the address of the connection into the extensions of every
+/// request. If the connection is made by a trusted proxy, the address is replaced by the client
+/// address the proxy sent in a PROXY protocol header, see [`RpcServerConfig::with_proxy`], or in
+/// a header of the request, see [`ProxyClientIpLayer`]. Requests of the ipc server have no client
+/// address.
+///
+/// [`RpcServerConfig::with_proxy`]: crate::RpcServerConfig::with_proxy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientIp(pub IpAddr);
+
+/// The addresses or networks of trusted proxies.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Arc<[IpNetwork]>);
+
+impl TrustedProxies {
+    /// Creates a new set of trusted proxies.
+    pub fn new(proxies: impl IntoIterator<Item = IpNetwork>) -> Self {
+        Self(proxies.into_iter().collect())
+    }
+
+    /// Returns `true` if the address belongs to a trusted proxy.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|network| network.contains(ip))
+    }
+}
+
+/// A [`Layer`] for http requests that reads the [`ClientIp`] of a request from a header set by a
+/// trusted reverse proxy, e.g. `X-Forwarded-For`.
+///
+/// By default, the header of all requests is trusted and the last address of the header is used,
+/// which is the one added by the proxy. With [`ProxyClientIpLayer::with_trusted_proxies`], the
+/// header is only trusted if the request is made by a trusted proxy, and the last address of the
+/// header that isn't a trusted proxy is used, so that chains of proxies are supported. Requests
+/// without a valid address in the header keep the address of their connection.
+#[derive(Debug, Clone)]
+pub struct ProxyClientIpLayer {
+    header: HeaderName,
+    trusted_proxies: Option<TrustedProxies>,
+}
+
+impl ProxyClientIpLayer {
+    /// Creates a new layer reading the client address from the given header.
+    pub const fn new(header: HeaderName) -> Self {
+        Self { header, trusted_proxies: None }
+    }
+
+    /// Only trusts the header of requests made by the given proxies.
+    pub fn with_trusted_proxies(mut self, trusted_proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = Some(trusted_proxies);
+        self
+    }
+}
+
+impl<S> Layer<S> for ProxyClientIpLayer {
+    type Service = ProxyClientIp<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ProxyClientIp {
+            inner,
+            header: self.header.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+        }
+    }
+}
+
+/// A http middleware that sets the [`ClientIp`] of a request from a trusted proxy header.
+///
+/// See [`ProxyClientIpLayer`].
+#[derive(Debug, Clone)]
+pub struct ProxyClientIp<S> {
+    inner: S,
+    header: HeaderName,
+    trusted_proxies: Option<TrustedProxies>,
+}
+
+impl<S> ProxyClientIp<S> {
+    /// Returns the client address of the proxy header of the request, if the header is trusted.
+    fn client_ip<B>(&self, req: &http::Request<B>) -> Option<IpAddr> {
+        let values = req.headers().get_all(&self.header);
+        let Some(trusted_proxies) = &self.trusted_proxies else {
+            return values.iter().last().and_then(parse_proxy_header)
+        };
+
+        // the header of other peers is ignored, they could send any address
+        let ClientIp(peer) = req.extensions().get::<ClientIp>()?;
+        if !trusted_proxies.contains(*peer) {
+            return None
+        }
+        client_ip_of_trusted_proxies(values.iter(), trusted_proxies)
+    }
+}
+
+impl<S, B> Service<http::Request<B>> for ProxyClientIp<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        if let Some(ip) = self.client_ip(&req) {
+            req.extensions_mut().insert(ClientIp(ip));
+        }
+        self.inner.call(req)
+    }
+}
+
+/// Parses the last address of a proxy header, with or without port.
+fn parse_proxy_header(value: &HeaderValue) -> Option<IpAddr> {
+    parse_proxy_addr(value.to_str().ok()?.rsplit(',').next()?)
+}
+
+/// Returns the last address of the proxy headers that isn't a trusted proxy, or the first address
+/// if all of them are trusted proxies.
+///
+/// Returns `None` if an address in front of the trusted proxies is invalid.
+fn client_ip_of_trusted_proxies<'a>(
+    values: impl DoubleEndedIterator<Item = &'a HeaderValue>,
+    trusted_proxies: &TrustedProxies,
+) -> Option<IpAddr> {
+    let mut client_ip = None;
+    for value in values.rev() {
+        for addr in value.to_str().ok()?.rsplit(',') {
+            let ip = parse_proxy_addr(addr)?;
+            client_ip = Some(ip);
+            if !trusted_proxies.contains(ip) {
+                return client_ip
+            }
+        }
+    }
+    client_ip
+}
+
+/// Parses an address of a proxy header, with or without port.
+fn parse_proxy_addr(addr: &str) -> Option<IpAddr> {
+    let addr = addr.trim();
+    addr.parse::<IpAddr>().ok().or_else(|| addr.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// Reads the [PROXY protocol v2](https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt)
+/// header at the start of a connection of a trusted proxy, and returns the address of the client
+/// the proxy forwards the connection of.
+///
+/// Returns `None` for `LOCAL` connections, e.g. health checks of the proxy, and for connections of
+/// clients that don't have an IP address, which keep the address of the proxy.
+pub(crate) async fn read_proxy_protocol_header<S>(stream: &mut S) -> io::Result<Option<SocketAddr>>
+where
+    S: AsyncRead + Unpin,
+{
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let mut header = [0; 16];
+    stream.read_exact(&mut header).await?;
+    if header[..12] != PROXY_PROTOCOL_V2_SIGNATURE {
+        return Err(invalid("missing PROXY protocol v2 header"))
+    }
+    let (version_command, family) = (header[12], header[13]);
+    if version_command >> 4 != 2 {
+        return Err(invalid("unsupported PROXY protocol version"))
+    }
+    let mut addresses = vec![0; u16::from_be_bytes([header[14], header[15]]) as usize];
+    stream.read_exact(&mut addresses).await?;
+
+    match version_command & 0x0f {
+        // LOCAL
+        0x0 => return Ok(None),
+        // PROXY
+        0x1 => {}
+        _ => return Err(invalid("unsupported PROXY protocol command")),
+    }
+
+    // the source and destination addresses are followed by the source and destination ports
+    let addr = match family >> 4 {
+        // AF_INET
+        0x1 if addresses.len() >= 12 => {
+            let ip: [u8; 4] = addresses[..4].try_into().expect("4 bytes");
+            SocketAddr::new(
+                Ipv4Addr::from(ip).into(),
+                u16::from_be_bytes([addresses[8], addresses[9]]),
+            )
+        }
+        // AF_INET6
+        0x2 if addresses.len() >= 36 => {
+            let ip: [u8; 16] = addresses[..16].try_into().expect("16 bytes");
+            SocketAddr::new(
+                Ipv6Addr::from(ip).into(),
+                u16::from_be_bytes([addresses[32], addresses[33]]),
+            )
+        }
+        // AF_UNSPEC or AF_UNIX
+        0x0 | 0x3 => return Ok(None),
+        _ => return Err(invalid("invalid PROXY protocol addresses")),
+    };
+    Ok(Some(addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a PROXY protocol v2 header with the given command, family and addresses.
+    fn proxy_protocol_header(command: u8, family: u8, addresses: &[u8]) -> Vec<u8> {
+        let mut header = PROXY_PROTOCOL_V2_SIGNATURE.to_vec();
+        header.extend([0x20 | command, family]);
+        header.extend((addresses.len() as u16).to_be_bytes());
+        header.extend(addresses);
+        header
+    }
+
+    #[test]
+    fn parse_proxy_headers() {
+        let parse = |value: &'static str| parse_proxy_header(&HeaderValue::from_static(value));
+        assert_eq!(parse("203.0.113.7"), Some(IpAddr::from([203, 0, 113, 7])));
+        assert_eq!(parse("10.0.0.1, 203.0.113.7"), Some(IpAddr::from([203, 0, 113, 7])));
+        assert_eq!(parse("203.0.113.7:8545"), Some(IpAddr::from([203, 0, 113, 7])));
+        assert_eq!(parse("2001:db8::1"), "2001:db8::1".parse().ok());
+        assert_eq!(parse("[2001:db8::1]:8545"), "2001:db8::1".parse().ok());
+        assert_eq!(parse("unknown"), None);
+    }
+
+    #[test]
+    fn skip_trusted_proxies() {
+        let trusted_proxies = TrustedProxies::new(["10.0.0.0/8".parse().unwrap()]);
+        let client_ip = |values: &[&'static str]| {
+            let values = values.iter().map(|value| HeaderValue::from_static(value));
+            client_ip_of_trusted_proxies(values.collect::<Vec<_>>().iter(), &trusted_proxies)
+        };
+        let client = IpAddr::from([203, 0, 113, 7]);
+
+        assert_eq!(client_ip(&["203.0.113.7"]), Some(client));
+        // addresses in front of the client can be chosen by the client
+        assert_eq!(client_ip(&["198.51.100.1, 203.0.113.7, 10.0.0.2, 10.0.0.1"]), Some(client));
+        assert_eq!(client_ip(&["198.51.100.1, 203.0.113.7", "10.0.0.2"]), Some(client));
+        assert_eq!(client_ip(&["10.0.0.2, 10.0.0.1"]), Some(IpAddr::from([10, 0, 0, 2])));
+        assert_eq!(client_ip(&["unknown, 10.0.0.1"]), None);
+        assert_eq!(client_ip(&["unknown, 203.0.113.7"]), Some(client));
+        assert_eq!(client_ip(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn read_proxy_protocol_headers() {
+        let ipv4 = [[203, 0, 113, 7], [10, 0, 0, 1]].concat();
+        let mut header =
+            proxy_protocol_header(0x1, 0x11, &[&ipv4[..], &[0x1f, 0x90, 0x21, 0x45]].concat());
+        // the header is followed by the data of the client, which must not be consumed
+        header.extend(b"POST / HTTP/1.1");
+        let mut stream = header.as_slice();
+        assert_eq!(
+            read_proxy_protocol_header(&mut stream).await.unwrap(),
+            Some(SocketAddr::from(([203, 0, 113, 7], 8080)))
+        );
+        assert_eq!(stream, b"POST / HTTP/1.1");
+
+        let ipv6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let addresses = [&ipv6.octets()[..], &[0; 16], &[0x1f, 0x90, 0x21, 0x45]].concat();
+        let header = proxy_protocol_header(0x1, 0x21, &addresses);
+        assert_eq!(
+            read_proxy_protocol_header(&mut header.as_slice()).await.unwrap(),
+            Some(SocketAddr::new(ipv6.into(), 8080))
+        );
+
+        // TLVs after the addresses are skipped
+        let header =
+            proxy_protocol_header(0x1, 0x11, &[&ipv4[..], &[0; 4], &[0x04, 0, 1, 0]].concat());
+        assert!(read_proxy_protocol_header(&mut header.as_slice()).await.unwrap().is_some());
+
+        // health checks of the proxy
+        let header = proxy_protocol_header(0x0, 0x00, &[]);
+        assert_eq!(read_proxy_protocol_header(&mut header.as_slice()).await.unwrap(), None);
+
+        let truncated = proxy_protocol_header(0x1, 0x11, &ipv4);
+        assert!(read_proxy_protocol_header(&mut truncated.as_slice()).await.is_err());
+        assert!(read_proxy_protocol_header(&mut &b"GET / HTTP/1.1\r\n\r\n"[..]).await.is_err());
+        let v1 = b"PROXY TCP4 203.0.113.7 10.0.0.1 8080 8545\r\n";
+        assert!(read_proxy_protocol_header(&mut &v1[..]).await.is_err());
+    }
+}
@@ -0,0 +1,543 @@
+//! Middlewares limiting the number of concurrently processed RPC requests, the rate of individual
+//! RPC methods and the rate of individual clients.
+
+use crate::ClientIp;
+use jsonrpsee::{
+    server::middleware::rpc::{ResponseFuture, RpcServiceT},
+    types::{ErrorObject, Request},
+    MethodResponse,
+};
+use parking_lot::Mutex;
+use reth_config::{IpRateLimitConfig, RateQuota};
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    future::Future,
+    net::{IpAddr, Ipv6Addr},
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Semaphore;
+use tower::Layer;
+use tracing::warn;
+
+/// The error code of calls that are rejected because the quota of their method is exhausted.
+pub const RATE_LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+/// A [`Layer`] that limits the number of RPC requests that are processed concurrently.
+///
+/// All services created by this layer share the same limit, so applied to a server, the limit
+/// holds across all connections of that server. Requests that exceed the limit are not rejected,
+/// instead they wait until one of the requests in flight has been processed.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    semaphore: Arc<Semaphore>,
+}
+
+impl RateLimitLayer {
+    /// Creates a new layer that processes at most `max_concurrent_requests` requests at a time.
+    ///
+    /// # Panics
+    ///
+    /// If `max_concurrent_requests` is zero.
+    pub fn new(max_concurrent_requests: usize) -> Self {
+        assert!(max_concurrent_requests > 0, "max concurrent requests must be greater than zero");
+        Self { semaphore: Arc::new(Semaphore::new(max_concurrent_requests)) }
+    }
+
+    /// Returns the number of requests that can currently be processed without waiting.
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit { inner, semaphore: self.semaphore.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that waits for a free slot before processing a request.
+///
+/// See [`RateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    semaphore: Arc<Semaphore>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RateLimit<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let inner = self.inner.clone();
+        let semaphore = self.semaphore.clone();
+        Box::pin(async move {
+            // the semaphore is never closed, so acquiring the permit can't fail
+            let _permit = semaphore.acquire_owned().await;
+            inner.call(req).await
+        })
+    }
+}
+
+/// A [`Layer`] that limits the rate at which individual RPC methods can be called.
+///
+/// Every method with a [`RateQuota`] gets a token bucket that holds up to
+/// [`RateQuota::requests`] tokens and is refilled over [`RateQuota::period`]. Calls of a method
+/// with an empty bucket are rejected with [`RATE_LIMIT_EXCEEDED_CODE`], calls of methods without a
+/// quota are not limited.
+///
+/// All services created by this layer share the same buckets, so the quotas hold across all
+/// connections and all servers the layer is applied to.
+#[derive(Debug, Clone, Default)]
+pub struct MethodRateLimitLayer {
+    buckets: Arc<HashMap<String, Mutex<TokenBucket>>>,
+}
+
+impl MethodRateLimitLayer {
+    /// Creates a new layer enforcing the given quotas, keyed by method name.
+    pub fn new(quotas: impl IntoIterator<Item = (String, RateQuota)>) -> Self {
+        let now = Instant::now();
+        let buckets = quotas
+            .into_iter()
+            .map(|(method, quota)| (method, Mutex::new(TokenBucket::new(quota, now))))
+            .collect();
+        Self { buckets: Arc::new(buckets) }
+    }
+
+    /// Returns the quota of the given method, if any.
+    pub fn quota(&self, method: &str) -> Option<RateQuota> {
+        self.buckets.get(method).map(|bucket| bucket.lock().quota)
+    }
+
+    /// Returns `true` if no method is limited.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+}
+
+impl<S> Layer<S> for MethodRateLimitLayer {
+    type Service = MethodRateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodRateLimit { inner, buckets: self.buckets.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that rejects calls of methods that exceeded their quota.
+///
+/// See [`MethodRateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct MethodRateLimit<S> {
+    inner: S,
+    buckets: Arc<HashMap<String, Mutex<TokenBucket>>>,
+}
+
+impl<'a, S> RpcServiceT<'a> for MethodRateLimit<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if let Some(bucket) = self.buckets.get(req.method_name()) {
+            let mut bucket = bucket.lock();
+            if !bucket.try_acquire(Instant::now()) {
+                let err = ErrorObject::owned(
+                    RATE_LIMIT_EXCEEDED_CODE,
+                    format!(
+                        "rate limit of {} exceeded for method {}",
+                        bucket.quota,
+                        req.method_name()
+                    ),
+                    None::<()>,
+                );
+                return ResponseFuture::ready(MethodResponse::error(req.id, err))
+            }
+        }
+
+        ResponseFuture::future(self.inner.call(req))
+    }
+}
+
+/// A [`Layer`] that limits the rate of the requests of individual clients, identified by their
+/// [`ClientIp`].
+///
+/// Every client gets a token bucket like the ones of the [`MethodRateLimitLayer`], requests of a
+/// client with an empty bucket are rejected with [`RATE_LIMIT_EXCEEDED_CODE`]. Clients that
+/// exceed their quota with [`IpRateLimitConfig::ban_threshold`] consecutive requests are banned
+/// for [`IpRateLimitConfig::ban_duration`], during which all of their requests are rejected.
+/// IPv6 clients are identified by the [`IpRateLimitConfig::ipv6_prefix_len`] leading bits of their
+/// address, so all addresses of a host's network share a quota. Requests without a client address
+/// are not limited.
+///
+/// All services created by this layer share the same buckets and bans.
+#[derive(Debug, Clone)]
+pub struct IpRateLimitLayer {
+    limiter: Arc<IpRateLimiter>,
+}
+
+impl IpRateLimitLayer {
+    /// Creates a new layer from the given config.
+    pub fn new(config: &IpRateLimitConfig) -> Self {
+        Self {
+            limiter: Arc::new(IpRateLimiter {
+                quota: config.quota,
+                ipv6_prefix_len: config.ipv6_prefix_len.min(128),
+                ban_threshold: config.ban_threshold,
+                ban_duration: config.ban_duration,
+                clients: Mutex::new(Clients {
+                    clients: HashMap::new(),
+                    last_prune: Instant::now(),
+                }),
+                metrics: Default::default(),
+            }),
+        }
+    }
+
+    /// Returns `true` if the client is currently banned.
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        let key = self.limiter.client_key(ip);
+        self.limiter.clients.lock().clients.get(&key).is_some_and(|client| {
+            client.banned_until.is_some_and(|banned_until| Instant::now() < banned_until)
+        })
+    }
+}
+
+impl<S> Layer<S> for IpRateLimitLayer {
+    type Service = IpRateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IpRateLimit { inner, limiter: self.limiter.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that rejects requests of clients that exceeded their quota or are
+/// banned.
+///
+/// See [`IpRateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct IpRateLimit<S> {
+    inner: S,
+    limiter: Arc<IpRateLimiter>,
+}
+
+impl<'a, S> RpcServiceT<'a> for IpRateLimit<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if let Some(ClientIp(ip)) = req.extensions().get::<ClientIp>().copied() {
+            let message = match self.limiter.check(ip, Instant::now()) {
+                Admission::Allowed => None,
+                Admission::RateLimited => {
+                    Some(format!("rate limit of {} exceeded for client {ip}", self.limiter.quota))
+                }
+                Admission::Banned(remaining) => Some(format!(
+                    "client {ip} is banned for another {}s for exceeding its rate limit",
+                    remaining.as_secs().max(1)
+                )),
+            };
+            if let Some(message) = message {
+                let err = ErrorObject::owned(RATE_LIMIT_EXCEEDED_CODE, message, None::<()>);
+                return ResponseFuture::ready(MethodResponse::error(req.id, err))
+            }
+        }
+
+        ResponseFuture::future(self.inner.call(req))
+    }
+}
+
+/// The token buckets and bans of the clients of an [`IpRateLimitLayer`].
+#[derive(Debug)]
+struct IpRateLimiter {
+    /// The quota of every client.
+    quota: RateQuota,
+    /// Length of the prefix that identifies IPv6 clients.
+    ipv6_prefix_len: u8,
+    /// Number of consecutively rejected requests after which a client is banned, zero if bans
+    /// are disabled.
+    ban_threshold: u32,
+    /// How long a client is banned.
+    ban_duration: Duration,
+    /// The state of the clients.
+    clients: Mutex<Clients>,
+    /// Metrics of the rejected requests.
+    metrics: IpRateLimitMetrics,
+}
+
+impl IpRateLimiter {
+    /// Returns the address the state of the client is tracked by, the network of the configured
+    /// prefix length for IPv6 clients.
+    fn client_key(&self, ip: IpAddr) -> IpAddr {
+        match ip.to_canonical() {
+            IpAddr::V4(ip) => IpAddr::V4(ip),
+            IpAddr::V6(ip) => {
+                let mask = u128::MAX.checked_shl(128 - self.ipv6_prefix_len as u32).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+            }
+        }
+    }
+
+    /// Takes a token from the bucket of the client, banning it if it exceeded its quota too often.
+    fn check(&self, ip: IpAddr, now: Instant) -> Admission {
+        let mut clients = self.clients.lock();
+        self.prune(&mut clients, now);
+
+        let client = match clients.clients.entry(self.client_key(ip)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                self.metrics.tracked_clients.increment(1);
+                entry.insert(ClientState {
+                    bucket: TokenBucket::new(self.quota, now),
+                    rejected: 0,
+                    banned_until: None,
+                })
+            }
+        };
+
+        if let Some(banned_until) = client.banned_until {
+            if now < banned_until {
+                self.metrics.banned_requests_total.increment(1);
+                return Admission::Banned(banned_until - now)
+            }
+            client.banned_until = None;
+        }
+
+        if client.bucket.try_acquire(now) {
+            client.rejected = 0;
+            return Admission::Allowed
+        }
+
+        self.metrics.rate_limited_requests_total.increment(1);
+        client.rejected += 1;
+        if self.ban_threshold > 0 && client.rejected >= self.ban_threshold {
+            warn!(target: "rpc::rate_limit", %ip, duration = ?self.ban_duration, "Banning client for exceeding its rate limit");
+            client.rejected = 0;
+            client.banned_until = Some(now + self.ban_duration);
+            self.metrics.bans_total.increment(1);
+        }
+
+        Admission::RateLimited
+    }
+
+    /// Forgets the clients that have not been seen for a whole period of the quota, and whose
+    /// buckets are therefore full again, once per period.
+    fn prune(&self, clients: &mut Clients, now: Instant) {
+        let period = self.quota.period();
+        if now.saturating_duration_since(clients.last_prune) < period {
+            return
+        }
+        clients.clients.retain(|_, client| {
+            client.banned_until.is_some_and(|banned_until| now < banned_until) ||
+                now.saturating_duration_since(client.bucket.last_refill) < period
+        });
+        clients.last_prune = now;
+        self.metrics.tracked_clients.set(clients.clients.len() as f64);
+    }
+}
+
+/// The clients tracked by an [`IpRateLimiter`].
+#[derive(Debug)]
+struct Clients {
+    /// The state of every client that was seen within the last period of the quota.
+    clients: HashMap<IpAddr, ClientState>,
+    /// When the clients were last pruned.
+    last_prune: Instant,
+}
+
+/// The rate limit state of a client.
+#[derive(Debug)]
+struct ClientState {
+    /// The token bucket of the client.
+    bucket: TokenBucket,
+    /// The number of consecutively rejected requests of the client.
+    rejected: u32,
+    /// Until when the client is banned, if it is.
+    banned_until: Option<Instant>,
+}
+
+/// Whether a request of a client is admitted by an [`IpRateLimiter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Admission {
+    /// The request is processed.
+    Allowed,
+    /// The request is rejected because the client exceeded its quota.
+    RateLimited,
+    /// The request is rejected because the client is banned for the given remaining duration.
+    Banned(Duration),
+}
+
+/// Metrics of the [`IpRateLimitLayer`].
+#[derive(Metrics)]
+#[metrics(scope = "rpc_server.ip_rate_limit")]
+struct IpRateLimitMetrics {
+    /// The number of requests rejected because their client exceeded its quota
+    rate_limited_requests_total: Counter,
+    /// The number of requests rejected because their client is banned
+    banned_requests_total: Counter,
+    /// The number of times a client was banned
+    bans_total: Counter,
+    /// The number of clients whose rate limit state is tracked
+    tracked_clients: Gauge,
+}
+
+/// Token bucket of a method or client with a [`RateQuota`].
+#[derive(Debug)]
+struct TokenBucket {
+    /// The quota of the method.
+    quota: RateQuota,
+    /// The number of calls that can currently be made.
+    tokens: f64,
+    /// When the tokens were last refilled.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket.
+    fn new(quota: RateQuota, now: Instant) -> Self {
+        Self { quota, tokens: f64::from(quota.requests().get()), last_refill: now }
+    }
+
+    /// Refills the bucket for the time that passed since the last refill and takes a token if
+    /// there is one.
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        let capacity = f64::from(self.quota.requests().get());
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens +
+            capacity * elapsed.as_secs_f64() / self.quota.period().as_secs_f64())
+        .min(capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn token_bucket_refills_over_period() {
+        let quota = RateQuota::new(2.try_into().unwrap(), Duration::from_secs(10));
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(quota, start);
+
+        assert!(bucket.try_acquire(start));
+        assert!(bucket.try_acquire(start));
+        assert!(!bucket.try_acquire(start));
+
+        // one token is refilled every 5 seconds
+        assert!(!bucket.try_acquire(start + Duration::from_secs(4)));
+        assert!(bucket.try_acquire(start + Duration::from_secs(5)));
+        assert!(!bucket.try_acquire(start + Duration::from_secs(5)));
+
+        // the bucket never holds more than the quota
+        let later = start + Duration::from_secs(100);
+        assert!(bucket.try_acquire(later));
+        assert!(bucket.try_acquire(later));
+        assert!(!bucket.try_acquire(later));
+    }
+
+    #[test]
+    fn ip_rate_limiter_bans_clients() {
+        let layer = IpRateLimitLayer::new(&IpRateLimitConfig {
+            quota: RateQuota::new(1.try_into().unwrap(), Duration::from_secs(10)),
+            ban_threshold: 2,
+            ban_duration: Duration::from_secs(60),
+            ..Default::default()
+        });
+        let limiter = &layer.limiter;
+        let client = IpAddr::from([10, 0, 0, 1]);
+        let other = IpAddr::from([10, 0, 0, 2]);
+        let start = Instant::now();
+
+        assert_eq!(limiter.check(client, start), Admission::Allowed);
+        assert_eq!(limiter.check(client, start), Admission::RateLimited);
+        // other clients have their own quota
+        assert_eq!(limiter.check(other, start), Admission::Allowed);
+
+        // an accepted request resets the rejected requests
+        let refilled = start + Duration::from_secs(10);
+        assert_eq!(limiter.check(client, refilled), Admission::Allowed);
+        assert_eq!(limiter.check(client, refilled), Admission::RateLimited);
+        assert_eq!(limiter.check(client, refilled), Admission::RateLimited);
+
+        // the client is banned even though its bucket refills
+        let banned = refilled + Duration::from_secs(20);
+        assert_eq!(limiter.check(client, banned), Admission::Banned(Duration::from_secs(40)));
+        assert_eq!(limiter.check(other, banned), Admission::Allowed);
+
+        let unbanned = refilled + Duration::from_secs(60);
+        assert_eq!(limiter.check(client, unbanned), Admission::Allowed);
+    }
+
+    #[test]
+    fn ip_rate_limiter_prunes_idle_clients() {
+        let layer = IpRateLimitLayer::new(&IpRateLimitConfig {
+            quota: RateQuota::new(1.try_into().unwrap(), Duration::from_secs(10)),
+            ban_threshold: 1,
+            ban_duration: Duration::from_secs(60),
+            ..Default::default()
+        });
+        let limiter = &layer.limiter;
+        let banned = IpAddr::from([10, 0, 0, 1]);
+        let idle = IpAddr::from([10, 0, 0, 2]);
+        let start = Instant::now();
+
+        limiter.check(banned, start);
+        limiter.check(banned, start);
+        limiter.check(idle, start);
+        assert_eq!(limiter.clients.lock().clients.len(), 2);
+
+        // banned clients are kept until their ban expires
+        limiter.check(IpAddr::from([10, 0, 0, 3]), start + Duration::from_secs(30));
+        assert!(limiter.clients.lock().clients.contains_key(&banned));
+        assert!(!limiter.clients.lock().clients.contains_key(&idle));
+    }
+
+    #[test]
+    fn ip_rate_limiter_groups_ipv6_networks() {
+        let layer = IpRateLimitLayer::new(&IpRateLimitConfig {
+            quota: RateQuota::new(1.try_into().unwrap(), Duration::from_secs(10)),
+            ban_threshold: 0,
+            ..Default::default()
+        });
+        let limiter = &layer.limiter;
+        let start = Instant::now();
+
+        // addresses of the same /64 network share a quota
+        let client: IpAddr = "2001:db8:0:1::1".parse().unwrap();
+        assert_eq!(limiter.check(client, start), Admission::Allowed);
+        assert_eq!(
+            limiter.check("2001:db8:0:1:ffff::2".parse().unwrap(), start),
+            Admission::RateLimited
+        );
+        assert_eq!(limiter.check("2001:db8:0:2::1".parse().unwrap(), start), Admission::Allowed);
+
+        // IPv4 clients are identified by their full address, even if mapped to IPv6
+        assert_eq!(limiter.check(IpAddr::from([10, 0, 0, 1]), start), Admission::Allowed);
+        assert_eq!(
+            limiter.check("::ffff:10.0.0.1".parse().unwrap(), start),
+            Admission::RateLimited
+        );
+        assert_eq!(limiter.check(IpAddr::from([10, 0, 0, 2]), start), Admission::Allowed);
+    }
+}
@@ -0,0 +1,391 @@
+//! Middleware caching the responses of idempotent RPC calls until the canonical chain changes.
+
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT, types::Request, MethodResponse, ResponsePayload,
+};
+use parking_lot::Mutex;
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use reth_primitives::{BlockId, BlockNumber, BlockNumberOrTag};
+use reth_provider::{CanonStateNotification, CanonStateNotifications};
+use schnellru::{ByLength, LruMap};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::value::RawValue;
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+use tokio::sync::broadcast::error::RecvError;
+
+/// A cache of the responses of idempotent RPC calls, shared by all servers.
+///
+/// Only calls whose response is fully determined by the canonical chain are cached:
+///
+/// - `eth_chainId`
+/// - `eth_blockNumber`, until the canonical head changes
+/// - `eth_getBlockByNumber` for an explicit block number or `earliest`
+/// - `eth_call` at an explicit block number, `earliest` or a block hash
+///
+/// Responses depending on a block are invalidated when that block is reorged out, see
+/// [`ResponseCache::on_canon_state`]. Calls at the `latest`, `pending`, `safe` and `finalized`
+/// tags are never cached. Only successful responses are cached.
+#[derive(Clone)]
+pub struct ResponseCache {
+    inner: Arc<ResponseCacheInner>,
+}
+
+impl ResponseCache {
+    /// Creates a new cache holding at most `max_entries` responses.
+    pub fn new(max_entries: u32) -> Self {
+        Self {
+            inner: Arc::new(ResponseCacheInner {
+                entries: Mutex::new(CacheEntries {
+                    responses: LruMap::new(ByLength::new(max_entries)),
+                    generation: 0,
+                }),
+                metrics: ResponseCacheMetrics::default(),
+            }),
+        }
+    }
+
+    /// Returns the number of cached responses.
+    pub fn len(&self) -> usize {
+        self.inner.entries.lock().responses.len()
+    }
+
+    /// Returns `true` if no response is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        let mut entries = self.inner.entries.lock();
+        entries.generation += 1;
+        entries.responses.clear();
+        self.inner.metrics.entries.set(0.0);
+    }
+
+    /// Removes the responses that may have changed with the canonical chain, i.e. the responses
+    /// depending on the canonical head and on blocks at or above the first block that was
+    /// committed or reverted.
+    pub fn on_canon_state(&self, notification: &CanonStateNotification) {
+        let mut first_changed = notification.committed().first().number;
+        if let Some(reverted) = notification.reverted() {
+            first_changed = first_changed.min(reverted.first().number);
+        }
+        self.invalidate(first_changed);
+    }
+
+    /// Invalidates cached responses on every canonical state notification, until the channel is
+    /// closed.
+    ///
+    /// If notifications were missed because the receiver lagged behind, the whole cache is
+    /// cleared.
+    pub async fn invalidate_on(self, mut notifications: CanonStateNotifications) {
+        loop {
+            match notifications.recv().await {
+                Ok(notification) => self.on_canon_state(&notification),
+                Err(RecvError::Lagged(_)) => self.clear(),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    fn invalidate(&self, first_changed: BlockNumber) {
+        let mut entries = self.inner.entries.lock();
+        entries.generation += 1;
+        let stale = entries
+            .responses
+            .iter()
+            .filter(|(_, response)| response.dependency.is_stale(first_changed))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in stale {
+            entries.responses.remove(&key);
+        }
+        self.inner.metrics.entries.set(entries.responses.len() as f64);
+    }
+
+    /// Returns the cached result of the call and the generation of the cache.
+    ///
+    /// The generation changes whenever responses are invalidated, so a response computed
+    /// concurrently with an invalidation must not be inserted.
+    fn get(&self, key: &CacheKey) -> (Option<Box<RawValue>>, u64) {
+        let mut entries = self.inner.entries.lock();
+        let result = entries.responses.get(key).map(|response| response.result.clone());
+        if result.is_some() {
+            self.inner.metrics.hits_total.increment(1);
+        } else {
+            self.inner.metrics.misses_total.increment(1);
+        }
+        (result, entries.generation)
+    }
+
+    /// Caches the result of the call, unless responses were invalidated since `generation`.
+    fn insert(
+        &self,
+        key: CacheKey,
+        result: Box<RawValue>,
+        dependency: Dependency,
+        generation: u64,
+    ) {
+        let mut entries = self.inner.entries.lock();
+        if entries.generation != generation {
+            return
+        }
+        entries.responses.insert(key, CachedResponse { result, dependency });
+        self.inner.metrics.entries.set(entries.responses.len() as f64);
+    }
+}
+
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseCache").field("len", &self.len()).finish_non_exhaustive()
+    }
+}
+
+struct ResponseCacheInner {
+    entries: Mutex<CacheEntries>,
+    metrics: ResponseCacheMetrics,
+}
+
+/// The cached responses, keyed by method name and raw params.
+type CacheKey = (String, String);
+
+struct CacheEntries {
+    responses: LruMap<CacheKey, CachedResponse, ByLength>,
+    /// Incremented whenever responses are invalidated.
+    generation: u64,
+}
+
+struct CachedResponse {
+    /// The raw `result` of the response.
+    result: Box<RawValue>,
+    /// The canonical state the response depends on.
+    dependency: Dependency,
+}
+
+/// The canonical state a cached response depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dependency {
+    /// The response never changes, e.g. `eth_chainId`.
+    None,
+    /// The response changes with the canonical head, e.g. `eth_blockNumber`.
+    Head,
+    /// The response changes if the canonical block with the given number changes.
+    Block(BlockNumber),
+}
+
+impl Dependency {
+    /// Returns the canonical state the response of the call depends on, or `None` if the call is
+    /// not cacheable.
+    fn of(method: &str, params: Option<&str>) -> Option<Self> {
+        match method {
+            "eth_chainId" => Some(Self::None),
+            "eth_blockNumber" => Some(Self::Head),
+            "eth_getBlockByNumber" => match param(params, 0)? {
+                BlockNumberOrTag::Number(number) => Some(Self::Block(number)),
+                BlockNumberOrTag::Earliest => Some(Self::Block(0)),
+                _ => None,
+            },
+            "eth_call" => match param(params, 1)? {
+                BlockId::Number(BlockNumberOrTag::Number(number)) => Some(Self::Block(number)),
+                BlockId::Number(BlockNumberOrTag::Earliest) => Some(Self::Block(0)),
+                // the state of a block doesn't change, but whether it is canonical does
+                BlockId::Hash(hash) if hash.require_canonical != Some(true) => Some(Self::None),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the response may have changed after the canonical chain changed from
+    /// the given block on.
+    const fn is_stale(&self, first_changed: BlockNumber) -> bool {
+        match self {
+            Self::None => false,
+            Self::Head => true,
+            Self::Block(number) => *number >= first_changed,
+        }
+    }
+}
+
+/// Deserializes the positional param at the given index, if present.
+fn param<T: DeserializeOwned>(params: Option<&str>, index: usize) -> Option<T> {
+    let params: Vec<&RawValue> = serde_json::from_str(params?).ok()?;
+    serde_json::from_str(params.get(index)?.get()).ok()
+}
+
+/// A [`Layer`](tower::Layer) that serves idempotent calls from a [`ResponseCache`].
+#[derive(Debug, Clone)]
+pub struct ResponseCacheLayer {
+    cache: ResponseCache,
+}
+
+impl ResponseCacheLayer {
+    /// Creates a new layer serving calls from the given cache.
+    pub const fn new(cache: ResponseCache) -> Self {
+        Self { cache }
+    }
+}
+
+impl<S> tower::Layer<S> for ResponseCacheLayer {
+    type Service = CachedResponses<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CachedResponses { inner, cache: self.cache.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that serves idempotent calls from a [`ResponseCache`].
+///
+/// See [`ResponseCacheLayer`].
+#[derive(Debug, Clone)]
+pub struct CachedResponses<S> {
+    inner: S,
+    cache: ResponseCache,
+}
+
+impl<'a, S> RpcServiceT<'a> for CachedResponses<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let params = req.params();
+        let Some(dependency) = Dependency::of(req.method_name(), params.as_str()) else {
+            drop(params);
+            return Box::pin(self.inner.call(req))
+        };
+        let key = (req.method_name().to_owned(), params.as_str().unwrap_or_default().to_owned());
+        drop(params);
+
+        let (cached, generation) = self.cache.get(&key);
+        if let Some(result) = cached {
+            let response =
+                MethodResponse::response(req.id, ResponsePayload::success(result), usize::MAX);
+            return Box::pin(std::future::ready(response))
+        }
+
+        let cache = self.cache.clone();
+        let call = self.inner.call(req);
+        Box::pin(async move {
+            let response = call.await;
+            if response.is_success() {
+                if let Ok(success) = serde_json::from_str::<Success>(response.as_result()) {
+                    cache.insert(key, success.result, dependency, generation);
+                }
+            }
+            response
+        })
+    }
+}
+
+/// The result of a successful response.
+#[derive(Deserialize)]
+struct Success {
+    result: Box<RawValue>,
+}
+
+/// Metrics of the [`ResponseCache`].
+#[derive(Metrics)]
+#[metrics(scope = "rpc_server.response_cache")]
+struct ResponseCacheMetrics {
+    /// The number of cacheable calls served from the cache
+    hits_total: Counter,
+    /// The number of cacheable calls that were not cached
+    misses_total: Counter,
+    /// The number of cached responses
+    entries: Gauge,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cacheable_calls() {
+        assert_eq!(Dependency::of("eth_chainId", None), Some(Dependency::None));
+        assert_eq!(Dependency::of("eth_blockNumber", Some("[]")), Some(Dependency::Head));
+        assert_eq!(
+            Dependency::of("eth_getBlockByNumber", Some(r#"["0x10", false]"#)),
+            Some(Dependency::Block(16))
+        );
+        assert_eq!(
+            Dependency::of("eth_getBlockByNumber", Some(r#"["earliest", true]"#)),
+            Some(Dependency::Block(0))
+        );
+        assert_eq!(Dependency::of("eth_getBlockByNumber", Some(r#"["latest", false]"#)), None);
+        assert_eq!(Dependency::of("eth_getBlockByNumber", Some(r#"["finalized", false]"#)), None);
+
+        let call = r#"{"to":"0x0000000000000000000000000000000000000001"}"#;
+        assert_eq!(
+            Dependency::of("eth_call", Some(&format!(r#"[{call}, "0x10"]"#))),
+            Some(Dependency::Block(16))
+        );
+        let hash = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            Dependency::of("eth_call", Some(&format!(r#"[{call}, {{"blockHash": "{hash}"}}]"#))),
+            Some(Dependency::None)
+        );
+        assert_eq!(
+            Dependency::of(
+                "eth_call",
+                Some(&format!(r#"[{call}, {{"blockHash": "{hash}", "requireCanonical": true}}]"#))
+            ),
+            None
+        );
+        assert_eq!(Dependency::of("eth_call", Some(&format!("[{call}]"))), None);
+        assert_eq!(Dependency::of("eth_call", Some(&format!(r#"[{call}, "pending"]"#))), None);
+        assert_eq!(Dependency::of("eth_getBalance", Some(r#"["0x00", "0x10"]"#)), None);
+    }
+
+    #[test]
+    fn invalidate_changed_blocks() {
+        let cache = ResponseCache::new(10);
+        let result = |value: &str| RawValue::from_string(value.to_owned()).unwrap();
+        let key = |method: &str, params: &str| (method.to_owned(), params.to_owned());
+
+        let (_, generation) = cache.get(&key("eth_chainId", ""));
+        cache.insert(key("eth_chainId", ""), result(r#""0x1""#), Dependency::None, generation);
+        cache.insert(
+            key("eth_blockNumber", "[]"),
+            result(r#""0x20""#),
+            Dependency::Head,
+            generation,
+        );
+        for number in [8, 16] {
+            let params = format!(r#"["{number:#x}",false]"#);
+            cache.insert(
+                key("eth_getBlockByNumber", &params),
+                result("null"),
+                Dependency::Block(number),
+                generation,
+            );
+        }
+        assert_eq!(cache.len(), 4);
+        assert_eq!(
+            cache.get(&key("eth_chainId", "")).0.map(|result| result.get().to_owned()),
+            Some(r#""0x1""#.to_owned())
+        );
+
+        cache.invalidate(10);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key("eth_blockNumber", "[]")).0.is_none());
+        assert!(cache.get(&key("eth_getBlockByNumber", r#"["0x8",false]"#)).0.is_some());
+        assert!(cache.get(&key("eth_getBlockByNumber", r#"["0x10",false]"#)).0.is_none());
+
+        // responses computed before the invalidation are not cached
+        cache.insert(
+            key("eth_blockNumber", "[]"),
+            result(r#""0x20""#),
+            Dependency::Head,
+            generation,
+        );
+        assert!(cache.get(&key("eth_blockNumber", "[]")).0.is_none());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Middleware limiting the number of active subscriptions across all connections of the servers.
+
+use jsonrpsee::{
+    core::server::MethodCallback,
+    server::middleware::rpc::RpcServiceT,
+    types::{error::TOO_MANY_SUBSCRIPTIONS_CODE, ErrorObject, Request},
+    MethodResponse, Methods,
+};
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tower::Layer;
+
+/// The number of subscriptions that can be active at the same time across all connections of
+/// the servers it is applied to.
+///
+/// All clones share the same budget.
+#[derive(Debug, Clone)]
+pub struct SubscriptionBudget {
+    inner: Arc<BudgetInner>,
+}
+
+#[derive(Debug)]
+struct BudgetInner {
+    /// The maximum number of active subscriptions.
+    max: usize,
+    /// The number of active and pending subscriptions.
+    active: AtomicUsize,
+}
+
+impl SubscriptionBudget {
+    /// Creates a new budget of at most `max_subscriptions` active subscriptions.
+    pub fn new(max_subscriptions: usize) -> Self {
+        Self {
+            inner: Arc::new(BudgetInner { max: max_subscriptions, active: AtomicUsize::new(0) }),
+        }
+    }
+
+    /// Returns the maximum number of active subscriptions.
+    pub fn max_subscriptions(&self) -> usize {
+        self.inner.max
+    }
+
+    /// Returns the number of active subscriptions.
+    pub fn active_subscriptions(&self) -> usize {
+        self.inner.active.load(Ordering::Acquire)
+    }
+
+    /// Reserves a subscription, returns `false` if the budget is exhausted.
+    fn try_reserve(&self) -> bool {
+        self.inner
+            .active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < self.inner.max).then_some(active + 1)
+            })
+            .is_ok()
+    }
+
+    /// Releases the given number of subscriptions.
+    fn release(&self, count: usize) {
+        self.inner.active.fetch_sub(count, Ordering::AcqRel);
+    }
+}
+
+/// A [`Layer`] that enforces a [`SubscriptionBudget`].
+///
+/// This complements the limit of subscriptions per connection of the servers, so that a client
+/// can't exhaust the server by opening many connections either. Subscriptions that exceed the
+/// budget are rejected with [`TOO_MANY_SUBSCRIPTIONS_CODE`], the same error code as subscriptions
+/// that exceed the limit of their connection.
+///
+/// A subscription is released when it is unsubscribed, or when its connection is closed.
+#[derive(Debug, Clone)]
+pub struct SubscriptionLimitLayer {
+    budget: SubscriptionBudget,
+    /// The subscribe methods of the server.
+    subscribe: Arc<HashSet<&'static str>>,
+    /// The unsubscribe methods of the server.
+    unsubscribe: Arc<HashSet<&'static str>>,
+}
+
+impl SubscriptionLimitLayer {
+    /// Creates a new layer enforcing the budget for the subscriptions of the given methods of a
+    /// server.
+    pub fn new(budget: SubscriptionBudget, methods: &Methods) -> Self {
+        let mut subscribe = HashSet::new();
+        let mut unsubscribe = HashSet::new();
+        for name in methods.method_names() {
+            match methods.method(name) {
+                Some(MethodCallback::Subscription(_)) => {
+                    subscribe.insert(name);
+                }
+                Some(MethodCallback::Unsubscription(_)) => {
+                    unsubscribe.insert(name);
+                }
+                _ => {}
+            }
+        }
+        Self { budget, subscribe: Arc::new(subscribe), unsubscribe: Arc::new(unsubscribe) }
+    }
+}
+
+impl<S> Layer<S> for SubscriptionLimitLayer {
+    type Service = SubscriptionLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SubscriptionLimit {
+            inner,
+            subscribe: self.subscribe.clone(),
+            unsubscribe: self.unsubscribe.clone(),
+            connection: Arc::new(ConnectionSubscriptions {
+                budget: self.budget.clone(),
+                ids: Default::default(),
+            }),
+        }
+    }
+}
+
+/// The subscriptions of a connection, released from the budget when the connection is closed.
+#[derive(Debug)]
+struct ConnectionSubscriptions {
+    budget: SubscriptionBudget,
+    /// The JSON encoded ids of the active subscriptions of the connection.
+    ids: Mutex<HashSet<String>>,
+}
+
+impl Drop for ConnectionSubscriptions {
+    fn drop(&mut self) {
+        self.budget.release(self.ids.get_mut().len());
+    }
+}
+
+/// A [`RpcServiceT`] middleware that tracks the subscriptions of a connection and rejects new
+/// subscriptions if the [`SubscriptionBudget`] is exhausted.
+///
+/// See [`SubscriptionLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct SubscriptionLimit<S> {
+    inner: S,
+    subscribe: Arc<HashSet<&'static str>>,
+    unsubscribe: Arc<HashSet<&'static str>>,
+    connection: Arc<ConnectionSubscriptions>,
+}
+
+impl<'a, S> RpcServiceT<'a> for SubscriptionLimit<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let inner = self.inner.clone();
+        let connection = self.connection.clone();
+
+        if self.subscribe.contains(req.method_name()) {
+            return Box::pin(async move {
+                if !connection.budget.try_reserve() {
+                    let err = ErrorObject::owned(
+                        TOO_MANY_SUBSCRIPTIONS_CODE,
+                        "Too many subscriptions on the server",
+                        Some(format!(
+                            "Exceeded max limit of {}",
+                            connection.budget.max_subscriptions()
+                        )),
+                    );
+                    return MethodResponse::error(req.id, err)
+                }
+
+                let response = inner.call(req).await;
+                match response.is_success().then(|| response_result(&response)).flatten() {
+                    Some(id) => {
+                        connection.ids.lock().insert(id.to_string());
+                    }
+                    None => connection.budget.release(1),
+                }
+                response
+            })
+        }
+
+        if self.unsubscribe.contains(req.method_name()) {
+            let id = req.params().sequence().next::<Value>().ok();
+            return Box::pin(async move {
+                let response = inner.call(req).await;
+                if let Some(id) = id {
+                    if response_result(&response) == Some(Value::Bool(true)) &&
+                        connection.ids.lock().remove(&id.to_string())
+                    {
+                        connection.budget.release(1);
+                    }
+                }
+                response
+            })
+        }
+
+        Box::pin(inner.call(req))
+    }
+}
+
+/// Returns the `result` of a JSON-RPC response.
+fn response_result(response: &MethodResponse) -> Option<Value> {
+    let mut response = serde_json::from_str::<Value>(response.as_result()).ok()?;
+    Some(response.get_mut("result")?.take())
+}
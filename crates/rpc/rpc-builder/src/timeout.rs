@@ -0,0 +1,142 @@
+//! Middleware failing RPC calls that exceed the deadline of their namespace.
+
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObject, Request},
+    MethodResponse,
+};
+use std::{collections::BTreeMap, future::Future, pin::Pin, sync::Arc, time::Duration};
+use tower::Layer;
+
+/// The error code of calls that did not finish within their deadline.
+pub const REQUEST_TIMEOUT_CODE: i32 = -32002;
+
+/// Deadlines of RPC calls, configured per namespace.
+///
+/// The namespace of a call is the prefix of its method name up to the first `_`, e.g. `debug` for
+/// `debug_traceTransaction`. Calls of namespaces without a deadline of their own use the default
+/// deadline, if any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestTimeouts {
+    /// The deadline of calls of namespaces without a deadline of their own.
+    default: Option<Duration>,
+    /// The deadlines of individual namespaces.
+    namespaces: BTreeMap<String, Duration>,
+}
+
+impl RequestTimeouts {
+    /// Sets the deadline of calls of namespaces without a deadline of their own.
+    pub const fn with_default(mut self, timeout: Duration) -> Self {
+        self.default = Some(timeout);
+        self
+    }
+
+    /// Sets the deadline of the calls of the given namespace, e.g. `debug`.
+    pub fn with_namespace(mut self, namespace: impl Into<String>, timeout: Duration) -> Self {
+        self.namespaces.insert(namespace.into(), timeout);
+        self
+    }
+
+    /// Returns the deadline of calls of namespaces without a deadline of their own.
+    pub const fn default_timeout(&self) -> Option<Duration> {
+        self.default
+    }
+
+    /// Returns the deadline of a call of the given method, if any.
+    pub fn timeout(&self, method: &str) -> Option<Duration> {
+        method
+            .split_once('_')
+            .and_then(|(namespace, _)| self.namespaces.get(namespace))
+            .copied()
+            .or(self.default)
+    }
+
+    /// Returns `true` if no deadline is configured.
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.namespaces.is_empty()
+    }
+}
+
+/// A [`Layer`] that fails calls that exceed their deadline, see [`RequestTimeouts`].
+///
+/// Calls that time out are answered with [`REQUEST_TIMEOUT_CODE`]. The call is cancelled by
+/// dropping it, work it already handed off to other tasks, e.g. blocking tracing tasks, may keep
+/// running until it completes.
+#[derive(Debug, Clone)]
+pub struct RequestTimeoutLayer {
+    timeouts: Arc<RequestTimeouts>,
+}
+
+impl RequestTimeoutLayer {
+    /// Creates a new layer enforcing the given deadlines.
+    pub fn new(timeouts: RequestTimeouts) -> Self {
+        Self { timeouts: Arc::new(timeouts) }
+    }
+}
+
+impl<S> Layer<S> for RequestTimeoutLayer {
+    type Service = RequestTimeout<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestTimeout { inner, timeouts: self.timeouts.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that fails calls that exceed their deadline.
+///
+/// See [`RequestTimeoutLayer`].
+#[derive(Debug, Clone)]
+pub struct RequestTimeout<S> {
+    inner: S,
+    timeouts: Arc<RequestTimeouts>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RequestTimeout<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let Some(timeout) = self.timeouts.timeout(req.method_name()) else {
+            return Box::pin(self.inner.call(req))
+        };
+
+        let id = req.id.clone();
+        let call = self.inner.call(req);
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, call).await {
+                Ok(response) => response,
+                Err(_) => {
+                    let err = ErrorObject::owned(
+                        REQUEST_TIMEOUT_CODE,
+                        format!("request timed out after {}ms", timeout.as_millis()),
+                        None::<()>,
+                    );
+                    MethodResponse::error(id, err)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_timeouts() {
+        let timeouts = RequestTimeouts::default();
+        assert!(timeouts.is_empty());
+        assert_eq!(timeouts.timeout("eth_call"), None);
+
+        let timeouts = timeouts.with_namespace("debug", Duration::from_secs(60));
+        assert_eq!(timeouts.timeout("debug_traceTransaction"), Some(Duration::from_secs(60)));
+        assert_eq!(timeouts.timeout("eth_call"), None);
+
+        let timeouts = timeouts.with_default(Duration::from_secs(5));
+        assert_eq!(timeouts.timeout("debug_traceTransaction"), Some(Duration::from_secs(60)));
+        assert_eq!(timeouts.timeout("eth_call"), Some(Duration::from_secs(5)));
+        assert_eq!(timeouts.timeout("debugtraceTransaction"), Some(Duration::from_secs(5)));
+    }
+}
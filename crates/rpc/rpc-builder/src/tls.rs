@@ -0,0 +1,218 @@
+//! TLS termination of the http and ws servers.
+
+use parking_lot::RwLock;
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::net::TcpStream;
+use tokio_rustls::{
+    rustls::{
+        crypto::ring,
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ServerConfig,
+    },
+    server::TlsStream,
+};
+use tracing::{debug, info, warn};
+
+/// The default interval at which the certificate and private key files are checked for changes.
+pub const DEFAULT_TLS_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long the TLS handshake of a new connection may take.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The TLS config of the http and ws servers.
+///
+/// The certificate chain and the private key are read from PEM files. The files are checked for
+/// changes periodically, and reloaded if they were modified, so that renewed certificates are used
+/// for new connections without restarting the servers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// The PEM file with the certificate chain, leaf certificate first.
+    cert_path: PathBuf,
+    /// The PEM file with the private key of the certificate.
+    key_path: PathBuf,
+    /// How often the files are checked for changes.
+    reload_interval: Duration,
+}
+
+impl TlsConfig {
+    /// Creates a new config with the given certificate chain and private key files.
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            reload_interval: DEFAULT_TLS_RELOAD_INTERVAL,
+        }
+    }
+
+    /// Sets how often the files are checked for changes.
+    ///
+    /// Defaults to [`DEFAULT_TLS_RELOAD_INTERVAL`].
+    pub const fn with_reload_interval(mut self, reload_interval: Duration) -> Self {
+        self.reload_interval = reload_interval;
+        self
+    }
+
+    /// Returns the certificate chain file.
+    pub fn cert_path(&self) -> &Path {
+        &self.cert_path
+    }
+
+    /// Returns the private key file.
+    pub fn key_path(&self) -> &Path {
+        &self.key_path
+    }
+
+    /// Returns how often the files are checked for changes.
+    pub const fn reload_interval(&self) -> Duration {
+        self.reload_interval
+    }
+}
+
+/// Accepts the TLS connections of the http and ws servers.
+#[derive(Clone)]
+pub(crate) struct TlsAcceptor {
+    acceptor: tokio_rustls::TlsAcceptor,
+    resolver: Arc<ReloadingCertResolver>,
+}
+
+impl TlsAcceptor {
+    /// Loads the certificate and private key of the config.
+    pub(crate) fn new(config: &TlsConfig) -> io::Result<Self> {
+        let resolver = Arc::new(ReloadingCertResolver::new(config.clone())?);
+        let mut server_config =
+            ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(io::Error::other)?
+                .with_no_client_auth()
+                .with_cert_resolver(resolver.clone());
+        // websocket upgrades require http/1.1
+        server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        Ok(Self { acceptor: Arc::new(server_config).into(), resolver })
+    }
+
+    /// Returns how often the certificate and private key files are checked for changes.
+    pub(crate) fn reload_interval(&self) -> Duration {
+        self.resolver.config.reload_interval
+    }
+
+    /// Reloads the certificate and private key if their files changed.
+    pub(crate) fn reload_if_changed(&self) {
+        self.resolver.reload_if_changed()
+    }
+
+    /// Performs the TLS handshake of a new connection.
+    pub(crate) async fn accept(&self, stream: TcpStream) -> io::Result<TlsStream<TcpStream>> {
+        tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, self.acceptor.accept(stream))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "TLS handshake timed out"))?
+    }
+}
+
+impl fmt::Debug for TlsAcceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsAcceptor").field("config", &self.resolver.config).finish_non_exhaustive()
+    }
+}
+
+/// Resolves the certificate of all connections to the current certificate of the files.
+#[derive(Debug)]
+struct ReloadingCertResolver {
+    config: TlsConfig,
+    /// The current certificate, and the modification times of the files it was loaded from.
+    current: RwLock<(Arc<CertifiedKey>, FileTimes)>,
+}
+
+impl ReloadingCertResolver {
+    fn new(config: TlsConfig) -> io::Result<Self> {
+        let modified = FileTimes::read(&config);
+        let key = load_certified_key(&config)?;
+        Ok(Self { config, current: RwLock::new((Arc::new(key), modified)) })
+    }
+
+    fn reload_if_changed(&self) {
+        let modified = FileTimes::read(&self.config);
+        if self.current.read().1 == modified {
+            return
+        }
+
+        match load_certified_key(&self.config) {
+            Ok(key) => {
+                info!(target: "rpc", cert = ?self.config.cert_path, "Reloaded TLS certificate");
+                *self.current.write() = (Arc::new(key), modified);
+            }
+            Err(err) => {
+                // keep serving the previous certificate, the files might be written partially
+                warn!(target: "rpc", %err, cert = ?self.config.cert_path, "Failed to reload TLS certificate");
+                self.current.write().1 = modified;
+            }
+        }
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().0.clone())
+    }
+}
+
+/// The modification times of the certificate and private key files, if they can be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileTimes {
+    cert: Option<SystemTime>,
+    key: Option<SystemTime>,
+}
+
+impl FileTimes {
+    fn read(config: &TlsConfig) -> Self {
+        let modified = |path: &Path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .inspect_err(
+                    |err| debug!(target: "rpc", %err, ?path, "Failed to read modification time"),
+                )
+                .ok()
+        };
+        Self { cert: modified(&config.cert_path), key: modified(&config.key_path) }
+    }
+}
+
+/// Reads the certificate chain and private key files of the config.
+fn load_certified_key(config: &TlsConfig) -> io::Result<CertifiedKey> {
+    let TlsConfig { cert_path, key_path, .. } = config;
+
+    let mut certs = BufReader::new(File::open(cert_path).map_err(|err| with_path(cert_path, err))?);
+    let certs = rustls_pemfile::certs(&mut certs)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| with_path(cert_path, err))?;
+    if certs.is_empty() {
+        return Err(invalid_data(cert_path, "no certificate found"))
+    }
+
+    let mut key = BufReader::new(File::open(key_path).map_err(|err| with_path(key_path, err))?);
+    let key = rustls_pemfile::private_key(&mut key)
+        .map_err(|err| with_path(key_path, err))?
+        .ok_or_else(|| invalid_data(key_path, "no private key found"))?;
+    let key = ring::sign::any_supported_type(&key)
+        .map_err(|err| invalid_data(key_path, &err.to_string()))?;
+
+    Ok(CertifiedKey::new(certs, key))
+}
+
+/// Adds the path of the file to an error reading it.
+fn with_path(path: &Path, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {err}", path.display()))
+}
+
+/// Returns an error for a file with invalid contents.
+fn invalid_data(path: &Path, msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {msg}", path.display()))
+}
@@ -0,0 +1,62 @@
+//! Middleware rejecting the calls of disabled RPC namespaces.
+
+use jsonrpsee::{
+    server::middleware::rpc::{ResponseFuture, RpcServiceT},
+    types::{error::METHOD_NOT_FOUND_CODE, ErrorObject, Request},
+    MethodResponse,
+};
+use reth_rpc_server_types::RpcModuleToggle;
+use tower::Layer;
+
+/// A [`Layer`] that rejects the calls of namespaces disabled with the [`RpcModuleToggle`].
+///
+/// Calls of disabled namespaces are rejected with [`METHOD_NOT_FOUND_CODE`], as if the namespace
+/// was not installed.
+#[derive(Debug, Clone)]
+pub struct RpcModuleToggleLayer {
+    toggle: RpcModuleToggle,
+}
+
+impl RpcModuleToggleLayer {
+    /// Creates a new layer for the given toggle.
+    pub const fn new(toggle: RpcModuleToggle) -> Self {
+        Self { toggle }
+    }
+}
+
+impl<S> Layer<S> for RpcModuleToggleLayer {
+    type Service = RpcModuleToggleService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcModuleToggleService { inner, toggle: self.toggle.clone() }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that rejects the calls of disabled namespaces.
+///
+/// See [`RpcModuleToggleLayer`].
+#[derive(Debug, Clone)]
+pub struct RpcModuleToggleService<S> {
+    inner: S,
+    toggle: RpcModuleToggle,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcModuleToggleService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if !self.toggle.is_method_enabled(req.method_name()) {
+            let err = ErrorObject::owned(
+                METHOD_NOT_FOUND_CODE,
+                format!("the namespace of method {} is disabled", req.method_name()),
+                None::<()>,
+            );
+            return ResponseFuture::ready(MethodResponse::error(req.id, err))
+        }
+
+        ResponseFuture::future(self.inner.call(req))
+    }
+}
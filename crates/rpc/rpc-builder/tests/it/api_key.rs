@@ -0,0 +1,128 @@
+//! API key authentication tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use http::{HeaderMap, HeaderValue};
+use jsonrpsee::{
+    core::client::{ClientT, Error},
+    http_client::HttpClientBuilder,
+    rpc_params,
+    ws_client::WsClientBuilder,
+};
+use reth_config::{ApiKeyConfig, ApiKeyKind};
+use reth_primitives::hex;
+use reth_rpc_builder::{
+    api_key::{API_KEY_HEADER, METHOD_NOT_ALLOWED_CODE},
+    ApiKeys, RpcServerConfig, RpcServerHandle, TransportRpcModuleConfig,
+};
+use reth_rpc_server_types::RethRpcModule;
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Launches http and ws servers on the same port with a static `wallet` key that can call all
+/// methods and a signed `indexer` key that can only call `web3_clientVersion`.
+async fn launch_with_api_keys() -> RpcServerHandle {
+    let server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3])
+            .with_http(vec![RethRpcModule::Web3]),
+    );
+    let keys = BTreeMap::from([
+        (
+            "wallet".to_string(),
+            ApiKeyConfig {
+                secret: "wallet-secret".to_string(),
+                kind: ApiKeyKind::Static,
+                methods: vec![],
+            },
+        ),
+        (
+            "indexer".to_string(),
+            ApiKeyConfig {
+                secret: "indexer-secret".to_string(),
+                kind: ApiKeyKind::Hmac,
+                methods: vec!["web3_clientVersion".to_string()],
+            },
+        ),
+    ]);
+    let addr = test_address();
+    server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(addr)
+                .with_http(Default::default())
+                .with_http_address(addr)
+                .with_api_keys(ApiKeys::new(&keys)),
+        )
+        .await
+        .unwrap()
+}
+
+/// Returns a token of the `indexer` key that expires in an hour.
+fn indexer_token() -> String {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let expiry = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 60 * 60;
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"indexer-secret").unwrap();
+    mac.update(format!("indexer.{expiry}").as_bytes());
+    format!("indexer.{expiry}.{}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn api_key_headers(key: &str) -> HeaderMap {
+    HeaderMap::from_iter([(API_KEY_HEADER.parse().unwrap(), HeaderValue::from_str(key).unwrap())])
+}
+
+async fn client_version<C: ClientT>(client: &C) -> Result<String, Error> {
+    client.request("web3_clientVersion", rpc_params![]).await
+}
+
+async fn sha3<C: ClientT>(client: &C) -> Result<String, Error> {
+    client.request("web3_sha3", rpc_params!["0x"]).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_api_keys() {
+    let handle = launch_with_api_keys().await;
+    let url = handle.http_url().unwrap();
+
+    // requests without a valid key are rejected
+    let client = handle.http_client().unwrap();
+    assert!(matches!(client_version(&client).await, Err(Error::Transport(_))));
+    let client =
+        HttpClientBuilder::default().set_headers(api_key_headers("unknown")).build(&url).unwrap();
+    assert!(matches!(client_version(&client).await, Err(Error::Transport(_))));
+
+    let client = HttpClientBuilder::default()
+        .set_headers(api_key_headers("wallet-secret"))
+        .build(&url)
+        .unwrap();
+    client_version(&client).await.unwrap();
+    sha3(&client).await.unwrap();
+
+    let client = HttpClientBuilder::default()
+        .set_headers(api_key_headers(&indexer_token()))
+        .build(&url)
+        .unwrap();
+    client_version(&client).await.unwrap();
+    let Err(Error::Call(err)) = sha3(&client).await else { panic!("expected call error") };
+    assert_eq!(err.code(), METHOD_NOT_ALLOWED_CODE);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ws_api_keys() {
+    let handle = launch_with_api_keys().await;
+    let url = handle.ws_url().unwrap();
+
+    // the connection of clients without a valid key is rejected
+    assert!(WsClientBuilder::default().build(&url).await.is_err());
+
+    let client = WsClientBuilder::default()
+        .set_headers(api_key_headers(&indexer_token()))
+        .build(&url)
+        .await
+        .unwrap();
+    client_version(&client).await.unwrap();
+    let Err(Error::Call(err)) = sha3(&client).await else { panic!("expected call error") };
+    assert_eq!(err.code(), METHOD_NOT_ALLOWED_CODE);
+}
@@ -1,15 +1,22 @@
 //! Auth server tests
 
-use crate::utils::launch_auth;
-use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
+use crate::utils::{launch_auth, launch_auth_module, test_address, test_auth_module};
+use jsonrpsee::{
+    core::client::{ClientT, SubscriptionClientT},
+    http_client::HttpClientBuilder,
+    rpc_params, RpcModule,
+};
 use reth_ethereum_engine_primitives::EthEngineTypes;
 use reth_primitives::{Block, U64};
 use reth_rpc_api::clients::EngineApiClient;
-use reth_rpc_layer::JwtSecret;
+use reth_rpc_builder::{auth::AuthServerConfig, IpcServerBuilder, IpcSocketPermissions};
+use reth_rpc_layer::{AuthClientLayer, JwtSecret};
 use reth_rpc_types::engine::{ForkchoiceState, PayloadId, TransitionConfiguration};
 use reth_rpc_types_compat::engine::payload::{
     block_to_payload_v1, convert_block_to_payload_input_v2,
 };
+use std::time::Duration;
+
 #[allow(unused_must_use)]
 async fn test_basic_engine_calls<C>(client: &C)
 where
@@ -45,3 +52,151 @@ async fn test_auth_endpoints_ws() {
     let client = handle.ws_client().await;
     test_basic_engine_calls(&client).await
 }
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_endpoints_ipc() {
+    use std::os::unix::fs::PermissionsExt;
+
+    reth_tracing::init_test_tracing();
+    let dir = tempfile::tempdir().unwrap();
+    let endpoint = dir.path().join("engine.ipc").to_string_lossy().into_owned();
+    let config =
+        AuthServerConfig::builder(JwtSecret::random())
+            .socket_addr(test_address())
+            .ipc_endpoint(endpoint.clone())
+            .with_ipc_config(IpcServerBuilder::default().socket_permissions(IpcSocketPermissions {
+                mode: Some(0o600),
+                ..Default::default()
+            }))
+            .build();
+    let handle = test_auth_module().start_server(config).await.unwrap();
+    assert_eq!(handle.ipc_endpoint(), Some(endpoint.clone()));
+    let mode = std::fs::metadata(&endpoint).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // no JWT is required over IPC
+    let client = handle.ipc_client().await.unwrap();
+    test_basic_engine_calls(&client).await;
+
+    // the IPC server is stopped with the auth server
+    let ipc_handle = handle.ipc_handle().unwrap();
+    handle.stop().unwrap();
+    ipc_handle.stopped().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_custom_namespace() {
+    reth_tracing::init_test_tracing();
+    let mut custom = RpcModule::new(());
+    custom.register_method("custom_privileged", |_, _, _| "ok").unwrap();
+
+    let mut module = test_auth_module();
+    module.merge_configured(custom.clone()).unwrap();
+    // methods can't be merged twice
+    assert!(module.merge_configured(custom).is_err());
+
+    let secret = JwtSecret::random();
+    let handle = launch_auth_module(secret, module).await;
+
+    let response: String =
+        handle.http_client().request("custom_privileged", rpc_params![]).await.unwrap();
+    assert_eq!(response, "ok");
+    let response: String =
+        handle.ws_client().await.request("custom_privileged", rpc_params![]).await.unwrap();
+    assert_eq!(response, "ok");
+
+    // requests without a jwt are rejected
+    let client = HttpClientBuilder::default().build(handle.http_url()).unwrap();
+    assert!(client.request::<String, _>("custom_privileged", rpc_params![]).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_secret_rotation() {
+    reth_tracing::init_test_tracing();
+    const OLD: &str = "f79ae8046bc11c9927afe911db7143c51a806c4a537cc08e0d37140b0192f430";
+    const NEW: &str = "7365637265747365637265747365637265747365637265747365637265747365";
+    let (old, new) = (JwtSecret::from_hex(OLD).unwrap(), JwtSecret::from_hex(NEW).unwrap());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("jwt.hex");
+    std::fs::write(&path, OLD).unwrap();
+
+    let mut module = test_auth_module();
+    let mut custom = RpcModule::new(());
+    custom.register_method("custom_privileged", |_, _, _| "ok").unwrap();
+    module.merge_configured(custom).unwrap();
+    let config = AuthServerConfig::builder(old)
+        .socket_addr(test_address())
+        .secret_file(path.clone())
+        .secret_reload_interval(Duration::from_millis(50))
+        .build();
+    let handle = module.start_server(config).await.unwrap();
+
+    let client = |secret: JwtSecret| {
+        HttpClientBuilder::default()
+            .set_http_middleware(tower::ServiceBuilder::new().layer(AuthClientLayer::new(secret)))
+            .build(handle.http_url())
+            .unwrap()
+    };
+    let call = |secret: JwtSecret| {
+        let client = client(secret);
+        async move { client.request::<String, _>("custom_privileged", rpc_params![]).await }
+    };
+    assert!(call(old).await.is_ok());
+    assert!(call(new).await.is_err());
+
+    // both secrets are accepted during the rotation
+    std::fs::write(&path, format!("{NEW}\n{OLD}\n")).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(call(old).await.is_ok());
+    assert!(call(new).await.is_ok());
+
+    // and only the new one once the old secret is removed
+    std::fs::write(&path, NEW).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(call(old).await.is_err());
+    assert!(call(new).await.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_secret_reload_on_demand() {
+    const OLD: &str = "f79ae8046bc11c9927afe911db7143c51a806c4a537cc08e0d37140b0192f430";
+    const NEW: &str = "7365637265747365637265747365637265747365637265747365637265747365";
+    let (old, new) = (JwtSecret::from_hex(OLD).unwrap(), JwtSecret::from_hex(NEW).unwrap());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("jwt.hex");
+    std::fs::write(&path, OLD).unwrap();
+
+    let config = AuthServerConfig::builder(old)
+        .socket_addr(test_address())
+        .secret_file(path.clone())
+        .secret_reload_interval(Duration::from_secs(60 * 60))
+        .build();
+    let secret_file = config.secret_file().unwrap().clone();
+    let mut module = test_auth_module();
+    let mut custom = RpcModule::new(());
+    custom.register_method("custom_privileged", |_, _, _| "ok").unwrap();
+    module.merge_configured(custom).unwrap();
+    let handle = module.start_server(config).await.unwrap();
+
+    let call = |secret: JwtSecret| {
+        let client = HttpClientBuilder::default()
+            .set_http_middleware(tower::ServiceBuilder::new().layer(AuthClientLayer::new(secret)))
+            .build(handle.http_url())
+            .unwrap();
+        async move { client.request::<String, _>("custom_privileged", rpc_params![]).await }
+    };
+    assert!(call(old).await.is_ok());
+
+    // the secrets are only replaced by reloading the file
+    std::fs::write(&path, NEW).unwrap();
+    assert!(call(new).await.is_err());
+    assert_eq!(secret_file.reload().unwrap(), 1);
+    assert!(call(old).await.is_err());
+    assert!(call(new).await.is_ok());
+
+    // an invalid file keeps the current secret
+    std::fs::write(&path, "invalid").unwrap();
+    assert!(secret_file.reload().is_err());
+    assert!(call(new).await.is_ok());
+}
@@ -0,0 +1,89 @@
+//! Batch request limit tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use jsonrpsee::{
+    core::{client::ClientT, params::BatchRequestBuilder},
+    rpc_params,
+    types::error::TOO_BIG_BATCH_REQUEST_CODE,
+    RpcModule,
+};
+use reth_rpc_builder::{
+    BatchCostBudget, BatchCostExceeded, RpcServerConfig, TransportRpcModuleConfig,
+};
+use reth_rpc_server_types::RethRpcModule;
+
+/// Returns a module with a cheap `test_cheap` and an expensive `test_expensive` method.
+fn batch_module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module.register_method("test_cheap", |_, _, _| "cheap").unwrap();
+    module.register_method("test_expensive", |_, _, _| "expensive").unwrap();
+    module
+}
+
+/// Returns a batch calling the given methods.
+fn batch(methods: &[&'static str]) -> BatchRequestBuilder<'static> {
+    let mut batch = BatchRequestBuilder::new();
+    for method in methods {
+        batch.insert(method, rpc_params![]).unwrap();
+    }
+    batch
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_batch_limits() {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    server.merge_http(batch_module()).unwrap();
+
+    let budget = BatchCostBudget::new(25).with_method_costs([("test_expensive".to_string(), 10)]);
+    let handle = server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_max_batch_size(Some(4))
+                .with_batch_cost_budget(Some(budget)),
+        )
+        .await
+        .unwrap();
+    let client = handle.http_client().unwrap();
+
+    // the batch fits into the budget
+    let response = client
+        .batch_request::<String>(batch(&["test_expensive", "test_expensive", "test_cheap"]))
+        .await
+        .unwrap();
+    assert_eq!(response.num_successful_calls(), 3);
+
+    // the third call exceeds the budget, and so does the fourth
+    let response = client
+        .batch_request::<String>(batch(&[
+            "test_expensive",
+            "test_expensive",
+            "test_expensive",
+            "test_cheap",
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(response.num_successful_calls(), 2);
+    let errors = response.into_iter().filter_map(Result::err).collect::<Vec<_>>();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].code(), TOO_BIG_BATCH_REQUEST_CODE);
+    let exceeded: BatchCostExceeded =
+        serde_json::from_str(errors[0].data().unwrap().get()).unwrap();
+    assert_eq!(
+        exceeded,
+        BatchCostExceeded { index: 2, method: "test_expensive".to_string(), cost: 30, budget: 25 }
+    );
+
+    // batches with too many calls are rejected as a whole
+    let result = client
+        .batch_request::<String>(batch(&[
+            "test_cheap",
+            "test_cheap",
+            "test_cheap",
+            "test_cheap",
+            "test_cheap",
+        ]))
+        .await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,86 @@
+//! Http response compression tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use jsonrpsee::{types::ErrorObjectOwned, RpcModule};
+use reth_rpc_builder::{RpcServerConfig, RpcServerHandle, TransportRpcModuleConfig};
+use reth_rpc_server_types::RethRpcModule;
+use std::net::SocketAddr;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Sends a `test_large` request with the given `Accept-Encoding` header and returns the headers of
+/// the response, in lowercase.
+async fn response_headers(addr: SocketAddr, accept_encoding: Option<&str>) -> String {
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"test_large","params":[]}"#;
+    let accept_encoding = accept_encoding
+        .map(|encoding| format!("Accept-Encoding: {encoding}\r\n"))
+        .unwrap_or_default();
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n{accept_encoding}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+
+    let response = String::from_utf8_lossy(&response);
+    response.split("\r\n\r\n").next().unwrap().to_lowercase()
+}
+
+/// Launches a http server with a `test_large` method, compressing responses of at least
+/// `min_size` bytes.
+async fn launch_http(min_size: Option<u16>) -> RpcServerHandle {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    let mut module = RpcModule::new(());
+    module
+        .register_method("test_large", |_, _, _| Ok::<_, ErrorObjectOwned>("a".repeat(4096)))
+        .unwrap();
+    server.merge_http(module).unwrap();
+
+    server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_http_compression(min_size),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_compression() {
+    let handle = launch_http(Some(1024)).await;
+    let addr = handle.http_local_addr().unwrap();
+
+    let headers = response_headers(addr, Some("gzip")).await;
+    assert!(headers.contains("content-encoding: gzip"), "{headers}");
+
+    let headers = response_headers(addr, Some("br")).await;
+    assert!(headers.contains("content-encoding: br"), "{headers}");
+
+    // unsupported or no accepted encodings
+    let headers = response_headers(addr, Some("deflate")).await;
+    assert!(!headers.contains("content-encoding"), "{headers}");
+    let headers = response_headers(addr, None).await;
+    assert!(!headers.contains("content-encoding"), "{headers}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_compression_threshold() {
+    // the response is smaller than the threshold
+    let handle = launch_http(Some(8192)).await;
+    let addr = handle.http_local_addr().unwrap();
+    let headers = response_headers(addr, Some("gzip")).await;
+    assert!(!headers.contains("content-encoding"), "{headers}");
+
+    // compression is disabled
+    let handle = launch_http(None).await;
+    let addr = handle.http_local_addr().unwrap();
+    let headers = response_headers(addr, Some("gzip")).await;
+    assert!(!headers.contains("content-encoding"), "{headers}");
+}
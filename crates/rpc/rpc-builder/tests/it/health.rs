@@ -0,0 +1,69 @@
+//! Health endpoint tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use reth_config::HealthConfig;
+use reth_network_api::noop::NoopNetwork;
+use reth_provider::test_utils::NoopProvider;
+use reth_rpc_builder::{
+    HealthLayer, NodeHealthCheck, RpcServerConfig, RpcServerHandle, TransportRpcModuleConfig,
+};
+use reth_rpc_server_types::RethRpcModule;
+use std::net::SocketAddr;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Sends a request with the given method and path and returns the response.
+async fn request(addr: SocketAddr, method: &str, path: &str) -> String {
+    let request =
+        format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+/// Launches a http server reporting the health of a node without blocks and peers.
+async fn launch_http(config: HealthConfig) -> RpcServerHandle {
+    let server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    let check = NodeHealthCheck::new(NoopProvider::default(), NoopNetwork::default());
+    server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_health(HealthLayer::new(check, config)),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_health() {
+    let handle = launch_http(HealthConfig::default()).await;
+    let addr = handle.http_local_addr().unwrap();
+
+    let health =
+        r#"{"syncing":false,"latestBlock":0,"latestBlockAge":null,"peers":0,"database":true}"#;
+    for path in ["/health", "/ready"] {
+        let response = request(addr, "GET", path).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.to_lowercase().contains("content-type: application/json"), "{response}");
+        assert!(response.ends_with(health), "{response}");
+    }
+
+    // other requests are handled by the JSON-RPC server
+    assert!(!request(addr, "POST", "/health").await.contains(health));
+    assert!(!request(addr, "GET", "/").await.contains(health));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_not_ready() {
+    let handle = launch_http(HealthConfig { min_peers: 1, max_block_age: None }).await;
+    let addr = handle.http_local_addr().unwrap();
+
+    assert!(request(addr, "GET", "/health").await.starts_with("HTTP/1.1 200 OK"));
+    assert!(request(addr, "GET", "/ready").await.starts_with("HTTP/1.1 503 Service Unavailable"));
+}
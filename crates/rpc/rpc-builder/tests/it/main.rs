@@ -1,7 +1,17 @@
+mod api_key;
 mod auth;
+mod batch;
+mod compression;
+mod health;
 mod http;
+mod middleware;
+mod rate_limit;
 mod serde;
+mod shutdown;
 mod startup;
+mod subscriptions;
+mod tls;
+mod toggle;
 pub mod utils;
 
 const fn main() {}
@@ -0,0 +1,75 @@
+//! Custom http middleware tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use http::{HeaderMap, HeaderValue};
+use jsonrpsee::{
+    core::client::ClientT, http_client::HttpClientBuilder, rpc_params, server::HttpRequest,
+    ws_client::WsClientBuilder,
+};
+use reth_rpc_builder::{HttpMiddlewareLayer, RpcServerConfig, TransportRpcModuleConfig};
+use reth_rpc_server_types::RethRpcModule;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tower::{filter::FilterLayer, util::MapRequestLayer, BoxError};
+
+const TENANT_HEADER: &str = "x-tenant";
+
+fn tenant_headers() -> HeaderMap {
+    HeaderMap::from_iter([(TENANT_HEADER.parse().unwrap(), HeaderValue::from_static("test"))])
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_middleware_layers() {
+    let server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3])
+            .with_http(vec![RethRpcModule::Web3]),
+    );
+
+    // the outer layer rejects requests without the tenant header before they reach the inner one
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    let middleware = HttpMiddlewareLayer::default()
+        .layer(FilterLayer::new(|req: HttpRequest| {
+            if req.headers().contains_key(TENANT_HEADER) {
+                Ok(req)
+            } else {
+                Err(BoxError::from("missing tenant"))
+            }
+        }))
+        .layer(MapRequestLayer::new(move |req: HttpRequest| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            req
+        }));
+
+    let handle = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(test_address())
+                .with_http(Default::default())
+                .with_http_address(test_address())
+                .with_http_middleware(middleware),
+        )
+        .await
+        .unwrap();
+
+    let client = handle.http_client().unwrap();
+    assert!(client.request::<String, _>("web3_clientVersion", rpc_params![]).await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+    let client = HttpClientBuilder::default()
+        .set_headers(tenant_headers())
+        .build(handle.http_url().unwrap())
+        .unwrap();
+    client.request::<String, _>("web3_clientVersion", rpc_params![]).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    // the layers also apply to the upgrade requests of the ws server
+    let url = handle.ws_url().unwrap();
+    assert!(WsClientBuilder::default().build(&url).await.is_err());
+    let client =
+        WsClientBuilder::default().set_headers(tenant_headers()).build(&url).await.unwrap();
+    client.request::<String, _>("web3_clientVersion", rpc_params![]).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
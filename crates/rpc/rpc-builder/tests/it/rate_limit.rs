@@ -0,0 +1,409 @@
+//! Rate limit tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use http::{HeaderMap, HeaderValue};
+use jsonrpsee::{
+    core::client::{ClientT, Error},
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+    types::ErrorObjectOwned,
+    RpcModule,
+};
+use reth_config::{IpRateLimitConfig, ProxyConfig, RateQuota};
+use reth_ipc::client::IpcClientBuilder;
+use reth_rpc_builder::{
+    rate_limit::RATE_LIMIT_EXCEEDED_CODE, RpcServerConfig, TransportRpcModuleConfig,
+};
+use reth_rpc_server_types::RethRpcModule;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Number of requests sent concurrently in every test.
+const REQUESTS: usize = 8;
+
+/// Records how many requests are processed at the same time.
+#[derive(Debug, Default)]
+struct Concurrency {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl Concurrency {
+    fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// Returns a module with a `test_slow` method that takes a while to respond and records the number
+/// of concurrent calls in the returned [`Concurrency`].
+fn slow_module() -> (RpcModule<Arc<Concurrency>>, Arc<Concurrency>) {
+    let concurrency = Arc::new(Concurrency::default());
+    let mut module = RpcModule::new(concurrency.clone());
+    module
+        .register_async_method("test_slow", |_, concurrency, _| async move {
+            let in_flight = concurrency.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            concurrency.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            concurrency.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok::<_, ErrorObjectOwned>(in_flight)
+        })
+        .unwrap();
+    (module, concurrency)
+}
+
+/// Sends [`REQUESTS`] concurrent `test_slow` requests with the given client.
+async fn send_concurrent<C>(client: Arc<C>)
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    let handles = (0..REQUESTS)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client.request::<usize, _>("test_slow", rpc_params![]).await.unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_max_concurrent_requests() {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    let (module, concurrency) = slow_module();
+    server.merge_http(module).unwrap();
+
+    let handle = server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_http_max_concurrent_requests(Some(2)),
+        )
+        .await
+        .unwrap();
+
+    send_concurrent(Arc::new(handle.http_client().unwrap())).await;
+    assert_eq!(concurrency.max_in_flight(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ws_max_concurrent_requests_is_per_transport() {
+    let mut server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3])
+            .with_http(vec![RethRpcModule::Web3]),
+    );
+    let (ws_module, ws_concurrency) = slow_module();
+    server.merge_ws(ws_module).unwrap();
+    let (http_module, http_concurrency) = slow_module();
+    server.merge_http(http_module).unwrap();
+
+    let handle = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(test_address())
+                .with_ws_max_concurrent_requests(Some(1))
+                .with_http(Default::default())
+                // a different address than the ws server, so that the servers don't share a port
+                .with_http_address(SocketAddr::from(([127, 0, 0, 1], 0))),
+        )
+        .await
+        .unwrap();
+
+    send_concurrent(Arc::new(handle.ws_client().await.unwrap())).await;
+    assert_eq!(ws_concurrency.max_in_flight(), 1);
+
+    // the http server is not limited
+    send_concurrent(Arc::new(handle.http_client().unwrap())).await;
+    assert!(http_concurrency.max_in_flight() > 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_same_port_max_concurrent_requests() {
+    let mut server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3])
+            .with_http(vec![RethRpcModule::Web3]),
+    );
+    let (module, concurrency) = slow_module();
+    server.merge_configured(module).unwrap();
+
+    let addr = test_address();
+    let handle = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(addr)
+                .with_http(Default::default())
+                .with_http_address(addr)
+                .with_max_concurrent_requests(Some(3)),
+        )
+        .await
+        .unwrap();
+
+    // requests of both transports share the limit of the combined server
+    let ws_client = Arc::new(handle.ws_client().await.unwrap());
+    tokio::join!(
+        send_concurrent(ws_client),
+        send_concurrent(Arc::new(handle.http_client().unwrap()))
+    );
+    assert_eq!(concurrency.max_in_flight(), 3);
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ipc_max_concurrent_requests() {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_ipc(vec![RethRpcModule::Web3]));
+    let (module, concurrency) = slow_module();
+    server.merge_ipc(module).unwrap();
+
+    let endpoint = std::env::temp_dir()
+        .join(format!("reth-rate-limit-{}.ipc", std::process::id()))
+        .to_string_lossy()
+        .into_owned();
+    let _handle = server
+        .start_server(
+            RpcServerConfig::ipc(Default::default())
+                .with_ipc_endpoint(endpoint.clone())
+                .with_ipc_max_concurrent_requests(Some(2)),
+        )
+        .await
+        .unwrap();
+
+    let client = IpcClientBuilder::default().build(&endpoint).await.unwrap();
+    send_concurrent(Arc::new(client)).await;
+    assert_eq!(concurrency.max_in_flight(), 2);
+}
+
+/// Returns a module with the `test_limited` and `test_unlimited` methods.
+fn ping_module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module.register_method("test_limited", |_, _, _| "pong").unwrap();
+    module.register_method("test_unlimited", |_, _, _| "pong").unwrap();
+    module
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_method_rate_limits() {
+    let mut server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3])
+            .with_http(vec![RethRpcModule::Web3]),
+    );
+    server.merge_configured(ping_module()).unwrap();
+
+    let quota = RateQuota::new(2.try_into().unwrap(), Duration::from_secs(60 * 60));
+    let handle = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(test_address())
+                .with_http(Default::default())
+                .with_http_address(test_address())
+                .with_method_rate_limits([("test_limited".to_string(), quota)]),
+        )
+        .await
+        .unwrap();
+    let http_client = handle.http_client().unwrap();
+    let ws_client = handle.ws_client().await.unwrap();
+
+    // the quota is shared by both transports
+    http_client.request::<String, _>("test_limited", rpc_params![]).await.unwrap();
+    ws_client.request::<String, _>("test_limited", rpc_params![]).await.unwrap();
+    for result in [
+        http_client.request::<String, _>("test_limited", rpc_params![]).await,
+        ws_client.request::<String, _>("test_limited", rpc_params![]).await,
+    ] {
+        let Err(Error::Call(err)) = result else { panic!("expected rate limit error: {result:?}") };
+        assert_eq!(err.code(), RATE_LIMIT_EXCEEDED_CODE);
+    }
+
+    // other methods are not limited
+    for _ in 0..REQUESTS {
+        http_client.request::<String, _>("test_unlimited", rpc_params![]).await.unwrap();
+    }
+}
+
+/// Calls the unlimited method of the [`ping_module`].
+async fn ping<C: ClientT>(client: &C) -> Result<String, Error> {
+    client.request("test_unlimited", rpc_params![]).await
+}
+
+/// Asserts that the call was rejected by a rate limit and returns the error message.
+fn rate_limit_error(result: Result<String, Error>) -> String {
+    let Err(Error::Call(err)) = result else { panic!("expected rate limit error: {result:?}") };
+    assert_eq!(err.code(), RATE_LIMIT_EXCEEDED_CODE);
+    err.message().to_string()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ip_rate_limit() {
+    let mut server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3])
+            .with_http(vec![RethRpcModule::Web3]),
+    );
+    server.merge_configured(ping_module()).unwrap();
+
+    let config = IpRateLimitConfig {
+        quota: RateQuota::new(2.try_into().unwrap(), Duration::from_secs(60 * 60)),
+        ban_threshold: 2,
+        ban_duration: Duration::from_secs(60 * 60),
+        ..Default::default()
+    };
+    let proxy =
+        ProxyConfig { trusted_proxies: vec!["127.0.0.0/8".parse().unwrap()], ..Default::default() };
+    let addr = test_address();
+    let handle = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(addr)
+                .with_http(Default::default())
+                .with_http_address(addr)
+                .with_proxy(&proxy)
+                .unwrap()
+                .with_ip_rate_limit(&config),
+        )
+        .await
+        .unwrap();
+    let url = handle.http_url().unwrap();
+    let proxied_client = |forwarded_for: &'static str| -> HttpClient {
+        HttpClientBuilder::default()
+            .set_headers(HeaderMap::from_iter([(
+                "X-Forwarded-For".parse().unwrap(),
+                HeaderValue::from_static(forwarded_for),
+            )]))
+            .build(&url)
+            .unwrap()
+    };
+
+    // the last address of the header identifies the client
+    let client = proxied_client("10.0.0.1");
+    let spoofed = proxied_client("10.0.0.1, 10.0.0.2");
+    ping(&client).await.unwrap();
+    ping(&client).await.unwrap();
+    assert!(rate_limit_error(ping(&client).await).contains("exceeded for client"));
+    ping(&spoofed).await.unwrap();
+
+    // the client is banned after exceeding its quota twice in a row
+    assert!(rate_limit_error(ping(&client).await).contains("exceeded for client"));
+    assert!(rate_limit_error(ping(&client).await).contains("banned"));
+
+    // requests without the header are limited by the address of their connection, shared by
+    // both transports
+    let ws_client = handle.ws_client().await.unwrap();
+    ping(&ws_client).await.unwrap();
+    ping(&handle.http_client().unwrap()).await.unwrap();
+    rate_limit_error(ping(&ws_client).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ip_rate_limit_behind_trusted_proxy() {
+    let config = IpRateLimitConfig {
+        quota: RateQuota::new(1.try_into().unwrap(), Duration::from_secs(60 * 60)),
+        ban_threshold: 0,
+        ..Default::default()
+    };
+    let launch = |trusted_proxy: &str| {
+        let proxy = ProxyConfig {
+            trusted_proxies: vec![trusted_proxy.parse().unwrap()],
+            ..Default::default()
+        };
+        let mut server =
+            test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+        server.merge_configured(ping_module()).unwrap();
+        let server_config = RpcServerConfig::http(Default::default())
+            .with_http_address(test_address())
+            .with_proxy(&proxy)
+            .unwrap()
+            .with_ip_rate_limit(&config);
+        async move { server.start_server(server_config).await.unwrap() }
+    };
+    let proxied_client = |url: &str, forwarded_for: &'static str| -> HttpClient {
+        HttpClientBuilder::default()
+            .set_headers(HeaderMap::from_iter([(
+                "X-Forwarded-For".parse().unwrap(),
+                HeaderValue::from_static(forwarded_for),
+            )]))
+            .build(url)
+            .unwrap()
+    };
+
+    // the client is the last address in front of the trusted proxies, addresses in front of it
+    // can't be used to evade the rate limit
+    let handle = launch("127.0.0.0/8").await;
+    let url = handle.http_url().unwrap();
+    ping(&proxied_client(&url, "203.0.113.7, 127.0.0.2")).await.unwrap();
+    assert!(rate_limit_error(ping(&proxied_client(&url, "10.0.0.1, 203.0.113.7")).await)
+        .contains("client 203.0.113.7"));
+    ping(&proxied_client(&url, "203.0.113.8")).await.unwrap();
+
+    // the header of untrusted peers is ignored
+    let handle = launch("10.0.0.0/8").await;
+    let url = handle.http_url().unwrap();
+    ping(&proxied_client(&url, "203.0.113.7")).await.unwrap();
+    assert!(rate_limit_error(ping(&proxied_client(&url, "203.0.113.8")).await)
+        .contains("client 127.0.0.1"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ip_rate_limit_proxy_protocol() {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    server.merge_configured(ping_module()).unwrap();
+    let config = IpRateLimitConfig {
+        quota: RateQuota::new(1.try_into().unwrap(), Duration::from_secs(60 * 60)),
+        ban_threshold: 0,
+        ..Default::default()
+    };
+    let proxy = ProxyConfig {
+        trusted_proxies: vec!["127.0.0.0/8".parse().unwrap()],
+        proxy_protocol: true,
+        ..Default::default()
+    };
+    let handle = server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_ip_rate_limit(&config)
+                .with_proxy(&proxy)
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let port = handle.http_local_addr().unwrap().port();
+
+    // sends a request over a connection of a proxy forwarding a connection of the client
+    let ping = |client: Option<[u8; 4]>| async move {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        if let Some(client) = client {
+            let mut header = b"\r\n\r\n\0\r\nQUIT\n\x21\x11\x00\x0c".to_vec();
+            header.extend(client);
+            header.extend([127, 0, 0, 1, 0x1f, 0x90, 0x21, 0x45]);
+            stream.write_all(&header).await.unwrap();
+        }
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"test_unlimited","params":[]}"#;
+        let request = format!(
+            "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.map(|_| response)
+    };
+
+    assert!(ping(Some([203, 0, 113, 7])).await.unwrap().contains(r#""result":"pong""#));
+    assert!(ping(Some([203, 0, 113, 7])).await.unwrap().contains("client 203.0.113.7"));
+    assert!(ping(Some([203, 0, 113, 8])).await.unwrap().contains(r#""result":"pong""#));
+
+    // connections of trusted proxies without a PROXY protocol header are closed
+    assert!(!ping(None).await.unwrap_or_default().contains("result"));
+}
@@ -0,0 +1,66 @@
+//! Graceful shutdown tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use jsonrpsee::{core::client::ClientT, rpc_params, RpcModule};
+use reth_rpc_builder::{error::GracefulShutdownError, RpcServerConfig, TransportRpcModuleConfig};
+use reth_rpc_server_types::RethRpcModule;
+use std::time::Duration;
+
+/// Returns a module with a `test_slow` method that takes the given number of milliseconds.
+fn slow_module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_async_method("test_slow", |params, _, _| async move {
+            let millis = params.one::<u64>().unwrap_or_default();
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            "done"
+        })
+        .unwrap();
+    module
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graceful_shutdown_drains_calls() {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    server.merge_http(slow_module()).unwrap();
+    let handle = server
+        .start_server(RpcServerConfig::http(Default::default()).with_http_address(test_address()))
+        .await
+        .unwrap();
+    let client = handle.http_client().unwrap();
+
+    let call =
+        tokio::spawn(
+            async move { client.request::<String, _>("test_slow", rpc_params![300u64]).await },
+        );
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // the in-flight call is completed before the server stops
+    let http_client = handle.http_client().unwrap();
+    handle.shutdown_graceful(Duration::from_secs(5)).await.unwrap();
+    assert_eq!(call.await.unwrap().unwrap(), "done");
+
+    // and new calls are refused
+    assert!(http_client.request::<String, _>("test_slow", rpc_params![0u64]).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graceful_shutdown_timeout() {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    server.merge_http(slow_module()).unwrap();
+    let handle = server
+        .start_server(RpcServerConfig::http(Default::default()).with_http_address(test_address()))
+        .await
+        .unwrap();
+    let client = handle.http_client().unwrap();
+
+    tokio::spawn(
+        async move { client.request::<String, _>("test_slow", rpc_params![5_000u64]).await },
+    );
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let err = handle.shutdown_graceful(Duration::from_millis(100)).await.unwrap_err();
+    assert!(matches!(err, GracefulShutdownError::Timeout(_)));
+}
@@ -3,12 +3,21 @@
 use crate::utils::{
     launch_http, launch_http_ws_same_port, launch_ws, test_address, test_rpc_builder,
 };
+use jsonrpsee::{
+    core::client::{ClientT, Error},
+    http_client::HttpClientBuilder,
+    rpc_params,
+    types::error::METHOD_NOT_FOUND_CODE,
+};
 use reth_rpc_builder::{
     error::{RpcError, ServerKind, WsHttpSamePortError},
-    RpcServerConfig, TransportRpcModuleConfig,
+    CorsConfig, RpcServerConfig, TransportRpcModuleConfig,
 };
 use reth_rpc_server_types::RethRpcModule;
-use std::io;
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr},
+};
 
 fn is_addr_in_use_kind(err: &RpcError, kind: ServerKind) -> bool {
     match err {
@@ -88,8 +97,8 @@ async fn test_launch_same_port_same_cors() {
             RpcServerConfig::ws(Default::default())
                 .with_ws_address(addr)
                 .with_http(Default::default())
-                .with_cors(Some("*".to_string()))
-                .with_http_cors(Some("*".to_string()))
+                .with_cors(Some(CorsConfig::any()))
+                .with_http_cors(Some(CorsConfig::any()))
                 .with_http_address(addr),
         )
         .await;
@@ -109,8 +118,8 @@ async fn test_launch_same_port_different_cors() {
             RpcServerConfig::ws(Default::default())
                 .with_ws_address(addr)
                 .with_http(Default::default())
-                .with_cors(Some("*".to_string()))
-                .with_http_cors(Some("example".to_string()))
+                .with_cors(Some(CorsConfig::any()))
+                .with_http_cors(Some(CorsConfig::new(["example"])))
                 .with_http_address(addr),
         )
         .await;
@@ -120,3 +129,58 @@ async fn test_launch_same_port_different_cors() {
         RpcError::WsHttpSamePortError(WsHttpSamePortError::ConflictingCorsDomains { .. })
     ));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_launch_multiple_addresses() {
+    let builder = test_rpc_builder();
+    let server = builder
+        .build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3, RethRpcModule::Net]));
+    let handle = server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_addresses([
+                    SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+                    SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+                ])
+                .with_additional_http_address(
+                    SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+                    Some(vec![RethRpcModule::Web3].into()),
+                ),
+        )
+        .await
+        .unwrap();
+    let additional_addrs = handle.http_additional_local_addrs().to_vec();
+    assert_eq!(additional_addrs.len(), 2);
+
+    // all modules are served on the main address and the other address of the list
+    for addr in [handle.http_local_addr().unwrap(), additional_addrs[0]] {
+        let client = HttpClientBuilder::default().build(format!("http://{addr}")).unwrap();
+        client.request::<String, _>("web3_clientVersion", rpc_params![]).await.unwrap();
+        client.request::<String, _>("net_version", rpc_params![]).await.unwrap();
+    }
+
+    // only the selected modules are served on the additional address
+    let client =
+        HttpClientBuilder::default().build(format!("http://{}", additional_addrs[1])).unwrap();
+    client.request::<String, _>("web3_clientVersion", rpc_params![]).await.unwrap();
+    let result = client.request::<String, _>("net_version", rpc_params![]).await;
+    let Err(Error::Call(err)) = result else { panic!("expected unknown method: {result:?}") };
+    assert_eq!(err.code(), METHOD_NOT_FOUND_CODE);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_additional_addr_in_use() {
+    let handle = launch_http(vec![RethRpcModule::Admin]).await;
+    let addr = handle.http_local_addr().unwrap();
+    let builder = test_rpc_builder();
+    let server = builder.build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Admin]));
+    let result = server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_additional_http_address(addr, None),
+        )
+        .await;
+    let err = result.unwrap_err();
+    assert!(is_addr_in_use_kind(&err, ServerKind::Http(addr)), "{err}");
+}
@@ -0,0 +1,85 @@
+//! Subscription limit tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use jsonrpsee::{
+    core::{
+        client::{Error, Subscription, SubscriptionClientT},
+        SubscriptionResult,
+    },
+    rpc_params,
+    types::error::TOO_MANY_SUBSCRIPTIONS_CODE,
+    RpcModule,
+};
+use reth_rpc_builder::{RpcServerConfig, TransportRpcModuleConfig};
+use reth_rpc_server_types::RethRpcModule;
+use std::time::Duration;
+
+/// Returns a module with a `test_subscribe` subscription that stays open until it is cancelled.
+fn subscription_module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_subscription(
+            "test_subscribe",
+            "test_subscription",
+            "test_unsubscribe",
+            |_, pending, _, _| async move {
+                let sink = pending.accept().await?;
+                sink.closed().await;
+                SubscriptionResult::Ok(())
+            },
+        )
+        .unwrap();
+    module
+}
+
+/// Subscribes to `test_subscribe` with the given client.
+async fn subscribe<C: SubscriptionClientT>(client: &C) -> Result<Subscription<u64>, Error> {
+    client.subscribe("test_subscribe", rpc_params![], "test_unsubscribe").await
+}
+
+/// Retries to subscribe until the budget has room again, subscriptions are released
+/// asynchronously after they are cancelled by the client.
+async fn subscribe_eventually<C: SubscriptionClientT>(client: &C) -> Subscription<u64> {
+    for _ in 0..50 {
+        if let Ok(subscription) = subscribe(client).await {
+            return subscription
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("subscription was not released")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_max_subscriptions() {
+    let mut server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3]));
+    server.merge_ws(subscription_module()).unwrap();
+
+    let handle = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(test_address())
+                .with_max_subscriptions(Some(2)),
+        )
+        .await
+        .unwrap();
+    let client = handle.ws_client().await.unwrap();
+    let other_client = handle.ws_client().await.unwrap();
+
+    // the budget is shared by all connections
+    let first = subscribe(&client).await.unwrap();
+    let _second = subscribe(&other_client).await.unwrap();
+    let result = subscribe(&other_client).await;
+    let Err(Error::Call(err)) = result else { panic!("expected subscription limit error") };
+    assert_eq!(err.code(), TOO_MANY_SUBSCRIPTIONS_CODE);
+
+    // unsubscribing releases the subscription
+    first.unsubscribe().await.unwrap();
+    let _third = subscribe_eventually(&other_client).await;
+    assert!(subscribe(&client).await.is_err());
+
+    // closing a connection releases all of its subscriptions
+    drop(other_client);
+    let _fourth = subscribe_eventually(&client).await;
+    let _fifth = subscribe(&client).await.unwrap();
+}
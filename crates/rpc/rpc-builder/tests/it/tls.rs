@@ -0,0 +1,132 @@
+//! Native TLS tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use reth_rpc_builder::{
+    error::RpcError, RpcServerConfig, RpcServerHandle, TlsConfig, TransportRpcModuleConfig,
+};
+use reth_rpc_server_types::RethRpcModule;
+use std::{
+    io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    rustls::{crypto::ring, pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+
+/// Returns the path of a file in the testdata directory.
+fn testdata(file: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testdata").join(file)
+}
+
+/// Sends a `web3_clientVersion` request over TLS, trusting only the given certificate, and returns
+/// the response.
+async fn tls_request(addr: SocketAddr, trusted_cert: &Path) -> io::Result<String> {
+    let mut roots = RootCertStore::empty();
+    let cert = std::fs::read(trusted_cert)?;
+    for cert in rustls_pemfile::certs(&mut cert.as_slice()) {
+        roots.add(cert?).map_err(io::Error::other)?;
+    }
+    let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    let stream = TcpStream::connect(addr).await?;
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let mut stream = TlsConnector::from(Arc::new(config)).connect(server_name, stream).await?;
+
+    stream.write_all(http_request().as_bytes()).await?;
+    let mut response = Vec::new();
+    // the server may close the connection without a close_notify alert
+    let _ = stream.read_to_end(&mut response).await;
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+/// Returns a `web3_clientVersion` http request.
+fn http_request() -> String {
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"web3_clientVersion","params":[]}"#;
+    format!(
+        "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Launches a http server with the `web3` module, terminating TLS with the given config.
+async fn launch_http(tls: TlsConfig) -> Result<RpcServerHandle, RpcError> {
+    let server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_tls_config(tls),
+        )
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_tls() {
+    let handle = launch_http(TlsConfig::new(testdata("tls-cert-1.pem"), testdata("tls-key-1.pem")))
+        .await
+        .unwrap();
+    let addr = handle.http_local_addr().unwrap();
+
+    let response = tls_request(addr, &testdata("tls-cert-1.pem")).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.contains(r#""result""#), "{response}");
+
+    // the certificate is not trusted
+    assert!(tls_request(addr, &testdata("tls-cert-2.pem")).await.is_err());
+
+    // plaintext requests are not served
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(http_request().as_bytes()).await.unwrap();
+    let mut response = Vec::new();
+    let _ = stream.read_to_end(&mut response).await;
+    assert!(!String::from_utf8_lossy(&response).contains("result"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tls_certificate_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert = dir.path().join("cert.pem");
+    let key = dir.path().join("key.pem");
+    std::fs::copy(testdata("tls-cert-1.pem"), &cert).unwrap();
+    std::fs::copy(testdata("tls-key-1.pem"), &key).unwrap();
+
+    let handle =
+        launch_http(TlsConfig::new(&cert, &key).with_reload_interval(Duration::from_millis(50)))
+            .await
+            .unwrap();
+    let addr = handle.http_local_addr().unwrap();
+    tls_request(addr, &testdata("tls-cert-1.pem")).await.unwrap();
+
+    // an invalid certificate is not loaded
+    std::fs::write(&cert, "invalid").unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    tls_request(addr, &testdata("tls-cert-1.pem")).await.unwrap();
+
+    // the renewed certificate is used for new connections
+    std::fs::copy(testdata("tls-cert-2.pem"), &cert).unwrap();
+    std::fs::copy(testdata("tls-key-2.pem"), &key).unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    tls_request(addr, &testdata("tls-cert-2.pem")).await.unwrap();
+    assert!(tls_request(addr, &testdata("tls-cert-1.pem")).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tls_missing_certificate() {
+    let err = launch_http(TlsConfig::new(testdata("missing.pem"), testdata("tls-key-1.pem")))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RpcError::Tls(_)), "{err}");
+}
@@ -0,0 +1,60 @@
+//! Runtime toggling of rpc namespaces
+
+use crate::utils::{test_address, test_rpc_builder};
+use jsonrpsee::{
+    core::client::{ClientT, Error},
+    rpc_params,
+    types::error::METHOD_NOT_FOUND_CODE,
+};
+use reth_rpc_builder::{RpcServerConfig, TransportRpcModuleConfig};
+use reth_rpc_server_types::{RethRpcModule, RpcModuleToggleError};
+
+/// Calls `web3_clientVersion` with the given client.
+async fn client_version<C: ClientT>(client: &C) -> Result<String, Error> {
+    client.request("web3_clientVersion", rpc_params![]).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_toggle_rpc_module() {
+    let server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3, RethRpcModule::Net])
+            .with_http(vec![RethRpcModule::Web3, RethRpcModule::Net]),
+    );
+    let toggle = server.module_toggle().clone();
+
+    let handle = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(test_address())
+                .with_http(Default::default())
+                .with_http_address(test_address()),
+        )
+        .await
+        .unwrap();
+    let http_client = handle.http_client().unwrap();
+    let ws_client = handle.ws_client().await.unwrap();
+    client_version(&http_client).await.unwrap();
+
+    // the namespace is disabled on all transports
+    assert_eq!(toggle.set_enabled("web3", false), Ok(true));
+    for result in [client_version(&http_client).await, client_version(&ws_client).await] {
+        let Err(Error::Call(err)) = result else {
+            panic!("expected disabled namespace: {result:?}")
+        };
+        assert_eq!(err.code(), METHOD_NOT_FOUND_CODE);
+    }
+    assert_eq!(toggle.namespaces().get("web3"), Some(&false));
+
+    // other namespaces are still served
+    http_client.request::<String, _>("net_version", rpc_params![]).await.unwrap();
+
+    assert_eq!(toggle.set_enabled("web3", true), Ok(true));
+    client_version(&http_client).await.unwrap();
+    client_version(&ws_client).await.unwrap();
+
+    // namespaces that are not installed can't be toggled
+    assert_eq!(
+        toggle.set_enabled("debug", false),
+        Err(RpcModuleToggleError::Unknown("debug".to_string()))
+    );
+}
@@ -25,7 +25,17 @@ pub const fn test_address() -> SocketAddr {
 
 /// Launches a new server for the auth module
 pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
+    launch_auth_module(secret, test_auth_module()).await
+}
+
+/// Launches a new auth server with the given module
+pub async fn launch_auth_module(secret: JwtSecret, module: AuthRpcModule) -> AuthServerHandle {
     let config = AuthServerConfig::builder(secret).socket_addr(test_address()).build();
+    module.start_server(config).await.unwrap()
+}
+
+/// Returns an auth module with the engine API
+pub fn test_auth_module() -> AuthRpcModule {
     let (tx, _rx) = unbounded_channel();
     let beacon_engine_handle =
         BeaconConsensusEngineHandle::<EthEngineTypes>::new(tx, Default::default());
@@ -44,8 +54,7 @@ pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
         Box::<TokioTaskExecutor>::default(),
         client,
     );
-    let module = AuthRpcModule::new(engine_api);
-    module.start_server(config).await.unwrap()
+    AuthRpcModule::new(engine_api)
 }
 
 /// Launches a new server with http only with the given modules
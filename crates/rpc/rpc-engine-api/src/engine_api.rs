@@ -821,7 +821,18 @@ where
 
     /// Handler for `engine_exchangeCapabilitiesV1`
     /// See also <https://github.com/ethereum/execution-apis/blob/6452a6b194d7db269bf1dbd087a267251d3cc7f8/src/engine/common.md#capabilities>
-    async fn exchange_capabilities(&self, _capabilities: Vec<String>) -> RpcResult<Vec<String>> {
+    ///
+    /// Methods of the consensus client that are not supported by this node are logged, since the
+    /// consensus client can't use them, e.g. on a fork this node doesn't support yet.
+    async fn exchange_capabilities(&self, capabilities: Vec<String>) -> RpcResult<Vec<String>> {
+        trace!(target: "rpc::engine", "Serving engine_exchangeCapabilities");
+        let unsupported = capabilities
+            .iter()
+            .filter(|capability| !CAPABILITIES.contains(&capability.as_str()))
+            .collect::<Vec<_>>();
+        if !unsupported.is_empty() {
+            warn!(target: "rpc::engine", ?unsupported, "Consensus client supports engine methods that this node doesn't support");
+        }
         Ok(CAPABILITIES.iter().cloned().map(str::to_owned).collect())
     }
 }
@@ -893,6 +904,31 @@ mod tests {
         assert_eq!(res.unwrap(), vec![client]);
     }
 
+    #[tokio::test]
+    async fn capabilities_match_methods() {
+        let (_, api) = setup_engine_api();
+        let exchanged = EngineApiServer::exchange_capabilities(
+            &api,
+            vec!["engine_newPayloadV1".to_string(), "engine_unknownV1".to_string()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(exchanged, CAPABILITIES);
+
+        // every engine method is advertised, and every advertised method is served
+        let module = api.into_rpc();
+        let mut methods = module
+            .method_names()
+            .filter(|method| {
+                method.starts_with("engine_") && *method != "engine_exchangeCapabilities"
+            })
+            .collect::<Vec<_>>();
+        methods.sort_unstable();
+        let mut capabilities = CAPABILITIES.to_vec();
+        capabilities.sort_unstable();
+        assert_eq!(methods, capabilities);
+    }
+
     struct EngineApiTestHandle {
         chain_spec: Arc<ChainSpec>,
         provider: Arc<MockEthProvider>,
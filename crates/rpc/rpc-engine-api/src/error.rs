@@ -164,6 +164,13 @@ impl From<EngineApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             // Error responses from the consensus engine
             EngineApiError::ForkChoiceUpdate(ref err) => match err {
                 BeaconForkChoiceUpdateError::ForkchoiceUpdateError(err) => (*err).into(),
+                BeaconForkChoiceUpdateError::InvalidPayloadAttributes(_) => {
+                    jsonrpsee_types::error::ErrorObject::owned(
+                        INVALID_PAYLOAD_ATTRIBUTES,
+                        INVALID_PAYLOAD_ATTRIBUTES_MSG,
+                        Some(ErrorData::new(error)),
+                    )
+                }
                 BeaconForkChoiceUpdateError::EngineUnavailable |
                 BeaconForkChoiceUpdateError::Internal(_) => {
                     jsonrpsee_types::error::ErrorObject::owned(
@@ -258,6 +265,16 @@ mod tests {
             )),
         );
 
+        ensure_engine_rpc_error(
+            INVALID_PAYLOAD_ATTRIBUTES,
+            INVALID_PAYLOAD_ATTRIBUTES_MSG,
+            EngineApiError::ForkChoiceUpdate(
+                BeaconForkChoiceUpdateError::InvalidPayloadAttributes(
+                    "withdrawals not allowed".into(),
+                ),
+            ),
+        );
+
         ensure_engine_rpc_error(
             UNKNOWN_PAYLOAD_CODE,
             "Unknown payload",
@@ -2,7 +2,7 @@
 //! are executed on the `tokio` runtime.
 
 use futures::Future;
-use reth_rpc_eth_types::{EthApiError, EthResult};
+use reth_rpc_eth_types::{EthApiError, EthResult, ExecutionLimits};
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner};
 use tokio::sync::oneshot;
 
@@ -18,6 +18,13 @@ pub trait SpawnBlocking: Clone + Send + Sync + 'static {
     /// Thread pool access in default trait method implementations.
     fn tracing_task_pool(&self) -> &BlockingTaskPool;
 
+    /// Returns the limits of the EVM executions of spawned tasks.
+    ///
+    /// Each spawned task runs in a new [`ExecutionScope`](reth_rpc_eth_types::ExecutionScope),
+    /// which is cancelled if the returned future is dropped, e.g. because the client
+    /// disconnected.
+    fn execution_limits(&self) -> &ExecutionLimits;
+
     /// Executes the future on a new blocking task.
     ///
     /// Note: This is expected for futures that are dominated by blocking IO operations, for tracing
@@ -29,12 +36,17 @@ pub trait SpawnBlocking: Clone + Send + Sync + 'static {
     {
         let (tx, rx) = oneshot::channel();
         let this = self.clone();
+        let scope = self.execution_limits().new_scope();
+        let guard = scope.token().clone().drop_guard();
         self.io_task_spawner().spawn_blocking(Box::pin(async move {
-            let res = async move { f(this) }.await;
+            let res = async move { scope.enter(|| f(this)) }.await;
             let _ = tx.send(res);
         }));
 
-        async move { rx.await.map_err(|_| EthApiError::InternalEthError)? }
+        async move {
+            let _guard = guard;
+            rx.await.map_err(|_| EthApiError::InternalEthError)?
+        }
     }
 
     /// Executes a blocking task on the tracing pool.
@@ -48,7 +60,12 @@ pub trait SpawnBlocking: Clone + Send + Sync + 'static {
         R: Send + 'static,
     {
         let this = self.clone();
-        let fut = self.tracing_task_pool().spawn(move || f(this));
-        async move { fut.await.map_err(|_| EthApiError::InternalBlockingTaskError)? }
+        let scope = self.execution_limits().new_scope();
+        let guard = scope.token().clone().drop_guard();
+        let fut = self.tracing_task_pool().spawn(move || scope.enter(|| f(this)));
+        async move {
+            let _guard = guard;
+            fut.await.map_err(|_| EthApiError::InternalBlockingTaskError)?
+        }
     }
 }
@@ -20,7 +20,7 @@ use reth_rpc_eth_types::{
         apply_state_overrides, build_call_evm_env, caller_gas_allowance,
         cap_tx_gas_limit_with_caller_allowance, get_precompiles, prepare_call_env,
     },
-    EthApiError, EthResult, RevertError, RpcInvalidTransactionError, StateCacheDb,
+    EthApiError, EthResult, ExecutionScope, RevertError, RpcInvalidTransactionError, StateCacheDb,
 };
 use reth_rpc_server_types::constants::gas_oracle::{ESTIMATE_GAS_ERROR_RATIO, MIN_TRANSACTION_GAS};
 use reth_rpc_types::{
@@ -28,7 +28,7 @@ use reth_rpc_types::{
     AccessListWithGasUsed, BlockId, Bundle, EthCallResponse, StateContext, TransactionInfo,
     TransactionRequest,
 };
-use revm::{Database, DatabaseCommit};
+use revm::{inspectors::NoOpInspector, Database, DatabaseCommit};
 use revm_inspectors::access_list::AccessListInspector;
 use tracing::trace;
 
@@ -286,17 +286,40 @@ pub trait Call: LoadState + SpawnBlocking {
 
     /// Executes the [`EnvWithHandlerCfg`] against the given [Database] without committing state
     /// changes.
+    ///
+    /// If called within an [`ExecutionScope`], the memory limit of the scope applies and the
+    /// execution doesn't start if the scope is cancelled. If the scope is
+    /// [interruptible](ExecutionScope::is_interruptible), the running execution is aborted once
+    /// the scope is cancelled as well.
     fn transact<DB>(
         &self,
         db: DB,
-        env: EnvWithHandlerCfg,
+        mut env: EnvWithHandlerCfg,
     ) -> EthResult<(ResultAndState, EnvWithHandlerCfg)>
     where
         DB: Database,
         <DB as Database>::Error: Into<EthApiError>,
     {
-        let mut evm = self.evm_config().evm_with_env(db, env);
-        let res = evm.transact()?;
+        let scope = ExecutionScope::current();
+        if let Some(scope) = &scope {
+            if scope.is_cancelled() {
+                return Err(EthApiError::ExecutionCancelled)
+            }
+            scope.configure(&mut env.cfg);
+        }
+
+        // checking for cancellation between opcodes requires an inspector, which is only
+        // installed if enabled
+        let Some(scope) = scope.filter(|scope| scope.is_interruptible()) else {
+            let mut evm = self.evm_config().evm_with_env(db, env);
+            let res = evm.transact()?;
+            let (_, env) = evm.into_db_and_env_with_handler_cfg();
+            return Ok((res, env))
+        };
+
+        let mut evm =
+            self.evm_config().evm_with_env_and_inspector(db, env, scope.inspector(NoOpInspector));
+        let res = evm.transact().map_err(|err| scope.execution_error(err))?;
         let (_, env) = evm.into_db_and_env_with_handler_cfg();
         Ok((res, env))
     }
@@ -452,7 +475,11 @@ pub trait Call: LoadState + SpawnBlocking {
         DB: DatabaseRef,
         EthApiError: From<<DB as DatabaseRef>::Error>,
     {
-        let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, Default::default());
+        let mut env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, Default::default());
+        let scope = ExecutionScope::current();
+        if let Some(scope) = &scope {
+            scope.configure(&mut env.cfg);
+        }
 
         let mut evm = self.evm_config().evm_with_env(db, env);
         let mut index = 0;
@@ -461,6 +488,11 @@ pub trait Call: LoadState + SpawnBlocking {
                 // reached the target transaction
                 break
             }
+            // the replayed transactions are bounded by the block gas limit, so it's sufficient to
+            // check for cancellation between them
+            if scope.as_ref().is_some_and(|scope| scope.is_cancelled()) {
+                return Err(EthApiError::ExecutionCancelled)
+            }
 
             let sender = tx.signer();
             self.evm_config().fill_tx_env(evm.tx_mut(), &tx.into_signed(), sender);
@@ -6,7 +6,7 @@ use reth_primitives::{revm::env::tx_env_with_recovered, B256};
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_eth_types::{
     cache::db::{StateCacheDb, StateCacheDbRefMutWrapper, StateProviderTraitObjWrapper},
-    EthApiError, EthResult,
+    EthApiError, EthResult, ExecutionScope,
 };
 use reth_rpc_types::{BlockId, TransactionInfo};
 use revm::{db::CacheDB, Database, DatabaseCommit, GetInspector, Inspector};
@@ -43,10 +43,13 @@ pub trait Trace: LoadState {
     /// Even though [Database] is also implemented on `&mut`
     /// this is still useful if there are certain trait bounds on the Inspector's database generic
     /// type
+    ///
+    /// If called within an [`ExecutionScope`], the memory limit of the scope applies and the
+    /// execution is aborted once the scope is cancelled.
     fn inspect_and_return_db<DB, I>(
         &self,
         db: DB,
-        env: EnvWithHandlerCfg,
+        mut env: EnvWithHandlerCfg,
         inspector: I,
     ) -> EthResult<(ResultAndState, EnvWithHandlerCfg, DB)>
     where
@@ -54,8 +57,17 @@ pub trait Trace: LoadState {
         <DB as Database>::Error: Into<EthApiError>,
         I: GetInspector<DB>,
     {
-        let mut evm = self.evm_config().evm_with_env_and_inspector(db, env, inspector);
-        let res = evm.transact()?;
+        let Some(scope) = ExecutionScope::current() else {
+            let mut evm = self.evm_config().evm_with_env_and_inspector(db, env, inspector);
+            let res = evm.transact()?;
+            let (db, env) = evm.into_db_and_env_with_handler_cfg();
+            return Ok((res, env, db))
+        };
+
+        scope.configure(&mut env.cfg);
+        let mut evm =
+            self.evm_config().evm_with_env_and_inspector(db, env, scope.inspector(inspector));
+        let res = evm.transact().map_err(|err| scope.execution_error(err))?;
         let (db, env) = evm.into_db_and_env_with_handler_cfg();
         Ok((res, env, db))
     }
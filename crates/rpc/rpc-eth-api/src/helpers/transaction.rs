@@ -9,7 +9,10 @@ use reth_primitives::{
     Address, BlockId, Bytes, FromRecoveredPooledTransaction, IntoRecoveredTransaction, Receipt,
     SealedBlockWithSenders, TransactionMeta, TransactionSigned, TxHash, TxKind, B256, U256,
 };
-use reth_provider::{BlockReaderIdExt, ReceiptProvider, TransactionsProvider};
+use reth_provider::{
+    BlockReaderIdExt, PruneCheckpointReader, ReceiptProvider, TransactionsProvider,
+};
+use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_rpc_eth_types::{
     utils::recover_raw_transaction, EthApiError, EthResult, EthStateCache, SignError,
     TransactionSource,
@@ -109,9 +112,13 @@ pub trait EthTransactions: LoadTransaction {
             }
 
             self.spawn_blocking_io(move |ref this| {
-                Ok(LoadTransaction::provider(this)
-                    .transaction_by_hash(hash)?
-                    .map(|tx| tx.envelope_encoded()))
+                match LoadTransaction::provider(this).transaction_by_hash(hash)? {
+                    Some(tx) => Ok(Some(tx.envelope_encoded())),
+                    None => {
+                        this.ensure_transaction_lookup_not_pruned()?;
+                        Ok(None)
+                    }
+                }
             })
             .await
         }
@@ -166,7 +173,13 @@ pub trait EthTransactions: LoadTransaction {
             let (tx, meta) =
                 match LoadTransaction::provider(&this).transaction_by_hash_with_meta(hash)? {
                     Some((tx, meta)) => (tx, meta),
-                    None => return Ok(None),
+                    None => {
+                        // pending transactions don't have a receipt yet
+                        if !LoadTransaction::pool(&this).contains(&hash) {
+                            this.ensure_transaction_lookup_not_pruned()?;
+                        }
+                        return Ok(None)
+                    }
                 };
 
             let receipt = match EthTransactions::provider(&this).receipt_by_hash(hash)? {
@@ -519,7 +532,7 @@ pub trait LoadTransaction: SpawnBlocking {
     /// Returns a handle for reading data from disk.
     ///
     /// Data access in default (L1) trait method implementations.
-    fn provider(&self) -> impl TransactionsProvider;
+    fn provider(&self) -> impl TransactionsProvider + PruneCheckpointReader;
 
     /// Returns a handle for reading data from memory.
     ///
@@ -573,6 +586,9 @@ pub trait LoadTransaction: SpawnBlocking {
                     self.pool().get(&hash).map(|tx| tx.transaction.to_recovered_transaction())
                 {
                     resp = Some(TransactionSource::Pool(tx));
+                } else {
+                    self.spawn_blocking_io(|this| this.ensure_transaction_lookup_not_pruned())
+                        .await?;
                 }
             }
 
@@ -580,6 +596,18 @@ pub trait LoadTransaction: SpawnBlocking {
         }
     }
 
+    /// Returns [`EthApiError::TransactionLookupPruned`] if the transaction lookup index was
+    /// pruned, because a transaction that wasn't found by its hash may be part of the unindexed
+    /// history.
+    fn ensure_transaction_lookup_not_pruned(&self) -> EthResult<()> {
+        match self.provider().get_prune_checkpoint(PruneSegment::TransactionLookup)? {
+            Some(PruneCheckpoint { block_number: Some(block_number), .. }) => {
+                Err(EthApiError::TransactionLookupPruned(block_number))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Returns the transaction by including its corresponding [`BlockId`].
     ///
     /// Note: this supports pending transactions
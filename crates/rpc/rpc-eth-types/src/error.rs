@@ -4,7 +4,7 @@ use std::time::Duration;
 
 use alloy_sol_types::decode_revert_reason;
 use reth_errors::RethError;
-use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes};
+use reth_primitives::{revm_primitives::InvalidHeader, Address, BlockNumber, Bytes};
 use reth_rpc_server_types::result::{
     internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code,
 };
@@ -21,6 +21,9 @@ use revm_inspectors::tracing::{js::JsInspectorError, MuxError};
 /// Result alias
 pub type EthResult<T> = Result<T, EthApiError>;
 
+/// Error code returned for requests of expired history, as used by other execution clients.
+pub const PRUNED_HISTORY_UNAVAILABLE_CODE: i32 = 4444;
+
 /// Errors that can occur when interacting with the `eth_` namespace
 #[derive(Debug, thiserror::Error)]
 pub enum EthApiError {
@@ -82,6 +85,14 @@ pub enum EthApiError {
     /// Thrown when a requested transaction is not found
     #[error("transaction not found")]
     TransactionNotFound,
+    /// Thrown when a transaction can't be found by its hash, because the transaction lookup index
+    /// doesn't cover the history up to the given block
+    #[error("transaction not found, transaction lookup index is unavailable up to block {0}")]
+    TransactionLookupPruned(BlockNumber),
+    /// Thrown when the requested block data has expired and was deleted from the node, see
+    /// [EIP-4444](https://eips.ethereum.org/EIPS/eip-4444)
+    #[error("pruned history unavailable")]
+    PrunedHistoryUnavailable,
     /// Some feature is unsupported
     #[error("unsupported")]
     Unsupported(&'static str),
@@ -106,6 +117,9 @@ pub enum EthApiError {
     /// Error thrown when a (tracing) call exceeds the configured timeout
     #[error("execution aborted (timeout = {0:?})")]
     ExecutionTimedOut(Duration),
+    /// Error thrown when a (tracing) call was cancelled, e.g. because the client disconnected
+    #[error("execution cancelled")]
+    ExecutionCancelled,
     /// Internal Error thrown by the javascript tracer
     #[error("{0}")]
     InternalJsTracerError(String),
@@ -158,19 +172,26 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthApiError::EvmCustom(_) |
             EthApiError::EvmPrecompile(_) |
             EthApiError::InvalidRewardPercentiles => internal_rpc_err(error.to_string()),
-            EthApiError::UnknownBlockNumber | EthApiError::UnknownBlockOrTxIndex => {
+            EthApiError::UnknownBlockNumber |
+            EthApiError::UnknownBlockOrTxIndex |
+            EthApiError::TransactionLookupPruned(_) => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
             }
             EthApiError::UnknownSafeOrFinalizedBlock => {
                 rpc_error_with_code(EthRpcErrorCode::UnknownBlock.code(), error.to_string())
             }
+            EthApiError::PrunedHistoryUnavailable => {
+                rpc_error_with_code(PRUNED_HISTORY_UNAVAILABLE_CODE, error.to_string())
+            }
             EthApiError::Unsupported(msg) => internal_rpc_err(msg),
             EthApiError::InternalJsTracerError(msg) => internal_rpc_err(msg),
             EthApiError::InvalidParams(msg) => invalid_params_rpc_err(msg),
-            err @ EthApiError::ExecutionTimedOut(_) => rpc_error_with_code(
-                jsonrpsee_types::error::CALL_EXECUTION_FAILED_CODE,
-                err.to_string(),
-            ),
+            err @ EthApiError::ExecutionTimedOut(_) | err @ EthApiError::ExecutionCancelled => {
+                rpc_error_with_code(
+                    jsonrpsee_types::error::CALL_EXECUTION_FAILED_CODE,
+                    err.to_string(),
+                )
+            }
             err @ EthApiError::InternalBlockingTaskError | err @ EthApiError::InternalEthError => {
                 internal_rpc_err(err.to_string())
             }
@@ -212,6 +233,9 @@ impl From<reth_errors::ProviderError> for EthApiError {
             ProviderError::FinalizedBlockNotFound | ProviderError::SafeBlockNotFound => {
                 Self::UnknownSafeOrFinalizedBlock
             }
+            ProviderError::ExpiredStaticFileBlock(..) | ProviderError::ExpiredStaticFileTx(..) => {
+                Self::PrunedHistoryUnavailable
+            }
             err => Self::Internal(err.into()),
         }
     }
@@ -679,4 +703,17 @@ mod tests {
         let err = EthApiError::ExecutionTimedOut(Duration::from_secs(10));
         assert_eq!(err.to_string(), "execution aborted (timeout = 10s)");
     }
+
+    #[test]
+    fn expired_static_file_error() {
+        let err: EthApiError = reth_errors::ProviderError::ExpiredStaticFileBlock(
+            reth_primitives::StaticFileSegment::Headers,
+            1,
+            500_000,
+        )
+        .into();
+        let err: jsonrpsee_types::error::ErrorObject<'static> = err.into();
+        assert_eq!(err.code(), PRUNED_HISTORY_UNAVAILABLE_CODE);
+        assert_eq!(err.message(), "pruned history unavailable");
+    }
 }
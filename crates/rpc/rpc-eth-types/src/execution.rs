@@ -0,0 +1,309 @@
+//! Limits and cancellation of RPC-initiated EVM executions, e.g. `eth_call` and tracing.
+
+use std::{cell::RefCell, sync::Arc};
+
+use parking_lot::Mutex;
+use reth_primitives::{Address, Log, U256};
+use reth_rpc_server_types::constants::DEFAULT_EVM_MEMORY_LIMIT;
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, InstructionResult,
+        Interpreter,
+    },
+    Database, EvmContext, GetInspector, Inspector,
+};
+use revm_primitives::{CfgEnv, EVMError};
+use tokio_util::sync::CancellationToken;
+
+use crate::EthApiError;
+
+/// Number of interpreter steps between two checks of the cancellation token.
+const CANCELLATION_CHECK_INTERVAL: u64 = 1024;
+
+thread_local! {
+    /// The scope of the execution running on the current thread.
+    static CURRENT_SCOPE: RefCell<Option<ExecutionScope>> = const { RefCell::new(None) };
+}
+
+/// Limits of the EVM executions of RPC requests, shared by all requests of an API.
+///
+/// The gas ceiling of executions is the configured gas cap of the API.
+#[derive(Debug, Clone)]
+pub struct ExecutionLimits {
+    /// Maximum memory an execution can allocate, in bytes.
+    memory_limit: u64,
+    /// Whether executions without an inspector, e.g. `eth_call`, can be aborted while running.
+    interruptible: bool,
+    /// The parent of the tokens of all executions, replaced once cancelled.
+    cancellation: Arc<Mutex<CancellationToken>>,
+}
+
+impl ExecutionLimits {
+    /// Creates new limits with the given memory limit of an execution in bytes.
+    pub fn new(memory_limit: u64) -> Self {
+        Self { memory_limit, interruptible: false, cancellation: Default::default() }
+    }
+
+    /// Sets whether executions without an inspector, e.g. `eth_call`, can be aborted while
+    /// running.
+    ///
+    /// This requires a [`CancellableInspector`] that checks for cancellation between opcodes,
+    /// which slows down these executions. Otherwise they are only aborted before they start.
+    pub const fn with_interruptible(mut self, interruptible: bool) -> Self {
+        self.interruptible = interruptible;
+        self
+    }
+
+    /// Returns the memory limit of an execution in bytes.
+    pub const fn memory_limit(&self) -> u64 {
+        self.memory_limit
+    }
+
+    /// Cancels all running executions.
+    ///
+    /// Executions started afterwards are not affected.
+    pub fn cancel_all(&self) {
+        let mut cancellation = self.cancellation.lock();
+        cancellation.cancel();
+        *cancellation = CancellationToken::new();
+    }
+
+    /// Returns the scope of a new execution, which is cancelled by [`Self::cancel_all`].
+    pub fn new_scope(&self) -> ExecutionScope {
+        ExecutionScope {
+            token: self.cancellation.lock().child_token(),
+            memory_limit: self.memory_limit,
+            interruptible: self.interruptible,
+        }
+    }
+}
+
+impl Default for ExecutionLimits {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVM_MEMORY_LIMIT)
+    }
+}
+
+/// The limits and cancellation token of the EVM executions of a single RPC request.
+///
+/// The scope is entered on the thread the request is executed on with [`Self::enter`], from where
+/// the executions pick it up with [`Self::current`].
+#[derive(Debug, Clone)]
+pub struct ExecutionScope {
+    /// Token cancelling the executions of the request.
+    token: CancellationToken,
+    /// Maximum memory an execution can allocate, in bytes.
+    memory_limit: u64,
+    /// Whether executions without an inspector can be aborted while running.
+    interruptible: bool,
+}
+
+impl ExecutionScope {
+    /// Returns the scope entered on the current thread, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT_SCOPE.with(|scope| scope.borrow().clone())
+    }
+
+    /// Runs the closure within the scope on the current thread.
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        /// Restores the previous scope, also if the closure panics.
+        struct Restore(Option<ExecutionScope>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_SCOPE.with(|scope| *scope.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(CURRENT_SCOPE.with(|scope| scope.replace(Some(self.clone()))));
+        f()
+    }
+
+    /// Runs the closure within the given scope, if any.
+    ///
+    /// This can be used to enter the scope of a request on other threads executing parts of it.
+    pub fn enter_optional<R>(scope: Option<&Self>, f: impl FnOnce() -> R) -> R {
+        match scope {
+            Some(scope) => scope.enter(f),
+            None => f(),
+        }
+    }
+
+    /// Returns the token cancelling the executions of the request.
+    pub const fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Returns `true` if the executions of the request are cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Returns `true` if executions without an inspector should be wrapped in a
+    /// [`CancellableInspector`], so they can be aborted while running.
+    pub const fn is_interruptible(&self) -> bool {
+        self.interruptible
+    }
+
+    /// Applies the memory limit to the config of an execution.
+    pub fn configure(&self, cfg: &mut CfgEnv) {
+        cfg.memory_limit = self.memory_limit;
+    }
+
+    /// Converts the error of an execution within the scope, which is
+    /// [`EthApiError::ExecutionCancelled`] if the scope is cancelled.
+    pub fn execution_error<E>(&self, err: EVMError<E>) -> EthApiError
+    where
+        E: Into<EthApiError>,
+    {
+        if self.is_cancelled() {
+            EthApiError::ExecutionCancelled
+        } else {
+            err.into()
+        }
+    }
+
+    /// Wraps the inspector of an execution, so that the execution is aborted once cancelled.
+    pub fn inspector<I>(&self, inspector: I) -> CancellableInspector<I> {
+        CancellableInspector { inspector, token: self.token.clone(), steps: 0 }
+    }
+}
+
+/// An [Inspector] aborting the execution once its token is cancelled, and otherwise delegating to
+/// the wrapped inspector.
+///
+/// An aborted execution fails with [`EVMError::Custom`].
+#[derive(Debug)]
+pub struct CancellableInspector<I> {
+    /// The wrapped inspector.
+    inspector: I,
+    /// Token cancelling the execution.
+    token: CancellationToken,
+    /// Number of executed steps.
+    steps: u64,
+}
+
+impl<I> CancellableInspector<I> {
+    /// Returns the wrapped inspector.
+    pub fn into_inner(self) -> I {
+        self.inspector
+    }
+}
+
+impl<DB, I> Inspector<DB> for CancellableInspector<I>
+where
+    DB: Database,
+    I: GetInspector<DB>,
+{
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.inspector.get_inspector().initialize_interp(interp, context);
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.steps += 1;
+        if self.steps % CANCELLATION_CHECK_INTERVAL == 0 && self.token.is_cancelled() {
+            context.error = Err(EVMError::Custom("execution cancelled".to_string()));
+            interp.instruction_result = InstructionResult::FatalExternalError;
+            return
+        }
+        self.inspector.get_inspector().step(interp, context);
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.inspector.get_inspector().step_end(interp, context);
+    }
+
+    fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
+        self.inspector.get_inspector().log(context, log);
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.inspector.get_inspector().call(context, inputs)
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.inspector.get_inspector().call_end(context, inputs, outcome)
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.inspector.get_inspector().create(context, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.inspector.get_inspector().create_end(context, inputs, outcome)
+    }
+
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.inspector.get_inspector().eofcreate(context, inputs)
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.inspector.get_inspector().eofcreate_end(context, inputs, outcome)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        GetInspector::<DB>::get_inspector(&mut self.inspector)
+            .selfdestruct(contract, target, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_all_cancels_running_scopes() {
+        let limits = ExecutionLimits::default();
+        let running = limits.new_scope();
+        limits.cancel_all();
+        assert!(running.is_cancelled());
+        assert!(!limits.new_scope().is_cancelled());
+    }
+
+    #[test]
+    fn interruptible_scopes() {
+        assert!(!ExecutionLimits::default().new_scope().is_interruptible());
+        let limits = ExecutionLimits::default().with_interruptible(true);
+        assert!(limits.new_scope().is_interruptible());
+    }
+
+    #[test]
+    fn enter_scope() {
+        assert!(ExecutionScope::current().is_none());
+        let scope = ExecutionLimits::new(1024).new_scope();
+        let nested = ExecutionLimits::new(2048).new_scope();
+        scope.enter(|| {
+            assert_eq!(ExecutionScope::current().unwrap().memory_limit, 1024);
+            nested.enter(|| assert_eq!(ExecutionScope::current().unwrap().memory_limit, 2048));
+            assert_eq!(ExecutionScope::current().unwrap().memory_limit, 1024);
+        });
+        assert!(ExecutionScope::current().is_none());
+    }
+}
@@ -10,6 +10,7 @@
 
 pub mod cache;
 pub mod error;
+pub mod execution;
 pub mod fee_history;
 pub mod gas_oracle;
 pub mod id_provider;
@@ -25,6 +26,7 @@ pub use cache::{
     EthStateCache,
 };
 pub use error::{EthApiError, EthResult, RevertError, RpcInvalidTransactionError, SignError};
+pub use execution::{CancellableInspector, ExecutionLimits, ExecutionScope};
 pub use fee_history::{FeeHistoryCache, FeeHistoryCacheConfig, FeeHistoryEntry};
 pub use gas_oracle::{GasCap, GasPriceOracle, GasPriceOracleConfig, GasPriceOracleResult};
 pub use id_provider::EthSubscriptionIdProvider;
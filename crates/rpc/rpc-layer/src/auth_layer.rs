@@ -38,6 +38,7 @@ use tower::{Layer, Service};
 ///         .unwrap();
 /// }
 /// ```
+#[derive(Clone)]
 #[allow(missing_debug_implementations)]
 pub struct AuthLayer<V> {
     validator: V,
@@ -0,0 +1,202 @@
+use crate::{JwtError, JwtSecret};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+/// The JWT secrets accepted by a [`JwtAuthValidator`](crate::JwtAuthValidator).
+///
+/// The first secret is the primary secret, the others are accepted as well, e.g. the previous
+/// secret during a rotation window in which the consensus client switches to the new secret.
+///
+/// Clones share the same secrets, so these can be replaced while the server is running, see
+/// [`JwtSecrets::replace`].
+#[derive(Debug, Clone)]
+pub struct JwtSecrets(Arc<RwLock<Vec<JwtSecret>>>);
+
+impl JwtSecrets {
+    /// Creates a new instance accepting the given secrets.
+    ///
+    /// # Panics
+    ///
+    /// If `secrets` is empty.
+    pub fn new(secrets: Vec<JwtSecret>) -> Self {
+        assert!(!secrets.is_empty(), "at least one JWT secret is required");
+        Self(Arc::new(RwLock::new(secrets)))
+    }
+
+    /// Reads the secrets of a file, see [`read_jwt_secrets`].
+    pub fn from_file(path: &Path) -> Result<Self, JwtError> {
+        read_jwt_secrets(path).map(Self::new)
+    }
+
+    /// Returns the primary secret.
+    pub fn primary(&self) -> JwtSecret {
+        self.0.read().unwrap()[0]
+    }
+
+    /// Replaces the accepted secrets.
+    ///
+    /// An empty list of secrets is ignored.
+    pub fn replace(&self, secrets: Vec<JwtSecret>) {
+        if !secrets.is_empty() {
+            *self.0.write().unwrap() = secrets;
+        }
+    }
+
+    /// Validates the JWT with all secrets, and returns the index of the first secret it is valid
+    /// for.
+    ///
+    /// If the JWT is invalid for all secrets, the most specific error is returned, e.g. an
+    /// invalid issuance timestamp of a JWT signed with one of the secrets rather than an invalid
+    /// signature.
+    pub fn validate(&self, jwt: &str) -> Result<usize, JwtError> {
+        for (index, secret) in self.0.read().unwrap().iter().enumerate() {
+            match secret.validate(jwt) {
+                Ok(()) => return Ok(index),
+                // the JWT isn't signed with this secret
+                Err(JwtError::InvalidSignature) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Err(JwtError::InvalidSignature)
+    }
+}
+
+impl From<JwtSecret> for JwtSecrets {
+    fn from(secret: JwtSecret) -> Self {
+        Self::new(vec![secret])
+    }
+}
+
+/// The file that [`JwtSecrets`] are read from.
+///
+/// Clones share the same secrets, so a running server accepts the secrets of the file after it was
+/// [reloaded](JwtSecretsFile::reload).
+#[derive(Debug, Clone)]
+pub struct JwtSecretsFile {
+    path: PathBuf,
+    secrets: JwtSecrets,
+}
+
+impl JwtSecretsFile {
+    /// Creates a new instance that replaces the given secrets when reloaded.
+    pub const fn new(path: PathBuf, secrets: JwtSecrets) -> Self {
+        Self { path, secrets }
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the secrets that are replaced when the file is reloaded.
+    pub const fn secrets(&self) -> &JwtSecrets {
+        &self.secrets
+    }
+
+    /// Replaces the secrets with the secrets of the file, and returns their number.
+    ///
+    /// The previous secrets are kept if the file can't be read, e.g. because it was only written
+    /// partially.
+    pub fn reload(&self) -> Result<usize, JwtError> {
+        let secrets = read_jwt_secrets(&self.path)?;
+        let len = secrets.len();
+        self.secrets.replace(secrets);
+        Ok(len)
+    }
+}
+
+/// Reads the JWT secrets of a file.
+///
+/// The file contains one hex encoded secret per line, the first of which is the primary secret.
+/// Empty lines are ignored, so a file with a single secret is read the same way as
+/// [`JwtSecret::from_file`].
+pub fn read_jwt_secrets(path: &Path) -> Result<Vec<JwtSecret>, JwtError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| JwtError::Read { source: err, path: path.into() })?;
+    let secrets = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(JwtSecret::from_hex)
+        .collect::<Result<Vec<_>, _>>()?;
+    if secrets.is_empty() {
+        // reports the invalid length of the empty secret
+        return JwtSecret::from_hex(contents.trim()).map(|secret| vec![secret])
+    }
+    Ok(secrets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Claims;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn jwt(secret: &JwtSecret, iat: u64) -> String {
+        secret.encode(&Claims { iat, exp: None }).unwrap()
+    }
+
+    #[test]
+    fn validate_with_all_secrets() {
+        let (primary, previous, other) =
+            (JwtSecret::random(), JwtSecret::random(), JwtSecret::random());
+        let secrets = JwtSecrets::new(vec![primary, previous]);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        assert_eq!(secrets.validate(&jwt(&primary, now)).unwrap(), 0);
+        assert_eq!(secrets.validate(&jwt(&previous, now)).unwrap(), 1);
+        assert!(matches!(secrets.validate(&jwt(&other, now)), Err(JwtError::InvalidSignature)));
+        assert!(matches!(
+            secrets.validate(&jwt(&previous, now + 1000)),
+            Err(JwtError::InvalidIssuanceTimestamp)
+        ));
+
+        // the secrets are shared with the clones
+        let shared = secrets.clone();
+        secrets.replace(vec![other]);
+        assert_eq!(shared.primary(), other);
+        assert!(matches!(shared.validate(&jwt(&primary, now)), Err(JwtError::InvalidSignature)));
+    }
+
+    #[test]
+    fn read_secrets_file() {
+        const PRIMARY: &str = "f79ae8046bc11c9927afe911db7143c51a806c4a537cc08e0d37140b0192f430";
+        const PREVIOUS: &str = "0x7365637265747365637265747365637265747365637265747365637265747365";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+
+        fs::write(&path, PRIMARY).unwrap();
+        assert_eq!(read_jwt_secrets(&path).unwrap(), vec![JwtSecret::from_file(&path).unwrap()]);
+
+        fs::write(&path, format!("{PRIMARY}\n\n{PREVIOUS}\n")).unwrap();
+        assert_eq!(
+            read_jwt_secrets(&path).unwrap(),
+            vec![JwtSecret::from_hex(PRIMARY).unwrap(), JwtSecret::from_hex(PREVIOUS).unwrap()]
+        );
+
+        fs::write(&path, "\n").unwrap();
+        assert!(matches!(read_jwt_secrets(&path), Err(JwtError::InvalidLength(64, 0))));
+    }
+
+    #[test]
+    fn reload_secrets_file() {
+        const PRIMARY: &str = "f79ae8046bc11c9927afe911db7143c51a806c4a537cc08e0d37140b0192f430";
+        let (primary, previous) = (JwtSecret::from_hex(PRIMARY).unwrap(), JwtSecret::random());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+        let secrets = JwtSecrets::new(vec![previous]);
+        let file = JwtSecretsFile::new(path.clone(), secrets.clone());
+
+        // the secrets are kept if the file can't be read
+        assert!(matches!(file.reload(), Err(JwtError::Read { .. })));
+        fs::write(&path, "invalid").unwrap();
+        assert!(file.reload().is_err());
+        assert_eq!(secrets.primary(), previous);
+
+        fs::write(&path, PRIMARY).unwrap();
+        assert_eq!(file.reload().unwrap(), 1);
+        assert_eq!(secrets.primary(), primary);
+    }
+}
@@ -1,6 +1,7 @@
-use crate::{AuthValidator, JwtError, JwtSecret};
+use crate::{AuthValidator, JwtError, JwtSecret, JwtSecrets};
 use http::{header, HeaderMap, Response, StatusCode};
 use jsonrpsee_http_client::{HttpBody, HttpResponse};
+use reth_metrics::{metrics::Counter, Metrics};
 use tracing::error;
 
 /// Implements JWT validation logics and integrates
@@ -9,25 +10,38 @@ use tracing::error;
 #[derive(Clone)]
 #[allow(missing_debug_implementations)]
 pub struct JwtAuthValidator {
-    secret: JwtSecret,
+    secrets: JwtSecrets,
+    metrics: JwtAuthMetrics,
 }
 
 impl JwtAuthValidator {
     /// Creates a new instance of [`JwtAuthValidator`].
     /// Validation logics are implemented by the `secret`
     /// argument (see [`JwtSecret`]).
-    pub const fn new(secret: JwtSecret) -> Self {
-        Self { secret }
+    pub fn new(secret: JwtSecret) -> Self {
+        Self::with_secrets(secret.into())
+    }
+
+    /// Creates a new instance of [`JwtAuthValidator`] that accepts JWTs signed with any of the
+    /// `secrets`, which can be replaced while the validator is in use (see [`JwtSecrets`]).
+    pub fn with_secrets(secrets: JwtSecrets) -> Self {
+        Self { secrets, metrics: Default::default() }
     }
 }
 
 impl AuthValidator for JwtAuthValidator {
     fn validate(&self, headers: &HeaderMap) -> Result<(), HttpResponse> {
         match get_bearer(headers) {
-            Some(jwt) => match self.secret.validate(&jwt) {
-                Ok(_) => Ok(()),
+            Some(jwt) => match self.secrets.validate(&jwt) {
+                Ok(index) => {
+                    if index > 0 {
+                        self.metrics.non_primary_secret_total.increment(1);
+                    }
+                    Ok(())
+                }
                 Err(e) => {
                     error!(target: "engine::jwt-validator", "Invalid JWT: {e}");
+                    self.metrics.invalid_token_total.increment(1);
                     let response = err_response(e);
                     Err(response)
                 }
@@ -35,6 +49,7 @@ impl AuthValidator for JwtAuthValidator {
             None => {
                 let e = JwtError::MissingOrInvalidAuthorizationHeader;
                 error!(target: "engine::jwt-validator", "Invalid JWT: {e}");
+                self.metrics.missing_token_total.increment(1);
                 let response = err_response(e);
                 Err(response)
             }
@@ -42,6 +57,19 @@ impl AuthValidator for JwtAuthValidator {
     }
 }
 
+/// Metrics of the JWT authentication of the rpc servers.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.jwt_auth")]
+struct JwtAuthMetrics {
+    /// The number of requests rejected because of a missing or malformed authorization header
+    missing_token_total: Counter,
+    /// The number of requests rejected because of a JWT that is invalid for all secrets
+    invalid_token_total: Counter,
+    /// The number of requests authenticated with a JWT signed with another secret than the
+    /// primary secret
+    non_primary_secret_total: Counter,
+}
+
 /// This is an utility function that retrieves a bearer
 /// token from an authorization Http header.
 fn get_bearer(headers: &HeaderMap) -> Option<String> {
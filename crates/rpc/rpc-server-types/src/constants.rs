@@ -15,6 +15,17 @@ pub const DEFAULT_MAX_BLOCKS_PER_FILTER: u64 = 100_000;
 /// The default maximum of logs in a single response.
 pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
 
+/// The default maximum number of witnesses returned by a single `reth_getWitnessRange` call.
+pub const DEFAULT_MAX_WITNESSES_PER_RESPONSE: u64 = 16;
+
+/// The default maximum size of the compressed witnesses returned by a single
+/// `reth_getWitnessRange` call in bytes: 16 MiB.
+pub const DEFAULT_MAX_WITNESS_RESPONSE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The default memory limit of the EVM executions of `eth_call` and tracing requests in bytes:
+/// 32 MiB.
+pub const DEFAULT_EVM_MEMORY_LIMIT: u64 = 32 * 1024 * 1024;
+
 /// The default maximum number tracing requests we're allowing concurrently.
 /// Tracing is mostly CPU bound so we're limiting the number of concurrent requests to something
 /// lower that the number of cores, in order to minimize the impact on the rest of the system.
@@ -15,4 +15,7 @@ pub mod result;
 mod module;
 pub use module::{RethRpcModule, RpcModuleSelection};
 
+mod toggle;
+pub use toggle::{RpcModuleToggle, RpcModuleToggleError, ADMIN_NAMESPACE};
+
 pub use result::ToRpcResult;
@@ -107,6 +107,15 @@ impl RpcModuleSelection {
         }
     }
 
+    /// Returns true if the given module is part of the selection.
+    pub fn contains(&self, module: &RethRpcModule) -> bool {
+        match self {
+            Self::All => true,
+            Self::Standard => Self::STANDARD_MODULES.contains(module),
+            Self::Selection(s) => s.contains(module),
+        }
+    }
+
     /// Clones the set of configured [`RethRpcModule`].
     pub fn to_selection(&self) -> HashSet<RethRpcModule> {
         match self {
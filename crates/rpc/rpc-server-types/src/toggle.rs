@@ -0,0 +1,124 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+/// The namespace that can't be disabled, so that disabled namespaces can always be enabled again.
+pub const ADMIN_NAMESPACE: &str = "admin";
+
+/// A handle to enable and disable whole RPC namespaces of running servers.
+///
+/// Disabled namespaces stay installed, but their calls are rejected by the servers. All clones of
+/// the handle share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct RpcModuleToggle {
+    inner: Arc<RwLock<ToggleState>>,
+}
+
+#[derive(Debug, Default)]
+struct ToggleState {
+    /// The namespaces installed on any of the servers.
+    namespaces: HashSet<String>,
+    /// The namespaces that are disabled.
+    disabled: HashSet<String>,
+}
+
+impl RpcModuleToggle {
+    /// Registers the namespaces of the given method names, so they can be toggled.
+    pub fn register_methods<'a>(&self, methods: impl IntoIterator<Item = &'a str>) {
+        let mut state = self.inner.write().expect("not poisoned");
+        state.namespaces.extend(methods.into_iter().filter_map(namespace).map(str::to_string));
+    }
+
+    /// Enables or disables the given namespace.
+    ///
+    /// Returns `true` if the state of the namespace changed.
+    pub fn set_enabled(
+        &self,
+        namespace: &str,
+        enabled: bool,
+    ) -> Result<bool, RpcModuleToggleError> {
+        if namespace == ADMIN_NAMESPACE && !enabled {
+            return Err(RpcModuleToggleError::Admin)
+        }
+        let mut state = self.inner.write().expect("not poisoned");
+        if !state.namespaces.contains(namespace) {
+            return Err(RpcModuleToggleError::Unknown(namespace.to_string()))
+        }
+        Ok(if enabled {
+            state.disabled.remove(namespace)
+        } else {
+            state.disabled.insert(namespace.to_string())
+        })
+    }
+
+    /// Returns `true` if the namespace of the given method is not disabled.
+    pub fn is_method_enabled(&self, method: &str) -> bool {
+        let state = self.inner.read().expect("not poisoned");
+        state.disabled.is_empty() ||
+            namespace(method).map_or(true, |namespace| !state.disabled.contains(namespace))
+    }
+
+    /// Returns all registered namespaces and whether they are enabled.
+    pub fn namespaces(&self) -> BTreeMap<String, bool> {
+        let state = self.inner.read().expect("not poisoned");
+        state
+            .namespaces
+            .iter()
+            .map(|namespace| (namespace.clone(), !state.disabled.contains(namespace)))
+            .collect()
+    }
+}
+
+/// Returns the namespace of a method name, e.g. `eth` for `eth_call`.
+fn namespace(method: &str) -> Option<&str> {
+    method.split_once('_').map(|(namespace, _)| namespace)
+}
+
+/// Errors of toggling a namespace with a [`RpcModuleToggle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcModuleToggleError {
+    /// The namespace isn't installed on any server.
+    Unknown(String),
+    /// The admin namespace can't be disabled.
+    Admin,
+}
+
+impl fmt::Display for RpcModuleToggleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(namespace) => write!(f, "unknown namespace: {namespace}"),
+            Self::Admin => write!(f, "the {ADMIN_NAMESPACE} namespace can't be disabled"),
+        }
+    }
+}
+
+impl std::error::Error for RpcModuleToggleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_namespaces() {
+        let toggle = RpcModuleToggle::default();
+        toggle.register_methods(["eth_call", "debug_traceTransaction", "admin_peers"]);
+        assert!(toggle.is_method_enabled("debug_traceTransaction"));
+
+        assert_eq!(toggle.set_enabled("debug", false), Ok(true));
+        assert_eq!(toggle.set_enabled("debug", false), Ok(false));
+        assert!(!toggle.clone().is_method_enabled("debug_traceTransaction"));
+        assert!(toggle.is_method_enabled("eth_call"));
+        assert_eq!(toggle.namespaces().get("debug"), Some(&false));
+
+        assert_eq!(toggle.set_enabled("debug", true), Ok(true));
+        assert!(toggle.is_method_enabled("debug_traceTransaction"));
+
+        assert_eq!(toggle.set_enabled("admin", false), Err(RpcModuleToggleError::Admin));
+        assert_eq!(
+            toggle.set_enabled("trace", false),
+            Err(RpcModuleToggleError::Unknown("trace".to_string()))
+        );
+    }
+}
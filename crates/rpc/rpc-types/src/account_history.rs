@@ -0,0 +1,17 @@
+use alloy_primitives::{BlockNumber, U256};
+use serde::{Deserialize, Serialize};
+
+/// The balance and nonce of an account from a block on, an entry of the response of
+/// `reth_accountHistory`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountHistoryEntry {
+    /// The block from which on the account has this balance and nonce.
+    #[serde(with = "alloy_serde::quantity")]
+    pub block: BlockNumber,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+}
@@ -9,11 +9,13 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
+mod account_history;
 #[allow(hidden_glob_reexports)]
 mod eth;
 mod mev;
 mod peer;
 mod rpc;
+mod witness;
 
 // re-export for convenience
 pub use alloy_rpc_types::serde_helpers;
@@ -51,6 +53,8 @@ pub use eth::{
     transaction::{self, TransactionRequest, TypedTransactionRequest},
 };
 
+pub use account_history::*;
 pub use mev::*;
 pub use peer::*;
 pub use rpc::*;
+pub use witness::*;
@@ -0,0 +1,55 @@
+use alloy_primitives::{BlockHash, BlockNumber, Bytes};
+use serde::{Deserialize, Serialize};
+
+/// The compressed execution witness of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockWitness {
+    /// The number of the block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub number: BlockNumber,
+    /// The hash of the block.
+    pub hash: BlockHash,
+    /// The snappy compressed RLP encoding of the execution witness of the block.
+    pub witness: Bytes,
+}
+
+/// A page of the execution witnesses of a range of blocks, the response of
+/// `reth_getWitnessRange`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WitnessRange {
+    /// The witnesses of consecutive blocks, starting with the first block of the requested range.
+    pub witnesses: Vec<BlockWitness>,
+    /// The number of the first block whose witness didn't fit into the page, if the range is not
+    /// complete. The remaining witnesses are requested with this number as the start of the range.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "alloy_serde::quantity::opt")]
+    pub next_block: Option<BlockNumber>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    #[test]
+    fn serde_witness_range() {
+        let range = WitnessRange {
+            witnesses: vec![BlockWitness {
+                number: 100,
+                hash: B256::with_last_byte(1),
+                witness: Bytes::from_static(&[0xc3, 0xc0, 0xc0, 0xc0]),
+            }],
+            next_block: Some(101),
+        };
+        let json = serde_json::to_value(&range).unwrap();
+        assert_eq!(json["witnesses"][0]["number"], "0x64");
+        assert_eq!(json["nextBlock"], "0x65");
+        assert_eq!(serde_json::from_value::<WitnessRange>(json).unwrap(), range);
+
+        let complete = WitnessRange { next_block: None, ..range };
+        let json = serde_json::to_value(&complete).unwrap();
+        assert!(json.get("nextBlock").is_none());
+        assert_eq!(serde_json::from_value::<WitnessRange>(json).unwrap(), complete);
+    }
+}
@@ -0,0 +1,349 @@
+//! Materialized views of the balance and nonce history of accounts.
+
+use futures::{Stream, StreamExt};
+use parking_lot::RwLock;
+use reth_primitives::{Account, Address, BlockNumber, U256};
+use reth_provider::{
+    AccountReader, BlockNumReader, CanonStateNotification, Chain, ChangeSetReader, ProviderResult,
+    StateProviderFactory,
+};
+use reth_rpc_types::AccountHistoryEntry;
+use revm::db::states::reverts::AccountInfoRevert;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+    sync::Arc,
+};
+use tracing::{debug, warn};
+
+/// The balance and nonce of an account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct AccountValue {
+    balance: U256,
+    nonce: u64,
+}
+
+impl From<Option<Account>> for AccountValue {
+    fn from(account: Option<Account>) -> Self {
+        account.map_or_else(Self::default, |account| Self {
+            balance: account.balance,
+            nonce: account.nonce,
+        })
+    }
+}
+
+/// The values of an account after the blocks that changed them.
+type Timeline = BTreeMap<BlockNumber, AccountValue>;
+
+/// Materialized balance and nonce timelines of a configured set of accounts.
+///
+/// Every timeline maps the blocks that changed the balance or nonce of the account to its values
+/// after the block, so the history of an account can be read without querying the historical
+/// state at every block. The timelines start at the block they were initialized at, see
+/// [`Self::init`], and are kept up to date with the canonical chain, see
+/// [`account_history_views_task`].
+///
+/// The timelines are kept in memory, and are rebuilt when the node is restarted.
+#[derive(Debug, Clone)]
+pub struct AccountHistoryViews {
+    timelines: Arc<RwLock<HashMap<Address, Timeline>>>,
+}
+
+impl AccountHistoryViews {
+    /// Creates empty views of the given accounts.
+    pub fn new(addresses: impl IntoIterator<Item = Address>) -> Self {
+        let timelines = addresses.into_iter().map(|address| (address, Timeline::new())).collect();
+        Self { timelines: Arc::new(RwLock::new(timelines)) }
+    }
+
+    /// Returns `true` if the history of the account is materialized.
+    pub fn contains(&self, address: &Address) -> bool {
+        self.timelines.read().contains_key(address)
+    }
+
+    /// Initializes the timelines with the state of the canonical head, and the changes of the
+    /// `backfill` blocks before it, read from the account changesets.
+    pub fn init<P>(&self, provider: &P, backfill: u64) -> ProviderResult<()>
+    where
+        P: StateProviderFactory + ChangeSetReader + BlockNumReader,
+    {
+        let head = provider.best_block_number()?;
+        let start = head.saturating_sub(backfill);
+        let latest = provider.latest()?;
+
+        let mut timelines = self.timelines.write();
+        let mut changes = Changes::default();
+        for address in timelines.keys() {
+            changes.current.insert(*address, latest.basic_account(*address)?.into());
+        }
+        for number in (start + 1..=head).rev() {
+            for change in provider.account_block_changeset(number)? {
+                changes.revert(number, change.address, change.info.into());
+            }
+        }
+
+        for (address, timeline) in timelines.iter_mut() {
+            timeline.clear();
+            timeline.insert(start, changes.current[address]);
+        }
+        changes.apply(&mut timelines);
+
+        debug!(target: "rpc::account_history", start, head, accounts = timelines.len(), "Initialized account history views");
+        Ok(())
+    }
+
+    /// Updates the timelines with the changes of the canonical chain.
+    ///
+    /// The changes of reverted blocks are removed from the timelines.
+    pub fn on_canonical_state(&self, notification: &CanonStateNotification) {
+        let mut timelines = self.timelines.write();
+        if let CanonStateNotification::Reorg { old, .. } = notification {
+            let fork_block = old.first().number.saturating_sub(1);
+            let changes = Changes::from_chain(old, &timelines);
+            for timeline in timelines.values_mut() {
+                timeline.split_off(&(fork_block + 1));
+            }
+            for (address, value) in changes.current {
+                if let Some(timeline) = timelines.get_mut(&address) {
+                    insert_value(timeline, fork_block, value);
+                }
+            }
+        }
+
+        let new = notification.committed();
+        for timeline in timelines.values_mut() {
+            timeline.split_off(&new.first().number);
+        }
+        Changes::from_chain(&new, &timelines).apply(&mut timelines);
+    }
+
+    /// Returns the balance and nonce history of the account in the given block range, or `None`
+    /// if the history of the account is not materialized.
+    ///
+    /// The first entry is the value of the account at the start of the range, followed by the
+    /// changes in the range. Changes before the start of the timeline are not included.
+    pub fn history(
+        &self,
+        address: &Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Option<Vec<AccountHistoryEntry>> {
+        let timelines = self.timelines.read();
+        let timeline = timelines.get(address)?;
+        let (from, to) = range.into_inner();
+        if from > to {
+            return Some(Vec::new())
+        }
+
+        let start = timeline.range(..=from).next_back();
+        let changes = timeline.range(from..=to).filter(|(block, _)| **block > from);
+        Some(
+            start
+                .into_iter()
+                .chain(changes)
+                .map(|(block, value)| AccountHistoryEntry {
+                    block: *block,
+                    balance: value.balance,
+                    nonce: value.nonce,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The changes of the accounts of the views, collected by walking back from their current values.
+#[derive(Debug, Default)]
+struct Changes {
+    /// The values of the accounts before the blocks walked so far.
+    current: HashMap<Address, AccountValue>,
+    /// The values of the accounts after the blocks that changed them, in descending order of
+    /// blocks.
+    changes: HashMap<Address, Vec<(BlockNumber, AccountValue)>>,
+}
+
+impl Changes {
+    /// Collects the changes of the accounts of the timelines in the blocks of the chain, from the
+    /// reverts of its execution outcome.
+    fn from_chain(chain: &Chain, timelines: &HashMap<Address, Timeline>) -> Self {
+        let outcome = chain.execution_outcome();
+        let mut changes = Self::default();
+        for address in timelines.keys() {
+            if let Some(account) = outcome.account(address) {
+                changes.current.insert(*address, account.into());
+            }
+        }
+
+        if outcome.bundle.reverts.len() != chain.len() {
+            // without the reverts of the blocks, only the state after the chain is known
+            warn!(target: "rpc::account_history", blocks = chain.len(), reverts = outcome.bundle.reverts.len(), "Missing reverts of canonical chain");
+            for (address, value) in &changes.current {
+                changes.changes.insert(*address, vec![(chain.tip().number, *value)]);
+            }
+            return changes
+        }
+
+        for (index, reverts) in outcome.bundle.reverts.iter().enumerate().rev() {
+            let number = outcome.first_block + index as u64;
+            for (address, revert) in reverts {
+                let previous = match &revert.account {
+                    AccountInfoRevert::DoNothing => continue,
+                    AccountInfoRevert::DeleteIt => AccountValue::default(),
+                    AccountInfoRevert::RevertTo(info) => {
+                        AccountValue { balance: info.balance, nonce: info.nonce }
+                    }
+                };
+                changes.revert(number, *address, previous);
+            }
+        }
+        changes
+    }
+
+    /// Records the change of the account in the block, if it belongs to the views, and walks back
+    /// to its value before the block.
+    fn revert(&mut self, number: BlockNumber, address: Address, previous: AccountValue) {
+        if let Some(current) = self.current.get_mut(&address) {
+            self.changes.entry(address).or_default().push((number, *current));
+            *current = previous;
+        }
+    }
+
+    /// Inserts the changes into the timelines.
+    fn apply(self, timelines: &mut HashMap<Address, Timeline>) {
+        for (address, changes) in self.changes {
+            if let Some(timeline) = timelines.get_mut(&address) {
+                for (number, value) in changes.into_iter().rev() {
+                    insert_value(timeline, number, value);
+                }
+            }
+        }
+    }
+}
+
+/// Inserts the value of the account after the block into the timeline, unless it didn't change.
+fn insert_value(timeline: &mut Timeline, number: BlockNumber, value: AccountValue) {
+    if timeline.range(..number).next_back().map(|(_, previous)| previous) == Some(&value) {
+        timeline.remove(&number);
+    } else {
+        timeline.insert(number, value);
+    }
+}
+
+/// Initializes the views and keeps them up to date with the canonical chain.
+pub async fn account_history_views_task<St, Provider>(
+    views: AccountHistoryViews,
+    provider: Provider,
+    backfill: u64,
+    mut events: St,
+) where
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+    Provider: StateProviderFactory + ChangeSetReader + BlockNumReader + 'static,
+{
+    if let Err(err) = views.init(&provider, backfill) {
+        warn!(target: "rpc::account_history", %err, "Failed to initialize account history views");
+    }
+
+    while let Some(notification) = events.next().await {
+        views.on_canonical_state(&notification);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, SealedBlock, SealedBlockWithSenders};
+    use reth_provider::ExecutionOutcome;
+    use revm::{
+        db::{states::reverts::Reverts, AccountRevert, AccountStatus, BundleAccount, BundleState},
+        primitives::AccountInfo,
+    };
+
+    fn info(balance: u64, nonce: u64) -> AccountInfo {
+        AccountInfo { balance: U256::from(balance), nonce, ..Default::default() }
+    }
+
+    /// Creates a chain of the given blocks, with the final state of the account and the reverts
+    /// of each block.
+    fn chain(
+        address: Address,
+        blocks: RangeInclusive<BlockNumber>,
+        state: AccountInfo,
+        reverts: Vec<Option<AccountInfoRevert>>,
+    ) -> Arc<Chain> {
+        let first_block = *blocks.start();
+        let blocks = blocks.map(|number| SealedBlockWithSenders {
+            block: SealedBlock {
+                header: Header { number, ..Default::default() }.seal_slow(),
+                ..Default::default()
+            },
+            senders: Vec::new(),
+        });
+        let account =
+            BundleAccount::new(None, Some(state), Default::default(), AccountStatus::Changed);
+        let reverts = reverts
+            .into_iter()
+            .map(|revert| {
+                revert
+                    .map(|account| (address, AccountRevert { account, ..Default::default() }))
+                    .into_iter()
+                    .collect()
+            })
+            .collect();
+        let bundle = BundleState {
+            state: [(address, account)].into_iter().collect(),
+            reverts: Reverts::new(reverts),
+            ..Default::default()
+        };
+        let outcome = ExecutionOutcome { bundle, first_block, ..Default::default() };
+        Arc::new(Chain::new(blocks, outcome, None))
+    }
+
+    fn entry(block: BlockNumber, balance: u64, nonce: u64) -> AccountHistoryEntry {
+        AccountHistoryEntry { block, balance: U256::from(balance), nonce }
+    }
+
+    #[test]
+    fn account_timelines() {
+        let address = Address::with_last_byte(1);
+        let views = AccountHistoryViews::new([address]);
+        views.timelines.write().get_mut(&address).unwrap().insert(10, AccountValue::default());
+
+        // changed in blocks 11 and 13
+        views.on_canonical_state(&CanonStateNotification::Commit {
+            new: chain(
+                address,
+                11..=13,
+                info(3, 2),
+                vec![
+                    Some(AccountInfoRevert::DeleteIt),
+                    None,
+                    Some(AccountInfoRevert::RevertTo(info(1, 1))),
+                ],
+            ),
+        });
+        assert_eq!(
+            views.history(&address, 10..=20).unwrap(),
+            vec![entry(10, 0, 0), entry(11, 1, 1), entry(13, 3, 2)]
+        );
+        assert_eq!(views.history(&address, 12..=12).unwrap(), vec![entry(11, 1, 1)]);
+        assert!(views.history(&Address::ZERO, 10..=20).is_none());
+
+        // reorg of block 13, changed in block 14 of the new chain
+        views.on_canonical_state(&CanonStateNotification::Reorg {
+            old: chain(
+                address,
+                13..=13,
+                info(3, 2),
+                vec![Some(AccountInfoRevert::RevertTo(info(1, 1)))],
+            ),
+            new: chain(
+                address,
+                13..=14,
+                info(5, 2),
+                vec![None, Some(AccountInfoRevert::RevertTo(info(1, 1)))],
+            ),
+        });
+        assert_eq!(
+            views.history(&address, 10..=20).unwrap(),
+            vec![entry(10, 0, 0), entry(11, 1, 1), entry(14, 5, 2)]
+        );
+    }
+}
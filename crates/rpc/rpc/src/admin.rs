@@ -1,18 +1,28 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use alloy_genesis::ChainConfig;
 use alloy_primitives::B256;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_chainspec::ChainSpec;
+use reth_config::{ConfigReloadReport, ConfigReloader};
 use reth_network_api::{NetworkInfo, PeerKind, Peers};
 use reth_network_peers::{AnyNode, NodeRecord};
-use reth_rpc_api::AdminApiServer;
-use reth_rpc_server_types::ToRpcResult;
+use reth_rpc_api::{
+    AdminApiServer, AdminConfigApiServer, AdminExecutionApiServer, AdminJwtApiServer,
+    AdminPipelineApiServer, AdminRpcModulesApiServer,
+};
+use reth_rpc_eth_types::ExecutionLimits;
+use reth_rpc_layer::JwtSecretsFile;
+use reth_rpc_server_types::{
+    result::{internal_rpc_err, invalid_params_rpc_err},
+    RpcModuleToggle, ToRpcResult,
+};
 use reth_rpc_types::{
     admin::{EthProtocolInfo, NodeInfo, Ports, ProtocolInfo},
     PeerEthProtocolInfo, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 };
+use reth_stages_api::PipelinePauseHandle;
 
 /// `admin` API implementation.
 ///
@@ -142,3 +152,134 @@ impl<N> std::fmt::Debug for AdminApi<N> {
         f.debug_struct("AdminApi").finish_non_exhaustive()
     }
 }
+
+/// `admin` API implementation for controlling the sync pipeline.
+#[derive(Debug, Clone)]
+pub struct AdminPipelineApi {
+    /// Handle to pause and resume the pipeline.
+    pause_handle: PipelinePauseHandle,
+}
+
+impl AdminPipelineApi {
+    /// Creates a new instance of `AdminPipelineApi`.
+    pub const fn new(pause_handle: PipelinePauseHandle) -> Self {
+        Self { pause_handle }
+    }
+}
+
+impl AdminPipelineApiServer for AdminPipelineApi {
+    /// Handler for `admin_pausePipeline`
+    fn pause_pipeline(&self) -> RpcResult<bool> {
+        self.pause_handle
+            .pause()
+            .map_err(|err| internal_rpc_err(format!("failed to pause pipeline: {err}")))
+    }
+
+    /// Handler for `admin_resumePipeline`
+    fn resume_pipeline(&self) -> RpcResult<bool> {
+        self.pause_handle
+            .resume()
+            .map_err(|err| internal_rpc_err(format!("failed to resume pipeline: {err}")))
+    }
+
+    /// Handler for `admin_pipelinePaused`
+    fn pipeline_paused(&self) -> RpcResult<bool> {
+        Ok(self.pause_handle.is_paused())
+    }
+}
+
+/// `admin` API implementation for reloading the configuration file.
+#[derive(Debug, Clone)]
+pub struct AdminConfigApi {
+    /// Reloads the configuration file of the node.
+    reloader: Arc<dyn ConfigReloader>,
+}
+
+impl AdminConfigApi {
+    /// Creates a new instance of `AdminConfigApi`.
+    pub fn new(reloader: Arc<dyn ConfigReloader>) -> Self {
+        Self { reloader }
+    }
+}
+
+impl AdminConfigApiServer for AdminConfigApi {
+    /// Handler for `admin_reloadConfig`
+    fn reload_config(&self) -> RpcResult<ConfigReloadReport> {
+        self.reloader
+            .reload_config()
+            .map_err(|err| internal_rpc_err(format!("failed to reload config: {err}")))
+    }
+}
+
+/// `admin` API implementation for rotating the JWT secrets of the auth server.
+#[derive(Debug, Clone)]
+pub struct AdminJwtApi {
+    /// The secret file of the auth server.
+    secret_file: JwtSecretsFile,
+}
+
+impl AdminJwtApi {
+    /// Creates a new instance of `AdminJwtApi`.
+    pub const fn new(secret_file: JwtSecretsFile) -> Self {
+        Self { secret_file }
+    }
+}
+
+impl AdminJwtApiServer for AdminJwtApi {
+    /// Handler for `admin_reloadJwtSecret`
+    fn reload_jwt_secret(&self) -> RpcResult<usize> {
+        self.secret_file
+            .reload()
+            .map_err(|err| internal_rpc_err(format!("failed to reload JWT secrets: {err}")))
+    }
+}
+
+/// `admin` API implementation for aborting the EVM executions of rpc requests.
+#[derive(Debug, Clone)]
+pub struct AdminExecutionApi {
+    /// The limits of the executions of the `eth` API.
+    limits: ExecutionLimits,
+}
+
+impl AdminExecutionApi {
+    /// Creates a new instance of `AdminExecutionApi`.
+    pub const fn new(limits: ExecutionLimits) -> Self {
+        Self { limits }
+    }
+}
+
+impl AdminExecutionApiServer for AdminExecutionApi {
+    /// Handler for `admin_cancelExecutions`
+    fn cancel_executions(&self) -> RpcResult<()> {
+        self.limits.cancel_all();
+        Ok(())
+    }
+}
+
+/// `admin` API implementation for enabling and disabling rpc namespaces at runtime.
+#[derive(Debug, Clone)]
+pub struct AdminRpcModulesApi {
+    /// Enables and disables the namespaces of the running servers.
+    toggle: RpcModuleToggle,
+}
+
+impl AdminRpcModulesApi {
+    /// Creates a new instance of `AdminRpcModulesApi`.
+    pub const fn new(toggle: RpcModuleToggle) -> Self {
+        Self { toggle }
+    }
+}
+
+impl AdminRpcModulesApiServer for AdminRpcModulesApi {
+    /// Handler for `admin_toggleRpcModule`
+    fn toggle_rpc_module(&self, module: String, enabled: bool) -> RpcResult<bool> {
+        self.toggle
+            .set_enabled(&module, enabled)
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `admin_rpcModules`
+    fn rpc_modules(&self) -> RpcResult<BTreeMap<String, bool>> {
+        Ok(self.toggle.namespaces())
+    }
+}
@@ -1,21 +1,28 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
+use rayon::prelude::*;
 use reth_chainspec::EthereumHardforks;
 use reth_primitives::{
     revm::env::tx_env_with_recovered, Address, Block, BlockId, BlockNumberOrTag, Bytes,
     TransactionSignedEcRecovered, Withdrawals, B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory,
-    TransactionVariant,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProvider,
+    StateProviderFactory, TransactionVariant,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_eth_api::helpers::{EthApiSpec, EthTransactions, TraceExt};
-use reth_rpc_eth_types::{revm_utils::prepare_call_env, EthApiError, EthResult, StateCacheDb};
+use reth_rpc_eth_types::{
+    cache::db::StateProviderTraitObjWrapper, revm_utils::prepare_call_env, EthApiError, EthResult,
+    ExecutionScope, StateCacheDb,
+};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
 use reth_rpc_types::{
     state::EvmOverrides,
@@ -28,11 +35,17 @@ use reth_rpc_types::{
 use reth_tasks::pool::BlockingTaskGuard;
 use revm::{
     db::CacheDB,
-    primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
+    primitives::{
+        db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg, EvmState,
+    },
+    DatabaseRef,
 };
-use revm_inspectors::tracing::{
-    js::{JsInspector, TransactionContext},
-    FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig,
+use revm_inspectors::{
+    access_list::AccessListInspector,
+    tracing::{
+        js::{JsInspector, TransactionContext},
+        FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig,
+    },
 };
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
@@ -94,9 +107,43 @@ where
         self.eth_api()
             .spawn_with_state_at_block(at, move |state| {
                 let block_hash = at.as_block_hash();
+
+                // the leading transactions that don't depend on each other are traced in parallel
+                // on top of the parent state, and their state changes are applied in block order
+                let independent = if transactions.len() >= MIN_PARALLEL_TRACING_TRANSACTIONS &&
+                    supports_parallel_tracing(&opts)
+                {
+                    this.trace_independent_transactions(
+                        state.0,
+                        &transactions,
+                        &cfg,
+                        &block_env,
+                        &opts,
+                        block_hash,
+                    )?
+                } else {
+                    Vec::new()
+                };
+
                 let mut results = Vec::with_capacity(transactions.len());
                 let mut db = CacheDB::new(StateProviderDatabase::new(state));
-                let mut transactions = transactions.into_iter().enumerate().peekable();
+                for (result, mut state_changes) in independent {
+                    // every transaction paid its fee on top of the parent balance of the
+                    // coinbase, so only the difference is applied
+                    if let Some(coinbase) = state_changes.get_mut(&block_env.coinbase) {
+                        let parent = db.db.basic_ref(block_env.coinbase)?.unwrap_or_default();
+                        let current = db.basic_ref(block_env.coinbase)?.unwrap_or_default();
+                        coinbase.info.balance = current
+                            .balance
+                            .saturating_add(coinbase.info.balance)
+                            .saturating_sub(parent.balance);
+                    }
+                    db.commit(state_changes);
+                    results.push(result);
+                }
+
+                let mut transactions =
+                    transactions.into_iter().enumerate().skip(results.len()).peekable();
                 while let Some((index, tx)) = transactions.next() {
                     let tx_hash = tx.hash;
                     let tx = tx_env_with_recovered(&tx);
@@ -128,6 +175,107 @@ where
             .await
     }
 
+    /// Traces the leading transactions of a block that don't depend on the state changes of a
+    /// previous transaction in parallel, each on top of the state of the parent block.
+    ///
+    /// The transactions are first executed in parallel, in chunks of the size of the thread pool,
+    /// to determine the state they read and change. The analysis stops at the first transaction
+    /// that reads state changed by a previous transaction, because its trace on top of the parent
+    /// state could differ. Only the transactions before it are traced, the rest of the block must
+    /// be traced serially on top of their state changes.
+    ///
+    /// This runs on the threads of the tracing pool the caller runs on.
+    ///
+    /// Caution: this is blocking and should be performed on a blocking task.
+    fn trace_independent_transactions(
+        &self,
+        state: &dyn StateProvider,
+        transactions: &[TransactionSignedEcRecovered],
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        opts: &GethDebugTracingOptions,
+        block_hash: Option<B256>,
+    ) -> EthResult<Vec<(TraceResult, EvmState)>> {
+        // transactions of the same sender always depend on each other through the nonce
+        let mut senders = HashSet::with_capacity(transactions.len());
+        let candidates = transactions.iter().take_while(|tx| senders.insert(tx.signer())).count();
+        let transactions = &transactions[..candidates];
+
+        let env = |tx: &TransactionSignedEcRecovered| EnvWithHandlerCfg {
+            env: Env::boxed(cfg.cfg_env.clone(), block_env.clone(), tx_env_with_recovered(tx)),
+            handler_cfg: cfg.handler_cfg,
+        };
+        let parent_db =
+            || CacheDB::new(StateProviderDatabase::new(StateProviderTraitObjWrapper(state)));
+        // the transactions are executed on other threads of the pool, which must enter the scope
+        // of the request to be cancellable
+        let scope = ExecutionScope::current();
+
+        // a transaction that can't be executed on top of the parent state, e.g. because it can
+        // only pay for gas with funds received in the block, depends on a previous transaction
+        let chunk_size = rayon::current_num_threads().max(MIN_PARALLEL_TRACING_TRANSACTIONS);
+        let mut accesses = Vec::with_capacity(transactions.len());
+        for chunk in transactions.chunks(chunk_size) {
+            let chunk_accesses = chunk
+                .par_iter()
+                .map(|tx| {
+                    let mut db = parent_db();
+                    let mut inspector = AccessListInspector::default();
+                    let (res, _) = ExecutionScope::enter_optional(scope.as_ref(), || {
+                        self.eth_api().inspect(&mut db, env(tx), &mut inspector)
+                    })
+                    .ok()?;
+                    let coinbase_accessed = inspector
+                        .into_access_list()
+                        .0
+                        .iter()
+                        .any(|item| item.address == block_env.coinbase);
+                    TransactionAccesses::new(
+                        tx,
+                        block_env.coinbase,
+                        coinbase_accessed,
+                        &res.state,
+                        &db,
+                    )
+                    .ok()
+                })
+                .collect::<Vec<_>>();
+            let executed = accesses.len() + chunk.len();
+            accesses.extend(chunk_accesses.into_iter().map_while(|accesses| accesses));
+            if let Some(conflict) = TransactionAccesses::first_conflict(&accesses) {
+                accesses.truncate(conflict);
+            }
+            if accesses.len() < executed {
+                break
+            }
+        }
+        if accesses.len() < 2 {
+            // nothing to gain from tracing a single transaction in parallel
+            return Ok(Vec::new())
+        }
+
+        transactions[..accesses.len()]
+            .par_iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let (result, state_changes) =
+                    ExecutionScope::enter_optional(scope.as_ref(), || {
+                        self.trace_transaction(
+                            opts.clone(),
+                            env(tx),
+                            &mut parent_db(),
+                            Some(TransactionContext {
+                                block_hash,
+                                tx_hash: Some(tx.hash),
+                                tx_index: Some(index),
+                            }),
+                        )
+                    })?;
+                Ok((TraceResult::Success { result, tx_hash: Some(tx.hash) }, state_changes))
+            })
+            .collect()
+    }
+
     /// Replays the given block and returns the trace of each transaction.
     ///
     /// This expects a rlp encoded block
@@ -1008,6 +1156,107 @@ impl<Provider, Eth> Clone for DebugApi<Provider, Eth> {
     }
 }
 
+/// Minimum number of transactions of a block to trace them in parallel, below which the cost of
+/// analyzing the dependencies of the transactions outweighs the gain.
+const MIN_PARALLEL_TRACING_TRANSACTIONS: usize = 4;
+
+/// Returns `true` if the traces of the tracer only depend on the execution of the transaction, so
+/// independent transactions can be traced in parallel.
+///
+/// Tracers that report account state, e.g. the prestate tracer, or that can read it, e.g. JS
+/// tracers, are always run serially.
+const fn supports_parallel_tracing(opts: &GethDebugTracingOptions) -> bool {
+    match &opts.tracer {
+        None => true,
+        Some(GethDebugTracerType::BuiltInTracer(tracer)) => matches!(
+            tracer,
+            GethDebugBuiltInTracerType::FourByteTracer |
+                GethDebugBuiltInTracerType::CallTracer |
+                GethDebugBuiltInTracerType::NoopTracer
+        ),
+        Some(GethDebugTracerType::JsTracer(_)) => false,
+    }
+}
+
+/// State read and changed by a transaction executed on top of the state of the parent block.
+#[derive(Debug, Default)]
+struct TransactionAccesses {
+    /// Read accounts, with the read storage slots.
+    reads: HashMap<Address, HashSet<U256>>,
+    /// Accounts whose balance, nonce or code changed, including created and removed accounts.
+    changed_accounts: HashSet<Address>,
+    /// Changed storage slots.
+    changed_slots: HashSet<(Address, U256)>,
+}
+
+impl TransactionAccesses {
+    /// Returns the state accessed by the transaction, given the state loaded by its execution on
+    /// top of `db`.
+    ///
+    /// Every transaction pays its fee to the coinbase, so the coinbase is only considered read if
+    /// the transaction itself accesses it, e.g. with `BALANCE`.
+    fn new(
+        tx: &TransactionSignedEcRecovered,
+        coinbase: Address,
+        coinbase_accessed: bool,
+        state: &EvmState,
+        db: &StateCacheDb<'_>,
+    ) -> EthResult<Self> {
+        let coinbase_read =
+            coinbase_accessed || tx.signer() == coinbase || tx.to() == Some(coinbase);
+
+        let mut accesses = Self::default();
+        for (address, account) in state {
+            if *address != coinbase || coinbase_read || account.is_created() {
+                accesses.reads.insert(*address, account.storage.keys().copied().collect());
+            }
+            if !account.is_touched() {
+                continue
+            }
+
+            // touched empty accounts are removed
+            let info_changed = account.is_created() ||
+                account.is_selfdestructed() ||
+                account.info.is_empty() ||
+                db.basic_ref(*address)?.map_or(true, |original| {
+                    original.balance != account.info.balance ||
+                        original.nonce != account.info.nonce ||
+                        original.code_hash != account.info.code_hash
+                });
+            if info_changed {
+                accesses.changed_accounts.insert(*address);
+            }
+            accesses.changed_slots.extend(
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(slot, _)| (*address, *slot)),
+            );
+        }
+        Ok(accesses)
+    }
+
+    /// Returns the index of the first of the transactions, in block order, that reads state
+    /// changed by a previous transaction.
+    fn first_conflict(transactions: &[Self]) -> Option<usize> {
+        let mut changed_accounts = HashSet::new();
+        let mut changed_slots = HashSet::new();
+        for (index, tx) in transactions.iter().enumerate() {
+            let conflict = tx.reads.iter().any(|(address, slots)| {
+                changed_accounts.contains(address) ||
+                    slots.iter().any(|slot| changed_slots.contains(&(*address, *slot)))
+            });
+            if conflict {
+                return Some(index)
+            }
+            changed_accounts.extend(tx.changed_accounts.iter().copied());
+            changed_slots.extend(tx.changed_slots.iter().copied());
+        }
+        None
+    }
+}
+
 struct DebugApiInner<Provider, Eth> {
     /// The provider that can interact with the chain.
     provider: Provider,
@@ -1016,3 +1265,41 @@ struct DebugApiInner<Provider, Eth> {
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicts_of_transaction_accesses() {
+        let (token, alice, bob) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let transfer = |sender: Address, slot: u64| TransactionAccesses {
+            reads: HashMap::from([
+                (sender, HashSet::new()),
+                (token, HashSet::from([U256::from(slot)])),
+            ]),
+            changed_accounts: HashSet::from([sender]),
+            changed_slots: HashSet::from([(token, U256::from(slot))]),
+        };
+
+        // transfers of the same token changing different slots are independent
+        assert_eq!(
+            TransactionAccesses::first_conflict(&[transfer(alice, 1), transfer(bob, 2)]),
+            None
+        );
+        // reading a slot changed by a previous transaction is a conflict
+        assert_eq!(
+            TransactionAccesses::first_conflict(&[
+                transfer(alice, 1),
+                transfer(bob, 2),
+                transfer(bob, 1)
+            ]),
+            Some(2)
+        );
+        // reading an account changed by a previous transaction is a conflict
+        let mut read_alice = transfer(bob, 2);
+        read_alice.reads.insert(alice, HashSet::new());
+        assert_eq!(TransactionAccesses::first_conflict(&[transfer(alice, 1), read_alice]), Some(1));
+    }
+}
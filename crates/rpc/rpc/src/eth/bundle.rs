@@ -23,7 +23,8 @@ use reth_rpc_eth_api::{
     EthCallBundleApiServer,
 };
 use reth_rpc_eth_types::{
-    utils::recover_raw_transaction, EthApiError, EthResult, RpcInvalidTransactionError,
+    utils::recover_raw_transaction, EthApiError, EthResult, ExecutionScope,
+    RpcInvalidTransactionError,
 };
 
 /// `Eth` bundle implementation.
@@ -108,7 +109,11 @@ where
             .spawn_with_state_at_block(at, move |state| {
                 let coinbase = block_env.coinbase;
                 let basefee = Some(block_env.basefee.to::<u64>());
-                let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
+                let mut env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
+                let scope = ExecutionScope::current();
+                if let Some(scope) = &scope {
+                    scope.configure(&mut env.cfg);
+                }
                 let db = CacheDB::new(StateProviderDatabase::new(state));
 
                 let initial_coinbase = DatabaseRef::basic_ref(&db, coinbase)?
@@ -127,6 +132,11 @@ where
                 let mut transactions = transactions.into_iter().peekable();
 
                 while let Some((tx, signer)) = transactions.next() {
+                    // the transactions of the bundle are only checked for cancellation in between
+                    if scope.as_ref().is_some_and(|scope| scope.is_cancelled()) {
+                        return Err(EthApiError::ExecutionCancelled)
+                    }
+
                     // Verify that the given blob data, commitments, and proofs are all valid for
                     // this transaction.
                     if let PooledTransactionsElement::BlobTransaction(ref tx) = tx {
@@ -9,7 +9,9 @@ use reth_rpc_eth_api::{
     helpers::{EthSigner, SpawnBlocking},
     RawTransactionForwarder,
 };
-use reth_rpc_eth_types::{EthStateCache, FeeHistoryCache, GasCap, GasPriceOracle, PendingBlock};
+use reth_rpc_eth_types::{
+    EthStateCache, ExecutionLimits, FeeHistoryCache, GasCap, GasPriceOracle, PendingBlock,
+};
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner, TokioTaskExecutor};
 use tokio::sync::Mutex;
 
@@ -63,6 +65,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap.into().into(),
+            ExecutionLimits::default(),
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
             fee_history_cache,
@@ -80,6 +83,7 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: u64,
+        execution_limits: ExecutionLimits,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
@@ -102,6 +106,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap,
+            execution_limits,
             starting_block: U256::from(latest_block),
             task_spawner,
             pending_block: Default::default(),
@@ -129,6 +134,13 @@ where
         self.inner.gas_cap
     }
 
+    /// Returns the limits of the EVM executions of `eth_call` and tracing related calls.
+    ///
+    /// This can be used to cancel all running executions.
+    pub fn execution_limits(&self) -> &ExecutionLimits {
+        &self.inner.execution_limits
+    }
+
     /// Returns the inner `Provider`
     pub fn provider(&self) -> &Provider {
         &self.inner.provider
@@ -178,6 +190,11 @@ where
     fn tracing_task_pool(&self) -> &reth_tasks::pool::BlockingTaskPool {
         self.inner.blocking_task_pool()
     }
+
+    #[inline]
+    fn execution_limits(&self) -> &ExecutionLimits {
+        self.inner.execution_limits()
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig> {
@@ -206,6 +223,8 @@ pub struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     gas_oracle: GasPriceOracle<Provider>,
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     gas_cap: u64,
+    /// Memory limit and cancellation of the EVM executions of RPC methods.
+    execution_limits: ExecutionLimits,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
@@ -277,6 +296,12 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
         self.gas_cap
     }
 
+    /// Returns a handle to the limits of EVM executions.
+    #[inline]
+    pub const fn execution_limits(&self) -> &ExecutionLimits {
+        &self.execution_limits
+    }
+
     /// Returns a handle to the gas oracle.
     #[inline]
     pub const fn gas_oracle(&self) -> &GasPriceOracle<Provider> {
@@ -340,7 +365,7 @@ mod tests {
         provider: P,
     ) -> EthApi<P, TestPool, NoopNetwork, EthEvmConfig> {
         let evm_config = EthEvmConfig::default();
-        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config.clone());
         let fee_history_cache =
             FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
 
@@ -1,6 +1,6 @@
 //! Contains RPC handler implementations specific to transactions
 
-use reth_provider::{BlockReaderIdExt, TransactionsProvider};
+use reth_provider::{BlockReaderIdExt, PruneCheckpointReader, TransactionsProvider};
 use reth_rpc_eth_api::{
     helpers::{EthSigner, EthTransactions, LoadTransaction, SpawnBlocking},
     RawTransactionForwarder,
@@ -37,13 +37,13 @@ impl<Provider, Pool, Network, EvmConfig> LoadTransaction
     for EthApi<Provider, Pool, Network, EvmConfig>
 where
     Self: SpawnBlocking,
-    Provider: TransactionsProvider,
+    Provider: TransactionsProvider + PruneCheckpointReader,
     Pool: TransactionPool,
 {
     type Pool = Pool;
 
     #[inline]
-    fn provider(&self) -> impl reth_provider::TransactionsProvider {
+    fn provider(&self) -> impl TransactionsProvider + PruneCheckpointReader {
         self.inner.provider()
     }
 
@@ -81,7 +81,7 @@ mod tests {
         let pool = testing_pool();
 
         let evm_config = EthEvmConfig::default();
-        let cache = EthStateCache::spawn(noop_provider, Default::default(), evm_config);
+        let cache = EthStateCache::spawn(noop_provider, Default::default(), evm_config.clone());
         let fee_history_cache =
             FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
         let eth_api = EthApi::new(
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_auto_seal_consensus::MiningHandle;
+use reth_primitives::U64;
+use reth_rpc_api::EvmApiServer;
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use reth_rpc_types::anvil::MineOptions;
+
+/// `evm` API implementation of the dev mode.
+///
+/// This type provides the functionality for mining blocks on demand.
+#[derive(Debug, Clone)]
+pub struct EvmApi {
+    /// Handle to the task mining the blocks of the dev mode.
+    mining_handle: MiningHandle,
+}
+
+impl EvmApi {
+    /// Creates a new instance of `EvmApi`.
+    pub const fn new(mining_handle: MiningHandle) -> Self {
+        Self { mining_handle }
+    }
+
+    /// Sets the timestamp of the next block.
+    async fn set_next_block_timestamp(&self, timestamp: u64) -> RpcResult<()> {
+        self.mining_handle
+            .set_next_block_timestamp(timestamp)
+            .await
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))
+    }
+
+    /// Mines a single block.
+    async fn mine_block(&self) -> RpcResult<()> {
+        self.mining_handle
+            .mine()
+            .await
+            .map(|_| ())
+            .ok_or_else(|| internal_rpc_err("failed to mine block"))
+    }
+}
+
+#[async_trait]
+impl EvmApiServer for EvmApi {
+    /// Handler for `evm_mine`
+    async fn evm_mine(&self, opts: Option<MineOptions>) -> RpcResult<String> {
+        let (timestamp, blocks) = match opts.unwrap_or_default() {
+            MineOptions::Options { timestamp, blocks } => (timestamp, blocks),
+            MineOptions::Timestamp(timestamp) => (timestamp, None),
+        };
+
+        if let Some(timestamp) = timestamp {
+            self.set_next_block_timestamp(timestamp).await?;
+        }
+        for _ in 0..blocks.unwrap_or(1) {
+            self.mine_block().await?;
+        }
+
+        Ok("0x0".to_string())
+    }
+
+    /// Handler for `evm_setNextBlockTimestamp`
+    async fn evm_set_next_block_timestamp(&self, timestamp: U64) -> RpcResult<()> {
+        self.set_next_block_timestamp(timestamp.to()).await
+    }
+}
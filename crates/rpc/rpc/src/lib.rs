@@ -32,10 +32,12 @@ use jsonwebtoken as _;
 use pin_project as _;
 use tower as _;
 
+mod account_history;
 mod admin;
 mod debug;
 mod engine;
 pub mod eth;
+mod evm;
 mod net;
 mod otterscan;
 mod reth;
@@ -43,13 +45,17 @@ mod rpc;
 mod trace;
 mod txpool;
 mod web3;
-pub use admin::AdminApi;
+pub use account_history::{account_history_views_task, AccountHistoryViews};
+pub use admin::{
+    AdminApi, AdminConfigApi, AdminExecutionApi, AdminJwtApi, AdminPipelineApi, AdminRpcModulesApi,
+};
 pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthBundle, EthFilter, EthPubSub};
+pub use evm::EvmApi;
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
-pub use reth::RethApi;
+pub use reth::{RethAccountHistoryApi, RethApi, RethWitnessApi};
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
 pub use txpool::TxPoolApi;
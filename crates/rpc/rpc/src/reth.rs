@@ -1,15 +1,28 @@
-use std::{collections::HashMap, future::Future, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_errors::RethResult;
-use reth_primitives::{Address, BlockId, U256};
-use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
-use reth_rpc_api::RethApiServer;
+use reth_evm::execute::BlockExecutorProvider;
+use reth_primitives::{Address, BlockId, BlockNumber, BlockNumberOrTag, U256};
+use reth_provider::{
+    BlockReaderIdExt, ChangeSetReader, PruneCheckpointReader, StateProviderFactory,
+    TransactionVariant,
+};
+use reth_prune_types::{PruneCheckpoint, PruneSegment};
+use reth_rpc_api::{RethAccountHistoryApiServer, RethApiServer, RethWitnessApiServer};
 use reth_rpc_eth_types::{EthApiError, EthResult};
+use reth_rpc_types::{AccountHistoryEntry, BlockWitness, WitnessRange};
+use reth_stateless::{WitnessGenerator, WitnessedExecution};
 use reth_tasks::TaskSpawner;
 use tokio::sync::oneshot;
 
+use crate::AccountHistoryViews;
+
 /// `reth` API implementation.
 ///
 /// This type provides the functionality for handling `reth` prototype RPC requests.
@@ -34,7 +47,8 @@ impl<Provider> RethApi<Provider> {
 
 impl<Provider> RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + StateProviderFactory + PruneCheckpointReader + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -82,12 +96,28 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the prune checkpoints of all segments that have been pruned.
+    pub async fn prune_status(&self) -> EthResult<BTreeMap<PruneSegment, PruneCheckpoint>> {
+        self.on_blocking_task(|this| async move { this.try_prune_status() }).await
+    }
+
+    fn try_prune_status(&self) -> EthResult<BTreeMap<PruneSegment, PruneCheckpoint>> {
+        let mut checkpoints = BTreeMap::new();
+        for segment in PruneSegment::ALL {
+            if let Some(checkpoint) = self.provider().get_prune_checkpoint(segment)? {
+                checkpoints.insert(segment, checkpoint);
+            }
+        }
+        Ok(checkpoints)
+    }
 }
 
 #[async_trait]
 impl<Provider> RethApiServer for RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + StateProviderFactory + PruneCheckpointReader + 'static,
 {
     /// Handler for `reth_getBalanceChangesInBlock`
     async fn reth_get_balance_changes_in_block(
@@ -96,6 +126,11 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(Self::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_pruneStatus`
+    async fn reth_prune_status(&self) -> RpcResult<BTreeMap<PruneSegment, PruneCheckpoint>> {
+        Ok(Self::prune_status(self).await?)
+    }
 }
 
 impl<Provider> std::fmt::Debug for RethApi<Provider> {
@@ -116,3 +151,242 @@ struct RethApiInner<Provider> {
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
 }
+
+/// `reth` API implementation serving the execution witnesses of blocks.
+///
+/// The witnesses are generated by re-executing the blocks on top of the historical state of their
+/// parent, so the history of the requested blocks must not be pruned.
+pub struct RethWitnessApi<Provider, E> {
+    inner: Arc<RethWitnessApiInner<Provider, E>>,
+}
+
+// === impl RethWitnessApi ===
+
+impl<Provider, E> RethWitnessApi<Provider, E> {
+    /// The provider that can interact with the chain.
+    pub fn provider(&self) -> &Provider {
+        &self.inner.provider
+    }
+
+    /// Create a new instance of the [`RethWitnessApi`] returning at most `max_witnesses` witnesses
+    /// with at most `max_response_size` bytes per call.
+    pub fn new(
+        provider: Provider,
+        executor_provider: E,
+        task_spawner: Box<dyn TaskSpawner>,
+        max_witnesses: u64,
+        max_response_size: u64,
+    ) -> Self {
+        let inner = Arc::new(RethWitnessApiInner {
+            provider,
+            witness_generator: WitnessGenerator::new(executor_provider),
+            task_spawner,
+            max_witnesses,
+            max_response_size,
+        });
+        Self { inner }
+    }
+}
+
+impl<Provider, E> RethWitnessApi<Provider, E>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Returns a page of the witnesses of the blocks in the given range.
+    pub async fn witness_range(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> EthResult<WitnessRange> {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(this.try_witness_range(from_block, to_block));
+        }));
+        rx.await.map_err(|_| EthApiError::InternalEthError)?
+    }
+
+    fn try_witness_range(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> EthResult<WitnessRange> {
+        let from = self
+            .provider()
+            .convert_block_number(from_block)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let to = self
+            .provider()
+            .convert_block_number(to_block)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        if from == 0 {
+            return Err(EthApiError::InvalidParams("the genesis block has no witness".to_string()))
+        }
+        if from > to {
+            return Err(EthApiError::InvalidBlockRange)
+        }
+
+        let mut range = WitnessRange::default();
+        let mut size = 0;
+        for number in from..=to {
+            if range.witnesses.len() as u64 >= self.inner.max_witnesses {
+                range.next_block = Some(number);
+                break
+            }
+
+            let witness = self.block_witness(number)?;
+            size += witness.witness.len() as u64;
+            if size > self.inner.max_response_size && !range.witnesses.is_empty() {
+                range.next_block = Some(number);
+                break
+            }
+            range.witnesses.push(witness);
+        }
+        Ok(range)
+    }
+
+    /// Re-executes the block on top of the state of its parent to generate its witness.
+    fn block_witness(&self, number: BlockNumber) -> EthResult<BlockWitness> {
+        let block = self
+            .provider()
+            .block_with_senders(number.into(), TransactionVariant::WithHash)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let total_difficulty =
+            self.provider().header_td_by_number(number)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        let state_provider = self.provider().history_by_block_number(number - 1)?;
+
+        let WitnessedExecution { witness, output } = self.inner.witness_generator.execute(
+            &state_provider,
+            self.provider(),
+            &block,
+            total_difficulty,
+        )?;
+        output.map_err(|err| EthApiError::Internal(err.into()))?;
+
+        Ok(BlockWitness {
+            number,
+            hash: block.header.hash_slow(),
+            witness: witness.encode_compressed().into(),
+        })
+    }
+}
+
+#[async_trait]
+impl<Provider, E> RethWitnessApiServer for RethWitnessApi<Provider, E>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Handler for `reth_getWitnessRange`
+    async fn reth_get_witness_range(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> RpcResult<WitnessRange> {
+        Ok(Self::witness_range(self, from_block, to_block).await?)
+    }
+}
+
+impl<Provider, E> std::fmt::Debug for RethWitnessApi<Provider, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RethWitnessApi")
+            .field("max_witnesses", &self.inner.max_witnesses)
+            .field("max_response_size", &self.inner.max_response_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Provider, E> Clone for RethWitnessApi<Provider, E> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct RethWitnessApiInner<Provider, E> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// Re-executes blocks to generate their witnesses.
+    witness_generator: WitnessGenerator<E>,
+    /// The type that can spawn tasks which would otherwise block.
+    task_spawner: Box<dyn TaskSpawner>,
+    /// The maximum number of witnesses of a response.
+    max_witnesses: u64,
+    /// The maximum size of the compressed witnesses of a response in bytes.
+    max_response_size: u64,
+}
+
+/// `reth` API implementation serving the materialized balance and nonce history of accounts.
+///
+/// See [`AccountHistoryViews`].
+pub struct RethAccountHistoryApi<Provider> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// The materialized history of the configured accounts.
+    views: AccountHistoryViews,
+}
+
+// === impl RethAccountHistoryApi ===
+
+impl<Provider> RethAccountHistoryApi<Provider> {
+    /// Create a new instance of the [`RethAccountHistoryApi`] serving the history of the given
+    /// views.
+    pub const fn new(provider: Provider, views: AccountHistoryViews) -> Self {
+        Self { provider, views }
+    }
+}
+
+impl<Provider> RethAccountHistoryApi<Provider>
+where
+    Provider: BlockReaderIdExt + 'static,
+{
+    /// Returns the balance and nonce history of the account in the given range.
+    pub fn account_history(
+        &self,
+        address: Address,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> EthResult<Vec<AccountHistoryEntry>> {
+        let from = self
+            .provider
+            .convert_block_number(from_block)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let to =
+            self.provider.convert_block_number(to_block)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        if from > to {
+            return Err(EthApiError::InvalidBlockRange)
+        }
+
+        self.views.history(&address, from..=to).ok_or_else(|| {
+            EthApiError::InvalidParams(format!("the history of account {address} is not available"))
+        })
+    }
+}
+
+#[async_trait]
+impl<Provider> RethAccountHistoryApiServer for RethAccountHistoryApi<Provider>
+where
+    Provider: BlockReaderIdExt + 'static,
+{
+    /// Handler for `reth_accountHistory`
+    async fn reth_account_history(
+        &self,
+        address: Address,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> RpcResult<Vec<AccountHistoryEntry>> {
+        Ok(Self::account_history(self, address, from_block, to_block)?)
+    }
+}
+
+impl<Provider> std::fmt::Debug for RethAccountHistoryApi<Provider> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RethAccountHistoryApi").field("views", &self.views).finish_non_exhaustive()
+    }
+}
+
+impl<Provider: Clone> Clone for RethAccountHistoryApi<Provider> {
+    fn clone(&self) -> Self {
+        Self { provider: self.provider.clone(), views: self.views.clone() }
+    }
+}
@@ -1,10 +1,11 @@
-use crate::PipelineEvent;
+use crate::{PipelineEvent, StageId};
 use alloy_primitives::{BlockNumber, TxNumber};
 use reth_consensus::ConsensusError;
 use reth_errors::{BlockExecutionError, DatabaseError, RethError};
 use reth_network_p2p::error::DownloadError;
 use reth_primitives_traits::SealedHeader;
 use reth_provider::ProviderError;
+use reth_prune::PruneSegment;
 use reth_static_file_types::StaticFileSegment;
 use thiserror::Error;
 use tokio::sync::broadcast::error::SendError;
@@ -178,4 +179,20 @@ pub enum PipelineError {
     /// Internal error
     #[error(transparent)]
     Internal(#[from] RethError),
+    /// The unwind target is below the data that was pruned or expired, while the data is needed to
+    /// unwind the stage.
+    #[error(
+        "cannot unwind stage {stage} to block {target}: {segment} data is unavailable below block \
+         {min_target}, which is the lowest block the pipeline can be unwound to"
+    )]
+    UnwindTargetPruned {
+        /// The stage that needs the pruned data to be unwound.
+        stage: StageId,
+        /// The segment of the pruned data.
+        segment: PruneSegment,
+        /// The unwind target.
+        target: BlockNumber,
+        /// The lowest block the pipeline can be unwound to.
+        min_target: BlockNumber,
+    },
 }
@@ -5,6 +5,7 @@ use std::{
     future::Future,
     pin::Pin,
     task::{ready, Context, Poll},
+    time::Duration,
 };
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tracing::trace;
@@ -30,6 +31,28 @@ pub enum MetricEvent {
         /// If specified, `entities_total` metric is updated.
         max_block_number: Option<BlockNumber>,
     },
+    /// Stage execution was committed.
+    StageExecuted {
+        /// Stage ID.
+        stage_id: StageId,
+        /// Stage checkpoint before the execution.
+        prev_checkpoint: Option<StageCheckpoint>,
+        /// Stage checkpoint after the execution.
+        checkpoint: StageCheckpoint,
+        /// Time spent executing the stage, excluding the commit.
+        execute_duration: Duration,
+        /// Time spent committing the execution.
+        commit_duration: Duration,
+    },
+    /// Stage execution wrote rows to a database table.
+    StageTableWrites {
+        /// Stage ID.
+        stage_id: StageId,
+        /// Name of the table.
+        table: &'static str,
+        /// Number of rows written.
+        rows: u64,
+    },
     /// Execution stage processed some amount of gas.
     ExecutionStageGas {
         /// Gas processed.
@@ -82,6 +105,31 @@ impl MetricsListener {
                     stage_metrics.entities_total.set(total as f64);
                 }
             }
+            MetricEvent::StageExecuted {
+                stage_id,
+                prev_checkpoint,
+                checkpoint,
+                execute_duration,
+                commit_duration,
+            } => {
+                let stage_metrics = self.sync_metrics.get_stage_metrics(stage_id);
+
+                let processed = processed_entities(prev_checkpoint.unwrap_or_default(), checkpoint);
+                stage_metrics.entities_processed_total.increment(processed);
+                if !execute_duration.is_zero() {
+                    stage_metrics
+                        .entities_per_second
+                        .set(processed as f64 / execute_duration.as_secs_f64());
+                }
+                stage_metrics.executions_total.increment(1);
+                stage_metrics.execution_duration_seconds.record(execute_duration);
+                stage_metrics.commit_duration_seconds.record(commit_duration);
+            }
+            MetricEvent::StageTableWrites { stage_id, table, rows } => self
+                .sync_metrics
+                .get_stage_table_metrics(stage_id, table)
+                .rows_written_total
+                .increment(rows),
             MetricEvent::ExecutionStageGas { gas } => {
                 self.sync_metrics.execution_stage.mgas_processed_total.increment(gas / MGAS_TO_GAS)
             }
@@ -89,6 +137,19 @@ impl MetricsListener {
     }
 }
 
+/// Returns the number of entities processed between two checkpoints of a stage.
+///
+/// Uses the entities of the stage checkpoints if both have them, otherwise falls back to the number
+/// of blocks.
+fn processed_entities(prev_checkpoint: StageCheckpoint, checkpoint: StageCheckpoint) -> u64 {
+    match (prev_checkpoint.entities(), checkpoint.entities()) {
+        (Some(prev), Some(current)) if current.processed >= prev.processed => {
+            current.processed - prev.processed
+        }
+        _ => checkpoint.block_number.saturating_sub(prev_checkpoint.block_number),
+    }
+}
+
 impl Future for MetricsListener {
     type Output = ();
 
@@ -106,3 +167,38 @@ impl Future for MetricsListener {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_stages_types::{EntitiesCheckpoint, ExecutionCheckpoint, StageUnitCheckpoint};
+
+    #[test]
+    fn processed_entities_from_checkpoints() {
+        // no entities, fall back to blocks
+        assert_eq!(processed_entities(StageCheckpoint::new(10), StageCheckpoint::new(25)), 15);
+
+        let with_entities = |block_number, processed| {
+            StageCheckpoint::new(block_number).with_execution_stage_checkpoint(
+                ExecutionCheckpoint {
+                    block_range: Default::default(),
+                    progress: EntitiesCheckpoint { processed, total: 1_000 },
+                },
+            )
+        };
+        assert_eq!(processed_entities(with_entities(10, 100), with_entities(25, 400)), 300);
+
+        // entities went backwards, fall back to blocks
+        assert_eq!(processed_entities(with_entities(10, 400), with_entities(25, 100)), 15);
+
+        let unit = StageCheckpoint {
+            block_number: 3,
+            stage_checkpoint: Some(StageUnitCheckpoint::Entities(EntitiesCheckpoint {
+                processed: 50,
+                total: 100,
+            })),
+        };
+        // previous checkpoint has no entities, fall back to blocks
+        assert_eq!(processed_entities(StageCheckpoint::default(), unit), 3);
+    }
+}
@@ -1,5 +1,7 @@
 mod listener;
 mod sync_metrics;
+mod tables;
 
 pub use listener::{MetricEvent, MetricEventsSender, MetricsListener};
 use sync_metrics::*;
+pub(crate) use tables::TableEntries;
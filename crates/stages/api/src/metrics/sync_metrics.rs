@@ -1,6 +1,6 @@
 use crate::StageId;
 use reth_metrics::{
-    metrics::{Counter, Gauge},
+    metrics::{Counter, Gauge, Histogram},
     Metrics,
 };
 use std::collections::HashMap;
@@ -8,6 +8,7 @@ use std::collections::HashMap;
 #[derive(Debug, Default)]
 pub(crate) struct SyncMetrics {
     pub(crate) stages: HashMap<StageId, StageMetrics>,
+    pub(crate) stage_tables: HashMap<(StageId, &'static str), StageTableMetrics>,
     pub(crate) execution_stage: ExecutionStageMetrics,
 }
 
@@ -19,6 +20,21 @@ impl SyncMetrics {
             .entry(stage_id)
             .or_insert_with(|| StageMetrics::new_with_labels(&[("stage", stage_id.to_string())]))
     }
+
+    /// Returns existing or initializes a new instance of [`StageTableMetrics`] for the provided
+    /// [`StageId`] and table name.
+    pub(crate) fn get_stage_table_metrics(
+        &mut self,
+        stage_id: StageId,
+        table: &'static str,
+    ) -> &mut StageTableMetrics {
+        self.stage_tables.entry((stage_id, table)).or_insert_with(|| {
+            StageTableMetrics::new_with_labels(&[
+                ("stage", stage_id.to_string()),
+                ("table", table.to_string()),
+            ])
+        })
+    }
 }
 
 #[derive(Metrics)]
@@ -30,6 +46,24 @@ pub(crate) struct StageMetrics {
     pub(crate) entities_processed: Gauge,
     /// The number of total entities of the last commit for a stage, if applicable.
     pub(crate) entities_total: Gauge,
+    /// The total number of entities processed by a stage across all executions.
+    pub(crate) entities_processed_total: Counter,
+    /// The number of entities processed per second during the last execution of a stage.
+    pub(crate) entities_per_second: Gauge,
+    /// The number of executions of a stage.
+    pub(crate) executions_total: Counter,
+    /// The time it took to execute a stage, excluding the commit.
+    pub(crate) execution_duration_seconds: Histogram,
+    /// The time it took to commit the changes of a stage execution.
+    pub(crate) commit_duration_seconds: Histogram,
+}
+
+/// Metrics for the database tables written by a stage.
+#[derive(Metrics)]
+#[metrics(scope = "sync.table")]
+pub(crate) struct StageTableMetrics {
+    /// The total number of rows written to the table by the stage.
+    pub(crate) rows_written_total: Counter,
 }
 
 /// Execution stage metrics.
@@ -0,0 +1,60 @@
+use reth_db::{TableViewer, Tables};
+use reth_db_api::{table::Table, transaction::DbTx, DatabaseError};
+
+/// Snapshot of the number of entries in every database table.
+///
+/// Taking a snapshot before and after a stage execution allows attributing table writes to the
+/// stage. Entry counts are read from the database statistics, so this is cheap.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TableEntries(Vec<(&'static str, usize)>);
+
+impl TableEntries {
+    /// Collects the number of entries of all tables using the provided transaction.
+    pub(crate) fn collect<TX: DbTx>(tx: &TX) -> Result<Self, DatabaseError> {
+        let viewer = EntriesViewer { tx };
+        Tables::ALL
+            .iter()
+            .map(|table| Ok((table.name(), viewer.view_rt(*table)?)))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    /// Returns the number of rows written to each table since the `previous` snapshot.
+    ///
+    /// Note: this is the net growth of the table, tables that didn't grow are skipped.
+    pub(crate) fn written_since<'a>(
+        &'a self,
+        previous: &'a Self,
+    ) -> impl Iterator<Item = (&'static str, u64)> + 'a {
+        self.0.iter().zip(previous.0.iter()).filter_map(|((table, entries), (_, previous))| {
+            let written = entries.saturating_sub(*previous) as u64;
+            (written > 0).then_some((*table, written))
+        })
+    }
+}
+
+/// Returns the number of entries in a table.
+struct EntriesViewer<'a, TX> {
+    tx: &'a TX,
+}
+
+impl<TX: DbTx> TableViewer<usize> for EntriesViewer<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        self.tx.entries::<T>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn written_since_skips_unchanged_tables() {
+        let previous = TableEntries(vec![("Headers", 10), ("Receipts", 5), ("PlainState", 7)]);
+        let current = TableEntries(vec![("Headers", 15), ("Receipts", 5), ("PlainState", 3)]);
+
+        assert_eq!(current.written_since(&previous).collect::<Vec<_>>(), vec![("Headers", 5)]);
+    }
+}
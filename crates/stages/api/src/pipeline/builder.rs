@@ -1,4 +1,7 @@
-use crate::{pipeline::BoxedStage, MetricEventsSender, Pipeline, Stage, StageId, StageSet};
+use crate::{
+    pipeline::BoxedStage, MetricEventsSender, Pipeline, PipelinePauseHandle, Stage, StageId,
+    StageSet,
+};
 use alloy_primitives::{BlockNumber, B256};
 use reth_db_api::database::Database;
 use reth_provider::ProviderFactory;
@@ -18,6 +21,8 @@ where
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Handle to pause the pipeline at stage boundaries.
+    pause_handle: PipelinePauseHandle,
 }
 
 impl<DB> PipelineBuilder<DB>
@@ -67,13 +72,19 @@ where
         self
     }
 
+    /// Set the handle used to pause and resume the pipeline.
+    pub fn with_pause_handle(mut self, pause_handle: PipelinePauseHandle) -> Self {
+        self.pause_handle = pause_handle;
+        self
+    }
+
     /// Builds the final [`Pipeline`] using the given database.
     pub fn build(
         self,
         provider_factory: ProviderFactory<DB>,
         static_file_producer: StaticFileProducer<DB>,
     ) -> Pipeline<DB> {
-        let Self { stages, max_block, tip_tx, metrics_tx } = self;
+        let Self { stages, max_block, tip_tx, metrics_tx, pause_handle } = self;
         Pipeline {
             provider_factory,
             stages,
@@ -83,13 +94,20 @@ where
             event_sender: Default::default(),
             progress: Default::default(),
             metrics_tx,
+            pause_handle,
         }
     }
 }
 
 impl<DB: Database> Default for PipelineBuilder<DB> {
     fn default() -> Self {
-        Self { stages: Vec::new(), max_block: None, tip_tx: None, metrics_tx: None }
+        Self {
+            stages: Vec::new(),
+            max_block: None,
+            tip_tx: None,
+            metrics_tx: None,
+            pause_handle: PipelinePauseHandle::default(),
+        }
     }
 }
 
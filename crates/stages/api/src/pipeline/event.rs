@@ -64,6 +64,16 @@ pub enum PipelineEvent {
         /// The stage that encountered an error.
         stage_id: StageId,
     },
+    /// Emitted when the pipeline was paused before running a stage.
+    Paused {
+        /// The stage that will run once the pipeline is resumed.
+        stage_id: StageId,
+    },
+    /// Emitted when a paused pipeline was resumed.
+    Resumed {
+        /// The stage that is about to be run.
+        stage_id: StageId,
+    },
     /// Emitted when a stage was skipped due to it's run conditions not being met:
     ///
     /// - The stage might have progressed beyond the point of our target block
@@ -8,25 +8,29 @@ use futures_util::Future;
 use reth_db_api::database::Database;
 use reth_primitives_traits::constants::BEACON_CONSENSUS_REORG_UNWIND_DEPTH;
 use reth_provider::{
-    providers::StaticFileWriter, FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory,
-    StageCheckpointReader, StageCheckpointWriter, StaticFileProviderFactory,
+    providers::StaticFileWriter, BlockNumReader, FinalizedBlockReader, FinalizedBlockWriter,
+    ProviderFactory, PruneCheckpointReader, StageCheckpointReader, StageCheckpointWriter,
+    StaticFileProviderFactory,
 };
-use reth_prune::PrunerBuilder;
+use reth_prune::{PruneSegment, PrunerBuilder};
 use reth_static_file::StaticFileProducer;
+use reth_static_file_types::StaticFileSegment;
 use reth_tokio_util::{EventSender, EventStream};
-use std::pin::Pin;
+use std::{pin::Pin, time::Instant};
 use tokio::sync::watch;
 use tracing::*;
 
 mod builder;
+mod pause;
 mod progress;
 mod set;
 
 use crate::{
-    BlockErrorKind, ExecInput, ExecOutput, MetricEvent, MetricEventsSender, PipelineError, Stage,
-    StageError, StageExt, UnwindInput,
+    metrics::TableEntries, BlockErrorKind, ExecInput, ExecOutput, MetricEvent, MetricEventsSender,
+    PipelineError, Stage, StageError, StageExt, UnwindInput,
 };
 pub use builder::*;
+pub use pause::PipelinePauseHandle;
 use progress::*;
 use reth_errors::RethResult;
 pub use set::*;
@@ -78,6 +82,8 @@ pub struct Pipeline<DB: Database> {
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Handle to pause the pipeline at stage boundaries.
+    pause_handle: PipelinePauseHandle,
 }
 
 impl<DB> Pipeline<DB>
@@ -108,6 +114,16 @@ where
         self.event_sender.new_listener()
     }
 
+    /// Returns a handle to pause and resume the pipeline.
+    pub fn pause_handle(&self) -> PipelinePauseHandle {
+        self.pause_handle.clone()
+    }
+
+    /// Set the handle used to pause and resume the pipeline.
+    pub fn set_pause_handle(&mut self, pause_handle: PipelinePauseHandle) {
+        self.pause_handle = pause_handle;
+    }
+
     /// Registers progress metrics for each registered stage
     pub fn register_metrics(&mut self) -> Result<(), PipelineError> {
         let Some(metrics_tx) = &mut self.metrics_tx else { return Ok(()) };
@@ -192,19 +208,31 @@ where
     /// up to the block that caused the error.
     ///
     /// Returns the control flow after it ran the pipeline.
-    /// This will be [`ControlFlow::Continue`] or [`ControlFlow::NoProgress`] of the _last_ stage in
-    /// the pipeline (for example the `Finish` stage). Or [`ControlFlow::Unwind`] of the stage
-    /// that caused the unwind.
+    /// This will be [`ControlFlow::Continue`] of the _last_ stage in the pipeline (for example the
+    /// `Finish` stage), or [`ControlFlow::NoProgress`] if no stage made progress in this run. Or
+    /// [`ControlFlow::Unwind`] of the stage that caused the unwind.
     pub async fn run_loop(&mut self) -> Result<ControlFlow, PipelineError> {
         self.move_to_static_files()?;
 
         let mut previous_stage = None;
+        let mut made_progress = false;
         for stage_index in 0..self.stages.len() {
             let stage = &self.stages[stage_index];
             let stage_id = stage.id();
 
+            if self.pause_handle.is_paused() {
+                debug!(target: "sync::pipeline", stage = %stage_id, "Pipeline paused");
+                self.event_sender.notify(PipelineEvent::Paused { stage_id });
+                self.pause_handle.wait_until_resumed().await;
+                debug!(target: "sync::pipeline", stage = %stage_id, "Pipeline resumed");
+                self.event_sender.notify(PipelineEvent::Resumed { stage_id });
+            }
+
             trace!(target: "sync::pipeline", stage = %stage_id, "Executing stage");
-            let next = self.execute_stage_to_completion(previous_stage, stage_index).await?;
+            let next = self
+                .execute_stage_to_completion(previous_stage, stage_index)
+                .instrument(debug_span!(target: "sync::pipeline", "stage", stage = %stage_id))
+                .await?;
 
             trace!(target: "sync::pipeline", stage = %stage_id, ?next, "Completed stage");
 
@@ -214,7 +242,10 @@ where
                         self.progress.update(block_number);
                     }
                 }
-                ControlFlow::Continue { block_number } => self.progress.update(block_number),
+                ControlFlow::Continue { block_number } => {
+                    made_progress = true;
+                    self.progress.update(block_number)
+                }
                 ControlFlow::Unwind { target, bad_block } => {
                     self.unwind(target, Some(bad_block.number))?;
                     return Ok(ControlFlow::Unwind { target, bad_block })
@@ -230,7 +261,13 @@ where
             );
         }
 
-        Ok(self.progress.next_ctrl())
+        // the progress is kept across runs, so a run in which no stage advanced reports no
+        // progress
+        if made_progress {
+            Ok(self.progress.next_ctrl())
+        } else {
+            Ok(ControlFlow::NoProgress { block_number: self.progress.block_number })
+        }
     }
 
     /// Run [static file producer](StaticFileProducer) and [pruner](reth_prune::Pruner) to **move**
@@ -255,8 +292,11 @@ where
         if let Some(prune_tip) = lowest_static_file_height {
             // Run the pruner so we don't potentially end up with higher height in the database vs
             // static files during a pipeline unwind
+            // only blocks that are persisted to the database can be pruned
+            let persisted_height = self.provider_factory.best_block_number()?;
             let mut pruner = PrunerBuilder::new(Default::default())
                 .prune_delete_limit(usize::MAX)
+                .persisted_height(watch::channel(Some(persisted_height)).1)
                 .build(self.provider_factory.clone());
 
             pruner.run(prune_tip)?;
@@ -265,14 +305,66 @@ where
         Ok(())
     }
 
+    /// Checks that the stages can be unwound to the target block, i.e. that the data they need to
+    /// unwind the blocks above the target hasn't been pruned or expired.
+    ///
+    /// Returns [`PipelineError::UnwindTargetPruned`] with the lowest block the pipeline can be
+    /// unwound to otherwise.
+    pub fn check_unwind_target(&self, to: BlockNumber) -> Result<(), PipelineError> {
+        let provider = self.provider_factory.provider()?;
+        let static_file_provider = self.provider_factory.static_file_provider();
+
+        let mut lowest_target = None;
+        for stage in &self.stages {
+            let stage_id = stage.id();
+            if provider.get_stage_checkpoint(stage_id)?.unwrap_or_default().block_number <= to {
+                continue
+            }
+
+            for segment in unwind_required_segments(stage_id) {
+                let min_target = match segment {
+                    // Blocks below the lowest static file block have expired. The lowest block
+                    // itself is the first available one and must be kept as the new tip.
+                    PruneSegment::Headers => static_file_provider
+                        .get_lowest_static_file_block(StaticFileSegment::Headers),
+                    PruneSegment::Transactions => static_file_provider
+                        .get_lowest_static_file_block(StaticFileSegment::Transactions),
+                    // Data is pruned up to the checkpoint block, inclusive, and only the data of
+                    // the blocks above the target is needed to unwind
+                    segment => provider
+                        .get_prune_checkpoint(*segment)?
+                        .and_then(|checkpoint| checkpoint.block_number),
+                };
+
+                if let Some(min_target) = min_target {
+                    if lowest_target.map_or(true, |(lowest, _, _)| min_target > lowest) {
+                        lowest_target = Some((min_target, stage_id, *segment));
+                    }
+                }
+            }
+        }
+
+        match lowest_target {
+            Some((min_target, stage, segment)) if to < min_target => {
+                Err(PipelineError::UnwindTargetPruned { stage, segment, target: to, min_target })
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Unwind the stages to the target block.
     ///
     /// If the unwind is due to a bad block the number of that block should be specified.
+    ///
+    /// Fails without unwinding any stage if the target is below the data that was pruned, see
+    /// [`Self::check_unwind_target`].
     pub fn unwind(
         &mut self,
         to: BlockNumber,
         bad_block: Option<BlockNumber>,
     ) -> Result<(), PipelineError> {
+        self.check_unwind_target(to)?;
+
         // Unwind stages in reverse order of execution
         let unwind_pipeline = self.stages.iter_mut().rev();
 
@@ -431,8 +523,16 @@ where
             });
 
             let provider_rw = self.provider_factory.provider_rw()?;
+            // table entries are only collected if metrics are enabled
+            let table_entries = self
+                .metrics_tx
+                .is_some()
+                .then(|| TableEntries::collect(provider_rw.tx_ref()))
+                .transpose()?;
+            let execute_start = Instant::now();
             match stage.execute(&provider_rw, exec_input) {
                 Ok(out @ ExecOutput { checkpoint, done }) => {
+                    let execute_duration = execute_start.elapsed();
                     made_progress |=
                         checkpoint.block_number != prev_checkpoint.unwrap_or_default().block_number;
 
@@ -445,6 +545,13 @@ where
                     }
                     provider_rw.save_stage_checkpoint(stage_id, checkpoint)?;
 
+                    let table_writes = table_entries
+                        .map(|before| {
+                            TableEntries::collect(provider_rw.tx_ref())
+                                .map(|after| after.written_since(&before).collect::<Vec<_>>())
+                        })
+                        .transpose()?;
+
                     self.event_sender.notify(PipelineEvent::Ran {
                         pipeline_stages_progress: PipelineStagesProgress {
                             current: stage_index + 1,
@@ -458,8 +565,27 @@ where
                     // this function is interrupted before the database commit, we can just truncate
                     // the static files according to the checkpoints on the next
                     // start-up.
+                    let commit_start = Instant::now();
                     self.provider_factory.static_file_provider().commit()?;
                     provider_rw.commit()?;
+                    let commit_duration = commit_start.elapsed();
+
+                    if let Some(metrics_tx) = &mut self.metrics_tx {
+                        let _ = metrics_tx.send(MetricEvent::StageExecuted {
+                            stage_id,
+                            prev_checkpoint,
+                            checkpoint,
+                            execute_duration,
+                            commit_duration,
+                        });
+                        for (table, rows) in table_writes.into_iter().flatten() {
+                            let _ = metrics_tx.send(MetricEvent::StageTableWrites {
+                                stage_id,
+                                table,
+                                rows,
+                            });
+                        }
+                    }
 
                     stage.post_execute_commit()?;
 
@@ -574,6 +700,22 @@ fn on_stage_error<DB: Database>(
     }
 }
 
+/// Returns the segments of data that are needed to unwind the stage, and that can be pruned or
+/// expired.
+const fn unwind_required_segments(stage_id: StageId) -> &'static [PruneSegment] {
+    match stage_id {
+        StageId::Headers => &[PruneSegment::Headers],
+        StageId::Bodies => &[PruneSegment::Transactions],
+        // State is reverted using the changesets
+        StageId::Execution | StageId::MerkleUnwind => {
+            &[PruneSegment::AccountHistory, PruneSegment::StorageHistory]
+        }
+        StageId::AccountHashing | StageId::IndexAccountHistory => &[PruneSegment::AccountHistory],
+        StageId::StorageHashing | StageId::IndexStorageHistory => &[PruneSegment::StorageHistory],
+        _ => &[],
+    }
+}
+
 impl<DB: Database> std::fmt::Debug for Pipeline<DB> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Pipeline")
@@ -588,11 +730,14 @@ impl<DB: Database> std::fmt::Debug for Pipeline<DB> {
 mod tests {
     use super::*;
     use crate::{test_utils::TestStage, UnwindOutput};
+    use alloy_primitives::{B256, U256};
     use assert_matches::assert_matches;
     use reth_consensus::ConsensusError;
     use reth_errors::ProviderError;
-    use reth_provider::test_utils::create_test_provider_factory;
-    use reth_prune::PruneModes;
+    use reth_primitives_traits::Header;
+    use reth_provider::{test_utils::create_test_provider_factory, PruneCheckpointWriter};
+    use reth_prune::{PruneCheckpoint, PruneMode, PruneModes};
+    use reth_static_file_types::BLOCKS_PER_STATIC_FILE;
     use reth_testing_utils::{generators, generators::random_header};
     use tokio_stream::StreamExt;
 
@@ -691,6 +836,64 @@ mod tests {
         );
     }
 
+    /// Pauses a pipeline before its first stage and resumes it.
+    #[tokio::test]
+    async fn pause_and_resume_pipeline() {
+        let provider_factory = create_test_provider_factory();
+        let pause_handle = PipelinePauseHandle::new();
+        assert!(pause_handle.pause().unwrap());
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(10), done: true })),
+            )
+            .with_max_block(10)
+            .with_pause_handle(pause_handle.clone())
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+            );
+        let mut events = pipeline.events();
+
+        // Run pipeline
+        tokio::spawn(async move {
+            pipeline.run().await.unwrap();
+        });
+
+        // The pipeline stops before running the first stage
+        assert_eq!(
+            events.next().await,
+            Some(PipelineEvent::Paused { stage_id: StageId::Other("A") })
+        );
+        assert!(pause_handle.is_paused());
+
+        assert!(pause_handle.resume().unwrap());
+        assert_eq!(
+            events.collect::<Vec<PipelineEvent>>().await,
+            vec![
+                PipelineEvent::Resumed { stage_id: StageId::Other("A") },
+                PipelineEvent::Prepare {
+                    pipeline_stages_progress: PipelineStagesProgress { current: 1, total: 1 },
+                    stage_id: StageId::Other("A"),
+                    checkpoint: None,
+                    target: Some(10),
+                },
+                PipelineEvent::Run {
+                    pipeline_stages_progress: PipelineStagesProgress { current: 1, total: 1 },
+                    stage_id: StageId::Other("A"),
+                    checkpoint: None,
+                    target: Some(10),
+                },
+                PipelineEvent::Ran {
+                    pipeline_stages_progress: PipelineStagesProgress { current: 1, total: 1 },
+                    stage_id: StageId::Other("A"),
+                    result: ExecOutput { checkpoint: StageCheckpoint::new(10), done: true },
+                },
+            ]
+        );
+    }
+
     /// Unwinds a simple pipeline.
     #[tokio::test]
     async fn unwind_pipeline() {
@@ -1046,6 +1249,122 @@ mod tests {
         );
     }
 
+    /// Checks that the pipeline refuses to unwind below pruned data needed by the stages.
+    #[test]
+    fn unwind_pipeline_below_pruned_data() {
+        let provider_factory = create_test_provider_factory();
+
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        provider_rw
+            .save_stage_checkpoint(StageId::IndexAccountHistory, StageCheckpoint::new(100))
+            .unwrap();
+        provider_rw
+            .save_prune_checkpoint(
+                PruneSegment::AccountHistory,
+                PruneCheckpoint {
+                    block_number: Some(50),
+                    tx_number: None,
+                    prune_mode: PruneMode::Distance(50),
+                },
+            )
+            .unwrap();
+        // Storage history isn't needed to unwind the account history index
+        provider_rw
+            .save_prune_checkpoint(
+                PruneSegment::StorageHistory,
+                PruneCheckpoint {
+                    block_number: Some(80),
+                    tx_number: None,
+                    prune_mode: PruneMode::Distance(20),
+                },
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::IndexAccountHistory)
+                    .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(50) })),
+            )
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+            );
+
+        assert_matches!(
+            pipeline.unwind(10, None),
+            Err(PipelineError::UnwindTargetPruned {
+                stage: StageId::IndexAccountHistory,
+                segment: PruneSegment::AccountHistory,
+                target: 10,
+                min_target: 50,
+            })
+        );
+        // Nothing was unwound
+        assert_eq!(
+            provider_factory
+                .provider()
+                .unwrap()
+                .get_stage_checkpoint(StageId::IndexAccountHistory)
+                .unwrap(),
+            Some(StageCheckpoint::new(100))
+        );
+
+        assert_matches!(pipeline.unwind(50, None), Ok(()));
+    }
+
+    /// Checks that the pipeline can be unwound to the first available static file block, but not
+    /// below it.
+    #[test]
+    fn unwind_pipeline_to_first_static_file_block() {
+        let provider_factory = create_test_provider_factory();
+
+        // Fill the first static file of headers, so that it can expire
+        let static_file_provider = provider_factory.static_file_provider();
+        {
+            let mut writer =
+                static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+            for number in 0..=BLOCKS_PER_STATIC_FILE {
+                writer
+                    .append_header(Header { number, ..Default::default() }, U256::ZERO, B256::ZERO)
+                    .unwrap();
+            }
+            writer.commit().unwrap();
+        }
+        static_file_provider
+            .expire_jars_before(StaticFileSegment::Headers, BLOCKS_PER_STATIC_FILE)
+            .unwrap();
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::Headers),
+            Some(BLOCKS_PER_STATIC_FILE)
+        );
+
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        provider_rw
+            .save_stage_checkpoint(
+                StageId::Headers,
+                StageCheckpoint::new(BLOCKS_PER_STATIC_FILE + 10),
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let pipeline = Pipeline::builder().add_stage(TestStage::new(StageId::Headers)).build(
+            provider_factory.clone(),
+            StaticFileProducer::new(provider_factory, PruneModes::default()),
+        );
+
+        assert_matches!(
+            pipeline.check_unwind_target(BLOCKS_PER_STATIC_FILE - 1),
+            Err(PipelineError::UnwindTargetPruned {
+                stage: StageId::Headers,
+                segment: PruneSegment::Headers,
+                target,
+                min_target: BLOCKS_PER_STATIC_FILE,
+            }) if target == BLOCKS_PER_STATIC_FILE - 1
+        );
+        assert_matches!(pipeline.check_unwind_target(BLOCKS_PER_STATIC_FILE), Ok(()));
+    }
+
     /// Checks that the pipeline re-runs stages on non-fatal errors and stops on fatal ones.
     #[tokio::test]
     async fn pipeline_error_handling() {
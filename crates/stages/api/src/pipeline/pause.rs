@@ -0,0 +1,110 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::sync::watch;
+
+/// A handle to pause and resume a [`Pipeline`](crate::Pipeline) at stage boundaries.
+///
+/// Pausing doesn't interrupt the stage that is currently running: the pipeline commits it and
+/// waits before starting the next stage, so that the database is consistent while paused. The
+/// consensus engine that drives the pipeline shares its handle and pauses live sync as well.
+///
+/// If the handle is [persistent](Self::persistent), the paused state is kept as a marker file on
+/// disk, so a restarted node stays paused until it's explicitly resumed.
+#[derive(Debug, Clone)]
+pub struct PipelinePauseHandle {
+    /// Whether the pipeline is paused.
+    paused: Arc<watch::Sender<bool>>,
+    /// Path of the marker file that persists the paused state.
+    path: Option<Arc<Path>>,
+}
+
+impl PipelinePauseHandle {
+    /// Creates a new handle that doesn't persist the paused state.
+    pub fn new() -> Self {
+        Self { paused: Arc::new(watch::Sender::new(false)), path: None }
+    }
+
+    /// Creates a new handle that persists the paused state at the given path.
+    ///
+    /// The pipeline starts paused if the file already exists.
+    pub fn persistent(path: impl Into<PathBuf>) -> Self {
+        let path: Arc<Path> = path.into().into();
+        Self { paused: Arc::new(watch::Sender::new(path.exists())), path: Some(path) }
+    }
+
+    /// Returns the path the paused state is persisted at, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Returns `true` if the pipeline is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Pauses the pipeline before the next stage.
+    ///
+    /// Returns `false` if the pipeline was already paused.
+    pub fn pause(&self) -> io::Result<bool> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, b"")?;
+        }
+        Ok(!self.paused.send_replace(true))
+    }
+
+    /// Resumes a paused pipeline.
+    ///
+    /// Returns `false` if the pipeline wasn't paused.
+    pub fn resume(&self) -> io::Result<bool> {
+        if let Some(path) = &self.path {
+            match std::fs::remove_file(path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        Ok(self.paused.send_replace(false))
+    }
+
+    /// Waits until the pipeline is not paused anymore.
+    pub(crate) async fn wait_until_resumed(&self) {
+        let mut paused = self.paused.subscribe();
+        // The sender is owned by `self`, so the channel can't be closed here.
+        let _ = paused.wait_for(|paused| !paused).await;
+    }
+}
+
+impl Default for PipelinePauseHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persists_paused_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pipeline.paused");
+
+        let handle = PipelinePauseHandle::persistent(&path);
+        assert!(!handle.is_paused());
+
+        assert!(handle.pause().unwrap());
+        assert!(!handle.pause().unwrap());
+        assert!(path.exists());
+        assert!(PipelinePauseHandle::persistent(&path).is_paused());
+
+        assert!(handle.resume().unwrap());
+        assert!(!handle.resume().unwrap());
+        assert!(!path.exists());
+        assert!(!PipelinePauseHandle::persistent(&path).is_paused());
+    }
+}
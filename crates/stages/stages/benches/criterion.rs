@@ -89,7 +89,7 @@ fn transaction_lookup(c: &mut Criterion, runtime: &Runtime) {
     // don't need to run each stage for that many times
     group.sample_size(10);
     let stage = TransactionLookupStage::new(
-        TransactionLookupConfig { chunk_size: DEFAULT_NUM_BLOCKS },
+        TransactionLookupConfig { chunk_size: DEFAULT_NUM_BLOCKS, ..Default::default() },
         EtlConfig::default(),
         None,
     );
@@ -114,7 +114,7 @@ fn merkle(c: &mut Criterion, runtime: &Runtime) {
 
     let db = setup::txs_testdata(DEFAULT_NUM_BLOCKS);
 
-    let stage = MerkleStage::Both { clean_threshold: u64::MAX };
+    let stage = MerkleStage::Both { clean_threshold: u64::MAX, clean_chunk_nibbles: 0 };
     measure_stage(
         runtime,
         &mut group,
@@ -125,7 +125,7 @@ fn merkle(c: &mut Criterion, runtime: &Runtime) {
         "Merkle-incremental".to_string(),
     );
 
-    let stage = MerkleStage::Both { clean_threshold: 0 };
+    let stage = MerkleStage::Both { clean_threshold: 0, clean_chunk_nibbles: 0 };
     measure_stage(
         runtime,
         &mut group,
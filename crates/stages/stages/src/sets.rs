@@ -367,6 +367,10 @@ pub struct HistoryIndexingStages {
 
 impl<DB: Database> StageSet<DB> for HistoryIndexingStages {
     fn builder(self) -> StageSetBuilder<DB> {
+        // History of the addresses exempt from pruning has to be indexed for all blocks, so
+        // indexing can't skip the blocks below the prune target.
+        let retain_all_history = !self.prune_modes.retained_addresses.is_empty();
+
         StageSetBuilder::default()
             .add_stage(TransactionLookupStage::new(
                 self.stages_config.transaction_lookup,
@@ -376,12 +380,12 @@ impl<DB: Database> StageSet<DB> for HistoryIndexingStages {
             .add_stage(IndexStorageHistoryStage::new(
                 self.stages_config.index_storage_history,
                 self.stages_config.etl.clone(),
-                self.prune_modes.account_history,
+                self.prune_modes.account_history.filter(|_| !retain_all_history),
             ))
             .add_stage(IndexAccountHistoryStage::new(
                 self.stages_config.index_account_history,
                 self.stages_config.etl.clone(),
-                self.prune_modes.storage_history,
+                self.prune_modes.storage_history.filter(|_| !retain_all_history),
             ))
     }
 }
@@ -13,9 +13,10 @@ use reth_primitives::{
 use reth_provider::{
     providers::{StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
     BlockReader, DatabaseProviderRW, HeaderProvider, LatestStateProviderRef, OriginalValuesKnown,
-    ProviderError, StateWriter, StatsReader, TransactionVariant,
+    ProviderError, PruneCheckpointReader, PruneCheckpointWriter, StateWriter, StatsReader,
+    TransactionVariant,
 };
-use reth_prune_types::PruneModes;
+use reth_prune_types::{PruneCheckpoint, PruneMode, PruneModes, PrunePurpose, PruneSegment};
 use reth_revm::database::StateProviderDatabase;
 use reth_stages_api::{
     BlockErrorKind, CheckpointBlockRange, EntitiesCheckpoint, ExecInput, ExecOutput,
@@ -170,6 +171,145 @@ impl<E> ExecutionStage<E> {
         }
         Ok(prune_modes)
     }
+
+    /// Saves a [`PruneSegment::Receipts`] checkpoint for the receipts that were never written
+    /// because they already fell outside the retention window when the block was executed.
+    ///
+    /// The checkpoint is only saved if there's none yet, otherwise the pruner may skip receipts
+    /// that were written by earlier runs and still need to be pruned. Receipts that are retained
+    /// by [`PruneModes::receipts_log_filter`] are always written, so no checkpoint is saved then.
+    fn save_skipped_receipts_checkpoint<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        start_block: BlockNumber,
+        end_block: BlockNumber,
+        tip: BlockNumber,
+    ) -> Result<(), StageError> {
+        if !self.prune_modes.receipts_log_filter.is_empty() ||
+            !self.prune_modes.retained_addresses.is_empty()
+        {
+            return Ok(())
+        }
+
+        let Some((target_block, prune_mode)) = self
+            .prune_modes
+            .receipts
+            .map(|mode| mode.prune_target_block(tip, PruneSegment::Receipts, PrunePurpose::User))
+            .transpose()?
+            .flatten()
+        else {
+            return Ok(())
+        };
+
+        let Some(last_skipped_block) =
+            last_skipped_block(prune_mode, target_block, start_block, end_block, tip)
+        else {
+            return Ok(())
+        };
+        if provider.get_prune_checkpoint(PruneSegment::Receipts)?.is_some() {
+            return Ok(())
+        }
+
+        let tx_number = provider
+            .block_body_indices(last_skipped_block)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(last_skipped_block))?
+            .last_tx_num();
+        provider.save_prune_checkpoint(
+            PruneSegment::Receipts,
+            PruneCheckpoint {
+                block_number: Some(last_skipped_block),
+                tx_number: Some(tx_number),
+                prune_mode,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Saves [`PruneSegment::AccountHistory`] and [`PruneSegment::StorageHistory`] checkpoints for
+    /// the changesets that were never written because they already fell outside the retention
+    /// window when the block was executed, see [`BlockBatchRecord::prune_reverts`].
+    ///
+    /// Like for receipts, a checkpoint is only saved if there's none yet and no changesets were
+    /// written below the range by earlier runs, so that the pruner doesn't skip them.
+    ///
+    /// [`BlockBatchRecord::prune_reverts`]: reth_revm::batch::BlockBatchRecord::prune_reverts
+    fn save_skipped_changesets_checkpoints<DB: Database>(
+        provider: &DatabaseProviderRW<DB>,
+        account_history: Option<PruneMode>,
+        storage_history: Option<PruneMode>,
+        start_block: BlockNumber,
+        end_block: BlockNumber,
+        tip: BlockNumber,
+    ) -> Result<(), StageError> {
+        let first_changeset_blocks = [
+            provider
+                .tx_ref()
+                .cursor_read::<tables::AccountChangeSets>()?
+                .first()?
+                .map(|(block, _)| block),
+            provider
+                .tx_ref()
+                .cursor_read::<tables::StorageChangeSets>()?
+                .first()?
+                .map(|(key, _)| key.block_number()),
+        ];
+
+        for ((segment, mode), first_changeset_block) in [
+            (PruneSegment::AccountHistory, account_history),
+            (PruneSegment::StorageHistory, storage_history),
+        ]
+        .into_iter()
+        .zip(first_changeset_blocks)
+        {
+            let Some((target_block, prune_mode)) = mode
+                .map(|mode| mode.prune_target_block(tip, segment, PrunePurpose::User))
+                .transpose()?
+                .flatten()
+            else {
+                continue
+            };
+
+            let Some(last_skipped_block) =
+                last_skipped_block(prune_mode, target_block, start_block, end_block, tip)
+            else {
+                continue
+            };
+            if first_changeset_block.map_or(false, |block| block < start_block) ||
+                provider.get_prune_checkpoint(segment)?.is_some()
+            {
+                continue
+            }
+
+            provider.save_prune_checkpoint(
+                segment,
+                PruneCheckpoint {
+                    block_number: Some(last_skipped_block),
+                    tx_number: None,
+                    prune_mode,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the highest block between `start_block` and `end_block` whose data was skipped during
+/// execution because it fell outside the retention window of the prune mode, whose target block
+/// is `target_block`.
+fn last_skipped_block(
+    prune_mode: PruneMode,
+    target_block: BlockNumber,
+    start_block: BlockNumber,
+    end_block: BlockNumber,
+    tip: BlockNumber,
+) -> Option<BlockNumber> {
+    let mut last_skipped_block = target_block.min(end_block);
+    if !prune_mode.should_prune(last_skipped_block, tip) {
+        last_skipped_block = last_skipped_block.checked_sub(1)?;
+    }
+    (last_skipped_block >= start_block).then_some(last_skipped_block)
 }
 
 impl<E, DB> Stage<DB> for ExecutionStage<E>
@@ -224,6 +364,8 @@ where
             provider.tx_ref(),
             provider.static_file_provider().clone(),
         ));
+        let (account_history, storage_history) =
+            (prune_modes.account_history, prune_modes.storage_history);
         let mut executor = self.executor_provider.batch_executor(db, prune_modes);
         executor.set_tip(max_block);
 
@@ -342,6 +484,17 @@ where
             OriginalValuesKnown::Yes,
         )?;
         let db_write_duration = time.elapsed();
+
+        // Don't let the pruner revisit receipts and changesets that were skipped during execution.
+        self.save_skipped_receipts_checkpoint(provider, start_block, stage_progress, max_block)?;
+        Self::save_skipped_changesets_checkpoints(
+            provider,
+            account_history,
+            storage_history,
+            start_block,
+            stage_progress,
+            max_block,
+        )?;
         debug!(
             target: "sync::stages::execution",
             block_fetch = ?fetch_block_duration,
@@ -719,9 +872,10 @@ mod tests {
         test_utils::create_test_provider_factory, AccountReader, ReceiptProvider,
         StaticFileProviderFactory,
     };
-    use reth_prune_types::{PruneMode, ReceiptsLogPruneConfig};
+    use reth_prune_types::{
+        ReceiptsLogPruneConfig, ReceiptsLogPruneRule, MINIMUM_PRUNING_DISTANCE,
+    };
     use reth_stages_api::StageUnitCheckpoint;
-    use std::collections::BTreeMap;
 
     fn stage() -> ExecutionStage<EthExecutorProvider> {
         let executor_provider = EthExecutorProvider::ethereum(Arc::new(
@@ -967,8 +1121,10 @@ mod tests {
         // If there is a pruning configuration, then it's forced to use the database.
         // This way we test both cases.
         let modes = [None, Some(PruneModes::none())];
-        let random_filter =
-            ReceiptsLogPruneConfig(BTreeMap::from([(Address::random(), PruneMode::Full)]));
+        let random_filter = ReceiptsLogPruneConfig(vec![ReceiptsLogPruneRule::address(
+            Address::random(),
+            PruneMode::Full,
+        )]);
 
         // Tests node with database and node with static files
         for mut mode in modes {
@@ -1113,8 +1269,10 @@ mod tests {
         // If there is a pruning configuration, then it's forced to use the database.
         // This way we test both cases.
         let modes = [None, Some(PruneModes::none())];
-        let random_filter =
-            ReceiptsLogPruneConfig(BTreeMap::from([(Address::random(), PruneMode::Full)]));
+        let random_filter = ReceiptsLogPruneConfig(vec![ReceiptsLogPruneRule::address(
+            Address::random(),
+            PruneMode::Full,
+        )]);
 
         // Tests node with database and node with static files
         for mut mode in modes {
@@ -1326,4 +1484,64 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn save_skipped_changesets_checkpoints() {
+        let factory = create_test_provider_factory();
+        let tip = MINIMUM_PRUNING_DISTANCE + 100;
+        let mode = PruneMode::Distance(MINIMUM_PRUNING_DISTANCE);
+
+        // The changesets of the whole range were skipped
+        let provider = factory.provider_rw().unwrap();
+        ExecutionStage::<EthExecutorProvider>::save_skipped_changesets_checkpoints(
+            &provider,
+            Some(mode),
+            None,
+            1,
+            50,
+            tip,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.get_prune_checkpoint(PruneSegment::AccountHistory).unwrap(),
+            Some(PruneCheckpoint { block_number: Some(50), tx_number: None, prune_mode: mode })
+        );
+        assert_eq!(provider.get_prune_checkpoint(PruneSegment::StorageHistory).unwrap(), None);
+
+        // Only the changesets below the target block were skipped
+        ExecutionStage::<EthExecutorProvider>::save_skipped_changesets_checkpoints(
+            &provider,
+            None,
+            Some(mode),
+            51,
+            200,
+            tip,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.get_prune_checkpoint(PruneSegment::StorageHistory).unwrap(),
+            Some(PruneCheckpoint { block_number: Some(99), tx_number: None, prune_mode: mode })
+        );
+
+        // Changesets written below the range by earlier runs still need to be pruned
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        provider
+            .tx_ref()
+            .put::<tables::AccountChangeSets>(
+                1,
+                AccountBeforeTx { address: Address::random(), info: None },
+            )
+            .unwrap();
+        ExecutionStage::<EthExecutorProvider>::save_skipped_changesets_checkpoints(
+            &provider,
+            Some(mode),
+            None,
+            2,
+            50,
+            tip,
+        )
+        .unwrap();
+        assert_eq!(provider.get_prune_checkpoint(PruneSegment::AccountHistory).unwrap(), None);
+    }
 }
@@ -1,3 +1,4 @@
+use super::changesets_pruned;
 use itertools::Itertools;
 use reth_config::config::{EtlConfig, HashingConfig};
 use reth_db::{tables, RawKey, RawTable, RawValue};
@@ -9,6 +10,7 @@ use reth_db_api::{
 use reth_etl::Collector;
 use reth_primitives::{keccak256, Account, B256};
 use reth_provider::{AccountExtReader, DatabaseProviderRW, HashingWriter, StatsReader};
+use reth_prune_types::PruneSegment;
 use reth_stages_api::{
     AccountHashingCheckpoint, EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint,
     StageError, StageId, UnwindInput, UnwindOutput,
@@ -143,7 +145,12 @@ impl<DB: Database> Stage<DB> for AccountHashingStage {
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
         // genesis accounts are not in changeset.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        //
+        // If the changesets of the range were pruned, they can't be used either.
+        if to_block - from_block > self.clean_threshold ||
+            from_block == 1 ||
+            changesets_pruned(provider, PruneSegment::AccountHistory, from_block)?
+        {
             let tx = provider.tx_ref();
 
             // clear table, load all accounts and hash it
@@ -1,3 +1,4 @@
+use super::changesets_pruned;
 use itertools::Itertools;
 use reth_config::config::{EtlConfig, HashingConfig};
 use reth_db::tables;
@@ -11,6 +12,7 @@ use reth_db_api::{
 use reth_etl::Collector;
 use reth_primitives::{keccak256, BufMut, StorageEntry, B256};
 use reth_provider::{DatabaseProviderRW, HashingWriter, StatsReader, StorageReader};
+use reth_prune_types::PruneSegment;
 use reth_stages_api::{
     EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
     StorageHashingCheckpoint, UnwindInput, UnwindOutput,
@@ -85,7 +87,12 @@ impl<DB: Database> Stage<DB> for StorageHashingStage {
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
         // genesis accounts are not in changeset, along with their storages.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        //
+        // If the changesets of the range were pruned, they can't be used either.
+        if to_block - from_block > self.clean_threshold ||
+            from_block == 1 ||
+            changesets_pruned(provider, PruneSegment::StorageHistory, from_block)?
+        {
             // clear table, load all accounts and hash it
             tx.clear::<tables::HashedStorages>()?;
 
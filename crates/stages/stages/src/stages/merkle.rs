@@ -40,6 +40,13 @@ Once you have this information, please submit a github issue at https://github.c
 /// of changes to whole rebuild.
 pub const MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD: u64 = 5_000;
 
+/// The default number of leading nibbles of the hashed account key that define a chunk of the
+/// keyspace during a whole trie rebuild.
+///
+/// The progress is committed at every chunk boundary (256 chunks in total), so an interrupted
+/// rebuild resumes from the last committed chunk instead of starting over.
+pub const MERKLE_STAGE_CLEAN_CHUNK_NIBBLES: usize = 2;
+
 /// The merkle hashing stage uses input from
 /// [`AccountHashingStage`][crate::stages::AccountHashingStage] and
 /// [`StorageHashingStage`][crate::stages::AccountHashingStage] to calculate intermediate hashes
@@ -68,6 +75,9 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The number of leading nibbles of the hashed account key that define a chunk of the
+        /// keyspace committed at once during a whole rebuild. `0` disables chunking.
+        clean_chunk_nibbles: usize,
     },
     /// The unwind portion of the merkle stage.
     Unwind,
@@ -77,13 +87,16 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The number of leading nibbles of the hashed account key that define a chunk of the
+        /// keyspace committed at once during a whole rebuild. `0` disables chunking.
+        clean_chunk_nibbles: usize,
     },
 }
 
 impl MerkleStage {
     /// Stage default for the [`MerkleStage::Execution`].
     pub const fn default_execution() -> Self {
-        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD }
+        Self::new_execution(MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD)
     }
 
     /// Stage default for the [`MerkleStage::Unwind`].
@@ -93,7 +106,7 @@ impl MerkleStage {
 
     /// Create new instance of [`MerkleStage::Execution`].
     pub const fn new_execution(clean_threshold: u64) -> Self {
-        Self::Execution { clean_threshold }
+        Self::Execution { clean_threshold, clean_chunk_nibbles: MERKLE_STAGE_CLEAN_CHUNK_NIBBLES }
     }
 
     /// Gets the hashing progress
@@ -148,14 +161,18 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         provider: &DatabaseProviderRW<DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let threshold = match self {
+        let (threshold, chunk_nibbles) = match self {
             Self::Unwind => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
             }
-            Self::Execution { clean_threshold } => *clean_threshold,
+            Self::Execution { clean_threshold, clean_chunk_nibbles } => {
+                (*clean_threshold, *clean_chunk_nibbles)
+            }
             #[cfg(any(test, feature = "test-utils"))]
-            Self::Both { clean_threshold } => *clean_threshold,
+            Self::Both { clean_threshold, clean_chunk_nibbles } => {
+                (*clean_threshold, *clean_chunk_nibbles)
+            }
         };
 
         let range = input.next_block_range();
@@ -209,6 +226,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
 
             let tx = provider.tx_ref();
             let progress = StateRoot::from_tx(tx)
+                .with_chunk_nibbles(chunk_nibbles)
                 .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
                 .root_with_progress()
                 .map_err(|e| {
@@ -219,15 +237,24 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 StateRootProgress::Progress(state, hashed_entries_walked, updates) => {
                     updates.flush(tx)?;
 
+                    let last_account_key = state.last_account_key;
                     let checkpoint = MerkleCheckpoint::new(
                         to_block,
-                        state.last_account_key,
+                        last_account_key,
                         state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
                         state.hash_builder.into(),
                     );
                     self.save_execution_checkpoint(provider, Some(checkpoint))?;
 
                     entities_checkpoint.processed += hashed_entries_walked as u64;
+                    info!(
+                        target: "sync::stages::merkle::exec",
+                        target_block = ?to_block,
+                        ?last_account_key,
+                        processed = entities_checkpoint.processed,
+                        total = entities_checkpoint.total,
+                        "Committing intermediate trie rebuild progress"
+                    );
 
                     return Ok(ExecOutput {
                         checkpoint: input
@@ -420,6 +447,63 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    /// Execute from genesis in keyspace chunks, committing and resuming after each one
+    #[tokio::test]
+    async fn execute_clean_merkle_in_chunks() {
+        let (previous_stage, stage_progress) = (500, 0);
+
+        // Set up the runner
+        let mut runner = MerkleTestRunner { clean_chunk_nibbles: 1, ..Default::default() };
+        let mut input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        // Run the stage until it's done, resuming from the saved checkpoint every time
+        let mut runs = 0;
+        let result = loop {
+            let result = runner.execute(input).await.unwrap();
+            runs += 1;
+            match result {
+                Ok(ExecOutput { checkpoint, done: false }) => {
+                    assert_eq!(checkpoint.block_number, stage_progress);
+                    input.checkpoint = Some(checkpoint);
+                }
+                result => break result,
+            }
+        };
+        // 31 accounts are spread over more than one of the 16 chunks
+        assert!(runs > 1);
+        assert!(runs <= 16);
+
+        assert_matches!(
+            result,
+            Ok(ExecOutput {
+                checkpoint: StageCheckpoint {
+                    block_number,
+                    stage_checkpoint: Some(StageUnitCheckpoint::Entities(EntitiesCheckpoint {
+                        processed,
+                        total
+                    }))
+                },
+                done: true
+            }) if block_number == previous_stage && processed == total &&
+                total == (
+                    runner.db.table::<tables::HashedAccounts>().unwrap().len() +
+                    runner.db.table::<tables::HashedStorages>().unwrap().len()
+                ) as u64
+        );
+
+        // Validate the stage execution
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+        assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
+    }
+
     /// Update small trie
     #[tokio::test]
     async fn execute_small_merkle() {
@@ -463,11 +547,12 @@ mod tests {
     struct MerkleTestRunner {
         db: TestStageDB,
         clean_threshold: u64,
+        clean_chunk_nibbles: usize,
     }
 
     impl Default for MerkleTestRunner {
         fn default() -> Self {
-            Self { db: TestStageDB::default(), clean_threshold: 10000 }
+            Self { db: TestStageDB::default(), clean_threshold: 10000, clean_chunk_nibbles: 0 }
         }
     }
 
@@ -479,7 +564,10 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            Self::S::Both { clean_threshold: self.clean_threshold }
+            Self::S::Both {
+                clean_threshold: self.clean_threshold,
+                clean_chunk_nibbles: self.clean_chunk_nibbles,
+            }
         }
     }
 
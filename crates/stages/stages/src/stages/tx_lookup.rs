@@ -7,7 +7,7 @@ use reth_db_api::{
     transaction::{DbTx, DbTxMut},
 };
 use reth_etl::Collector;
-use reth_primitives::{TxHash, TxNumber};
+use reth_primitives::{BlockNumber, TxHash, TxNumber};
 use reth_provider::{
     BlockReader, DatabaseProviderRW, PruneCheckpointReader, PruneCheckpointWriter, StatsReader,
     TransactionsProvider, TransactionsProviderExt,
@@ -18,6 +18,7 @@ use reth_stages_api::{
     UnwindInput, UnwindOutput,
 };
 use reth_storage_errors::provider::ProviderError;
+use std::collections::VecDeque;
 use tracing::*;
 
 /// The transaction lookup stage.
@@ -28,32 +29,98 @@ use tracing::*;
 /// hash.
 ///
 /// It uses [`reth_etl::Collector`] to collect all entries before finally writing them to disk.
-#[derive(Debug, Clone)]
+///
+/// The collected entries are split into a configurable number of shards by their hash prefix.
+/// Every shard is written and committed separately, one per stage execution, which bounds the
+/// size of a single database transaction. Until all shards are written, the stage checkpoint
+/// stays at the start of the range, so an interrupted run is restarted from scratch.
+///
+/// The stage can be disabled entirely by setting the `transaction_lookup` prune mode to `full`.
+#[derive(Debug)]
 pub struct TransactionLookupStage {
     /// The maximum number of lookup entries to hold in memory before pushing them to
     /// [`reth_etl::Collector`].
     chunk_size: u64,
+    /// The number of hash prefix shards the index is written in.
+    shards: usize,
     etl_config: EtlConfig,
     prune_mode: Option<PruneMode>,
+    /// Collected entries that are not written to the database yet.
+    pending: Option<PendingShards>,
 }
 
 impl Default for TransactionLookupStage {
     fn default() -> Self {
-        Self { chunk_size: 5_000_000, etl_config: EtlConfig::default(), prune_mode: None }
+        Self {
+            chunk_size: 5_000_000,
+            shards: 1,
+            etl_config: EtlConfig::default(),
+            prune_mode: None,
+            pending: None,
+        }
+    }
+}
+
+impl Clone for TransactionLookupStage {
+    /// Clones the stage configuration. Entries that are not written to the database yet are not
+    /// cloned.
+    fn clone(&self) -> Self {
+        Self {
+            chunk_size: self.chunk_size,
+            shards: self.shards,
+            etl_config: self.etl_config.clone(),
+            prune_mode: self.prune_mode,
+            pending: None,
+        }
     }
 }
 
 impl TransactionLookupStage {
     /// Create new instance of [`TransactionLookupStage`].
-    pub const fn new(
+    pub fn new(
         config: TransactionLookupConfig,
         etl_config: EtlConfig,
         prune_mode: Option<PruneMode>,
     ) -> Self {
-        Self { chunk_size: config.chunk_size, etl_config, prune_mode }
+        Self {
+            chunk_size: config.chunk_size,
+            shards: config.shards.clamp(1, MAX_SHARDS),
+            etl_config,
+            prune_mode,
+            pending: None,
+        }
     }
 }
 
+/// The maximum number of shards, one per value of the first byte of the hash.
+const MAX_SHARDS: usize = 256;
+
+/// Transaction hash entries collected for a block range, split into shards by hash prefix.
+#[derive(Debug)]
+struct PendingShards {
+    /// The checkpoint block the entries were collected from.
+    from: BlockNumber,
+    /// The target block the entries were collected up to.
+    target: BlockNumber,
+    /// Whether the table was empty before writing the first shard, so all entries can be
+    /// appended.
+    append_only: bool,
+    /// The remaining shards in ascending hash order.
+    shards: VecDeque<Collector<TxHash, TxNumber>>,
+}
+
+impl PendingShards {
+    /// Returns `true` if the entries were collected for the given input.
+    fn matches(&self, input: &ExecInput) -> bool {
+        self.from == input.checkpoint().block_number && self.target == input.target()
+    }
+}
+
+/// Returns the index of the shard the hash belongs to.
+fn shard_index(hash: &TxHash, shards: usize) -> usize {
+    hash[0] as usize * shards / MAX_SHARDS
+}
+
 impl<DB: Database> Stage<DB> for TransactionLookupStage {
     /// Return the id of the stage
     fn id(&self) -> StageId {
@@ -101,72 +168,38 @@ impl<DB: Database> Stage<DB> for TransactionLookupStage {
             }
         }
         if input.target_reached() {
+            self.pending = None;
             return Ok(ExecOutput::done(input.checkpoint()))
         }
 
-        // 500MB temporary files
-        let mut hash_collector: Collector<TxHash, TxNumber> =
-            Collector::new(self.etl_config.file_size, self.etl_config.dir.clone());
-
-        info!(
-            target: "sync::stages::transaction_lookup",
-            tx_range = ?input.checkpoint().block_number..=input.target(),
-            "Updating transaction lookup"
-        );
-
-        loop {
-            let (tx_range, block_range, is_final_range) =
-                input.next_block_range_with_transaction_threshold(provider, self.chunk_size)?;
-
-            let end_block = *block_range.end();
-
-            info!(target: "sync::stages::transaction_lookup", ?tx_range, "Calculating transaction hashes");
+        // Entries collected during a previous execution can only be reused for the same range
+        if !self.pending.as_ref().is_some_and(|pending| pending.matches(&input)) {
+            self.pending = Some(self.collect_hashes(provider, input)?);
+        }
+        let pending = self.pending.as_mut().expect("pending shards are set");
 
-            for (key, value) in provider.transaction_hashes_by_range(tx_range)? {
-                hash_collector.insert(key, value)?;
+        // Write the next non-empty shard
+        while let Some(mut shard) = pending.shards.pop_front() {
+            if !shard.is_empty() {
+                write_shard(provider, &mut shard, pending.append_only)?;
+                break
             }
+        }
 
-            input.checkpoint = Some(
-                StageCheckpoint::new(end_block)
-                    .with_entities_stage_checkpoint(stage_checkpoint(provider)?),
+        if !pending.shards.is_empty() {
+            debug!(
+                target: "sync::stages::transaction_lookup",
+                remaining_shards = pending.shards.len(),
+                "Transaction hashes shard inserted"
             );
-
-            if is_final_range {
-                let append_only =
-                    provider.count_entries::<tables::TransactionHashNumbers>()?.is_zero();
-                let mut txhash_cursor = provider
-                    .tx_ref()
-                    .cursor_write::<tables::RawTable<tables::TransactionHashNumbers>>()?;
-
-                let total_hashes = hash_collector.len();
-                let interval = (total_hashes / 10).max(1);
-                for (index, hash_to_number) in hash_collector.iter()?.enumerate() {
-                    let (hash, number) = hash_to_number?;
-                    if index > 0 && index % interval == 0 {
-                        info!(
-                            target: "sync::stages::transaction_lookup",
-                            ?append_only,
-                            progress = %format!("{:.2}%", (index as f64 / total_hashes as f64) * 100.0),
-                            "Inserting hashes"
-                        );
-                    }
-
-                    let key = RawKey::<TxHash>::from_vec(hash);
-                    if append_only {
-                        txhash_cursor.append(key, RawValue::<TxNumber>::from_vec(number))?
-                    } else {
-                        txhash_cursor.insert(key, RawValue::<TxNumber>::from_vec(number))?
-                    }
-                }
-
-                trace!(target: "sync::stages::transaction_lookup",
-                    total_hashes,
-                    "Transaction hashes inserted"
-                );
-
-                break
-            }
+            return Ok(ExecOutput {
+                checkpoint: input
+                    .checkpoint()
+                    .with_entities_stage_checkpoint(stage_checkpoint(provider)?),
+                done: false,
+            })
         }
+        self.pending = None;
 
         Ok(ExecOutput {
             checkpoint: StageCheckpoint::new(input.target())
@@ -181,6 +214,8 @@ impl<DB: Database> Stage<DB> for TransactionLookupStage {
         provider: &DatabaseProviderRW<DB>,
         input: UnwindInput,
     ) -> Result<UnwindOutput, StageError> {
+        self.pending = None;
+
         let tx = provider.tx_ref();
         let (range, unwind_to, _) = input.unwind_block_range_with_threshold(self.chunk_size);
 
@@ -212,6 +247,97 @@ impl<DB: Database> Stage<DB> for TransactionLookupStage {
     }
 }
 
+impl TransactionLookupStage {
+    /// Calculates the transaction hashes for the whole input range and collects them into shards.
+    fn collect_hashes<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        mut input: ExecInput,
+    ) -> Result<PendingShards, StageError> {
+        // 500MB temporary files, split between the shards
+        let mut shards = (0..self.shards)
+            .map(|_| {
+                Collector::new(
+                    (self.etl_config.file_size / self.shards).max(1),
+                    self.etl_config.dir.clone(),
+                )
+            })
+            .collect::<VecDeque<Collector<TxHash, TxNumber>>>();
+
+        let from = input.checkpoint().block_number;
+        info!(
+            target: "sync::stages::transaction_lookup",
+            tx_range = ?from..=input.target(),
+            shards = self.shards,
+            "Updating transaction lookup"
+        );
+
+        loop {
+            let (tx_range, block_range, is_final_range) =
+                input.next_block_range_with_transaction_threshold(provider, self.chunk_size)?;
+
+            let end_block = *block_range.end();
+
+            info!(target: "sync::stages::transaction_lookup", ?tx_range, "Calculating transaction hashes");
+
+            for (key, value) in provider.transaction_hashes_by_range(tx_range)? {
+                shards[shard_index(&key, self.shards)].insert(key, value)?;
+            }
+
+            input.checkpoint = Some(StageCheckpoint::new(end_block));
+
+            if is_final_range {
+                break
+            }
+        }
+
+        Ok(PendingShards {
+            from,
+            target: input.target(),
+            append_only: provider.count_entries::<tables::TransactionHashNumbers>()?.is_zero(),
+            shards,
+        })
+    }
+}
+
+/// Writes all entries of the shard to [`tables::TransactionHashNumbers`].
+fn write_shard<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    shard: &mut Collector<TxHash, TxNumber>,
+    append_only: bool,
+) -> Result<(), StageError> {
+    let mut txhash_cursor =
+        provider.tx_ref().cursor_write::<tables::RawTable<tables::TransactionHashNumbers>>()?;
+
+    let total_hashes = shard.len();
+    let interval = (total_hashes / 10).max(1);
+    for (index, hash_to_number) in shard.iter()?.enumerate() {
+        let (hash, number) = hash_to_number?;
+        if index > 0 && index % interval == 0 {
+            info!(
+                target: "sync::stages::transaction_lookup",
+                ?append_only,
+                progress = %format!("{:.2}%", (index as f64 / total_hashes as f64) * 100.0),
+                "Inserting hashes"
+            );
+        }
+
+        let key = RawKey::<TxHash>::from_vec(hash);
+        if append_only {
+            txhash_cursor.append(key, RawValue::<TxNumber>::from_vec(number))?
+        } else {
+            txhash_cursor.insert(key, RawValue::<TxNumber>::from_vec(number))?
+        }
+    }
+
+    trace!(target: "sync::stages::transaction_lookup",
+        total_hashes,
+        "Transaction hashes inserted"
+    );
+
+    Ok(())
+}
+
 fn stage_checkpoint<DB: Database>(
     provider: &DatabaseProviderRW<DB>,
 ) -> Result<EntitiesCheckpoint, StageError> {
@@ -348,6 +474,69 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    #[tokio::test]
+    async fn execute_sharded_transaction_lookup() {
+        let (previous_stage, stage_progress) = (500, 100);
+        let mut rng = generators::rng();
+
+        let runner = TransactionLookupTestRunner { shards: 4, ..Default::default() };
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+
+        let seed =
+            random_block_range(&mut rng, stage_progress + 1..=previous_stage, B256::ZERO, 0..4);
+        runner
+            .db
+            .insert_blocks(seed.iter(), StorageKind::Static)
+            .expect("failed to seed execution");
+
+        let mut stage = runner.stage();
+        let mut shards_written = 0;
+        let output = loop {
+            let provider = runner.db.factory.provider_rw().unwrap();
+            let output = stage.execute(&provider, input).expect("execute");
+            provider.commit().expect("commit");
+            shards_written += 1;
+
+            if output.done {
+                break output
+            }
+            // the checkpoint doesn't advance until all shards are written
+            assert_eq!(output.checkpoint.block_number, stage_progress);
+        };
+
+        assert_eq!(shards_written, 4);
+        assert_matches!(
+            output.checkpoint,
+            StageCheckpoint {
+                block_number,
+                stage_checkpoint: Some(StageUnitCheckpoint::Entities(EntitiesCheckpoint {
+                    processed,
+                    total
+                }))
+            } if block_number == previous_stage && processed == total
+        );
+        assert!(runner.validate_execution(input, Some(output)).is_ok(), "execution validation");
+    }
+
+    #[test]
+    fn shard_index_is_monotonic() {
+        let hash = |first: u8| {
+            let mut hash = TxHash::ZERO;
+            hash[0] = first;
+            hash
+        };
+        assert_eq!(shard_index(&hash(0), 1), 0);
+        assert_eq!(shard_index(&hash(255), 1), 0);
+        assert_eq!(shard_index(&hash(0), 4), 0);
+        assert_eq!(shard_index(&hash(63), 4), 0);
+        assert_eq!(shard_index(&hash(64), 4), 1);
+        assert_eq!(shard_index(&hash(255), 4), 3);
+        assert_eq!(shard_index(&hash(255), MAX_SHARDS), 255);
+    }
+
     #[test]
     fn stage_checkpoint_pruned() {
         let db = TestStageDB::default();
@@ -406,6 +595,7 @@ mod tests {
     struct TransactionLookupTestRunner {
         db: TestStageDB,
         chunk_size: u64,
+        shards: usize,
         etl_config: EtlConfig,
         prune_mode: Option<PruneMode>,
     }
@@ -415,6 +605,7 @@ mod tests {
             Self {
                 db: TestStageDB::default(),
                 chunk_size: 1000,
+                shards: 1,
                 etl_config: EtlConfig::default(),
                 prune_mode: None,
             }
@@ -465,8 +656,10 @@ mod tests {
         fn stage(&self) -> Self::S {
             TransactionLookupStage {
                 chunk_size: self.chunk_size,
+                shards: self.shards,
                 etl_config: self.etl_config.clone(),
                 prune_mode: self.prune_mode,
+                pending: None,
             }
         }
     }
@@ -10,6 +10,8 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::BlockNumber;
+use reth_provider::{DatabaseProvider, PruneCheckpointReader};
+use reth_prune_types::PruneSegment;
 use reth_stages_api::StageError;
 use std::{collections::HashMap, hash::Hash, ops::RangeBounds};
 use tracing::info;
@@ -243,3 +245,50 @@ impl LoadMode {
         matches!(self, Self::Flush)
     }
 }
+
+/// Returns `true` if the changesets of `from_block` were already pruned for the given history
+/// `segment`, or were never written because they fell outside the retention window during
+/// execution.
+///
+/// Stages that read changesets incrementally need to fall back to processing the plain state
+/// in that case.
+pub(crate) fn changesets_pruned<TX: DbTx>(
+    provider: &DatabaseProvider<TX>,
+    segment: PruneSegment,
+    from_block: BlockNumber,
+) -> Result<bool, StageError> {
+    Ok(provider
+        .get_prune_checkpoint(segment)?
+        .and_then(|checkpoint| checkpoint.block_number)
+        .map_or(false, |pruned_block| pruned_block >= from_block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_provider::{test_utils::create_test_provider_factory, PruneCheckpointWriter};
+    use reth_prune_types::{PruneCheckpoint, PruneMode};
+
+    #[test]
+    fn changesets_pruned_by_checkpoint() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+
+        assert!(!changesets_pruned(&provider, PruneSegment::AccountHistory, 1).unwrap());
+
+        provider
+            .save_prune_checkpoint(
+                PruneSegment::AccountHistory,
+                PruneCheckpoint {
+                    block_number: Some(10),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(11),
+                },
+            )
+            .unwrap();
+
+        assert!(changesets_pruned(&provider, PruneSegment::AccountHistory, 10).unwrap());
+        assert!(!changesets_pruned(&provider, PruneSegment::AccountHistory, 11).unwrap());
+        assert!(!changesets_pruned(&provider, PruneSegment::StorageHistory, 10).unwrap());
+    }
+}
@@ -99,6 +99,62 @@ impl StageId {
     pub const fn is_finish(&self) -> bool {
         matches!(self, Self::Finish)
     }
+
+    /// Returns the stages whose output is read by this stage, i.e. the stages that need to have
+    /// run up to a block before this stage can process it.
+    pub const fn dependencies(&self) -> &'static [Self] {
+        match self {
+            Self::Bodies => &[Self::Headers],
+            Self::SenderRecovery | Self::TransactionLookup => &[Self::Bodies],
+            Self::Execution => &[Self::Headers, Self::Bodies, Self::SenderRecovery],
+            Self::AccountHashing |
+            Self::StorageHashing |
+            Self::IndexAccountHistory |
+            Self::IndexStorageHistory => &[Self::Execution],
+            // The trie is built from the hashed state, and unwound based on it.
+            Self::MerkleUnwind | Self::MerkleExecute => {
+                &[Self::AccountHashing, Self::StorageHashing]
+            }
+            Self::Finish => &[
+                Self::MerkleExecute,
+                Self::TransactionLookup,
+                Self::IndexAccountHistory,
+                Self::IndexStorageHistory,
+            ],
+            #[allow(deprecated)]
+            Self::StaticFile => &[],
+            Self::Headers | Self::Other(_) => &[],
+        }
+    }
+
+    /// Returns all stages in [`StageId::ALL`] that directly or transitively depend on the output
+    /// of this stage, in pipeline order.
+    ///
+    /// Re-running this stage for a block range invalidates the output of all of these stages for
+    /// the same range.
+    pub fn dependents(&self) -> Vec<Self> {
+        let mut invalidated = vec![*self];
+        // Iterate until no new dependents are found, because the pipeline order isn't the
+        // dependency order for the merkle unwind stage.
+        loop {
+            let len = invalidated.len();
+            for stage in Self::ALL {
+                if !invalidated.contains(&stage) &&
+                    stage
+                        .dependencies()
+                        .iter()
+                        .any(|dependency| invalidated.contains(dependency))
+                {
+                    invalidated.push(stage);
+                }
+            }
+            if invalidated.len() == len {
+                break
+            }
+        }
+
+        Self::ALL.into_iter().filter(|stage| stage != self && invalidated.contains(stage)).collect()
+    }
 }
 
 impl std::fmt::Display for StageId {
@@ -137,6 +193,31 @@ mod tests {
         assert!(!StageId::Execution.is_downloading_stage());
     }
 
+    #[test]
+    fn stage_dependents() {
+        assert_eq!(
+            StageId::Execution.dependents(),
+            vec![
+                StageId::MerkleUnwind,
+                StageId::AccountHashing,
+                StageId::StorageHashing,
+                StageId::MerkleExecute,
+                StageId::IndexStorageHistory,
+                StageId::IndexAccountHistory,
+                StageId::Finish,
+            ]
+        );
+        assert_eq!(
+            StageId::AccountHashing.dependents(),
+            vec![StageId::MerkleUnwind, StageId::MerkleExecute, StageId::Finish]
+        );
+        assert_eq!(StageId::TransactionLookup.dependents(), vec![StageId::Finish]);
+        assert!(StageId::Finish.dependents().is_empty());
+
+        // Every stage but the first one depends on the headers
+        assert_eq!(StageId::Headers.dependents(), StageId::ALL[1..]);
+    }
+
     // Multiple places around the codebase assume headers is the first stage.
     // Feel free to remove this test if the assumption changes.
     #[test]
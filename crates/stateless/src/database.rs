@@ -0,0 +1,142 @@
+use crate::{trie::PartialTrie, ExecutionWitness, WitnessError};
+use alloy_rlp::Decodable;
+use reth_primitives::{keccak256, Address, Bytes, B256, U256};
+use reth_trie_common::{Nibbles, TrieAccount, EMPTY_ROOT_HASH};
+use revm::{
+    db::BundleState,
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use std::collections::HashMap;
+
+/// A [Database] serving the state of an [`ExecutionWitness`].
+///
+/// Accounts and storage slots are read from the partial state tries revealed by the witness, block
+/// hashes from its headers. State that is not part of the witness can't be read, which fails the
+/// execution of the block.
+#[derive(Debug)]
+pub struct WitnessDatabase {
+    /// Trie nodes of the witness, keyed by hash.
+    nodes: HashMap<B256, Bytes>,
+    /// The account trie of the parent state.
+    accounts: PartialTrie,
+    /// Storage tries of the accessed accounts, revealed on first access.
+    storage_tries: HashMap<Address, PartialTrie>,
+    /// Bytecodes of the witness, keyed by hash.
+    codes: HashMap<B256, Bytecode>,
+    /// Hashes of the ancestors of the block.
+    block_hashes: HashMap<u64, B256>,
+}
+
+impl WitnessDatabase {
+    /// Creates a database for the execution of the child of `parent_hash`.
+    ///
+    /// The headers of the witness must form the chain of ancestors of the block, which proves the
+    /// state root of the parent the state tries are revealed from.
+    pub fn new(witness: &ExecutionWitness, parent_hash: B256) -> Result<Self, WitnessError> {
+        let mut block_hashes = HashMap::with_capacity(witness.headers.len());
+        let mut expected_hash = parent_hash;
+        for header in &witness.headers {
+            let hash = header.hash_slow();
+            if hash != expected_hash {
+                return Err(WitnessError::UnlinkedHeader(header.number))
+            }
+            block_hashes.insert(header.number, hash);
+            expected_hash = header.parent_hash;
+        }
+        let parent = witness.headers.first().ok_or(WitnessError::MissingParentHeader)?;
+
+        let nodes = witness.state.iter().map(|node| (keccak256(node), node.clone())).collect();
+        let accounts = PartialTrie::from_nodes(parent.state_root, &nodes)?;
+        let codes = witness
+            .codes
+            .iter()
+            .map(|code| (keccak256(code), Bytecode::new_raw(code.clone())))
+            .collect();
+
+        Ok(Self { nodes, accounts, storage_tries: HashMap::new(), codes, block_hashes })
+    }
+
+    /// Returns the account from the account trie.
+    fn account(&self, address: Address) -> Result<Option<TrieAccount>, WitnessError> {
+        let Some(mut rlp) = self.accounts.get(&Nibbles::unpack(keccak256(address)))? else {
+            return Ok(None)
+        };
+        Ok(Some(TrieAccount::decode(&mut rlp)?))
+    }
+
+    /// Returns the storage trie of the account, revealing it on first access.
+    fn storage_trie(&mut self, address: Address) -> Result<&mut PartialTrie, WitnessError> {
+        if !self.storage_tries.contains_key(&address) {
+            let storage_root = self.account(address)?.map_or(EMPTY_ROOT_HASH, |a| a.storage_root);
+            let trie = PartialTrie::from_nodes(storage_root, &self.nodes)?;
+            self.storage_tries.insert(address, trie);
+        }
+        Ok(self.storage_tries.get_mut(&address).expect("storage trie is revealed"))
+    }
+
+    /// Applies the state changes of the executed block to the state tries, and returns the new
+    /// state root.
+    pub fn into_state_root(mut self, bundle: &BundleState) -> Result<B256, WitnessError> {
+        for (address, account) in bundle.state() {
+            let hashed_address = Nibbles::unpack(keccak256(address));
+            let Some(info) = &account.info else {
+                self.accounts.remove(&hashed_address)?;
+                continue
+            };
+
+            // the storage of destroyed accounts is wiped, and their new storage is in the bundle
+            if account.was_destroyed() {
+                self.storage_tries.insert(*address, PartialTrie::default());
+            }
+            let storage_trie = self.storage_trie(*address)?;
+            for (slot, value) in &account.storage {
+                let hashed_slot = Nibbles::unpack(keccak256(B256::new(slot.to_be_bytes())));
+                if value.present_value.is_zero() {
+                    storage_trie.remove(&hashed_slot)?;
+                } else {
+                    storage_trie.insert(&hashed_slot, alloy_rlp::encode(value.present_value))?;
+                }
+            }
+
+            let account = TrieAccount {
+                nonce: info.nonce,
+                balance: info.balance,
+                storage_root: storage_trie.root(),
+                code_hash: info.code_hash,
+            };
+            self.accounts.insert(&hashed_address, alloy_rlp::encode(account))?;
+        }
+        Ok(self.accounts.root())
+    }
+}
+
+impl Database for WitnessDatabase {
+    type Error = WitnessError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.account(address)?.map(|account| AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code: None,
+        }))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.codes.get(&code_hash).cloned().ok_or(WitnessError::MissingBytecode(code_hash))
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let hashed_slot = Nibbles::unpack(keccak256(B256::new(index.to_be_bytes())));
+        let Some(mut rlp) = self.storage_trie(address)?.get(&hashed_slot)? else {
+            return Ok(U256::ZERO)
+        };
+        Ok(U256::decode(&mut rlp)?)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        let number = number.to();
+        self.block_hashes.get(&number).copied().ok_or(WitnessError::MissingBlockHash(number))
+    }
+}
@@ -0,0 +1,53 @@
+use reth_execution_errors::BlockExecutionError;
+use reth_primitives::{GotExpected, B256};
+use reth_storage_errors::{db::DatabaseError, provider::ProviderError};
+
+/// Error of an [`ExecutionWitness`](crate::ExecutionWitness) that doesn't contain the state
+/// accessed by a block.
+#[derive(Debug, thiserror::Error)]
+pub enum WitnessError {
+    /// The witness has no headers, so the parent state root is unknown.
+    #[error("witness has no parent header")]
+    MissingParentHeader,
+    /// A header of the witness is not the parent of the previous one.
+    #[error("witness header of block {0} is not an ancestor of the block")]
+    UnlinkedHeader(u64),
+    /// A block hash accessed by the block is not in the headers of the witness.
+    #[error("hash of block {0} is missing from the witness")]
+    MissingBlockHash(u64),
+    /// A trie node accessed by the block is missing from the witness.
+    #[error("trie node {0} is missing from the witness")]
+    MissingTrieNode(B256),
+    /// A bytecode executed by the block is missing from the witness.
+    #[error("bytecode {0} is missing from the witness")]
+    MissingBytecode(B256),
+    /// A trie node or leaf of the witness could not be decoded.
+    #[error("invalid trie node in witness: {0}")]
+    InvalidTrieNode(#[from] alloy_rlp::Error),
+    /// A compressed witness could not be decompressed.
+    #[error("invalid compressed witness: {0}")]
+    InvalidCompression(#[from] snap::Error),
+    /// A witness could not be decoded.
+    #[error("invalid witness encoding: {0}")]
+    InvalidEncoding(alloy_rlp::Error),
+}
+
+impl From<WitnessError> for ProviderError {
+    fn from(err: WitnessError) -> Self {
+        Self::Database(DatabaseError::Other(err.to_string()))
+    }
+}
+
+/// Error of the stateless execution of a block.
+#[derive(Debug, thiserror::Error)]
+pub enum StatelessError {
+    /// The witness is invalid or incomplete.
+    #[error(transparent)]
+    Witness(#[from] WitnessError),
+    /// The block could not be executed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The state root after executing the block doesn't match the state root of the block.
+    #[error("state root mismatch: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+}
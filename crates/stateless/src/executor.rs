@@ -0,0 +1,208 @@
+use crate::{ExecutionWitness, StatelessError, WitnessDatabase};
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_primitives::{BlockWithSenders, GotExpected, Receipt, U256};
+
+/// Executes blocks against [`ExecutionWitness`]es instead of a database, verifying their state
+/// root.
+///
+/// Blocks are executed with the executors of the given [`BlockExecutorProvider`], so the block is
+/// processed exactly as by the stateful executors, e.g. including the system calls and rewards of
+/// the chain.
+#[derive(Debug, Clone)]
+pub struct StatelessExecutor<E> {
+    /// Provider of the executors of the chain.
+    executor_provider: E,
+}
+
+impl<E> StatelessExecutor<E> {
+    /// Creates a new stateless executor executing blocks with the given provider.
+    pub const fn new(executor_provider: E) -> Self {
+        Self { executor_provider }
+    }
+}
+
+impl<E: BlockExecutorProvider> StatelessExecutor<E> {
+    /// Executes the block against the witness, and verifies that the state root after execution
+    /// matches the state root of the block.
+    ///
+    /// As with [`Executor::execute`], the receipts and gas used of the block are not validated,
+    /// which is up to the caller, e.g. with the consensus of the chain.
+    pub fn execute(
+        &self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+        witness: &ExecutionWitness,
+    ) -> Result<BlockExecutionOutput<Receipt>, StatelessError> {
+        let mut db = WitnessDatabase::new(witness, block.parent_hash)?;
+        let output =
+            self.executor_provider.executor(&mut db).execute((block, total_difficulty).into())?;
+
+        let state_root = db.into_state_root(&output.state)?;
+        if state_root != block.state_root {
+            return Err(StatelessError::StateRootMismatch(GotExpected {
+                got: state_root,
+                expected: block.state_root,
+            }))
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{trie::PartialTrie, WitnessError};
+    use reth_chainspec::{ChainSpecBuilder, MAINNET};
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        keccak256, public_key_to_address, Address, Block, Bytes, Header, Transaction, TxKind,
+        TxLegacy, B256,
+    };
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_trie_common::{
+        root::{state_root_unhashed, storage_root_unhashed},
+        Nibbles, TrieAccount,
+    };
+    use revm::primitives::AccountInfo;
+    use secp256k1::{Keypair, Secp256k1};
+    use std::{collections::HashMap, sync::Arc};
+
+    type TestState = HashMap<Address, (AccountInfo, HashMap<U256, U256>)>;
+
+    fn storage_root(storage: &HashMap<U256, U256>) -> B256 {
+        storage_root_unhashed(
+            storage
+                .iter()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(slot, value)| (B256::new(slot.to_be_bytes()), *value)),
+        )
+    }
+
+    fn state_root(state: &TestState) -> B256 {
+        state_root_unhashed(state.iter().map(|(address, (info, storage))| {
+            (*address, TrieAccount::from((info.clone(), storage_root(storage))))
+        }))
+    }
+
+    /// Returns the witness of the full state.
+    fn state_witness(state: &TestState) -> Vec<Bytes> {
+        let mut accounts = PartialTrie::default();
+        let mut nodes = Vec::new();
+        for (address, (info, storage)) in state {
+            let mut storage_trie = PartialTrie::default();
+            for (slot, value) in storage {
+                let hashed_slot = Nibbles::unpack(keccak256(B256::new(slot.to_be_bytes())));
+                storage_trie.insert(&hashed_slot, alloy_rlp::encode(value)).unwrap();
+            }
+            nodes.extend(storage_trie.nodes());
+
+            let account = TrieAccount::from((info.clone(), storage_trie.root()));
+            accounts
+                .insert(&Nibbles::unpack(keccak256(address)), alloy_rlp::encode(account))
+                .unwrap();
+        }
+        assert_eq!(accounts.root(), state_root(state));
+        nodes.extend(accounts.nodes());
+        nodes
+    }
+
+    #[test]
+    fn execute_against_witness() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).berlin_activated().build());
+        let provider = StatelessExecutor::new(EthExecutorProvider::ethereum(chain_spec.clone()));
+
+        let secp = Secp256k1::new();
+        let sender_key_pair = Keypair::new(&secp, &mut generators::rng());
+        let sender = public_key_to_address(sender_key_pair.public_key());
+        let recipient = Address::repeat_byte(1);
+        let contract = Address::repeat_byte(2);
+        // PUSH1 0x2a PUSH1 0x00 SSTORE STOP
+        let code = Bytes::from_static(&[0x60, 0x2a, 0x60, 0x00, 0x55, 0x00]);
+
+        let mut pre_state = TestState::default();
+        pre_state.insert(
+            sender,
+            (
+                AccountInfo { balance: U256::from(1_000_000_000), ..Default::default() },
+                HashMap::new(),
+            ),
+        );
+        pre_state.insert(
+            recipient,
+            (AccountInfo { balance: U256::from(1), ..Default::default() }, HashMap::new()),
+        );
+        pre_state.insert(
+            contract,
+            (
+                AccountInfo { nonce: 1, code_hash: keccak256(&code), ..Default::default() },
+                HashMap::from([(U256::from(1), U256::from(5))]),
+            ),
+        );
+
+        let parent = Header { state_root: state_root(&pre_state), ..Default::default() };
+        let witness = ExecutionWitness {
+            state: state_witness(&pre_state),
+            codes: vec![code],
+            headers: vec![parent.clone()],
+        };
+
+        let transactions = [(recipient, U256::from(100)), (contract, U256::ZERO)]
+            .into_iter()
+            .enumerate()
+            .map(|(nonce, (to, value))| {
+                sign_tx_with_key_pair(
+                    sender_key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(chain_spec.chain.id()),
+                        nonce: nonce as u64,
+                        gas_price: 1,
+                        gas_limit: 100_000,
+                        to: TxKind::Call(to),
+                        value,
+                        input: Bytes::new(),
+                    }),
+                )
+            })
+            .collect();
+        let mut block = Block {
+            header: Header {
+                parent_hash: parent.hash_slow(),
+                number: 1,
+                gas_limit: 1_000_000,
+                ..Default::default()
+            },
+            body: transactions,
+            ..Default::default()
+        }
+        .with_recovered_senders()
+        .unwrap();
+
+        // the state root is verified
+        let err = provider.execute(&block, U256::ZERO, &witness).unwrap_err();
+        let StatelessError::StateRootMismatch(GotExpected { got, .. }) = err else {
+            panic!("expected state root mismatch, got {err:?}")
+        };
+        block.header.state_root = got;
+        let output = provider.execute(&block, U256::ZERO, &witness).unwrap();
+
+        // the state root matches the state root of the full post state
+        let mut post_state = pre_state;
+        for (address, account) in output.state.state() {
+            let (info, storage) = post_state.entry(*address).or_default();
+            *info = account.info.clone().unwrap();
+            storage
+                .extend(account.storage.iter().map(|(slot, value)| (*slot, value.present_value)));
+        }
+        assert_eq!(post_state[&contract].1[&U256::ZERO], U256::from(0x2a));
+        assert_eq!(got, state_root(&post_state));
+
+        // state missing from the witness fails the execution
+        let partial_witness = ExecutionWitness { codes: Vec::new(), ..witness };
+        assert!(provider.execute(&block, U256::ZERO, &partial_witness).is_err());
+        let unlinked_witness = ExecutionWitness { headers: Vec::new(), ..partial_witness };
+        assert!(matches!(
+            provider.execute(&block, U256::ZERO, &unlinked_witness),
+            Err(StatelessError::Witness(WitnessError::MissingParentHeader))
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::ExecutionWitness;
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_execution_errors::BlockExecutionError;
+use reth_primitives::{Address, BlockWithSenders, Receipt, B256, U256};
+use reth_revm::database::StateProviderDatabase;
+use reth_storage_api::{HeaderProvider, StateProvider};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use revm::{
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Generates the [`ExecutionWitness`] of blocks by executing them on top of the state of their
+/// parent and recording the state they access.
+///
+/// The trie nodes of the witness are taken from the proofs of the accessed state, so the state of
+/// the parent must support proofs.
+#[derive(Debug, Clone)]
+pub struct WitnessGenerator<E> {
+    /// Provider of the executors of the chain.
+    executor_provider: E,
+}
+
+impl<E> WitnessGenerator<E> {
+    /// Creates a new generator executing blocks with the given provider.
+    pub const fn new(executor_provider: E) -> Self {
+        Self { executor_provider }
+    }
+}
+
+impl<E: BlockExecutorProvider> WitnessGenerator<E> {
+    /// Executes the block on top of the given state of its parent and returns the witness of the
+    /// accessed state, together with the output of the execution.
+    ///
+    /// The witness is returned even if the execution of the block fails, containing the state
+    /// accessed until the failure.
+    pub fn execute<SP, H>(
+        &self,
+        state_provider: &SP,
+        headers: &H,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+    ) -> ProviderResult<WitnessedExecution>
+    where
+        SP: StateProvider + ?Sized,
+        H: HeaderProvider,
+    {
+        let parent_number = block
+            .number
+            .checked_sub(1)
+            .ok_or_else(|| ProviderError::HeaderNotFound(block.parent_hash.into()))?;
+
+        let mut accessed = AccessedState::default();
+        let db = RecordingDatabase::new(StateProviderDatabase::new(state_provider), &mut accessed);
+        let output = self.executor_provider.executor(db).execute((block, total_difficulty).into());
+
+        let witness = accessed.into_witness(state_provider, headers, parent_number)?;
+
+        Ok(WitnessedExecution { witness, output })
+    }
+}
+
+/// The result of the execution of a block by the [`WitnessGenerator`].
+#[derive(Debug)]
+pub struct WitnessedExecution {
+    /// The witness of the state accessed by the execution.
+    pub witness: ExecutionWitness,
+    /// The output of the execution.
+    pub output: Result<BlockExecutionOutput<Receipt>, BlockExecutionError>,
+}
+
+/// The state accessed by the execution of a block.
+#[derive(Debug, Default)]
+struct AccessedState {
+    /// The accessed accounts, with their accessed storage slots.
+    accounts: BTreeMap<Address, BTreeSet<B256>>,
+    /// Hashes of the accessed bytecodes.
+    codes: BTreeSet<B256>,
+    /// Numbers of the blocks whose hashes were accessed.
+    block_hashes: BTreeSet<u64>,
+}
+
+impl AccessedState {
+    /// Creates the witness of the accessed state from the proofs of the state of the parent.
+    fn into_witness<SP, H>(
+        self,
+        state_provider: &SP,
+        headers: &H,
+        parent_number: u64,
+    ) -> ProviderResult<ExecutionWitness>
+    where
+        SP: StateProvider + ?Sized,
+        H: HeaderProvider,
+    {
+        let mut state = BTreeSet::new();
+        for (address, slots) in self.accounts {
+            let slots = slots.into_iter().collect::<Vec<_>>();
+            let proof = state_provider.proof(address, &slots)?;
+            state.extend(proof.proof);
+            for storage_proof in proof.storage_proofs {
+                state.extend(storage_proof.proof);
+            }
+        }
+
+        let mut codes = Vec::with_capacity(self.codes.len());
+        for code_hash in self.codes {
+            if let Some(code) = state_provider.bytecode_by_hash(code_hash)? {
+                codes.push(code.original_bytes());
+            }
+        }
+
+        let oldest = self.block_hashes.first().map_or(parent_number, |n| (*n).min(parent_number));
+        let mut headers = headers.headers_range(oldest..=parent_number)?;
+        if headers.len() as u64 != parent_number - oldest + 1 {
+            return Err(ProviderError::HeaderNotFound(oldest.into()))
+        }
+        headers.reverse();
+
+        Ok(ExecutionWitness { state: state.into_iter().collect(), codes, headers })
+    }
+}
+
+/// A [`Database`] that records the state accessed through it in an [`AccessedState`].
+#[derive(Debug)]
+struct RecordingDatabase<'a, DB> {
+    inner: DB,
+    accessed: &'a mut AccessedState,
+}
+
+impl<'a, DB> RecordingDatabase<'a, DB> {
+    /// Creates a new database recording the accesses to `inner`.
+    fn new(inner: DB, accessed: &'a mut AccessedState) -> Self {
+        Self { inner, accessed }
+    }
+}
+
+impl<'a, DB: Database> Database for RecordingDatabase<'a, DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.accessed.accounts.entry(address).or_default();
+        self.inner.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.accessed.codes.insert(code_hash);
+        self.inner.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.accessed.accounts.entry(address).or_default().insert(B256::from(index.to_be_bytes()));
+        self.inner.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        self.accessed.block_hashes.insert(number.saturating_to());
+        self.inner.block_hash(number)
+    }
+}
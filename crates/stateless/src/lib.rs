@@ -0,0 +1,36 @@
+//! Stateless execution of blocks.
+//!
+//! A block is executed against an [`ExecutionWitness`] instead of a database: the witness contains
+//! the trie nodes, bytecodes and ancestor headers the block accesses, which are verified against
+//! the state root of the parent block. The state changes of the block are then applied to the
+//! revealed tries to verify the state root of the block.
+//!
+//! Witnesses are generated with the [`WitnessGenerator`], by executing blocks on top of the state
+//! of their parent. Stateless execution is the counterpart to the generation of execution
+//! witnesses, e.g. to cross-check the execution of other clients or to execute blocks in zk
+//! provers.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod database;
+pub use database::WitnessDatabase;
+
+mod error;
+pub use error::{StatelessError, WitnessError};
+
+mod executor;
+pub use executor::StatelessExecutor;
+
+mod generator;
+pub use generator::{WitnessGenerator, WitnessedExecution};
+
+pub mod trie;
+
+mod witness;
+pub use witness::ExecutionWitness;
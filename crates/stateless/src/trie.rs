@@ -0,0 +1,361 @@
+//! Partial Merkle Patricia tries, revealed from the trie nodes of an execution witness.
+
+use crate::WitnessError;
+use alloy_rlp::{Decodable, EMPTY_STRING_CODE};
+use reth_primitives::{keccak256, Bytes, B256};
+use reth_trie_common::{
+    nodes::{word_rlp, BranchNodeRef, ExtensionNodeRef, LeafNodeRef, TrieNode},
+    Nibbles, TrieMask, EMPTY_ROOT_HASH,
+};
+use std::collections::HashMap;
+
+/// A node of a [`PartialTrie`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum Node {
+    /// No node, i.e. the empty trie or an empty child of a branch.
+    #[default]
+    Empty,
+    /// A node that is not revealed, known by its hash only.
+    Blinded(B256),
+    /// A leaf node.
+    Leaf { key: Nibbles, value: Vec<u8> },
+    /// An extension node.
+    Extension { key: Nibbles, child: Box<Self> },
+    /// A branch node, without value.
+    Branch { children: Box<[Self; 16]> },
+}
+
+impl Node {
+    /// Returns a branch node without children.
+    fn empty_branch() -> Self {
+        Self::Branch { children: Box::new(core::array::from_fn(|_| Self::Empty)) }
+    }
+
+    /// Reveals the node referenced by a parent node, either by its hash or inline.
+    fn reveal(reference: &[u8], nodes: &HashMap<B256, Bytes>) -> Result<Self, WitnessError> {
+        if reference.len() == B256::len_bytes() + 1 {
+            let hash = B256::from_slice(&reference[1..]);
+            return match nodes.get(&hash) {
+                Some(rlp) => Self::decode(rlp, nodes),
+                None => Ok(Self::Blinded(hash)),
+            }
+        }
+        Self::decode(reference, nodes)
+    }
+
+    /// Decodes the RLP encoded node, revealing its children.
+    fn decode(mut rlp: &[u8], nodes: &HashMap<B256, Bytes>) -> Result<Self, WitnessError> {
+        let node = match TrieNode::decode(&mut rlp)? {
+            TrieNode::Leaf(leaf) => Self::Leaf { key: leaf.key, value: leaf.value },
+            TrieNode::Extension(extension) => Self::Extension {
+                key: extension.key,
+                child: Box::new(Self::reveal(&extension.child, nodes)?),
+            },
+            TrieNode::Branch(branch) => {
+                let mut node = Self::empty_branch();
+                let Self::Branch { children } = &mut node else { unreachable!() };
+                let mut stack = branch.stack.iter();
+                for index in 0..16 {
+                    if branch.state_mask.is_bit_set(index) {
+                        let reference = stack
+                            .next()
+                            .ok_or(alloy_rlp::Error::Custom("branch node child missing"))?;
+                        children[index as usize] = Self::reveal(reference, nodes)?;
+                    }
+                }
+                node
+            }
+        };
+        Ok(node)
+    }
+
+    /// Encodes the node into `out` and returns its reference in a parent node: the node itself if
+    /// its encoding is shorter than 32 bytes, the hash of the encoding otherwise.
+    ///
+    /// Blinded nodes are only referenced, and nothing is encoded into `out`.
+    fn encode(&self, out: &mut Vec<u8>) -> Vec<u8> {
+        match self {
+            Self::Empty => {
+                out.push(EMPTY_STRING_CODE);
+                out.clone()
+            }
+            Self::Blinded(hash) => word_rlp(hash),
+            Self::Leaf { key, value } => LeafNodeRef::new(key, value).rlp(out),
+            Self::Extension { key, child } => {
+                let child = child.encode(&mut Vec::new());
+                ExtensionNodeRef::new(key, &child).rlp(out)
+            }
+            Self::Branch { children } => {
+                let mut stack = Vec::new();
+                let mut state_mask = TrieMask::default();
+                for (index, child) in children.iter().enumerate() {
+                    if *child != Self::Empty {
+                        stack.push(child.encode(&mut Vec::new()));
+                        state_mask.set_bit(index as u8);
+                    }
+                }
+                BranchNodeRef::new(&stack, &state_mask).rlp(out)
+            }
+        }
+    }
+
+    /// Returns the value at the path below the node.
+    fn get(&self, path: &[u8]) -> Result<Option<&[u8]>, WitnessError> {
+        match self {
+            Self::Empty => Ok(None),
+            Self::Blinded(hash) => Err(WitnessError::MissingTrieNode(*hash)),
+            Self::Leaf { key, value } => Ok((key.as_slice() == path).then_some(value.as_slice())),
+            Self::Extension { key, child } => match path.strip_prefix(key.as_slice()) {
+                Some(rest) => child.get(rest),
+                None => Ok(None),
+            },
+            Self::Branch { children } => match path.split_first() {
+                Some((index, rest)) => children[*index as usize].get(rest),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Inserts the value at the path below the node.
+    fn insert(&mut self, path: &[u8], value: Vec<u8>) -> Result<(), WitnessError> {
+        match self {
+            Self::Empty => *self = Self::Leaf { key: Nibbles::from_nibbles_unchecked(path), value },
+            Self::Blinded(hash) => return Err(WitnessError::MissingTrieNode(*hash)),
+            Self::Leaf { key, value: existing } => {
+                if key.as_slice() == path {
+                    *existing = value;
+                    return Ok(())
+                }
+                // split the leaf into a branch at the first differing nibble
+                let common = key.common_prefix_length(path);
+                let mut branch = Self::empty_branch();
+                let Self::Branch { children } = &mut branch else { unreachable!() };
+                children[key[common] as usize] = Self::Leaf {
+                    key: Nibbles::from_nibbles_unchecked(&key[common + 1..]),
+                    value: core::mem::take(existing),
+                };
+                children[path[common] as usize] =
+                    Self::Leaf { key: Nibbles::from_nibbles_unchecked(&path[common + 1..]), value };
+                *self = Self::with_prefix(&path[..common], branch);
+            }
+            Self::Extension { key, child } => {
+                let common = key.common_prefix_length(path);
+                if common == key.len() {
+                    return child.insert(&path[common..], value)
+                }
+                // split the extension into a branch at the first differing nibble
+                let mut branch = Self::empty_branch();
+                let Self::Branch { children } = &mut branch else { unreachable!() };
+                children[key[common] as usize] =
+                    Self::with_prefix(&key[common + 1..], core::mem::take(child.as_mut()));
+                children[path[common] as usize] =
+                    Self::Leaf { key: Nibbles::from_nibbles_unchecked(&path[common + 1..]), value };
+                *self = Self::with_prefix(&path[..common], branch);
+            }
+            Self::Branch { children } => {
+                let (index, rest) = path.split_first().expect("paths have the same length");
+                children[*index as usize].insert(rest, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the value at the path below the node, if any.
+    ///
+    /// Branches left with a single child are merged with it, which requires the child to be
+    /// revealed.
+    fn remove(&mut self, path: &[u8]) -> Result<(), WitnessError> {
+        match self {
+            Self::Empty => {}
+            Self::Blinded(hash) => return Err(WitnessError::MissingTrieNode(*hash)),
+            Self::Leaf { key, .. } => {
+                if key.as_slice() == path {
+                    *self = Self::Empty;
+                }
+            }
+            Self::Extension { key, child } => {
+                let Some(rest) = path.strip_prefix(key.as_slice()) else { return Ok(()) };
+                child.remove(rest)?;
+                let key = core::mem::take(key);
+                *self = Self::with_prefix(&key, core::mem::take(child.as_mut()));
+            }
+            Self::Branch { children } => {
+                let Some((index, rest)) = path.split_first() else { return Ok(()) };
+                children[*index as usize].remove(rest)?;
+
+                let mut remaining =
+                    children.iter_mut().enumerate().filter(|(_, c)| **c != Self::Empty);
+                match (remaining.next(), remaining.next()) {
+                    (None, _) => *self = Self::Empty,
+                    (Some((index, child)), None) => {
+                        if let Self::Blinded(hash) = child {
+                            return Err(WitnessError::MissingTrieNode(*hash))
+                        }
+                        let child = core::mem::take(child);
+                        *self = Self::with_prefix(&[index as u8], child);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the node at the path `prefix` below a new node, merging it into the node if it's a
+    /// leaf or an extension.
+    fn with_prefix(prefix: &[u8], node: Self) -> Self {
+        let join = |key: &Nibbles| Nibbles::from_nibbles_unchecked([prefix, key].concat());
+        match node {
+            node if prefix.is_empty() => node,
+            Self::Empty => Self::Empty,
+            Self::Leaf { key, value } => Self::Leaf { key: join(&key), value },
+            Self::Extension { key, child } => Self::Extension { key: join(&key), child },
+            node => Self::Extension {
+                key: Nibbles::from_nibbles_unchecked(prefix),
+                child: Box::new(node),
+            },
+        }
+    }
+
+    /// Collects the encoding of the node and its descendants that are referenced by hash.
+    fn collect_nodes(&self, root: bool, nodes: &mut Vec<Bytes>) {
+        match self {
+            Self::Empty | Self::Blinded(_) => return,
+            Self::Leaf { .. } => {}
+            Self::Extension { child, .. } => child.collect_nodes(false, nodes),
+            Self::Branch { children } => {
+                for child in children.iter() {
+                    child.collect_nodes(false, nodes);
+                }
+            }
+        }
+        let mut rlp = Vec::new();
+        self.encode(&mut rlp);
+        if root || rlp.len() >= B256::len_bytes() {
+            nodes.push(rlp.into());
+        }
+    }
+}
+
+/// A Merkle Patricia trie of which only some nodes are known, e.g. the nodes of an execution
+/// witness.
+///
+/// Values can be read and updated at the revealed paths, and the root of the updated trie can be
+/// computed without the nodes that are not revealed. Accessing a path through a node that is not
+/// revealed fails with [`WitnessError::MissingTrieNode`].
+///
+/// All keys of a trie must have the same length, e.g. the 64 nibbles of hashed keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartialTrie {
+    root: Node,
+}
+
+impl PartialTrie {
+    /// Reveals the trie with the given root from the RLP encoded trie nodes, keyed by hash.
+    ///
+    /// Nodes without preimage are not revealed.
+    pub fn from_nodes(root: B256, nodes: &HashMap<B256, Bytes>) -> Result<Self, WitnessError> {
+        if root == EMPTY_ROOT_HASH {
+            return Ok(Self::default())
+        }
+        Ok(Self { root: Node::reveal(&word_rlp(&root), nodes)? })
+    }
+
+    /// Returns the value at the key.
+    pub fn get(&self, key: &Nibbles) -> Result<Option<&[u8]>, WitnessError> {
+        self.root.get(key)
+    }
+
+    /// Inserts the value at the key, replacing the existing value.
+    pub fn insert(&mut self, key: &Nibbles, value: Vec<u8>) -> Result<(), WitnessError> {
+        self.root.insert(key, value)
+    }
+
+    /// Removes the value at the key, if any.
+    pub fn remove(&mut self, key: &Nibbles) -> Result<(), WitnessError> {
+        self.root.remove(key)
+    }
+
+    /// Returns the root hash of the trie.
+    pub fn root(&self) -> B256 {
+        match &self.root {
+            Node::Empty => EMPTY_ROOT_HASH,
+            Node::Blinded(hash) => *hash,
+            node => {
+                let mut rlp = Vec::new();
+                node.encode(&mut rlp);
+                keccak256(rlp)
+            }
+        }
+    }
+
+    /// Returns the RLP encoded nodes of the trie that are referenced by hash, i.e. the nodes to
+    /// include in a witness to reveal the trie.
+    pub fn nodes(&self) -> Vec<Bytes> {
+        let mut nodes = Vec::new();
+        self.root.collect_nodes(true, &mut nodes);
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::U256;
+    use reth_trie_common::root::storage_root_unhashed;
+
+    fn slot_key(slot: u64) -> Nibbles {
+        Nibbles::unpack(keccak256(B256::with_last_byte(slot as u8)))
+    }
+
+    fn storage(slots: impl Iterator<Item = u64>) -> Vec<(B256, U256)> {
+        slots.map(|slot| (B256::with_last_byte(slot as u8), U256::from(slot + 1))).collect()
+    }
+
+    #[test]
+    fn root_matches_hash_builder() {
+        let mut trie = PartialTrie::default();
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+
+        for (slot, value) in storage(0..64) {
+            trie.insert(&Nibbles::unpack(keccak256(slot)), alloy_rlp::encode(value)).unwrap();
+        }
+        assert_eq!(trie.root(), storage_root_unhashed(storage(0..64)));
+
+        for slot in 32..64 {
+            trie.remove(&slot_key(slot)).unwrap();
+        }
+        assert_eq!(trie.root(), storage_root_unhashed(storage(0..32)));
+
+        for slot in 0..32 {
+            trie.remove(&slot_key(slot)).unwrap();
+        }
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn reveal_from_nodes() {
+        let mut trie = PartialTrie::default();
+        for (slot, value) in storage(0..16) {
+            trie.insert(&Nibbles::unpack(keccak256(slot)), alloy_rlp::encode(value)).unwrap();
+        }
+        let root = trie.root();
+
+        let nodes = trie.nodes().into_iter().map(|node| (keccak256(&node), node)).collect();
+        let revealed = PartialTrie::from_nodes(root, &nodes).unwrap();
+        assert_eq!(revealed, trie);
+        assert_eq!(
+            revealed.get(&slot_key(3)).unwrap(),
+            Some(&alloy_rlp::encode(U256::from(4))[..])
+        );
+        assert_eq!(revealed.get(&slot_key(16)).unwrap(), None);
+
+        // only the root is revealed
+        let root_node =
+            nodes.iter().filter(|(hash, _)| **hash == root).map(|(h, n)| (*h, n.clone()));
+        let mut partial = PartialTrie::from_nodes(root, &root_node.collect()).unwrap();
+        assert_eq!(partial.root(), root);
+        assert!(matches!(partial.get(&slot_key(3)), Err(WitnessError::MissingTrieNode(_))));
+        assert!(matches!(partial.remove(&slot_key(3)), Err(WitnessError::MissingTrieNode(_))));
+    }
+}
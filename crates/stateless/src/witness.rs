@@ -0,0 +1,57 @@
+use crate::WitnessError;
+use alloy_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use reth_primitives::{Bytes, Header};
+use serde::{Deserialize, Serialize};
+
+/// The state accessed by the execution of a block, proven against the state root of its parent.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, RlpEncodable, RlpDecodable,
+)]
+pub struct ExecutionWitness {
+    /// RLP encoded nodes of the account trie and the storage tries of the parent state, from the
+    /// root to the leaves the block accesses, including the siblings needed to apply the state
+    /// changes of the block.
+    pub state: Vec<Bytes>,
+    /// Bytecodes of the contracts executed by the block.
+    pub codes: Vec<Bytes>,
+    /// Ancestor headers of the block, starting with the parent, up to the oldest block whose hash
+    /// is accessed by the block.
+    pub headers: Vec<Header>,
+}
+
+impl ExecutionWitness {
+    /// Returns the snappy compressed RLP encoding of the witness, the format in which witnesses
+    /// are served to stateless clients.
+    pub fn encode_compressed(&self) -> Vec<u8> {
+        let mut rlp = Vec::with_capacity(self.length());
+        self.encode(&mut rlp);
+        snap::raw::Encoder::new().compress_vec(&rlp).expect("witness fits into a snappy block")
+    }
+
+    /// Decodes a witness from its snappy compressed RLP encoding, see [`Self::encode_compressed`].
+    pub fn decode_compressed(compressed: &[u8]) -> Result<Self, WitnessError> {
+        let rlp = snap::raw::Decoder::new().decompress_vec(compressed)?;
+        Self::decode(&mut rlp.as_slice()).map_err(WitnessError::InvalidEncoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_witness_roundtrip() {
+        let witness = ExecutionWitness {
+            state: vec![Bytes::from_static(&[0xc0]), Bytes::from_static(&[0x80; 64])],
+            codes: vec![Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3])],
+            headers: vec![Header { number: 1, ..Default::default() }, Header::default()],
+        };
+        let compressed = witness.encode_compressed();
+        assert_eq!(ExecutionWitness::decode_compressed(&compressed).unwrap(), witness);
+
+        assert!(matches!(
+            ExecutionWitness::decode_compressed(&compressed[1..]),
+            Err(WitnessError::InvalidCompression(_) | WitnessError::InvalidEncoding(_))
+        ));
+    }
+}
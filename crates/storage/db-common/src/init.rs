@@ -23,7 +23,7 @@ use reth_trie::{IntermediateStateRootState, StateRoot as StateRootComputer, Stat
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
-    io::BufRead,
+    io::{BufRead, Read},
     ops::DerefMut,
     sync::Arc,
 };
@@ -58,6 +58,13 @@ pub enum InitDatabaseError {
         /// Actual genesis hash.
         database_hash: B256,
     },
+    /// The genesis header has expired from the static files, and the genesis hash of the
+    /// chainspec is not the one of the database.
+    #[error("genesis header has expired and the database has no genesis block with the chainspec hash {chainspec_hash}")]
+    ExpiredGenesisHashMismatch {
+        /// Expected genesis hash.
+        chainspec_hash: B256,
+    },
     /// Provider error.
     #[error(transparent)]
     Provider(#[from] ProviderError),
@@ -100,6 +107,16 @@ pub fn init_genesis<DB: Database>(factory: ProviderFactory<DB>) -> Result<B256,
                 database_hash: block_hash,
             })
         }
+        // The static file with the genesis header was deleted by history expiry, so the genesis
+        // block is only known by its hash.
+        Err(ProviderError::ExpiredStaticFileBlock(StaticFileSegment::Headers, 0, _)) => {
+            if factory.provider()?.block_number(hash)? == Some(0) {
+                debug!("Genesis already written and expired, skipping.");
+                return Ok(hash)
+            }
+
+            return Err(InitDatabaseError::ExpiredGenesisHashMismatch { chainspec_hash: hash })
+        }
         Err(e) => return Err(dbg!(e).into()),
     }
 
@@ -322,6 +339,55 @@ pub fn init_from_state_dump<DB: Database>(
     // remaining lines are accounts
     let collector = parse_accounts(&mut reader, etl_config)?;
 
+    init_state_at_block(collector, expected_state_root, factory, block)?;
+
+    Ok(hash)
+}
+
+/// Reads account state from a reader in the format of `geth dump` and initializes it at the highest
+/// block that can be found on database.
+///
+/// Unlike [`init_from_state_dump`], the whole state dump is read into memory. Large states should
+/// be dumped with `geth dump --iterative`, which is read by [`init_from_state_dump`].
+pub fn init_from_geth_state_dump<DB: Database>(
+    reader: impl Read,
+    factory: ProviderFactory<DB>,
+    etl_config: EtlConfig,
+) -> eyre::Result<B256> {
+    let block = factory.last_block_number()?;
+    let hash = factory.block_hash(block)?.unwrap();
+
+    debug!(target: "reth::cli",
+        block,
+        chain=%factory.chain_spec().chain,
+        "Initializing state at block"
+    );
+
+    let GethStateDump { root, accounts } = serde_json::from_reader(reader)?;
+    trace!(target: "reth::cli",
+        %root,
+        accounts=accounts.len(),
+        "Read state dump from file"
+    );
+
+    let mut collector = Collector::new(etl_config.file_size, etl_config.dir);
+    for (address, account) in accounts {
+        collector.insert(address, account.genesis_account)?;
+    }
+
+    init_state_at_block(collector, root, factory, block)?;
+
+    Ok(hash)
+}
+
+/// Writes the accounts of the [`Collector`] as the state at the given block, verifies the state
+/// root, and sets the checkpoints of the stages that require state to the block.
+fn init_state_at_block<DB: Database>(
+    collector: Collector<Address, GenesisAccount>,
+    expected_state_root: B256,
+    factory: ProviderFactory<DB>,
+    block: u64,
+) -> eyre::Result<()> {
     // write state to db
     let mut provider_rw = factory.provider_rw()?;
     dump_state(collector, &mut provider_rw, block)?;
@@ -350,7 +416,7 @@ pub fn init_from_state_dump<DB: Database>(
 
     provider_rw.commit()?;
 
-    Ok(hash)
+    Ok(())
 }
 
 /// Parses and returns expected state root.
@@ -522,6 +588,24 @@ struct GenesisAccountWithAddress {
     address: Address,
 }
 
+/// A state dump in the format of `geth dump`, with the accounts keyed by their address.
+#[derive(Debug, Deserialize)]
+struct GethStateDump {
+    /// The state root.
+    root: B256,
+    /// The accounts of the state.
+    accounts: BTreeMap<Address, GethDumpAccount>,
+}
+
+/// An account of a `geth dump`. Fields that are not part of a [`GenesisAccount`], like the storage
+/// root and code hash, are ignored.
+#[derive(Debug, Deserialize)]
+struct GethDumpAccount {
+    /// The account's balance, nonce, code, and storage.
+    #[serde(flatten)]
+    genesis_account: GenesisAccount,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -534,7 +618,10 @@ mod tests {
         table::{Table, TableRow},
         transaction::DbTx,
     };
-    use reth_primitives::{GOERLI_GENESIS_HASH, MAINNET_GENESIS_HASH, SEPOLIA_GENESIS_HASH};
+    use reth_primitives::{
+        address, b256, bytes, static_file::BLOCKS_PER_STATIC_FILE, Header, GOERLI_GENESIS_HASH,
+        MAINNET_GENESIS_HASH, SEPOLIA_GENESIS_HASH,
+    };
     use reth_primitives_traits::IntegerList;
     use reth_provider::test_utils::create_test_provider_factory_with_chain_spec;
 
@@ -597,6 +684,52 @@ mod tests {
         )
     }
 
+    #[test]
+    fn init_genesis_after_history_expiry() {
+        let factory = create_test_provider_factory_with_chain_spec(SEPOLIA.clone());
+        init_genesis(factory.clone()).unwrap();
+
+        // Fill the first static file of headers, so that it can expire
+        let static_file_provider = factory.static_file_provider();
+        {
+            let mut writer =
+                static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+            for number in 1..=BLOCKS_PER_STATIC_FILE {
+                writer
+                    .append_header(Header { number, ..Default::default() }, U256::ZERO, B256::ZERO)
+                    .unwrap();
+            }
+            writer.commit().unwrap();
+        }
+        static_file_provider
+            .expire_jars_before(StaticFileSegment::Headers, BLOCKS_PER_STATIC_FILE)
+            .unwrap();
+
+        // Restart with a fresh static file provider that only finds the remaining static files
+        let db = factory.into_db();
+        let static_file_provider =
+            StaticFileProvider::read_write(static_file_provider.path()).unwrap();
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::Headers),
+            Some(BLOCKS_PER_STATIC_FILE)
+        );
+
+        let genesis_hash = init_genesis(ProviderFactory::new(
+            db.clone(),
+            SEPOLIA.clone(),
+            static_file_provider.clone(),
+        ));
+        assert_eq!(genesis_hash, Ok(SEPOLIA_GENESIS_HASH));
+
+        // A different chain is still detected
+        let genesis_hash =
+            init_genesis(ProviderFactory::new(db, MAINNET.clone(), static_file_provider));
+        assert_eq!(
+            genesis_hash.unwrap_err(),
+            InitDatabaseError::ExpiredGenesisHashMismatch { chainspec_hash: MAINNET_GENESIS_HASH }
+        );
+    }
+
     #[test]
     fn init_genesis_history() {
         let address_with_balance = Address::with_last_byte(1);
@@ -652,4 +785,40 @@ mod tests {
             )],
         );
     }
+
+    #[test]
+    fn parse_geth_state_dump() {
+        let dump = r#"{
+            "root": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+            "accounts": {
+                "0x000000000000000000000000000000000000dead": {
+                    "balance": "1000",
+                    "nonce": 1,
+                    "root": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+                    "codeHash": "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+                    "code": "0x6001",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": "02"
+                    },
+                    "address": "0x000000000000000000000000000000000000dead",
+                    "key": "0x0bd1a8e4ffd1d3a39f1a5f4bdd9bc5b0e6bbbd4ee2e8a3a4eb80e8f7cb8c1b14"
+                }
+            }
+        }"#;
+
+        let GethStateDump { root, accounts } = serde_json::from_str(dump).unwrap();
+        assert_eq!(root, b256!("d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544"));
+
+        let account = &accounts[&address!("000000000000000000000000000000000000dead")];
+        assert_eq!(
+            account.genesis_account,
+            GenesisAccount {
+                nonce: Some(1),
+                balance: U256::from(1000),
+                code: Some(bytes!("6001")),
+                storage: Some(BTreeMap::from([(B256::with_last_byte(1), B256::with_last_byte(2))])),
+                private_key: None,
+            }
+        );
+    }
 }
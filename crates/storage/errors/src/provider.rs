@@ -124,6 +124,14 @@ pub enum ProviderError {
     /// Static File is not found for requested transaction.
     #[error("unable to find {0} static file for transaction id {1}")]
     MissingStaticFileTx(StaticFileSegment, TxNumber),
+    /// Static File for requested block has expired and was deleted.
+    #[error("{0} static file for block number {1} has expired, lowest available block is {2}")]
+    ExpiredStaticFileBlock(StaticFileSegment, BlockNumber, BlockNumber),
+    /// Static File for requested transaction has expired and was deleted.
+    #[error(
+        "{0} static file for transaction id {1} has expired, lowest available transaction id is {2}"
+    )]
+    ExpiredStaticFileTx(StaticFileSegment, TxNumber, TxNumber),
     /// Static File is finalized and cannot be written to.
     #[error("unable to write block #{1} to finalized static file {0}")]
     FinalizedStaticFile(StaticFileSegment, BlockNumber),
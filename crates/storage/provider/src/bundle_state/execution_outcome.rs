@@ -5,9 +5,10 @@ use reth_db_api::{
     transaction::{DbTx, DbTxMut},
 };
 pub use reth_execution_types::*;
-use reth_primitives::StaticFileSegment;
+use reth_primitives::{BlockNumber, StaticFileSegment};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 pub use revm::db::states::OriginalValuesKnown;
+use revm::db::{states::reverts::Reverts, BundleState};
 
 impl StateWriter for ExecutionOutcome {
     fn write_to_storage<TX>(
@@ -19,26 +20,28 @@ impl StateWriter for ExecutionOutcome {
     where
         TX: DbTxMut + DbTx,
     {
-        let (plain_state, reverts) = self.bundle.into_plain_state_and_reverts(is_value_known);
+        let Self { mut bundle, receipts, first_block, .. } = self;
 
-        StateReverts(reverts).write_to_db(tx, self.first_block)?;
+        // reverts of wiped storage read the storage from the plain state, so they have to be
+        // written before the plain state
+        write_reverts(tx, &mut bundle, first_block)?;
 
-        // write receipts
+        // The receipts are written block by block, so that the written blocks are released while
+        // the rest of the range is written.
         let mut bodies_cursor = tx.cursor_read::<tables::BlockBodyIndices>()?;
         let mut receipts_cursor = tx.cursor_write::<tables::Receipts>()?;
 
-        // ATTENTION: Any potential future refactor or change to how this loop works should keep in
-        // mind that the static file producer must always call `increment_block` even if the block
-        // has no receipts. Keeping track of the exact block range of the segment is needed for
-        // consistency, querying and file range segmentation.
-        let blocks = self.receipts.into_iter().enumerate();
-        for (idx, receipts) in blocks {
-            let block_number = self.first_block + idx as u64;
+        for (block_number, receipts) in (first_block..).zip(receipts) {
             let first_tx_index = bodies_cursor
                 .seek_exact(block_number)?
                 .map(|(_, indices)| indices.first_tx_num())
                 .ok_or_else(|| ProviderError::BlockBodyIndicesNotFound(block_number))?;
 
+            // ATTENTION: Any potential future refactor or change to how receipts are written
+            // should keep in mind that the static file producer must always call
+            // `increment_block` even if the block has no receipts. Keeping track of the exact
+            // block range of the segment is needed for consistency, querying and file range
+            // segmentation.
             if let Some(static_file_producer) = &mut static_file_producer {
                 // Increment block on static file header.
                 static_file_producer.increment_block(StaticFileSegment::Receipts, block_number)?;
@@ -59,12 +62,30 @@ impl StateWriter for ExecutionOutcome {
             }
         }
 
-        StateChanges(plain_state).write_to_db(tx)?;
+        StateChanges(bundle.into_plain_state(is_value_known)).write_to_db(tx)?;
 
         Ok(())
     }
 }
 
+/// Writes the reverts of the bundle block by block, starting at `first_block`.
+///
+/// The reverts are drained from the bundle, so only the reverts of a single block are converted to
+/// plain reverts at a time, and the reverts of each written block are released while the rest of
+/// the range is written.
+fn write_reverts<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    bundle: &mut BundleState,
+    first_block: BlockNumber,
+) -> ProviderResult<()> {
+    bundle.reverts_size = 0;
+    for (block_number, block_reverts) in (first_block..).zip(bundle.reverts.drain(..)) {
+        StateReverts(Reverts::new(vec![block_reverts]).into_plain_state_reverts())
+            .write_to_db(tx, block_number)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,8 +154,13 @@ mod tests {
         state.merge_transitions(BundleRetention::Reverts);
         let mut revm_bundle_state = state.take_bundle();
 
-        // Write plain state and reverts separately.
-        let reverts = revm_bundle_state.take_all_reverts().into_plain_state_reverts();
+        // Write reverts and plain state separately.
+        let reverts = revm_bundle_state.reverts.clone().into_plain_state_reverts();
+        assert_eq!(reverts.storage, [[]]);
+        write_reverts(provider.tx_ref(), &mut revm_bundle_state, 1)
+            .expect("Could not write reverts to DB");
+        assert!(revm_bundle_state.reverts.is_empty());
+
         let plain_state = revm_bundle_state.into_plain_state(OriginalValuesKnown::Yes);
         assert!(plain_state.storage.is_empty());
         assert!(plain_state.contracts.is_empty());
@@ -142,11 +168,6 @@ mod tests {
             .write_to_db(provider.tx_ref())
             .expect("Could not write plain state to DB");
 
-        assert_eq!(reverts.storage, [[]]);
-        StateReverts(reverts)
-            .write_to_db(provider.tx_ref(), 1)
-            .expect("Could not write reverts to DB");
-
         let reth_account_a = account_a.into();
         let reth_account_b = account_b.into();
         let reth_account_b_changed = account_b_changed.clone().into();
@@ -195,8 +216,15 @@ mod tests {
         state.merge_transitions(BundleRetention::Reverts);
         let mut revm_bundle_state = state.take_bundle();
 
-        // Write plain state and reverts separately.
-        let reverts = revm_bundle_state.take_all_reverts().into_plain_state_reverts();
+        // Write reverts and plain state separately.
+        let reverts = revm_bundle_state.reverts.clone().into_plain_state_reverts();
+        assert_eq!(
+            reverts.storage,
+            [[PlainStorageRevert { address: address_b, wiped: true, storage_revert: vec![] }]]
+        );
+        write_reverts(provider.tx_ref(), &mut revm_bundle_state, 2)
+            .expect("Could not write reverts to DB");
+
         let plain_state = revm_bundle_state.into_plain_state(OriginalValuesKnown::Yes);
         // Account B selfdestructed so flag for it should be present.
         assert_eq!(
@@ -208,14 +236,6 @@ mod tests {
             .write_to_db(provider.tx_ref())
             .expect("Could not write plain state to DB");
 
-        assert_eq!(
-            reverts.storage,
-            [[PlainStorageRevert { address: address_b, wiped: true, storage_revert: vec![] }]]
-        );
-        StateReverts(reverts)
-            .write_to_db(provider.tx_ref(), 2)
-            .expect("Could not write reverts to DB");
-
         // Check new plain state for account B
         assert_eq!(
             provider.basic_account(address_b).expect("Could not read account state"),
@@ -863,6 +883,59 @@ mod tests {
         assert_eq!(this.receipts.len(), 7);
     }
 
+    #[test]
+    fn write_reverts_block_by_block() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+
+        let address = Address::random();
+        let info = |nonce| RevmAccountInfo { nonce, ..Default::default() };
+        let mut bundle = BundleState::builder(10..=12)
+            .revert_account_info(10, address, Some(None))
+            .revert_account_info(11, address, Some(Some(info(1))))
+            .revert_account_info(12, address, Some(Some(info(2))))
+            .revert_storage(11, address, vec![(U256::from(1), U256::from(2))])
+            .build();
+
+        write_reverts(provider.tx_ref(), &mut bundle, 10).expect("Could not write reverts to DB");
+        // the reverts of all blocks are flushed from the bundle
+        assert!(bundle.reverts.is_empty());
+        assert_eq!(bundle.reverts_size, 0);
+
+        // every block has its own changesets
+        let account_changesets = provider
+            .tx_ref()
+            .cursor_dup_read::<tables::AccountChangeSets>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            account_changesets,
+            vec![
+                (10, AccountBeforeTx { address, info: None }),
+                (11, AccountBeforeTx { address, info: Some(info(1).into()) }),
+                (12, AccountBeforeTx { address, info: Some(info(2).into()) }),
+            ]
+        );
+        let storage_changesets = provider
+            .tx_ref()
+            .cursor_dup_read::<tables::StorageChangeSets>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            storage_changesets,
+            vec![(
+                BlockNumberAddress((11, address)),
+                StorageEntry { key: B256::from(U256::from(1)), value: U256::from(2) }
+            )]
+        );
+    }
+
     #[test]
     fn bundle_state_state_root() {
         type PreState = BTreeMap<Address, (Account, BTreeMap<B256, U256>)>;
@@ -107,6 +107,12 @@ pub struct StaticFileProviderInner {
     map: DashMap<(BlockNumber, StaticFileSegment), LoadedJar>,
     /// Max static file block for each segment
     static_files_max_block: RwLock<HashMap<StaticFileSegment, u64>>,
+    /// Min static file block for each segment. Blocks below it have expired, see
+    /// [`StaticFileProvider::expire_jars_before`].
+    static_files_min_block: RwLock<HashMap<StaticFileSegment, u64>>,
+    /// Min static file transaction for each segment. Transactions below it have expired, see
+    /// [`StaticFileProvider::expire_jars_before`].
+    static_files_min_tx: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Available static file block ranges on disk indexed by max transactions.
     static_files_tx_index: RwLock<SegmentRanges>,
     /// Directory where `static_files` are located
@@ -136,6 +142,8 @@ impl StaticFileProviderInner {
             map: Default::default(),
             writers: Default::default(),
             static_files_max_block: Default::default(),
+            static_files_min_block: Default::default(),
+            static_files_min_tx: Default::default(),
             static_files_tx_index: Default::default(),
             path: path.as_ref().to_path_buf(),
             load_filters: false,
@@ -216,6 +224,12 @@ impl StaticFileProvider {
         block: BlockNumber,
         path: Option<&Path>,
     ) -> ProviderResult<StaticFileJarProvider<'_>> {
+        if let Some(lowest_block) =
+            self.get_lowest_static_file_block(segment).filter(|lowest_block| block < *lowest_block)
+        {
+            return Err(ProviderError::ExpiredStaticFileBlock(segment, block, lowest_block))
+        }
+
         self.get_segment_provider(
             segment,
             || self.get_segment_ranges_from_block(segment, block),
@@ -231,6 +245,12 @@ impl StaticFileProvider {
         tx: TxNumber,
         path: Option<&Path>,
     ) -> ProviderResult<StaticFileJarProvider<'_>> {
+        if let Some(lowest_tx) =
+            self.get_lowest_static_file_tx(segment).filter(|lowest_tx| tx < *lowest_tx)
+        {
+            return Err(ProviderError::ExpiredStaticFileTx(segment, tx, lowest_tx))
+        }
+
         self.get_segment_provider(
             segment,
             || self.get_segment_ranges_from_transaction(segment, tx),
@@ -318,6 +338,51 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Deletes all jars of the segment whose block range ends before `block`, so that the segment
+    /// starts at the first remaining jar. The jar with the highest block is never deleted.
+    ///
+    /// Returns the fixed block ranges of the deleted jars.
+    ///
+    /// CAUTION: destructive. Deletes files on disk.
+    pub fn expire_jars_before(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<SegmentRangeInclusive>> {
+        let Some(highest_block) = self.get_highest_static_file_block(segment) else {
+            return Ok(Vec::new())
+        };
+        let highest_range = find_fixed_range(highest_block);
+
+        let mut expired = Vec::new();
+        let mut range =
+            find_fixed_range(self.get_lowest_static_file_block(segment).unwrap_or_default());
+        while range.end() < block && range.start() < highest_range.start() {
+            let jar = if let Some((_, jar)) = self.map.remove(&(range.end(), segment)) {
+                jar.jar
+            } else {
+                NippyJar::<SegmentHeader>::load(&self.path.join(segment.filename(&range)))
+                    .map_err(|e| ProviderError::NippyJar(e.to_string()))?
+            };
+
+            // Make the jar unavailable before deleting its files
+            self.static_files_min_block.write().insert(segment, range.end() + 1);
+            if let Some(tx_range) = jar.user_header().tx_range() {
+                self.static_files_min_tx.write().insert(segment, tx_range.end() + 1);
+                if let Some(index) = self.static_files_tx_index.write().get_mut(&segment) {
+                    index.remove(&tx_range.end());
+                }
+            }
+
+            jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+            expired.push(range);
+
+            range = find_fixed_range(range.end() + 1);
+        }
+
+        Ok(expired)
+    }
+
     /// Given a segment and block range it returns a cached
     /// [`StaticFileJarProvider`]. TODO(joshie): we should check the size and pop N if there's too
     /// many.
@@ -462,6 +527,8 @@ impl StaticFileProvider {
             None => {
                 tx_index.remove(&segment);
                 max_block.remove(&segment);
+                self.static_files_min_block.write().remove(&segment);
+                self.static_files_min_tx.write().remove(&segment);
             }
         };
 
@@ -471,9 +538,13 @@ impl StaticFileProvider {
     /// Initializes the inner transaction and block index
     pub fn initialize_index(&self) -> ProviderResult<()> {
         let mut max_block = self.static_files_max_block.write();
+        let mut min_block = self.static_files_min_block.write();
+        let mut min_tx = self.static_files_min_tx.write();
         let mut tx_index = self.static_files_tx_index.write();
 
         tx_index.clear();
+        min_block.clear();
+        min_tx.clear();
 
         for (segment, ranges) in
             iter_static_files(&self.path).map_err(|e| ProviderError::NippyJar(e.to_string()))?
@@ -483,6 +554,15 @@ impl StaticFileProvider {
                 max_block.insert(segment, block_range.end());
             }
 
+            // Update first block and transaction for each segment, which are only above zero if
+            // older jars have expired
+            if let Some((block_range, _)) = ranges.first() {
+                min_block.insert(segment, block_range.start());
+            }
+            if let Some(tx_range) = ranges.iter().find_map(|(_, tx_range)| tx_range.as_ref()) {
+                min_tx.insert(segment, tx_range.start());
+            }
+
             // Update tx -> block_range index
             for (block_range, tx_range) in ranges {
                 if let Some(tx_range) = tx_range {
@@ -740,6 +820,18 @@ impl StaticFileProvider {
         self.static_files_max_block.read().get(&segment).copied()
     }
 
+    /// Gets the lowest static file block if it exists for a static file segment. Blocks below it
+    /// have expired.
+    pub fn get_lowest_static_file_block(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
+        self.static_files_min_block.read().get(&segment).copied()
+    }
+
+    /// Gets the lowest static file transaction if it exists for a static file segment.
+    /// Transactions below it have expired.
+    pub fn get_lowest_static_file_tx(&self, segment: StaticFileSegment) -> Option<TxNumber> {
+        self.static_files_min_tx.read().get(&segment).copied()
+    }
+
     /// Gets the highest static file transaction.
     pub fn get_highest_static_file_tx(&self, segment: StaticFileSegment) -> Option<TxNumber> {
         self.static_files_tx_index
@@ -765,8 +857,9 @@ impl StaticFileProvider {
         func: impl Fn(StaticFileJarProvider<'_>) -> ProviderResult<Option<T>>,
     ) -> ProviderResult<Option<T>> {
         if let Some(highest_block) = self.get_highest_static_file_block(segment) {
+            let lowest_block = self.get_lowest_static_file_block(segment).unwrap_or_default();
             let mut range = find_fixed_range(highest_block);
-            while range.end() > 0 {
+            while range.end() > 0 && range.start() >= lowest_block {
                 if let Some(res) = func(self.get_or_create_jar_provider(segment, &range)?)? {
                     return Ok(Some(res))
                 }
@@ -83,6 +83,15 @@ pub trait BlockIdReader: BlockNumReader + Send + Sync {
                 BlockNumberOrTag::Pending => self
                     .pending_block_num_hash()
                     .map(|res_opt| res_opt.map(|num_hash| num_hash.hash)),
+                // the tracked hashes are used directly, the blocks might not be persisted yet
+                BlockNumberOrTag::Finalized => match self.finalized_block_hash()? {
+                    Some(hash) => Ok(Some(hash)),
+                    None => Err(ProviderError::FinalizedBlockNotFound),
+                },
+                BlockNumberOrTag::Safe => match self.safe_block_hash()? {
+                    Some(hash) => Ok(Some(hash)),
+                    None => Err(ProviderError::SafeBlockNotFound),
+                },
                 _ => self
                     .convert_block_number(num)?
                     .map(|num| self.block_hash(num))
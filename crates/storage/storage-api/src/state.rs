@@ -1,6 +1,6 @@
 use super::{AccountReader, BlockHashReader, BlockIdReader, StateRootProvider};
 use auto_impl::auto_impl;
-use reth_execution_types::ExecutionOutcome;
+use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_primitives::{
     Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, Bytecode, StorageKey,
     StorageValue, B256, KECCAK_EMPTY, U256,
@@ -214,7 +214,7 @@ pub trait BlockchainTreePendingStateProvider: Send + Sync {
 /// * [`ExecutionOutcome`] - Captures all account and storage changes in the pending chain.
 /// * Block hashes - Provides access to the block hashes of both the pending chain and canonical
 ///   blocks.
-#[auto_impl(&, Box)]
+#[auto_impl(&, Arc, Box)]
 pub trait ExecutionDataProvider: Send + Sync {
     /// Return the execution outcome.
     fn execution_outcome(&self) -> &ExecutionOutcome;
@@ -233,6 +233,17 @@ impl ExecutionDataProvider for ExecutionOutcome {
     }
 }
 
+impl ExecutionDataProvider for Chain {
+    fn execution_outcome(&self) -> &ExecutionOutcome {
+        Self::execution_outcome(self)
+    }
+
+    /// Returns the hashes of the blocks in the chain, and [None] for all other blocks.
+    fn block_hash(&self, block_number: BlockNumber) -> Option<BlockHash> {
+        self.blocks().get(&block_number).map(|block| block.hash())
+    }
+}
+
 /// Fork data needed for execution on it.
 ///
 /// It contains a canonical fork, the block on what pending chain was forked from.
@@ -13,6 +13,11 @@ pub enum LogFormat {
     /// Represents JSON formatting for logs.
     /// This format outputs log records as JSON objects,
     /// making it suitable for structured logging.
+    ///
+    /// Every record is a single line with the stable top-level fields `timestamp`, `level`,
+    /// `target` and `message`, followed by the fields of the event. If the event was emitted
+    /// inside a span, the current span is included as `span` and all entered spans, from the root
+    /// to the current one, as `spans`.
     Json,
 
     /// Represents logfmt (key=value) formatting for logs.
@@ -58,8 +63,15 @@ impl LogFormat {
 
         match self {
             Self::Json => {
-                let layer =
-                    tracing_subscriber::fmt::layer().json().with_ansi(ansi).with_target(target);
+                // The JSON output is meant to be consumed by log processors, so the set of fields
+                // is fixed and doesn't depend on the terminal settings.
+                let layer = tracing_subscriber::fmt::layer()
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .with_ansi(false)
+                    .with_target(true);
 
                 if let Some(writer) = file_writer {
                     layer.with_writer(writer).with_filter(filter).boxed()
@@ -86,6 +86,51 @@ impl Layers {
         Ok(())
     }
 
+    /// Adds a layer exporting spans to an OpenTelemetry collector to the layers collection.
+    ///
+    /// The spans are exported by a batch processor running on a dedicated thread, so this doesn't
+    /// require a Tokio runtime.
+    ///
+    /// # Arguments
+    /// * `otlp_info` - The endpoint, service name and filter of the exported spans.
+    ///
+    /// # Returns
+    /// An `eyre::Result<()>` indicating the success or failure of the operation. Fails if reth was
+    /// built without the `otlp` feature.
+    #[cfg_attr(not(feature = "otlp"), allow(clippy::needless_pass_by_ref_mut))]
+    pub(crate) fn otlp(&mut self, otlp_info: OtlpInfo) -> eyre::Result<()> {
+        #[cfg(feature = "otlp")]
+        {
+            use opentelemetry::KeyValue;
+            use opentelemetry_otlp::WithExportConfig;
+            use opentelemetry_sdk::{runtime, trace, Resource};
+
+            let otlp_filter = build_env_filter(None, &otlp_info.filter)?;
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter().http().with_endpoint(otlp_info.endpoint),
+                )
+                .with_trace_config(trace::config().with_resource(Resource::new([KeyValue::new(
+                    "service.name",
+                    otlp_info.service_name,
+                )])))
+                .install_batch(runtime::TokioCurrentThread)?;
+            let layer =
+                tracing_opentelemetry::layer().with_tracer(tracer).with_filter(otlp_filter).boxed();
+            self.inner.push(layer);
+            Ok(())
+        }
+
+        #[cfg(not(feature = "otlp"))]
+        {
+            let _ = otlp_info;
+            eyre::bail!(
+                "exporting spans over OTLP requires reth to be built with the `otlp` feature"
+            )
+        }
+    }
+
     /// Adds a file logging layer to the layers collection.
     ///
     /// # Arguments
@@ -109,6 +154,30 @@ impl Layers {
     }
 }
 
+/// Holds configuration information for the export of spans to an OpenTelemetry collector.
+///
+/// Spans are exported in batches over OTLP/HTTP. Exporting requires reth to be built with the
+/// `otlp` feature.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "otlp"), allow(dead_code))]
+pub struct OtlpInfo {
+    endpoint: String,
+    service_name: String,
+    filter: String,
+}
+
+impl OtlpInfo {
+    /// Creates a new `OtlpInfo` instance.
+    ///
+    /// # Arguments
+    /// * `endpoint` - The endpoint of the OTLP/HTTP receiver of the collector.
+    /// * `service_name` - The `service.name` resource attribute of the exported spans.
+    /// * `filter` - Filter directives for the exported spans.
+    pub const fn new(endpoint: String, service_name: String, filter: String) -> Self {
+        Self { endpoint, service_name, filter }
+    }
+}
+
 /// Holds configuration information for file logging.
 ///
 /// Contains details about the log file's path, name, size, and rotation strategy.
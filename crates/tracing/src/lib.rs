@@ -1,9 +1,9 @@
 //!  The `tracing` module provides functionalities for setting up and configuring logging.
 //!
 //!  It includes structures and functions to create and manage various logging layers: stdout,
-//!  file, or journald. The module's primary entry point is the `Tracer` struct, which can be
-//!  configured to use different logging formats and destinations. If no layer is specified, it will
-//!  default to stdout.
+//!  file, or journald, and optionally exports spans to an OpenTelemetry collector. The module's
+//! primary entry point is the `Tracer` struct, which can be  configured to use different logging
+//! formats and destinations. If no layer is specified, it will  default to stdout.
 //!
 //!  # Examples
 //!
@@ -49,7 +49,7 @@ pub use tracing_subscriber;
 
 // Re-export LogFormat
 pub use formatter::LogFormat;
-pub use layers::{FileInfo, FileWorkerGuard};
+pub use layers::{FileInfo, FileWorkerGuard, OtlpInfo};
 
 pub use test_tracer::TestTracer;
 
@@ -71,15 +71,16 @@ pub struct RethTracer {
     stdout: LayerInfo,
     journald: Option<String>,
     file: Option<(LayerInfo, FileInfo)>,
+    otlp: Option<OtlpInfo>,
 }
 
 impl RethTracer {
     ///  Constructs a new `Tracer` with default settings.
     ///
     ///  Initializes with default stdout layer configuration.
-    ///  Journald and file layers are not set by default.
+    ///  Journald, file and OTLP layers are not set by default.
     pub fn new() -> Self {
-        Self { stdout: LayerInfo::default(), journald: None, file: None }
+        Self { stdout: LayerInfo::default(), journald: None, file: None, otlp: None }
     }
 
     ///  Sets a custom configuration for the stdout layer.
@@ -109,6 +110,15 @@ impl RethTracer {
         self.file = Some((config, file_info));
         self
     }
+
+    ///  Sets the OTLP layer configuration, exporting spans to an OpenTelemetry collector.
+    ///
+    ///  # Arguments
+    ///  * `otlp_info` - The `OtlpInfo` containing the collector endpoint and span filter.
+    pub fn with_otlp(mut self, otlp_info: OtlpInfo) -> Self {
+        self.otlp = Some(otlp_info);
+        self
+    }
 }
 
 impl Default for RethTracer {
@@ -183,7 +193,7 @@ impl Tracer for RethTracer {
     ///  Initializes the logging system based on the configured layers.
     ///
     ///  This method sets up the global tracing subscriber with the specified
-    ///  stdout, journald, and file layers.
+    ///  stdout, journald, file, and OTLP layers.
     ///
     ///  The default layer is stdout.
     ///
@@ -210,6 +220,10 @@ impl Tracer for RethTracer {
             None
         };
 
+        if let Some(otlp_info) = self.otlp {
+            layers.otlp(otlp_info)?;
+        }
+
         // The error is returned if the global default subscriber is already set,
         // so it's safe to ignore it
         let _ = tracing_subscriber::registry().with(layers.into_inner()).try_init();
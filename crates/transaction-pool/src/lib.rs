@@ -184,6 +184,7 @@ pub mod maintain;
 pub mod metrics;
 pub mod noop;
 pub mod pool;
+pub mod prewarm;
 pub mod validate;
 
 pub mod blobstore;
@@ -0,0 +1,208 @@
+//! Support for pre-loading the state referenced by new transactions of the pool.
+//!
+//! Transactions that enter the pool are likely to be included in one of the next blocks. Loading
+//! the accounts and storage slots they reference ahead of time, while the node is otherwise idle,
+//! moves the database reads out of the critical path of block validation.
+
+use crate::{traits::TransactionPool, PoolTransaction, ValidPoolTransaction};
+use futures_util::{future::BoxFuture, FutureExt, StreamExt};
+use reth_primitives::{B256, KECCAK_EMPTY, U256};
+use reth_provider::{ProviderResult, StateProviderFactory};
+use reth_revm::{
+    cached::{CachedReads, SharedCachedReads},
+    database::StateProviderDatabase,
+};
+use reth_tasks::TaskSpawner;
+use revm::Database;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use tracing::{debug, trace};
+
+/// Maximum number of new transactions whose state is loaded at once.
+pub const MAX_PREWARM_BATCH_SIZE: usize = 256;
+
+/// Returns a spawnable future for [`prewarm_state_cache`].
+pub fn prewarm_state_cache_future<Client, P, Tasks>(
+    client: Client,
+    pool: P,
+    cache: SharedCachedReads,
+    task_spawner: Tasks,
+) -> BoxFuture<'static, ()>
+where
+    Client: StateProviderFactory + Clone + Send + 'static,
+    P: TransactionPool + 'static,
+    Tasks: TaskSpawner + 'static,
+{
+    async move {
+        prewarm_state_cache(client, pool, cache, task_spawner).await;
+    }
+    .boxed()
+}
+
+/// Pre-loads the state referenced by new pending transactions of the pool into the shared cache.
+///
+/// For every transaction, this loads the account of the sender, the account and code of the
+/// recipient, and the storage slots of the access list. The reads are cached for the block the
+/// pool currently validates transactions against, so they're reused when a block on top of it is
+/// executed, see [`SharedCachedReads`].
+pub async fn prewarm_state_cache<Client, P, Tasks>(
+    client: Client,
+    pool: P,
+    cache: SharedCachedReads,
+    task_spawner: Tasks,
+) where
+    Client: StateProviderFactory + Clone + Send + 'static,
+    P: TransactionPool + 'static,
+    Tasks: TaskSpawner + 'static,
+{
+    let mut new_transactions = pool.new_pending_pool_transactions_listener();
+
+    while let Some(event) = new_transactions.next().await {
+        let mut transactions = vec![event.transaction];
+        while transactions.len() < MAX_PREWARM_BATCH_SIZE {
+            let Ok(event) = new_transactions.try_recv() else { break };
+            transactions.push(event.transaction);
+        }
+
+        let block_hash = pool.block_info().last_seen_block_hash;
+        let (tx, rx) = oneshot::channel();
+        let (client, cache) = (client.clone(), cache.clone());
+        task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(prewarm_transactions(&client, block_hash, &transactions, &cache));
+        }));
+
+        match rx.await {
+            Ok(Ok(count)) => {
+                trace!(target: "txpool", %block_hash, count, "prewarmed state of new transactions")
+            }
+            Ok(Err(err)) => {
+                debug!(target: "txpool", %block_hash, %err, "failed to prewarm state of new transactions")
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Loads the state referenced by the transactions at the given block into the shared cache.
+///
+/// Returns the number of transactions whose state was loaded.
+fn prewarm_transactions<Client, T>(
+    client: &Client,
+    block_hash: B256,
+    transactions: &[Arc<ValidPoolTransaction<T>>],
+    cache: &SharedCachedReads,
+) -> ProviderResult<usize>
+where
+    Client: StateProviderFactory,
+    T: PoolTransaction,
+{
+    let state = client.state_by_block_hash(block_hash)?;
+    let mut cached_reads = CachedReads::default();
+    let mut db = cached_reads.as_db_mut(StateProviderDatabase::new(state));
+
+    for tx in transactions {
+        db.basic(tx.sender())?;
+
+        if let Some(to) = tx.transaction.to() {
+            if let Some(info) = db.basic(to)? {
+                if info.code_hash != KECCAK_EMPTY {
+                    db.code_by_hash(info.code_hash)?;
+                }
+            }
+        }
+
+        if let Some(access_list) = tx.transaction.access_list() {
+            for item in access_list.iter() {
+                for key in &item.storage_keys {
+                    db.storage(item.address, U256::from_be_bytes(key.0))?;
+                }
+            }
+        }
+    }
+
+    cache.insert(block_hash, cached_reads);
+    Ok(transactions.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{testing_pool, MockTransaction, MockTransactionFactory},
+        TransactionOrigin,
+    };
+    use reth_primitives::{keccak256, AccessList, AccessListItem, Address, Bytes};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_tasks::TokioTaskExecutor;
+    use std::time::Duration;
+
+    #[test]
+    fn prewarm_transaction_state() {
+        let storage_address = Address::random();
+        let storage_key = B256::with_last_byte(1);
+        let mut transaction = MockTransaction::eip1559();
+        transaction.set_accesslist(AccessList(vec![AccessListItem {
+            address: storage_address,
+            storage_keys: vec![storage_key],
+        }]));
+        let (sender, recipient) = (transaction.sender(), transaction.to().unwrap());
+
+        let code = Bytes::from_static(&[0x00]);
+        let client = MockEthProvider::default();
+        client.add_account(sender, ExtendedAccount::new(0, U256::from(100)));
+        client.add_account(
+            recipient,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(code.clone()),
+        );
+        client.add_account(
+            storage_address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(storage_key, U256::from(7))]),
+        );
+
+        let cache = SharedCachedReads::new(1);
+        let block_hash = B256::random();
+        let transactions = [MockTransactionFactory::default().validated_arc(transaction)];
+        assert_eq!(prewarm_transactions(&client, block_hash, &transactions, &cache).unwrap(), 1);
+
+        // the state is read from the cache, not from the empty database
+        let mut cached_reads = cache.get(block_hash).unwrap();
+        let mut db = cached_reads.as_db_mut(StateProviderDatabase::new(MockEthProvider::default()));
+        assert_eq!(db.basic(sender).unwrap().unwrap().balance, U256::from(100));
+        let code_hash = db.basic(recipient).unwrap().unwrap().code_hash;
+        assert_eq!(code_hash, keccak256(&code));
+        assert_eq!(db.code_by_hash(code_hash).unwrap().original_bytes(), code);
+        assert_eq!(db.storage(storage_address, U256::from(1)).unwrap(), U256::from(7));
+    }
+
+    #[tokio::test]
+    async fn prewarm_new_pending_transactions() {
+        let pool = testing_pool();
+        let client = MockEthProvider::default();
+        let transaction = MockTransaction::eip1559();
+        let sender = transaction.sender();
+        client.add_account(sender, ExtendedAccount::new(0, U256::from(100)));
+
+        let cache = SharedCachedReads::new(1);
+        tokio::spawn(prewarm_state_cache(
+            client,
+            pool.clone(),
+            cache.clone(),
+            TokioTaskExecutor::default(),
+        ));
+        // let the task subscribe to new pending transactions
+        tokio::task::yield_now().await;
+
+        pool.add_transaction(TransactionOrigin::External, transaction).await.unwrap();
+
+        // the state is cached for the block the pool validates transactions against
+        let block_hash = pool.block_info().last_seen_block_hash;
+        let mut cached_reads = loop {
+            if let Some(cached_reads) = cache.get(block_hash) {
+                break cached_reads
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        let mut db = cached_reads.as_db_mut(StateProviderDatabase::new(MockEthProvider::default()));
+        assert_eq!(db.basic(sender).unwrap().unwrap().balance, U256::from(100));
+    }
+}
@@ -0,0 +1,103 @@
+#![allow(missing_docs, unreachable_pub)]
+//! Replays hashed state changes recorded with `reth debug trie-bench record` through the state
+//! root implementations on top of a synced mainnet database.
+//!
+//! The benchmark is skipped unless `RETH_BENCH_DATADIR` points to the datadir of the database and
+//! `RETH_BENCH_STATES` to the file of recorded states.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rayon::ThreadPoolBuilder;
+use reth_chainspec::MAINNET;
+use reth_db::open_db_read_only;
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    providers::{ConsistentDbView, StaticFileProvider},
+    ProviderFactory,
+};
+use reth_tasks::pool::BlockingTaskPool;
+use reth_trie::{hashed_cursor::HashedPostStateCursorFactory, HashedPostState, StateRoot};
+use reth_trie_parallel::{async_root::AsyncStateRoot, parallel_root::ParallelStateRoot};
+use serde::Deserialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// The hashed state changes of a block, as recorded by `reth debug trie-bench record`.
+#[derive(Deserialize)]
+struct RecordedState {
+    block_number: BlockNumber,
+    state: HashedPostState,
+}
+
+pub fn replay_state_root(c: &mut Criterion) {
+    let (Ok(datadir), Ok(states)) =
+        (std::env::var("RETH_BENCH_DATADIR"), std::env::var("RETH_BENCH_STATES"))
+    else {
+        eprintln!(
+            "RETH_BENCH_DATADIR and RETH_BENCH_STATES are not set, skipping replay benchmark"
+        );
+        return
+    };
+    let datadir = PathBuf::from(datadir);
+    let db = Arc::new(open_db_read_only(&datadir.join("db"), Default::default()).unwrap());
+    let provider_factory = ProviderFactory::new(
+        db,
+        MAINNET.clone(),
+        StaticFileProvider::read_only(datadir.join("static_files")).unwrap(),
+    );
+    let states = BufReader::new(File::open(states).unwrap())
+        .lines()
+        .map(|line| serde_json::from_str::<RecordedState>(&line.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("Replay State Root");
+    group.sample_size(10);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let blocking_pool = BlockingTaskPool::new(ThreadPoolBuilder::default().build().unwrap());
+    let view = ConsistentDbView::new_with_latest_tip(provider_factory.clone()).unwrap();
+
+    for RecordedState { block_number, state } in &states {
+        // incremental root
+        group.bench_function(BenchmarkId::new("sync root", block_number), |b| {
+            b.iter_with_setup(
+                || {
+                    let sorted_state = state.clone().into_sorted();
+                    let prefix_sets = state.construct_prefix_sets();
+                    let provider = provider_factory.provider().unwrap();
+                    (provider, sorted_state, prefix_sets)
+                },
+                |(provider, sorted_state, prefix_sets)| {
+                    StateRoot::from_tx(provider.tx_ref())
+                        .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                            provider.tx_ref(),
+                            &sorted_state,
+                        ))
+                        .with_prefix_sets(prefix_sets)
+                        .root()
+                },
+            )
+        });
+
+        // parallel root
+        group.bench_function(BenchmarkId::new("parallel root", block_number), |b| {
+            b.iter_with_setup(
+                || ParallelStateRoot::new(view.clone(), state.clone()),
+                |calculator| calculator.incremental_root(),
+            );
+        });
+
+        // async root
+        group.bench_function(BenchmarkId::new("async root", block_number), |b| {
+            b.to_async(&runtime).iter_with_setup(
+                || AsyncStateRoot::new(view.clone(), blocking_pool.clone(), state.clone()),
+                |calculator| calculator.incremental_root(),
+            );
+        });
+    }
+}
+
+criterion_group!(replay, replay_state_root);
+criterion_main!(replay);
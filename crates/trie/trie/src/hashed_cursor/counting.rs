@@ -0,0 +1,74 @@
+use super::{HashedCursor, HashedCursorFactory, HashedStorageCursor};
+use reth_db::DatabaseError;
+use reth_primitives::B256;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// The hashed cursor factory that counts the entries read by the cursors of the inner factory.
+///
+/// The count is shared by all clones of the factory and all cursors created by them.
+#[derive(Debug, Clone)]
+pub struct CountingHashedCursorFactory<CF> {
+    cursor_factory: CF,
+    reads: Arc<AtomicU64>,
+}
+
+impl<CF> CountingHashedCursorFactory<CF> {
+    /// Create a new factory.
+    pub fn new(cursor_factory: CF) -> Self {
+        Self { cursor_factory, reads: Arc::default() }
+    }
+
+    /// Returns the number of hashed entries read so far.
+    pub fn reads(&self) -> u64 {
+        self.reads.load(Ordering::Relaxed)
+    }
+}
+
+impl<CF: HashedCursorFactory> HashedCursorFactory for CountingHashedCursorFactory<CF> {
+    type AccountCursor = CountingHashedCursor<CF::AccountCursor>;
+    type StorageCursor = CountingHashedCursor<CF::StorageCursor>;
+
+    fn hashed_account_cursor(&self) -> Result<Self::AccountCursor, DatabaseError> {
+        let cursor = self.cursor_factory.hashed_account_cursor()?;
+        Ok(CountingHashedCursor { cursor, reads: self.reads.clone() })
+    }
+
+    fn hashed_storage_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Self::StorageCursor, DatabaseError> {
+        let cursor = self.cursor_factory.hashed_storage_cursor(hashed_address)?;
+        Ok(CountingHashedCursor { cursor, reads: self.reads.clone() })
+    }
+}
+
+/// The hashed cursor that counts the entries read by the inner cursor.
+#[derive(Debug)]
+pub struct CountingHashedCursor<C> {
+    cursor: C,
+    reads: Arc<AtomicU64>,
+}
+
+impl<C: HashedCursor> HashedCursor for CountingHashedCursor<C> {
+    type Value = C::Value;
+
+    fn seek(&mut self, key: B256) -> Result<Option<(B256, Self::Value)>, DatabaseError> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.cursor.seek(key)
+    }
+
+    fn next(&mut self) -> Result<Option<(B256, Self::Value)>, DatabaseError> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.cursor.next()
+    }
+}
+
+impl<C: HashedStorageCursor> HashedStorageCursor for CountingHashedCursor<C> {
+    fn is_storage_empty(&mut self) -> Result<bool, DatabaseError> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.cursor.is_storage_empty()
+    }
+}
@@ -8,6 +8,10 @@ pub use default::DatabaseHashedStorageCursor;
 mod post_state;
 pub use post_state::*;
 
+/// Implementation of hashed state cursor traits counting the reads of another implementation.
+mod counting;
+pub use counting::{CountingHashedCursor, CountingHashedCursorFactory};
+
 /// The factory trait for creating cursors over the hashed state.
 pub trait HashedCursorFactory {
     /// The hashed account cursor type.
@@ -1,7 +1,7 @@
 use crate::{
     hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
     node_iter::{TrieElement, TrieNodeIter},
-    prefix_set::PrefixSetMut,
+    prefix_set::{PrefixSetMut, TriePrefixSets},
     trie_cursor::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
     walker::TrieWalker,
     HashBuilder, Nibbles,
@@ -17,18 +17,37 @@ use reth_trie_common::{proof::ProofRetainer, AccountProof, StorageProof, TrieAcc
 /// Proof generator adds the target address and slots to the prefix set, enables the proof retainer
 /// on the hash builder and follows the same algorithm as the state root calculator.
 /// See `StateRoot::root` for more info.
+///
+/// Like the state root calculator, proofs of a state that differs from the database, e.g. a
+/// historical state, can be generated by overlaying the changes with the hashed cursor factory and
+/// adding their keys to the prefix sets.
 #[derive(Debug)]
 pub struct Proof<'a, TX, H> {
     /// A reference to the database transaction.
     tx: &'a TX,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
+    /// A set of prefix sets that have changes.
+    prefix_sets: TriePrefixSets,
 }
 
 impl<'a, TX> Proof<'a, TX, &'a TX> {
     /// Create a new [Proof] instance.
-    pub const fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+    pub fn new(tx: &'a TX) -> Self {
+        Self { tx, hashed_cursor_factory: tx, prefix_sets: TriePrefixSets::default() }
+    }
+}
+
+impl<'a, TX, H> Proof<'a, TX, H> {
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<'a, TX, HF> {
+        Proof { tx: self.tx, hashed_cursor_factory, prefix_sets: self.prefix_sets }
+    }
+
+    /// Set the prefix sets of the changes of the hashed cursor factory.
+    pub fn with_prefix_sets(mut self, prefix_sets: TriePrefixSets) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
     }
 }
 
@@ -52,7 +71,8 @@ where
             DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
 
         // Create the walker.
-        let mut prefix_set = PrefixSetMut::default();
+        let mut prefix_set =
+            PrefixSetMut::from(self.prefix_sets.account_prefix_set.iter().cloned());
         prefix_set.insert(target_nibbles.clone());
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
@@ -117,7 +137,13 @@ where
         }
 
         let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
-        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let mut prefix_set = PrefixSetMut::from(target_nibbles.clone());
+        if let Some(changed) = self.prefix_sets.storage_prefix_sets.get(&hashed_address) {
+            for nibbles in changed.iter() {
+                prefix_set.insert(nibbles.clone());
+            }
+        }
+        let prefix_set = prefix_set.freeze();
         let trie_cursor = DatabaseStorageTrieCursor::new(
             self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
             hashed_address,
@@ -162,7 +188,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StateRoot;
+    use crate::{HashedPostState, HashedStorage, StateRoot};
     use once_cell::sync::Lazy;
     use reth_chainspec::{Chain, ChainSpec, HOLESKY, MAINNET};
     use reth_db_api::database::Database;
@@ -446,4 +472,39 @@ mod tests {
         similar_asserts::assert_eq!(account_proof, expected);
         assert_eq!(account_proof.verify(root), Ok(()));
     }
+
+    #[test]
+    fn overlay_account_proof() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let target = Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap();
+        let changed = Address::from_str("0x62b0dd4aab2b1a0a04e279e2b828791a10755528").unwrap();
+        let slots = Vec::from([B256::with_last_byte(1)]);
+        let post_state = HashedPostState::default()
+            .with_accounts([(
+                keccak256(changed),
+                Some(Account { nonce: 1, balance: U256::from(1), bytecode_hash: None }),
+            )])
+            .with_storages([(
+                keccak256(target),
+                HashedStorage::from_iter(false, [(keccak256(slots[0]), U256::from(2))]),
+            )]);
+
+        // The proofs of the overlaid state verify against its state root.
+        let provider = factory.provider().unwrap();
+        let root = post_state.state_root(provider.tx_ref()).unwrap();
+        for address in [target, changed] {
+            let account_proof =
+                post_state.account_proof(provider.tx_ref(), address, &slots).unwrap();
+            assert_eq!(account_proof.verify(root), Ok(()));
+        }
+
+        let account_proof = post_state.account_proof(provider.tx_ref(), target, &slots).unwrap();
+        assert_ne!(account_proof.storage_root, EMPTY_ROOT_HASH);
+        let storage_proof = &account_proof.storage_proofs[0];
+        assert_eq!(storage_proof.value, U256::from(2));
+        assert_eq!(storage_proof.verify(account_proof.storage_root), Ok(()));
+    }
 }
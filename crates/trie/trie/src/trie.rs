@@ -32,6 +32,9 @@ pub struct StateRoot<T, H> {
     previous_state: Option<IntermediateStateRootState>,
     /// The number of updates after which the intermediate progress should be returned.
     threshold: u64,
+    /// The number of leading nibbles of the hashed account key that define a chunk of the
+    /// keyspace. Intermediate progress is returned before moving on to the next chunk.
+    chunk_nibbles: usize,
     #[cfg(feature = "metrics")]
     /// State root metrics.
     metrics: StateRootMetrics,
@@ -56,6 +59,16 @@ impl<T, H> StateRoot<T, H> {
         self
     }
 
+    /// Set the number of leading nibbles of the hashed account key that define a chunk of the
+    /// keyspace, e.g. `2` splits the keyspace into 256 chunks.
+    ///
+    /// Intermediate progress is returned every time the calculation is about to move on to the
+    /// next chunk, in addition to the update threshold. `0` disables chunking.
+    pub const fn with_chunk_nibbles(mut self, chunk_nibbles: usize) -> Self {
+        self.chunk_nibbles = chunk_nibbles;
+        self
+    }
+
     /// Set the previously recorded intermediate state.
     pub fn with_intermediate_state(mut self, state: Option<IntermediateStateRootState>) -> Self {
         self.previous_state = state;
@@ -69,6 +82,7 @@ impl<T, H> StateRoot<T, H> {
             hashed_cursor_factory,
             prefix_sets: self.prefix_sets,
             threshold: self.threshold,
+            chunk_nibbles: self.chunk_nibbles,
             previous_state: self.previous_state,
             #[cfg(feature = "metrics")]
             metrics: self.metrics,
@@ -82,6 +96,7 @@ impl<T, H> StateRoot<T, H> {
             hashed_cursor_factory: self.hashed_cursor_factory,
             prefix_sets: self.prefix_sets,
             threshold: self.threshold,
+            chunk_nibbles: self.chunk_nibbles,
             previous_state: self.previous_state,
             #[cfg(feature = "metrics")]
             metrics: self.metrics,
@@ -98,6 +113,7 @@ impl<'a, TX: DbTx> StateRoot<&'a TX, &'a TX> {
             prefix_sets: TriePrefixSets::default(),
             previous_state: None,
             threshold: 100_000,
+            chunk_nibbles: 0,
             #[cfg(feature = "metrics")]
             metrics: StateRootMetrics::default(),
         }
@@ -237,6 +253,8 @@ where
 
         let mut account_rlp = Vec::with_capacity(128);
         let mut hashed_entries_walked = 0;
+        // The last account key processed by this calculation.
+        let mut last_account_key = None;
         while let Some(node) = account_node_iter.try_next()? {
             match node {
                 TrieElement::Branch(node) => {
@@ -244,6 +262,23 @@ where
                     hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
                 }
                 TrieElement::Leaf(hashed_address, account) => {
+                    // Return intermediate progress before entering the next chunk, so that the
+                    // chunk boundary is also the resume point.
+                    let chunk_boundary = last_account_key.filter(|last| {
+                        retain_updates &&
+                            is_chunk_boundary(self.chunk_nibbles, last, &hashed_address)
+                    });
+                    if let Some(last_account_key) = chunk_boundary {
+                        return Ok(intermediate_progress(
+                            account_node_iter.walker,
+                            hash_builder,
+                            last_account_key,
+                            hashed_entries_walked,
+                            trie_updates,
+                        ))
+                    }
+                    last_account_key = Some(hashed_address);
+
                     tracker.inc_leaf();
                     hashed_entries_walked += 1;
 
@@ -289,20 +324,10 @@ where
                         account_node_iter.walker.updates_len() +
                         hash_builder.updates_len();
                     if retain_updates && total_updates_len as u64 >= self.threshold {
-                        let (walker_stack, walker_updates) = account_node_iter.walker.split();
-                        let (hash_builder, hash_builder_updates) = hash_builder.split();
-
-                        let state = IntermediateStateRootState {
+                        return Ok(intermediate_progress(
+                            account_node_iter.walker,
                             hash_builder,
-                            walker_stack,
-                            last_account_key: hashed_address,
-                        };
-
-                        trie_updates.extend(walker_updates);
-                        trie_updates.extend_with_account_updates(hash_builder_updates);
-
-                        return Ok(StateRootProgress::Progress(
-                            Box::new(state),
+                            hashed_address,
                             hashed_entries_walked,
                             trie_updates,
                         ))
@@ -337,6 +362,35 @@ where
     }
 }
 
+/// Returns `true` if the two hashed account keys belong to different chunks of the keyspace.
+///
+/// Chunking is disabled if `chunk_nibbles` is zero.
+fn is_chunk_boundary(chunk_nibbles: usize, last_account_key: &B256, account_key: &B256) -> bool {
+    chunk_nibbles != 0 &&
+        Nibbles::unpack(last_account_key).common_prefix_length(&Nibbles::unpack(account_key)) <
+            chunk_nibbles
+}
+
+/// Splits the walker and hash builder into the intermediate state root progress, resuming after
+/// `last_account_key`.
+fn intermediate_progress<C>(
+    walker: TrieWalker<C>,
+    hash_builder: HashBuilder,
+    last_account_key: B256,
+    hashed_entries_walked: usize,
+    mut trie_updates: TrieUpdates,
+) -> StateRootProgress {
+    let (walker_stack, walker_updates) = walker.split();
+    let (hash_builder, hash_builder_updates) = hash_builder.split();
+
+    let state = IntermediateStateRootState { hash_builder, walker_stack, last_account_key };
+
+    trie_updates.extend(walker_updates);
+    trie_updates.extend_with_account_updates(hash_builder_updates);
+
+    StateRootProgress::Progress(Box::new(state), hashed_entries_walked, trie_updates)
+}
+
 /// `StorageRoot` is used to compute the root node of an account storage trie.
 #[derive(Debug)]
 pub struct StorageRoot<T, H> {
@@ -546,10 +600,10 @@ where
 mod tests {
     use super::*;
     use crate::{
-        hashed_cursor::HashedPostStateCursorFactory,
+        hashed_cursor::{CountingHashedCursorFactory, HashedPostStateCursorFactory},
         prefix_set::PrefixSetMut,
         test_utils::{state_root, state_root_prehashed, storage_root, storage_root_prehashed},
-        trie_cursor::TrieUpdatesCursorFactory,
+        trie_cursor::{CountingTrieCursorFactory, TrieUpdatesCursorFactory},
         BranchNodeCompact, HashedPostState, HashedStorage, TrieMask,
     };
     use proptest::{prelude::ProptestConfig, proptest};
@@ -758,6 +812,32 @@ mod tests {
         assert_eq!(storage_root(storage.into_iter()), got);
     }
 
+    #[test]
+    fn count_cursor_reads() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap();
+
+        let storage =
+            BTreeMap::from([(B256::ZERO, U256::from(3)), (B256::with_last_byte(2), U256::from(1))]);
+        let account = Account { nonce: 1, balance: U256::from(1), bytecode_hash: None };
+        insert_account(tx.tx_ref(), Address::random(), account, &storage);
+        insert_account(tx.tx_ref(), Address::random(), account, &Default::default());
+
+        let trie_cursor_factory = CountingTrieCursorFactory::new(tx.tx_ref());
+        let hashed_cursor_factory = CountingHashedCursorFactory::new(tx.tx_ref());
+        let root = StateRoot::from_tx(tx.tx_ref())
+            .with_trie_cursor_factory(trie_cursor_factory.clone())
+            .with_hashed_cursor_factory(hashed_cursor_factory.clone())
+            .root()
+            .unwrap();
+        assert_eq!(root, StateRoot::from_tx(tx.tx_ref()).root().unwrap());
+
+        // the walkers look up the root nodes of the tries
+        assert!(trie_cursor_factory.reads() > 0);
+        // both accounts and both storage slots are read
+        assert!(hashed_cursor_factory.reads() >= 4);
+    }
+
     type State = BTreeMap<Address, (Account, BTreeMap<B256, U256>)>;
 
     #[test]
@@ -813,6 +893,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn arbitrary_state_root_with_chunks() {
+        proptest!(
+            ProptestConfig::with_cases(10), | (state in arb::<State>()) | {
+                let hashed_entries_total = state.len() +
+                    state.values().map(|(_, slots)| slots.len()).sum::<usize>();
+
+                let factory = create_test_provider_factory();
+                let tx = factory.provider_rw().unwrap();
+
+                for (address, (account, storage)) in &state {
+                    insert_account(tx.tx_ref(), *address, *account, storage)
+                }
+                tx.commit().unwrap();
+                let tx =  factory.provider_rw().unwrap();
+
+                let expected = state_root(state);
+
+                let mut got = None;
+                let mut hashed_entries_walked = 0;
+                let mut last_chunk = None;
+
+                let mut intermediate_state: Option<Box<IntermediateStateRootState>> = None;
+                while got.is_none() {
+                    let calculator = StateRoot::from_tx(tx.tx_ref())
+                        .with_no_threshold()
+                        .with_chunk_nibbles(1)
+                        .with_intermediate_state(intermediate_state.take().map(|state| *state));
+                    match calculator.root_with_progress().unwrap() {
+                        StateRootProgress::Progress(state, walked, _) => {
+                            // Every step ends at the last key of a chunk
+                            let chunk = Nibbles::unpack(state.last_account_key)[0];
+                            assert!(last_chunk < Some(chunk));
+                            last_chunk = Some(chunk);
+
+                            intermediate_state = Some(state);
+                            hashed_entries_walked += walked;
+                        },
+                        StateRootProgress::Complete(root, walked, _) => {
+                            got = Some(root);
+                            hashed_entries_walked += walked;
+                        },
+                    };
+                }
+                assert_eq!(expected, got.unwrap());
+                assert_eq!(hashed_entries_total, hashed_entries_walked)
+            }
+        );
+    }
+
     fn test_state_root_with_state(state: State) {
         let factory = create_test_provider_factory();
         let tx = factory.provider_rw().unwrap();
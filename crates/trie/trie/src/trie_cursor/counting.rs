@@ -0,0 +1,76 @@
+use super::{TrieCursor, TrieCursorFactory};
+use crate::{updates::TrieKey, BranchNodeCompact, Nibbles};
+use reth_db::DatabaseError;
+use reth_primitives::B256;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// The trie cursor factory that counts the trie nodes read by the cursors of the inner factory.
+///
+/// The count is shared by all clones of the factory and all cursors created by them.
+#[derive(Debug, Clone)]
+pub struct CountingTrieCursorFactory<CF> {
+    cursor_factory: CF,
+    reads: Arc<AtomicU64>,
+}
+
+impl<CF> CountingTrieCursorFactory<CF> {
+    /// Create a new factory.
+    pub fn new(cursor_factory: CF) -> Self {
+        Self { cursor_factory, reads: Arc::default() }
+    }
+
+    /// Returns the number of trie nodes read so far.
+    pub fn reads(&self) -> u64 {
+        self.reads.load(Ordering::Relaxed)
+    }
+}
+
+impl<CF: TrieCursorFactory> TrieCursorFactory for CountingTrieCursorFactory<CF> {
+    type AccountTrieCursor = CountingTrieCursor<CF::AccountTrieCursor>;
+    type StorageTrieCursor = CountingTrieCursor<CF::StorageTrieCursor>;
+
+    fn account_trie_cursor(&self) -> Result<Self::AccountTrieCursor, DatabaseError> {
+        let cursor = self.cursor_factory.account_trie_cursor()?;
+        Ok(CountingTrieCursor { cursor, reads: self.reads.clone() })
+    }
+
+    fn storage_trie_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Self::StorageTrieCursor, DatabaseError> {
+        let cursor = self.cursor_factory.storage_trie_cursor(hashed_address)?;
+        Ok(CountingTrieCursor { cursor, reads: self.reads.clone() })
+    }
+}
+
+/// The trie cursor that counts the nodes read by the inner cursor.
+#[derive(Debug)]
+pub struct CountingTrieCursor<C> {
+    cursor: C,
+    reads: Arc<AtomicU64>,
+}
+
+impl<C: TrieCursor> TrieCursor for CountingTrieCursor<C> {
+    fn seek_exact(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.cursor.seek_exact(key)
+    }
+
+    fn seek(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.cursor.seek(key)
+    }
+
+    fn current(&mut self) -> Result<Option<TrieKey>, DatabaseError> {
+        self.cursor.current()
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{updates::TrieKey, BranchNodeCompact, Nibbles};
 use reth_db::DatabaseError;
 use reth_primitives::B256;
+mod counting;
 mod database_cursors;
 mod subnode;
 mod update;
@@ -9,6 +10,7 @@ mod update;
 pub mod noop;
 
 pub use self::{
+    counting::{CountingTrieCursor, CountingTrieCursorFactory},
     database_cursors::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
     subnode::CursorSubNode,
     update::*,
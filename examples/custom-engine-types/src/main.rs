@@ -282,7 +282,8 @@ where
         &self,
         args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
-        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload } = args;
+        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload, prefix } =
+            args;
         let PayloadConfig {
             initialized_block_env,
             initialized_cfg,
@@ -290,6 +291,8 @@ where
             extra_data,
             attributes,
             chain_spec,
+            budget,
+            resumable,
         } = config;
 
         // This reuses the default EthereumPayloadBuilder to build the payload
@@ -305,9 +308,12 @@ where
                 extra_data,
                 attributes: attributes.0,
                 chain_spec,
+                budget,
+                resumable,
             },
             cancel,
             best_payload,
+            prefix,
         })
     }
 
@@ -323,9 +329,11 @@ where
             extra_data,
             attributes,
             chain_spec,
+            budget,
+            resumable,
         } = config;
         <reth_ethereum_payload_builder::EthereumPayloadBuilder as PayloadBuilder<Pool, Client>>::build_empty_payload(&reth_ethereum_payload_builder::EthereumPayloadBuilder::default(),client,
-                                                                                                                     PayloadConfig { initialized_block_env, initialized_cfg, parent_block, extra_data, attributes: attributes.0, chain_spec })
+                                                                                                                     PayloadConfig { initialized_block_env, initialized_cfg, parent_block, extra_data, attributes: attributes.0, chain_spec, budget, resumable })
     }
 }
 
@@ -0,0 +1,343 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use reth_primitives::{Address, BlockNumber, B256, U256};
+use rusqlite::{Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// An output root proposed to the `L2OutputOracle` of a rollup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputProposal {
+    /// Block of the proposal.
+    pub block_number: BlockNumber,
+    /// Transaction of the proposal.
+    pub tx_hash: B256,
+    /// Address of the `L2OutputOracle`.
+    pub oracle: Address,
+    /// The proposed output root.
+    pub output_root: B256,
+    /// Index of the output in the oracle.
+    pub l2_output_index: U256,
+    /// L2 block number the output root was computed at.
+    pub l2_block_number: U256,
+    /// Timestamp of the proposal.
+    pub l1_timestamp: U256,
+}
+
+/// A dispute game created by the `DisputeGameFactory` of a rollup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeGame {
+    /// Block the game was created in.
+    pub block_number: BlockNumber,
+    /// Transaction that created the game.
+    pub tx_hash: B256,
+    /// Address of the `DisputeGameFactory`.
+    pub factory: Address,
+    /// Address of the game.
+    pub game: Address,
+    /// Type of the game.
+    pub game_type: u32,
+    /// The disputed root claim.
+    pub root_claim: B256,
+    /// Resolution of the game, if it was resolved.
+    pub resolution: Option<GameResolution>,
+}
+
+/// Resolution of a [`DisputeGame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameResolution {
+    /// Block the game was resolved in.
+    pub block_number: BlockNumber,
+    /// Transaction that resolved the game.
+    pub tx_hash: B256,
+    /// Status of the resolved game: `1` if the challenger won, `2` if the defender won.
+    pub status: u8,
+}
+
+/// An event decoded from the logs of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexedEvent {
+    /// An output root was proposed.
+    OutputProposed(OutputProposal),
+    /// A dispute game was created. The resolution is always `None`.
+    DisputeGameCreated(DisputeGame),
+    /// The dispute game with the given address was resolved.
+    Resolved(Address, GameResolution),
+}
+
+/// SQLite database of the indexed events, shared by the ExEx and the RPC.
+#[derive(Debug, Clone)]
+pub struct Database {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Database {
+    /// Create new database with the provided connection.
+    pub fn new(connection: Connection) -> eyre::Result<Self> {
+        let database = Self { connection: Arc::new(Mutex::new(connection)) };
+        database.create_tables()?;
+        Ok(database)
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().expect("failed to acquire database lock")
+    }
+
+    /// Create SQLite tables if they do not exist.
+    ///
+    /// Every table has a `block_number` column, so that reverting a chain is a matter of deleting
+    /// all rows from its first block onwards.
+    fn create_tables(&self) -> eyre::Result<()> {
+        self.connection().execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS output_proposals (
+                id              INTEGER PRIMARY KEY,
+                block_number    INTEGER NOT NULL,
+                tx_hash         TEXT NOT NULL,
+                oracle          TEXT NOT NULL,
+                output_root     TEXT NOT NULL,
+                l2_output_index TEXT NOT NULL,
+                l2_block_number TEXT NOT NULL,
+                l1_timestamp    TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS output_proposals_block_number ON output_proposals (block_number);
+            CREATE TABLE IF NOT EXISTS dispute_games (
+                id           INTEGER PRIMARY KEY,
+                block_number INTEGER NOT NULL,
+                tx_hash      TEXT NOT NULL,
+                factory      TEXT NOT NULL,
+                game         TEXT NOT NULL UNIQUE,
+                game_type    INTEGER NOT NULL,
+                root_claim   TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS dispute_games_block_number ON dispute_games (block_number);
+            CREATE TABLE IF NOT EXISTS game_resolutions (
+                id           INTEGER PRIMARY KEY,
+                block_number INTEGER NOT NULL,
+                tx_hash      TEXT NOT NULL,
+                game         TEXT NOT NULL UNIQUE,
+                status       INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS checkpoint (
+                id           INTEGER PRIMARY KEY CHECK (id = 0),
+                block_number INTEGER NOT NULL
+            );
+            "#,
+        )?;
+        Ok(())
+    }
+
+    /// Returns the highest block the events were indexed up to.
+    pub fn indexed_height(&self) -> eyre::Result<Option<BlockNumber>> {
+        Ok(self
+            .connection()
+            .query_row("SELECT block_number FROM checkpoint WHERE id = 0", (), |row| row.get(0))
+            .optional()?)
+    }
+
+    /// Returns the addresses of all indexed dispute games.
+    pub fn dispute_game_addresses(&self) -> eyre::Result<Vec<Address>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT game FROM dispute_games")?;
+        let addresses = statement
+            .query_map((), |row| row.get::<_, String>(0))?
+            .map(|address| -> eyre::Result<Address> { Ok(address?.parse()?) })
+            .collect::<eyre::Result<_>>()?;
+        Ok(addresses)
+    }
+
+    /// Atomically removes all events from the `revert_from` block onwards, if any, inserts the
+    /// new events and moves the checkpoint to `indexed_height`.
+    pub fn write(
+        &self,
+        revert_from: Option<BlockNumber>,
+        events: &[IndexedEvent],
+        indexed_height: BlockNumber,
+    ) -> eyre::Result<()> {
+        let mut connection = self.connection();
+        let tx = connection.transaction()?;
+
+        if let Some(block_number) = revert_from {
+            for table in ["output_proposals", "dispute_games", "game_resolutions"] {
+                tx.execute(
+                    &format!("DELETE FROM {table} WHERE block_number >= ?"),
+                    (block_number,),
+                )?;
+            }
+        }
+
+        for event in events {
+            match event {
+                IndexedEvent::OutputProposed(proposal) => {
+                    tx.execute(
+                        r#"
+                        INSERT INTO output_proposals (block_number, tx_hash, oracle, output_root, l2_output_index, l2_block_number, l1_timestamp)
+                        VALUES (?, ?, ?, ?, ?, ?, ?)
+                        "#,
+                        (
+                            proposal.block_number,
+                            proposal.tx_hash.to_string(),
+                            proposal.oracle.to_string(),
+                            proposal.output_root.to_string(),
+                            proposal.l2_output_index.to_string(),
+                            proposal.l2_block_number.to_string(),
+                            proposal.l1_timestamp.to_string(),
+                        ),
+                    )?;
+                }
+                IndexedEvent::DisputeGameCreated(game) => {
+                    tx.execute(
+                        r#"
+                        INSERT INTO dispute_games (block_number, tx_hash, factory, game, game_type, root_claim)
+                        VALUES (?, ?, ?, ?, ?, ?)
+                        "#,
+                        (
+                            game.block_number,
+                            game.tx_hash.to_string(),
+                            game.factory.to_string(),
+                            game.game.to_string(),
+                            game.game_type,
+                            game.root_claim.to_string(),
+                        ),
+                    )?;
+                }
+                IndexedEvent::Resolved(game, resolution) => {
+                    tx.execute(
+                        r#"
+                        INSERT INTO game_resolutions (block_number, tx_hash, game, status)
+                        VALUES (?, ?, ?, ?)
+                        "#,
+                        (
+                            resolution.block_number,
+                            resolution.tx_hash.to_string(),
+                            game.to_string(),
+                            resolution.status,
+                        ),
+                    )?;
+                }
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO checkpoint (id, block_number) VALUES (0, ?) ON CONFLICT(id) DO UPDATE SET block_number = excluded.block_number",
+            (indexed_height,),
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns the output proposals in the given block range, in the order they were proposed.
+    pub fn output_proposals(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> eyre::Result<Vec<OutputProposal>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            r#"
+            SELECT block_number, tx_hash, oracle, output_root, l2_output_index, l2_block_number, l1_timestamp
+            FROM output_proposals
+            WHERE block_number BETWEEN ? AND ?
+            ORDER BY id
+            "#,
+        )?;
+        let rows = statement.query_map((from_block, to_block), |row| {
+            Ok((
+                row.get(0)?,
+                text(row, 1)?,
+                text(row, 2)?,
+                text(row, 3)?,
+                text(row, 4)?,
+                text(row, 5)?,
+                text(row, 6)?,
+            ))
+        })?;
+
+        rows.map(|row| -> eyre::Result<OutputProposal> {
+            let (
+                block_number,
+                tx_hash,
+                oracle,
+                output_root,
+                l2_output_index,
+                l2_block_number,
+                l1_timestamp,
+            ) = row?;
+            Ok(OutputProposal {
+                block_number,
+                tx_hash: tx_hash.parse()?,
+                oracle: oracle.parse()?,
+                output_root: output_root.parse()?,
+                l2_output_index: l2_output_index.parse()?,
+                l2_block_number: l2_block_number.parse()?,
+                l1_timestamp: l1_timestamp.parse()?,
+            })
+        })
+        .collect()
+    }
+
+    /// Returns the dispute games created in the given block range together with their
+    /// resolutions, in the order they were created.
+    pub fn dispute_games(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> eyre::Result<Vec<DisputeGame>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            r#"
+            SELECT games.block_number, games.tx_hash, games.factory, games.game, games.game_type, games.root_claim,
+                   resolutions.block_number, resolutions.tx_hash, resolutions.status
+            FROM dispute_games AS games
+            LEFT JOIN game_resolutions AS resolutions ON resolutions.game = games.game
+            WHERE games.block_number BETWEEN ? AND ?
+            ORDER BY games.id
+            "#,
+        )?;
+        let rows = statement.query_map((from_block, to_block), |row| {
+            Ok((
+                (
+                    row.get(0)?,
+                    text(row, 1)?,
+                    text(row, 2)?,
+                    text(row, 3)?,
+                    row.get(4)?,
+                    text(row, 5)?,
+                ),
+                (
+                    row.get::<_, Option<BlockNumber>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                    row.get::<_, Option<u8>>(8)?,
+                ),
+            ))
+        })?;
+
+        rows.map(|row| -> eyre::Result<DisputeGame> {
+            let ((block_number, tx_hash, factory, game, game_type, root_claim), resolution) = row?;
+            let resolution = match resolution {
+                (Some(block_number), Some(tx_hash), Some(status)) => {
+                    Some(GameResolution { block_number, tx_hash: tx_hash.parse()?, status })
+                }
+                _ => None,
+            };
+            Ok(DisputeGame {
+                block_number,
+                tx_hash: tx_hash.parse()?,
+                factory: factory.parse()?,
+                game: game.parse()?,
+                game_type,
+                root_claim: root_claim.parse()?,
+                resolution,
+            })
+        })
+        .collect()
+    }
+}
+
+/// Returns the text value of the column with the given index.
+fn text(row: &Row<'_>, index: usize) -> rusqlite::Result<String> {
+    row.get(index)
+}
@@ -0,0 +1,423 @@
+//! Example of an ExEx that indexes the settlement and dispute events of a rollup on L1 into a
+//! SQLite database, and serves them over an additional RPC namespace.
+//!
+//! This is meant as a template for indexer ExExes:
+//! - the indexed contracts are configured with CLI arguments,
+//! - the ExEx only receives the blocks and receipts it needs, by emitting an
+//!   [`ExExEvent::NotificationFilter`] event,
+//! - it catches up with the blocks it missed while the node was offline using a backfill job,
+//! - reverts and reorgs are applied atomically together with the indexed height,
+//! - the RPC namespace is registered from the ExEx context, so it's only served while the ExEx is
+//!   running.
+//!
+//! Run with
+//!
+//! ```not_rust
+//! cargo run -p example-exex-settlement-indexer -- node --http \
+//!     --settlement.contracts 0xdfe97868233d1aa22e815a266982f2cf17685a27,0xe5965Ab5962eDc7477C8520243A95517CD252fA9
+//! ```
+//!
+//! and query the indexed events via [cast](https://github.com/foundry-rs/foundry)
+//!
+//! ```sh
+//! cast rpc settlement_indexedHeight
+//! cast rpc settlement_outputProposals 20000000 20001000
+//! cast rpc settlement_disputeGames 20000000 20001000
+//! ```
+
+use std::{collections::HashSet, path::PathBuf};
+
+use alloy_sol_types::{sol, SolEventInterface};
+use clap::Parser;
+use futures::StreamExt;
+use reth::cli::Cli;
+use reth_execution_types::Chain;
+use reth_exex::{ExExContext, ExExEvent, ExExNotification, ExExNotificationFilter};
+use reth_node_api::FullNodeComponents;
+use reth_node_ethereum::EthereumNode;
+use reth_primitives::{Address, BlockNumber};
+use reth_tracing::tracing::info;
+use rusqlite::Connection;
+
+use crate::{
+    db::{Database, DisputeGame, GameResolution, IndexedEvent, OutputProposal},
+    rpc::{SettlementApiServer, SettlementRpc},
+};
+
+mod db;
+mod rpc;
+
+sol! {
+    /// Settlement and dispute events of the OP Stack contracts on L1.
+    interface Settlement {
+        /// Emitted by the `L2OutputOracle` when an output root is proposed.
+        event OutputProposed(
+            bytes32 indexed outputRoot,
+            uint256 indexed l2OutputIndex,
+            uint256 indexed l2BlockNumber,
+            uint256 l1Timestamp
+        );
+
+        /// Emitted by the `DisputeGameFactory` when a dispute game is created.
+        event DisputeGameCreated(
+            address indexed disputeProxy,
+            uint32 indexed gameType,
+            bytes32 indexed rootClaim
+        );
+
+        /// Emitted by a dispute game when it's resolved.
+        event Resolved(uint8 indexed status);
+    }
+}
+use crate::Settlement::SettlementEvents;
+
+/// Our custom cli args extension that configures the indexer.
+#[derive(Debug, Clone, Default, clap::Args)]
+struct SettlementIndexerArgs {
+    /// Addresses of the `L2OutputOracle` and `DisputeGameFactory` contracts to index the events
+    /// of.
+    #[arg(long = "settlement.contracts", value_delimiter = ',', required = true)]
+    contracts: Vec<Address>,
+
+    /// Path of the SQLite database of the indexer.
+    #[arg(long = "settlement.db", default_value = "settlement.db")]
+    db: PathBuf,
+
+    /// Block to start indexing from if nothing was indexed yet.
+    ///
+    /// Without it, the indexer starts with the first block the node processes.
+    #[arg(long = "settlement.from-block")]
+    from_block: Option<BlockNumber>,
+}
+
+/// An ExEx that indexes the settlement and dispute events of the configured contracts.
+struct SettlementIndexer<Node: FullNodeComponents> {
+    ctx: ExExContext<Node>,
+    db: Database,
+    /// Configured `L2OutputOracle` and `DisputeGameFactory` contracts.
+    contracts: HashSet<Address>,
+    /// Dispute games created by the configured factories, which emit the `Resolved` events.
+    games: HashSet<Address>,
+}
+
+impl<Node: FullNodeComponents> SettlementIndexer<Node> {
+    /// Creates the indexer, loading the already indexed dispute games from the database, and
+    /// registers the `settlement` RPC namespace that serves the indexed events.
+    fn new(
+        ctx: ExExContext<Node>,
+        db: Database,
+        contracts: impl IntoIterator<Item = Address>,
+    ) -> eyre::Result<Self> {
+        let games = db.dispute_game_addresses()?.into_iter().collect();
+        ctx.register_rpc_module(SettlementRpc::new(db.clone()).into_rpc())?;
+        Ok(Self { ctx, db, contracts: contracts.into_iter().collect(), games })
+    }
+
+    /// Runs the indexer: catches up with the blocks between the indexed height and the node head,
+    /// and then indexes all new chain state notifications.
+    async fn start(mut self, from_block: Option<BlockNumber>) -> eyre::Result<()> {
+        self.update_filter()?;
+
+        let start = self.db.indexed_height()?.map(|height| height + 1).or(from_block);
+        if let Some(start) = start.filter(|start| *start <= self.ctx.head.number) {
+            info!(from = start, to = self.ctx.head.number, "Backfilling settlement events");
+
+            let mut notifications = self
+                .ctx
+                .backfill_job_factory()
+                .backfill(start..=self.ctx.head.number)
+                .into_stream();
+            while let Some(notification) = notifications.next().await {
+                self.process(&notification?)?;
+            }
+        }
+
+        while let Some(notification) = self.ctx.notifications.recv().await {
+            self.process(&notification)?;
+
+            if let Some(committed_chain) = notification.committed_chain() {
+                // Send a finished height event, signaling the node that we don't need any blocks
+                // below this height anymore
+                self.ctx.events.send(ExExEvent::FinishedHeight(committed_chain.tip().number))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Asks the node to only send the blocks with logs of the configured contracts and known
+    /// dispute games.
+    ///
+    /// Blocks that were already sent with the previous filter are not sent again, so a game
+    /// resolved in the same notification that created it is missed. Dispute games can only be
+    /// resolved after a challenge period of several days, so this doesn't happen in practice.
+    fn update_filter(&self) -> eyre::Result<()> {
+        let addresses = self.contracts.union(&self.games).copied().collect::<HashSet<_>>();
+        self.ctx.events.send(ExExEvent::NotificationFilter(ExExNotificationFilter {
+            log_addresses: addresses,
+            // We don't need any state, so only keep the (small) state of the contracts
+            state_addresses: self.contracts.clone(),
+        }))?;
+        Ok(())
+    }
+
+    /// Reverts the events of the reverted chain and indexes the events of the committed chain of
+    /// the notification.
+    fn process(&mut self, notification: &ExExNotification) -> eyre::Result<()> {
+        let revert_from = notification.reverted_chain().map(|chain| chain.first().number);
+
+        let (events, indexed_height) = match notification.committed_chain() {
+            Some(committed_chain) => {
+                (self.decode_chain(&committed_chain), committed_chain.tip().number)
+            }
+            None => (Vec::new(), revert_from.expect("notification has a chain") - 1),
+        };
+
+        self.db.write(revert_from, &events, indexed_height)?;
+
+        let mut new_games = false;
+        for event in &events {
+            if let IndexedEvent::DisputeGameCreated(game) = event {
+                new_games |= self.games.insert(game.game);
+            }
+        }
+        if new_games {
+            self.update_filter()?;
+        }
+
+        info!(
+            ?revert_from,
+            %indexed_height,
+            events = events.len(),
+            "Indexed settlement events"
+        );
+
+        Ok(())
+    }
+
+    /// Decodes the settlement events of the configured contracts and the dispute games created
+    /// by them in the given chain.
+    fn decode_chain(&self, chain: &Chain) -> Vec<IndexedEvent> {
+        let mut games = self.games.clone();
+        let mut events = Vec::new();
+
+        for (block, receipts) in chain.blocks_and_receipts() {
+            for (tx, receipt) in block.body.iter().zip(receipts.iter().flatten()) {
+                for log in &receipt.logs {
+                    let Ok(event) =
+                        SettlementEvents::decode_raw_log(log.topics(), &log.data.data, true)
+                    else {
+                        continue
+                    };
+
+                    let event = match event {
+                        SettlementEvents::OutputProposed(event)
+                            if self.contracts.contains(&log.address) =>
+                        {
+                            IndexedEvent::OutputProposed(OutputProposal {
+                                block_number: block.number,
+                                tx_hash: tx.hash(),
+                                oracle: log.address,
+                                output_root: event.outputRoot,
+                                l2_output_index: event.l2OutputIndex,
+                                l2_block_number: event.l2BlockNumber,
+                                l1_timestamp: event.l1Timestamp,
+                            })
+                        }
+                        SettlementEvents::DisputeGameCreated(event)
+                            if self.contracts.contains(&log.address) =>
+                        {
+                            games.insert(event.disputeProxy);
+                            IndexedEvent::DisputeGameCreated(DisputeGame {
+                                block_number: block.number,
+                                tx_hash: tx.hash(),
+                                factory: log.address,
+                                game: event.disputeProxy,
+                                game_type: event.gameType,
+                                root_claim: event.rootClaim,
+                                resolution: None,
+                            })
+                        }
+                        SettlementEvents::Resolved(event) if games.contains(&log.address) => {
+                            IndexedEvent::Resolved(
+                                log.address,
+                                GameResolution {
+                                    block_number: block.number,
+                                    tx_hash: tx.hash(),
+                                    status: event.status,
+                                },
+                            )
+                        }
+                        _ => continue,
+                    };
+                    events.push(event);
+                }
+            }
+        }
+
+        events
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    Cli::<SettlementIndexerArgs>::parse().run(|builder, args| async move {
+        let handle = builder
+            .node(EthereumNode::default())
+            .install_exex("SettlementIndexer", move |ctx| async move {
+                let db = Database::new(Connection::open(&args.db)?)?;
+                let indexer = SettlementIndexer::new(ctx, db, args.contracts)?;
+                Ok(indexer.start(args.from_block))
+            })
+            .launch()
+            .await?;
+
+        handle.wait_for_node_exit().await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use alloy_sol_types::SolEvent;
+    use reth::revm::db::BundleState;
+    use reth_execution_types::{Chain, ExecutionOutcome};
+    use reth_exex_test_utils::{test_exex_context, PollOnce};
+    use reth_primitives::{
+        Address, Block, Header, Log, Receipt, Transaction, TransactionSigned, TxKind, TxLegacy,
+        TxType, B256, U256,
+    };
+    use reth_testing_utils::generators::sign_tx_with_random_key_pair;
+    use rusqlite::Connection;
+
+    use super::*;
+
+    /// Given the address of a contract and an event, construct a transaction signed with a
+    /// random private key and a receipt for that transaction.
+    fn construct_tx_and_receipt<E: SolEvent>(
+        to: Address,
+        event: E,
+    ) -> eyre::Result<(TransactionSigned, Receipt)> {
+        let tx = Transaction::Legacy(TxLegacy { to: TxKind::Call(to), ..Default::default() });
+        let log = Log::new(
+            to,
+            event.encode_topics().into_iter().map(|topic| topic.0).collect(),
+            event.encode_data().into(),
+        )
+        .ok_or_else(|| eyre::eyre!("failed to encode event"))?;
+        #[allow(clippy::needless_update)] // side-effect of optimism fields
+        let receipt = Receipt {
+            tx_type: TxType::Legacy,
+            success: true,
+            cumulative_gas_used: 0,
+            logs: vec![log],
+            ..Default::default()
+        };
+        Ok((sign_tx_with_random_key_pair(&mut rand::thread_rng(), tx), receipt))
+    }
+
+    /// Construct a chain of one block with the given transactions and receipts.
+    fn construct_chain(
+        number: BlockNumber,
+        txs_and_receipts: Vec<(TransactionSigned, Receipt)>,
+    ) -> eyre::Result<Chain> {
+        let (body, receipts): (Vec<_>, Vec<_>) = txs_and_receipts.into_iter().unzip();
+        let block =
+            Block { header: Header { number, ..Default::default() }, body, ..Default::default() }
+                .seal_slow()
+                .seal_with_senders()
+                .ok_or_else(|| eyre::eyre!("failed to recover senders"))?;
+
+        Ok(Chain::new(
+            vec![block.clone()],
+            ExecutionOutcome::new(
+                BundleState::default(),
+                receipts.into(),
+                block.number,
+                vec![block.requests.clone().unwrap_or_default()],
+            ),
+            None,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_exex() -> eyre::Result<()> {
+        // Initialize the test Execution Extension context with all dependencies
+        let (ctx, handle) = test_exex_context().await?;
+        // Create a temporary database file, so we can access it later for assertions
+        let db_file = tempfile::NamedTempFile::new()?;
+        let db = Database::new(Connection::open(&db_file)?)?;
+
+        let oracle = Address::random();
+        let factory = Address::random();
+        let game = Address::random();
+
+        // Initialize the ExEx
+        let indexer = SettlementIndexer::new(ctx, db.clone(), [oracle, factory])?;
+        let mut exex = pin!(indexer.start(None));
+
+        // Construct an output proposal, a dispute game creation, and an event of an unknown
+        // contract that must be ignored
+        let proposal_event = Settlement::OutputProposed {
+            outputRoot: B256::random(),
+            l2OutputIndex: U256::from(1),
+            l2BlockNumber: U256::from(1800),
+            l1Timestamp: U256::from(1_700_000_000),
+        };
+        let game_event = Settlement::DisputeGameCreated {
+            disputeProxy: game,
+            gameType: 0,
+            rootClaim: proposal_event.outputRoot,
+        };
+        let chain = construct_chain(
+            1,
+            vec![
+                construct_tx_and_receipt(oracle, proposal_event.clone())?,
+                construct_tx_and_receipt(factory, game_event)?,
+                construct_tx_and_receipt(Address::random(), proposal_event.clone())?,
+            ],
+        )?;
+
+        // Send a notification that the chain has been committed
+        handle.send_notification_chain_committed(chain.clone()).await?;
+        // Poll the ExEx once, it will process the notification that we just sent
+        exex.poll_once().await?;
+
+        assert_eq!(db.indexed_height()?, Some(1));
+        let proposals = db.output_proposals(0, 1)?;
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].oracle, oracle);
+        assert_eq!(proposals[0].output_root, proposal_event.outputRoot);
+        assert_eq!(proposals[0].l2_block_number, proposal_event.l2BlockNumber);
+
+        // Resolve the game in the next block
+        let resolved_chain = construct_chain(
+            2,
+            vec![construct_tx_and_receipt(game, Settlement::Resolved { status: 2 })?],
+        )?;
+        handle.send_notification_chain_committed(resolved_chain.clone()).await?;
+        exex.poll_once().await?;
+
+        let games = db.dispute_games(0, 2)?;
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].game, game);
+        assert_eq!(games[0].resolution.map(|resolution| resolution.status), Some(2));
+
+        // Send a notification that the resolution has been reverted
+        handle.send_notification_chain_reverted(resolved_chain).await?;
+        exex.poll_once().await?;
+
+        assert_eq!(db.indexed_height()?, Some(1));
+        assert_eq!(db.dispute_games(0, 2)?[0].resolution, None);
+
+        // Send a notification that the first chain has been reverted
+        handle.send_notification_chain_reverted(chain).await?;
+        exex.poll_once().await?;
+
+        assert_eq!(db.indexed_height()?, Some(0));
+        assert!(db.output_proposals(0, 2)?.is_empty());
+        assert!(db.dispute_games(0, 2)?.is_empty());
+
+        Ok(())
+    }
+}
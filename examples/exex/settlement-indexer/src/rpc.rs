@@ -0,0 +1,132 @@
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::{
+        error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+        ErrorObject, ErrorObjectOwned,
+    },
+};
+use reth_primitives::BlockNumber;
+
+use crate::db::{Database, DisputeGame, OutputProposal};
+
+/// Maximum number of blocks that can be queried at once.
+const MAX_BLOCK_RANGE: u64 = 100_000;
+
+/// trait interface for the `settlement` rpc namespace, serving the indexed events.
+#[cfg_attr(not(test), rpc(server, namespace = "settlement"))]
+#[cfg_attr(test, rpc(server, client, namespace = "settlement"))]
+pub trait SettlementApi {
+    /// Returns the highest block the events were indexed up to, or `None` if nothing was indexed
+    /// yet.
+    #[method(name = "indexedHeight")]
+    fn indexed_height(&self) -> RpcResult<Option<BlockNumber>>;
+
+    /// Returns the output proposals in the given inclusive block range.
+    #[method(name = "outputProposals")]
+    fn output_proposals(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> RpcResult<Vec<OutputProposal>>;
+
+    /// Returns the dispute games created in the given inclusive block range, together with their
+    /// resolutions.
+    #[method(name = "disputeGames")]
+    fn dispute_games(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> RpcResult<Vec<DisputeGame>>;
+}
+
+/// The type that implements the `settlement` rpc namespace trait
+#[derive(Debug)]
+pub struct SettlementRpc {
+    db: Database,
+}
+
+impl SettlementRpc {
+    /// Creates a new RPC handler serving the events from the given database.
+    pub const fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+impl SettlementApiServer for SettlementRpc {
+    fn indexed_height(&self) -> RpcResult<Option<BlockNumber>> {
+        self.db.indexed_height().map_err(internal_error)
+    }
+
+    fn output_proposals(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> RpcResult<Vec<OutputProposal>> {
+        validate_range(from_block, to_block)?;
+        self.db.output_proposals(from_block, to_block).map_err(internal_error)
+    }
+
+    fn dispute_games(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> RpcResult<Vec<DisputeGame>> {
+        validate_range(from_block, to_block)?;
+        self.db.dispute_games(from_block, to_block).map_err(internal_error)
+    }
+}
+
+/// Rejects empty block ranges and ranges larger than [`MAX_BLOCK_RANGE`].
+fn validate_range(from_block: BlockNumber, to_block: BlockNumber) -> RpcResult<()> {
+    if from_block > to_block || to_block - from_block >= MAX_BLOCK_RANGE {
+        return Err(ErrorObject::owned(
+            INVALID_PARAMS_CODE,
+            format!("invalid block range, at most {MAX_BLOCK_RANGE} blocks can be queried"),
+            None::<()>,
+        ))
+    }
+    Ok(())
+}
+
+fn internal_error(err: eyre::Report) -> ErrorObjectOwned {
+    ErrorObject::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::{http_client::HttpClientBuilder, server::ServerBuilder};
+    use reth_primitives::{Address, B256, U256};
+    use rusqlite::Connection;
+
+    use crate::db::IndexedEvent;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_output_proposals_http() -> eyre::Result<()> {
+        let db = Database::new(Connection::open_in_memory()?)?;
+        let proposal = OutputProposal {
+            block_number: 10,
+            tx_hash: B256::random(),
+            oracle: Address::random(),
+            output_root: B256::random(),
+            l2_output_index: U256::from(1),
+            l2_block_number: U256::from(1800),
+            l1_timestamp: U256::from(1_700_000_000),
+        };
+        db.write(None, &[IndexedEvent::OutputProposed(proposal.clone())], 10)?;
+
+        let server = ServerBuilder::default().build("127.0.0.1:0").await?;
+        let addr = server.local_addr()?;
+        let server_handle = server.start(SettlementRpc::new(db).into_rpc());
+        tokio::spawn(server_handle.stopped());
+
+        let client = HttpClientBuilder::default().build(format!("http://{addr}"))?;
+        assert_eq!(SettlementApiClient::indexed_height(&client).await?, Some(10));
+        assert_eq!(SettlementApiClient::output_proposals(&client, 0, 10).await?, vec![proposal]);
+        assert!(SettlementApiClient::output_proposals(&client, 11, 20).await?.is_empty());
+        assert!(SettlementApiClient::output_proposals(&client, 10, 0).await.is_err());
+
+        Ok(())
+    }
+}
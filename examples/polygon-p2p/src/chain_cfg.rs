@@ -28,6 +28,7 @@ pub(crate) fn polygon_chain_spec() -> Arc<ChainSpec> {
         deposit_contract: None,
         base_fee_params: reth_chainspec::BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
         prune_delete_limit: 0,
+        bootnodes: None,
     }
     .into()
 }